@@ -1 +1,3 @@
+pub mod anthropic;
+pub mod ollama;
 pub mod open_ai;
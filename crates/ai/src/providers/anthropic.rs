@@ -0,0 +1,514 @@
+use std::{env, io, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use futures::{
+    future::BoxFuture, io::BufReader, stream::BoxStream, AsyncBufReadExt, AsyncReadExt, FutureExt,
+    Stream, StreamExt,
+};
+use gpui::{AppContext, BackgroundExecutor};
+use isahc::{http::StatusCode, Request, RequestExt};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use util::ResultExt;
+
+use crate::{
+    auth::{CredentialProvider, ProviderCredential},
+    completion::{CompletionProvider, CompletionRequest},
+    models::LanguageModel,
+    providers::open_ai::{OpenAiLanguageModel, OpenAiRequest, Role},
+};
+
+pub const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1";
+
+/// https://docs.anthropic.com/claude/reference/versions
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+
+/// The Messages API requires an upper bound for the length of each response.
+const MAX_RESPONSE_TOKENS: usize = 4096;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct AnthropicMessage {
+    pub role: AnthropicRole,
+    pub content: String,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnthropicRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnthropicRequest {
+    pub model: String,
+    pub max_tokens: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    pub messages: Vec<AnthropicMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    pub temperature: f32,
+    pub stream: bool,
+}
+
+impl AnthropicRequest {
+    /// Converts a request in OpenAI's chat format, which is what the assistant builds.
+    ///
+    /// Anthropic only accepts system prompts separately from the conversation, and requires
+    /// the conversation to alternate between the user and the assistant.
+    pub fn from_open_ai(request: OpenAiRequest) -> Self {
+        let mut system = Vec::new();
+        let mut messages = Vec::<AnthropicMessage>::new();
+        for message in request.messages {
+            let content = message.content.trim_end();
+            if content.is_empty() {
+                continue;
+            }
+
+            let role = match message.role {
+                Role::System => {
+                    system.push(content.to_string());
+                    continue;
+                }
+                Role::User => AnthropicRole::User,
+                Role::Assistant => AnthropicRole::Assistant,
+            };
+            match messages.last_mut() {
+                Some(last) if last.role == role => {
+                    last.content.push_str("\n\n");
+                    last.content.push_str(content);
+                }
+                _ => messages.push(AnthropicMessage {
+                    role,
+                    content: content.to_string(),
+                }),
+            }
+        }
+
+        Self {
+            model: request.model,
+            max_tokens: MAX_RESPONSE_TOKENS,
+            system: (!system.is_empty()).then(|| system.join("\n\n")),
+            messages,
+            stop_sequences: request.stop,
+            temperature: request.temperature.clamp(0., 1.),
+            stream: true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicStreamEvent {
+    MessageStart,
+    ContentBlockStart,
+    ContentBlockDelta { delta: AnthropicDelta },
+    ContentBlockStop,
+    MessageDelta,
+    MessageStop,
+    Ping,
+    Error { error: AnthropicError },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicDelta {
+    TextDelta { text: String },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnthropicError {
+    pub message: String,
+}
+
+async fn stream_completion(
+    api_url: String,
+    credential: ProviderCredential,
+    executor: BackgroundExecutor,
+    request: AnthropicRequest,
+) -> Result<impl Stream<Item = Result<AnthropicStreamEvent>>> {
+    let api_key = match credential {
+        ProviderCredential::Credentials { api_key } => api_key,
+        _ => {
+            return Err(anyhow!("no credentials provider for completion"));
+        }
+    };
+
+    let (tx, rx) = futures::channel::mpsc::unbounded::<Result<AnthropicStreamEvent>>();
+
+    let json_data = serde_json::to_string(&request)?;
+    // https://docs.anthropic.com/claude/reference/messages_post
+    let mut response = Request::post(format!("{api_url}/messages"))
+        .header("Content-Type", "application/json")
+        .header("X-Api-Key", api_key)
+        .header("Anthropic-Version", ANTHROPIC_API_VERSION)
+        .body(json_data)?
+        .send_async()
+        .await?;
+
+    let status = response.status();
+    if status == StatusCode::OK {
+        executor
+            .spawn(async move {
+                let mut lines = BufReader::new(response.body_mut()).lines();
+
+                fn parse_line(
+                    line: Result<String, io::Error>,
+                ) -> Result<Option<AnthropicStreamEvent>> {
+                    if let Some(data) = line?.strip_prefix("data: ") {
+                        let event = serde_json::from_str(data)?;
+                        Ok(Some(event))
+                    } else {
+                        Ok(None)
+                    }
+                }
+
+                while let Some(line) = lines.next().await {
+                    if let Some(event) = parse_line(line).transpose() {
+                        let done = matches!(
+                            event,
+                            Ok(AnthropicStreamEvent::MessageStop)
+                                | Ok(AnthropicStreamEvent::Error { .. })
+                        );
+                        if tx.unbounded_send(event).is_err() {
+                            break;
+                        }
+
+                        if done {
+                            break;
+                        }
+                    }
+                }
+
+                anyhow::Ok(())
+            })
+            .detach();
+
+        Ok(rx)
+    } else {
+        let mut body = String::new();
+        response.body_mut().read_to_string(&mut body).await?;
+
+        #[derive(Deserialize)]
+        struct AnthropicResponse {
+            error: AnthropicError,
+        }
+
+        match serde_json::from_str::<AnthropicResponse>(&body) {
+            Ok(response) if !response.error.message.is_empty() => Err(anyhow!(
+                "Failed to connect to Anthropic API: {}",
+                response.error.message,
+            )),
+
+            _ => Err(anyhow!(
+                "Failed to connect to Anthropic API: {} {}",
+                response.status(),
+                body,
+            )),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AnthropicCompletionProvider {
+    api_url: String,
+    model: OpenAiLanguageModel,
+    credential: Arc<RwLock<ProviderCredential>>,
+    executor: BackgroundExecutor,
+}
+
+impl AnthropicCompletionProvider {
+    pub async fn new(
+        api_url: String,
+        model_name: String,
+        max_tokens: usize,
+        executor: BackgroundExecutor,
+    ) -> Self {
+        // Anthropic doesn't publish its tokenizer, so token counts are estimated with OpenAI's.
+        let model = executor
+            .spawn(async move { OpenAiLanguageModel::load(&model_name).with_capacity(max_tokens) })
+            .await;
+        let credential = Arc::new(RwLock::new(ProviderCredential::NoCredentials));
+        Self {
+            api_url,
+            model,
+            credential,
+            executor,
+        }
+    }
+}
+
+impl CredentialProvider for AnthropicCompletionProvider {
+    fn has_credentials(&self) -> bool {
+        match *self.credential.read() {
+            ProviderCredential::Credentials { .. } => true,
+            _ => false,
+        }
+    }
+
+    fn retrieve_credentials(&self, cx: &mut AppContext) -> BoxFuture<ProviderCredential> {
+        let existing_credential = self.credential.read().clone();
+        let retrieved_credential = match existing_credential {
+            ProviderCredential::Credentials { .. } => {
+                return async move { existing_credential }.boxed()
+            }
+            _ => {
+                if let Some(api_key) = env::var("ANTHROPIC_API_KEY").log_err() {
+                    async move { ProviderCredential::Credentials { api_key } }.boxed()
+                } else {
+                    let credentials = cx.read_credentials(ANTHROPIC_API_URL);
+                    async move {
+                        if let Some(Some((_, api_key))) = credentials.await.log_err() {
+                            if let Some(api_key) = String::from_utf8(api_key).log_err() {
+                                ProviderCredential::Credentials { api_key }
+                            } else {
+                                ProviderCredential::NoCredentials
+                            }
+                        } else {
+                            ProviderCredential::NoCredentials
+                        }
+                    }
+                    .boxed()
+                }
+            }
+        };
+
+        async move {
+            let retrieved_credential = retrieved_credential.await;
+            *self.credential.write() = retrieved_credential.clone();
+            retrieved_credential
+        }
+        .boxed()
+    }
+
+    fn save_credentials(
+        &self,
+        cx: &mut AppContext,
+        credential: ProviderCredential,
+    ) -> BoxFuture<()> {
+        *self.credential.write() = credential.clone();
+        let write_credentials = match credential {
+            ProviderCredential::Credentials { api_key } => {
+                Some(cx.write_credentials(ANTHROPIC_API_URL, "Bearer", api_key.as_bytes()))
+            }
+            _ => None,
+        };
+
+        async move {
+            if let Some(write_credentials) = write_credentials {
+                write_credentials.await.log_err();
+            }
+        }
+        .boxed()
+    }
+
+    fn delete_credentials(&self, cx: &mut AppContext) -> BoxFuture<()> {
+        *self.credential.write() = ProviderCredential::NoCredentials;
+        let delete_credentials = cx.delete_credentials(ANTHROPIC_API_URL);
+        async move {
+            delete_credentials.await.log_err();
+        }
+        .boxed()
+    }
+}
+
+impl CompletionProvider for AnthropicCompletionProvider {
+    fn base_model(&self) -> Box<dyn LanguageModel> {
+        let model: Box<dyn LanguageModel> = Box::new(self.model.clone());
+        model
+    }
+
+    fn complete(
+        &self,
+        prompt: Box<dyn CompletionRequest>,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+        let credential = self.credential.read().clone();
+        let api_url = self.api_url.clone();
+        let executor = self.executor.clone();
+        let request = prompt
+            .data()
+            .and_then(|data| serde_json::from_str::<OpenAiRequest>(&data));
+        async move {
+            let request = AnthropicRequest::from_open_ai(request?);
+            let response = stream_completion(api_url, credential, executor, request).await?;
+            let stream = response
+                .filter_map(|event| async move {
+                    match event {
+                        Ok(AnthropicStreamEvent::ContentBlockDelta {
+                            delta: AnthropicDelta::TextDelta { text },
+                        }) => Some(Ok(text)),
+                        Ok(AnthropicStreamEvent::Error { error }) => {
+                            Some(Err(anyhow!("Anthropic API error: {}", error.message)))
+                        }
+                        Ok(_) => None,
+                        Err(error) => Some(Err(error)),
+                    }
+                })
+                .boxed();
+            Ok(stream)
+        }
+        .boxed()
+    }
+
+    fn box_clone(&self) -> Box<dyn CompletionProvider> {
+        Box::new((*self).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+
+    use super::*;
+    use crate::{providers::open_ai::RequestMessage, test::FakeHttpServer};
+
+    fn request(messages: Vec<(Role, &str)>) -> OpenAiRequest {
+        OpenAiRequest {
+            model: "claude-2.1".into(),
+            messages: messages
+                .into_iter()
+                .map(|(role, content)| RequestMessage {
+                    role,
+                    content: content.into(),
+                })
+                .collect(),
+            stream: true,
+            stop: vec!["|END|>".into()],
+            temperature: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_request_from_open_ai() {
+        let request = AnthropicRequest::from_open_ai(request(vec![
+            (Role::System, "Be terse."),
+            (Role::User, "Hello"),
+            (Role::User, "there"),
+            (Role::Assistant, ""),
+            (Role::Assistant, "Hi!\n"),
+            (Role::User, "Bye"),
+        ]));
+        assert_eq!(request.system.as_deref(), Some("Be terse."));
+        assert_eq!(
+            request.messages,
+            vec![
+                AnthropicMessage {
+                    role: AnthropicRole::User,
+                    content: "Hello\n\nthere".into(),
+                },
+                AnthropicMessage {
+                    role: AnthropicRole::Assistant,
+                    content: "Hi!".into(),
+                },
+                AnthropicMessage {
+                    role: AnthropicRole::User,
+                    content: "Bye".into(),
+                },
+            ]
+        );
+        assert_eq!(request.stop_sequences, vec!["|END|>".to_string()]);
+    }
+
+    #[gpui::test]
+    async fn test_streaming_completion(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let server = FakeHttpServer::respond_once(
+            200,
+            "text/event-stream",
+            concat!(
+                "event: message_start\n",
+                "data: {\"type\":\"message_start\",\"message\":{}}\n\n",
+                "event: content_block_start\n",
+                "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+                "event: ping\n",
+                "data: {\"type\":\"ping\"}\n\n",
+                "event: content_block_delta\n",
+                "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hello\"}}\n\n",
+                "event: content_block_delta\n",
+                "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\", world\"}}\n\n",
+                "event: content_block_stop\n",
+                "data: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+                "event: message_delta\n",
+                "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"}}\n\n",
+                "event: message_stop\n",
+                "data: {\"type\":\"message_stop\"}\n\n",
+            ),
+        );
+
+        let provider = AnthropicCompletionProvider::new(
+            server.url(),
+            "claude-2.1".into(),
+            200_000,
+            cx.executor(),
+        )
+        .await;
+        cx.update(|cx| {
+            provider.save_credentials(
+                cx,
+                ProviderCredential::Credentials {
+                    api_key: "test-key".into(),
+                },
+            )
+        })
+        .await;
+        assert_eq!(provider.base_model().capacity().unwrap(), 200_000);
+
+        let stream = provider
+            .complete(Box::new(request(vec![(Role::User, "Hi")])))
+            .await
+            .unwrap();
+        let chunks = stream.collect::<Vec<_>>().await;
+        let chunks = chunks.into_iter().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(chunks, vec!["Hello".to_string(), ", world".to_string()]);
+
+        let request = server.request();
+        assert_eq!(request.request_line, "POST /messages HTTP/1.1");
+        assert_eq!(request.header("x-api-key"), Some("test-key"));
+        assert_eq!(
+            request.header("anthropic-version"),
+            Some(ANTHROPIC_API_VERSION)
+        );
+        let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["model"], "claude-2.1");
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["messages"][0]["content"], "Hi");
+    }
+
+    #[gpui::test]
+    async fn test_error_response(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let server = FakeHttpServer::respond_once(
+            401,
+            "application/json",
+            r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#,
+        );
+
+        let provider = AnthropicCompletionProvider::new(
+            server.url(),
+            "claude-2.1".into(),
+            200_000,
+            cx.executor(),
+        )
+        .await;
+        cx.update(|cx| {
+            provider.save_credentials(
+                cx,
+                ProviderCredential::Credentials {
+                    api_key: "wrong-key".into(),
+                },
+            )
+        })
+        .await;
+
+        let error = provider
+            .complete(Box::new(request(vec![(Role::User, "Hi")])))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Failed to connect to Anthropic API: invalid x-api-key"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use std::io;
+
+use anyhow::{anyhow, Result};
+use futures::{
+    future::BoxFuture, io::BufReader, stream::BoxStream, AsyncBufReadExt, AsyncReadExt, FutureExt,
+    Stream, StreamExt,
+};
+use gpui::{AppContext, BackgroundExecutor};
+use isahc::{http::StatusCode, Request, RequestExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{CredentialProvider, ProviderCredential},
+    completion::{CompletionProvider, CompletionRequest},
+    models::LanguageModel,
+    providers::open_ai::{OpenAiLanguageModel, OpenAiRequest, RequestMessage},
+};
+
+pub const OLLAMA_API_URL: &str = "http://localhost:11434";
+
+#[derive(Debug, Serialize)]
+pub struct OllamaRequest {
+    pub model: String,
+    pub messages: Vec<RequestMessage>,
+    pub stream: bool,
+    pub options: OllamaOptions,
+}
+
+/// https://github.com/ollama/ollama/blob/main/docs/modelfile.md#valid-parameters-and-values
+#[derive(Debug, Serialize)]
+pub struct OllamaOptions {
+    pub num_ctx: usize,
+    pub temperature: f32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+}
+
+impl OllamaRequest {
+    /// Converts a request in OpenAI's chat format, which is what the assistant builds.
+    pub fn from_open_ai(request: OpenAiRequest, num_ctx: usize) -> Self {
+        Self {
+            model: request.model,
+            messages: request.messages,
+            stream: true,
+            options: OllamaOptions {
+                num_ctx,
+                temperature: request.temperature,
+                stop: request.stop,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaResponseMessage {
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaResponseStreamEvent {
+    pub message: Option<OllamaResponseMessage>,
+    #[serde(default)]
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+async fn stream_completion(
+    api_url: String,
+    executor: BackgroundExecutor,
+    request: OllamaRequest,
+) -> Result<impl Stream<Item = Result<OllamaResponseStreamEvent>>> {
+    let (tx, rx) = futures::channel::mpsc::unbounded::<Result<OllamaResponseStreamEvent>>();
+
+    let json_data = serde_json::to_string(&request)?;
+    // https://github.com/ollama/ollama/blob/main/docs/api.md#generate-a-chat-completion
+    let mut response = Request::post(format!("{api_url}/api/chat"))
+        .header("Content-Type", "application/json")
+        .body(json_data)?
+        .send_async()
+        .await?;
+
+    let status = response.status();
+    if status == StatusCode::OK {
+        executor
+            .spawn(async move {
+                // Ollama streams one JSON object per line.
+                let mut lines = BufReader::new(response.body_mut()).lines();
+
+                fn parse_line(
+                    line: Result<String, io::Error>,
+                ) -> Result<Option<OllamaResponseStreamEvent>> {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        Ok(None)
+                    } else {
+                        Ok(Some(serde_json::from_str(&line)?))
+                    }
+                }
+
+                while let Some(line) = lines.next().await {
+                    if let Some(event) = parse_line(line).transpose() {
+                        let done = event
+                            .as_ref()
+                            .map_or(false, |event| event.done || event.error.is_some());
+                        if tx.unbounded_send(event).is_err() {
+                            break;
+                        }
+
+                        if done {
+                            break;
+                        }
+                    }
+                }
+
+                anyhow::Ok(())
+            })
+            .detach();
+
+        Ok(rx)
+    } else {
+        let mut body = String::new();
+        response.body_mut().read_to_string(&mut body).await?;
+
+        #[derive(Deserialize)]
+        struct OllamaResponse {
+            error: String,
+        }
+
+        match serde_json::from_str::<OllamaResponse>(&body) {
+            Ok(response) if !response.error.is_empty() => Err(anyhow!(
+                "Failed to connect to Ollama API: {}",
+                response.error,
+            )),
+
+            _ => Err(anyhow!(
+                "Failed to connect to Ollama API: {} {}",
+                response.status(),
+                body,
+            )),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OllamaCompletionProvider {
+    api_url: String,
+    model: OpenAiLanguageModel,
+    max_tokens: usize,
+    executor: BackgroundExecutor,
+}
+
+impl OllamaCompletionProvider {
+    pub async fn new(
+        api_url: String,
+        model_name: String,
+        max_tokens: usize,
+        executor: BackgroundExecutor,
+    ) -> Self {
+        // Ollama doesn't expose the tokenizers of its models, so token counts are estimated
+        // with OpenAI's.
+        let model = executor
+            .spawn(async move { OpenAiLanguageModel::load(&model_name).with_capacity(max_tokens) })
+            .await;
+        Self {
+            api_url,
+            model,
+            max_tokens,
+            executor,
+        }
+    }
+}
+
+impl CredentialProvider for OllamaCompletionProvider {
+    fn has_credentials(&self) -> bool {
+        true
+    }
+
+    fn retrieve_credentials(&self, _cx: &mut AppContext) -> BoxFuture<ProviderCredential> {
+        async { ProviderCredential::NotNeeded }.boxed()
+    }
+
+    fn save_credentials(
+        &self,
+        _cx: &mut AppContext,
+        _credential: ProviderCredential,
+    ) -> BoxFuture<()> {
+        async {}.boxed()
+    }
+
+    fn delete_credentials(&self, _cx: &mut AppContext) -> BoxFuture<()> {
+        async {}.boxed()
+    }
+}
+
+impl CompletionProvider for OllamaCompletionProvider {
+    fn base_model(&self) -> Box<dyn LanguageModel> {
+        let model: Box<dyn LanguageModel> = Box::new(self.model.clone());
+        model
+    }
+
+    fn complete(
+        &self,
+        prompt: Box<dyn CompletionRequest>,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+        let api_url = self.api_url.clone();
+        let executor = self.executor.clone();
+        let max_tokens = self.max_tokens;
+        let request = prompt
+            .data()
+            .and_then(|data| serde_json::from_str::<OpenAiRequest>(&data));
+        async move {
+            // Ollama defaults to a small context window, regardless of what the model supports.
+            let request = OllamaRequest::from_open_ai(request?, max_tokens);
+            let response = stream_completion(api_url, executor, request).await?;
+            let stream = response
+                .filter_map(|event| async move {
+                    match event {
+                        Ok(OllamaResponseStreamEvent {
+                            error: Some(error), ..
+                        }) => Some(Err(anyhow!("Ollama API error: {error}"))),
+                        Ok(event) => {
+                            let content = event.message?.content;
+                            (!content.is_empty()).then(|| Ok(content))
+                        }
+                        Err(error) => Some(Err(error)),
+                    }
+                })
+                .boxed();
+            Ok(stream)
+        }
+        .boxed()
+    }
+
+    fn box_clone(&self) -> Box<dyn CompletionProvider> {
+        Box::new((*self).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+
+    use super::*;
+    use crate::{providers::open_ai::Role, test::FakeHttpServer};
+
+    fn request() -> OpenAiRequest {
+        OpenAiRequest {
+            model: "llama2".into(),
+            messages: vec![
+                RequestMessage {
+                    role: Role::System,
+                    content: "Be terse.".into(),
+                },
+                RequestMessage {
+                    role: Role::User,
+                    content: "Hi".into(),
+                },
+            ],
+            stream: true,
+            stop: Vec::new(),
+            temperature: 0.5,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_streaming_completion(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let server = FakeHttpServer::respond_once(
+            200,
+            "application/x-ndjson",
+            concat!(
+                "{\"model\":\"llama2\",\"message\":{\"role\":\"assistant\",\"content\":\"Hello\"},\"done\":false}\n",
+                "{\"model\":\"llama2\",\"message\":{\"role\":\"assistant\",\"content\":\", world\"},\"done\":false}\n",
+                "{\"model\":\"llama2\",\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"eval_count\":3}\n",
+            ),
+        );
+
+        let provider =
+            OllamaCompletionProvider::new(server.url(), "llama2".into(), 8192, cx.executor()).await;
+        assert!(provider.has_credentials());
+        assert_eq!(provider.base_model().capacity().unwrap(), 8192);
+
+        let stream = provider.complete(Box::new(request())).await.unwrap();
+        let chunks = stream.collect::<Vec<_>>().await;
+        let chunks = chunks.into_iter().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(chunks, vec!["Hello".to_string(), ", world".to_string()]);
+
+        let request = server.request();
+        assert_eq!(request.request_line, "POST /api/chat HTTP/1.1");
+        assert_eq!(request.header("authorization"), None);
+        let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["model"], "llama2");
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["content"], "Hi");
+        assert_eq!(body["options"]["num_ctx"], 8192);
+        assert_eq!(body["options"]["temperature"], 0.5);
+    }
+
+    #[gpui::test]
+    async fn test_missing_model(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let server = FakeHttpServer::respond_once(
+            404,
+            "application/json",
+            r#"{"error":"model 'llama2' not found, try pulling it first"}"#,
+        );
+
+        let provider =
+            OllamaCompletionProvider::new(server.url(), "llama2".into(), 4096, cx.executor()).await;
+        let error = provider.complete(Box::new(request())).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Failed to connect to Ollama API: model 'llama2' not found, try pulling it first"
+        );
+    }
+}
@@ -58,12 +58,14 @@ pub struct RequestMessage {
     pub content: String,
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct OpenAiRequest {
     pub model: String,
     pub messages: Vec<RequestMessage>,
     pub stream: bool,
+    #[serde(default)]
     pub stop: Vec<String>,
+    #[serde(default)]
     pub temperature: f32,
 }
 
@@ -291,10 +293,11 @@ impl OpenAiCompletionProvider {
         api_url: String,
         kind: OpenAiCompletionProviderKind,
         model_name: String,
+        max_tokens: usize,
         executor: BackgroundExecutor,
     ) -> Self {
         let model = executor
-            .spawn(async move { OpenAiLanguageModel::load(&model_name) })
+            .spawn(async move { OpenAiLanguageModel::load(&model_name).with_capacity(max_tokens) })
             .await;
         let credential = Arc::new(RwLock::new(ProviderCredential::NoCredentials));
         Self {
@@ -9,6 +9,7 @@ use super::open_ai_bpe_tokenizer;
 pub struct OpenAiLanguageModel {
     name: String,
     bpe: Option<CoreBPE>,
+    capacity: Option<usize>,
 }
 
 impl OpenAiLanguageModel {
@@ -18,8 +19,15 @@ impl OpenAiLanguageModel {
         OpenAiLanguageModel {
             name: model_name.to_string(),
             bpe: Some(bpe),
+            capacity: None,
         }
     }
+
+    /// Overrides the context size, for models that tiktoken doesn't know about.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
 }
 
 impl LanguageModel for OpenAiLanguageModel {
@@ -54,6 +62,9 @@ impl LanguageModel for OpenAiLanguageModel {
         }
     }
     fn capacity(&self) -> anyhow::Result<usize> {
-        anyhow::Ok(tiktoken_rs::model::get_context_size(&self.name))
+        anyhow::Ok(
+            self.capacity
+                .unwrap_or_else(|| tiktoken_rs::model::get_context_size(&self.name)),
+        )
     }
 }
@@ -1,6 +1,12 @@
 use std::{
-    sync::atomic::{self, AtomicUsize, Ordering},
-    time::Instant,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{self, AtomicUsize, Ordering},
+        mpsc as std_mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
 use async_trait::async_trait;
@@ -204,3 +210,97 @@ impl CompletionProvider for FakeCompletionProvider {
         Box::new((*self).clone())
     }
 }
+
+/// A request received by a [`FakeHttpServer`].
+pub struct FakeHttpRequest {
+    /// The request line, e.g. `POST /v1/messages HTTP/1.1`.
+    pub request_line: String,
+    /// The headers, with lowercased names.
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl FakeHttpRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// An HTTP server on a local port that answers a single request with a canned response,
+/// for testing providers against the real HTTP stack.
+pub struct FakeHttpServer {
+    url: String,
+    requests: std_mpsc::Receiver<FakeHttpRequest>,
+}
+
+impl FakeHttpServer {
+    pub fn respond_once(status: u16, content_type: &str, body: impl Into<String>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let body = body.into();
+        let response = format!(
+            "HTTP/1.1 {status} {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            if status == 200 { "OK" } else { "Error" },
+            body.len(),
+        );
+
+        let (tx, requests) = std_mpsc::channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+                }
+            }
+
+            let request = FakeHttpRequest {
+                request_line: request_line.trim_end().to_string(),
+                headers,
+                body: String::new(),
+            };
+            if request.header("expect") == Some("100-continue") {
+                stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").unwrap();
+            }
+            let content_length = request
+                .header("content-length")
+                .and_then(|length| length.parse().ok())
+                .unwrap_or(0);
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            stream.write_all(response.as_bytes()).unwrap();
+            tx.send(FakeHttpRequest {
+                body: String::from_utf8(body).unwrap(),
+                ..request
+            })
+            .ok();
+        });
+
+        Self { url, requests }
+    }
+
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Returns the request the server received, waiting for it if necessary.
+    pub fn request(&self) -> FakeHttpRequest {
+        self.requests
+            .recv_timeout(Duration::from_secs(10))
+            .expect("no request received")
+    }
+}
@@ -2,12 +2,13 @@ pub mod assistant_panel;
 pub mod assistant_settings;
 mod codegen;
 mod prompts;
+mod slash_command;
 mod streaming_diff;
 
 use ai::providers::open_ai::Role;
 use anyhow::Result;
 pub use assistant_panel::AssistantPanel;
-use assistant_settings::OpenAiModel;
+use assistant_settings::AssistantModel;
 use chrono::{DateTime, Local};
 use collections::HashMap;
 use fs::Fs;
@@ -69,7 +70,7 @@ struct SavedConversation {
     message_metadata: HashMap<MessageId, MessageMetadata>,
     summary: String,
     api_url: Option<String>,
-    model: OpenAiModel,
+    model: AssistantModel,
 }
 
 impl SavedConversation {
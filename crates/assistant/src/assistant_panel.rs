@@ -1,7 +1,10 @@
 use crate::{
-    assistant_settings::{AssistantDockPosition, AssistantSettings, OpenAiModel},
+    assistant_settings::{
+        AiProviderSettings, AssistantDockPosition, AssistantModel, AssistantSettings, OpenAiModel,
+    },
     codegen::{self, Codegen, CodegenKind},
     prompts::generate_content_prompt,
+    slash_command::{SlashCommand, SlashCommandOutput},
     Assist, CycleMessageRole, InlineAssist, MessageId, MessageMetadata, MessageStatus,
     NewConversation, QuoteSelection, ResetKey, Role, SavedConversation, SavedConversationMetadata,
     SavedMessage, Split, ToggleFocus, ToggleIncludeConversation, ToggleRetrieveContext,
@@ -10,16 +13,17 @@ use ai::prompts::repository_context::PromptCodeSnippet;
 use ai::{
     auth::ProviderCredential,
     completion::{CompletionProvider, CompletionRequest},
-    providers::open_ai::{
-        OpenAiCompletionProvider, OpenAiCompletionProviderKind, OpenAiRequest, RequestMessage,
-        OPEN_AI_API_URL,
+    providers::{
+        anthropic::AnthropicCompletionProvider,
+        ollama::OllamaCompletionProvider,
+        open_ai::{OpenAiCompletionProvider, OpenAiRequest, RequestMessage, OPEN_AI_API_URL},
     },
 };
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Local};
 use collections::{hash_map, HashMap, HashSet, VecDeque};
 use editor::{
-    actions::{MoveDown, MoveUp},
+    actions::{MoveDown, MoveUp, Newline},
     display_map::{
         BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, ToDisplayPoint,
     },
@@ -37,7 +41,9 @@ use gpui::{
     StatefulInteractiveElement, Styled, Subscription, Task, TextStyle, UniformListScrollHandle,
     View, ViewContext, VisualContext, WeakModel, WeakView, WhiteSpace, WindowContext,
 };
-use language::{language_settings::SoftWrap, Buffer, BufferId, LanguageRegistry, ToOffset as _};
+use language::{
+    language_settings::SoftWrap, Buffer, BufferId, LanguageRegistry, Point, ToOffset as _,
+};
 use project::Project;
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use semantic_index::{SemanticIndex, SemanticIndexStatus};
@@ -124,22 +130,7 @@ impl AssistantPanel {
                 .await
                 .log_err()
                 .unwrap_or_default();
-            let (provider_kind, api_url, model_name) = cx.update(|cx| {
-                let settings = AssistantSettings::get_global(cx);
-                anyhow::Ok((
-                    settings.provider_kind()?,
-                    settings.provider_api_url()?,
-                    settings.provider_model_name()?,
-                ))
-            })??;
-
-            let completion_provider = OpenAiCompletionProvider::new(
-                api_url,
-                provider_kind,
-                model_name,
-                cx.background_executor().clone(),
-            )
-            .await;
+            let completion_provider = build_completion_provider(&mut cx).await?;
 
             // TODO: deserialize state.
             let workspace_handle = workspace.clone();
@@ -188,7 +179,7 @@ impl AssistantPanel {
                         zoomed: false,
                         focus_handle,
                         toolbar,
-                        completion_provider: Arc::new(completion_provider),
+                        completion_provider,
                         api_key_editor: None,
                         languages: workspace.app_state().languages.clone(),
                         fs: workspace.app_state().fs.clone(),
@@ -729,7 +720,7 @@ impl AssistantPanel {
                     .messages(cx)
                     .map(|message| message.to_open_ai_message(buffer)),
             );
-            model_name = conversation.model.full_name().to_string();
+            model_name = conversation.model.name().to_string();
         }
 
         cx.spawn(|_, mut cx| async move {
@@ -1119,12 +1110,18 @@ impl AssistantPanel {
         let fs = self.fs.clone();
         let workspace = self.workspace.clone();
         let languages = self.languages.clone();
+        let completion_provider = self.completion_provider.clone();
         cx.spawn(|this, mut cx| async move {
             let saved_conversation = fs.load(&path).await?;
             let saved_conversation = serde_json::from_str(&saved_conversation)?;
-            let conversation =
-                Conversation::deserialize(saved_conversation, path.clone(), languages, &mut cx)
-                    .await?;
+            let conversation = Conversation::deserialize(
+                saved_conversation,
+                path.clone(),
+                languages,
+                completion_provider,
+                &mut cx,
+            )
+            .await?;
 
             this.update(&mut cx, |this, cx| {
                 // If, by the time we've loaded the conversation, the user has already opened
@@ -1165,6 +1162,58 @@ impl AssistantPanel {
     }
 }
 
+async fn build_completion_provider(
+    cx: &mut AsyncWindowContext,
+) -> Result<Arc<dyn CompletionProvider>> {
+    let (provider, api_url, model) = cx.update(|cx| {
+        let settings = AssistantSettings::get_global(cx);
+        anyhow::Ok((
+            settings.provider.clone(),
+            settings.provider_api_url()?,
+            settings.provider_model()?,
+        ))
+    })??;
+
+    let executor = cx.background_executor().clone();
+    let completion_provider: Arc<dyn CompletionProvider> = match provider {
+        AiProviderSettings::OpenAi(_) | AiProviderSettings::AzureOpenAi(_) => {
+            let (provider_kind, model_name) = cx.update(|cx| {
+                let settings = AssistantSettings::get_global(cx);
+                anyhow::Ok((settings.provider_kind()?, settings.provider_model_name()?))
+            })??;
+            Arc::new(
+                OpenAiCompletionProvider::new(
+                    api_url,
+                    provider_kind,
+                    model_name,
+                    model.max_tokens(),
+                    executor,
+                )
+                .await,
+            )
+        }
+        AiProviderSettings::Anthropic(_) => Arc::new(
+            AnthropicCompletionProvider::new(
+                api_url,
+                model.name().to_string(),
+                model.max_tokens(),
+                executor,
+            )
+            .await,
+        ),
+        AiProviderSettings::Ollama(_) => Arc::new(
+            OllamaCompletionProvider::new(
+                api_url,
+                model.name().to_string(),
+                model.max_tokens(),
+                executor,
+            )
+            .await,
+        ),
+    };
+    Ok(completion_provider)
+}
+
 fn build_api_key_editor(cx: &mut WindowContext) -> View<Editor> {
     cx.new_view(|cx| {
         let mut editor = Editor::single_line(cx);
@@ -1176,7 +1225,7 @@ fn build_api_key_editor(cx: &mut WindowContext) -> View<Editor> {
 impl Render for AssistantPanel {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         if let Some(api_key_editor) = self.api_key_editor.clone() {
-            const INSTRUCTIONS: [&'static str; 6] = [
+            const OPEN_AI_INSTRUCTIONS: [&'static str; 6] = [
                 "To use the assistant panel or inline assistant, you need to add your OpenAI API key.",
                 " - You can create an API key at: platform.openai.com/api-keys",
                 " - Make sure your OpenAI account has credits",
@@ -1184,6 +1233,18 @@ impl Render for AssistantPanel {
                 " ",
                 "Paste your OpenAI API key and press Enter to use the assistant:"
             ];
+            const ANTHROPIC_INSTRUCTIONS: [&'static str; 6] = [
+                "To use the assistant panel or inline assistant, you need to add your Anthropic API key.",
+                " - You can create an API key at: console.anthropic.com/settings/keys",
+                " - Make sure your Anthropic account has credits",
+                " - Having a subscription for another service like GitHub Copilot won't work.",
+                " ",
+                "Paste your Anthropic API key and press Enter to use the assistant:"
+            ];
+            let instructions = match AssistantSettings::get_global(cx).provider {
+                AiProviderSettings::Anthropic(_) => ANTHROPIC_INSTRUCTIONS,
+                _ => OPEN_AI_INSTRUCTIONS,
+            };
 
             v_flex()
                 .p_4()
@@ -1191,7 +1252,7 @@ impl Render for AssistantPanel {
                 .on_action(cx.listener(AssistantPanel::save_credentials))
                 .track_focus(&self.focus_handle)
                 .children(
-                    INSTRUCTIONS.map(|instruction| Label::new(instruction).size(LabelSize::Small)),
+                    instructions.map(|instruction| Label::new(instruction).size(LabelSize::Small)),
                 )
                 .child(
                     h_flex()
@@ -1426,7 +1487,7 @@ struct Conversation {
     pending_summary: Task<Option<()>>,
     completion_count: usize,
     pending_completions: Vec<PendingCompletion>,
-    model: OpenAiModel,
+    model: AssistantModel,
     api_url: Option<String>,
     token_count: Option<usize>,
     max_token_count: usize,
@@ -1464,7 +1525,7 @@ impl Conversation {
         let model = settings
             .provider_model()
             .log_err()
-            .unwrap_or(OpenAiModel::FourTurbo);
+            .unwrap_or_else(|| OpenAiModel::FourTurbo.into());
         let api_url = settings
             .provider_api_url()
             .log_err()
@@ -1480,7 +1541,7 @@ impl Conversation {
             completion_count: Default::default(),
             pending_completions: Default::default(),
             token_count: None,
-            max_token_count: tiktoken_rs::model::get_context_size(&model.full_name()),
+            max_token_count: model.max_tokens(),
             pending_token_count: Task::ready(None),
             api_url: Some(api_url),
             model,
@@ -1527,7 +1588,7 @@ impl Conversation {
                 .as_ref()
                 .map(|summary| summary.text.clone())
                 .unwrap_or_default(),
-            model: self.model,
+            model: self.model.clone(),
             api_url: self.api_url.clone(),
         }
     }
@@ -1536,25 +1597,26 @@ impl Conversation {
         saved_conversation: SavedConversation,
         path: PathBuf,
         language_registry: Arc<LanguageRegistry>,
+        completion_provider: Arc<dyn CompletionProvider>,
         cx: &mut AsyncAppContext,
     ) -> Result<Model<Self>> {
         let id = match saved_conversation.id {
             Some(id) => Some(id),
             None => Some(Uuid::new_v4().to_string()),
         };
-        let model = saved_conversation.model;
         let api_url = saved_conversation.api_url;
-        let completion_provider: Arc<dyn CompletionProvider> = Arc::new(
-            OpenAiCompletionProvider::new(
-                api_url
-                    .clone()
-                    .unwrap_or_else(|| OPEN_AI_API_URL.to_string()),
-                OpenAiCompletionProviderKind::OpenAi,
-                model.full_name().into(),
-                cx.background_executor().clone(),
-            )
-            .await,
-        );
+        // The conversation continues with the configured provider, which might not offer the
+        // model the conversation was saved with.
+        let model = cx.update(|cx| {
+            let settings = AssistantSettings::get_global(cx);
+            let models = settings.provider_models().log_err().unwrap_or_default();
+            models
+                .iter()
+                .find(|model| model.name() == saved_conversation.model.name())
+                .or(models.first())
+                .cloned()
+                .unwrap_or(saved_conversation.model)
+        })?;
         cx.update(|cx| completion_provider.retrieve_credentials(cx))?
             .await;
 
@@ -1600,7 +1662,7 @@ impl Conversation {
                 completion_count: Default::default(),
                 pending_completions: Default::default(),
                 token_count: None,
-                max_token_count: tiktoken_rs::model::get_context_size(&model.full_name()),
+                max_token_count: model.max_tokens(),
                 pending_token_count: Task::ready(None),
                 api_url,
                 model,
@@ -1649,7 +1711,7 @@ impl Conversation {
                 function_call: None,
             })
             .collect::<Vec<_>>();
-        let model = self.model;
+        let model = self.model.clone();
         self.pending_token_count = cx.spawn(|this, mut cx| {
             async move {
                 cx.background_executor()
@@ -1658,13 +1720,15 @@ impl Conversation {
                 let token_count = cx
                     .background_executor()
                     .spawn(async move {
-                        tiktoken_rs::num_tokens_from_messages(&model.full_name(), &messages)
+                        tiktoken_rs::num_tokens_from_messages(
+                            model.tokenizer_model_name(),
+                            &messages,
+                        )
                     })
                     .await?;
 
                 this.update(&mut cx, |this, cx| {
-                    this.max_token_count =
-                        tiktoken_rs::model::get_context_size(&this.model.full_name());
+                    this.max_token_count = this.model.max_tokens();
                     this.token_count = Some(token_count);
                     cx.notify()
                 })?;
@@ -1678,7 +1742,7 @@ impl Conversation {
         Some(self.max_token_count as isize - self.token_count? as isize)
     }
 
-    fn set_model(&mut self, model: OpenAiModel, cx: &mut ModelContext<Self>) {
+    fn set_model(&mut self, model: AssistantModel, cx: &mut ModelContext<Self>) {
         self.model = model;
         self.count_remaining_tokens(cx);
         cx.notify();
@@ -1733,7 +1797,7 @@ impl Conversation {
             }
 
             let request: Box<dyn CompletionRequest> = Box::new(OpenAiRequest {
-                model: self.model.full_name().to_string(),
+                model: self.model.name().to_string(),
                 messages: self
                     .messages(cx)
                     .filter(|message| matches!(message.status, MessageStatus::Done))
@@ -2016,7 +2080,7 @@ impl Conversation {
                         .into(),
                 }));
             let request: Box<dyn CompletionRequest> = Box::new(OpenAiRequest {
-                model: self.model.full_name().to_string(),
+                model: self.model.name().to_string(),
                 messages: messages.collect(),
                 stream: true,
                 stop: vec![],
@@ -2198,12 +2262,27 @@ struct ScrollPosition {
     cursor: Anchor,
 }
 
+const SLASH_COMMAND_ERROR_TOAST_ID: usize = 0x5a5c0d;
+
+/// Text inserted by a slash command, which is rendered with a header that folds it away.
+struct SlashCommandSection {
+    id: usize,
+    range: Range<language::Anchor>,
+    title: String,
+    icon: IconName,
+    token_count: Option<usize>,
+    folded: bool,
+}
+
 struct ConversationEditor {
     conversation: Model<Conversation>,
     fs: Arc<dyn Fs>,
     workspace: WeakView<Workspace>,
     editor: View<Editor>,
     blocks: HashSet<BlockId>,
+    slash_command_sections: Vec<SlashCommandSection>,
+    slash_command_blocks: HashSet<BlockId>,
+    next_slash_command_section_id: usize,
     scroll_position: Option<ScrollPosition>,
     _subscriptions: Vec<Subscription>,
 }
@@ -2245,6 +2324,9 @@ impl ConversationEditor {
             conversation,
             editor,
             blocks: Default::default(),
+            slash_command_sections: Vec::new(),
+            slash_command_blocks: Default::default(),
+            next_slash_command_section_id: 0,
             scroll_position: None,
             fs,
             workspace,
@@ -2294,6 +2376,200 @@ impl ConversationEditor {
         }
     }
 
+    fn run_slash_command(&mut self, _: &Newline, cx: &mut ViewContext<Self>) {
+        let Some((command, line_range)) = self.slash_command_at_cursor(cx) else {
+            cx.propagate();
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            cx.propagate();
+            return;
+        };
+
+        let output = command.run(&workspace, cx);
+        cx.spawn(|this, mut cx| async move {
+            match output.await {
+                Ok(output) => this.update(&mut cx, |this, cx| {
+                    this.insert_slash_command_output(line_range, output, cx)
+                }),
+                Err(error) => workspace.update(&mut cx, |workspace, cx| {
+                    workspace.show_toast(
+                        Toast::new(
+                            SLASH_COMMAND_ERROR_TOAST_ID,
+                            format!("Slash command failed: {error}"),
+                        ),
+                        cx,
+                    )
+                }),
+            }
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn slash_command_at_cursor(
+        &self,
+        cx: &AppContext,
+    ) -> Option<(SlashCommand, Range<language::Anchor>)> {
+        let editor = self.editor.read(cx);
+        if editor.selections.count() != 1 {
+            return None;
+        }
+        let selection = editor.selections.newest::<Point>(cx);
+        if !selection.is_empty() {
+            return None;
+        }
+
+        let buffer = self.conversation.read(cx).buffer.read(cx);
+        let row = selection.head().row;
+        let line_range = Point::new(row, 0)..Point::new(row, buffer.line_len(row));
+        let line = buffer
+            .text_for_range(line_range.clone())
+            .collect::<String>();
+        let command = SlashCommand::parse(&line)?;
+        Some((
+            command,
+            buffer.anchor_before(line_range.start)..buffer.anchor_after(line_range.end),
+        ))
+    }
+
+    fn insert_slash_command_output(
+        &mut self,
+        line_range: Range<language::Anchor>,
+        output: SlashCommandOutput,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let token_count = self
+            .conversation
+            .read(cx)
+            .completion_provider
+            .base_model()
+            .count_tokens(&output.text)
+            .log_err();
+        let range = self.conversation.update(cx, |conversation, cx| {
+            conversation.buffer.update(cx, |buffer, cx| {
+                let start = line_range.start.to_offset(buffer);
+                buffer.edit([(line_range, format!("{}\n", output.text))], None, cx);
+                start..start + output.text.len()
+            })
+        });
+
+        let buffer = self.conversation.read(cx).buffer.read(cx);
+        let section_range = buffer.anchor_after(range.start)..buffer.anchor_before(range.end);
+        self.slash_command_sections.push(SlashCommandSection {
+            id: post_inc(&mut self.next_slash_command_section_id),
+            range: section_range,
+            title: output.title,
+            icon: output.icon,
+            token_count,
+            folded: true,
+        });
+
+        self.editor.update(cx, |editor, cx| {
+            editor.fold_ranges([range.clone()], false, cx);
+            let cursor = range.end + 1;
+            editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                selections.select_ranges([cursor..cursor])
+            });
+        });
+        self.update_slash_command_headers(cx);
+    }
+
+    fn toggle_slash_command_section(&mut self, section_id: usize, cx: &mut ViewContext<Self>) {
+        let Some(section) = self
+            .slash_command_sections
+            .iter_mut()
+            .find(|section| section.id == section_id)
+        else {
+            return;
+        };
+
+        let buffer = self.conversation.read(cx).buffer.read(cx);
+        let range = section.range.start.to_offset(buffer)..section.range.end.to_offset(buffer);
+        self.editor.update(cx, |editor, cx| {
+            // The fold may have been toggled from within the editor, so check its actual state.
+            let snapshot = editor.snapshot(cx);
+            let start_row = range.start.to_point(&snapshot.buffer_snapshot).row;
+            if snapshot.is_line_folded(start_row) {
+                editor.unfold_ranges([range], true, false, cx);
+                section.folded = false;
+            } else {
+                editor.fold_ranges([range], false, cx);
+                section.folded = true;
+            }
+        });
+        self.update_slash_command_headers(cx);
+    }
+
+    fn update_slash_command_headers(&mut self, cx: &mut ViewContext<Self>) {
+        let buffer = self.conversation.read(cx).buffer.read(cx).snapshot();
+        // Drop sections whose text was deleted.
+        self.slash_command_sections
+            .retain(|section| section.range.start.cmp(&section.range.end, &buffer).is_lt());
+
+        let this = cx.view().downgrade();
+        self.editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            let excerpt_id = *buffer.as_singleton().unwrap().0;
+            let old_blocks = std::mem::take(&mut self.slash_command_blocks);
+            let new_blocks = self
+                .slash_command_sections
+                .iter()
+                .map(|section| {
+                    let section_id = section.id;
+                    let title = SharedString::from(section.title.clone());
+                    let icon = section.icon;
+                    let token_count = section.token_count;
+                    let folded = section.folded;
+                    let this = this.clone();
+                    BlockProperties {
+                        position: buffer
+                            .anchor_in_excerpt(excerpt_id, section.range.start)
+                            .unwrap(),
+                        height: 1,
+                        style: BlockStyle::Sticky,
+                        render: Arc::new(move |_cx| {
+                            let this = this.clone();
+                            ButtonLike::new(("slash_command_section", section_id))
+                                .style(ButtonStyle::Subtle)
+                                .child(
+                                    h_flex()
+                                        .gap_1()
+                                        .child(
+                                            Icon::new(if folded {
+                                                IconName::ChevronRight
+                                            } else {
+                                                IconName::ChevronDown
+                                            })
+                                            .size(IconSize::Small)
+                                            .color(Color::Muted),
+                                        )
+                                        .child(Icon::new(icon).size(IconSize::Small))
+                                        .child(Label::new(title.clone()))
+                                        .children(token_count.map(|token_count| {
+                                            Label::new(format!("{token_count} tokens"))
+                                                .size(LabelSize::XSmall)
+                                                .color(Color::Muted)
+                                        })),
+                                )
+                                .on_click(move |_, cx| {
+                                    this.update(cx, |this, cx| {
+                                        this.toggle_slash_command_section(section_id, cx)
+                                    })
+                                    .ok();
+                                })
+                                .into_any_element()
+                        }),
+                        disposition: BlockDisposition::Above,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            editor.remove_blocks(old_blocks, None, cx);
+            let ids = editor.insert_blocks(new_blocks, None, cx);
+            self.slash_command_blocks = HashSet::from_iter(ids);
+        });
+    }
+
     fn cancel_last_assist(&mut self, _: &editor::actions::Cancel, cx: &mut ViewContext<Self>) {
         if !self
             .conversation
@@ -2332,6 +2608,7 @@ impl ConversationEditor {
         match event {
             ConversationEvent::MessagesEdited => {
                 self.update_message_headers(cx);
+                self.update_slash_command_headers(cx);
                 self.conversation.update(cx, |conversation, cx| {
                     conversation.save(Some(Duration::from_millis(500)), self.fs.clone(), cx);
                 });
@@ -2608,9 +2885,18 @@ impl ConversationEditor {
     }
 
     fn cycle_model(&mut self, cx: &mut ViewContext<Self>) {
+        let models = AssistantSettings::get_global(cx)
+            .provider_models()
+            .log_err()
+            .unwrap_or_default();
         self.conversation.update(cx, |conversation, cx| {
-            let new_model = conversation.model.cycle();
-            conversation.set_model(new_model, cx);
+            let next_ix = models
+                .iter()
+                .position(|model| model.name() == conversation.model.name())
+                .map_or(0, |ix| (ix + 1) % models.len());
+            if let Some(new_model) = models.get(next_ix).cloned() {
+                conversation.set_model(new_model, cx);
+            }
         });
     }
 
@@ -2626,7 +2912,7 @@ impl ConversationEditor {
     fn render_current_model(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         Button::new(
             "current_model",
-            self.conversation.read(cx).model.short_name(),
+            self.conversation.read(cx).model.short_name().to_string(),
         )
         .style(ButtonStyle::Filled)
         .tooltip(move |cx| Tooltip::text("Change Model", cx))
@@ -2656,6 +2942,7 @@ impl Render for ConversationEditor {
             .capture_action(cx.listener(ConversationEditor::save))
             .capture_action(cx.listener(ConversationEditor::copy))
             .capture_action(cx.listener(ConversationEditor::cycle_message_role))
+            .capture_action(cx.listener(ConversationEditor::run_slash_command))
             .on_action(cx.listener(ConversationEditor::assist))
             .on_action(cx.listener(ConversationEditor::split))
             .size_full()
@@ -3585,7 +3872,7 @@ mod tests {
         let registry = Arc::new(LanguageRegistry::test(cx.executor()));
         let completion_provider = Arc::new(FakeCompletionProvider::new());
         let conversation =
-            cx.new_model(|cx| Conversation::new(registry.clone(), cx, completion_provider));
+            cx.new_model(|cx| Conversation::new(registry.clone(), cx, completion_provider.clone()));
         let buffer = conversation.read_with(cx, |conversation, _| conversation.buffer.clone());
         let message_0 =
             conversation.read_with(cx, |conversation, _| conversation.message_anchors[0].id);
@@ -3623,6 +3910,7 @@ mod tests {
             conversation.read_with(cx, |conversation, cx| conversation.serialize(cx)),
             Default::default(),
             registry.clone(),
+            completion_provider,
             &mut cx.to_async(),
         )
         .await
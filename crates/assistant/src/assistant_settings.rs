@@ -1,5 +1,7 @@
-use ai::providers::open_ai::{
-    AzureOpenAiApiVersion, OpenAiCompletionProviderKind, OPEN_AI_API_URL,
+use ai::providers::{
+    anthropic::ANTHROPIC_API_URL,
+    ollama::OLLAMA_API_URL,
+    open_ai::{AzureOpenAiApiVersion, OpenAiCompletionProviderKind, OPEN_AI_API_URL},
 };
 use anyhow::anyhow;
 use gpui::Pixels;
@@ -42,6 +44,63 @@ impl OpenAiModel {
             Self::FourTurbo => Self::ThreePointFiveTurbo,
         }
     }
+
+    fn all() -> [Self; 3] {
+        [Self::ThreePointFiveTurbo, Self::Four, Self::FourTurbo]
+    }
+}
+
+/// A model to use for completions, referred to by the name its provider knows it by.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(untagged)]
+pub enum AssistantModel {
+    /// Only the model's name, e.g. `"gpt-4-1106-preview"` or `"llama2"`.
+    Name(String),
+    /// The model's name along with the size of its context window, in tokens.
+    WithContextSize { name: String, max_tokens: usize },
+}
+
+impl AssistantModel {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::WithContextSize { name, .. } => name,
+        }
+    }
+
+    pub fn short_name(&self) -> &str {
+        OpenAiModel::all()
+            .into_iter()
+            .find(|model| model.full_name() == self.name())
+            .map_or(self.name(), |model| model.short_name())
+    }
+
+    /// The size of the model's context window, in tokens.
+    pub fn max_tokens(&self) -> usize {
+        match self {
+            Self::WithContextSize { max_tokens, .. } => *max_tokens,
+            // https://docs.anthropic.com/claude/reference/selecting-a-model
+            Self::Name(name) if name.starts_with("claude-2.1") => 200_000,
+            Self::Name(name) if name.starts_with("claude-") => 100_000,
+            Self::Name(name) => tiktoken_rs::model::get_context_size(name),
+        }
+    }
+
+    /// The name of the model whose tokenizer is used to count this model's tokens.
+    ///
+    /// Only OpenAI's tokenizers are available, so other models' token counts are estimates.
+    pub fn tokenizer_model_name(&self) -> &str {
+        if self.name().starts_with("gpt-") {
+            self.name()
+        } else {
+            OpenAiModel::Four.full_name()
+        }
+    }
+}
+
+impl From<OpenAiModel> for AssistantModel {
+    fn from(model: OpenAiModel) -> Self {
+        Self::Name(model.full_name().to_string())
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
@@ -90,6 +149,9 @@ impl AssistantSettings {
                     api_version,
                 })
             }
+            AiProviderSettings::Anthropic(_) | AiProviderSettings::Ollama(_) => {
+                Err(anyhow!("not an OpenAI provider"))
+            }
         }
     }
 
@@ -103,14 +165,23 @@ impl AssistantSettings {
                 .api_url
                 .clone()
                 .ok_or_else(|| anyhow!("no Azure OpenAI API URL")),
+            AiProviderSettings::Anthropic(settings) => Ok(settings
+                .api_url
+                .clone()
+                .unwrap_or_else(|| ANTHROPIC_API_URL.to_string())),
+            AiProviderSettings::Ollama(settings) => Ok(settings
+                .api_url
+                .clone()
+                .unwrap_or_else(|| OLLAMA_API_URL.to_string())),
         }
     }
 
-    pub fn provider_model(&self) -> anyhow::Result<OpenAiModel> {
+    pub fn provider_model(&self) -> anyhow::Result<AssistantModel> {
         match &self.provider {
-            AiProviderSettings::OpenAi(settings) => {
-                Ok(settings.default_model.unwrap_or(OpenAiModel::FourTurbo))
-            }
+            AiProviderSettings::OpenAi(settings) => Ok(settings
+                .default_model
+                .clone()
+                .unwrap_or_else(|| OpenAiModel::FourTurbo.into())),
             AiProviderSettings::AzureOpenAi(settings) => {
                 let deployment_id = settings
                     .deployment_id
@@ -119,34 +190,76 @@ impl AssistantSettings {
 
                 match deployment_id {
                     // https://learn.microsoft.com/en-us/azure/ai-services/openai/concepts/models#gpt-4-and-gpt-4-turbo-preview
-                    "gpt-4" | "gpt-4-32k" => Ok(OpenAiModel::Four),
+                    "gpt-4" | "gpt-4-32k" => Ok(OpenAiModel::Four.into()),
                     // https://learn.microsoft.com/en-us/azure/ai-services/openai/concepts/models#gpt-35
                     "gpt-35-turbo" | "gpt-35-turbo-16k" | "gpt-35-turbo-instruct" => {
-                        Ok(OpenAiModel::ThreePointFiveTurbo)
+                        Ok(OpenAiModel::ThreePointFiveTurbo.into())
                     }
                     _ => Err(anyhow!(
                         "no matching OpenAI model found for deployment ID: '{deployment_id}'"
                     )),
                 }
             }
+            AiProviderSettings::Anthropic(settings) => Ok(settings
+                .default_model
+                .clone()
+                .unwrap_or_else(|| AssistantModel::Name(DEFAULT_ANTHROPIC_MODEL.to_string()))),
+            AiProviderSettings::Ollama(settings) => Ok(settings
+                .default_model
+                .clone()
+                .or_else(|| settings.available_models.as_ref()?.first().cloned())
+                .unwrap_or_else(|| AssistantModel::Name(DEFAULT_OLLAMA_MODEL.to_string()))),
         }
     }
 
     pub fn provider_model_name(&self) -> anyhow::Result<String> {
         match &self.provider {
-            AiProviderSettings::OpenAi(settings) => Ok(settings
-                .default_model
-                .unwrap_or(OpenAiModel::FourTurbo)
-                .full_name()
-                .to_string()),
             AiProviderSettings::AzureOpenAi(settings) => settings
                 .deployment_id
                 .clone()
                 .ok_or_else(|| anyhow!("no Azure OpenAI deployment ID")),
+            _ => Ok(self.provider_model()?.name().to_string()),
         }
     }
+
+    /// The models that conversations can switch between, starting with the default one.
+    pub fn provider_models(&self) -> anyhow::Result<Vec<AssistantModel>> {
+        let available_models = match &self.provider {
+            AiProviderSettings::OpenAi(settings) => settings
+                .available_models
+                .clone()
+                .unwrap_or_else(|| OpenAiModel::all().into_iter().map(Into::into).collect()),
+            AiProviderSettings::AzureOpenAi(_) => Vec::new(),
+            AiProviderSettings::Anthropic(settings) => {
+                settings.available_models.clone().unwrap_or_else(|| {
+                    ANTHROPIC_MODELS
+                        .iter()
+                        .map(|name| AssistantModel::Name(name.to_string()))
+                        .collect()
+                })
+            }
+            AiProviderSettings::Ollama(settings) => {
+                settings.available_models.clone().unwrap_or_default()
+            }
+        };
+
+        let default_model = self.provider_model()?;
+        let mut models = vec![default_model.clone()];
+        models.extend(
+            available_models
+                .into_iter()
+                .filter(|model| model.name() != default_model.name()),
+        );
+        Ok(models)
+    }
 }
 
+/// https://docs.anthropic.com/claude/reference/selecting-a-model
+const ANTHROPIC_MODELS: [&str; 3] = ["claude-2.1", "claude-2.0", "claude-instant-1.2"];
+const DEFAULT_ANTHROPIC_MODEL: &str = ANTHROPIC_MODELS[0];
+/// https://ollama.com/library/llama2
+const DEFAULT_OLLAMA_MODEL: &str = "llama2";
+
 impl Settings for AssistantSettings {
     const KEY: Option<&'static str> = Some("assistant");
 
@@ -157,7 +270,17 @@ impl Settings for AssistantSettings {
         user_values: &[&Self::FileContent],
         _: &mut gpui::AppContext,
     ) -> anyhow::Result<Self> {
-        Self::load_via_json_merge(default_value, user_values)
+        let mut settings: Self = Self::load_via_json_merge(default_value, user_values)?;
+        // The defaults for one provider don't apply to the others, so the provider
+        // settings are taken as a whole from the last file that configures them.
+        if let Some(provider) = user_values
+            .iter()
+            .rev()
+            .find_map(|value| value.provider.as_ref())
+        {
+            settings.provider = serde_json::from_value(serde_json::to_value(provider)?)?;
+        }
+        Ok(settings)
     }
 }
 
@@ -193,8 +316,7 @@ pub struct AssistantSettingsContent {
     #[deprecated = "Please use `provider.api_url` instead."]
     pub openai_api_url: Option<String>,
     /// The settings for the AI provider.
-    #[serde(default)]
-    pub provider: AiProviderSettingsContent,
+    pub provider: Option<AiProviderSettingsContent>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -206,6 +328,12 @@ pub enum AiProviderSettings {
     /// The settings for the Azure OpenAI provider.
     #[serde(rename = "azure_openai")]
     AzureOpenAi(AzureOpenAiProviderSettings),
+    /// The settings for the Anthropic provider.
+    #[serde(rename = "anthropic")]
+    Anthropic(AnthropicProviderSettings),
+    /// The settings for the Ollama provider.
+    #[serde(rename = "ollama")]
+    Ollama(OllamaProviderSettings),
 }
 
 /// The settings for the AI provider used by the Zed Assistant.
@@ -218,6 +346,12 @@ pub enum AiProviderSettingsContent {
     /// The settings for the Azure OpenAI provider.
     #[serde(rename = "azure_openai")]
     AzureOpenAi(AzureOpenAiProviderSettingsContent),
+    /// The settings for the Anthropic provider.
+    #[serde(rename = "anthropic")]
+    Anthropic(AnthropicProviderSettingsContent),
+    /// The settings for a local Ollama server.
+    #[serde(rename = "ollama")]
+    Ollama(OllamaProviderSettingsContent),
 }
 
 impl Default for AiProviderSettingsContent {
@@ -231,19 +365,28 @@ pub struct OpenAiProviderSettings {
     /// The OpenAI API base URL to use when starting new conversations.
     pub api_url: Option<String>,
     /// The default OpenAI model to use when starting new conversations.
-    pub default_model: Option<OpenAiModel>,
+    pub default_model: Option<AssistantModel>,
+    /// The models that conversations can switch between.
+    pub available_models: Option<Vec<AssistantModel>>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OpenAiProviderSettingsContent {
     /// The OpenAI API base URL to use when starting new conversations.
+    /// This can point to any server implementing OpenAI's chat completions API.
     ///
     /// Default: https://api.openai.com/v1
     pub api_url: Option<String>,
     /// The default OpenAI model to use when starting new conversations.
+    /// Either a model name, or an object with `name` and `max_tokens` for
+    /// models whose context size isn't known to Zed.
     ///
     /// Default: gpt-4-1106-preview
-    pub default_model: Option<OpenAiModel>,
+    pub default_model: Option<AssistantModel>,
+    /// The models that conversations can switch between.
+    ///
+    /// Default: ["gpt-3.5-turbo-0613", "gpt-4-0613", "gpt-4-1106-preview"]
+    pub available_models: Option<Vec<AssistantModel>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -265,3 +408,148 @@ pub struct AzureOpenAiProviderSettingsContent {
     /// The Azure OpenAI deployment ID.
     pub deployment_id: Option<String>,
 }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnthropicProviderSettings {
+    /// The Anthropic API base URL to use when starting new conversations.
+    pub api_url: Option<String>,
+    /// The default Anthropic model to use when starting new conversations.
+    pub default_model: Option<AssistantModel>,
+    /// The models that conversations can switch between.
+    pub available_models: Option<Vec<AssistantModel>>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AnthropicProviderSettingsContent {
+    /// The Anthropic API base URL to use when starting new conversations.
+    ///
+    /// Default: https://api.anthropic.com/v1
+    pub api_url: Option<String>,
+    /// The default Anthropic model to use when starting new conversations.
+    ///
+    /// Default: claude-2.1
+    pub default_model: Option<AssistantModel>,
+    /// The models that conversations can switch between.
+    ///
+    /// Default: ["claude-2.1", "claude-2.0", "claude-instant-1.2"]
+    pub available_models: Option<Vec<AssistantModel>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OllamaProviderSettings {
+    /// The URL of the Ollama server.
+    pub api_url: Option<String>,
+    /// The default model to use when starting new conversations.
+    pub default_model: Option<AssistantModel>,
+    /// The models that conversations can switch between.
+    pub available_models: Option<Vec<AssistantModel>>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OllamaProviderSettingsContent {
+    /// The URL of the Ollama server.
+    ///
+    /// Default: http://localhost:11434
+    pub api_url: Option<String>,
+    /// The default model to use when starting new conversations, which must
+    /// have been pulled into Ollama already. Either a model name, or an object
+    /// with `name` and `max_tokens` to set the size of its context window.
+    pub default_model: Option<AssistantModel>,
+    /// The models that conversations can switch between.
+    pub available_models: Option<Vec<AssistantModel>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::AppContext;
+    use settings::SettingsStore;
+
+    use super::*;
+
+    #[gpui::test]
+    fn test_provider_settings(cx: &mut AppContext) {
+        let store = SettingsStore::test(cx);
+        cx.set_global(store);
+        AssistantSettings::register(cx);
+
+        let settings = AssistantSettings::get_global(cx);
+        assert_eq!(settings.provider_api_url().unwrap(), OPEN_AI_API_URL);
+        assert_eq!(
+            settings.provider_models().unwrap(),
+            vec![
+                OpenAiModel::FourTurbo.into(),
+                OpenAiModel::ThreePointFiveTurbo.into(),
+                OpenAiModel::Four.into(),
+            ]
+        );
+
+        // The OpenAI defaults aren't carried over to other providers.
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store
+                .set_user_settings(
+                    r#"{
+                        "assistant": {
+                            "provider": {
+                                "type": "ollama",
+                                "default_model": { "name": "codellama", "max_tokens": 16384 },
+                                "available_models": ["llama2"]
+                            }
+                        }
+                    }"#,
+                    cx,
+                )
+                .unwrap();
+        });
+        let settings = AssistantSettings::get_global(cx);
+        assert_eq!(settings.provider_api_url().unwrap(), OLLAMA_API_URL);
+        let model = settings.provider_model().unwrap();
+        assert_eq!(model.name(), "codellama");
+        assert_eq!(model.max_tokens(), 16384);
+        assert_eq!(model.tokenizer_model_name(), "gpt-4-0613");
+        assert_eq!(
+            settings.provider_models().unwrap(),
+            vec![model, AssistantModel::Name("llama2".into())]
+        );
+
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store
+                .set_user_settings(
+                    r#"{ "assistant": { "provider": { "type": "anthropic" } } }"#,
+                    cx,
+                )
+                .unwrap();
+        });
+        let settings = AssistantSettings::get_global(cx);
+        assert_eq!(settings.provider_api_url().unwrap(), ANTHROPIC_API_URL);
+        let model = settings.provider_model().unwrap();
+        assert_eq!(model.name(), "claude-2.1");
+        assert_eq!(model.max_tokens(), 200_000);
+
+        // Ollama falls back to its first available model, then to a default one.
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store
+                .set_user_settings(
+                    r#"{
+                        "assistant": {
+                            "provider": { "type": "ollama", "available_models": ["mistral"] }
+                        }
+                    }"#,
+                    cx,
+                )
+                .unwrap();
+        });
+        let settings = AssistantSettings::get_global(cx);
+        assert_eq!(settings.provider_model().unwrap().name(), "mistral");
+
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store
+                .set_user_settings(
+                    r#"{ "assistant": { "provider": { "type": "ollama" } } }"#,
+                    cx,
+                )
+                .unwrap();
+        });
+        let settings = AssistantSettings::get_global(cx);
+        assert_eq!(settings.provider_model().unwrap().name(), "llama2");
+    }
+}
@@ -0,0 +1,556 @@
+use std::{fmt::Write, sync::atomic::AtomicBool};
+
+use anyhow::{anyhow, Result};
+use collections::HashSet;
+use editor::Editor;
+use gpui::{AppContext, Model, Task, View, WindowContext};
+use language::{Buffer, BufferSnapshot, DiagnosticSeverity, Point, ToOffset as _, ToPoint as _};
+use project::{search::SearchQuery, PathMatchCandidateSet, Project, ProjectPath, WorktreeId};
+use semantic_index::SemanticIndex;
+use ui::IconName;
+use workspace::Workspace;
+
+/// How many matches `/search` and `/semantic` insert at most.
+const MAX_SEARCH_RESULTS: usize = 50;
+const MAX_SEMANTIC_RESULTS: usize = 10;
+/// How many bytes of a file `/file` inserts at most, cut at the end of a line.
+const MAX_FILE_LEN: usize = 32 * 1024;
+
+/// A command typed on its own line in a conversation, which is replaced by context from the
+/// project when confirmed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum SlashCommand {
+    File(String),
+    Diagnostics,
+    Selection,
+    Symbols,
+    Search(String),
+    Semantic(String),
+}
+
+pub(crate) struct SlashCommandOutput {
+    pub title: String,
+    pub icon: IconName,
+    pub text: String,
+}
+
+impl SlashCommand {
+    pub fn parse(line: &str) -> Option<Self> {
+        let command = line.trim().strip_prefix('/')?;
+        let (name, argument) = command
+            .split_once(char::is_whitespace)
+            .map_or((command, ""), |(name, argument)| (name, argument.trim()));
+        match (name, argument) {
+            ("file", path) if !path.is_empty() => Some(Self::File(path.to_string())),
+            ("diagnostics", "") => Some(Self::Diagnostics),
+            ("selection", "") => Some(Self::Selection),
+            ("symbols", "") => Some(Self::Symbols),
+            ("search", query) if !query.is_empty() => Some(Self::Search(query.to_string())),
+            ("semantic", query) if !query.is_empty() => Some(Self::Semantic(query.to_string())),
+            _ => None,
+        }
+    }
+
+    pub fn run(
+        self,
+        workspace: &View<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<SlashCommandOutput>> {
+        let project = workspace.read(cx).project().clone();
+        match self {
+            Self::File(query) => run_file(query, project, cx),
+            Self::Diagnostics => run_diagnostics(project, cx),
+            Self::Selection => run_selection(workspace, cx),
+            Self::Symbols => run_symbols(workspace, cx),
+            Self::Search(query) => run_search(query, project, cx),
+            Self::Semantic(query) => run_semantic(query, project, cx),
+        }
+    }
+}
+
+fn run_file(
+    query: String,
+    project: Model<Project>,
+    cx: &mut WindowContext,
+) -> Task<Result<SlashCommandOutput>> {
+    let worktrees = project.read(cx).visible_worktrees(cx).collect::<Vec<_>>();
+    let include_root_name = worktrees.len() > 1;
+    let candidate_sets = worktrees
+        .into_iter()
+        .map(|worktree| {
+            let worktree = worktree.read(cx);
+            PathMatchCandidateSet {
+                snapshot: worktree.snapshot(),
+                include_ignored: worktree
+                    .root_entry()
+                    .map_or(false, |entry| entry.is_ignored),
+                include_root_name,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let executor = cx.background_executor().clone();
+    cx.spawn(|mut cx| async move {
+        let cancel_flag = AtomicBool::new(false);
+        let path_match = fuzzy::match_path_sets(
+            candidate_sets.as_slice(),
+            &query,
+            None,
+            false,
+            1,
+            &cancel_flag,
+            executor,
+        )
+        .await
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("no file matches '{query}'"))?;
+
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_usize(path_match.worktree_id),
+            path: path_match.path,
+        };
+        let buffer = project
+            .update(&mut cx, |project, cx| project.open_buffer(project_path, cx))?
+            .await?;
+        cx.update(|cx| {
+            let path = buffer_path(&buffer, cx);
+            let snapshot = buffer.read(cx).snapshot();
+            let mut text = snapshot.text();
+            let mut title = path.clone();
+            if text.len() > MAX_FILE_LEN {
+                let line_count = text.lines().count();
+                truncate_to_lines(&mut text, MAX_FILE_LEN);
+                title = format!(
+                    "{path} (first {} of {line_count} lines)",
+                    text.lines().count()
+                );
+            }
+            Ok(SlashCommandOutput {
+                title,
+                icon: IconName::File,
+                text: code_block(&snapshot, Some(&path), &text),
+            })
+        })?
+    })
+}
+
+fn run_diagnostics(
+    project: Model<Project>,
+    cx: &mut WindowContext,
+) -> Task<Result<SlashCommandOutput>> {
+    let mut paths = HashSet::default();
+    let project_paths = project
+        .read(cx)
+        .diagnostic_summaries(false, cx)
+        .filter(|(_, _, summary)| summary.error_count + summary.warning_count > 0)
+        .filter_map(|(path, _, _)| paths.insert(path.clone()).then_some(path))
+        .collect::<Vec<_>>();
+
+    cx.spawn(|mut cx| async move {
+        let mut text = String::new();
+        let (mut error_count, mut warning_count) = (0, 0);
+        for project_path in project_paths {
+            let buffer = project
+                .update(&mut cx, |project, cx| project.open_buffer(project_path, cx))?
+                .await?;
+            cx.update(|cx| {
+                let path = buffer_path(&buffer, cx);
+                let snapshot = buffer.read(cx).snapshot();
+                for entry in snapshot.diagnostics_in_range::<_, Point>(0..snapshot.len(), false) {
+                    let diagnostic = &entry.diagnostic;
+                    if !diagnostic.is_primary {
+                        continue;
+                    }
+                    let severity = match diagnostic.severity {
+                        DiagnosticSeverity::ERROR => {
+                            error_count += 1;
+                            "error"
+                        }
+                        DiagnosticSeverity::WARNING => {
+                            warning_count += 1;
+                            "warning"
+                        }
+                        _ => continue,
+                    };
+                    let start = entry.range.start;
+                    writeln!(
+                        text,
+                        "{path}:{}:{}: {severity}: {}",
+                        start.row + 1,
+                        start.column + 1,
+                        diagnostic.message
+                    )
+                    .unwrap();
+                }
+            })?;
+        }
+
+        if text.is_empty() {
+            return Err(anyhow!("no errors or warnings in the project"));
+        }
+        Ok(SlashCommandOutput {
+            title: format!("Diagnostics ({error_count} errors, {warning_count} warnings)"),
+            icon: IconName::XCircle,
+            text: text.trim_end().to_string(),
+        })
+    })
+}
+
+fn active_buffer(workspace: &View<Workspace>, cx: &WindowContext) -> Result<View<Editor>> {
+    workspace
+        .read(cx)
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))
+        .ok_or_else(|| anyhow!("no active editor"))
+}
+
+fn run_selection(
+    workspace: &View<Workspace>,
+    cx: &mut WindowContext,
+) -> Task<Result<SlashCommandOutput>> {
+    let output = active_buffer(workspace, cx).and_then(|editor| {
+        let editor = editor.read(cx);
+        let range = editor.selections.newest::<usize>(cx).range();
+        if range.is_empty() {
+            return Err(anyhow!("no text selected"));
+        }
+
+        let (excerpt_buffer, start, end) = editor
+            .buffer()
+            .read(cx)
+            .range_to_buffer_ranges(range, cx)
+            .into_iter()
+            .next()
+            .map(|(buffer, range, _)| (buffer, range.start, range.end))
+            .ok_or_else(|| anyhow!("no text selected"))?;
+        let path = buffer_path(&excerpt_buffer, cx);
+        let snapshot = excerpt_buffer.read(cx).snapshot();
+        let start_row = snapshot.offset_to_point(start).row + 1;
+        let end_row = snapshot.offset_to_point(end).row + 1;
+        let location = format!("{path}:{start_row}-{end_row}");
+        Ok(SlashCommandOutput {
+            title: format!("Selection from {location}"),
+            icon: IconName::Snip,
+            text: code_block(
+                &snapshot,
+                Some(&location),
+                &snapshot.text_for_range(start..end).collect::<String>(),
+            ),
+        })
+    });
+    Task::ready(output)
+}
+
+fn run_symbols(
+    workspace: &View<Workspace>,
+    cx: &mut WindowContext,
+) -> Task<Result<SlashCommandOutput>> {
+    let output = active_buffer(workspace, cx).and_then(|editor| {
+        let buffer = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .ok_or_else(|| anyhow!("the active editor doesn't contain a single file"))?;
+        let path = buffer_path(&buffer, cx);
+        let snapshot = buffer.read(cx).snapshot();
+        let outline = snapshot
+            .outline(None)
+            .ok_or_else(|| anyhow!("no symbols in {path}"))?;
+
+        let mut text = String::new();
+        for item in outline.items {
+            let row = item.range.start.to_point(&snapshot).row + 1;
+            writeln!(
+                text,
+                "{}{} (line {row})",
+                "  ".repeat(item.depth),
+                item.text
+            )
+            .unwrap();
+        }
+        Ok(SlashCommandOutput {
+            title: format!("Symbols in {path}"),
+            icon: IconName::Hash,
+            text: format!("{path}\n{}", text.trim_end()),
+        })
+    });
+    Task::ready(output)
+}
+
+fn run_search(
+    query: String,
+    project: Model<Project>,
+    cx: &mut WindowContext,
+) -> Task<Result<SlashCommandOutput>> {
+    let search_query = match SearchQuery::text(&query, false, false, false, Vec::new(), Vec::new())
+    {
+        Ok(search_query) => search_query,
+        Err(error) => return Task::ready(Err(error)),
+    };
+    let results = project.update(cx, |project, cx| project.search(search_query, cx));
+
+    cx.spawn(|mut cx| async move {
+        let mut text = String::new();
+        let mut match_count = 0;
+        while let Ok(result) = results.recv().await {
+            let project::search::SearchResult::Buffer { buffer, ranges } = result else {
+                break;
+            };
+            cx.update(|cx| {
+                let path = buffer_path(&buffer, cx);
+                let snapshot = buffer.read(cx).snapshot();
+                writeln!(text, "{path}").unwrap();
+                let mut last_row = None;
+                for range in ranges {
+                    let row = range.start.to_point(&snapshot).row;
+                    if last_row == Some(row) {
+                        continue;
+                    }
+                    last_row = Some(row);
+                    match_count += 1;
+                    let line = snapshot
+                        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+                        .collect::<String>();
+                    writeln!(text, "{}: {}", row + 1, line.trim()).unwrap();
+                }
+                writeln!(text).unwrap();
+            })?;
+
+            if match_count >= MAX_SEARCH_RESULTS {
+                break;
+            }
+        }
+
+        if match_count == 0 {
+            return Err(anyhow!("no matches for '{query}'"));
+        }
+        Ok(SlashCommandOutput {
+            title: format!("Search results for '{query}'"),
+            icon: IconName::MagnifyingGlass,
+            text: text.trim_end().to_string(),
+        })
+    })
+}
+
+fn run_semantic(
+    query: String,
+    project: Model<Project>,
+    cx: &mut WindowContext,
+) -> Task<Result<SlashCommandOutput>> {
+    let Some(semantic_index) = SemanticIndex::global(cx) else {
+        return Task::ready(Err(anyhow!("semantic search is not available")));
+    };
+    let results = semantic_index.update(cx, |semantic_index, cx| {
+        semantic_index.search_project(
+            project,
+            query.clone(),
+            MAX_SEMANTIC_RESULTS,
+            Vec::new(),
+            Vec::new(),
+            cx,
+        )
+    });
+
+    cx.spawn(|mut cx| async move {
+        let results = results.await?;
+        if results.is_empty() {
+            return Err(anyhow!("no results for '{query}'"));
+        }
+
+        cx.update(|cx| {
+            let mut text = String::new();
+            for result in results {
+                let path = buffer_path(&result.buffer, cx);
+                let snapshot = result.buffer.read(cx).snapshot();
+                let range =
+                    result.range.start.to_offset(&snapshot)..result.range.end.to_offset(&snapshot);
+                let start_row = snapshot.offset_to_point(range.start).row + 1;
+                let location = format!("{path}:{start_row}");
+                let excerpt = snapshot.text_for_range(range).collect::<String>();
+                writeln!(
+                    text,
+                    "{}\n",
+                    code_block(&snapshot, Some(&location), &excerpt)
+                )
+                .unwrap();
+            }
+            Ok(SlashCommandOutput {
+                title: format!("Semantic search results for '{query}'"),
+                icon: IconName::MagnifyingGlass,
+                text: text.trim_end().to_string(),
+            })
+        })?
+    })
+}
+
+fn buffer_path(buffer: &Model<Buffer>, cx: &AppContext) -> String {
+    buffer
+        .read(cx)
+        .file()
+        .map(|file| file.full_path(cx).to_string_lossy().to_string())
+        .unwrap_or_else(|| "untitled".to_string())
+}
+
+/// Truncates `text` to at most `max_len` bytes, at the end of a line if there is one.
+fn truncate_to_lines(text: &mut String, max_len: usize) {
+    if text.len() <= max_len {
+        return;
+    }
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    if let Some(newline_ix) = text[..end].rfind('\n') {
+        end = newline_ix + 1;
+    }
+    text.truncate(end);
+}
+
+/// Wraps `text` in a fenced code block, tagged with the buffer's language and `location`.
+fn code_block(buffer: &BufferSnapshot, location: Option<&str>, text: &str) -> String {
+    let language = buffer
+        .language()
+        .map(|language| language.name().to_lowercase())
+        .unwrap_or_default();
+    let info = match location {
+        Some(location) if !language.is_empty() => format!("{language} {location}"),
+        Some(location) => location.to_string(),
+        None => language,
+    };
+    // Use a longer fence when the text itself contains one.
+    let mut fence = "```".to_string();
+    while text.contains(fence.as_str()) {
+        fence.push('`');
+    }
+    format!("{fence}{info}\n{}\n{fence}", text.trim_end_matches('\n'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualTestContext};
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            SlashCommand::parse("/file src/main.rs"),
+            Some(SlashCommand::File("src/main.rs".into()))
+        );
+        assert_eq!(
+            SlashCommand::parse("  /search  fn main  "),
+            Some(SlashCommand::Search("fn main".into()))
+        );
+        assert_eq!(
+            SlashCommand::parse("/diagnostics"),
+            Some(SlashCommand::Diagnostics)
+        );
+        assert_eq!(
+            SlashCommand::parse("/selection"),
+            Some(SlashCommand::Selection)
+        );
+        assert_eq!(SlashCommand::parse("/symbols"), Some(SlashCommand::Symbols));
+        assert_eq!(
+            SlashCommand::parse("/semantic where is auth handled"),
+            Some(SlashCommand::Semantic("where is auth handled".into()))
+        );
+        assert_eq!(SlashCommand::parse("/file"), None);
+        assert_eq!(SlashCommand::parse("/symbols foo"), None);
+        assert_eq!(SlashCommand::parse("/unknown"), None);
+        assert_eq!(SlashCommand::parse("file src/main.rs"), None);
+        assert_eq!(SlashCommand::parse("a/b"), None);
+    }
+
+    #[test]
+    fn test_truncate_to_lines() {
+        let mut text = "one\ntwo\nthree\n".to_string();
+        truncate_to_lines(&mut text, 100);
+        assert_eq!(text, "one\ntwo\nthree\n");
+        truncate_to_lines(&mut text, 10);
+        assert_eq!(text, "one\ntwo\n");
+
+        // A line longer than the limit is cut at a character boundary.
+        let mut text = "ααααα".to_string();
+        truncate_to_lines(&mut text, 5);
+        assert_eq!(text, "αα");
+    }
+
+    #[gpui::test]
+    async fn test_file_and_search_commands(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init_settings(cx);
+        });
+
+        let large_file = (0..5000)
+            .map(|ix| format!("line {ix:>4}\n"))
+            .collect::<String>();
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "src": {
+                    "main.rs": "fn main() {\n    println!(\"hello\");\n}\n",
+                    "lib.rs": "pub fn hello() {}\n",
+                    "large.txt": large_file,
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let cx: &mut VisualTestContext = cx;
+
+        let output = cx
+            .update(|cx| SlashCommand::File("main".into()).run(&workspace, cx))
+            .await
+            .unwrap();
+        assert_eq!(output.title, "root/src/main.rs");
+        assert_eq!(
+            output.text,
+            "```root/src/main.rs\nfn main() {\n    println!(\"hello\");\n}\n```"
+        );
+
+        let output = cx
+            .update(|cx| SlashCommand::File("large".into()).run(&workspace, cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            output.title,
+            "root/src/large.txt (first 3276 of 5000 lines)"
+        );
+        assert!(output.text.len() <= MAX_FILE_LEN + "```root/src/large.txt\n\n```".len());
+        assert!(output.text.ends_with("line 3275\n```"));
+
+        let output = cx
+            .update(|cx| SlashCommand::Search("hello".into()).run(&workspace, cx))
+            .await
+            .unwrap();
+        assert_eq!(output.title, "Search results for 'hello'");
+        let mut sections = output.text.split("\n\n").collect::<Vec<_>>();
+        sections.sort();
+        assert_eq!(
+            sections,
+            [
+                "root/src/lib.rs\n1: pub fn hello() {}",
+                "root/src/main.rs\n2: println!(\"hello\");",
+            ]
+        );
+
+        let error = cx
+            .update(|cx| SlashCommand::Search("missing".into()).run(&workspace, cx))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "no matches for 'missing'");
+    }
+}
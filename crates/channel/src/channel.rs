@@ -1,23 +1,32 @@
+mod channel_activity_store;
 mod channel_buffer;
 mod channel_chat;
 mod channel_store;
+mod comment_store;
+mod persistence;
 
 use client::{Client, UserStore};
 use gpui::{AppContext, Model};
 use std::sync::Arc;
 
-pub use channel_buffer::{ChannelBuffer, ChannelBufferEvent, ACKNOWLEDGE_DEBOUNCE_INTERVAL};
+pub use channel_activity_store::{ChannelActivity, ChannelActivityKind, ChannelActivityStore};
+pub use channel_buffer::{
+    ChannelBuffer, ChannelBufferCheckpoint, ChannelBufferEvent, ACKNOWLEDGE_DEBOUNCE_INTERVAL,
+};
 pub use channel_chat::{
-    mentions_to_proto, ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId,
-    MessageParams,
+    mentions_to_proto, ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageEdit,
+    ChannelMessageId, ChannelMessageReaction, MessageParams,
 };
 pub use channel_store::{Channel, ChannelEvent, ChannelMembership, ChannelStore};
+pub use comment_store::{Comment, CommentStore, CommentStoreEvent, CommentTarget, CommentThread};
 
 #[cfg(test)]
 mod channel_store_tests;
 
 pub fn init(client: &Arc<Client>, user_store: Model<UserStore>, cx: &mut AppContext) {
-    channel_store::init(client, user_store, cx);
+    channel_store::init(client, user_store.clone(), cx);
+    comment_store::init(client, user_store.clone(), cx);
+    channel_activity_store::init(client, user_store, cx);
     channel_buffer::init(client);
     channel_chat::init(client);
 }
@@ -0,0 +1,263 @@
+use anyhow::{Context as _, Result};
+use client::{ChannelId, Client, Subscription, User, UserStore};
+use collections::HashSet;
+use futures::StreamExt;
+use gpui::{AppContext, AsyncAppContext, Context, Global, Model, ModelContext, Task};
+use rpc::{proto, TypedEnvelope};
+use std::sync::Arc;
+use time::OffsetDateTime;
+use util::ResultExt;
+
+pub fn init(client: &Arc<Client>, user_store: Model<UserStore>, cx: &mut AppContext) {
+    let activity_store =
+        cx.new_model(|cx| ChannelActivityStore::new(client.clone(), user_store, cx));
+    cx.set_global(GlobalChannelActivityStore(activity_store));
+}
+
+/// Keeps track of what other members have been doing in the user's channels, and of how
+/// much of it the user has seen.
+pub struct ChannelActivityStore {
+    /// The loaded activity, newest first.
+    activities: Vec<ChannelActivity>,
+    observed_at: Option<OffsetDateTime>,
+    loaded_all_activities: bool,
+    client: Arc<Client>,
+    user_store: Model<UserStore>,
+    _watch_connection_status: Task<Option<()>>,
+    _rpc_subscription: Subscription,
+}
+
+#[derive(Clone, Debug)]
+pub struct ChannelActivity {
+    pub channel_id: ChannelId,
+    pub user: Arc<User>,
+    pub timestamp: OffsetDateTime,
+    pub kind: ChannelActivityKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChannelActivityKind {
+    JoinedCall,
+    SharedProject {
+        project_id: u64,
+    },
+    /// Edits to the channel's notes that the user hasn't seen, summarized per author.
+    EditedNotes,
+    Mention {
+        message_id: u64,
+        body: String,
+    },
+}
+
+struct GlobalChannelActivityStore(Model<ChannelActivityStore>);
+
+impl Global for GlobalChannelActivityStore {}
+
+impl ChannelActivityStore {
+    pub fn global(cx: &AppContext) -> Model<Self> {
+        cx.global::<GlobalChannelActivityStore>().0.clone()
+    }
+
+    pub fn new(
+        client: Arc<Client>,
+        user_store: Model<UserStore>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        let mut connection_status = client.status();
+        let watch_connection_status = cx.spawn(|this, mut cx| async move {
+            while let Some(status) = connection_status.next().await {
+                let this = this.upgrade()?;
+                if status.is_connected() {
+                    if let Some(task) = this
+                        .update(&mut cx, |this, cx| this.load_more_activities(true, cx))
+                        .log_err()?
+                    {
+                        task.await.log_err();
+                    }
+                } else {
+                    this.update(&mut cx, |_, cx| cx.notify()).log_err()?;
+                }
+            }
+            Some(())
+        });
+
+        Self {
+            activities: Vec::new(),
+            observed_at: None,
+            loaded_all_activities: false,
+            _watch_connection_status: watch_connection_status,
+            _rpc_subscription: client
+                .add_message_handler(cx.weak_model(), Self::handle_channel_activity_added),
+            client,
+            user_store,
+        }
+    }
+
+    pub fn activity_count(&self) -> usize {
+        self.activities.len()
+    }
+
+    /// Returns the nth newest activity.
+    pub fn activity_at(&self, ix: usize) -> Option<&ChannelActivity> {
+        self.activities.get(ix)
+    }
+
+    pub fn loaded_all_activities(&self) -> bool {
+        self.loaded_all_activities
+    }
+
+    pub fn is_unread(&self, activity: &ChannelActivity) -> bool {
+        self.observed_at
+            .map_or(true, |observed_at| activity.timestamp > observed_at)
+    }
+
+    pub fn unread_activity_count(&self) -> usize {
+        self.activities
+            .iter()
+            .take_while(|activity| self.is_unread(activity))
+            .count()
+    }
+
+    /// Loads the next page of older activity, or reloads the newest page when `clear_old`
+    /// is true, which also refreshes the summary of unseen edits to channel notes.
+    pub fn load_more_activities(
+        &mut self,
+        clear_old: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        if self.loaded_all_activities && !clear_old {
+            return None;
+        }
+
+        let before = if clear_old {
+            None
+        } else {
+            self.activities
+                .last()
+                .map(|activity| timestamp_to_proto(activity.timestamp))
+        };
+        let request = self.client.request(proto::GetChannelActivity { before });
+        let user_store = self.user_store.clone();
+        Some(cx.spawn(|this, mut cx| async move {
+            let response = request.await?;
+            let activities =
+                ChannelActivity::from_proto_vec(response.activities, &user_store, &mut cx).await?;
+            let observed_at = response.observed_at.map(timestamp_from_proto).transpose()?;
+            this.update(&mut cx, |this, cx| {
+                if clear_old {
+                    this.activities.clear();
+                }
+                this.activities.extend(activities);
+                this.observed_at = observed_at.max(this.observed_at);
+                this.loaded_all_activities = response.done;
+                cx.notify();
+            })
+        }))
+    }
+
+    /// Marks all of the loaded activity as read.
+    pub fn acknowledge(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(newest) = self.activities.first() else {
+            return;
+        };
+        if !self.is_unread(newest) {
+            return;
+        }
+
+        let timestamp = newest.timestamp;
+        self.observed_at = Some(timestamp);
+        self.client
+            .send(proto::AckChannelActivity {
+                timestamp: timestamp_to_proto(timestamp),
+            })
+            .log_err();
+        cx.notify();
+    }
+
+    async fn handle_channel_activity_added(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::ChannelActivityAdded>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let activity = message
+            .payload
+            .activity
+            .context("invalid channel activity")?;
+        let user_store = this.update(&mut cx, |this, _| this.user_store.clone())?;
+        let activities =
+            ChannelActivity::from_proto_vec(vec![activity], &user_store, &mut cx).await?;
+        this.update(&mut cx, |this, cx| {
+            let current_user_id = this.user_store.read(cx).current_user().map(|user| user.id);
+            for activity in activities {
+                // The server sends the user's own activity to all of their connections too.
+                if Some(activity.user.id) == current_user_id {
+                    continue;
+                }
+                let ix = this
+                    .activities
+                    .partition_point(|existing| existing.timestamp > activity.timestamp);
+                this.activities.insert(ix, activity);
+            }
+            cx.notify();
+        })
+    }
+}
+
+impl ChannelActivity {
+    async fn from_proto_vec(
+        activities: Vec<proto::ChannelActivity>,
+        user_store: &Model<UserStore>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Vec<Self>> {
+        let unique_user_ids = activities
+            .iter()
+            .map(|activity| activity.user_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        user_store
+            .update(cx, |user_store, cx| {
+                user_store.get_users(unique_user_ids, cx)
+            })?
+            .await?;
+
+        let mut result = Vec::with_capacity(activities.len());
+        for activity in activities {
+            let kind = match activity.kind() {
+                proto::ChannelActivityKind::JoinedCall => ChannelActivityKind::JoinedCall,
+                proto::ChannelActivityKind::SharedProject => ChannelActivityKind::SharedProject {
+                    project_id: activity.project_id.context("invalid project id")?,
+                },
+                proto::ChannelActivityKind::EditedNotes => ChannelActivityKind::EditedNotes,
+                proto::ChannelActivityKind::Mention => ChannelActivityKind::Mention {
+                    message_id: activity.message_id.context("invalid message id")?,
+                    body: activity.body.clone().unwrap_or_default(),
+                },
+            };
+            let user = user_store
+                .update(cx, |user_store, cx| {
+                    user_store.get_user(activity.user_id, cx)
+                })?
+                .await?;
+            result.push(ChannelActivity {
+                channel_id: ChannelId(activity.channel_id),
+                user,
+                timestamp: timestamp_from_proto(activity.timestamp)?,
+                kind,
+            });
+        }
+        Ok(result)
+    }
+}
+
+/// Channel activity timestamps are sent as microseconds since the Unix epoch.
+fn timestamp_to_proto(timestamp: OffsetDateTime) -> u64 {
+    (timestamp.unix_timestamp_nanos() / 1000) as u64
+}
+
+fn timestamp_from_proto(timestamp: u64) -> Result<OffsetDateTime> {
+    Ok(OffsetDateTime::from_unix_timestamp_nanos(
+        timestamp as i128 * 1000,
+    )?)
+}
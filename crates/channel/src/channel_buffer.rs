@@ -1,18 +1,22 @@
-use crate::{Channel, ChannelStore};
-use anyhow::Result;
-use client::{ChannelId, Client, Collaborator, UserStore, ZED_ALWAYS_ACTIVE};
-use collections::HashMap;
+use crate::{persistence::DB, Channel, ChannelStore};
+use anyhow::{anyhow, Result};
+use client::{ChannelId, Client, Collaborator, User, UserStore, ZED_ALWAYS_ACTIVE};
+use collections::{HashMap, HashSet};
 use gpui::{AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task};
-use language::proto::serialize_version;
+use language::proto::{deserialize_version, serialize_version};
+use prost::Message as _;
 use rpc::{
     proto::{self, PeerId},
     TypedEnvelope,
 };
-use std::{sync::Arc, time::Duration};
+use similar::{ChangeTag, TextDiff};
+use std::{ops::Range, sync::Arc, time::Duration};
 use text::BufferId;
+use time::OffsetDateTime;
 use util::ResultExt;
 
 pub const ACKNOWLEDGE_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(250);
+const SAVE_OFFLINE_EDITS_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(500);
 
 pub(crate) fn init(client: &Arc<Client>) {
     client.add_model_message_handler(ChannelBuffer::handle_update_channel_buffer);
@@ -30,6 +34,27 @@ pub struct ChannelBuffer {
     client: Arc<Client>,
     subscription: Option<client::Subscription>,
     acknowledge_task: Option<Task<Result<()>>>,
+    offline_edits: Option<OfflineEdits>,
+    save_offline_edits_task: Option<Task<()>>,
+}
+
+/// The edits made to the notes while the connection to the server was lost.
+struct OfflineEdits {
+    /// The version of the buffer when the connection was lost.
+    version: clock::Global,
+    /// The contents of the buffer when the connection was lost, which the offline edits
+    /// are merged from if they can't be replayed.
+    base_text: text::BufferSnapshot,
+    operations: Vec<proto::Operation>,
+}
+
+/// A saved version of the channel notes, taken when everyone stopped editing them.
+#[derive(Clone, Debug)]
+pub struct ChannelBufferCheckpoint {
+    pub epoch: u64,
+    pub created_at: OffsetDateTime,
+    /// The users who edited the notes since the previous checkpoint.
+    pub authors: Vec<Arc<User>>,
 }
 
 pub enum ChannelBufferEvent {
@@ -37,6 +62,7 @@ pub enum ChannelBufferEvent {
     Disconnected,
     BufferEdited,
     ChannelChanged,
+    ConnectionChanged,
 }
 
 impl EventEmitter<ChannelBufferEvent> for ChannelBuffer {}
@@ -62,6 +88,19 @@ impl ChannelBuffer {
             .map(language::proto::deserialize_operation)
             .collect::<Result<Vec<_>, _>>()?;
 
+        // Look for edits that were made while offline, before the app was restarted.
+        let user_id = client.user_id();
+        let channel_id = channel.id;
+        let offline_edits = cx
+            .background_executor()
+            .spawn(async move {
+                DB.offline_channel_buffer(user_id?, channel_id.0)
+                    .log_err()
+                    .flatten()
+            })
+            .await
+            .map(|offline_edits| (base_text.clone(), operations.clone(), offline_edits));
+
         let buffer = cx.new_model(|cx| {
             let capability = channel_store.read(cx).channel_capability(channel.id);
             language::Buffer::remote(buffer_id, response.replica_id as u16, capability, base_text)
@@ -84,14 +123,29 @@ impl ChannelBuffer {
                 subscription: Some(subscription.set_model(&cx.handle(), &mut cx.to_async())),
                 user_store,
                 channel_store,
+                offline_edits: None,
+                save_offline_edits_task: None,
             };
             this.replace_collaborators(response.collaborators, cx);
+            if let Some((base_text, operations, offline_edits)) = offline_edits {
+                // Keep the offline edits around until they've been merged into the notes.
+                if this
+                    .restore_offline_edits(base_text, operations, offline_edits, cx)
+                    .log_err()
+                    .is_some()
+                {
+                    this.delete_offline_edits(cx);
+                }
+            }
             this
         })?)
     }
 
     fn release(&mut self, _: &mut AppContext) {
-        if self.connected {
+        if let Some(task) = self.save_offline_edits_task.take() {
+            task.detach();
+        }
+        if self.is_connected() {
             if let Some(task) = self.acknowledge_task.take() {
                 task.detach();
             }
@@ -189,6 +243,16 @@ impl ChannelBuffer {
                     }
                 }
                 let operation = language::proto::serialize_operation(operation);
+                if let Some(offline_edits) = &mut self.offline_edits {
+                    if !matches!(
+                        operation.variant,
+                        Some(proto::operation::Variant::UpdateSelections(_))
+                    ) {
+                        offline_edits.operations.push(operation);
+                        self.save_offline_edits(cx);
+                    }
+                    return;
+                }
                 self.client
                     .send(proto::UpdateChannelBuffer {
                         channel_id: self.channel_id.0,
@@ -225,6 +289,86 @@ impl ChannelBuffer {
         }));
     }
 
+    /// Lists the saved versions of the notes, newest first.
+    pub fn checkpoints(
+        &self,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<ChannelBufferCheckpoint>>> {
+        let response = self.client.request(proto::GetChannelBufferCheckpoints {
+            channel_id: self.channel_id.0,
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(|_, mut cx| async move {
+            let response = response.await?;
+            let author_ids = response
+                .checkpoints
+                .iter()
+                .flat_map(|checkpoint| checkpoint.author_ids.iter().copied())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            let authors = user_store
+                .update(&mut cx, |user_store, cx| {
+                    user_store.get_users(author_ids, cx)
+                })?
+                .await?
+                .into_iter()
+                .map(|user| (user.id, user))
+                .collect::<HashMap<_, _>>();
+
+            let mut checkpoints = Vec::with_capacity(response.checkpoints.len());
+            for checkpoint in response.checkpoints {
+                checkpoints.push(ChannelBufferCheckpoint {
+                    epoch: checkpoint.epoch,
+                    created_at: OffsetDateTime::from_unix_timestamp(checkpoint.created_at as i64)?,
+                    authors: checkpoint
+                        .author_ids
+                        .iter()
+                        .filter_map(|author_id| authors.get(author_id).cloned())
+                        .collect(),
+                });
+            }
+            Ok(checkpoints)
+        })
+    }
+
+    /// Loads the text of the notes as of the given checkpoint.
+    pub fn load_checkpoint(&self, epoch: u64, cx: &mut ModelContext<Self>) -> Task<Result<String>> {
+        let response = self.client.request(proto::LoadChannelBufferCheckpoint {
+            channel_id: self.channel_id.0,
+            epoch,
+        });
+        cx.background_executor()
+            .spawn(async move { Ok(response.await?.text) })
+    }
+
+    /// Replaces the contents of the notes with the text of the given checkpoint.
+    ///
+    /// The restore is applied as an ordinary edit, so it is sent to the other collaborators
+    /// and can be undone.
+    pub fn restore_checkpoint(&self, epoch: u64, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        if self.buffer.read(cx).read_only() {
+            return Task::ready(Err(anyhow!(
+                "you don't have permission to edit these notes"
+            )));
+        }
+
+        let text = self.load_checkpoint(epoch, cx);
+        let buffer = self.buffer.clone();
+        cx.spawn(|_, mut cx| async move {
+            let text = text.await?;
+            let diff = buffer
+                .update(&mut cx, |buffer, cx| buffer.diff(text, cx))?
+                .await;
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.finalize_last_transaction();
+                buffer.apply_diff(diff, cx);
+                buffer.finalize_last_transaction();
+            })?;
+            Ok(())
+        })
+    }
+
     pub fn epoch(&self) -> u64 {
         self.buffer_epoch
     }
@@ -244,6 +388,186 @@ impl ChannelBuffer {
             .cloned()
     }
 
+    /// Keeps the notes editable after the connection was lost, queueing the edits
+    /// until the buffer is rejoined.
+    pub(crate) fn go_offline(&mut self, cx: &mut ModelContext<Self>) {
+        if self.connected && self.offline_edits.is_none() {
+            log::info!("channel buffer {} is offline", self.channel_id);
+            let buffer = self.buffer.read(cx);
+            self.offline_edits = Some(OfflineEdits {
+                version: buffer.version(),
+                base_text: buffer.text_snapshot(),
+                operations: Vec::new(),
+            });
+            cx.emit(ChannelBufferEvent::ConnectionChanged);
+            cx.notify();
+        }
+    }
+
+    /// Called once the buffer has been rejoined, and the operations made while
+    /// offline have been sent to the server.
+    pub(crate) fn rejoined(&mut self, cx: &mut ModelContext<Self>) {
+        if self.offline_edits.take().is_some() {
+            self.delete_offline_edits(cx);
+            cx.emit(ChannelBufferEvent::ConnectionChanged);
+            cx.notify();
+        }
+    }
+
+    /// The version to rejoin the buffer with. If the buffer went offline, this is the version
+    /// from when the connection was lost, so that the server can tell whether the buffer's
+    /// replica id has been used by anyone else since.
+    pub(crate) fn rejoin_version(&self, cx: &AppContext) -> clock::Global {
+        if let Some(offline_edits) = &self.offline_edits {
+            offline_edits.version.clone()
+        } else {
+            self.buffer.read(cx).version()
+        }
+    }
+
+    fn save_offline_edits(&mut self, cx: &mut ModelContext<Self>) {
+        let (Some(user_id), Some(offline_edits)) = (self.client.user_id(), &self.offline_edits)
+        else {
+            return;
+        };
+
+        let version = proto::ChannelBufferVersion {
+            channel_id: self.channel_id.0,
+            version: serialize_version(&offline_edits.version),
+            epoch: self.buffer_epoch,
+            replica_id: Some(self.replica_id(cx) as u32),
+        }
+        .encode_to_vec();
+        let mut operations = Vec::new();
+        for operation in &offline_edits.operations {
+            operation.encode_length_delimited(&mut operations).log_err();
+        }
+
+        let base_text = offline_edits.base_text.clone();
+        let text = self.buffer.read(cx).text_snapshot();
+        let channel_id = self.channel_id.0;
+        self.save_offline_edits_task = Some(cx.background_executor().spawn({
+            let executor = cx.background_executor().clone();
+            async move {
+                executor.timer(SAVE_OFFLINE_EDITS_DEBOUNCE_INTERVAL).await;
+                DB.save_offline_channel_buffer(
+                    user_id,
+                    channel_id,
+                    version,
+                    operations,
+                    base_text.text(),
+                    text.text(),
+                )
+                .await
+                .log_err();
+            }
+        }));
+    }
+
+    fn delete_offline_edits(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(user_id) = self.client.user_id() else {
+            return;
+        };
+        let channel_id = self.channel_id.0;
+        self.save_offline_edits_task = Some(cx.background_executor().spawn(async move {
+            DB.delete_offline_channel_buffer(user_id, channel_id)
+                .await
+                .log_err();
+        }));
+    }
+
+    /// Re-applies edits that were made offline in a previous session, whose
+    /// operations can no longer be sent as they are, because the buffer was
+    /// rejoined with a different replica id. If the notes were reset to a new
+    /// epoch in the meantime, the offline changes to their text are merged into
+    /// the new text instead.
+    fn restore_offline_edits(
+        &mut self,
+        base_text: String,
+        operations: Vec<language::Operation>,
+        (version, offline_operations, offline_base_text, offline_text): (
+            Vec<u8>,
+            Vec<u8>,
+            Option<String>,
+            Option<String>,
+        ),
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let version = proto::ChannelBufferVersion::decode(version.as_slice())?;
+        if version.epoch != self.buffer_epoch {
+            let (Some(offline_base_text), Some(offline_text)) = (offline_base_text, offline_text)
+            else {
+                return Err(anyhow!(
+                    "can't restore offline edits to channel notes {}, the notes have changed",
+                    self.channel_id
+                ));
+            };
+            let current_text = self.buffer.read(cx).text();
+            let edits = merge_text_changes(
+                self.remote_id(cx),
+                offline_base_text,
+                &current_text,
+                &offline_text,
+            )?;
+            self.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            return Ok(());
+        }
+        let version = deserialize_version(&version.version);
+
+        let mut bytes = offline_operations.as_slice();
+        let mut offline_text_operations = Vec::new();
+        while !bytes.is_empty() {
+            let operation = proto::Operation::decode_length_delimited(&mut bytes)?;
+            if let language::Operation::Buffer(operation) =
+                language::proto::deserialize_operation(operation)?
+            {
+                offline_text_operations.push(operation);
+            }
+        }
+        if offline_text_operations.is_empty() {
+            return Ok(());
+        }
+
+        // Reconstruct the notes as they were when the connection was lost, and replay
+        // the offline edits on top of them.
+        let mut scratch = text::Buffer::new(0, self.remote_id(cx), base_text);
+        scratch.apply_ops(operations.into_iter().filter_map(|operation| {
+            if let language::Operation::Buffer(operation) = operation {
+                version.observed(operation.timestamp()).then_some(operation)
+            } else {
+                None
+            }
+        }))?;
+        let synced = scratch.snapshot();
+        scratch.apply_ops(offline_text_operations)?;
+        if scratch.deferred_ops_len() > 0 {
+            log::warn!(
+                "some offline edits to channel notes {} could not be restored",
+                self.channel_id
+            );
+        }
+
+        let edits = scratch
+            .edits_since::<usize>(&version)
+            .map(|edit| {
+                let range = synced.anchor_before(edit.old.start)..synced.anchor_after(edit.old.end);
+                let text = scratch.text_for_range(edit.new).collect::<String>();
+                (range, text)
+            })
+            .collect::<Vec<_>>();
+        self.buffer.update(cx, |buffer, cx| {
+            let edits = edits
+                .into_iter()
+                .filter(|(range, _)| {
+                    buffer.can_resolve(&range.start) && buffer.can_resolve(&range.end)
+                })
+                .collect::<Vec<_>>();
+            buffer.edit(edits, None, cx);
+        });
+        Ok(())
+    }
+
     pub(crate) fn disconnect(&mut self, cx: &mut ModelContext<Self>) {
         log::info!("channel buffer {} disconnected", self.channel_id);
         if self.connected {
@@ -260,10 +584,63 @@ impl ChannelBuffer {
     }
 
     pub fn is_connected(&self) -> bool {
-        self.connected
+        self.connected && self.offline_edits.is_none()
+    }
+
+    /// Whether the connection was lost, and edits to the notes are being queued
+    /// until it is restored.
+    pub fn is_offline(&self) -> bool {
+        self.connected && self.offline_edits.is_some()
     }
 
     pub fn replica_id(&self, cx: &AppContext) -> u16 {
         self.buffer.read(cx).replica_id()
     }
 }
+
+/// Merges the changes from `base_text` to `theirs` with those from `base_text` to `ours`,
+/// returning the edits that apply the latter to `theirs`.
+fn merge_text_changes(
+    buffer_id: BufferId,
+    base_text: String,
+    theirs: &str,
+    ours: &str,
+) -> Result<Vec<(Range<usize>, String)>> {
+    let mut merged = text::Buffer::new(0, buffer_id, base_text.clone());
+    merged.edit(text_diff(&base_text, theirs));
+    let their_version = merged.version();
+
+    let mut our_buffer = text::Buffer::new(1, buffer_id, base_text.clone());
+    let our_operation = our_buffer.edit(text_diff(&base_text, ours));
+    merged.apply_ops([our_operation])?;
+
+    Ok(merged
+        .edits_since::<usize>(&their_version)
+        .map(|edit| (edit.old, merged.text_for_range(edit.new).collect()))
+        .collect())
+}
+
+/// The edits that turn `old_text` into `new_text`.
+fn text_diff(old_text: &str, new_text: &str) -> Vec<(Range<usize>, String)> {
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    let mut offset = 0;
+    for change in TextDiff::from_chars(old_text, new_text).iter_all_changes() {
+        let value = change.value();
+        match change.tag() {
+            ChangeTag::Equal => offset += value.len(),
+            ChangeTag::Delete => {
+                let end = offset + value.len();
+                match edits.last_mut() {
+                    Some((range, _)) if range.end == offset => range.end = end,
+                    _ => edits.push((offset..end, String::new())),
+                }
+                offset = end;
+            }
+            ChangeTag::Insert => match edits.last_mut() {
+                Some((range, text)) if range.end == offset => text.push_str(value),
+                _ => edits.push((offset..offset, value.to_string())),
+            },
+        }
+    }
+    edits
+}
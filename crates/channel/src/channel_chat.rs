@@ -5,7 +5,7 @@ use client::{
     user::{User, UserStore},
     ChannelId, Client, Subscription, TypedEnvelope, UserId,
 };
-use collections::HashSet;
+use collections::{HashMap, HashSet};
 use futures::lock::Mutex;
 use gpui::{
     AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task, WeakModel,
@@ -51,6 +51,23 @@ pub struct ChannelMessage {
     pub nonce: u128,
     pub mentions: Vec<(Range<usize>, UserId)>,
     pub reply_to_message_id: Option<u64>,
+    pub edited_at: Option<OffsetDateTime>,
+    pub reactions: Vec<ChannelMessageReaction>,
+}
+
+/// The users who reacted to a message with a given emoji.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelMessageReaction {
+    pub emoji: String,
+    pub user_ids: Vec<UserId>,
+}
+
+/// A previous body of an edited message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelMessageEdit {
+    pub body: String,
+    /// When this body was replaced by a newer one.
+    pub edited_at: OffsetDateTime,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -87,12 +104,17 @@ pub enum ChannelChatEvent {
         channel_id: ChannelId,
         message_id: u64,
     },
+    UpdateMessage {
+        message_id: ChannelMessageId,
+        message_ix: usize,
+    },
 }
 
 impl EventEmitter<ChannelChatEvent> for ChannelChat {}
 pub fn init(client: &Arc<Client>) {
     client.add_model_message_handler(ChannelChat::handle_message_sent);
     client.add_model_message_handler(ChannelChat::handle_message_removed);
+    client.add_model_message_handler(ChannelChat::handle_message_updated);
 }
 
 impl ChannelChat {
@@ -189,6 +211,8 @@ impl ChannelChat {
                     mentions: message.mentions.clone(),
                     nonce,
                     reply_to_message_id: message.reply_to_message_id,
+                    edited_at: None,
+                    reactions: Vec::new(),
                 },
                 &(),
             ),
@@ -234,6 +258,87 @@ impl ChannelChat {
         })
     }
 
+    /// Replaces the body and mentions of one of the current user's messages.
+    pub fn update_message(
+        &mut self,
+        id: u64,
+        message: MessageParams,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<Task<Result<()>>> {
+        if message.text.trim().is_empty() {
+            Err(anyhow!("message body can't be empty"))?;
+        }
+
+        let response = self.rpc.request(proto::UpdateChannelMessage {
+            channel_id: self.channel_id.0,
+            message_id: id,
+            body: message.text,
+            mentions: mentions_to_proto(&message.mentions),
+        });
+        Ok(cx.spawn(move |this, mut cx| async move {
+            Self::apply_message_update(this, response.await?, &mut cx).await
+        }))
+    }
+
+    /// Adds the current user's reaction to a message, or removes it if they already reacted with
+    /// that emoji.
+    pub fn toggle_reaction(
+        &mut self,
+        id: u64,
+        emoji: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(user_id) = self.rpc.user_id() else {
+            return Task::ready(Err(anyhow!("not signed in")));
+        };
+        let has_reacted = self
+            .find_loaded_message(id)
+            .map_or(false, |message| message.has_reaction(&emoji, user_id));
+
+        let channel_id = self.channel_id.0;
+        let response = if has_reacted {
+            self.rpc.request(proto::RemoveChannelMessageReaction {
+                channel_id,
+                message_id: id,
+                emoji,
+            })
+        } else {
+            self.rpc.request(proto::AddChannelMessageReaction {
+                channel_id,
+                message_id: id,
+                emoji,
+            })
+        };
+        cx.spawn(move |this, mut cx| async move {
+            Self::apply_message_update(this, response.await?, &mut cx).await
+        })
+    }
+
+    /// Loads the previous bodies of an edited message, oldest first.
+    pub fn load_message_edits(
+        &self,
+        id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<ChannelMessageEdit>>> {
+        let response = self.rpc.request(proto::GetChannelMessageEdits {
+            channel_id: self.channel_id.0,
+            message_id: id,
+        });
+        cx.background_executor().spawn(async move {
+            response
+                .await?
+                .edits
+                .into_iter()
+                .map(|edit| {
+                    Ok(ChannelMessageEdit {
+                        body: edit.body,
+                        edited_at: OffsetDateTime::from_unix_timestamp(edit.edited_at as i64)?,
+                    })
+                })
+                .collect()
+        })
+    }
+
     pub fn load_more_messages(&mut self, cx: &mut ModelContext<Self>) -> Option<Task<Option<()>>> {
         if self.loaded_all_messages {
             return None;
@@ -279,6 +384,40 @@ impl ChannelChat {
         })
     }
 
+    /// Returns the indices of the loaded messages in the thread that starts with the given
+    /// message, which are the message itself and the messages that reply to it, directly or
+    /// through other replies.
+    pub fn thread_message_ixs(&self, root_id: u64) -> Vec<usize> {
+        let mut thread_message_ids = HashSet::default();
+        let mut ixs = Vec::new();
+        for (ix, message) in self.messages.iter().enumerate() {
+            let is_in_thread = match message.id {
+                ChannelMessageId::Saved(id) if id == root_id => true,
+                _ => message
+                    .reply_to_message_id
+                    .map_or(false, |id| thread_message_ids.contains(&id)),
+            };
+            if is_in_thread {
+                if let ChannelMessageId::Saved(id) = message.id {
+                    thread_message_ids.insert(id);
+                }
+                ixs.push(ix);
+            }
+        }
+        ixs
+    }
+
+    /// Returns the number of loaded messages that directly reply to each message.
+    pub fn reply_counts(&self) -> HashMap<u64, usize> {
+        let mut counts = HashMap::default();
+        for message in self.messages.iter() {
+            if let Some(reply_to_message_id) = message.reply_to_message_id {
+                *counts.entry(reply_to_message_id).or_default() += 1;
+            }
+        }
+        counts
+    }
+
     /// Load all of the chat messages since a certain message id.
     ///
     /// For now, we always maintain a suffix of the channel's messages.
@@ -523,6 +662,26 @@ impl ChannelChat {
         Ok(())
     }
 
+    async fn handle_message_updated(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::ChannelMessageUpdate>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        Self::apply_message_update(this.downgrade(), message.payload, &mut cx).await
+    }
+
+    async fn apply_message_update(
+        this: WeakModel<Self>,
+        update: proto::ChannelMessageUpdate,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let user_store = this.update(cx, |this, _| this.user_store.clone())?;
+        let message = update.message.ok_or_else(|| anyhow!("empty message"))?;
+        let message = ChannelMessage::from_proto(message, &user_store, cx).await?;
+        this.update(cx, |this, cx| this.message_updated(message, cx))
+    }
+
     fn insert_messages(&mut self, messages: SumTree<ChannelMessage>, cx: &mut ModelContext<Self>) {
         if let Some((first_message, last_message)) = messages.first().zip(messages.last()) {
             let nonces = messages
@@ -582,6 +741,27 @@ impl ChannelChat {
         }
     }
 
+    fn message_updated(&mut self, message: ChannelMessage, cx: &mut ModelContext<Self>) {
+        let mut cursor = self.messages.cursor::<ChannelMessageId>();
+        let mut messages = cursor.slice(&message.id, Bias::Left, &());
+        if let Some(item) = cursor.item() {
+            if item.id == message.id {
+                let message_id = message.id;
+                let message_ix = messages.summary().count;
+                cursor.next(&());
+                messages.push(message, &());
+                messages.append(cursor.suffix(&()), &());
+                drop(cursor);
+                self.messages = messages;
+                cx.emit(ChannelChatEvent::UpdateMessage {
+                    message_id,
+                    message_ix,
+                });
+                cx.notify();
+            }
+        }
+    }
+
     fn message_removed(&mut self, id: u64, cx: &mut ModelContext<Self>) {
         let mut cursor = self.messages.cursor::<ChannelMessageId>();
         let mut messages = cursor.slice(&ChannelMessageId::Saved(id), Bias::Left, &());
@@ -641,6 +821,18 @@ impl ChannelMessage {
                 .ok_or_else(|| anyhow!("nonce is required"))?
                 .into(),
             reply_to_message_id: message.reply_to_message_id,
+            edited_at: message
+                .edited_at
+                .map(|edited_at| OffsetDateTime::from_unix_timestamp(edited_at as i64))
+                .transpose()?,
+            reactions: message
+                .reactions
+                .into_iter()
+                .map(|reaction| ChannelMessageReaction {
+                    emoji: reaction.emoji,
+                    user_ids: reaction.user_ids,
+                })
+                .collect(),
         })
     }
 
@@ -648,6 +840,16 @@ impl ChannelMessage {
         matches!(self.id, ChannelMessageId::Pending(_))
     }
 
+    pub fn is_edited(&self) -> bool {
+        self.edited_at.is_some()
+    }
+
+    pub fn has_reaction(&self, emoji: &str, user_id: UserId) -> bool {
+        self.reactions
+            .iter()
+            .any(|reaction| reaction.emoji == emoji && reaction.user_ids.contains(&user_id))
+    }
+
     pub async fn from_proto_vec(
         proto_messages: Vec<proto::ChannelMessage>,
         user_store: &Model<UserStore>,
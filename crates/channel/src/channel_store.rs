@@ -16,11 +16,9 @@ use rpc::{
     TypedEnvelope,
 };
 use settings::Settings;
-use std::{mem, sync::Arc, time::Duration};
+use std::{mem, sync::Arc};
 use util::{async_maybe, maybe, ResultExt};
 
-pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
-
 pub fn init(client: &Arc<Client>, user_store: Model<UserStore>, cx: &mut AppContext) {
     let channel_store =
         cx.new_model(|cx| ChannelStore::new(client.clone(), user_store.clone(), cx));
@@ -67,7 +65,6 @@ pub struct ChannelStore {
     user_store: Model<UserStore>,
     _rpc_subscriptions: [Subscription; 2],
     _watch_connection_status: Task<Option<()>>,
-    disconnect_channel_buffers_task: Option<Task<()>>,
     _update_channels: Task<()>,
 }
 
@@ -225,7 +222,6 @@ impl ChannelStore {
             user_store,
             _rpc_subscriptions: rpc_subscriptions,
             _watch_connection_status: watch_connection_status,
-            disconnect_channel_buffers_task: None,
             _update_channels: cx.spawn(|this, mut cx| async move {
                 async_maybe!({
                     while let Some(update_channels) = update_channels_rx.next().await {
@@ -920,7 +916,6 @@ impl ChannelStore {
         self.channel_participants.clear();
         self.channel_index.clear();
         self.outgoing_invites.clear();
-        self.disconnect_channel_buffers_task.take();
 
         for chat in self.opened_chats.values() {
             if let OpenedModelHandle::Open(chat) = chat {
@@ -937,11 +932,13 @@ impl ChannelStore {
             if let OpenedModelHandle::Open(buffer) = buffer {
                 if let Some(buffer) = buffer.upgrade() {
                     let channel_buffer = buffer.read(cx);
-                    let buffer = channel_buffer.buffer().read(cx);
                     buffer_versions.push(proto::ChannelBufferVersion {
                         channel_id: channel_buffer.channel_id.0,
                         epoch: channel_buffer.epoch(),
-                        version: language::proto::serialize_version(&buffer.version()),
+                        version: language::proto::serialize_version(
+                            &channel_buffer.rejoin_version(cx),
+                        ),
+                        replica_id: Some(channel_buffer.replica_id(cx) as u32),
                     });
                 }
             }
@@ -997,6 +994,7 @@ impl ChannelStore {
                                     .log_err();
 
                                 if let Some(operations) = operations {
+                                    channel_buffer.rejoined(cx);
                                     let client = this.client.clone();
                                     cx.background_executor()
                                         .spawn(async move {
@@ -1032,26 +1030,25 @@ impl ChannelStore {
     fn handle_disconnect(&mut self, wait_for_reconnect: bool, cx: &mut ModelContext<Self>) {
         cx.notify();
 
-        self.disconnect_channel_buffers_task.get_or_insert_with(|| {
-            cx.spawn(move |this, mut cx| async move {
-                if wait_for_reconnect {
-                    cx.background_executor().timer(RECONNECT_TIMEOUT).await;
+        // While waiting to reconnect, the channel buffers stay editable, and their
+        // edits are merged when they are rejoined.
+        if wait_for_reconnect {
+            for buffer in self.opened_buffers.values() {
+                if let OpenedModelHandle::Open(buffer) = buffer {
+                    if let Some(buffer) = buffer.upgrade() {
+                        buffer.update(cx, |buffer, cx| buffer.go_offline(cx));
+                    }
                 }
-
-                if let Some(this) = this.upgrade() {
-                    this.update(&mut cx, |this, cx| {
-                        for (_, buffer) in this.opened_buffers.drain() {
-                            if let OpenedModelHandle::Open(buffer) = buffer {
-                                if let Some(buffer) = buffer.upgrade() {
-                                    buffer.update(cx, |buffer, cx| buffer.disconnect(cx));
-                                }
-                            }
-                        }
-                    })
-                    .ok();
+            }
+        } else {
+            for (_, buffer) in self.opened_buffers.drain() {
+                if let OpenedModelHandle::Open(buffer) = buffer {
+                    if let Some(buffer) = buffer.upgrade() {
+                        buffer.update(cx, |buffer, cx| buffer.disconnect(cx));
+                    }
                 }
-            })
-        });
+            }
+        }
     }
 
     pub(crate) fn update_channels(
@@ -186,6 +186,8 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     mentions: vec![],
                     nonce: Some(1.into()),
                     reply_to_message_id: None,
+                    edited_at: None,
+                    reactions: vec![],
                 },
                 proto::ChannelMessage {
                     id: 11,
@@ -195,6 +197,8 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     mentions: vec![],
                     nonce: Some(2.into()),
                     reply_to_message_id: None,
+                    edited_at: None,
+                    reactions: vec![],
                 },
             ],
             done: false,
@@ -243,6 +247,8 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
             mentions: vec![],
             nonce: Some(3.into()),
             reply_to_message_id: None,
+            edited_at: None,
+            reactions: vec![],
         }),
     });
 
@@ -297,6 +303,8 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     nonce: Some(4.into()),
                     mentions: vec![],
                     reply_to_message_id: None,
+                    edited_at: None,
+                    reactions: vec![],
                 },
                 proto::ChannelMessage {
                     id: 9,
@@ -306,6 +314,8 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     nonce: Some(5.into()),
                     mentions: vec![],
                     reply_to_message_id: None,
+                    edited_at: None,
+                    reactions: vec![],
                 },
             ],
         },
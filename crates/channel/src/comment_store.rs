@@ -0,0 +1,382 @@
+use anyhow::{anyhow, Result};
+use client::{ChannelId, Client, Subscription, User, UserStore};
+use collections::{BTreeMap, HashSet};
+use gpui::{AppContext, AsyncAppContext, Context, EventEmitter, Global, Model, ModelContext, Task};
+use language::proto::{deserialize_anchor, serialize_anchor};
+use rpc::{proto, TypedEnvelope};
+use std::{ops::Range, path::Path, sync::Arc};
+use text::{BufferSnapshot, Point};
+use time::OffsetDateTime;
+
+pub fn init(client: &Arc<Client>, user_store: Model<UserStore>, cx: &mut AppContext) {
+    let comment_store = cx.new_model(|cx| CommentStore::new(client.clone(), user_store, cx));
+    cx.set_global(GlobalCommentStore(comment_store));
+}
+
+/// Keeps track of the review comments on channel notes and on the files of shared projects.
+pub struct CommentStore {
+    threads: BTreeMap<u64, CommentThread>,
+    client: Arc<Client>,
+    user_store: Model<UserStore>,
+    _rpc_subscription: Subscription,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CommentTarget {
+    Channel(ChannelId),
+    ProjectPath {
+        project_id: u64,
+        worktree_id: u64,
+        path: Arc<Path>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct CommentThread {
+    pub id: u64,
+    pub target: CommentTarget,
+    pub range: Range<text::Anchor>,
+    /// The row of the start of the thread's range, used when its anchors can't be resolved.
+    pub row: u32,
+    /// For channel notes, the epoch of the buffer in which the anchors were created.
+    pub buffer_epoch: Option<u64>,
+    pub resolved: bool,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Comment {
+    pub id: u64,
+    pub sender: Arc<User>,
+    pub body: String,
+    pub timestamp: OffsetDateTime,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommentStoreEvent {
+    ThreadsChanged { target: CommentTarget },
+}
+
+impl EventEmitter<CommentStoreEvent> for CommentStore {}
+
+struct GlobalCommentStore(Model<CommentStore>);
+
+impl Global for GlobalCommentStore {}
+
+impl CommentStore {
+    pub fn global(cx: &AppContext) -> Model<Self> {
+        cx.global::<GlobalCommentStore>().0.clone()
+    }
+
+    pub fn new(
+        client: Arc<Client>,
+        user_store: Model<UserStore>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        Self {
+            threads: BTreeMap::default(),
+            _rpc_subscription: client
+                .add_message_handler(cx.weak_model(), Self::handle_comment_thread_updated),
+            client,
+            user_store,
+        }
+    }
+
+    pub fn thread(&self, thread_id: u64) -> Option<&CommentThread> {
+        self.threads.get(&thread_id)
+    }
+
+    pub fn threads_for_target<'a>(
+        &'a self,
+        target: &'a CommentTarget,
+    ) -> impl Iterator<Item = &'a CommentThread> {
+        self.threads
+            .values()
+            .filter(move |thread| &thread.target == target)
+    }
+
+    pub fn project_threads(&self, project_id: u64) -> impl Iterator<Item = &CommentThread> {
+        self.threads.values().filter(move |thread| {
+            matches!(
+                thread.target,
+                CommentTarget::ProjectPath { project_id: id, .. } if id == project_id
+            )
+        })
+    }
+
+    pub fn load_channel_threads(
+        &mut self,
+        channel_id: ChannelId,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.load_threads(
+            proto::GetCommentThreads {
+                channel_id: Some(channel_id.0),
+                project_id: None,
+            },
+            cx,
+        )
+    }
+
+    pub fn load_project_threads(
+        &mut self,
+        project_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.load_threads(
+            proto::GetCommentThreads {
+                channel_id: None,
+                project_id: Some(project_id),
+            },
+            cx,
+        )
+    }
+
+    fn load_threads(
+        &mut self,
+        request: proto::GetCommentThreads,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let client = self.client.clone();
+        let user_store = self.user_store.clone();
+        cx.spawn(|this, mut cx| async move {
+            let response = client.request(request.clone()).await?;
+            let threads =
+                CommentThread::from_proto_vec(response.threads, &user_store, &mut cx).await?;
+            this.update(&mut cx, |this, cx| {
+                this.threads.retain(|_, thread| match &thread.target {
+                    CommentTarget::Channel(channel_id) => request.channel_id != Some(channel_id.0),
+                    CommentTarget::ProjectPath { project_id, .. } => {
+                        request.project_id != Some(*project_id)
+                    }
+                });
+                this.insert_threads(threads, cx);
+            })
+        })
+    }
+
+    /// Starts a thread on the given range of a buffer, returning its id.
+    pub fn create_thread(
+        &mut self,
+        target: CommentTarget,
+        range: Range<text::Anchor>,
+        buffer: &BufferSnapshot,
+        buffer_epoch: Option<u64>,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<u64>> {
+        let request = proto::CreateCommentThread {
+            target: Some(target.to_proto()),
+            start: Some(serialize_anchor(&range.start)),
+            end: Some(serialize_anchor(&range.end)),
+            row: text::ToPoint::to_point(&range.start, buffer).row,
+            buffer_epoch,
+            body,
+        };
+        let task = self.send_thread_request(request, cx);
+        cx.background_executor()
+            .spawn(async move { Ok(task.await?.id) })
+    }
+
+    pub fn add_comment(
+        &mut self,
+        thread_id: u64,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let task = self.send_thread_request(proto::AddComment { thread_id, body }, cx);
+        cx.background_executor().spawn(async move {
+            task.await?;
+            Ok(())
+        })
+    }
+
+    pub fn set_thread_resolved(
+        &mut self,
+        thread_id: u64,
+        resolved: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let task = self.send_thread_request(
+            proto::SetCommentThreadResolved {
+                thread_id,
+                resolved,
+            },
+            cx,
+        );
+        cx.background_executor().spawn(async move {
+            task.await?;
+            Ok(())
+        })
+    }
+
+    fn send_thread_request<T>(
+        &mut self,
+        request: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<proto::CommentThread>>
+    where
+        T: proto::RequestMessage<Response = proto::CommentThreadResponse>,
+    {
+        let client = self.client.clone();
+        let user_store = self.user_store.clone();
+        cx.spawn(|this, mut cx| async move {
+            let thread = client
+                .request(request)
+                .await?
+                .thread
+                .ok_or_else(|| anyhow!("invalid comment thread"))?;
+            let threads =
+                CommentThread::from_proto_vec(vec![thread.clone()], &user_store, &mut cx).await?;
+            this.update(&mut cx, |this, cx| this.insert_threads(threads, cx))?;
+            Ok(thread)
+        })
+    }
+
+    async fn handle_comment_thread_updated(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::CommentThreadUpdated>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let thread = message
+            .payload
+            .thread
+            .ok_or_else(|| anyhow!("invalid comment thread"))?;
+        let user_store = this.update(&mut cx, |this, _| this.user_store.clone())?;
+        let threads = CommentThread::from_proto_vec(vec![thread], &user_store, &mut cx).await?;
+        this.update(&mut cx, |this, cx| this.insert_threads(threads, cx))
+    }
+
+    fn insert_threads(&mut self, threads: Vec<CommentThread>, cx: &mut ModelContext<Self>) {
+        let mut changed_targets = Vec::new();
+        for thread in threads {
+            if !changed_targets.contains(&thread.target) {
+                changed_targets.push(thread.target.clone());
+            }
+            self.threads.insert(thread.id, thread);
+        }
+        for target in changed_targets {
+            cx.emit(CommentStoreEvent::ThreadsChanged { target });
+        }
+    }
+}
+
+impl CommentTarget {
+    fn to_proto(&self) -> proto::CommentTarget {
+        proto::CommentTarget {
+            variant: Some(match self {
+                CommentTarget::Channel(channel_id) => {
+                    proto::comment_target::Variant::ChannelId(channel_id.0)
+                }
+                CommentTarget::ProjectPath {
+                    project_id,
+                    worktree_id,
+                    path,
+                } => proto::comment_target::Variant::ProjectPath(
+                    proto::comment_target::ProjectPath {
+                        project_id: *project_id,
+                        worktree_id: *worktree_id,
+                        path: path.to_string_lossy().to_string(),
+                    },
+                ),
+            }),
+        }
+    }
+
+    fn from_proto(target: proto::CommentTarget) -> Result<Self> {
+        match target.variant {
+            Some(proto::comment_target::Variant::ChannelId(channel_id)) => {
+                Ok(CommentTarget::Channel(ChannelId(channel_id)))
+            }
+            Some(proto::comment_target::Variant::ProjectPath(project_path)) => {
+                Ok(CommentTarget::ProjectPath {
+                    project_id: project_path.project_id,
+                    worktree_id: project_path.worktree_id,
+                    path: Path::new(&project_path.path).into(),
+                })
+            }
+            None => Err(anyhow!("invalid comment target")),
+        }
+    }
+}
+
+impl CommentThread {
+    /// Returns the range of the given buffer that this thread is about. When the thread's anchors
+    /// belong to another version of the buffer, this falls back to the thread's row.
+    pub fn range_in(
+        &self,
+        buffer: &BufferSnapshot,
+        buffer_epoch: Option<u64>,
+    ) -> Range<text::Anchor> {
+        if self.buffer_epoch == buffer_epoch
+            && buffer.can_resolve(&self.range.start)
+            && buffer.can_resolve(&self.range.end)
+        {
+            return self.range.clone();
+        }
+
+        let row = self.row.min(buffer.max_point().row);
+        buffer.anchor_before(Point::new(row, 0))
+            ..buffer.anchor_after(Point::new(row, buffer.line_len(row)))
+    }
+
+    async fn from_proto_vec(
+        threads: Vec<proto::CommentThread>,
+        user_store: &Model<UserStore>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Vec<Self>> {
+        let unique_user_ids = threads
+            .iter()
+            .flat_map(|thread| thread.comments.iter().map(|comment| comment.sender_id))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        user_store
+            .update(cx, |user_store, cx| {
+                user_store.get_users(unique_user_ids, cx)
+            })?
+            .await?;
+
+        let mut result = Vec::with_capacity(threads.len());
+        for thread in threads {
+            let mut comments = Vec::with_capacity(thread.comments.len());
+            for comment in thread.comments {
+                let sender = user_store
+                    .update(cx, |user_store, cx| {
+                        user_store.get_user(comment.sender_id, cx)
+                    })?
+                    .await?;
+                comments.push(Comment {
+                    id: comment.id,
+                    sender,
+                    body: comment.body,
+                    timestamp: OffsetDateTime::from_unix_timestamp(comment.timestamp as i64)?,
+                });
+            }
+
+            let start = thread
+                .start
+                .and_then(deserialize_anchor)
+                .ok_or_else(|| anyhow!("invalid anchor"))?;
+            let end = thread
+                .end
+                .and_then(deserialize_anchor)
+                .ok_or_else(|| anyhow!("invalid anchor"))?;
+            result.push(CommentThread {
+                id: thread.id,
+                target: CommentTarget::from_proto(
+                    thread
+                        .target
+                        .ok_or_else(|| anyhow!("invalid comment target"))?,
+                )?,
+                range: start..end,
+                row: thread.row,
+                buffer_epoch: thread.buffer_epoch,
+                resolved: thread.resolved,
+                comments,
+            });
+        }
+        Ok(result)
+    }
+}
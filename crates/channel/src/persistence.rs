@@ -0,0 +1,63 @@
+use db::{define_connection, query, sqlez_macros::sql};
+
+define_connection!(
+    // Edits made to channel notes while the client was offline, so that they can be merged
+    // into the notes after a restart.
+    //
+    // offline_channel_buffers(
+    //   user_id: u64,
+    //   channel_id: u64,
+    //   version: Vec<u8>, // A `proto::ChannelBufferVersion`, from when the connection was lost.
+    //   operations: Vec<u8>, // Length-delimited `proto::Operation`s made since then.
+    //   base_text: Option<String>, // The text of the notes when the connection was lost.
+    //   text: Option<String>, // The text of the notes with the offline edits.
+    // )
+    pub static ref DB: ChannelDb<()> =
+        &[sql!(
+            CREATE TABLE offline_channel_buffers(
+                user_id INTEGER NOT NULL,
+                channel_id INTEGER NOT NULL,
+                version BLOB NOT NULL,
+                operations BLOB NOT NULL,
+                base_text TEXT,
+                text TEXT,
+                PRIMARY KEY(user_id, channel_id)
+            ) STRICT;
+        )];
+);
+
+impl ChannelDb {
+    query! {
+        pub fn offline_channel_buffer(
+            user_id: u64,
+            channel_id: u64
+        ) -> Result<Option<(Vec<u8>, Vec<u8>, Option<String>, Option<String>)>> {
+            SELECT version, operations, base_text, text
+            FROM offline_channel_buffers
+            WHERE user_id = ? AND channel_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_offline_channel_buffer(
+            user_id: u64,
+            channel_id: u64,
+            version: Vec<u8>,
+            operations: Vec<u8>,
+            base_text: String,
+            text: String
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO offline_channel_buffers(
+                user_id, channel_id, version, operations, base_text, text
+            )
+            VALUES (?, ?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub async fn delete_offline_channel_buffer(user_id: u64, channel_id: u64) -> Result<()> {
+            DELETE FROM offline_channel_buffers
+            WHERE user_id = ? AND channel_id = ?
+        }
+    }
+}
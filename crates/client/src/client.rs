@@ -58,6 +58,16 @@ lazy_static! {
     pub static ref ADMIN_API_TOKEN: Option<String> = std::env::var("ZED_ADMIN_API_TOKEN")
         .ok()
         .and_then(|s| if s.is_empty() { None } else { Some(s) });
+    /// Credentials issued by a self-hosted collaboration server, which are used instead of
+    /// signing in through the browser.
+    static ref PROVISIONED_CREDENTIALS: Option<Credentials> = std::env::var("ZED_USER_ID")
+        .ok()
+        .and_then(|user_id| user_id.parse().ok())
+        .zip(std::env::var("ZED_ACCESS_TOKEN").ok())
+        .map(|(user_id, access_token)| Credentials {
+            user_id,
+            access_token,
+        });
     pub static ref ZED_APP_PATH: Option<PathBuf> =
         std::env::var("ZED_APP_PATH").ok().map(PathBuf::from);
     pub static ref ZED_ALWAYS_ACTIVE: bool =
@@ -156,6 +166,7 @@ pub struct Client {
     http: Arc<HttpClientWithUrl>,
     telemetry: Arc<Telemetry>,
     state: RwLock<ClientState>,
+    provisioned_credentials: RwLock<Option<Credentials>>,
 
     #[allow(clippy::type_complexity)]
     #[cfg(any(test, feature = "test-support"))]
@@ -250,6 +261,7 @@ struct ClientState {
     entity_id_extractors: HashMap<TypeId, fn(&dyn AnyTypedEnvelope) -> u64>,
     _reconnect_task: Option<Task<()>>,
     reconnect_interval: Duration,
+    connected_directly: bool,
     entities_by_type_and_remote_id: HashMap<(TypeId, u64), WeakSubscriber>,
     models_by_message_type: HashMap<TypeId, AnyWeakModel>,
     entity_types_by_message_type: HashMap<TypeId, TypeId>,
@@ -288,6 +300,7 @@ impl Default for ClientState {
             entity_id_extractors: Default::default(),
             _reconnect_task: None,
             reconnect_interval: Duration::from_secs(5),
+            connected_directly: false,
             models_by_message_type: Default::default(),
             entities_by_type_and_remote_id: Default::default(),
             entity_types_by_message_type: Default::default(),
@@ -433,6 +446,7 @@ impl Client {
             telemetry: Telemetry::new(clock, http.clone(), cx),
             http,
             state: Default::default(),
+            provisioned_credentials: RwLock::new(PROVISIONED_CREDENTIALS.clone()),
 
             #[cfg(any(test, feature = "test-support"))]
             authenticate: Default::default(),
@@ -474,6 +488,12 @@ impl Client {
         self
     }
 
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn override_provisioned_credentials(&self, credentials: Credentials) -> &Self {
+        *self.provisioned_credentials.write() = Some(credentials);
+        self
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn override_establish_connection<F>(&self, connect: F) -> &Self
     where
@@ -522,6 +542,9 @@ impl Client {
             Status::Connected { .. } => {
                 state._reconnect_task = None;
             }
+            Status::ConnectionLost if state.connected_directly => {
+                state._reconnect_task.take();
+            }
             Status::ConnectionLost => {
                 let this = self.clone();
                 let reconnect_interval = state.reconnect_interval;
@@ -770,7 +793,7 @@ impl Client {
 
         let mut read_from_keychain = false;
         let mut credentials = self.state.read().credentials.clone();
-        if credentials.is_none() && try_keychain {
+        if credentials.is_none() && try_keychain && self.provisioned_credentials.read().is_none() {
             credentials = read_credentials_from_keychain(cx).await;
             read_from_keychain = credentials.is_some();
         }
@@ -808,7 +831,10 @@ impl Client {
                 match connection {
                     Ok(conn) => {
                         self.state.write().credentials = Some(credentials.clone());
-                        if !read_from_keychain && IMPERSONATE_LOGIN.is_none() {
+                        if !read_from_keychain
+                            && IMPERSONATE_LOGIN.is_none()
+                            && self.provisioned_credentials.read().is_none()
+                        {
                             write_credentials_to_keychain(credentials, cx).await.log_err();
                         }
 
@@ -848,6 +874,23 @@ impl Client {
         }
     }
 
+    /// Connects over an already established connection, such as the standard input and output of
+    /// a process, instead of authenticating with the server. The client won't try to reconnect if
+    /// this connection is lost.
+    pub async fn connect_over(
+        self: &Arc<Self>,
+        connection: Connection,
+        cx: &AsyncAppContext,
+    ) -> Result<()> {
+        self.state.write().connected_directly = true;
+        self.set_status(Status::Connecting, cx);
+        let result = self.set_connection(connection, cx).await;
+        if result.is_err() {
+            self.set_status(Status::ConnectionError, cx);
+        }
+        result
+    }
+
     async fn set_connection(
         self: &Arc<Self>,
         conn: Connection,
@@ -952,6 +995,10 @@ impl Client {
             return callback(cx);
         }
 
+        if let Some(credentials) = self.provisioned_credentials.read().clone() {
+            return Task::ready(Ok(credentials));
+        }
+
         self.authenticate_with_browser(cx)
     }
 
@@ -1515,6 +1562,98 @@ mod tests {
         assert_eq!(server.auth_count(), 2); // Client re-authenticated due to an invalid token
     }
 
+    #[gpui::test]
+    async fn test_provisioned_credentials(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        client.override_provisioned_credentials(Credentials {
+            user_id: 7,
+            access_token: "provisioned-token".into(),
+        });
+
+        // The client connects with the provisioned credentials, instead of signing in
+        // through the browser.
+        let server = Peer::new(0);
+        let used_credentials = Arc::new(Mutex::new(Vec::new()));
+        client.override_establish_connection({
+            let server = server.clone();
+            let used_credentials = used_credentials.clone();
+            move |credentials, cx| {
+                used_credentials.lock().push(credentials.clone());
+                let (client_conn, server_conn, _) =
+                    Connection::in_memory(cx.background_executor().clone());
+                let (connection_id, io, _incoming) =
+                    server.add_test_connection(server_conn, cx.background_executor().clone());
+                cx.background_executor().spawn(io).detach();
+                server
+                    .send(
+                        connection_id,
+                        proto::Hello {
+                            peer_id: Some(connection_id.into()),
+                        },
+                    )
+                    .unwrap();
+                Task::ready(Ok(client_conn))
+            }
+        });
+        client
+            .authenticate_and_connect(true, &cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(client.user_id(), Some(7));
+        assert_eq!(
+            used_credentials
+                .lock()
+                .iter()
+                .map(|credentials| (credentials.user_id, credentials.access_token.as_str()))
+                .collect::<Vec<_>>(),
+            &[(7, "provisioned-token")]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_connecting_over_connection(cx: &mut TestAppContext) {
+        init_test(cx);
+        let client = cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        client.override_authenticate(|_| panic!("should not authenticate"));
+
+        let server = Peer::new(0);
+        let (client_conn, server_conn, _) = Connection::in_memory(cx.executor());
+        let (connection_id, io, _incoming) = server.add_test_connection(server_conn, cx.executor());
+        cx.executor().spawn(io).detach();
+        server
+            .send(
+                connection_id,
+                proto::Hello {
+                    peer_id: Some(connection_id.into()),
+                },
+            )
+            .unwrap();
+
+        client
+            .connect_over(client_conn, &cx.to_async())
+            .await
+            .unwrap();
+        assert_eq!(client.peer_id(), Some(connection_id.into()));
+
+        // The client doesn't try to authenticate again when the connection is lost.
+        server.disconnect(connection_id);
+        cx.executor().run_until_parked();
+        assert_eq!(*client.status().borrow(), Status::ConnectionLost);
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_connection_timeout(executor: BackgroundExecutor, cx: &mut TestAppContext) {
         init_test(cx);
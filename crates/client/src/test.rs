@@ -174,6 +174,21 @@ impl FakeServer {
                 continue;
             }
 
+            if message.is::<TypedEnvelope<proto::GetChannelActivity>>() {
+                self.respond(
+                    message
+                        .downcast::<TypedEnvelope<proto::GetChannelActivity>>()
+                        .unwrap()
+                        .receipt(),
+                    proto::GetChannelActivityResponse {
+                        activities: Vec::new(),
+                        observed_at: None,
+                        done: true,
+                    },
+                );
+                continue;
+            }
+
             panic!(
                 "fake server received unexpected message type: {:?}",
                 type_name
@@ -220,7 +220,8 @@ CREATE TABLE IF NOT EXISTS "channel_messages" (
     "body" TEXT NOT NULL,
     "sent_at" TIMESTAMP,
     "nonce" BLOB NOT NULL,
-    "reply_to_message_id" INTEGER DEFAULT NULL
+    "reply_to_message_id" INTEGER DEFAULT NULL,
+    "edited_at" TIMESTAMP DEFAULT NULL
 );
 CREATE INDEX "index_channel_messages_on_channel_id" ON "channel_messages" ("channel_id");
 CREATE UNIQUE INDEX "index_channel_messages_on_sender_id_nonce" ON "channel_messages" ("sender_id", "nonce");
@@ -233,6 +234,52 @@ CREATE TABLE "channel_message_mentions" (
     PRIMARY KEY(message_id, start_offset)
 );
 
+CREATE TABLE "channel_message_edits" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "edited_at" TIMESTAMP NOT NULL
+);
+
+CREATE INDEX "index_channel_message_edits_on_message_id" ON "channel_message_edits" ("message_id");
+
+CREATE TABLE "channel_message_reactions" (
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL,
+    PRIMARY KEY(message_id, user_id, emoji)
+);
+
+CREATE TABLE "comment_threads" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "channel_id" INTEGER REFERENCES channels (id) ON DELETE CASCADE,
+    "project_id" INTEGER REFERENCES projects (id) ON DELETE SET NULL,
+    "host_user_id" INTEGER REFERENCES users (id) ON DELETE CASCADE,
+    "worktree_abs_path" VARCHAR,
+    "worktree_id" INTEGER,
+    "path" VARCHAR,
+    "start_anchor" BLOB NOT NULL,
+    "end_anchor" BLOB NOT NULL,
+    "row" INTEGER NOT NULL,
+    "buffer_epoch" INTEGER,
+    "resolved" BOOLEAN NOT NULL DEFAULT FALSE,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX "index_comment_threads_on_channel_id" ON "comment_threads" ("channel_id");
+CREATE INDEX "index_comment_threads_on_project_id" ON "comment_threads" ("project_id");
+CREATE INDEX "index_comment_threads_on_host_user_id_and_worktree_abs_path" ON "comment_threads" ("host_user_id", "worktree_abs_path");
+
+CREATE TABLE "comments" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "thread_id" INTEGER NOT NULL REFERENCES comment_threads (id) ON DELETE CASCADE,
+    "sender_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "sent_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX "index_comments_on_thread_id" ON "comments" ("thread_id");
+
 CREATE TABLE "channel_members" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
     "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
@@ -269,9 +316,26 @@ CREATE TABLE "buffer_snapshots" (
     "epoch" INTEGER NOT NULL,
     "text" TEXT NOT NULL,
     "operation_serialization_version" INTEGER NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     PRIMARY KEY(buffer_id, epoch)
 );
 
+CREATE TABLE "buffer_edit_authors" (
+    "buffer_id" INTEGER NOT NULL REFERENCES buffers (id) ON DELETE CASCADE,
+    "epoch" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "edited_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(buffer_id, epoch, user_id)
+);
+
+CREATE TABLE "buffer_replicas" (
+    "buffer_id" INTEGER NOT NULL REFERENCES buffers (id) ON DELETE CASCADE,
+    "epoch" INTEGER NOT NULL,
+    "replica_id" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    PRIMARY KEY(buffer_id, epoch, replica_id)
+);
+
 CREATE TABLE "channel_buffer_collaborators" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
     "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
@@ -319,6 +383,22 @@ CREATE TABLE "observed_buffer_edits" (
 
 CREATE UNIQUE INDEX "index_observed_buffers_user_and_buffer_id" ON "observed_buffer_edits" ("user_id", "buffer_id");
 
+CREATE TABLE "channel_events" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "kind" VARCHAR NOT NULL,
+    "project_id" INTEGER,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX "index_channel_events_on_channel_id_and_created_at" ON "channel_events" ("channel_id", "created_at");
+
+CREATE TABLE "observed_channel_activity" (
+    "user_id" INTEGER PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+    "observed_at" TIMESTAMP NOT NULL
+);
+
 CREATE TABLE IF NOT EXISTS "observed_channel_messages" (
     "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
     "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
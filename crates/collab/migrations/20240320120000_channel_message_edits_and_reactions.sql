@@ -0,0 +1,18 @@
+ALTER TABLE channel_messages ADD edited_at TIMESTAMP DEFAULT NULL;
+
+-- Stores the previous bodies of edited messages, along with when they were replaced.
+CREATE TABLE "channel_message_edits" (
+    "id" SERIAL PRIMARY KEY,
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "edited_at" TIMESTAMP NOT NULL
+);
+
+CREATE INDEX "index_channel_message_edits_on_message_id" ON "channel_message_edits" ("message_id");
+
+CREATE TABLE "channel_message_reactions" (
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL,
+    PRIMARY KEY(message_id, user_id, emoji)
+);
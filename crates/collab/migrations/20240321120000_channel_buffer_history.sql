@@ -0,0 +1,10 @@
+ALTER TABLE buffer_snapshots ADD created_at TIMESTAMP NOT NULL DEFAULT now();
+
+-- Records which users edited a channel buffer during each epoch, so that the snapshot that ends
+-- an epoch can be attributed to them.
+CREATE TABLE "buffer_edit_authors" (
+    "buffer_id" INTEGER NOT NULL REFERENCES buffers (id) ON DELETE CASCADE,
+    "epoch" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    PRIMARY KEY(buffer_id, epoch, user_id)
+);
@@ -0,0 +1,29 @@
+CREATE TABLE "comment_threads" (
+    "id" SERIAL PRIMARY KEY,
+    "channel_id" INTEGER REFERENCES channels (id) ON DELETE CASCADE,
+    "project_id" INTEGER REFERENCES projects (id) ON DELETE SET NULL,
+    "host_user_id" INTEGER REFERENCES users (id) ON DELETE CASCADE,
+    "worktree_abs_path" VARCHAR,
+    "worktree_id" INTEGER,
+    "path" VARCHAR,
+    "start_anchor" BYTEA NOT NULL,
+    "end_anchor" BYTEA NOT NULL,
+    "row" INTEGER NOT NULL,
+    "buffer_epoch" INTEGER,
+    "resolved" BOOLEAN NOT NULL DEFAULT FALSE,
+    "created_at" TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX "index_comment_threads_on_channel_id" ON "comment_threads" ("channel_id");
+CREATE INDEX "index_comment_threads_on_project_id" ON "comment_threads" ("project_id");
+CREATE INDEX "index_comment_threads_on_host_user_id_and_worktree_abs_path" ON "comment_threads" ("host_user_id", "worktree_abs_path");
+
+CREATE TABLE "comments" (
+    "id" SERIAL PRIMARY KEY,
+    "thread_id" INTEGER NOT NULL REFERENCES comment_threads (id) ON DELETE CASCADE,
+    "sender_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "sent_at" TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX "index_comments_on_thread_id" ON "comments" ("thread_id");
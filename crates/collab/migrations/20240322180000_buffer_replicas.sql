@@ -0,0 +1,9 @@
+-- Records which user was last assigned each replica id of a channel buffer, so that a client can
+-- only resume editing with a replica id that was its own.
+CREATE TABLE "buffer_replicas" (
+    "buffer_id" INTEGER NOT NULL REFERENCES buffers (id) ON DELETE CASCADE,
+    "epoch" INTEGER NOT NULL,
+    "replica_id" INTEGER NOT NULL,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    PRIMARY KEY(buffer_id, epoch, replica_id)
+);
@@ -0,0 +1,17 @@
+CREATE TABLE "channel_events" (
+    "id" SERIAL PRIMARY KEY,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "kind" VARCHAR NOT NULL,
+    "project_id" INTEGER,
+    "created_at" TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX "index_channel_events_on_channel_id_and_created_at" ON "channel_events" ("channel_id", "created_at");
+
+CREATE TABLE "observed_channel_activity" (
+    "user_id" INTEGER PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+    "observed_at" TIMESTAMP NOT NULL
+);
+
+ALTER TABLE "buffer_edit_authors" ADD "edited_at" TIMESTAMP NOT NULL DEFAULT now();
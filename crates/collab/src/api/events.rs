@@ -166,7 +166,12 @@ pub async fn post_crash(
         backtrace = %summary,
         "crash report");
 
-    if let Some(slack_panics_webhook) = app.config.slack_panics_webhook.clone() {
+    if let Some(slack_panics_webhook) = app
+        .config
+        .slack_panics_webhook
+        .clone()
+        .filter(|_| !app.config.is_standalone())
+    {
         let payload = slack::WebhookBody::new(|w| {
             w.add_section(|s| s.text(slack::Text::markdown(description)))
                 .add_section(|s| {
@@ -458,6 +458,18 @@ pub struct CreatedChannelMessage {
     pub notifications: NotificationBatch,
 }
 
+pub struct UpdatedChannelMessage {
+    pub message: proto::ChannelMessage,
+    pub participant_connection_ids: Vec<ConnectionId>,
+    pub notifications: NotificationBatch,
+}
+
+pub struct UpdatedCommentThread {
+    pub thread: proto::CommentThread,
+    pub connection_ids: Vec<ConnectionId>,
+    pub notifications: NotificationBatch,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize, Deserialize)]
 pub struct Invite {
     pub email_address: String,
@@ -598,13 +610,24 @@ pub struct ChannelsForUser {
 #[derive(Debug)]
 pub struct RejoinedChannelBuffer {
     pub buffer: proto::RejoinedChannelBuffer,
-    pub old_connection_id: ConnectionId,
+    /// `None` if the collaborator's previous connection had already been
+    /// cleaned up, and its replica id was reclaimed.
+    pub old_connection_id: Option<ConnectionId>,
 }
 
 #[derive(Clone)]
 pub struct JoinRoom {
     pub room: proto::Room,
     pub channel: Option<channel::Model>,
+    /// The entry added to the channel's activity feed, when joining a channel's call.
+    pub activity: Option<proto::ChannelActivity>,
+}
+
+pub struct SharedProject {
+    pub project_id: ProjectId,
+    pub room: proto::Room,
+    /// The channel whose call the project was shared in, and the entry added to its activity feed.
+    pub channel_activity: Option<(channel::Model, proto::ChannelActivity)>,
 }
 
 pub struct RejoinedRoom {
@@ -739,6 +762,16 @@ pub struct ExtensionMetadata {
     pub download_count: u64,
 }
 
+/// The current time in UTC, as stored in the database: truncated to microseconds, which
+/// is the precision of Postgres' timestamps.
+fn now_utc() -> PrimitiveDateTime {
+    let now = time::OffsetDateTime::now_utc();
+    let now = now
+        .replace_nanosecond(now.nanosecond() / 1000 * 1000)
+        .unwrap_or(now);
+    PrimitiveDateTime::new(now.date(), now.time())
+}
+
 pub fn serialize_iso8601<S: Serializer>(
     datetime: &PrimitiveDateTime,
     serializer: S,
@@ -70,9 +70,13 @@ macro_rules! id_type {
 id_type!(BufferId);
 id_type!(AccessTokenId);
 id_type!(ChannelChatParticipantId);
+id_type!(ChannelEventId);
 id_type!(ChannelId);
 id_type!(ChannelMemberId);
 id_type!(MessageId);
+id_type!(MessageEditId);
+id_type!(CommentId);
+id_type!(CommentThreadId);
 id_type!(ContactId);
 id_type!(FollowerId);
 id_type!(RoomId);
@@ -269,3 +273,24 @@ impl Into<i32> for ChannelVisibility {
         proto.into()
     }
 }
+
+/// ChannelEventKind describes something that happened in a channel, for its activity feed.
+#[derive(Eq, PartialEq, Copy, Clone, Debug, EnumIter, DeriveActiveEnum, Hash)]
+#[sea_orm(rs_type = "String", db_type = "String(None)")]
+pub enum ChannelEventKind {
+    /// Someone joined the channel's call.
+    #[sea_orm(string_value = "joined_call")]
+    JoinedCall,
+    /// Someone shared a project in the channel's call.
+    #[sea_orm(string_value = "shared_project")]
+    SharedProject,
+}
+
+impl From<ChannelEventKind> for proto::ChannelActivityKind {
+    fn from(value: ChannelEventKind) -> Self {
+        match value {
+            ChannelEventKind::JoinedCall => proto::ChannelActivityKind::JoinedCall,
+            ChannelEventKind::SharedProject => proto::ChannelActivityKind::SharedProject,
+        }
+    }
+}
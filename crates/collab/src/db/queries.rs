@@ -2,7 +2,9 @@ use super::*;
 
 pub mod access_tokens;
 pub mod buffers;
+pub mod channel_activity;
 pub mod channels;
+pub mod comments;
 pub mod contacts;
 pub mod contributors;
 pub mod extensions;
@@ -46,6 +46,7 @@ impl Database {
                     operation_serialization_version: ActiveValue::Set(
                         storage::SERIALIZATION_VERSION,
                     ),
+                    created_at: ActiveValue::NotSet,
                 }
                 .insert(&*tx)
                 .await?;
@@ -77,6 +78,26 @@ impl Database {
             .await?;
             collaborators.push(collaborator);
 
+            // Remember who holds this replica id, so that only they can resume
+            // editing with it after losing their connection.
+            buffer_replica::Entity::insert(buffer_replica::ActiveModel {
+                buffer_id: ActiveValue::Set(buffer.id),
+                epoch: ActiveValue::Set(buffer.epoch),
+                replica_id: ActiveValue::Set(replica_id),
+                user_id: ActiveValue::Set(user_id),
+            })
+            .on_conflict(
+                OnConflict::columns([
+                    buffer_replica::Column::BufferId,
+                    buffer_replica::Column::Epoch,
+                    buffer_replica::Column::ReplicaId,
+                ])
+                .update_column(buffer_replica::Column::UserId)
+                .to_owned(),
+            )
+            .exec(&*tx)
+            .await?;
+
             let (base_text, operations, max_operation) =
                 self.get_buffer_state(&buffer, &tx).await?;
 
@@ -159,25 +180,6 @@ impl Database {
                     continue;
                 }
 
-                // Find the collaborator record for this user's previous lost
-                // connection. Update it with the new connection id.
-                let Some(self_collaborator) =
-                    collaborators.iter_mut().find(|c| c.user_id == user_id)
-                else {
-                    log::info!("can't rejoin buffer, no previous collaborator found");
-                    continue;
-                };
-                let old_connection_id = self_collaborator.connection();
-                *self_collaborator = channel_buffer_collaborator::ActiveModel {
-                    id: ActiveValue::Unchanged(self_collaborator.id),
-                    connection_id: ActiveValue::Set(connection_id.id as i32),
-                    connection_server_id: ActiveValue::Set(ServerId(connection_id.owner_id as i32)),
-                    connection_lost: ActiveValue::Set(false),
-                    ..Default::default()
-                }
-                .update(&*tx)
-                .await?;
-
                 let client_version = version_from_wire(&client_buffer.version);
                 let serialization_version = self
                     .get_buffer_operation_serialization_version(buffer.id, buffer.epoch, &tx)
@@ -209,6 +211,69 @@ impl Database {
                         })
                     }
                 }
+                drop(rows);
+
+                // Find the collaborator record for this user's previous lost
+                // connection. Update it with the new connection id.
+                let old_connection_id = if let Some(self_collaborator) =
+                    collaborators.iter_mut().find(|c| c.user_id == user_id)
+                {
+                    let old_connection_id = self_collaborator.connection();
+                    *self_collaborator = channel_buffer_collaborator::ActiveModel {
+                        id: ActiveValue::Unchanged(self_collaborator.id),
+                        connection_id: ActiveValue::Set(connection_id.id as i32),
+                        connection_server_id: ActiveValue::Set(ServerId(
+                            connection_id.owner_id as i32,
+                        )),
+                        connection_lost: ActiveValue::Set(false),
+                        ..Default::default()
+                    }
+                    .update(&*tx)
+                    .await?;
+                    Some(old_connection_id)
+                } else {
+                    // The previous connection has already been cleaned up, but the
+                    // client may have kept editing while offline. It can resume with
+                    // its old replica id, as long as that id was assigned to this user
+                    // and nobody else has been assigned it since.
+                    let Some(replica_id) = client_buffer.replica_id.map(|id| ReplicaId(id as i32))
+                    else {
+                        log::info!("can't rejoin buffer, no previous collaborator found");
+                        continue;
+                    };
+                    let replica_holder =
+                        buffer_replica::Entity::find_by_id((buffer.id, buffer.epoch, replica_id))
+                            .one(&*tx)
+                            .await?
+                            .map(|replica| replica.user_id);
+                    if replica_holder != Some(user_id)
+                        || collaborators.iter().any(|c| c.replica_id == replica_id)
+                    {
+                        log::info!("can't rejoin buffer, replica id was assigned to another user");
+                        continue;
+                    }
+                    if server_version.get(replica_id.0 as u16)
+                        > client_version.get(replica_id.0 as u16)
+                    {
+                        log::info!("can't rejoin buffer, client is missing its own operations");
+                        continue;
+                    }
+
+                    let collaborator = channel_buffer_collaborator::ActiveModel {
+                        channel_id: ActiveValue::Set(channel.id),
+                        connection_id: ActiveValue::Set(connection_id.id as i32),
+                        connection_server_id: ActiveValue::Set(ServerId(
+                            connection_id.owner_id as i32,
+                        )),
+                        user_id: ActiveValue::Set(user_id),
+                        replica_id: ActiveValue::Set(replica_id),
+                        ..Default::default()
+                    }
+                    .insert(&*tx)
+                    .await?;
+                    collaborators.push(collaborator);
+                    None
+                };
 
                 results.push(RejoinedChannelBuffer {
                     old_connection_id,
@@ -268,6 +333,12 @@ impl Database {
                 .exec(&*tx)
                 .await?;
 
+            // The reconnect window for the stale collaborators has expired, so
+            // collapse the operations if nobody is collaborating anymore.
+            if collaborators.is_empty() {
+                self.snapshot_channel_buffer(channel_id, &tx).await?;
+            }
+
             Ok(RefreshedChannelBuffer {
                 connection_ids,
                 collaborators,
@@ -510,6 +581,26 @@ impl Database {
                     .await?;
                 channel_members.retain(|member| !collaborators.contains(member));
 
+                if requires_write_permission {
+                    buffer_edit_author::Entity::insert(buffer_edit_author::ActiveModel {
+                        buffer_id: ActiveValue::Set(buffer.id),
+                        epoch: ActiveValue::Set(buffer.epoch),
+                        user_id: ActiveValue::Set(user),
+                        edited_at: ActiveValue::Set(now_utc()),
+                    })
+                    .on_conflict(
+                        OnConflict::columns([
+                            buffer_edit_author::Column::BufferId,
+                            buffer_edit_author::Column::Epoch,
+                            buffer_edit_author::Column::UserId,
+                        ])
+                        .update_column(buffer_edit_author::Column::EditedAt)
+                        .to_owned(),
+                    )
+                    .exec_without_returning(&*tx)
+                    .await?;
+                }
+
                 buffer_operation::Entity::insert_many(operations)
                     .on_conflict(
                         OnConflict::columns([
@@ -701,21 +792,24 @@ impl Database {
             return Ok(());
         }
 
-        let mut text_buffer = text::Buffer::new(0, text::BufferId::new(1).unwrap(), base_text);
+        let mut text_buffer =
+            text::Buffer::new(0, text::BufferId::new(buffer.id.to_proto())?, base_text);
         text_buffer
             .apply_ops(operations.into_iter().filter_map(operation_from_wire))
             .unwrap();
+        self.reanchor_channel_comment_threads(channel_id, buffer.epoch, &text_buffer, tx)
+            .await?;
 
         let base_text = text_buffer.text();
         let epoch = buffer.epoch + 1;
 
-        buffer_snapshot::Model {
-            buffer_id: buffer.id,
-            epoch,
-            text: base_text,
-            operation_serialization_version: storage::SERIALIZATION_VERSION,
+        buffer_snapshot::ActiveModel {
+            buffer_id: ActiveValue::Set(buffer.id),
+            epoch: ActiveValue::Set(epoch),
+            text: ActiveValue::Set(base_text),
+            operation_serialization_version: ActiveValue::Set(storage::SERIALIZATION_VERSION),
+            created_at: ActiveValue::NotSet,
         }
-        .into_active_model()
         .insert(tx)
         .await?;
 
@@ -730,9 +824,101 @@ impl Database {
         .save(tx)
         .await?;
 
+        // Replica ids are reassigned in the new epoch.
+        buffer_replica::Entity::delete_many()
+            .filter(buffer_replica::Column::BufferId.eq(buffer.id))
+            .filter(buffer_replica::Column::Epoch.lt(epoch))
+            .exec(tx)
+            .await?;
+
         Ok(())
     }
 
+    /// Lists the snapshots of a channel's notes, newest first. Each snapshot is attributed to the
+    /// users who edited the notes between it and the previous snapshot.
+    pub async fn get_channel_buffer_checkpoints(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::ChannelBufferCheckpoint>> {
+        #[derive(Debug, FromQueryResult)]
+        struct Checkpoint {
+            epoch: i32,
+            created_at: PrimitiveDateTime,
+        }
+
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+            let buffer = self.get_channel_buffer(channel_id, &tx).await?;
+
+            let mut author_ids_by_epoch = HashMap::<i32, Vec<u64>>::default();
+            let mut rows = buffer_edit_author::Entity::find()
+                .filter(buffer_edit_author::Column::BufferId.eq(buffer.id))
+                .order_by_asc(buffer_edit_author::Column::UserId)
+                .stream(&*tx)
+                .await?;
+            while let Some(row) = rows.next().await {
+                let row = row?;
+                author_ids_by_epoch
+                    .entry(row.epoch)
+                    .or_default()
+                    .push(row.user_id.to_proto());
+            }
+            drop(rows);
+
+            // The snapshot for epoch 0 is the empty buffer the notes started from.
+            let checkpoints = buffer_snapshot::Entity::find()
+                .select_only()
+                .column(buffer_snapshot::Column::Epoch)
+                .column(buffer_snapshot::Column::CreatedAt)
+                .filter(buffer_snapshot::Column::BufferId.eq(buffer.id))
+                .filter(buffer_snapshot::Column::Epoch.gt(0))
+                .order_by_desc(buffer_snapshot::Column::Epoch)
+                .into_model::<Checkpoint>()
+                .all(&*tx)
+                .await?;
+
+            Ok(checkpoints
+                .into_iter()
+                .map(|checkpoint| proto::ChannelBufferCheckpoint {
+                    epoch: checkpoint.epoch as u64,
+                    created_at: checkpoint.created_at.assume_utc().unix_timestamp() as u64,
+                    author_ids: author_ids_by_epoch
+                        .remove(&(checkpoint.epoch - 1))
+                        .unwrap_or_default(),
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Returns the text of a channel's notes as of the snapshot taken at the start of the given
+    /// epoch.
+    pub async fn load_channel_buffer_checkpoint(
+        &self,
+        channel_id: ChannelId,
+        epoch: i32,
+        user_id: UserId,
+    ) -> Result<String> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+            let buffer = self.get_channel_buffer(channel_id, &tx).await?;
+
+            let snapshot = buffer_snapshot::Entity::find()
+                .filter(buffer_snapshot::Column::BufferId.eq(buffer.id))
+                .filter(buffer_snapshot::Column::Epoch.eq(epoch))
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such checkpoint"))?;
+            Ok(snapshot.text)
+        })
+        .await
+    }
+
     pub async fn observe_buffer_version(
         &self,
         buffer_id: BufferId,
@@ -784,6 +970,7 @@ impl Database {
                         replica_id: op.replica_id as u32,
                         timestamp: op.lamport_timestamp as u32,
                     }],
+                    replica_id: None,
                 })
             })
             .collect())
@@ -0,0 +1,289 @@
+use super::*;
+
+/// The maximum number of entries returned for each page of a user's channel activity.
+const PAGE_SIZE: usize = 50;
+
+/// How long entries stay in a channel's activity feed before they're pruned.
+const CHANNEL_EVENT_RETENTION: time::Duration = time::Duration::days(30);
+
+impl Database {
+    /// Returns a page of recent activity in the user's channels, newest first: calls
+    /// joined and projects shared by other members, edits to channel notes that the
+    /// user hasn't seen yet (one entry per author), and chat messages mentioning them.
+    pub async fn get_channel_activity(
+        &self,
+        user_id: UserId,
+        before: Option<PrimitiveDateTime>,
+    ) -> Result<proto::GetChannelActivityResponse> {
+        self.transaction(|tx| async move {
+            let channel_ids = self
+                .get_user_channels(user_id, None, &tx)
+                .await?
+                .channels
+                .iter()
+                .map(|channel| channel.id)
+                .collect::<Vec<_>>();
+
+            let mut activities = Vec::new();
+            activities.extend(
+                self.get_channel_events(&channel_ids, user_id, before, &tx)
+                    .await?,
+            );
+            activities.extend(
+                self.get_channel_mentions(&channel_ids, user_id, before, &tx)
+                    .await?,
+            );
+            activities.extend(
+                self.get_unseen_channel_notes_edits(&channel_ids, user_id, before, &tx)
+                    .await?,
+            );
+            activities.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+            // Events and mentions are each limited to a page, so if the sources don't
+            // fill one together, they have all been exhausted.
+            let done = activities.len() < PAGE_SIZE;
+            activities.truncate(PAGE_SIZE);
+
+            let observed_at = observed_channel_activity::Entity::find_by_id(user_id)
+                .one(&*tx)
+                .await?
+                .map(|row| activity_timestamp_to_proto(row.observed_at));
+
+            Ok(proto::GetChannelActivityResponse {
+                activities,
+                observed_at,
+                done,
+            })
+        })
+        .await
+    }
+
+    /// Records that the user has seen their channels' activity up to the given time.
+    pub async fn observe_channel_activity(
+        &self,
+        user_id: UserId,
+        observed_at: PrimitiveDateTime,
+    ) -> Result<()> {
+        self.transaction(|tx| async move {
+            use observed_channel_activity::Column;
+            observed_channel_activity::Entity::insert(observed_channel_activity::ActiveModel {
+                user_id: ActiveValue::Set(user_id),
+                observed_at: ActiveValue::Set(observed_at),
+            })
+            .on_conflict(
+                OnConflict::column(Column::UserId)
+                    .update_column(Column::ObservedAt)
+                    .action_cond_where(Column::ObservedAt.lt(observed_at))
+                    .to_owned(),
+            )
+            .exec_without_returning(&*tx)
+            .await?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Adds an entry to the channel's activity feed.
+    pub(crate) async fn record_channel_event(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        kind: ChannelEventKind,
+        project_id: Option<ProjectId>,
+        tx: &DatabaseTransaction,
+    ) -> Result<proto::ChannelActivity> {
+        let now = now_utc();
+
+        // Prune the channel's expired entries as new ones are added, so that its feed
+        // doesn't grow without bound.
+        channel_event::Entity::delete_many()
+            .filter(
+                channel_event::Column::ChannelId
+                    .eq(channel_id)
+                    .and(channel_event::Column::CreatedAt.lt(now - CHANNEL_EVENT_RETENTION)),
+            )
+            .exec(tx)
+            .await?;
+
+        let event = channel_event::ActiveModel {
+            id: ActiveValue::NotSet,
+            channel_id: ActiveValue::Set(channel_id),
+            user_id: ActiveValue::Set(user_id),
+            kind: ActiveValue::Set(kind),
+            project_id: ActiveValue::Set(project_id),
+            created_at: ActiveValue::Set(now),
+        }
+        .insert(tx)
+        .await?;
+        Ok(channel_event_to_proto(event))
+    }
+
+    async fn get_channel_events(
+        &self,
+        channel_ids: &[ChannelId],
+        user_id: UserId,
+        before: Option<PrimitiveDateTime>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ChannelActivity>> {
+        let mut condition = Condition::all()
+            .add(channel_event::Column::ChannelId.is_in(channel_ids.iter().copied()))
+            .add(channel_event::Column::UserId.ne(user_id));
+        if let Some(before) = before {
+            condition = condition.add(channel_event::Column::CreatedAt.lt(before));
+        }
+
+        Ok(channel_event::Entity::find()
+            .filter(condition)
+            .order_by_desc(channel_event::Column::CreatedAt)
+            .limit(PAGE_SIZE as u64)
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(channel_event_to_proto)
+            .collect())
+    }
+
+    async fn get_channel_mentions(
+        &self,
+        channel_ids: &[ChannelId],
+        user_id: UserId,
+        before: Option<PrimitiveDateTime>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ChannelActivity>> {
+        let mut condition = Condition::all()
+            .add(channel_message_mention::Column::UserId.eq(user_id))
+            .add(channel_message::Column::ChannelId.is_in(channel_ids.iter().copied()))
+            .add(channel_message::Column::SenderId.ne(user_id));
+        if let Some(before) = before {
+            condition = condition.add(channel_message::Column::SentAt.lt(before));
+        }
+
+        let messages = channel_message::Entity::find()
+            .join(
+                JoinType::InnerJoin,
+                channel_message_mention::Relation::Message.def().rev(),
+            )
+            .filter(condition)
+            .order_by_desc(channel_message::Column::SentAt)
+            .limit(PAGE_SIZE as u64)
+            .all(tx)
+            .await?;
+
+        let mut message_ids = HashSet::default();
+        Ok(messages
+            .into_iter()
+            .filter(|message| message_ids.insert(message.id))
+            .map(|message| proto::ChannelActivity {
+                channel_id: message.channel_id.to_proto(),
+                user_id: message.sender_id.to_proto(),
+                timestamp: activity_timestamp_to_proto(message.sent_at),
+                kind: proto::ChannelActivityKind::Mention.into(),
+                project_id: None,
+                message_id: Some(message.id.to_proto()),
+                body: Some(message.body),
+            })
+            .collect())
+    }
+
+    /// Summarizes the edits to the channels' notes that the user hasn't observed, with
+    /// one entry for each other author whose latest observed edit is newer than the user's.
+    async fn get_unseen_channel_notes_edits(
+        &self,
+        channel_ids: &[ChannelId],
+        user_id: UserId,
+        before: Option<PrimitiveDateTime>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ChannelActivity>> {
+        let buffers = buffer::Entity::find()
+            .filter(buffer::Column::ChannelId.is_in(channel_ids.iter().copied()))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|buffer| (buffer.id, buffer))
+            .collect::<HashMap<_, _>>();
+
+        let observed_versions = observed_buffer_edits::Entity::find()
+            .filter(observed_buffer_edits::Column::BufferId.is_in(buffers.keys().copied()))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|row| {
+                (
+                    (row.buffer_id, row.user_id),
+                    (row.epoch, row.lamport_timestamp, row.replica_id),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut condition = Condition::all()
+            .add(buffer_edit_author::Column::BufferId.is_in(buffers.keys().copied()))
+            .add(buffer_edit_author::Column::UserId.ne(user_id));
+        if let Some(before) = before {
+            condition = condition.add(buffer_edit_author::Column::EditedAt.lt(before));
+        }
+
+        // Most authors' edits are usually either stale or already observed, so keep
+        // reading rows until a page's worth of unseen edits has been found.
+        let mut activities = Vec::new();
+        let mut offset = 0;
+        while activities.len() < PAGE_SIZE {
+            let authors = buffer_edit_author::Entity::find()
+                .filter(condition.clone())
+                .order_by_desc(buffer_edit_author::Column::EditedAt)
+                .offset(offset)
+                .limit(PAGE_SIZE as u64)
+                .all(tx)
+                .await?;
+            let exhausted = authors.len() < PAGE_SIZE;
+            offset += authors.len() as u64;
+
+            activities.extend(authors.into_iter().filter_map(|author| {
+                let buffer = buffers.get(&author.buffer_id)?;
+                if author.epoch != buffer.epoch {
+                    return None;
+                }
+
+                let author_version = observed_versions.get(&(buffer.id, author.user_id))?;
+                if let Some(observed_version) = observed_versions.get(&(buffer.id, user_id)) {
+                    if author_version <= observed_version {
+                        return None;
+                    }
+                }
+
+                Some(proto::ChannelActivity {
+                    channel_id: buffer.channel_id.to_proto(),
+                    user_id: author.user_id.to_proto(),
+                    timestamp: activity_timestamp_to_proto(author.edited_at),
+                    kind: proto::ChannelActivityKind::EditedNotes.into(),
+                    project_id: None,
+                    message_id: None,
+                    body: None,
+                })
+            }));
+
+            if exhausted {
+                break;
+            }
+        }
+        activities.truncate(PAGE_SIZE);
+        Ok(activities)
+    }
+}
+
+fn channel_event_to_proto(event: channel_event::Model) -> proto::ChannelActivity {
+    proto::ChannelActivity {
+        channel_id: event.channel_id.to_proto(),
+        user_id: event.user_id.to_proto(),
+        timestamp: activity_timestamp_to_proto(event.created_at),
+        kind: proto::ChannelActivityKind::from(event.kind).into(),
+        project_id: event.project_id.map(|project_id| project_id.to_proto()),
+        message_id: None,
+        body: None,
+    }
+}
+
+/// Activity timestamps are sent with microsecond precision, so that paging through the
+/// feed doesn't skip over entries that happened within the same second.
+fn activity_timestamp_to_proto(timestamp: PrimitiveDateTime) -> u64 {
+    (timestamp.assume_utc().unix_timestamp_nanos() / 1000) as u64
+}
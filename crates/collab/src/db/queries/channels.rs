@@ -622,6 +622,7 @@ impl Database {
                 } else {
                     vec![]
                 },
+                replica_id: None,
             });
         }
         drop(rows);
@@ -0,0 +1,540 @@
+use super::*;
+use prost::Message as _;
+use rpc::Notification;
+use text::{Bias, Point, ToOffset as _};
+
+impl Database {
+    /// Returns the comment threads on a channel's notes, or on the files of a shared project.
+    pub async fn get_comment_threads(
+        &self,
+        channel_id: Option<ChannelId>,
+        project_id: Option<ProjectId>,
+        user_id: UserId,
+        connection_id: ConnectionId,
+    ) -> Result<Vec<proto::CommentThread>> {
+        self.transaction(|tx| async move {
+            let condition = match (channel_id, project_id) {
+                (Some(channel_id), None) => comment_thread::Column::ChannelId.eq(channel_id),
+                (None, Some(project_id)) => comment_thread::Column::ProjectId.eq(project_id),
+                _ => Err(anyhow!(
+                    "comment threads belong to either a channel or a project"
+                ))?,
+            };
+            self.comment_thread_connection_ids(
+                channel_id,
+                project_id,
+                user_id,
+                connection_id,
+                false,
+                &tx,
+            )
+            .await?;
+
+            let threads = comment_thread::Entity::find()
+                .filter(condition)
+                .order_by_asc(comment_thread::Column::Id)
+                .all(&*tx)
+                .await?;
+            self.comment_threads_to_proto(threads, &tx).await
+        })
+        .await
+    }
+
+    /// Starts a new comment thread on the given range of a buffer.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_comment_thread(
+        &self,
+        target: proto::CommentTarget,
+        start: proto::Anchor,
+        end: proto::Anchor,
+        row: u32,
+        buffer_epoch: Option<u64>,
+        body: &str,
+        user_id: UserId,
+        connection_id: ConnectionId,
+    ) -> Result<UpdatedCommentThread> {
+        self.transaction(|tx| {
+            let target = target.clone();
+            let start = start.clone();
+            let end = end.clone();
+            async move {
+                let (channel_id, project_id, worktree_id, path) = match target.variant {
+                    Some(proto::comment_target::Variant::ChannelId(channel_id)) => {
+                        (Some(ChannelId::from_proto(channel_id)), None, None, None)
+                    }
+                    Some(proto::comment_target::Variant::ProjectPath(project_path)) => (
+                        None,
+                        Some(ProjectId::from_proto(project_path.project_id)),
+                        Some(project_path.worktree_id as i64),
+                        Some(project_path.path),
+                    ),
+                    None => Err(anyhow!("invalid comment target"))?,
+                };
+                let connection_ids = self
+                    .comment_thread_connection_ids(
+                        channel_id,
+                        project_id,
+                        user_id,
+                        connection_id,
+                        true,
+                        &tx,
+                    )
+                    .await?;
+
+                // Remember where the file lives on the host's machine, so that the thread can be
+                // found again when the project is unshared and shared anew.
+                let (host_user_id, worktree_abs_path) =
+                    if let (Some(project_id), Some(worktree_id)) = (project_id, worktree_id) {
+                        let project = project::Entity::find_by_id(project_id)
+                            .one(&*tx)
+                            .await?
+                            .ok_or_else(|| anyhow!("no such project"))?;
+                        let worktree = worktree::Entity::find_by_id((worktree_id, project_id))
+                            .one(&*tx)
+                            .await?
+                            .ok_or_else(|| anyhow!("no such worktree"))?;
+                        (project.host_user_id, Some(worktree.abs_path))
+                    } else {
+                        (None, None)
+                    };
+
+                let thread = comment_thread::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    channel_id: ActiveValue::Set(channel_id),
+                    project_id: ActiveValue::Set(project_id),
+                    host_user_id: ActiveValue::Set(host_user_id),
+                    worktree_abs_path: ActiveValue::Set(worktree_abs_path),
+                    worktree_id: ActiveValue::Set(worktree_id),
+                    path: ActiveValue::Set(path),
+                    start_anchor: ActiveValue::Set(anchor_to_storage(start)),
+                    end_anchor: ActiveValue::Set(anchor_to_storage(end)),
+                    row: ActiveValue::Set(row as i32),
+                    buffer_epoch: ActiveValue::Set(buffer_epoch.map(|epoch| epoch as i32)),
+                    resolved: ActiveValue::Set(false),
+                    created_at: ActiveValue::NotSet,
+                }
+                .insert(&*tx)
+                .await?;
+
+                comment::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    thread_id: ActiveValue::Set(thread.id),
+                    sender_id: ActiveValue::Set(user_id),
+                    body: ActiveValue::Set(body.to_string()),
+                    sent_at: ActiveValue::NotSet,
+                }
+                .insert(&*tx)
+                .await?;
+
+                Ok(UpdatedCommentThread {
+                    thread: self.comment_thread_to_proto(thread, &tx).await?,
+                    connection_ids,
+                    notifications: Default::default(),
+                })
+            }
+        })
+        .await
+    }
+
+    /// Replies to a comment thread, notifying everyone else who has commented on it.
+    pub async fn add_comment(
+        &self,
+        thread_id: CommentThreadId,
+        body: &str,
+        user_id: UserId,
+        connection_id: ConnectionId,
+    ) -> Result<UpdatedCommentThread> {
+        self.transaction(|tx| async move {
+            let thread = comment_thread::Entity::find_by_id(thread_id)
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such comment thread"))?;
+            let connection_ids = self
+                .comment_thread_connection_ids(
+                    thread.channel_id,
+                    thread.project_id,
+                    user_id,
+                    connection_id,
+                    true,
+                    &tx,
+                )
+                .await?;
+
+            let comment = comment::ActiveModel {
+                id: ActiveValue::NotSet,
+                thread_id: ActiveValue::Set(thread_id),
+                sender_id: ActiveValue::Set(user_id),
+                body: ActiveValue::Set(body.to_string()),
+                sent_at: ActiveValue::NotSet,
+            }
+            .insert(&*tx)
+            .await?;
+
+            let previous_commenters = comment::Entity::find()
+                .filter(comment::Column::ThreadId.eq(thread_id))
+                .filter(comment::Column::SenderId.ne(user_id))
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(|comment| comment.sender_id)
+                .collect::<HashSet<_>>();
+
+            let mut notifications = NotificationBatch::default();
+            for recipient_id in previous_commenters {
+                notifications.extend(
+                    self.create_notification(
+                        recipient_id,
+                        Notification::CodeComment {
+                            comment_id: comment.id.to_proto(),
+                            thread_id: thread_id.to_proto(),
+                            sender_id: user_id.to_proto(),
+                            channel_id: thread.channel_id.map(|id| id.to_proto()),
+                            path: thread.path.clone(),
+                        },
+                        false,
+                        &tx,
+                    )
+                    .await?,
+                );
+            }
+
+            Ok(UpdatedCommentThread {
+                thread: self.comment_thread_to_proto(thread, &tx).await?,
+                connection_ids,
+                notifications,
+            })
+        })
+        .await
+    }
+
+    /// Marks a comment thread as resolved, or reopens it.
+    pub async fn set_comment_thread_resolved(
+        &self,
+        thread_id: CommentThreadId,
+        resolved: bool,
+        user_id: UserId,
+        connection_id: ConnectionId,
+    ) -> Result<UpdatedCommentThread> {
+        self.transaction(|tx| async move {
+            let thread = comment_thread::Entity::find_by_id(thread_id)
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such comment thread"))?;
+            let connection_ids = self
+                .comment_thread_connection_ids(
+                    thread.channel_id,
+                    thread.project_id,
+                    user_id,
+                    connection_id,
+                    true,
+                    &tx,
+                )
+                .await?;
+
+            let thread = comment_thread::ActiveModel {
+                id: ActiveValue::Unchanged(thread_id),
+                resolved: ActiveValue::Set(resolved),
+                ..Default::default()
+            }
+            .update(&*tx)
+            .await?;
+
+            Ok(UpdatedCommentThread {
+                thread: self.comment_thread_to_proto(thread, &tx).await?,
+                connection_ids,
+                notifications: Default::default(),
+            })
+        })
+        .await
+    }
+
+    /// Moves the anchors of a channel's comment threads into the buffer that starts the next
+    /// epoch of its notes, whose base text is the final text of the given buffer.
+    pub(super) async fn reanchor_channel_comment_threads(
+        &self,
+        channel_id: ChannelId,
+        epoch: i32,
+        text_buffer: &text::Buffer,
+        tx: &DatabaseTransaction,
+    ) -> Result<()> {
+        let threads = comment_thread::Entity::find()
+            .filter(comment_thread::Column::ChannelId.eq(channel_id))
+            .all(tx)
+            .await?;
+        if threads.is_empty() {
+            return Ok(());
+        }
+
+        let next_buffer = text::Buffer::new(0, text_buffer.remote_id(), text_buffer.text());
+        for thread in threads {
+            // Anchors from an earlier epoch can't be resolved, so fall back to the stored row.
+            let resolve = |anchor: &[u8]| {
+                if thread.buffer_epoch != Some(epoch) {
+                    return None;
+                }
+                let anchor = anchor_from_wire(proto::Anchor::decode(anchor).ok()?)?;
+                text_buffer
+                    .can_resolve(&anchor)
+                    .then(|| anchor.to_offset(text_buffer))
+            };
+            let start = resolve(&thread.start_anchor).unwrap_or_else(|| {
+                text_buffer.point_to_offset(
+                    text_buffer.clip_point(Point::new(thread.row as u32, 0), Bias::Left),
+                )
+            });
+            let end = resolve(&thread.end_anchor).unwrap_or(start).max(start);
+
+            comment_thread::ActiveModel {
+                id: ActiveValue::Unchanged(thread.id),
+                start_anchor: ActiveValue::Set(anchor_to_storage(anchor_to_wire(
+                    next_buffer.anchor_before(start),
+                ))),
+                end_anchor: ActiveValue::Set(anchor_to_storage(anchor_to_wire(
+                    next_buffer.anchor_after(end),
+                ))),
+                row: ActiveValue::Set(next_buffer.offset_to_point(start).row as i32),
+                buffer_epoch: ActiveValue::Set(Some(epoch + 1)),
+                ..Default::default()
+            }
+            .update(tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Associates a project with the comment threads left on its worktrees while they were
+    /// shared previously, and detaches the threads on worktrees that it no longer contains.
+    pub(super) async fn update_project_comment_threads(
+        &self,
+        project_id: ProjectId,
+        tx: &DatabaseTransaction,
+    ) -> Result<()> {
+        let project = project::Entity::find_by_id(project_id)
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such project"))?;
+        let Some(host_user_id) = project.host_user_id else {
+            return Ok(());
+        };
+        let worktrees = worktree::Entity::find()
+            .filter(worktree::Column::ProjectId.eq(project_id))
+            .all(tx)
+            .await?;
+
+        comment_thread::Entity::update_many()
+            .filter(
+                comment_thread::Column::ProjectId.eq(project_id).and(
+                    comment_thread::Column::WorktreeAbsPath
+                        .is_not_in(worktrees.iter().map(|worktree| worktree.abs_path.clone())),
+                ),
+            )
+            .set(comment_thread::ActiveModel {
+                project_id: ActiveValue::set(None),
+                ..Default::default()
+            })
+            .exec(tx)
+            .await?;
+
+        for worktree in worktrees {
+            comment_thread::Entity::update_many()
+                .filter(
+                    Condition::all()
+                        .add(comment_thread::Column::ProjectId.is_null())
+                        .add(comment_thread::Column::HostUserId.eq(host_user_id))
+                        .add(comment_thread::Column::WorktreeAbsPath.eq(worktree.abs_path)),
+                )
+                .set(comment_thread::ActiveModel {
+                    project_id: ActiveValue::set(Some(project_id)),
+                    worktree_id: ActiveValue::set(Some(worktree.id)),
+                    ..Default::default()
+                })
+                .exec(tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the user can see (or, if `requires_write` is set, add to) the comment threads
+    /// of a channel or project, and returns the connections that should be told about changes.
+    async fn comment_thread_connection_ids(
+        &self,
+        channel_id: Option<ChannelId>,
+        project_id: Option<ProjectId>,
+        user_id: UserId,
+        connection_id: ConnectionId,
+        requires_write: bool,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<ConnectionId>> {
+        if let Some(channel_id) = channel_id {
+            let channel = self.get_channel_internal(channel_id, tx).await?;
+            if requires_write {
+                self.check_user_is_channel_member(&channel, user_id, tx)
+                    .await?;
+            } else {
+                self.check_user_is_channel_participant(&channel, user_id, tx)
+                    .await?;
+            }
+
+            Ok(channel_buffer_collaborator::Entity::find()
+                .filter(channel_buffer_collaborator::Column::ChannelId.eq(channel_id))
+                .all(tx)
+                .await?
+                .into_iter()
+                .map(|collaborator| collaborator.connection())
+                .collect())
+        } else if let Some(project_id) = project_id {
+            let project = project::Entity::find_by_id(project_id)
+                .one(tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such project"))?;
+            let collaborators = project_collaborator::Entity::find()
+                .filter(project_collaborator::Column::ProjectId.eq(project_id))
+                .all(tx)
+                .await?;
+            let current_collaborator = collaborators
+                .iter()
+                .find(|collaborator| collaborator.connection() == connection_id)
+                .ok_or_else(|| anyhow!("no such project"))?;
+
+            if requires_write && !current_collaborator.is_host {
+                let room_id = project
+                    .room_id
+                    .ok_or_else(|| anyhow!("not authorized to comment on this project"))?;
+                let current_participant = room_participant::Entity::find()
+                    .filter(room_participant::Column::RoomId.eq(room_id))
+                    .filter(room_participant::Column::AnsweringConnectionId.eq(connection_id.id))
+                    .one(tx)
+                    .await?
+                    .ok_or_else(|| anyhow!("no such room"))?;
+                if !current_participant
+                    .role
+                    .map_or(false, |role| role.can_edit_projects())
+                {
+                    Err(anyhow!("not authorized to comment on this project"))?;
+                }
+            }
+
+            Ok(collaborators
+                .iter()
+                .map(|collaborator| collaborator.connection())
+                .collect())
+        } else {
+            Err(anyhow!(
+                "comment threads belong to either a channel or a project"
+            ))?
+        }
+    }
+
+    async fn comment_thread_to_proto(
+        &self,
+        thread: comment_thread::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<proto::CommentThread> {
+        Ok(self
+            .comment_threads_to_proto(vec![thread], tx)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("failed to load comment thread"))?)
+    }
+
+    async fn comment_threads_to_proto(
+        &self,
+        threads: Vec<comment_thread::Model>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::CommentThread>> {
+        let mut comments_by_thread_id =
+            HashMap::<CommentThreadId, Vec<proto::CodeComment>>::default();
+        let mut rows = comment::Entity::find()
+            .filter(comment::Column::ThreadId.is_in(threads.iter().map(|thread| thread.id)))
+            .order_by_asc(comment::Column::Id)
+            .stream(tx)
+            .await?;
+        while let Some(row) = rows.next().await {
+            let row = row?;
+            comments_by_thread_id
+                .entry(row.thread_id)
+                .or_default()
+                .push(proto::CodeComment {
+                    id: row.id.to_proto(),
+                    sender_id: row.sender_id.to_proto(),
+                    body: row.body,
+                    timestamp: row.sent_at.assume_utc().unix_timestamp() as u64,
+                });
+        }
+        drop(rows);
+
+        threads
+            .into_iter()
+            .map(|thread| {
+                let target = if let Some(channel_id) = thread.channel_id {
+                    proto::comment_target::Variant::ChannelId(channel_id.to_proto())
+                } else {
+                    proto::comment_target::Variant::ProjectPath(
+                        proto::comment_target::ProjectPath {
+                            project_id: thread
+                                .project_id
+                                .ok_or_else(|| anyhow!("comment thread has no target"))?
+                                .to_proto(),
+                            worktree_id: thread.worktree_id.unwrap_or_default() as u64,
+                            path: thread.path.unwrap_or_default(),
+                        },
+                    )
+                };
+                Ok(proto::CommentThread {
+                    id: thread.id.to_proto(),
+                    target: Some(proto::CommentTarget {
+                        variant: Some(target),
+                    }),
+                    start: Some(anchor_from_storage(&thread.start_anchor)?),
+                    end: Some(anchor_from_storage(&thread.end_anchor)?),
+                    row: thread.row as u32,
+                    buffer_epoch: thread.buffer_epoch.map(|epoch| epoch as u64),
+                    resolved: thread.resolved,
+                    comments: comments_by_thread_id.remove(&thread.id).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn anchor_to_storage(anchor: proto::Anchor) -> Vec<u8> {
+    anchor.encode_to_vec()
+}
+
+fn anchor_from_storage(anchor: &[u8]) -> Result<proto::Anchor> {
+    Ok(proto::Anchor::decode(anchor).map_err(|error| anyhow!("{}", error))?)
+}
+
+// These are currently manual copies of the anchor serialization code in the client's language crate
+fn anchor_to_wire(anchor: text::Anchor) -> proto::Anchor {
+    proto::Anchor {
+        replica_id: anchor.timestamp.replica_id as u32,
+        timestamp: anchor.timestamp.value,
+        offset: anchor.offset as u64,
+        bias: match anchor.bias {
+            Bias::Left => proto::Bias::Left as i32,
+            Bias::Right => proto::Bias::Right as i32,
+        },
+        buffer_id: anchor.buffer_id.map(Into::into),
+    }
+}
+
+fn anchor_from_wire(anchor: proto::Anchor) -> Option<text::Anchor> {
+    Some(text::Anchor {
+        timestamp: clock::Lamport {
+            replica_id: anchor.replica_id as clock::ReplicaId,
+            value: anchor.timestamp,
+        },
+        offset: anchor.offset as usize,
+        bias: match proto::Bias::from_i32(anchor.bias)? {
+            proto::Bias::Left => Bias::Left,
+            proto::Bias::Right => Bias::Right,
+        },
+        buffer_id: match anchor.buffer_id {
+            Some(buffer_id) => Some(text::BufferId::new(buffer_id).ok()?),
+            None => None,
+        },
+    })
+}
@@ -162,6 +162,10 @@ impl Database {
                         lower_half: nonce.1,
                     }),
                     reply_to_message_id: row.reply_to_message_id.map(|id| id.to_proto()),
+                    edited_at: row
+                        .edited_at
+                        .map(|edited_at| edited_at.assume_utc().unix_timestamp() as u64),
+                    reactions: vec![],
                 }
             })
             .collect::<Vec<_>>();
@@ -196,9 +200,77 @@ impl Database {
             }
         }
 
+        drop(mentions);
+
+        let mut reactions = channel_message_reaction::Entity::find()
+            .filter(
+                channel_message_reaction::Column::MessageId.is_in(messages.iter().map(|m| m.id)),
+            )
+            .order_by_asc(channel_message_reaction::Column::MessageId)
+            .order_by_asc(channel_message_reaction::Column::Emoji)
+            .order_by_asc(channel_message_reaction::Column::UserId)
+            .stream(tx)
+            .await?;
+
+        let mut message_ix = 0;
+        while let Some(reaction) = reactions.next().await {
+            let reaction = reaction?;
+            let message_id = reaction.message_id.to_proto();
+            while let Some(message) = messages.get_mut(message_ix) {
+                if message.id < message_id {
+                    message_ix += 1;
+                } else {
+                    if message.id == message_id {
+                        match message.reactions.last_mut() {
+                            Some(last) if last.emoji == reaction.emoji => {
+                                last.user_ids.push(reaction.user_id.to_proto());
+                            }
+                            _ => message.reactions.push(proto::ChannelMessageReaction {
+                                emoji: reaction.emoji,
+                                user_ids: vec![reaction.user_id.to_proto()],
+                            }),
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+
         Ok(messages)
     }
 
+    async fn create_channel_message_mentions(
+        &self,
+        message_id: MessageId,
+        body: &str,
+        mentions: &[proto::ChatMention],
+        tx: &DatabaseTransaction,
+    ) -> Result<()> {
+        let mentions = mentions
+            .iter()
+            .filter_map(|mention| {
+                let range = mention.range.as_ref()?;
+                if !body.is_char_boundary(range.start as usize)
+                    || !body.is_char_boundary(range.end as usize)
+                {
+                    return None;
+                }
+                Some(channel_message_mention::ActiveModel {
+                    message_id: ActiveValue::Set(message_id),
+                    start_offset: ActiveValue::Set(range.start as i32),
+                    end_offset: ActiveValue::Set(range.end as i32),
+                    user_id: ActiveValue::Set(UserId::from_proto(mention.user_id)),
+                })
+            })
+            .collect::<Vec<_>>();
+        if !mentions.is_empty() {
+            channel_message_mention::Entity::insert_many(mentions)
+                .exec(tx)
+                .await?;
+        }
+        Ok(())
+    }
+
     /// Creates a new channel message.
     #[allow(clippy::too_many_arguments)]
     pub async fn create_channel_message(
@@ -249,6 +321,7 @@ impl Database {
                 nonce: ActiveValue::Set(Uuid::from_u128(nonce)),
                 id: ActiveValue::NotSet,
                 reply_to_message_id: ActiveValue::Set(reply_to_message_id),
+                edited_at: ActiveValue::NotSet,
             })
             .on_conflict(
                 OnConflict::columns([
@@ -270,28 +343,8 @@ impl Database {
                     let mentioned_user_ids =
                         mentions.iter().map(|m| m.user_id).collect::<HashSet<_>>();
 
-                    let mentions = mentions
-                        .iter()
-                        .filter_map(|mention| {
-                            let range = mention.range.as_ref()?;
-                            if !body.is_char_boundary(range.start as usize)
-                                || !body.is_char_boundary(range.end as usize)
-                            {
-                                return None;
-                            }
-                            Some(channel_message_mention::ActiveModel {
-                                message_id: ActiveValue::Set(message_id),
-                                start_offset: ActiveValue::Set(range.start as i32),
-                                end_offset: ActiveValue::Set(range.end as i32),
-                                user_id: ActiveValue::Set(UserId::from_proto(mention.user_id)),
-                            })
-                        })
-                        .collect::<Vec<_>>();
-                    if !mentions.is_empty() {
-                        channel_message_mention::Entity::insert_many(mentions)
-                            .exec(&*tx)
-                            .await?;
-                    }
+                    self.create_channel_message_mentions(message_id, body, mentions, &tx)
+                        .await?;
 
                     for mentioned_user in mentioned_user_ids {
                         notifications.extend(
@@ -475,26 +528,10 @@ impl Database {
         user_id: UserId,
     ) -> Result<Vec<ConnectionId>> {
         self.transaction(|tx| async move {
-            let mut rows = channel_chat_participant::Entity::find()
-                .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
-                .stream(&*tx)
+            let participant_connection_ids = self
+                .channel_chat_participant_connection_ids(channel_id, user_id, &tx)
                 .await?;
 
-            let mut is_participant = false;
-            let mut participant_connection_ids = Vec::new();
-            while let Some(row) = rows.next().await {
-                let row = row?;
-                if row.user_id == user_id {
-                    is_participant = true;
-                }
-                participant_connection_ids.push(row.connection());
-            }
-            drop(rows);
-
-            if !is_participant {
-                Err(anyhow!("not a chat participant"))?;
-            }
-
             let result = channel_message::Entity::delete_by_id(message_id)
                 .filter(channel_message::Column::SenderId.eq(user_id))
                 .exec(&*tx)
@@ -522,4 +559,255 @@ impl Database {
         })
         .await
     }
+
+    /// Replaces the body and mentions of a channel message, keeping its previous body in the
+    /// message's edit history. Only the message's sender can edit it.
+    pub async fn update_channel_message(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        body: &str,
+        mentions: &[proto::ChatMention],
+        timestamp: OffsetDateTime,
+    ) -> Result<UpdatedChannelMessage> {
+        self.transaction(|tx| async move {
+            let participant_connection_ids = self
+                .channel_chat_participant_connection_ids(channel_id, user_id, &tx)
+                .await?;
+            let message = self
+                .get_channel_message_internal(channel_id, message_id, &tx)
+                .await?;
+            if message.sender_id != user_id {
+                Err(anyhow!("only the sender of a message can edit it"))?;
+            }
+
+            let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+            let timestamp = time::PrimitiveDateTime::new(timestamp.date(), timestamp.time());
+
+            channel_message_edit::ActiveModel {
+                id: ActiveValue::NotSet,
+                message_id: ActiveValue::Set(message_id),
+                body: ActiveValue::Set(message.body.clone()),
+                edited_at: ActiveValue::Set(timestamp),
+            }
+            .insert(&*tx)
+            .await?;
+
+            let mut model = message.into_active_model();
+            model.body = ActiveValue::Set(body.to_string());
+            model.edited_at = ActiveValue::Set(Some(timestamp));
+            let message = model.update(&*tx).await?;
+
+            // Only users who weren't already mentioned are notified about the edit.
+            let previously_mentioned_user_ids = channel_message_mention::Entity::find()
+                .filter(channel_message_mention::Column::MessageId.eq(message_id))
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(|mention| mention.user_id)
+                .collect::<HashSet<_>>();
+            channel_message_mention::Entity::delete_many()
+                .filter(channel_message_mention::Column::MessageId.eq(message_id))
+                .exec(&*tx)
+                .await?;
+            self.create_channel_message_mentions(message_id, body, mentions, &tx)
+                .await?;
+
+            let newly_mentioned_user_ids = mentions
+                .iter()
+                .map(|mention| UserId::from_proto(mention.user_id))
+                .filter(|user_id| !previously_mentioned_user_ids.contains(user_id))
+                .collect::<HashSet<_>>();
+            let mut notifications = Vec::new();
+            for mentioned_user in newly_mentioned_user_ids {
+                notifications.extend(
+                    self.create_notification(
+                        mentioned_user,
+                        rpc::Notification::ChannelMessageMention {
+                            message_id: message_id.to_proto(),
+                            sender_id: user_id.to_proto(),
+                            channel_id: channel_id.to_proto(),
+                        },
+                        false,
+                        &tx,
+                    )
+                    .await?,
+                );
+            }
+
+            let message = self
+                .load_channel_messages(vec![message], &tx)
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("no such message"))?;
+            Ok(UpdatedChannelMessage {
+                message,
+                participant_connection_ids,
+                notifications,
+            })
+        })
+        .await
+    }
+
+    /// Returns the previous bodies of a channel message, oldest first.
+    pub async fn get_channel_message_edits(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::ChannelMessageEdit>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+            self.get_channel_message_internal(channel_id, message_id, &tx)
+                .await?;
+
+            let edits = channel_message_edit::Entity::find()
+                .filter(channel_message_edit::Column::MessageId.eq(message_id))
+                .order_by_asc(channel_message_edit::Column::Id)
+                .all(&*tx)
+                .await?;
+            Ok(edits
+                .into_iter()
+                .map(|edit| proto::ChannelMessageEdit {
+                    body: edit.body,
+                    edited_at: edit.edited_at.assume_utc().unix_timestamp() as u64,
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Adds the user's reaction to a channel message. Adding a reaction twice has no effect.
+    pub async fn add_channel_message_reaction(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        emoji: &str,
+    ) -> Result<UpdatedChannelMessage> {
+        self.transaction(|tx| async move {
+            let participant_connection_ids = self
+                .channel_chat_participant_connection_ids(channel_id, user_id, &tx)
+                .await?;
+            let message = self
+                .get_channel_message_internal(channel_id, message_id, &tx)
+                .await?;
+
+            channel_message_reaction::Entity::insert(channel_message_reaction::ActiveModel {
+                message_id: ActiveValue::Set(message_id),
+                user_id: ActiveValue::Set(user_id),
+                emoji: ActiveValue::Set(emoji.to_string()),
+            })
+            .on_conflict(
+                OnConflict::columns([
+                    channel_message_reaction::Column::MessageId,
+                    channel_message_reaction::Column::UserId,
+                    channel_message_reaction::Column::Emoji,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&*tx)
+            .await?;
+
+            let message = self
+                .load_channel_messages(vec![message], &tx)
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("no such message"))?;
+            Ok(UpdatedChannelMessage {
+                message,
+                participant_connection_ids,
+                notifications: Vec::new(),
+            })
+        })
+        .await
+    }
+
+    /// Removes the user's reaction from a channel message.
+    pub async fn remove_channel_message_reaction(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        emoji: &str,
+    ) -> Result<UpdatedChannelMessage> {
+        self.transaction(|tx| async move {
+            let participant_connection_ids = self
+                .channel_chat_participant_connection_ids(channel_id, user_id, &tx)
+                .await?;
+            let message = self
+                .get_channel_message_internal(channel_id, message_id, &tx)
+                .await?;
+
+            channel_message_reaction::Entity::delete_many()
+                .filter(
+                    Condition::all()
+                        .add(channel_message_reaction::Column::MessageId.eq(message_id))
+                        .add(channel_message_reaction::Column::UserId.eq(user_id))
+                        .add(channel_message_reaction::Column::Emoji.eq(emoji)),
+                )
+                .exec(&*tx)
+                .await?;
+
+            let message = self
+                .load_channel_messages(vec![message], &tx)
+                .await?
+                .pop()
+                .ok_or_else(|| anyhow!("no such message"))?;
+            Ok(UpdatedChannelMessage {
+                message,
+                participant_connection_ids,
+                notifications: Vec::new(),
+            })
+        })
+        .await
+    }
+
+    async fn get_channel_message_internal(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        tx: &DatabaseTransaction,
+    ) -> Result<channel_message::Model> {
+        let message = channel_message::Entity::find_by_id(message_id)
+            .filter(channel_message::Column::ChannelId.eq(channel_id))
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such message"))?;
+        Ok(message)
+    }
+
+    /// Returns the connections of everyone in the channel's chat, failing if the given user isn't
+    /// in it.
+    async fn channel_chat_participant_connection_ids(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<ConnectionId>> {
+        let mut rows = channel_chat_participant::Entity::find()
+            .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
+            .stream(tx)
+            .await?;
+
+        let mut is_participant = false;
+        let mut participant_connection_ids = Vec::new();
+        while let Some(row) = rows.next().await {
+            let row = row?;
+            if row.user_id == user_id {
+                is_participant = true;
+            }
+            participant_connection_ids.push(row.connection());
+        }
+        drop(rows);
+
+        if !is_participant {
+            Err(anyhow!("not a chat participant"))?;
+        }
+        Ok(participant_connection_ids)
+    }
 }
@@ -28,7 +28,7 @@ impl Database {
         room_id: RoomId,
         connection: ConnectionId,
         worktrees: &[proto::WorktreeMetadata],
-    ) -> Result<RoomGuard<(ProjectId, proto::Room)>> {
+    ) -> Result<RoomGuard<SharedProject>> {
         self.room_transaction(room_id, |tx| async move {
             let participant = room_participant::Entity::find()
                 .filter(
@@ -84,6 +84,7 @@ impl Database {
                 .exec(&*tx)
                 .await?;
             }
+            self.update_project_comment_threads(project.id, &tx).await?;
 
             project_collaborator::ActiveModel {
                 project_id: ActiveValue::set(project.id),
@@ -97,8 +98,32 @@ impl Database {
             .insert(&*tx)
             .await?;
 
+            let channel_id = room::Entity::find_by_id(room_id)
+                .one(&*tx)
+                .await?
+                .and_then(|room| room.channel_id);
+            let channel_activity = if let Some(channel_id) = channel_id {
+                let channel = self.get_channel_internal(channel_id, &tx).await?;
+                let activity = self
+                    .record_channel_event(
+                        channel_id,
+                        participant.user_id,
+                        ChannelEventKind::SharedProject,
+                        Some(project.id),
+                        &tx,
+                    )
+                    .await?;
+                Some((channel, activity))
+            } else {
+                None
+            };
+
             let room = self.get_room(room_id, &tx).await?;
-            Ok((project.id, room))
+            Ok(SharedProject {
+                project_id: project.id,
+                room,
+                channel_activity,
+            })
         })
         .await
     }
@@ -196,6 +221,7 @@ impl Database {
             ))
             .exec(tx)
             .await?;
+        self.update_project_comment_threads(project_id, tx).await?;
 
         Ok(())
     }
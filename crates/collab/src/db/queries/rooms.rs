@@ -344,6 +344,7 @@ impl Database {
             Ok(JoinRoom {
                 room,
                 channel: None,
+                activity: None,
             })
         })
         .await
@@ -439,9 +440,13 @@ impl Database {
 
         let (channel, room) = self.get_channel_room(room_id, &tx).await?;
         let channel = channel.ok_or_else(|| anyhow!("no channel for room"))?;
+        let activity = self
+            .record_channel_event(channel.id, user_id, ChannelEventKind::JoinedCall, None, tx)
+            .await?;
         Ok(JoinRoom {
             room,
             channel: Some(channel),
+            activity: Some(activity),
         })
     }
 
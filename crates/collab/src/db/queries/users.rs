@@ -40,6 +40,30 @@ impl Database {
         .await
     }
 
+    /// Creates a user that isn't linked to a GitHub account, for servers running in standalone mode.
+    pub async fn create_local_user(&self, github_login: &str, admin: bool) -> Result<User> {
+        self.transaction(|tx| async move {
+            if user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(github_login))
+                .one(&*tx)
+                .await?
+                .is_some()
+            {
+                Err(anyhow!("user {} already exists", github_login))?;
+            }
+
+            Ok(user::Entity::insert(user::ActiveModel {
+                github_login: ActiveValue::set(github_login.into()),
+                admin: ActiveValue::set(admin),
+                metrics_id: ActiveValue::set(Uuid::new_v4()),
+                ..Default::default()
+            })
+            .exec_with_returning(&*tx)
+            .await?)
+        })
+        .await
+    }
+
     /// Returns a user by ID. There are no access checks here, so this should only be used internally.
     pub async fn get_user_by_id(&self, id: UserId) -> Result<Option<user::Model>> {
         self.transaction(|tx| async move { Ok(user::Entity::find_by_id(id).one(&*tx).await?) })
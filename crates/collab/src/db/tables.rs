@@ -1,13 +1,20 @@
 pub mod access_token;
 pub mod buffer;
+pub mod buffer_edit_author;
 pub mod buffer_operation;
+pub mod buffer_replica;
 pub mod buffer_snapshot;
 pub mod channel;
 pub mod channel_buffer_collaborator;
 pub mod channel_chat_participant;
+pub mod channel_event;
 pub mod channel_member;
 pub mod channel_message;
+pub mod channel_message_edit;
 pub mod channel_message_mention;
+pub mod channel_message_reaction;
+pub mod comment;
+pub mod comment_thread;
 pub mod contact;
 pub mod contributor;
 pub mod extension;
@@ -19,6 +26,7 @@ pub mod language_server;
 pub mod notification;
 pub mod notification_kind;
 pub mod observed_buffer_edits;
+pub mod observed_channel_activity;
 pub mod observed_channel_messages;
 pub mod project;
 pub mod project_collaborator;
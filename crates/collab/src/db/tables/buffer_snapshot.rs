@@ -1,5 +1,6 @@
 use crate::db::BufferId;
 use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
 #[sea_orm(table_name = "buffer_snapshots")]
@@ -10,6 +11,7 @@ pub struct Model {
     pub epoch: i32,
     pub text: String,
     pub operation_serialization_version: i32,
+    pub created_at: PrimitiveDateTime,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
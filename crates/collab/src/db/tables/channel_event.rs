@@ -0,0 +1,47 @@
+use crate::db::{ChannelEventId, ChannelEventKind, ChannelId, ProjectId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+/// Something that happened in a channel, shown in its members' activity feeds.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "channel_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: ChannelEventId,
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
+    pub kind: ChannelEventKind,
+    /// For shared projects, the project that was shared. It may since have been unshared.
+    pub project_id: Option<ProjectId>,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::channel::Entity",
+        from = "Column::ChannelId",
+        to = "super::channel::Column::Id"
+    )]
+    Channel,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Channel.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
@@ -0,0 +1,33 @@
+use crate::db::{MessageEditId, MessageId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+/// A previous body of an edited channel message.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "channel_message_edits")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: MessageEditId,
+    pub message_id: MessageId,
+    pub body: String,
+    /// When this body was replaced by a newer one.
+    pub edited_at: PrimitiveDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::channel_message::Entity",
+        from = "Column::MessageId",
+        to = "super::channel_message::Column::Id"
+    )]
+    Message,
+}
+
+impl Related<super::channel_message::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Message.def()
+    }
+}
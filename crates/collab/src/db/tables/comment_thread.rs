@@ -0,0 +1,69 @@
+use crate::db::{ChannelId, CommentThreadId, ProjectId, UserId};
+use sea_orm::entity::prelude::*;
+use time::PrimitiveDateTime;
+
+/// A discussion about a range of a channel's notes or of a file in a shared project.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "comment_threads")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: CommentThreadId,
+    pub channel_id: Option<ChannelId>,
+    /// The project in which the commented file is currently shared, if any. This is cleared
+    /// when the project is unshared, and restored when the host shares the worktree again.
+    pub project_id: Option<ProjectId>,
+    /// For project threads, the host of the project, which outlives any one share of it.
+    pub host_user_id: Option<UserId>,
+    /// For project threads, the absolute path of the worktree on the host's machine.
+    pub worktree_abs_path: Option<String>,
+    pub worktree_id: Option<i64>,
+    pub path: Option<String>,
+    /// A protobuf-encoded `proto::Anchor`.
+    pub start_anchor: Vec<u8>,
+    /// A protobuf-encoded `proto::Anchor`.
+    pub end_anchor: Vec<u8>,
+    /// The row of the start anchor when it was last resolved, used when the anchor can't be.
+    pub row: i32,
+    /// For channel notes, the epoch of the buffer in which the anchors were created.
+    pub buffer_epoch: Option<i32>,
+    pub resolved: bool,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::channel::Entity",
+        from = "Column::ChannelId",
+        to = "super::channel::Column::Id"
+    )]
+    Channel,
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id"
+    )]
+    Project,
+    #[sea_orm(has_many = "super::comment::Entity")]
+    Comments,
+}
+
+impl Related<super::channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Channel.def()
+    }
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl Related<super::comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comments.def()
+    }
+}
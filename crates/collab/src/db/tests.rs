@@ -1,5 +1,7 @@
 mod buffer_tests;
+mod channel_activity_tests;
 mod channel_tests;
+mod comment_tests;
 mod contributor_tests;
 mod db_tests;
 mod extension_tests;
@@ -164,6 +164,34 @@ async fn test_channel_buffers(db: &Arc<Database>) {
         .unwrap();
     assert_eq!(buffer_response_b.base_text, "hello, cruel world");
     assert_eq!(buffer_response_b.operations, &[]);
+
+    // The snapshot is listed as a checkpoint, attributed to the users who edited
+    // the buffer before it was taken.
+    let checkpoints = db
+        .get_channel_buffer_checkpoints(zed_id, b_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        checkpoints
+            .iter()
+            .map(|checkpoint| (checkpoint.epoch, checkpoint.author_ids.clone()))
+            .collect::<Vec<_>>(),
+        &[(1, vec![a_id.to_proto()])]
+    );
+    assert_eq!(
+        db.load_channel_buffer_checkpoint(zed_id, 1, b_id)
+            .await
+            .unwrap(),
+        "hello, cruel world"
+    );
+    assert!(db
+        .get_channel_buffer_checkpoints(zed_id, c_id)
+        .await
+        .is_err());
+    assert!(db
+        .load_channel_buffer_checkpoint(zed_id, 1, c_id)
+        .await
+        .is_err());
 }
 
 test_both_dbs!(
@@ -295,6 +323,7 @@ async fn test_channel_buffers_last_operations(db: &Database) {
                 channel_id: buffers[0].channel_id.to_proto(),
                 epoch: 0,
                 version: serialize_version(&text_buffers[0].version()),
+                replica_id: None,
             },
             rpc::proto::ChannelBufferVersion {
                 channel_id: buffers[1].channel_id.to_proto(),
@@ -303,14 +332,189 @@ async fn test_channel_buffers_last_operations(db: &Database) {
                     .into_iter()
                     .filter(|vector| vector.replica_id == text_buffers[1].replica_id() as u32)
                     .collect::<Vec<_>>(),
+                replica_id: None,
             },
             rpc::proto::ChannelBufferVersion {
                 channel_id: buffers[2].channel_id.to_proto(),
                 epoch: 0,
                 version: serialize_version(&text_buffers[2].version()),
+                replica_id: None,
+            },
+        ]
+    );
+}
+
+test_both_dbs!(
+    test_rejoin_channel_buffer_after_connection_cleanup,
+    test_rejoin_channel_buffer_after_connection_cleanup_postgres,
+    test_rejoin_channel_buffer_after_connection_cleanup_sqlite
+);
+
+async fn test_rejoin_channel_buffer_after_connection_cleanup(db: &Arc<Database>) {
+    let mut user_ids = Vec::new();
+    for (i, name) in ["user_a", "user_b", "user_c"].into_iter().enumerate() {
+        let user_id = db
+            .create_user(
+                &format!("{name}@example.com"),
+                false,
+                NewUserParams {
+                    github_login: name.into(),
+                    github_user_id: 101 + i as i32,
+                },
+            )
+            .await
+            .unwrap()
+            .user_id;
+        user_ids.push(user_id);
+    }
+    let (a_id, b_id, c_id) = (user_ids[0], user_ids[1], user_ids[2]);
+
+    let owner_id = db.create_server("production").await.unwrap().0 as u32;
+    let zed_id = db.create_root_channel("zed", a_id).await.unwrap();
+    for user_id in [b_id, c_id] {
+        db.invite_channel_member(zed_id, user_id, a_id, ChannelRole::Member)
+            .await
+            .unwrap();
+        db.respond_to_channel_invite(zed_id, user_id, true)
+            .await
+            .unwrap();
+    }
+
+    let connection_id_a = ConnectionId { owner_id, id: 1 };
+    db.join_channel_buffer(zed_id, a_id, connection_id_a)
+        .await
+        .unwrap();
+    let mut buffer_a = Buffer::new(0, text::BufferId::new(1).unwrap(), "".to_string());
+    update_buffer(zed_id, a_id, db, vec![buffer_a.edit([(0..0, "hello")])]).await;
+
+    // C keeps the notes open, so that they aren't collapsed when A leaves.
+    let buffer_response_c = db
+        .join_channel_buffer(zed_id, c_id, ConnectionId { owner_id, id: 2 })
+        .await
+        .unwrap();
+    assert_eq!(buffer_response_c.replica_id, 1);
+
+    // A's connection is cleaned up, while A keeps editing offline.
+    let client_buffer = rpc::proto::ChannelBufferVersion {
+        channel_id: zed_id.to_proto(),
+        epoch: 0,
+        version: serialize_version(&buffer_a.version()),
+        replica_id: Some(buffer_a.replica_id() as u32),
+    };
+    db.leave_channel_buffers(connection_id_a).await.unwrap();
+    let offline_operation = buffer_a.edit([(5..5, " world")]);
+
+    // B can't take over A's replica id, as it was never assigned to B.
+    let rejoined = db
+        .rejoin_channel_buffers(
+            &[client_buffer.clone()],
+            b_id,
+            ConnectionId { owner_id, id: 3 },
+        )
+        .await
+        .unwrap();
+    assert!(rejoined.is_empty());
+
+    // A can rejoin with its old replica id, because nobody else has taken it.
+    let connection_id_a2 = ConnectionId { owner_id, id: 4 };
+    let rejoined = db
+        .rejoin_channel_buffers(&[client_buffer.clone()], a_id, connection_id_a2)
+        .await
+        .unwrap();
+    assert_eq!(rejoined.len(), 1);
+    assert_eq!(rejoined[0].old_connection_id, None);
+    assert_eq!(rejoined[0].buffer.operations, &[]);
+    assert_eq!(
+        rejoined[0].buffer.collaborators,
+        &[
+            rpc::proto::Collaborator {
+                user_id: c_id.to_proto(),
+                peer_id: Some(rpc::proto::PeerId { id: 2, owner_id }),
+                replica_id: 1,
             },
+            rpc::proto::Collaborator {
+                user_id: a_id.to_proto(),
+                peer_id: Some(rpc::proto::PeerId { id: 4, owner_id }),
+                replica_id: 0,
+            }
         ]
     );
+    update_buffer(zed_id, a_id, db, vec![offline_operation]).await;
+
+    // Once another user has been assigned the same replica id, A can no longer
+    // rejoin with it.
+    db.leave_channel_buffers(connection_id_a2).await.unwrap();
+    let buffer_response_b = db
+        .join_channel_buffer(zed_id, b_id, ConnectionId { owner_id, id: 5 })
+        .await
+        .unwrap();
+    assert_eq!(buffer_response_b.replica_id, 0);
+    db.leave_channel_buffers(ConnectionId { owner_id, id: 5 })
+        .await
+        .unwrap();
+    let rejoined = db
+        .rejoin_channel_buffers(&[client_buffer], a_id, ConnectionId { owner_id, id: 6 })
+        .await
+        .unwrap();
+    assert!(rejoined.is_empty());
+}
+
+test_both_dbs!(
+    test_clear_stale_channel_buffer_collaborators,
+    test_clear_stale_channel_buffer_collaborators_postgres,
+    test_clear_stale_channel_buffer_collaborators_sqlite
+);
+
+async fn test_clear_stale_channel_buffer_collaborators(db: &Arc<Database>) {
+    let a_id = db
+        .create_user(
+            "user_a@example.com",
+            false,
+            NewUserParams {
+                github_login: "user_a".into(),
+                github_user_id: 101,
+            },
+        )
+        .await
+        .unwrap()
+        .user_id;
+
+    let old_server_id = db.create_server("production").await.unwrap();
+    let new_server_id = db.create_server("production").await.unwrap();
+    let zed_id = db.create_root_channel("zed", a_id).await.unwrap();
+
+    let connection_id_a = ConnectionId {
+        owner_id: old_server_id.0 as u32,
+        id: 1,
+    };
+    db.join_channel_buffer(zed_id, a_id, connection_id_a)
+        .await
+        .unwrap();
+    let mut buffer_a = Buffer::new(0, text::BufferId::new(1).unwrap(), "".to_string());
+    update_buffer(zed_id, a_id, db, vec![buffer_a.edit([(0..0, "hello")])]).await;
+
+    // Once the old server's collaborators have been cleared, nobody is editing
+    // the notes anymore, so their operations are collapsed into a new epoch.
+    let refreshed = db
+        .clear_stale_channel_buffer_collaborators(zed_id, new_server_id)
+        .await
+        .unwrap();
+    assert!(refreshed.collaborators.is_empty());
+
+    let buffer_response_a = db
+        .join_channel_buffer(
+            zed_id,
+            a_id,
+            ConnectionId {
+                owner_id: new_server_id.0 as u32,
+                id: 1,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(buffer_response_a.epoch, 1);
+    assert_eq!(buffer_response_a.base_text, "hello");
+    assert_eq!(buffer_response_a.operations, &[]);
 }
 
 async fn update_buffer(
@@ -0,0 +1,254 @@
+use super::new_test_user;
+use crate::{
+    db::{channel_event, BufferId, ChannelEventKind, ChannelRole, Database, UserId},
+    test_both_dbs,
+};
+use channel::mentions_to_proto;
+use language::proto::{serialize_operation, serialize_version};
+use rpc::{proto, ConnectionId};
+use sea_orm::{ActiveModelTrait, ActiveValue};
+use std::sync::Arc;
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+
+test_both_dbs!(
+    test_channel_activity,
+    test_channel_activity_postgres,
+    test_channel_activity_sqlite
+);
+
+async fn test_channel_activity(db: &Arc<Database>) {
+    let a_id = new_test_user(db, "user_a@example.com").await;
+    let b_id = new_test_user(db, "user_b@example.com").await;
+    let channel_id = db.create_root_channel("zed", a_id).await.unwrap();
+    db.invite_channel_member(channel_id, b_id, a_id, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel_id, b_id, true)
+        .await
+        .unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    let connection_a = ConnectionId { owner_id, id: 1 };
+
+    // User A joins the channel's call, which only shows up in user B's feed.
+    let (joined_room, _, _) = db
+        .join_channel(channel_id, a_id, connection_a)
+        .await
+        .unwrap();
+    assert_eq!(
+        joined_room.activity.map(|activity| activity.kind()),
+        Some(proto::ChannelActivityKind::JoinedCall)
+    );
+    assert_eq!(activity(db, a_id).await, &[]);
+    assert_eq!(
+        activity(db, b_id).await,
+        &[(proto::ChannelActivityKind::JoinedCall, a_id.to_proto())]
+    );
+
+    // User A edits the channel notes.
+    let response = db
+        .join_channel_buffer(channel_id, a_id, connection_a)
+        .await
+        .unwrap();
+    let mut buffer = text::Buffer::new(
+        0,
+        text::BufferId::new(response.buffer_id).unwrap(),
+        response.base_text,
+    );
+    let operation = buffer.edit([(0..0, "hello")]);
+    db.update_channel_buffer(
+        channel_id,
+        a_id,
+        &[serialize_operation(&language::Operation::Buffer(operation))],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        activity(db, b_id).await,
+        &[
+            (proto::ChannelActivityKind::JoinedCall, a_id.to_proto()),
+            (proto::ChannelActivityKind::EditedNotes, a_id.to_proto()),
+        ]
+    );
+
+    // Once user B has observed those edits, they're no longer listed.
+    db.observe_buffer_version(
+        BufferId::from_proto(response.buffer_id),
+        b_id,
+        response.epoch as i32,
+        &serialize_version(&buffer.version()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        activity(db, b_id).await,
+        &[(proto::ChannelActivityKind::JoinedCall, a_id.to_proto())]
+    );
+
+    // User A mentions user B in the channel's chat.
+    db.join_channel_chat(channel_id, connection_a, a_id)
+        .await
+        .unwrap();
+    db.create_channel_message(
+        channel_id,
+        a_id,
+        "hi @user_b",
+        &mentions_to_proto(&[(3..10, b_id.to_proto())]),
+        OffsetDateTime::now_utc(),
+        1,
+        None,
+    )
+    .await
+    .unwrap();
+    let response = db.get_channel_activity(b_id, None).await.unwrap();
+    let mention = response
+        .activities
+        .iter()
+        .find(|activity| activity.kind() == proto::ChannelActivityKind::Mention)
+        .unwrap();
+    assert_eq!(mention.user_id, a_id.to_proto());
+    assert_eq!(mention.body.as_deref(), Some("hi @user_b"));
+    assert!(response.done);
+    assert_eq!(response.observed_at, None);
+
+    // User B's feed remembers how far they've read.
+    let now = OffsetDateTime::now_utc();
+    let now = PrimitiveDateTime::new(now.date(), now.time());
+    db.observe_channel_activity(b_id, now).await.unwrap();
+    let response = db.get_channel_activity(b_id, None).await.unwrap();
+    assert_eq!(
+        response.observed_at,
+        Some((now.assume_utc().unix_timestamp_nanos() / 1000) as u64)
+    );
+
+    // Observing an earlier time doesn't move it back.
+    db.observe_channel_activity(b_id, now - time::Duration::hours(1))
+        .await
+        .unwrap();
+    let response = db.get_channel_activity(b_id, None).await.unwrap();
+    assert_eq!(
+        response.observed_at,
+        Some((now.assume_utc().unix_timestamp_nanos() / 1000) as u64)
+    );
+}
+
+test_both_dbs!(
+    test_channel_activity_paging,
+    test_channel_activity_paging_postgres,
+    test_channel_activity_paging_sqlite
+);
+
+async fn test_channel_activity_paging(db: &Arc<Database>) {
+    let a_id = new_test_user(db, "user_a@example.com").await;
+    let b_id = new_test_user(db, "user_b@example.com").await;
+    let channel_id = db.create_root_channel("zed", a_id).await.unwrap();
+    db.invite_channel_member(channel_id, b_id, a_id, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel_id, b_id, true)
+        .await
+        .unwrap();
+
+    // More than a page of events, most of which happen within the same second.
+    db.transaction(|tx| async move {
+        for _ in 0..60 {
+            db.record_channel_event(channel_id, a_id, ChannelEventKind::JoinedCall, None, &tx)
+                .await?;
+        }
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let first_page = db.get_channel_activity(b_id, None).await.unwrap();
+    assert_eq!(first_page.activities.len(), 50);
+    assert!(!first_page.done);
+
+    let before = first_page.activities.last().unwrap().timestamp;
+    let before = OffsetDateTime::from_unix_timestamp_nanos(before as i128 * 1000).unwrap();
+    let second_page = db
+        .get_channel_activity(
+            b_id,
+            Some(PrimitiveDateTime::new(before.date(), before.time())),
+        )
+        .await
+        .unwrap();
+    assert_eq!(second_page.activities.len(), 10);
+    assert!(second_page.done);
+}
+
+test_both_dbs!(
+    test_channel_activity_retention,
+    test_channel_activity_retention_postgres,
+    test_channel_activity_retention_sqlite
+);
+
+async fn test_channel_activity_retention(db: &Arc<Database>) {
+    let a_id = new_test_user(db, "user_a@example.com").await;
+    let b_id = new_test_user(db, "user_b@example.com").await;
+    let channel_id = db.create_root_channel("zed", a_id).await.unwrap();
+    db.invite_channel_member(channel_id, b_id, a_id, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel_id, b_id, true)
+        .await
+        .unwrap();
+
+    let now = OffsetDateTime::now_utc();
+    let created_at = |days_ago| {
+        let timestamp = now - Duration::days(days_ago);
+        PrimitiveDateTime::new(timestamp.date(), timestamp.time())
+    };
+    db.transaction(|tx| async move {
+        for days_ago in [45, 10] {
+            channel_event::ActiveModel {
+                id: ActiveValue::NotSet,
+                channel_id: ActiveValue::Set(channel_id),
+                user_id: ActiveValue::Set(a_id),
+                kind: ActiveValue::Set(ChannelEventKind::JoinedCall),
+                project_id: ActiveValue::Set(None),
+                created_at: ActiveValue::Set(created_at(days_ago)),
+            }
+            .insert(&*tx)
+            .await?;
+        }
+        Ok(())
+    })
+    .await
+    .unwrap();
+    assert_eq!(activity(db, b_id).await.len(), 2);
+
+    // Recording a new event prunes the ones that have expired.
+    db.transaction(|tx| async move {
+        db.record_channel_event(channel_id, a_id, ChannelEventKind::JoinedCall, None, &tx)
+            .await
+    })
+    .await
+    .unwrap();
+    let timestamps = db
+        .get_channel_activity(b_id, None)
+        .await
+        .unwrap()
+        .activities
+        .into_iter()
+        .map(|activity| activity.timestamp)
+        .collect::<Vec<_>>();
+    assert_eq!(timestamps.len(), 2);
+    let cutoff = (now - Duration::days(30)).unix_timestamp_nanos() / 1000;
+    assert!(timestamps
+        .iter()
+        .all(|timestamp| *timestamp as i128 > cutoff));
+}
+
+async fn activity(db: &Database, user_id: UserId) -> Vec<(proto::ChannelActivityKind, u64)> {
+    let mut activity = db
+        .get_channel_activity(user_id, None)
+        .await
+        .unwrap()
+        .activities
+        .into_iter()
+        .map(|activity| (activity.kind(), activity.user_id))
+        .collect::<Vec<_>>();
+    activity.sort_by_key(|(kind, _)| *kind as i32);
+    activity
+}
@@ -0,0 +1,292 @@
+use super::new_test_user;
+use crate::{
+    db::{ChannelRole, CommentThreadId, Database, RoomId},
+    test_both_dbs,
+};
+use language::proto::{deserialize_anchor, serialize_anchor, serialize_operation};
+use rpc::{proto, ConnectionId, Notification};
+use std::sync::Arc;
+use text::{Buffer, BufferId, Point, ToOffset as _, ToPoint as _};
+
+test_both_dbs!(
+    test_channel_comment_threads,
+    test_channel_comment_threads_postgres,
+    test_channel_comment_threads_sqlite
+);
+
+async fn test_channel_comment_threads(db: &Arc<Database>) {
+    let a_id = new_test_user(db, "user_a@example.com").await;
+    let b_id = new_test_user(db, "user_b@example.com").await;
+    let c_id = new_test_user(db, "user_c@example.com").await;
+    let channel_id = db.create_root_channel("zed", a_id).await.unwrap();
+    db.invite_channel_member(channel_id, b_id, a_id, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel_id, b_id, true)
+        .await
+        .unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    let connection_a = ConnectionId { owner_id, id: 1 };
+    let connection_b = ConnectionId { owner_id, id: 2 };
+    let connection_c = ConnectionId { owner_id, id: 3 };
+
+    let response = db
+        .join_channel_buffer(channel_id, a_id, connection_a)
+        .await
+        .unwrap();
+    let mut buffer = Buffer::new(
+        0,
+        BufferId::new(response.buffer_id).unwrap(),
+        response.base_text,
+    );
+    let operation = buffer.edit([(0..0, "one\ntwo\nthree\n")]);
+    db.update_channel_buffer(
+        channel_id,
+        a_id,
+        &[serialize_operation(&language::Operation::Buffer(operation))],
+    )
+    .await
+    .unwrap();
+
+    // Comment on the word "two".
+    let start = buffer.anchor_before(Point::new(1, 0));
+    let end = buffer.anchor_after(Point::new(1, 3));
+    let created = db
+        .create_comment_thread(
+            proto::CommentTarget {
+                variant: Some(proto::comment_target::Variant::ChannelId(
+                    channel_id.to_proto(),
+                )),
+            },
+            serialize_anchor(&start),
+            serialize_anchor(&end),
+            1,
+            Some(response.epoch),
+            "Should this be a number?",
+            a_id,
+            connection_a,
+        )
+        .await
+        .unwrap();
+    assert_eq!(created.connection_ids, &[connection_a]);
+    assert!(created.notifications.is_empty());
+    let thread_id = CommentThreadId::from_proto(created.thread.id);
+
+    // Users outside of the channel can't see or reply to the thread.
+    assert!(db
+        .get_comment_threads(Some(channel_id), None, c_id, connection_c)
+        .await
+        .is_err());
+    assert!(db
+        .add_comment(thread_id, "Hi", c_id, connection_c)
+        .await
+        .is_err());
+
+    // Replies notify the other commenters.
+    let reply = db
+        .add_comment(thread_id, "Yes, I think so.", b_id, connection_b)
+        .await
+        .unwrap();
+    assert_eq!(
+        reply
+            .thread
+            .comments
+            .iter()
+            .map(|comment| comment.body.as_str())
+            .collect::<Vec<_>>(),
+        &["Should this be a number?", "Yes, I think so."]
+    );
+    assert_eq!(reply.notifications.len(), 1);
+    assert_eq!(reply.notifications[0].0, a_id);
+    assert_eq!(
+        Notification::from_proto(&reply.notifications[0].1),
+        Some(Notification::CodeComment {
+            comment_id: reply.thread.comments[1].id,
+            thread_id: thread_id.to_proto(),
+            sender_id: b_id.to_proto(),
+            channel_id: Some(channel_id.to_proto()),
+            path: None,
+        })
+    );
+    let reply = db
+        .add_comment(thread_id, "Done.", a_id, connection_a)
+        .await
+        .unwrap();
+    assert_eq!(
+        reply
+            .notifications
+            .iter()
+            .map(|(user_id, _)| *user_id)
+            .collect::<Vec<_>>(),
+        &[b_id]
+    );
+
+    let resolved = db
+        .set_comment_thread_resolved(thread_id, true, b_id, connection_b)
+        .await
+        .unwrap();
+    assert!(resolved.thread.resolved);
+
+    // Insert a line above the comment, then close the notes so that they are
+    // collapsed into a new epoch.
+    let operation = buffer.edit([(0..0, "zero\n")]);
+    db.update_channel_buffer(
+        channel_id,
+        a_id,
+        &[serialize_operation(&language::Operation::Buffer(operation))],
+    )
+    .await
+    .unwrap();
+    db.leave_channel_buffer(channel_id, connection_a)
+        .await
+        .unwrap();
+
+    // The thread's anchors are moved into the new epoch.
+    let response = db
+        .join_channel_buffer(channel_id, b_id, connection_b)
+        .await
+        .unwrap();
+    assert_eq!(response.epoch, 1);
+    let buffer = Buffer::new(
+        0,
+        BufferId::new(response.buffer_id).unwrap(),
+        response.base_text,
+    );
+    let threads = db
+        .get_comment_threads(Some(channel_id), None, b_id, connection_b)
+        .await
+        .unwrap();
+    assert_eq!(threads.len(), 1);
+    let thread = &threads[0];
+    assert_eq!(thread.row, 2);
+    assert_eq!(thread.buffer_epoch, Some(1));
+    assert!(thread.resolved);
+    assert_eq!(thread.comments.len(), 3);
+
+    let start = deserialize_anchor(thread.start.clone().unwrap()).unwrap();
+    let end = deserialize_anchor(thread.end.clone().unwrap()).unwrap();
+    assert!(buffer.can_resolve(&start) && buffer.can_resolve(&end));
+    assert_eq!(start.to_point(&buffer), Point::new(2, 0));
+    assert_eq!(
+        buffer
+            .text_for_range(start.to_offset(&buffer)..end.to_offset(&buffer))
+            .collect::<String>(),
+        "two"
+    );
+}
+
+test_both_dbs!(
+    test_project_comment_threads_across_shares,
+    test_project_comment_threads_across_shares_postgres,
+    test_project_comment_threads_across_shares_sqlite
+);
+
+async fn test_project_comment_threads_across_shares(db: &Arc<Database>) {
+    let a_id = new_test_user(db, "user_a@example.com").await;
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    let connection_a = ConnectionId { owner_id, id: 1 };
+    let room_id = RoomId::from_proto(db.create_room(a_id, connection_a, "").await.unwrap().id);
+
+    let worktree = |id: u64, abs_path: &str| proto::WorktreeMetadata {
+        id,
+        root_name: "zed".into(),
+        visible: true,
+        abs_path: abs_path.into(),
+    };
+    let project_id = db
+        .share_project(room_id, connection_a, &[worktree(1, "/code/zed")])
+        .await
+        .unwrap()
+        .project_id;
+
+    let buffer = Buffer::new(0, BufferId::new(1).unwrap(), "one\ntwo\n".to_string());
+    let created = db
+        .create_comment_thread(
+            proto::CommentTarget {
+                variant: Some(proto::comment_target::Variant::ProjectPath(
+                    proto::comment_target::ProjectPath {
+                        project_id: project_id.to_proto(),
+                        worktree_id: 1,
+                        path: "src/main.rs".into(),
+                    },
+                )),
+            },
+            serialize_anchor(&buffer.anchor_before(Point::new(1, 0))),
+            serialize_anchor(&buffer.anchor_after(Point::new(1, 3))),
+            1,
+            None,
+            "Can this be removed?",
+            a_id,
+            connection_a,
+        )
+        .await
+        .unwrap();
+    assert_eq!(created.connection_ids, &[connection_a]);
+
+    // Unsharing the project keeps its threads around.
+    db.unshare_project(project_id, connection_a).await.unwrap();
+
+    // Sharing the same worktree again brings them back, under the new project and worktree ids.
+    let project_id = db
+        .share_project(
+            room_id,
+            connection_a,
+            &[worktree(2, "/code/other"), worktree(3, "/code/zed")],
+        )
+        .await
+        .unwrap()
+        .project_id;
+    let threads = db
+        .get_comment_threads(None, Some(project_id), a_id, connection_a)
+        .await
+        .unwrap();
+    assert_eq!(threads.len(), 1);
+    assert_eq!(threads[0].id, created.thread.id);
+    assert_eq!(threads[0].row, 1);
+    assert_eq!(
+        threads[0].target,
+        Some(proto::CommentTarget {
+            variant: Some(proto::comment_target::Variant::ProjectPath(
+                proto::comment_target::ProjectPath {
+                    project_id: project_id.to_proto(),
+                    worktree_id: 3,
+                    path: "src/main.rs".into(),
+                },
+            )),
+        })
+    );
+    assert_eq!(
+        threads[0]
+            .comments
+            .iter()
+            .map(|comment| comment.body.as_str())
+            .collect::<Vec<_>>(),
+        &["Can this be removed?"]
+    );
+
+    // Removing the worktree from the project detaches its threads.
+    db.update_project(project_id, connection_a, &[worktree(2, "/code/other")])
+        .await
+        .unwrap();
+    assert!(db
+        .get_comment_threads(None, Some(project_id), a_id, connection_a)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // The threads also outlive the room in which the project was shared.
+    db.leave_room(connection_a).await.unwrap();
+    let room_id = RoomId::from_proto(db.create_room(a_id, connection_a, "").await.unwrap().id);
+    let project_id = db
+        .share_project(room_id, connection_a, &[worktree(4, "/code/zed")])
+        .await
+        .unwrap()
+        .project_id;
+    let threads = db
+        .get_comment_threads(None, Some(project_id), a_id, connection_a)
+        .await
+        .unwrap();
+    assert_eq!(threads.len(), 1);
+    assert_eq!(threads[0].id, created.thread.id);
+}
@@ -118,6 +118,30 @@ async fn test_get_or_create_user_by_github_account(db: &Arc<Database>) {
     assert_eq!(user.email_address, Some("user3@example.com".into()));
 }
 
+test_both_dbs!(
+    test_create_local_user,
+    test_create_local_user_postgres,
+    test_create_local_user_sqlite
+);
+
+async fn test_create_local_user(db: &Arc<Database>) {
+    let user = db.create_local_user("local-admin", true).await.unwrap();
+    assert_eq!(&user.github_login, "local-admin");
+    assert_eq!(user.github_user_id, None);
+    assert_eq!(user.email_address, None);
+    assert!(user.admin);
+    assert_eq!(
+        db.get_user_by_github_login("local-admin")
+            .await
+            .unwrap()
+            .map(|user| user.id),
+        Some(user.id)
+    );
+
+    // Logins must be unique.
+    assert!(db.create_local_user("local-admin", false).await.is_err());
+}
+
 test_both_dbs!(
     test_create_access_tokens,
     test_create_access_tokens_postgres,
@@ -4,6 +4,7 @@ use crate::{
     test_both_dbs,
 };
 use channel::mentions_to_proto;
+use rpc::proto;
 use std::sync::Arc;
 use time::OffsetDateTime;
 
@@ -419,3 +420,154 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         ]
     );
 }
+
+test_both_dbs!(
+    test_channel_message_edits_and_reactions,
+    test_channel_message_edits_and_reactions_postgres,
+    test_channel_message_edits_and_reactions_sqlite
+);
+
+async fn test_channel_message_edits_and_reactions(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+
+    let channel = db
+        .create_channel("channel", None, user_a)
+        .await
+        .unwrap()
+        .0
+        .id;
+    db.invite_channel_member(channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, user_b, true)
+        .await
+        .unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 0 }, user_a)
+        .await
+        .unwrap();
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 1 }, user_b)
+        .await
+        .unwrap();
+
+    let message_id = db
+        .create_channel_message(
+            channel,
+            user_a,
+            "hi",
+            &[],
+            OffsetDateTime::now_utc(),
+            1,
+            None,
+        )
+        .await
+        .unwrap()
+        .message_id;
+
+    // Only the sender can edit a message.
+    db.update_channel_message(
+        channel,
+        message_id,
+        user_b,
+        "bye",
+        &[],
+        OffsetDateTime::now_utc(),
+    )
+    .await
+    .unwrap_err();
+
+    let updated = db
+        .update_channel_message(
+            channel,
+            message_id,
+            user_a,
+            "hi @user_b",
+            &mentions_to_proto(&[(3..10, user_b.to_proto())]),
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.message.body, "hi @user_b");
+    assert!(updated.message.edited_at.is_some());
+    assert_eq!(updated.participant_connection_ids.len(), 2);
+    assert_eq!(updated.notifications.len(), 1);
+
+    // Users who were already mentioned aren't notified again.
+    let updated = db
+        .update_channel_message(
+            channel,
+            message_id,
+            user_a,
+            "hello @user_b",
+            &mentions_to_proto(&[(6..13, user_b.to_proto())]),
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        updated.message.mentions,
+        mentions_to_proto(&[(6..13, user_b.to_proto())])
+    );
+    assert!(updated.notifications.is_empty());
+
+    let edits = db
+        .get_channel_message_edits(channel, message_id, user_b)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|edit| edit.body)
+        .collect::<Vec<_>>();
+    assert_eq!(edits, ["hi", "hi @user_b"]);
+
+    db.add_channel_message_reaction(channel, message_id, user_a, "👍")
+        .await
+        .unwrap();
+    db.add_channel_message_reaction(channel, message_id, user_b, "👍")
+        .await
+        .unwrap();
+    db.add_channel_message_reaction(channel, message_id, user_b, "🎉")
+        .await
+        .unwrap();
+    // Reacting twice with the same emoji has no effect.
+    let updated = db
+        .add_channel_message_reaction(channel, message_id, user_b, "🎉")
+        .await
+        .unwrap();
+    assert_eq!(
+        updated.message.reactions,
+        [
+            proto::ChannelMessageReaction {
+                emoji: "🎉".into(),
+                user_ids: vec![user_b.to_proto()],
+            },
+            proto::ChannelMessageReaction {
+                emoji: "👍".into(),
+                user_ids: vec![user_a.to_proto(), user_b.to_proto()],
+            },
+        ]
+    );
+
+    db.remove_channel_message_reaction(channel, message_id, user_a, "👍")
+        .await
+        .unwrap();
+    let messages = db
+        .get_channel_messages(channel, user_a, 5, None)
+        .await
+        .unwrap();
+    assert_eq!(messages[0].body, "hello @user_b");
+    assert_eq!(
+        messages[0].reactions,
+        [
+            proto::ChannelMessageReaction {
+                emoji: "🎉".into(),
+                user_ids: vec![user_b.to_proto()],
+            },
+            proto::ChannelMessageReaction {
+                emoji: "👍".into(),
+                user_ids: vec![user_b.to_proto()],
+            },
+        ]
+    );
+}
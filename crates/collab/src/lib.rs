@@ -129,12 +129,49 @@ pub struct Config {
     pub zed_client_checksum_seed: Option<String>,
     pub slack_panics_webhook: Option<String>,
     pub auto_join_channel_id: Option<ChannelId>,
+    /// Runs the server without any external services, for self-hosting. Calls have no audio or
+    /// screen sharing, and extensions, telemetry and crash reports are unavailable.
+    pub standalone: Option<bool>,
 }
 
 impl Config {
     pub fn is_development(&self) -> bool {
         self.zed_environment == "development".into()
     }
+
+    pub fn is_standalone(&self) -> bool {
+        self.standalone.unwrap_or(false)
+    }
+
+    #[cfg(test)]
+    pub fn test() -> Self {
+        Self {
+            http_port: 0,
+            database_url: "".into(),
+            database_max_connections: 0,
+            api_token: "".into(),
+            invite_link_prefix: "".into(),
+            live_kit_server: None,
+            live_kit_key: None,
+            live_kit_secret: None,
+            rust_log: None,
+            log_json: None,
+            zed_environment: "test".into(),
+            blob_store_url: None,
+            blob_store_region: None,
+            blob_store_access_key: None,
+            blob_store_secret_key: None,
+            blob_store_bucket: None,
+            clickhouse_url: None,
+            clickhouse_user: None,
+            clickhouse_password: None,
+            clickhouse_database: None,
+            zed_client_checksum_seed: None,
+            slack_panics_webhook: None,
+            auto_join_channel_id: None,
+            standalone: None,
+        }
+    }
 }
 
 #[derive(Default, Deserialize)]
@@ -153,10 +190,31 @@ pub struct AppState {
 
 impl AppState {
     pub async fn new(config: Config) -> Result<Arc<Self>> {
+        if config.is_standalone() && !config.database_url.starts_with("postgres") {
+            Err(anyhow!("standalone mode requires a Postgres database"))?;
+        }
+
         let mut db_options = db::ConnectOptions::new(config.database_url.clone());
         db_options.max_connections(config.database_max_connections);
         let mut db = Database::new(db_options, Executor::Production).await?;
         db.initialize_notification_kinds().await?;
+        Self::with_database(Arc::new(db), config).await
+    }
+
+    /// Builds the app state around a database that is already connected, along with clients
+    /// for the external services that are configured.
+    pub async fn with_database(db: Arc<Database>, config: Config) -> Result<Arc<Self>> {
+        if config.is_standalone() {
+            log::info!("running in standalone mode, external services are disabled");
+            let this = Self {
+                db,
+                live_kit_client: None,
+                blob_store_client: None,
+                clickhouse_client: None,
+                config,
+            };
+            return Ok(Arc::new(this));
+        }
 
         let live_kit_client = if let Some(((server, key), secret)) = config
             .live_kit_server
@@ -174,7 +232,7 @@ impl AppState {
         };
 
         let this = Self {
-            db: Arc::new(db),
+            db,
             live_kit_client,
             blob_store_client: build_blob_store_client(&config).await.log_err(),
             clickhouse_client: config
@@ -27,6 +27,8 @@ use util::ResultExt;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const REVISION: Option<&'static str> = option_env!("GITHUB_SHA");
+const USAGE: &str =
+    "usage: collab <version | migrate | serve [api|collab] | add-user LOGIN [--admin] | create-token LOGIN>";
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -52,9 +54,7 @@ async fn main() -> Result<()> {
                 (true, true)
             };
             if !is_api && !is_collab {
-                Err(anyhow!(
-                    "usage: collab <version | migrate | serve [api|collab]>"
-                ))?;
+                Err(anyhow!(USAGE))?;
             }
 
             let config = envy::from_env::<Config>().expect("error loading config");
@@ -150,10 +150,17 @@ async fn main() -> Result<()> {
             #[cfg(windows)]
             unimplemented!();
         }
+        Some("add-user") => {
+            let github_login = args.next().ok_or_else(|| anyhow!(USAGE))?;
+            let admin = args.next().as_deref() == Some("--admin");
+            add_user(&github_login, admin).await?;
+        }
+        Some("create-token") => {
+            let github_login = args.next().ok_or_else(|| anyhow!(USAGE))?;
+            create_token(&github_login).await?;
+        }
         _ => {
-            Err(anyhow!(
-                "usage: collab <version | migrate | serve [api|collab]>"
-            ))?;
+            Err(anyhow!(USAGE))?;
         }
     }
     Ok(())
@@ -182,6 +189,38 @@ async fn run_migrations() -> Result<()> {
     return Ok(());
 }
 
+/// Creates a user that signs in with an access token instead of a GitHub account, for servers
+/// running in standalone mode.
+async fn add_user(github_login: &str, admin: bool) -> Result<()> {
+    let db = connect_to_database().await?;
+    let user = db.create_local_user(github_login, admin).await?;
+    print_credentials(&db, user.id).await
+}
+
+/// Issues a new access token for an existing user.
+async fn create_token(github_login: &str) -> Result<()> {
+    let db = connect_to_database().await?;
+    let user = db
+        .get_user_by_github_login(github_login)
+        .await?
+        .ok_or_else(|| anyhow!("no such user {}", github_login))?;
+    print_credentials(&db, user.id).await
+}
+
+async fn connect_to_database() -> Result<Database> {
+    let config = envy::from_env::<MigrateConfig>().expect("error loading config");
+    let db_options = db::ConnectOptions::new(config.database_url);
+    Ok(Database::new(db_options, Executor::Production).await?)
+}
+
+/// Prints the environment variables with which the Zed client signs in as the given user.
+async fn print_credentials(db: &Database, user_id: db::UserId) -> Result<()> {
+    let access_token = collab::auth::create_access_token(db, user_id, None).await?;
+    println!("ZED_USER_ID={}", user_id);
+    println!("ZED_ACCESS_TOKEN='{}'", access_token);
+    Ok(())
+}
+
 async fn handle_root() -> String {
     format!("collab v{} ({})", VERSION, REVISION.unwrap_or("unknown"))
 }
@@ -3,9 +3,10 @@ mod connection_pool;
 use crate::{
     auth::{self, Impersonator},
     db::{
-        self, BufferId, Channel, ChannelId, ChannelRole, ChannelsForUser, CreatedChannelMessage,
-        Database, InviteMemberResult, MembershipUpdated, MessageId, NotificationId, Project,
-        ProjectId, RemoveChannelMemberResult, ReplicaId, RespondToChannelInvite, RoomId, ServerId,
+        self, BufferId, Channel, ChannelId, ChannelRole, ChannelsForUser, CommentThreadId,
+        CreatedChannelMessage, Database, InviteMemberResult, MembershipUpdated, MessageId,
+        NotificationId, Project, ProjectId, RemoveChannelMemberResult, ReplicaId,
+        RespondToChannelInvite, RoomId, ServerId, UpdatedChannelMessage, UpdatedCommentThread,
         User, UserId,
     },
     executor::Executor,
@@ -43,6 +44,7 @@ use rpc::{
         RequestMessage, ShareProject, UpdateChannelBufferCollaborators,
     },
     Connection, ConnectionId, ErrorCode, ErrorCodeExt, ErrorExt, Peer, Receipt, TypedEnvelope,
+    MAX_CHANNEL_MESSAGE_LEN,
 };
 use serde::{Serialize, Serializer};
 use std::{
@@ -60,7 +62,7 @@ use std::{
     },
     time::{Duration, Instant},
 };
-use time::OffsetDateTime;
+use time::{OffsetDateTime, PrimitiveDateTime};
 use tokio::sync::{watch, Semaphore};
 use tower::ServiceBuilder;
 use tracing::{field, info_span, instrument, Instrument};
@@ -72,7 +74,6 @@ pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
 pub const CLEANUP_TIMEOUT: Duration = Duration::from_secs(15);
 
 const MESSAGE_COUNT_PER_PAGE: usize = 100;
-const MAX_MESSAGE_LEN: usize = 1024;
 const NOTIFICATION_COUNT_PER_PAGE: usize = 50;
 
 type MessageHandler =
@@ -240,6 +241,8 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::ExpandProjectEntry>)
             .add_request_handler(forward_mutating_project_request::<proto::OnTypeFormatting>)
             .add_request_handler(forward_mutating_project_request::<proto::SaveBuffer>)
+            .add_request_handler(forward_mutating_project_request::<proto::TerminalInput>)
+            .add_request_handler(forward_mutating_project_request::<proto::RequestTerminalControl>)
             .add_message_handler(create_buffer_for_peer)
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
@@ -247,6 +250,9 @@ impl Server {
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateDiffBase>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::ShareTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UpdateTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UnshareTerminal>)
             .add_request_handler(get_users)
             .add_request_handler(fuzzy_search_users)
             .add_request_handler(request_contact)
@@ -263,6 +269,14 @@ impl Server {
             .add_request_handler(leave_channel_buffer)
             .add_message_handler(update_channel_buffer)
             .add_request_handler(rejoin_channel_buffers)
+            .add_request_handler(get_channel_buffer_checkpoints)
+            .add_request_handler(load_channel_buffer_checkpoint)
+            .add_request_handler(get_comment_threads)
+            .add_request_handler(create_comment_thread)
+            .add_request_handler(add_comment)
+            .add_request_handler(set_comment_thread_resolved)
+            .add_request_handler(get_channel_activity)
+            .add_message_handler(acknowledge_channel_activity)
             .add_request_handler(get_channel_members)
             .add_request_handler(respond_to_channel_invite)
             .add_request_handler(join_channel)
@@ -270,6 +284,10 @@ impl Server {
             .add_message_handler(leave_channel_chat)
             .add_request_handler(send_channel_message)
             .add_request_handler(remove_channel_message)
+            .add_request_handler(update_channel_message)
+            .add_request_handler(get_channel_message_edits)
+            .add_request_handler(add_channel_message_reaction)
+            .add_request_handler(remove_channel_message_reaction)
             .add_request_handler(get_channel_messages)
             .add_request_handler(get_channel_messages_by_id)
             .add_request_handler(get_notifications)
@@ -1565,7 +1583,7 @@ async fn share_project(
     response: Response<proto::ShareProject>,
     session: Session,
 ) -> Result<()> {
-    let (project_id, room) = &*session
+    let shared_project = &*session
         .db()
         .await
         .share_project(
@@ -1575,9 +1593,17 @@ async fn share_project(
         )
         .await?;
     response.send(proto::ShareProjectResponse {
-        project_id: project_id.to_proto(),
+        project_id: shared_project.project_id.to_proto(),
     })?;
-    room_updated(&room, &session.peer);
+    room_updated(&shared_project.room, &session.peer);
+    if let Some((channel, activity)) = &shared_project.channel_activity {
+        channel_activity_added(
+            channel,
+            activity,
+            &session.peer,
+            &*session.connection_pool().await,
+        );
+    }
 
     Ok(())
 }
@@ -2891,14 +2917,16 @@ async fn join_channel_internal(
         joined_room
     };
 
-    channel_updated(
-        &joined_room
-            .channel
-            .ok_or_else(|| anyhow!("channel not returned"))?,
-        &joined_room.room,
-        &session.peer,
-        &*session.connection_pool().await,
-    );
+    let channel = joined_room
+        .channel
+        .ok_or_else(|| anyhow!("channel not returned"))?;
+    {
+        let connection_pool = session.connection_pool().await;
+        channel_updated(&channel, &joined_room.room, &session.peer, &connection_pool);
+        if let Some(activity) = &joined_room.activity {
+            channel_activity_added(&channel, activity, &session.peer, &connection_pool);
+        }
+    }
 
     update_user_contacts(session.user_id, &session).await?;
     Ok(())
@@ -2973,6 +3001,7 @@ async fn update_channel_buffer(
                         channel_id: channel_id.to_proto(),
                         epoch: epoch as u64,
                         version: version.clone(),
+                        replica_id: None,
                     }],
                     ..Default::default()
                 },
@@ -3046,6 +3075,171 @@ async fn leave_channel_buffer(
     Ok(())
 }
 
+/// List the saved versions of the channel notes
+async fn get_channel_buffer_checkpoints(
+    request: proto::GetChannelBufferCheckpoints,
+    response: Response<proto::GetChannelBufferCheckpoints>,
+    session: Session,
+) -> Result<()> {
+    let checkpoints = session
+        .db()
+        .await
+        .get_channel_buffer_checkpoints(ChannelId::from_proto(request.channel_id), session.user_id)
+        .await?;
+    response.send(proto::GetChannelBufferCheckpointsResponse { checkpoints })?;
+    Ok(())
+}
+
+/// Load the text of a saved version of the channel notes
+async fn load_channel_buffer_checkpoint(
+    request: proto::LoadChannelBufferCheckpoint,
+    response: Response<proto::LoadChannelBufferCheckpoint>,
+    session: Session,
+) -> Result<()> {
+    let text = session
+        .db()
+        .await
+        .load_channel_buffer_checkpoint(
+            ChannelId::from_proto(request.channel_id),
+            request.epoch as i32,
+            session.user_id,
+        )
+        .await?;
+    response.send(proto::LoadChannelBufferCheckpointResponse { text })?;
+    Ok(())
+}
+
+/// List the comment threads on a channel's notes or on a shared project's files
+async fn get_comment_threads(
+    request: proto::GetCommentThreads,
+    response: Response<proto::GetCommentThreads>,
+    session: Session,
+) -> Result<()> {
+    let threads = session
+        .db()
+        .await
+        .get_comment_threads(
+            request.channel_id.map(ChannelId::from_proto),
+            request.project_id.map(ProjectId::from_proto),
+            session.user_id,
+            session.connection_id,
+        )
+        .await?;
+    response.send(proto::GetCommentThreadsResponse { threads })?;
+    Ok(())
+}
+
+/// Start a comment thread on a range of a buffer
+async fn create_comment_thread(
+    request: proto::CreateCommentThread,
+    response: Response<proto::CreateCommentThread>,
+    session: Session,
+) -> Result<()> {
+    let body = validate_comment_body(&request.body)?;
+    let target = request
+        .target
+        .ok_or_else(|| anyhow!("invalid comment target"))?;
+    let start = request.start.ok_or_else(|| anyhow!("invalid anchor"))?;
+    let end = request.end.ok_or_else(|| anyhow!("invalid anchor"))?;
+    let updated_thread = session
+        .db()
+        .await
+        .create_comment_thread(
+            target,
+            start,
+            end,
+            request.row,
+            request.buffer_epoch,
+            &body,
+            session.user_id,
+            session.connection_id,
+        )
+        .await?;
+    broadcast_comment_thread_update(updated_thread, response, &session).await
+}
+
+/// Reply to a comment thread
+async fn add_comment(
+    request: proto::AddComment,
+    response: Response<proto::AddComment>,
+    session: Session,
+) -> Result<()> {
+    let body = validate_comment_body(&request.body)?;
+    let updated_thread = session
+        .db()
+        .await
+        .add_comment(
+            CommentThreadId::from_proto(request.thread_id),
+            &body,
+            session.user_id,
+            session.connection_id,
+        )
+        .await?;
+    broadcast_comment_thread_update(updated_thread, response, &session).await
+}
+
+/// Resolve or reopen a comment thread
+async fn set_comment_thread_resolved(
+    request: proto::SetCommentThreadResolved,
+    response: Response<proto::SetCommentThreadResolved>,
+    session: Session,
+) -> Result<()> {
+    let updated_thread = session
+        .db()
+        .await
+        .set_comment_thread_resolved(
+            CommentThreadId::from_proto(request.thread_id),
+            request.resolved,
+            session.user_id,
+            session.connection_id,
+        )
+        .await?;
+    broadcast_comment_thread_update(updated_thread, response, &session).await
+}
+
+fn validate_comment_body(body: &str) -> Result<String> {
+    let body = body.trim();
+    if body.len() > MAX_CHANNEL_MESSAGE_LEN {
+        return Err(anyhow!("comment is too long"))?;
+    }
+    if body.is_empty() {
+        return Err(anyhow!("comment can't be blank"))?;
+    }
+    Ok(body.to_string())
+}
+
+async fn broadcast_comment_thread_update<T>(
+    updated_thread: UpdatedCommentThread,
+    response: Response<T>,
+    session: &Session,
+) -> Result<()>
+where
+    T: RequestMessage<Response = proto::CommentThreadResponse>,
+{
+    let UpdatedCommentThread {
+        thread,
+        connection_ids,
+        notifications,
+    } = updated_thread;
+    broadcast(Some(session.connection_id), connection_ids, |connection| {
+        session.peer.send(
+            connection,
+            proto::CommentThreadUpdated {
+                thread: Some(thread.clone()),
+            },
+        )
+    });
+    response.send(proto::CommentThreadResponse {
+        thread: Some(thread),
+    })?;
+    send_notifications(
+        &*session.connection_pool().await,
+        &session.peer,
+        notifications,
+    );
+    Ok(())
+}
+
 fn channel_buffer_updated<T: EnvelopedMessage>(
     sender_id: ConnectionId,
     collaborators: impl IntoIterator<Item = ConnectionId>,
@@ -3088,7 +3282,7 @@ async fn send_channel_message(
 ) -> Result<()> {
     // Validate the message body.
     let body = request.body.trim().to_string();
-    if body.len() > MAX_MESSAGE_LEN {
+    if body.len() > MAX_CHANNEL_MESSAGE_LEN {
         return Err(anyhow!("message is too long"))?;
     }
     if body.is_empty() {
@@ -3132,6 +3326,8 @@ async fn send_channel_message(
         timestamp: timestamp.unix_timestamp() as u64,
         nonce: Some(nonce),
         reply_to_message_id: request.reply_to_message_id,
+        edited_at: None,
+        reactions: Vec::new(),
     };
     broadcast(
         Some(session.connection_id),
@@ -3194,6 +3390,132 @@ async fn remove_channel_message(
     Ok(())
 }
 
+/// Edit a channel message
+async fn update_channel_message(
+    request: proto::UpdateChannelMessage,
+    response: Response<proto::UpdateChannelMessage>,
+    session: Session,
+) -> Result<()> {
+    let body = request.body.trim().to_string();
+    if body.len() > MAX_CHANNEL_MESSAGE_LEN {
+        return Err(anyhow!("message is too long"))?;
+    }
+    if body.is_empty() {
+        return Err(anyhow!("message can't be blank"))?;
+    }
+
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let message_id = MessageId::from_proto(request.message_id);
+    let updated_message = session
+        .db()
+        .await
+        .update_channel_message(
+            channel_id,
+            message_id,
+            session.user_id,
+            &body,
+            &request.mentions,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    broadcast_channel_message_update(channel_id, updated_message, response, &session).await
+}
+
+/// Retrieve the previous bodies of an edited channel message
+async fn get_channel_message_edits(
+    request: proto::GetChannelMessageEdits,
+    response: Response<proto::GetChannelMessageEdits>,
+    session: Session,
+) -> Result<()> {
+    let edits = session
+        .db()
+        .await
+        .get_channel_message_edits(
+            ChannelId::from_proto(request.channel_id),
+            MessageId::from_proto(request.message_id),
+            session.user_id,
+        )
+        .await?;
+    response.send(proto::GetChannelMessageEditsResponse { edits })?;
+    Ok(())
+}
+
+/// React to a channel message with an emoji
+async fn add_channel_message_reaction(
+    request: proto::AddChannelMessageReaction,
+    response: Response<proto::AddChannelMessageReaction>,
+    session: Session,
+) -> Result<()> {
+    if emojis::get(&request.emoji).is_none() {
+        return Err(anyhow!("reactions must be a single emoji"))?;
+    }
+
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let updated_message = session
+        .db()
+        .await
+        .add_channel_message_reaction(
+            channel_id,
+            MessageId::from_proto(request.message_id),
+            session.user_id,
+            &request.emoji,
+        )
+        .await?;
+    broadcast_channel_message_update(channel_id, updated_message, response, &session).await
+}
+
+/// Remove a reaction from a channel message
+async fn remove_channel_message_reaction(
+    request: proto::RemoveChannelMessageReaction,
+    response: Response<proto::RemoveChannelMessageReaction>,
+    session: Session,
+) -> Result<()> {
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let updated_message = session
+        .db()
+        .await
+        .remove_channel_message_reaction(
+            channel_id,
+            MessageId::from_proto(request.message_id),
+            session.user_id,
+            &request.emoji,
+        )
+        .await?;
+    broadcast_channel_message_update(channel_id, updated_message, response, &session).await
+}
+
+async fn broadcast_channel_message_update<T>(
+    channel_id: ChannelId,
+    updated_message: UpdatedChannelMessage,
+    response: Response<T>,
+    session: &Session,
+) -> Result<()>
+where
+    T: RequestMessage<Response = proto::ChannelMessageUpdate>,
+{
+    let UpdatedChannelMessage {
+        message,
+        participant_connection_ids,
+        notifications,
+    } = updated_message;
+    let update = proto::ChannelMessageUpdate {
+        channel_id: channel_id.to_proto(),
+        message: Some(message),
+    };
+    broadcast(
+        Some(session.connection_id),
+        participant_connection_ids,
+        |connection| session.peer.send(connection, update.clone()),
+    );
+    response.send(update)?;
+    send_notifications(
+        &*session.connection_pool().await,
+        &session.peer,
+        notifications,
+    );
+    Ok(())
+}
+
 /// Mark a channel message as read
 async fn acknowledge_channel_message(
     request: proto::AckChannelMessage,
@@ -3214,6 +3536,45 @@ async fn acknowledge_channel_message(
     Ok(())
 }
 
+/// Get the recent activity in the user's channels
+async fn get_channel_activity(
+    request: proto::GetChannelActivity,
+    response: Response<proto::GetChannelActivity>,
+    session: Session,
+) -> Result<()> {
+    let before = request
+        .before
+        .map(activity_timestamp_from_proto)
+        .transpose()?;
+    let activity = session
+        .db()
+        .await
+        .get_channel_activity(session.user_id, before)
+        .await?;
+    response.send(activity)?;
+    Ok(())
+}
+
+/// Mark the user's channel activity as read up to a given time
+async fn acknowledge_channel_activity(
+    request: proto::AckChannelActivity,
+    session: Session,
+) -> Result<()> {
+    let observed_at = activity_timestamp_from_proto(request.timestamp)?;
+    session
+        .db()
+        .await
+        .observe_channel_activity(session.user_id, observed_at)
+        .await?;
+    Ok(())
+}
+
+fn activity_timestamp_from_proto(timestamp: u64) -> Result<PrimitiveDateTime> {
+    let timestamp = OffsetDateTime::from_unix_timestamp_nanos(timestamp as i128 * 1000)
+        .map_err(|_| anyhow!("invalid timestamp"))?;
+    Ok(PrimitiveDateTime::new(timestamp.date(), timestamp.time()))
+}
+
 /// Mark a buffer version as synced
 async fn acknowledge_buffer_version(
     request: proto::AckBufferOperation,
@@ -3580,6 +3941,30 @@ fn channel_updated(
     );
 }
 
+fn channel_activity_added(
+    channel: &db::channel::Model,
+    activity: &proto::ChannelActivity,
+    peer: &Peer,
+    pool: &ConnectionPool,
+) {
+    broadcast(
+        None,
+        pool.channel_connection_ids(channel.root_id())
+            .filter_map(|(connection_id, role)| {
+                role.can_see_channel(channel.visibility)
+                    .then(|| connection_id)
+            }),
+        |connection_id| {
+            peer.send(
+                connection_id,
+                proto::ChannelActivityAdded {
+                    activity: Some(activity.clone()),
+                },
+            )
+        },
+    );
+}
+
 async fn update_user_contacts(user_id: UserId, session: &Session) -> Result<()> {
     let db = session.db().await;
 
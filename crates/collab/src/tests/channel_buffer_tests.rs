@@ -89,7 +89,7 @@ async fn test_core_channel_buffers(
     });
 
     // Client A rejoins the channel buffer
-    let _channel_buffer_a = client_a
+    let channel_buffer_a = client_a
         .channel_store()
         .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
         .await
@@ -114,8 +114,47 @@ async fn test_core_channel_buffers(
         assert_collaborators(&buffer.collaborators(), &[client_b.user_id()]);
     });
 
+    // Client A can still edit the notes while offline, while client B edits them too.
+    let buffer_a = channel_buffer_a.read_with(cx_a, |buffer, _| buffer.buffer());
+    channel_buffer_a.read_with(cx_a, |buffer, _| {
+        assert!(buffer.is_offline());
+        assert!(!buffer.is_connected());
+    });
+    assert!(!buffer_a.read_with(cx_a, |buffer, _| buffer.read_only()));
+    buffer_a.update(cx_a, |buffer, cx| {
+        buffer.edit([(0..0, "offline: ")], None, cx)
+    });
+    buffer_b.update(cx_b, |buffer, cx| buffer.edit([(22..22, "!")], None, cx));
+    executor.run_until_parked();
+    assert_eq!(
+        buffer_text(&buffer_a, cx_a),
+        "offline: hello, beautiful world"
+    );
+    assert_eq!(buffer_text(&buffer_b, cx_b), "hello, beautiful world!");
+
+    // Client A reconnects, and both clients see each other's edits.
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    executor.run_until_parked();
+    channel_buffer_a.read_with(cx_a, |buffer, _| {
+        assert!(buffer.is_connected());
+    });
+    assert_eq!(
+        buffer_text(&buffer_a, cx_a),
+        "offline: hello, beautiful world!"
+    );
+    assert_eq!(
+        buffer_text(&buffer_b, cx_b),
+        "offline: hello, beautiful world!"
+    );
+    channel_buffer_b.read_with(cx_b, |buffer, _| {
+        assert_collaborators(
+            &buffer.collaborators(),
+            &[client_a.user_id(), client_b.user_id()],
+        );
+    });
+
     // TODO:
-    // - Test synchronizing offline updates, what happens to A's channel buffer when A disconnects
     // - Test interaction with channel deletion while buffer is open
 }
 
@@ -512,6 +551,106 @@ async fn test_rejoin_channel_buffer(
     });
 }
 
+#[gpui::test]
+async fn test_offline_channel_buffer_edits_across_epochs(
+    deterministic: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(deterministic.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    channel_buffer_a.update(cx_a, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "hello")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+
+    // Client A loses connection for long enough to be removed from the notes, and
+    // keeps editing them.
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    channel_buffer_a.update(cx_a, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(5..5, " world")], None, cx);
+        })
+    });
+    deterministic.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    deterministic.run_until_parked();
+
+    // Client B edits the notes and closes them, which starts a new epoch.
+    channel_buffer_b.update(cx_b, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "oh, ")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+    cx_b.update(|_| drop(channel_buffer_b));
+    deterministic.run_until_parked();
+
+    // Client A reconnects, but can't rejoin the notes as they were.
+    server.allow_connections();
+    deterministic.advance_clock(RECEIVE_TIMEOUT);
+    deterministic.run_until_parked();
+    channel_buffer_a.read_with(cx_a, |buffer, _| assert!(!buffer.is_connected()));
+
+    // When the notes are reopened, the edits that client A made offline are merged
+    // into their new text.
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    deterministic.run_until_parked();
+    channel_buffer_a.read_with(cx_a, |buffer, cx| {
+        assert_eq!(buffer.buffer().read(cx).text(), "oh, hello world");
+    });
+
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    deterministic.run_until_parked();
+    channel_buffer_b.read_with(cx_b, |buffer, cx| {
+        assert_eq!(buffer.buffer().read(cx).text(), "oh, hello world");
+    });
+
+    // The offline edits have been merged, so they aren't applied again.
+    cx_a.update(|_| drop(channel_buffer_a));
+    deterministic.run_until_parked();
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    deterministic.run_until_parked();
+    channel_buffer_a.read_with(cx_a, |buffer, cx| {
+        assert_eq!(buffer.buffer().read(cx).text(), "oh, hello world");
+    });
+}
+
 #[gpui::test]
 async fn test_channel_buffers_and_server_restarts(
     deterministic: BackgroundExecutor,
@@ -676,6 +815,108 @@ async fn test_channel_buffer_changes_persist(
     });
 }
 
+#[gpui::test]
+async fn test_channel_notes_version_history(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+
+    // A version is saved each time everyone closes the notes.
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let buffer_a = channel_buffer_a.read_with(cx_a, |buffer, _| buffer.buffer());
+    buffer_a.update(cx_a, |buffer, cx| buffer.edit([(0..0, "hello")], None, cx));
+    executor.run_until_parked();
+    cx_a.update(|_| drop((channel_buffer_a, buffer_a)));
+    executor.run_until_parked();
+
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let buffer_a = channel_buffer_a.read_with(cx_a, |buffer, _| buffer.buffer());
+    let buffer_b = channel_buffer_b.read_with(cx_b, |buffer, _| buffer.buffer());
+    buffer_a.update(cx_a, |buffer, cx| buffer.edit([(5..5, " world")], None, cx));
+    executor.run_until_parked();
+    buffer_b.update(cx_b, |buffer, cx| buffer.edit([(11..11, "!")], None, cx));
+    executor.run_until_parked();
+    cx_a.update(|_| drop((channel_buffer_a, buffer_a)));
+    cx_b.update(|_| drop((channel_buffer_b, buffer_b)));
+    executor.run_until_parked();
+
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let buffer_a = channel_buffer_a.read_with(cx_a, |buffer, _| buffer.buffer());
+    let buffer_b = channel_buffer_b.read_with(cx_b, |buffer, _| buffer.buffer());
+    assert_eq!(buffer_text(&buffer_a, cx_a), "hello world!");
+
+    // Versions are listed newest first, along with the users who made them.
+    let checkpoints = channel_buffer_a
+        .update(cx_a, |buffer, cx| buffer.checkpoints(cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        checkpoints
+            .iter()
+            .map(|checkpoint| (
+                checkpoint.epoch,
+                checkpoint
+                    .authors
+                    .iter()
+                    .map(|user| user.github_login.as_str())
+                    .collect::<Vec<_>>()
+            ))
+            .collect::<Vec<_>>(),
+        &[(2, vec!["user_a", "user_b"]), (1, vec!["user_a"])]
+    );
+    assert_eq!(
+        channel_buffer_a
+            .update(cx_a, |buffer, cx| buffer.load_checkpoint(1, cx))
+            .await
+            .unwrap(),
+        "hello"
+    );
+
+    // Restoring a version edits the notes for every collaborator, and can be undone.
+    channel_buffer_b
+        .update(cx_b, |buffer, cx| buffer.restore_checkpoint(1, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(buffer_text(&buffer_a, cx_a), "hello");
+    assert_eq!(buffer_text(&buffer_b, cx_b), "hello");
+
+    buffer_b.update(cx_b, |buffer, cx| buffer.undo(cx));
+    executor.run_until_parked();
+    assert_eq!(buffer_text(&buffer_a, cx_a), "hello world!");
+    assert_eq!(buffer_text(&buffer_b, cx_b), "hello world!");
+}
+
 #[track_caller]
 fn assert_collaborators(collaborators: &HashMap<PeerId, Collaborator>, ids: &[Option<UserId>]) {
     let mut user_ids = collaborators
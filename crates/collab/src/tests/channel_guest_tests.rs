@@ -1,8 +1,9 @@
 use crate::{db::ChannelId, tests::TestServer};
 use call::ActiveCall;
 use editor::Editor;
-use gpui::{BackgroundExecutor, TestAppContext};
+use gpui::{BackgroundExecutor, Context as _, Entity as _, TestAppContext};
 use rpc::proto;
+use terminal::TerminalBuilder;
 
 #[gpui::test]
 async fn test_channel_guests(
@@ -60,6 +61,62 @@ async fn test_channel_guests(
     assert!(room_b.read_with(cx_b, |room, _| room.is_muted()));
 }
 
+#[gpui::test]
+async fn test_channel_guests_cannot_type_in_shared_terminals(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let channel_id = server
+        .make_public_channel("the-channel", &client_a, cx_a)
+        .await;
+
+    // Client A shares a project and a terminal in the channel
+    let project_a = client_a.build_test_project(cx_a).await;
+    active_call_a
+        .update(cx_a, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap();
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let terminal_a = cx_a.update(|cx| cx.new_model(|cx| TerminalBuilder::new_fake().subscribe(cx)));
+    project_a
+        .update(cx_a, |project, cx| project.share_terminal(&terminal_a, cx))
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // Client B joins channel A as a guest, and can't ask to type in the terminal
+    cx_b.update(|cx| workspace::join_channel(channel_id, client_b.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    let terminal_id = terminal_a.entity_id().as_u64();
+    assert!(client_b
+        .client()
+        .request(proto::RequestTerminalControl {
+            project_id,
+            terminal_id,
+        })
+        .await
+        .is_err());
+    assert!(client_b
+        .client()
+        .request(proto::TerminalInput {
+            project_id,
+            terminal_id,
+            input: b"q".to_vec(),
+        })
+        .await
+        .is_err());
+}
+
 #[gpui::test]
 async fn test_channel_guest_promotion(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
     let mut server = TestServer::start(cx_a.executor()).await;
@@ -466,3 +466,245 @@ async fn test_chat_replies(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
         )
     });
 }
+
+#[gpui::test]
+async fn test_edit_channel_message(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+
+    let message_id = channel_chat_a
+        .update(cx_a, |c, cx| c.send_message("one".into(), cx).unwrap())
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    channel_chat_a
+        .update(cx_a, |c, cx| {
+            c.update_message(message_id, "one, edited".into(), cx)
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    for (chat, cx) in [(&channel_chat_a, &mut *cx_a), (&channel_chat_b, &mut *cx_b)] {
+        chat.read_with(cx, |chat, _| {
+            let message = chat.find_loaded_message(message_id).unwrap();
+            assert_eq!(message.body, "one, edited");
+            assert!(message.is_edited());
+        });
+    }
+
+    // Only the sender can edit a message.
+    channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.update_message(message_id, "hijacked".into(), cx).unwrap()
+        })
+        .await
+        .unwrap_err();
+
+    channel_chat_a
+        .update(cx_a, |c, cx| {
+            c.update_message(message_id, "one, edited again".into(), cx)
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    let edits = channel_chat_b
+        .update(cx_b, |c, cx| c.load_message_edits(message_id, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        edits
+            .iter()
+            .map(|edit| edit.body.as_str())
+            .collect::<Vec<_>>(),
+        ["one", "one, edited"]
+    );
+    assert_messages(&channel_chat_b, &["one, edited again"], cx_b);
+}
+
+#[gpui::test]
+async fn test_channel_message_reactions(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+
+    let message_id = channel_chat_a
+        .update(cx_a, |c, cx| c.send_message("one".into(), cx).unwrap())
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    channel_chat_a
+        .update(cx_a, |c, cx| c.toggle_reaction(message_id, "👍".into(), cx))
+        .await
+        .unwrap();
+    channel_chat_b
+        .update(cx_b, |c, cx| c.toggle_reaction(message_id, "👍".into(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    for (chat, cx) in [(&channel_chat_a, &mut *cx_a), (&channel_chat_b, &mut *cx_b)] {
+        chat.read_with(cx, |chat, _| {
+            let message = chat.find_loaded_message(message_id).unwrap();
+            assert_eq!(message.reactions.len(), 1);
+            assert_eq!(message.reactions[0].emoji, "👍");
+            assert_eq!(
+                message.reactions[0].user_ids,
+                [client_a.id(), client_b.id()]
+            );
+        });
+    }
+
+    // Toggling a reaction a second time removes it.
+    channel_chat_a
+        .update(cx_a, |c, cx| c.toggle_reaction(message_id, "👍".into(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    channel_chat_b.read_with(cx_b, |chat, _| {
+        let message = chat.find_loaded_message(message_id).unwrap();
+        assert!(!message.has_reaction("👍", client_a.id()));
+        assert!(message.has_reaction("👍", client_b.id()));
+    });
+
+    // Reactions must be a single emoji.
+    for reaction in ["lgtm", "👍👍", "👍 "] {
+        channel_chat_a
+            .update(cx_a, |c, cx| {
+                c.toggle_reaction(message_id, reaction.into(), cx)
+            })
+            .await
+            .unwrap_err();
+    }
+}
+
+#[gpui::test]
+async fn test_chat_reply_threads(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+
+    let root_id = channel_chat_a
+        .update(cx_a, |c, cx| c.send_message("root".into(), cx).unwrap())
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    let reply_id = channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.send_message(
+                MessageParams {
+                    text: "reply".into(),
+                    mentions: Vec::new(),
+                    reply_to_message_id: Some(root_id),
+                },
+                cx,
+            )
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.send_message("unrelated".into(), cx).unwrap()
+        })
+        .await
+        .unwrap();
+    channel_chat_a
+        .update(cx_a, |c, cx| {
+            c.send_message(
+                MessageParams {
+                    text: "nested reply".into(),
+                    mentions: Vec::new(),
+                    reply_to_message_id: Some(reply_id),
+                },
+                cx,
+            )
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    for (chat, cx) in [(&channel_chat_a, &mut *cx_a), (&channel_chat_b, &mut *cx_b)] {
+        chat.read_with(cx, |chat, _| {
+            assert_eq!(
+                chat.thread_message_ixs(root_id)
+                    .into_iter()
+                    .map(|ix| chat.message(ix).body.as_str())
+                    .collect::<Vec<_>>(),
+                ["root", "reply", "nested reply"]
+            );
+            let reply_counts = chat.reply_counts();
+            assert_eq!(reply_counts.get(&root_id), Some(&1));
+            assert_eq!(reply_counts.get(&reply_id), Some(&1));
+        });
+    }
+}
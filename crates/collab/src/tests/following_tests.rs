@@ -12,13 +12,16 @@ use gpui::{
 };
 use language::Capability;
 use live_kit_client::MacOSDisplay;
-use project::WorktreeSettings;
+use project::{terminals::SHARED_TERMINAL_UPDATE_THROTTLE, WorktreeSettings};
 use rpc::proto::PeerId;
+use search::ProjectSearchView;
 use serde_json::json;
 use settings::SettingsStore;
+use terminal::TerminalBuilder;
+use terminal_view::TerminalView;
 use workspace::{
     dock::{test::TestPanel, DockPosition},
-    item::{test::TestItem, ItemHandle as _},
+    item::{test::TestItem, Item as _, ItemHandle as _},
     shared_screen::SharedScreen,
     SplitDirection, Workspace,
 };
@@ -1765,6 +1768,183 @@ async fn test_following_into_excluded_file(
     });
 }
 
+#[gpui::test]
+async fn test_following_terminal(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_server, client_a, client_b, channel_id) = TestServer::start2(cx_a, cx_b).await;
+    cx_a.update(terminal_view::init);
+    cx_b.update(terminal_view::init);
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    client_a
+        .host_workspace(&workspace_a, channel_id, cx_a)
+        .await;
+    let (workspace_b, cx_b) = client_b.join_workspace(channel_id, cx_b).await;
+
+    // a opens and shares a terminal without a process, so that no shell is spawned.
+    let terminal_a = cx_a.update(|cx| cx.new_model(|cx| TerminalBuilder::new_fake().subscribe(cx)));
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"$ cargo test\r\nrunning 1 test", cx)
+    });
+    workspace_a.update(cx_a, |workspace, cx| {
+        workspace
+            .project()
+            .update(cx, |project, cx| project.share_terminal(&terminal_a, cx))
+            .unwrap();
+        let terminal_view = cx.new_view(|cx| {
+            TerminalView::new(
+                terminal_a.clone(),
+                workspace.weak_handle(),
+                workspace.database_id(),
+                cx,
+            )
+        });
+        workspace.add_item_to_active_pane(Box::new(terminal_view), cx);
+    });
+    cx_a.executor()
+        .advance_clock(workspace::item::LEADER_UPDATE_THROTTLE);
+    cx_a.run_until_parked();
+
+    // b follows a into the tab that was opened for the shared terminal
+    let terminal_view_b = workspace_b.update(cx_b, |workspace, cx| {
+        assert_eq!(workspace.items_of_type::<TerminalView>(cx).count(), 1);
+        workspace.active_item_as::<TerminalView>(cx).unwrap()
+    });
+    terminal_view_b.update(cx_b, |terminal_view, cx| {
+        let terminal = terminal_view.model().read(cx);
+        assert!(terminal.is_remote());
+        assert_eq!(
+            terminal.screen_rows()[..2],
+            ["$ cargo test", "running 1 test"]
+        );
+    });
+
+    // the terminal of a prints more output, which reaches b through the shared terminal
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"\r\ntest result: ok", cx)
+    });
+    cx_a.executor()
+        .advance_clock(SHARED_TERMINAL_UPDATE_THROTTLE);
+    cx_a.run_until_parked();
+    terminal_view_b.update(cx_b, |terminal_view, cx| {
+        let rows = terminal_view.model().read(cx).screen_rows();
+        assert_eq!(
+            rows[..3],
+            ["$ cargo test", "running 1 test", "test result: ok"]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_following_unshared_terminal(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_server, client_a, client_b, channel_id) = TestServer::start2(cx_a, cx_b).await;
+    cx_a.update(terminal_view::init);
+    cx_b.update(terminal_view::init);
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    client_a
+        .host_workspace(&workspace_a, channel_id, cx_a)
+        .await;
+    let (workspace_b, cx_b) = client_b.join_workspace(channel_id, cx_b).await;
+
+    // a opens a terminal without sharing it
+    let terminal_a = cx_a.update(|cx| cx.new_model(|cx| TerminalBuilder::new_fake().subscribe(cx)));
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"$ env\r\nSECRET=hunter2", cx)
+    });
+    workspace_a.update(cx_a, |workspace, cx| {
+        let terminal_view = cx.new_view(|cx| {
+            TerminalView::new(
+                terminal_a.clone(),
+                workspace.weak_handle(),
+                workspace.database_id(),
+                cx,
+            )
+        });
+        workspace.add_item_to_active_pane(Box::new(terminal_view), cx);
+    });
+    cx_a.executor()
+        .advance_clock(workspace::item::LEADER_UPDATE_THROTTLE);
+    cx_a.run_until_parked();
+
+    // the terminal of a isn't shown to b
+    workspace_b.update(cx_b, |workspace, cx| {
+        assert!(workspace.items_of_type::<TerminalView>(cx).next().is_none());
+    });
+
+    // nor is its later output
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"\r\nSECRET=hunter3", cx)
+    });
+    cx_a.executor()
+        .advance_clock(SHARED_TERMINAL_UPDATE_THROTTLE);
+    cx_a.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        assert!(workspace.items_of_type::<TerminalView>(cx).next().is_none());
+    });
+}
+
+#[gpui::test]
+async fn test_following_project_search(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_server, client_a, client_b, channel_id) = TestServer::start2(cx_a, cx_b).await;
+    cx_a.update(search::init);
+    cx_b.update(search::init);
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    client_a
+        .host_workspace(&workspace_a, channel_id, cx_a)
+        .await;
+    let (workspace_b, cx_b) = client_b.join_workspace(channel_id, cx_b).await;
+
+    // a searches the project
+    cx_a.simulate_keystrokes("cmd-shift-f");
+    cx_a.simulate_input("one");
+    cx_a.simulate_keystrokes("enter");
+    cx_a.executor()
+        .advance_clock(workspace::item::LEADER_UPDATE_THROTTLE);
+    cx_a.run_until_parked();
+
+    let search_a = workspace_a.update(cx_a, |workspace, cx| {
+        workspace.active_item_as::<ProjectSearchView>(cx).unwrap()
+    });
+    let results_a = cx_a.update(|cx| {
+        assert!(search_a.read(cx).has_matches());
+        search_a.act_as::<Editor>(cx).unwrap().read(cx).text(cx)
+    });
+    assert_eq!(results_a, "one\none\none");
+
+    // b sees a's query and results
+    let search_b = workspace_b.update(cx_b, |workspace, cx| {
+        workspace.active_item_as::<ProjectSearchView>(cx).unwrap()
+    });
+    cx_b.update(|cx| {
+        let search = search_b.read(cx);
+        assert_eq!(search.tab_tooltip_text(cx).unwrap().to_string(), "one");
+        assert!(search.has_matches());
+        assert_eq!(
+            search_b.act_as::<Editor>(cx).unwrap().read(cx).text(cx),
+            results_a
+        );
+    });
+
+    // a searches for something else
+    cx_a.simulate_keystrokes("cmd-shift-f");
+    cx_a.simulate_input("two");
+    cx_a.simulate_keystrokes("enter");
+    cx_a.executor()
+        .advance_clock(workspace::item::LEADER_UPDATE_THROTTLE);
+    cx_a.run_until_parked();
+
+    cx_b.update(|cx| {
+        let search = search_b.read(cx);
+        assert_eq!(search.tab_tooltip_text(cx).unwrap().to_string(), "two");
+        assert!(search.has_matches());
+        assert_eq!(
+            search_b.act_as::<Editor>(cx).unwrap().read(cx).text(cx),
+            "function two() { return 2; }"
+        );
+    });
+}
+
 fn visible_push_notifications(
     cx: &mut TestAppContext,
 ) -> Vec<gpui::View<ProjectSharedNotification>> {
@@ -8,8 +8,8 @@ use collections::{HashMap, HashSet};
 use fs::{repository::GitFileStatus, FakeFs, Fs as _, RemoveOptions};
 use futures::StreamExt as _;
 use gpui::{
-    px, size, AppContext, BackgroundExecutor, Model, Modifiers, MouseButton, MouseDownEvent,
-    TestAppContext,
+    px, size, AppContext, BackgroundExecutor, Context as _, Entity as _, Model, Modifiers,
+    MouseButton, MouseDownEvent, TestAppContext,
 };
 use language::{
     language_settings::{AllLanguageSettings, Formatter},
@@ -19,10 +19,11 @@ use language::{
 use live_kit_client::MacOSDisplay;
 use lsp::LanguageServerId;
 use project::{
-    search::SearchQuery, DiagnosticSummary, FormatTrigger, HoverBlockKind, Project, ProjectPath,
-    SearchResult,
+    search::SearchQuery, terminals::SHARED_TERMINAL_UPDATE_THROTTLE, DiagnosticSummary,
+    FormatTrigger, HoverBlockKind, Project, ProjectPath, SearchResult,
 };
 use rand::prelude::*;
+use rpc::proto;
 use serde_json::json;
 use settings::SettingsStore;
 use std::{
@@ -36,6 +37,8 @@ use std::{
     },
     time::Duration,
 };
+use terminal::TerminalBuilder;
+use terminal_view::TerminalView;
 use unindent::Unindent as _;
 
 #[ctor::ctor]
@@ -3114,7 +3117,7 @@ async fn test_fs_operations(
 
     project_b
         .update(cx_b, |project, cx| {
-            project.copy_entry(entry.id, Path::new("f.txt"), cx)
+            project.copy_entry(entry.id, None, Path::new("f.txt"), cx)
         })
         .await
         .unwrap()
@@ -5928,3 +5931,83 @@ async fn test_cmd_k_left(cx: &mut TestAppContext) {
         assert!(workspace.items(cx).collect::<Vec<_>>().len() == 2);
     });
 }
+
+#[gpui::test]
+async fn test_shared_terminal(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_server, client_a, client_b, channel_id) = TestServer::start2(cx_a, cx_b).await;
+    cx_a.update(terminal_view::init);
+    cx_b.update(terminal_view::init);
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    client_a
+        .host_workspace(&workspace_a, channel_id, cx_a)
+        .await;
+    let (workspace_b, cx_b) = client_b.join_workspace(channel_id, cx_b).await;
+    let project_a = workspace_a.update(cx_a, |workspace, _| workspace.project().clone());
+    let project_b = workspace_b.update(cx_b, |workspace, _| workspace.project().clone());
+    let project_id = project_a.read_with(cx_a, |project, _| project.remote_id().unwrap());
+
+    // a shares a terminal without a process, so that no shell is spawned.
+    let terminal_a = cx_a.update(|cx| cx.new_model(|cx| TerminalBuilder::new_fake().subscribe(cx)));
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"$ cargo run", cx)
+    });
+    project_a
+        .update(cx_a, |project, cx| project.share_terminal(&terminal_a, cx))
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // b sees the shared terminal in a new tab
+    let terminal_b = workspace_b.update(cx_b, |workspace, cx| {
+        let terminal_view = workspace.items_of_type::<TerminalView>(cx).next().unwrap();
+        terminal_view.read(cx).model().clone()
+    });
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert!(terminal.is_remote());
+        assert_eq!(terminal.title(false), "Terminal");
+        assert_eq!(terminal.screen_rows()[0], "$ cargo run");
+    });
+
+    // only the rows that changed are sent to b
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"\r\nCompiling zed", cx)
+    });
+    cx_a.executor()
+        .advance_clock(SHARED_TERMINAL_UPDATE_THROTTLE);
+    cx_a.run_until_parked();
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert_eq!(
+            terminal.screen_rows()[..2],
+            ["$ cargo run", "Compiling zed"]
+        );
+        assert_eq!(terminal.cursor_position(), (1, 13));
+    });
+
+    // b can't type in the terminal before a allows it
+    let terminal_id = terminal_a.entity_id().as_u64();
+    let input = proto::TerminalInput {
+        project_id,
+        terminal_id,
+        input: b"q".to_vec(),
+    };
+    assert!(client_b.client().request(input.clone()).await.is_err());
+
+    let request = project_b.update(cx_b, |project, cx| {
+        project.request_terminal_control(&terminal_b, cx)
+    });
+    cx_a.run_until_parked();
+    assert!(cx_a.has_pending_prompt());
+    cx_a.simulate_prompt_answer(0);
+    assert!(request.await.unwrap());
+    assert!(project_b.read_with(cx_b, |project, _| project.has_terminal_control(&terminal_b)));
+    assert!(client_b.client().request(input).await.is_ok());
+
+    // b's tab is closed when a stops sharing the terminal
+    project_a.update(cx_a, |project, cx| {
+        project.unshare_terminal(&terminal_a, cx)
+    });
+    cx_a.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        assert_eq!(workspace.items_of_type::<TerminalView>(cx).count(), 0);
+    });
+}
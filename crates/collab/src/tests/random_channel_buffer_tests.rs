@@ -1,10 +1,11 @@
-use crate::db::ChannelRole;
+use crate::{db::ChannelRole, rpc::RECONNECT_TIMEOUT};
 
 use super::{run_randomized_test, RandomizedTest, TestClient, TestError, TestServer, UserTestPlan};
 use anyhow::Result;
 use async_trait::async_trait;
 use gpui::{BackgroundExecutor, SharedString, TestAppContext};
 use rand::prelude::*;
+use rpc::RECEIVE_TIMEOUT;
 use serde_derive::{Deserialize, Serialize};
 use std::{
     ops::{Deref, DerefMut, Range},
@@ -39,6 +40,7 @@ enum ChannelBufferOperation {
         channel_name: SharedString,
         edits: Vec<(Range<usize>, Arc<str>)>,
     },
+    DropConnection,
     Noop,
 }
 
@@ -108,6 +110,14 @@ impl RandomizedTest for RandomChannelBufferTest {
                     }
                 }
 
+                41..=45 => {
+                    if !channel_buffers.deref().is_empty()
+                        && client.status().borrow().is_connected()
+                    {
+                        break ChannelBufferOperation::DropConnection;
+                    }
+                }
+
                 _ => {
                     if let Some(buffer) = channel_buffers.deref().iter().choose(rng) {
                         break buffer.read_with(cx, |b, cx| {
@@ -215,6 +225,17 @@ impl RandomizedTest for RandomChannelBufferTest {
                 });
             }
 
+            ChannelBufferOperation::DropConnection => {
+                if !client.status().borrow().is_connected() {
+                    Err(TestError::Inapplicable)?;
+                }
+
+                // The notes stay editable while the client reconnects, and the edits
+                // are merged once their buffers are rejoined.
+                log::info!("{}: dropping connection", client.username);
+                cx.update(|cx| client.reconnect(&cx.to_async()));
+            }
+
             ChannelBufferOperation::Noop => Err(TestError::Inapplicable)?,
         }
         Ok(())
@@ -223,6 +244,16 @@ impl RandomizedTest for RandomChannelBufferTest {
     async fn on_quiesce(server: &mut TestServer, clients: &mut [(Rc<TestClient>, TestAppContext)]) {
         let channels = server.app_state.db.all_channels().await.unwrap();
 
+        // Let any clients whose connection was dropped reconnect and merge the edits
+        // they made while offline, and let the server clean up the notes they closed
+        // in the meantime.
+        if let Some((_, client_cx)) = clients.first() {
+            client_cx
+                .executor()
+                .advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+            client_cx.executor().run_until_parked();
+        }
+
         for (client, client_cx) in clients.iter_mut() {
             client_cx.update(|cx| {
                 client
@@ -0,0 +1,49 @@
+use crate::{db::TestDb, AppState, Config};
+use gpui::TestAppContext;
+
+#[gpui::test]
+async fn test_standalone_app_state(cx: &mut TestAppContext) {
+    let test_db = TestDb::sqlite(cx.executor());
+    let config = || Config {
+        live_kit_server: Some("https://livekit.example.com".into()),
+        live_kit_key: Some("key".into()),
+        live_kit_secret: Some("secret".into()),
+        clickhouse_url: Some("https://clickhouse.example.com".into()),
+        clickhouse_user: Some("user".into()),
+        clickhouse_password: Some("password".into()),
+        clickhouse_database: Some("default".into()),
+        ..Config::test()
+    };
+
+    // Configured services are connected to by default.
+    let app_state = AppState::with_database(test_db.db().clone(), config())
+        .await
+        .unwrap();
+    assert!(!app_state.config.is_standalone());
+    assert!(app_state.live_kit_client.is_some());
+    assert!(app_state.clickhouse_client.is_some());
+
+    // In standalone mode, they are ignored.
+    let app_state = AppState::with_database(
+        test_db.db().clone(),
+        Config {
+            standalone: Some(true),
+            ..config()
+        },
+    )
+    .await
+    .unwrap();
+    assert!(app_state.config.is_standalone());
+    assert!(app_state.live_kit_client.is_none());
+    assert!(app_state.blob_store_client.is_none());
+    assert!(app_state.clickhouse_client.is_none());
+
+    // Standalone servers can only be hosted on Postgres, whose schema is migrated in place.
+    let result = AppState::new(Config {
+        database_url: "sqlite::memory:".into(),
+        standalone: Some(true),
+        ..Config::test()
+    })
+    .await;
+    assert!(result.is_err());
+}
@@ -489,31 +489,7 @@ impl TestServer {
             live_kit_client: Some(Arc::new(fake_server.create_api_client())),
             blob_store_client: None,
             clickhouse_client: None,
-            config: Config {
-                http_port: 0,
-                database_url: "".into(),
-                database_max_connections: 0,
-                api_token: "".into(),
-                invite_link_prefix: "".into(),
-                live_kit_server: None,
-                live_kit_key: None,
-                live_kit_secret: None,
-                rust_log: None,
-                log_json: None,
-                zed_environment: "test".into(),
-                blob_store_url: None,
-                blob_store_region: None,
-                blob_store_access_key: None,
-                blob_store_secret_key: None,
-                blob_store_bucket: None,
-                clickhouse_url: None,
-                clickhouse_user: None,
-                clickhouse_password: None,
-                clickhouse_database: None,
-                zed_client_checksum_seed: None,
-                slack_panics_webhook: None,
-                auto_join_channel_id: None,
-            },
+            config: Config::test(),
         })
     }
 }
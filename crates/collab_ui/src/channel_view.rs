@@ -1,3 +1,6 @@
+mod notes_history;
+
+use crate::code_comments::{AddComment, CodeComments};
 use anyhow::Result;
 use call::report_call_event_for_channel;
 use channel::{Channel, ChannelBuffer, ChannelBufferEvent, ChannelStore};
@@ -29,7 +32,9 @@ use workspace::{
     ItemNavHistory, Pane, SaveIntent, Toast, ViewId, Workspace, WorkspaceId,
 };
 
-actions!(collab, [CopyLink]);
+pub use notes_history::NotesHistoryView;
+
+actions!(collab, [CopyLink, OpenNotesHistory, RestoreVersion]);
 
 pub fn init(cx: &mut AppContext) {
     register_followable_item::<ChannelView>(cx)
@@ -42,6 +47,7 @@ pub struct ChannelView {
     channel_store: Model<ChannelStore>,
     channel_buffer: Model<ChannelBuffer>,
     remote_id: Option<ViewId>,
+    _code_comments: View<CodeComments>,
     _editor_event_subscription: Subscription,
     _reparse_subscription: Option<Subscription>,
 }
@@ -170,14 +176,26 @@ impl ChannelView {
             editor.set_custom_context_menu(move |_, position, cx| {
                 let this = this.clone();
                 Some(ui::ContextMenu::build(cx, move |menu, _| {
+                    let history_this = this.clone();
                     menu.entry("Copy link to section", None, move |cx| {
                         this.update(cx, |this, cx| this.copy_link_for_position(position, cx))
                             .ok();
                     })
+                    .action("Add comment", Box::new(AddComment))
+                    .entry("Show version history", None, move |cx| {
+                        history_this
+                            .update(cx, |this, cx| {
+                                this.open_notes_history(&OpenNotesHistory, cx)
+                            })
+                            .ok();
+                    })
                 }))
             });
             editor
         });
+        let code_comments = editor.update(cx, |editor, cx| {
+            CodeComments::attach(editor, Some(channel_buffer.clone()), cx)
+        });
         let _editor_event_subscription =
             cx.subscribe(&editor, |_, _, e: &EditorEvent, cx| cx.emit(e.clone()));
 
@@ -191,6 +209,7 @@ impl ChannelView {
             channel_store,
             channel_buffer,
             remote_id: None,
+            _code_comments: code_comments,
             _editor_event_subscription,
             _reparse_subscription: None,
         }
@@ -247,6 +266,26 @@ impl ChannelView {
         self.copy_link_for_position(position, cx)
     }
 
+    fn open_notes_history(&mut self, _: &OpenNotesHistory, cx: &mut ViewContext<Self>) {
+        let channel_id = self.channel_buffer.read(cx).channel_id;
+        let channel_buffer = self.channel_buffer.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let existing = workspace
+                    .active_pane()
+                    .read(cx)
+                    .items_of_type::<NotesHistoryView>()
+                    .find(|view| view.read(cx).channel_id(cx) == channel_id);
+                if let Some(existing) = existing {
+                    workspace.activate_item(&existing, cx);
+                } else {
+                    let view = cx.new_view(|cx| NotesHistoryView::new(channel_buffer, cx));
+                    workspace.add_item_to_active_pane(Box::new(view), cx);
+                }
+            })
+            .ok();
+    }
+
     fn copy_link_for_position(&self, position: DisplayPoint, cx: &mut ViewContext<Self>) {
         let snapshot = self.editor.update(cx, |editor, cx| editor.snapshot(cx));
 
@@ -289,7 +328,7 @@ impl ChannelView {
                 editor.set_read_only(true);
                 cx.notify();
             }),
-            ChannelBufferEvent::ChannelChanged => {
+            ChannelBufferEvent::ChannelChanged | ChannelBufferEvent::ConnectionChanged => {
                 self.editor.update(cx, |_, cx| {
                     cx.emit(editor::EditorEvent::TitleChanged);
                     cx.notify()
@@ -337,6 +376,7 @@ impl Render for ChannelView {
         div()
             .size_full()
             .on_action(cx.listener(Self::copy_link))
+            .on_action(cx.listener(Self::open_notes_history))
             .child(self.editor.clone())
     }
 }
@@ -367,12 +407,14 @@ impl Item for ChannelView {
 
     fn tab_content(&self, _: Option<usize>, selected: bool, cx: &WindowContext) -> AnyElement {
         let label = if let Some(channel) = self.channel(cx) {
+            let channel_buffer = self.channel_buffer.read(cx);
             match (
-                self.channel_buffer.read(cx).buffer().read(cx).read_only(),
-                self.channel_buffer.read(cx).is_connected(),
+                channel_buffer.buffer().read(cx).read_only(),
+                channel_buffer.is_connected(),
             ) {
                 (false, true) => format!("#{}", channel.name),
                 (true, true) => format!("#{} (read-only)", channel.name),
+                (_, false) if channel_buffer.is_offline() => format!("#{} (offline)", channel.name),
                 (_, false) => format!("#{} (disconnected)", channel.name),
             }
         } else {
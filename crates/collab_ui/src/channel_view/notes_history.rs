@@ -0,0 +1,378 @@
+use std::ops::Range;
+
+use channel::{ChannelBuffer, ChannelBufferCheckpoint, ChannelBufferEvent};
+use client::ChannelId;
+use collections::HashMap;
+use git::diff::BufferDiff;
+use gpui::{
+    uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, KeyContext,
+    Model, Render, Subscription, Task, ViewContext,
+};
+use language::{Point, TextBufferSnapshot};
+use menu::{SelectNext, SelectPrev};
+use theme::ActiveTheme;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::item::{Item, ItemEvent};
+
+use super::RestoreVersion;
+
+/// Shows the saved versions of a channel's notes, and how each differs from the notes' current
+/// contents.
+pub struct NotesHistoryView {
+    focus_handle: FocusHandle,
+    channel_buffer: Model<ChannelBuffer>,
+    checkpoints: Vec<ChannelBufferCheckpoint>,
+    checkpoint_texts: HashMap<u64, String>,
+    selected_index: usize,
+    diff: Vec<DiffLine>,
+    local_timezone: UtcOffset,
+    load_checkpoints_task: Task<()>,
+    update_diff_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DiffLineKind {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DiffLine {
+    kind: DiffLineKind,
+    text: SharedString,
+}
+
+impl NotesHistoryView {
+    pub fn new(channel_buffer: Model<ChannelBuffer>, cx: &mut ViewContext<Self>) -> Self {
+        let buffer = channel_buffer.read(cx).buffer();
+        let subscriptions = vec![
+            cx.subscribe(&buffer, |this, _, event, cx| {
+                if let language::Event::Edited = event {
+                    this.update_diff(cx);
+                }
+            }),
+            cx.subscribe(&channel_buffer, |_, _, event, cx| match event {
+                ChannelBufferEvent::Disconnected
+                | ChannelBufferEvent::ChannelChanged
+                | ChannelBufferEvent::ConnectionChanged => {
+                    cx.emit(ItemEvent::UpdateTab);
+                    cx.notify();
+                }
+                _ => {}
+            }),
+        ];
+
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            channel_buffer,
+            checkpoints: Vec::new(),
+            checkpoint_texts: HashMap::default(),
+            selected_index: 0,
+            diff: Vec::new(),
+            local_timezone: cx.local_timezone(),
+            load_checkpoints_task: Task::ready(()),
+            update_diff_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.load_checkpoints(cx);
+        this
+    }
+
+    pub fn channel_id(&self, cx: &AppContext) -> ChannelId {
+        self.channel_buffer.read(cx).channel_id
+    }
+
+    fn load_checkpoints(&mut self, cx: &mut ViewContext<Self>) {
+        let checkpoints = self
+            .channel_buffer
+            .update(cx, |channel_buffer, cx| channel_buffer.checkpoints(cx));
+        self.load_checkpoints_task = cx.spawn(|this, mut cx| async move {
+            let Some(checkpoints) = checkpoints.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                this.checkpoints = checkpoints;
+                this.selected_index = 0;
+                this.update_diff(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn select(&mut self, index: usize, cx: &mut ViewContext<Self>) {
+        if index < self.checkpoints.len() && index != self.selected_index {
+            self.selected_index = index;
+            self.update_diff(cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        self.select(self.selected_index + 1, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if let Some(index) = self.selected_index.checked_sub(1) {
+            self.select(index, cx);
+        }
+    }
+
+    fn update_diff(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(epoch) = self
+            .checkpoints
+            .get(self.selected_index)
+            .map(|checkpoint| checkpoint.epoch)
+        else {
+            self.diff.clear();
+            cx.notify();
+            return;
+        };
+
+        // Saved versions never change, so each one is only loaded once.
+        let base_text = match self.checkpoint_texts.get(&epoch) {
+            Some(text) => Task::ready(Ok(text.clone())),
+            None => self.channel_buffer.update(cx, |channel_buffer, cx| {
+                channel_buffer.load_checkpoint(epoch, cx)
+            }),
+        };
+        let snapshot = self
+            .channel_buffer
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .text_snapshot();
+        self.update_diff_task = cx.spawn(|this, mut cx| async move {
+            let Some(base_text) = base_text.await.log_err() else {
+                return;
+            };
+            let diff = cx
+                .background_executor()
+                .spawn({
+                    let base_text = base_text.clone();
+                    async move {
+                        let mut diff = BufferDiff::new();
+                        diff.update(&base_text, &snapshot).await;
+                        diff_lines(&base_text, &diff, &snapshot)
+                    }
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.checkpoint_texts.insert(epoch, base_text);
+                this.diff = diff;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn restore(&mut self, _: &RestoreVersion, cx: &mut ViewContext<Self>) {
+        let Some(checkpoint) = self.checkpoints.get(self.selected_index) else {
+            return;
+        };
+
+        let epoch = checkpoint.epoch;
+        self.channel_buffer
+            .update(cx, |channel_buffer, cx| {
+                channel_buffer.restore_checkpoint(epoch, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn render_checkpoints(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let now = OffsetDateTime::now_utc();
+        v_flex()
+            .id("notes-history-checkpoints")
+            .w(rems(16.))
+            .h_full()
+            .p_1()
+            .overflow_y_scroll()
+            .border_r_1()
+            .border_color(cx.theme().colors().border)
+            .children(self.checkpoints.iter().enumerate().map(|(ix, checkpoint)| {
+                let authors = checkpoint
+                    .authors
+                    .iter()
+                    .map(|author| author.github_login.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ListItem::new(ix)
+                    .inset(true)
+                    .spacing(ListItemSpacing::Sparse)
+                    .selected(ix == self.selected_index)
+                    .on_click(cx.listener(move |this, _, cx| this.select(ix, cx)))
+                    .child(
+                        v_flex()
+                            .w_full()
+                            .child(Label::new(time_format::format_localized_timestamp(
+                                checkpoint.created_at,
+                                now,
+                                self.local_timezone,
+                                time_format::TimestampFormat::EnhancedAbsolute,
+                            )))
+                            .when(!authors.is_empty(), |this| {
+                                this.child(
+                                    Label::new(authors)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+            }))
+    }
+
+    fn render_diff(&self, cx: &mut ViewContext<Self>) -> AnyElement {
+        if self.checkpoints.is_empty() {
+            return v_flex()
+                .size_full()
+                .items_center()
+                .justify_center()
+                .child(Label::new("No saved versions of these notes yet").color(Color::Muted))
+                .into_any_element();
+        }
+
+        let channel_buffer = self.channel_buffer.read(cx);
+        let can_restore =
+            channel_buffer.is_connected() && !channel_buffer.buffer().read(cx).read_only();
+        v_flex()
+            .size_full()
+            .child(
+                h_flex()
+                    .p_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        Label::new("Changes made since this version was saved").color(Color::Muted),
+                    )
+                    .child(
+                        Button::new("restore", "Restore")
+                            .disabled(!can_restore)
+                            .tooltip(|cx| {
+                                Tooltip::for_action("Restore this version", &RestoreVersion, cx)
+                            })
+                            .on_click(cx.listener(|this, _, cx| this.restore(&RestoreVersion, cx))),
+                    ),
+            )
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "notes-history-diff",
+                    self.diff.len(),
+                    |this, range: Range<usize>, cx| {
+                        let colors = cx.theme().status();
+                        this.diff[range]
+                            .iter()
+                            .map(|line| {
+                                let (prefix, background) = match line.kind {
+                                    DiffLineKind::Unchanged => (" ", None),
+                                    DiffLineKind::Added => ("+", Some(colors.created_background)),
+                                    DiffLineKind::Removed => ("-", Some(colors.deleted_background)),
+                                };
+                                h_flex()
+                                    .w_full()
+                                    .px_2()
+                                    .font("Zed Mono")
+                                    .when_some(background, |this, background| this.bg(background))
+                                    .child(Label::new(format!("{prefix} {}", line.text)))
+                            })
+                            .collect()
+                    },
+                )
+                .size_full(),
+            )
+            .into_any_element()
+    }
+}
+
+/// Lists the lines of the notes, interleaved with the lines of `base_text` that they replaced.
+fn diff_lines(base_text: &str, diff: &BufferDiff, snapshot: &TextBufferSnapshot) -> Vec<DiffLine> {
+    let line = |row: u32| DiffLine {
+        kind: DiffLineKind::Unchanged,
+        text: snapshot
+            .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+            .collect::<String>()
+            .into(),
+    };
+
+    let mut lines = Vec::new();
+    let mut row = 0;
+    for hunk in diff.hunks_in_row_range(0..u32::MAX, snapshot) {
+        lines.extend((row..hunk.associated_range.start).map(line));
+        lines.extend(
+            base_text[hunk.diff_base_byte_range.clone()]
+                .lines()
+                .map(|text| DiffLine {
+                    kind: DiffLineKind::Removed,
+                    text: text.to_string().into(),
+                }),
+        );
+        lines.extend(hunk.associated_range.clone().map(|row| DiffLine {
+            kind: DiffLineKind::Added,
+            ..line(row)
+        }));
+        row = hunk.associated_range.end;
+    }
+    lines.extend((row..=snapshot.max_point().row).map(line));
+    lines
+}
+
+impl EventEmitter<ItemEvent> for NotesHistoryView {}
+
+impl FocusableView for NotesHistoryView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for NotesHistoryView {
+    type Event = ItemEvent;
+
+    fn tab_content(
+        &self,
+        _detail: Option<usize>,
+        selected: bool,
+        cx: &WindowContext,
+    ) -> AnyElement {
+        let label = match self.channel_buffer.read(cx).channel(cx) {
+            Some(channel) => format!("History: #{}", channel.name),
+            None => "History: channel notes (disconnected)".to_string(),
+        };
+        Label::new(label)
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for NotesHistoryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut key_context = KeyContext::default();
+        key_context.add("NotesHistory");
+        key_context.add("menu");
+
+        h_flex()
+            .key_context(key_context)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::restore))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_checkpoints(cx))
+            .child(self.render_diff(cx))
+    }
+}
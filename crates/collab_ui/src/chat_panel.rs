@@ -1,55 +1,66 @@
 use crate::{collab_panel, ChatPanelSettings};
 use anyhow::Result;
 use call::{room, ActiveCall};
-use channel::{ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelStore};
+use channel::{
+    ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageEdit, ChannelMessageId,
+    ChannelStore,
+};
 use client::{ChannelId, Client};
+use code_reference::{CodeReference, CODE_REFERENCE_LINK_PREFIX};
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
-use editor::Editor;
+use editor::{actions::ShareSelectionToChat, scroll::Autoscroll, Editor, MultiBuffer};
 use gpui::{
-    actions, div, list, prelude::*, px, Action, AppContext, AsyncWindowContext, ClipboardItem,
-    CursorStyle, DismissEvent, ElementId, EventEmitter, FocusHandle, FocusableView, FontWeight,
-    ListOffset, ListScrollEvent, ListState, Model, Render, Subscription, Task, View, ViewContext,
-    VisualContext, WeakView,
+    actions, div, list, prelude::*, px, Action, AnyElement, AppContext, AsyncWindowContext,
+    ClipboardItem, CursorStyle, DismissEvent, ElementId, EventEmitter, FocusHandle, FocusableView,
+    FontWeight, ListOffset, ListScrollEvent, ListState, Model, Render, Subscription, Task, View,
+    ViewContext, VisualContext, WeakView,
 };
-use language::LanguageRegistry;
+use language::{Bias, Buffer, BufferId, LanguageRegistry, Point};
 use menu::Confirm;
 use message_editor::MessageEditor;
-use project::Fs;
+use project::{Fs, Item as _, Project, ProjectPath};
 use rich_text::RichText;
+use rpc::MAX_CHANNEL_MESSAGE_LEN;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use std::{sync::Arc, time::Duration};
 use time::{OffsetDateTime, UtcOffset};
 use ui::{
-    popover_menu, prelude::*, Avatar, Button, ContextMenu, IconButton, IconName, KeyBinding, Label,
-    TabBar, Tooltip,
+    popover_menu, prelude::*, Avatar, Button, ButtonStyle, ContextMenu, IconButton, IconName,
+    KeyBinding, Label, TabBar, Tooltip,
 };
 use util::{ResultExt, TryFutureExt};
 use workspace::{
     dock::{DockPosition, Panel, PanelEvent},
-    Workspace,
+    notifications::DetachAndPromptErr,
+    Toast, Workspace,
 };
 
+mod code_reference;
 mod message_editor;
 
 const MESSAGE_LOADING_THRESHOLD: usize = 50;
 const CHAT_PANEL_KEY: &str = "ChatPanel";
+const REACTION_EMOJIS: &[&str] = &["👍", "👎", "😄", "🎉", "❤️", "👀"];
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(|workspace: &mut Workspace, _| {
         workspace.register_action(|workspace, _: &ToggleFocus, cx| {
             workspace.toggle_panel_focus::<ChatPanel>(cx);
         });
+        workspace.register_action(ChatPanel::share_selection_to_chat);
     })
     .detach();
 }
 
 pub struct ChatPanel {
+    workspace: WeakView<Workspace>,
     client: Arc<Client>,
     channel_store: Model<ChannelStore>,
     languages: Arc<LanguageRegistry>,
     message_list: ListState,
+    thread_list: ListState,
     active_chat: Option<(Model<ChannelChat>, Subscription)>,
     message_editor: View<MessageEditor>,
     local_timezone: UtcOffset,
@@ -65,6 +76,11 @@ pub struct ChatPanel {
     highlighted_message: Option<(u64, Task<()>)>,
     last_acknowledged_message_id: Option<u64>,
     selected_message_to_reply_id: Option<u64>,
+    editing_message_id: Option<u64>,
+    message_edits: HashMap<u64, Vec<ChannelMessageEdit>>,
+    reply_counts: HashMap<u64, usize>,
+    open_thread: Option<u64>,
+    thread_message_ixs: Vec<usize>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -80,6 +96,7 @@ impl ChatPanel {
         let client = workspace.app_state().client.clone();
         let channel_store = ChannelStore::global(cx);
         let languages = workspace.app_state().languages.clone();
+        let workspace_handle = workspace.weak_handle();
 
         let input_editor = cx.new_view(|cx| {
             MessageEditor::new(
@@ -103,6 +120,18 @@ impl ChatPanel {
                     }
                 });
 
+            let view = cx.view().downgrade();
+            let thread_list =
+                ListState::new(0, gpui::ListAlignment::Bottom, px(1000.), move |ix, cx| {
+                    if let Some(view) = view.upgrade() {
+                        view.update(cx, |view, cx| {
+                            view.render_thread_message(ix, cx).into_any_element()
+                        })
+                    } else {
+                        div().into_any()
+                    }
+                });
+
             message_list.set_scroll_handler(cx.listener(|this, event: &ListScrollEvent, cx| {
                 if event.visible_range.start < MESSAGE_LOADING_THRESHOLD {
                     this.load_more_messages(cx);
@@ -111,11 +140,13 @@ impl ChatPanel {
             }));
 
             let mut this = Self {
+                workspace: workspace_handle,
                 fs,
                 client,
                 channel_store,
                 languages,
                 message_list,
+                thread_list,
                 active_chat: Default::default(),
                 pending_serialization: Task::ready(None),
                 message_editor: input_editor,
@@ -130,6 +161,11 @@ impl ChatPanel {
                 highlighted_message: None,
                 last_acknowledged_message_id: None,
                 selected_message_to_reply_id: None,
+                editing_message_id: None,
+                message_edits: Default::default(),
+                reply_counts: Default::default(),
+                open_thread: None,
+                thread_message_ixs: Vec::new(),
             };
 
             if let Some(channel_id) = ActiveCall::global(cx)
@@ -236,8 +272,12 @@ impl ChatPanel {
             let channel_id = chat.read(cx).channel_id;
             {
                 self.markdown_data.clear();
+                self.message_edits.clear();
+                self.editing_message_id = None;
+                self.open_thread = None;
                 let chat = chat.read(cx);
                 self.message_list.reset(chat.message_count());
+                self.reply_counts = chat.reply_counts();
 
                 let channel_name = chat.channel(cx).map(|channel| channel.name.clone());
                 self.message_editor.update(cx, |editor, cx| {
@@ -264,10 +304,24 @@ impl ChatPanel {
                 new_count,
             } => {
                 self.message_list.splice(old_range.clone(), *new_count);
+                self.refresh_threads(cx);
                 if self.active {
                     self.acknowledge_last_message(cx);
                 }
             }
+            ChannelChatEvent::UpdateMessage {
+                message_id,
+                message_ix,
+            } => {
+                self.markdown_data.remove(message_id);
+                self.message_list.splice(*message_ix..*message_ix + 1, 1);
+                if let ChannelMessageId::Saved(id) = *message_id {
+                    if self.message_edits.contains_key(&id) {
+                        self.load_edit_history(id, cx);
+                    }
+                }
+                self.refresh_threads(cx);
+            }
             ChannelChatEvent::NewMessage {
                 channel_id,
                 message_id,
@@ -378,7 +432,26 @@ impl ChatPanel {
         )
     }
 
-    fn render_message(&mut self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+    fn render_message(&mut self, ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        self.render_chat_message(ix, ix.saturating_sub(1), cx)
+    }
+
+    fn render_thread_message(&mut self, ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        match self.thread_message_ixs.get(ix) {
+            Some(&message_ix) => {
+                let last_message_ix = self.thread_message_ixs[ix.saturating_sub(1)];
+                self.render_chat_message(message_ix, last_message_ix, cx)
+            }
+            None => div().into_any_element(),
+        }
+    }
+
+    fn render_chat_message(
+        &mut self,
+        ix: usize,
+        last_ix: usize,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
         let active_chat = &self.active_chat.as_ref().unwrap().0;
         let (message, is_continuation_from_previous, is_admin) =
             active_chat.update(cx, |active_chat, cx| {
@@ -387,7 +460,7 @@ impl ChatPanel {
                     .read(cx)
                     .is_channel_admin(active_chat.channel_id);
 
-                let last_message = active_chat.message(ix.saturating_sub(1));
+                let last_message = active_chat.message(last_ix);
                 let this_message = active_chat.message(ix).clone();
 
                 let duration_since_last_message = this_message.timestamp - last_message.timestamp;
@@ -437,6 +510,11 @@ impl ChatPanel {
         let replied_to_you =
             reply_to_message.as_ref().map(|m| m.sender.id) == self.client.user_id();
 
+        let reply_count = message_id
+            .filter(|_| self.open_thread.is_none())
+            .and_then(|id| self.reply_counts.get(&id).copied())
+            .unwrap_or(0);
+
         let is_highlighted_message = self
             .highlighted_message
             .as_ref()
@@ -517,6 +595,8 @@ impl ChatPanel {
                     )
                     .when(mentioning_you || replied_to_you, |this| this.my_0p5())
                     .map(|el| {
+                        let workspace = self.workspace.clone();
+                        let body = message.body.clone();
                         let text = self.markdown_data.entry(message.id).or_insert_with(|| {
                             Self::render_markdown_with_mentions(
                                 &self.languages,
@@ -529,11 +609,52 @@ impl ChatPanel {
                                 .w_full()
                                 .text_ui_sm()
                                 .id(element_id)
-                                .child(text.element("body".into(), cx)),
+                                .child(text.element_with_link_handler(
+                                    "body".into(),
+                                    move |url, cx| {
+                                        Self::open_code_reference_link(&workspace, url, &body, cx)
+                                    },
+                                    cx,
+                                ))
+                                .when(message.is_edited(), |el| {
+                                    el.child(
+                                        Label::new("(edited)")
+                                            .size(LabelSize::XSmall)
+                                            .color(Color::Muted),
+                                    )
+                                }),
                         )
                         .when(self.has_open_menu(message_id), |el| {
                             el.bg(cx.theme().colors().element_selected)
                         })
+                    })
+                    .when_some(
+                        message_id.and_then(|id| self.message_edits.get(&id)),
+                        |el, edits| el.child(self.render_edit_history(edits)),
+                    )
+                    .when_some(message_id, |el, message_id| {
+                        el.when(!message.reactions.is_empty(), |el| {
+                            el.child(self.render_reactions(message_id, &message, cx))
+                        })
+                        .when(reply_count > 0, |el| {
+                            el.child(
+                                Button::new(
+                                    ("open-thread", message_id),
+                                    if reply_count == 1 {
+                                        "1 reply".to_string()
+                                    } else {
+                                        format!("{reply_count} replies")
+                                    },
+                                )
+                                .label_size(LabelSize::XSmall)
+                                .color(Color::Accent)
+                                .on_click(
+                                    cx.listener(move |this, _, cx| {
+                                        this.open_thread(message_id, cx)
+                                    }),
+                                ),
+                            )
+                        })
                     }),
             )
             .when(
@@ -562,6 +683,62 @@ impl ChatPanel {
                 self.render_popover_buttons(&cx, message_id, can_delete_message)
                     .neg_mt_2p5(),
             )
+            .into_any_element()
+    }
+
+    fn render_edit_history(&self, edits: &[ChannelMessageEdit]) -> impl IntoElement {
+        v_flex()
+            .my_0p5()
+            .pl_2()
+            .gap_0p5()
+            .child(
+                Label::new("Edit history")
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted),
+            )
+            .children(edits.iter().map(|edit| {
+                v_flex()
+                    .child(
+                        Label::new(time_format::format_localized_timestamp(
+                            edit.edited_at,
+                            OffsetDateTime::now_utc(),
+                            self.local_timezone,
+                            time_format::TimestampFormat::EnhancedAbsolute,
+                        ))
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                    )
+                    .child(Label::new(edit.body.clone()).size(LabelSize::Small))
+            }))
+    }
+
+    fn render_reactions(
+        &self,
+        message_id: u64,
+        message: &ChannelMessage,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let current_user_id = self.client.user_id();
+        h_flex()
+            .id(("reactions", message_id))
+            .flex_wrap()
+            .gap_1()
+            .my_0p5()
+            .children(message.reactions.iter().enumerate().map(|(ix, reaction)| {
+                let has_reacted =
+                    current_user_id.map_or(false, |user_id| reaction.user_ids.contains(&user_id));
+                let emoji = reaction.emoji.clone();
+                Button::new(
+                    ("reaction", ix),
+                    format!("{} {}", reaction.emoji, reaction.user_ids.len()),
+                )
+                .style(ButtonStyle::Filled)
+                .label_size(LabelSize::XSmall)
+                .selected(has_reacted)
+                .on_click(cx.listener(move |this, _, cx| {
+                    this.toggle_reaction(message_id, emoji.clone(), cx)
+                }))
+            }))
     }
 
     fn has_open_menu(&self, message_id: Option<u64>) -> bool {
@@ -667,37 +844,90 @@ impl ChatPanel {
         can_delete_message: bool,
         cx: &mut WindowContext,
     ) -> View<ContextMenu> {
+        let message = this.read(cx).active_chat().and_then(|active_chat| {
+            active_chat
+                .read(cx)
+                .find_loaded_message(message_id)
+                .cloned()
+        });
+        let can_edit_message = message.as_ref().map_or(false, |message| {
+            Some(message.sender.id) == this.read(cx).client.user_id()
+        });
+        let is_edited = message.map_or(false, |message| message.is_edited());
+        let is_showing_edit_history = this.read(cx).message_edits.contains_key(&message_id);
+
         let menu = {
             ContextMenu::build(cx, move |menu, cx| {
-                menu.entry(
-                    "Reply to message",
-                    None,
-                    cx.handler_for(&this, move |this, cx| {
-                        this.selected_message_to_reply_id = Some(message_id);
-
-                        this.message_editor.update(cx, |editor, cx| {
-                            editor.set_reply_to_message_id(message_id);
-                            editor.focus_handle(cx).focus(cx);
-                        })
-                    }),
-                )
-                .entry(
-                    "Copy message text",
-                    None,
-                    cx.handler_for(&this, move |this, cx| {
-                        if let Some(message) = this.active_chat().and_then(|active_chat| {
-                            active_chat.read(cx).find_loaded_message(message_id)
-                        }) {
-                            let text = message.body.clone();
-                            cx.write_to_clipboard(ClipboardItem::new(text))
-                        }
-                    }),
-                )
-                .when(can_delete_message, move |menu| {
+                let menu = menu
+                    .entry(
+                        "Reply to message",
+                        None,
+                        cx.handler_for(&this, move |this, cx| {
+                            this.selected_message_to_reply_id = Some(message_id);
+
+                            this.message_editor.update(cx, |editor, cx| {
+                                editor.set_reply_to_message_id(message_id);
+                                editor.focus_handle(cx).focus(cx);
+                            })
+                        }),
+                    )
+                    .entry(
+                        "Copy message text",
+                        None,
+                        cx.handler_for(&this, move |this, cx| {
+                            if let Some(message) = this.active_chat().and_then(|active_chat| {
+                                active_chat.read(cx).find_loaded_message(message_id)
+                            }) {
+                                let text = message.body.clone();
+                                cx.write_to_clipboard(ClipboardItem::new(text))
+                            }
+                        }),
+                    )
+                    .entry(
+                        "Open thread",
+                        None,
+                        cx.handler_for(&this, move |this, cx| this.open_thread(message_id, cx)),
+                    )
+                    .when(can_edit_message, move |menu| {
+                        menu.entry(
+                            "Edit message",
+                            None,
+                            cx.handler_for(&this, move |this, cx| {
+                                this.start_editing_message(message_id, cx)
+                            }),
+                        )
+                    })
+                    .when(is_edited, move |menu| {
+                        menu.entry(
+                            if is_showing_edit_history {
+                                "Hide edit history"
+                            } else {
+                                "Show edit history"
+                            },
+                            None,
+                            cx.handler_for(&this, move |this, cx| {
+                                this.toggle_edit_history(message_id, cx)
+                            }),
+                        )
+                    })
+                    .when(can_delete_message, move |menu| {
+                        menu.entry(
+                            "Delete message",
+                            None,
+                            cx.handler_for(&this, move |this, cx| {
+                                this.remove_message(message_id, cx)
+                            }),
+                        )
+                    })
+                    .separator()
+                    .header("Add reaction");
+                REACTION_EMOJIS.iter().fold(menu, |menu, emoji| {
                     menu.entry(
-                        "Delete message",
+                        *emoji,
                         None,
-                        cx.handler_for(&this, move |this, cx| this.remove_message(message_id, cx)),
+                        cx.handler_for(&this, move |this, cx| {
+                            this.toggle_reaction(message_id, emoji.to_string(), cx)
+                        }),
                     )
                 })
             })
@@ -728,14 +958,222 @@ impl ChatPanel {
         rich_text::render_rich_text(message.body.clone(), &mentions, language_registry, None)
     }
 
+    fn share_selection_to_chat(
+        workspace: &mut Workspace,
+        _: &ShareSelectionToChat,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let Some(reference) = workspace
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| Self::code_reference_for_selection(&editor, &project, cx))
+        else {
+            workspace.show_toast(
+                Toast::new(0x3c1e7a2b, "Select lines in a project file to share them"),
+                cx,
+            );
+            return;
+        };
+
+        let Some(chat) = workspace
+            .panel::<ChatPanel>(cx)
+            .and_then(|panel| panel.read(cx).active_chat())
+        else {
+            workspace.show_toast(
+                Toast::new(0x3c1e7a2b, "Open a channel's chat to share code in it"),
+                cx,
+            );
+            return;
+        };
+
+        let Some(message) = reference.to_markdown_within(MAX_CHANNEL_MESSAGE_LEN) else {
+            workspace.show_toast(
+                Toast::new(
+                    0x3c1e7a2b,
+                    "The path of this file is too long to share in chat",
+                ),
+                cx,
+            );
+            return;
+        };
+        match chat.update(cx, |chat, cx| {
+            chat.send_message(message.as_str().into(), cx)
+        }) {
+            Ok(task) => task.detach_and_prompt_err("Failed to share code", cx, |_, _| None),
+            Err(error) => {
+                workspace.show_error(&error, cx);
+                return;
+            }
+        }
+        workspace.open_panel::<ChatPanel>(cx);
+    }
+
+    fn code_reference_for_selection(
+        editor: &View<Editor>,
+        project: &Model<Project>,
+        cx: &AppContext,
+    ) -> Option<CodeReference> {
+        let editor = editor.read(cx);
+        let buffer = editor.buffer().read(cx).as_singleton()?;
+        let buffer = buffer.read(cx);
+        let project_path = buffer.project_path(cx)?;
+        let worktree = project
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)?;
+
+        let selection = editor.selections.newest::<Point>(cx);
+        let start_row = selection.start.row;
+        let mut end_row = selection.end.row;
+        if end_row > start_row && selection.end.column == 0 {
+            end_row -= 1;
+        }
+        let mut snapshot = buffer
+            .text_for_range(Point::new(start_row, 0)..Point::new(end_row, buffer.line_len(end_row)))
+            .collect::<String>();
+        snapshot.push('\n');
+
+        let sha = project
+            .read(cx)
+            .get_repo(&project_path, cx)
+            .and_then(|repo| repo.lock().head_sha());
+
+        Some(CodeReference {
+            worktree_root_name: worktree.read(cx).root_name().to_string(),
+            path: project_path.path.to_path_buf(),
+            rows: start_row..=end_row,
+            sha,
+            language_name: buffer
+                .language()
+                .map(|language| language.name().to_string()),
+            snapshot,
+        })
+    }
+
+    fn open_code_reference_link(
+        workspace: &WeakView<Workspace>,
+        url: &str,
+        body: &str,
+        cx: &mut WindowContext,
+    ) -> bool {
+        if !url.starts_with(CODE_REFERENCE_LINK_PREFIX) {
+            return false;
+        }
+        if let Some(reference) = CodeReference::parse_with_link(body, url) {
+            workspace
+                .update(cx, |workspace, cx| {
+                    Self::open_code_reference(workspace, reference, cx)
+                })
+                .log_err();
+        }
+        true
+    }
+
+    /// Opens the referenced lines if the file is in one of the workspace's worktrees, checked
+    /// out at the commit the reference was shared from, or shows the snapshot that was shared
+    /// with the reference otherwise.
+    fn open_code_reference(
+        workspace: &mut Workspace,
+        reference: CodeReference,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().read(cx);
+        let worktree_id = project.visible_worktrees(cx).find_map(|worktree| {
+            let worktree = worktree.read(cx);
+            if worktree.root_name() != reference.worktree_root_name
+                || worktree.entry_for_path(&reference.path).is_none()
+            {
+                return None;
+            }
+
+            // Worktrees are matched by name, so only trust one that has the same commit checked
+            // out as the one the reference was shared from.
+            let project_path = ProjectPath {
+                worktree_id: worktree.id(),
+                path: reference.path.as_path().into(),
+            };
+            let head_sha = project
+                .get_repo(&project_path, cx)
+                .and_then(|repo| repo.lock().head_sha());
+            (reference.sha.is_some() && head_sha == reference.sha).then_some(worktree.id())
+        });
+
+        if let Some(worktree_id) = worktree_id {
+            let open_item =
+                workspace.open_path((worktree_id, reference.path.clone()), None, true, cx);
+            let rows = reference.rows;
+            cx.spawn(|_, mut cx| async move {
+                let item = open_item.await?;
+                if let Some(editor) = item.downcast::<Editor>() {
+                    editor.update(&mut cx, |editor, cx| {
+                        let snapshot = editor.buffer().read(cx).snapshot(cx);
+                        let start = Point::new(*rows.start(), 0);
+                        let end =
+                            snapshot.clip_point(Point::new(*rows.end(), u32::MAX), Bias::Left);
+                        editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                            selections.select_ranges([start..end])
+                        });
+                    })?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        } else {
+            let languages = workspace.app_state().languages.clone();
+            let title = format!(
+                "{}/{}",
+                reference.worktree_root_name,
+                reference.path.to_string_lossy()
+            );
+            cx.spawn(|workspace, mut cx| async move {
+                let language = match &reference.language_name {
+                    Some(name) => languages.language_for_name(name).await.log_err(),
+                    None => None,
+                };
+                workspace.update(&mut cx, |workspace, cx| {
+                    let buffer = cx.new_model(|cx| {
+                        let mut buffer = Buffer::new(
+                            0,
+                            BufferId::new(cx.entity_id().as_u64()).unwrap(),
+                            reference.snapshot,
+                        );
+                        buffer.set_language(language, cx);
+                        buffer
+                    });
+                    let buffer =
+                        cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+                    let editor = cx.new_view(|cx| {
+                        let mut editor = Editor::for_multibuffer(buffer, None, cx);
+                        editor.set_read_only(true);
+                        editor
+                    });
+                    workspace.add_item_to_active_pane(Box::new(editor), cx);
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
     fn send(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
         self.selected_message_to_reply_id = None;
 
         if let Some((chat, _)) = self.active_chat.as_ref() {
-            let message = self
+            let mut message = self
                 .message_editor
                 .update(cx, |editor, cx| editor.take_message(cx));
 
+            if let Some(message_id) = self.editing_message_id.take() {
+                if let Some(task) = chat
+                    .update(cx, |chat, cx| chat.update_message(message_id, message, cx))
+                    .log_err()
+                {
+                    task.detach_and_log_err(cx);
+                }
+                return;
+            }
+
+            if message.reply_to_message_id.is_none() {
+                message.reply_to_message_id = self.open_thread;
+            }
             if let Some(task) = chat
                 .update(cx, |chat, cx| chat.send_message(message, cx))
                 .log_err()
@@ -745,6 +1183,102 @@ impl ChatPanel {
         }
     }
 
+    fn start_editing_message(&mut self, message_id: u64, cx: &mut ViewContext<Self>) {
+        let Some(message) = self.active_chat().and_then(|active_chat| {
+            active_chat
+                .read(cx)
+                .find_loaded_message(message_id)
+                .cloned()
+        }) else {
+            return;
+        };
+
+        self.selected_message_to_reply_id = None;
+        self.editing_message_id = Some(message_id);
+        self.message_editor.update(cx, |editor, cx| {
+            editor.clear_reply_to_message_id();
+            editor.set_text(&message.body, cx);
+            editor.focus_handle(cx).focus(cx);
+        });
+        cx.notify();
+    }
+
+    fn toggle_edit_history(&mut self, message_id: u64, cx: &mut ViewContext<Self>) {
+        if self.message_edits.remove(&message_id).is_some() {
+            cx.notify();
+        } else {
+            self.load_edit_history(message_id, cx);
+        }
+    }
+
+    fn load_edit_history(&mut self, message_id: u64, cx: &mut ViewContext<Self>) {
+        let Some((chat, _)) = self.active_chat.as_ref() else {
+            return;
+        };
+        let edits = chat.update(cx, |chat, cx| chat.load_message_edits(message_id, cx));
+        cx.spawn(|this, mut cx| async move {
+            let edits = edits.await?;
+            this.update(&mut cx, |this, cx| {
+                this.message_edits.insert(message_id, edits);
+                this.refresh_message(message_id, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn toggle_reaction(&mut self, message_id: u64, emoji: String, cx: &mut ViewContext<Self>) {
+        if let Some((chat, _)) = self.active_chat.as_ref() {
+            chat.update(cx, |chat, cx| chat.toggle_reaction(message_id, emoji, cx))
+                .detach_and_log_err(cx);
+        }
+    }
+
+    /// Re-renders a message whose appearance depends on state kept by the panel.
+    fn refresh_message(&mut self, message_id: u64, cx: &mut ViewContext<Self>) {
+        if let Some(chat) = self.active_chat() {
+            let ix = chat
+                .read(cx)
+                .messages()
+                .iter()
+                .position(|message| message.id == ChannelMessageId::Saved(message_id));
+            if let Some(ix) = ix {
+                self.message_list.splice(ix..ix + 1, 1);
+                self.refresh_threads(cx);
+            }
+        }
+        cx.notify();
+    }
+
+    fn open_thread(&mut self, message_id: u64, cx: &mut ViewContext<Self>) {
+        self.open_thread = Some(message_id);
+        self.refresh_threads(cx);
+        self.message_editor
+            .update(cx, |editor, cx| editor.focus_handle(cx).focus(cx));
+        cx.notify();
+    }
+
+    fn close_thread(&mut self, cx: &mut ViewContext<Self>) {
+        self.open_thread = None;
+        self.thread_message_ixs.clear();
+        self.thread_list.reset(0);
+        cx.notify();
+    }
+
+    fn refresh_threads(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(chat) = self.active_chat() else {
+            return;
+        };
+        let chat = chat.read(cx);
+        self.reply_counts = chat.reply_counts();
+        if let Some(root_id) = self.open_thread {
+            self.thread_message_ixs = chat.thread_message_ixs(root_id);
+            if self.thread_message_ixs.is_empty() {
+                self.open_thread = None;
+            }
+            self.thread_list.reset(self.thread_message_ixs.len());
+        }
+    }
+
     fn remove_message(&mut self, id: u64, cx: &mut ViewContext<Self>) {
         if let Some((chat, _)) = self.active_chat.as_ref() {
             chat.update(cx, |chat, cx| chat.remove_message(id, cx).detach())
@@ -827,8 +1361,50 @@ impl ChatPanel {
 
     fn close_reply_preview(&mut self, _: &CloseReplyPreview, cx: &mut ViewContext<Self>) {
         self.selected_message_to_reply_id = None;
-        self.message_editor
-            .update(cx, |editor, _| editor.clear_reply_to_message_id());
+        let was_editing = self.editing_message_id.take().is_some();
+        self.message_editor.update(cx, |editor, cx| {
+            editor.clear_reply_to_message_id();
+            if was_editing {
+                editor.set_text("", cx);
+            }
+        });
+        cx.notify();
+    }
+
+    fn render_thread_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .justify_between()
+            .py_1()
+            .px_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new("Thread").size(LabelSize::Small))
+            .child(
+                IconButton::new("close-thread", IconName::Close)
+                    .shape(ui::IconButtonShape::Square)
+                    .tooltip(|cx| Tooltip::text("Close thread", cx))
+                    .on_click(cx.listener(|this, _, cx| this.close_thread(cx))),
+            )
+    }
+
+    fn render_editing_preview(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .when(!self.is_scrolled_to_bottom, |el| {
+                el.border_t_1().border_color(cx.theme().colors().border)
+            })
+            .justify_between()
+            .py_1()
+            .px_2()
+            .bg(cx.theme().colors().background)
+            .child(Label::new("Editing message").size(LabelSize::Small))
+            .child(
+                IconButton::new("cancel-edit", IconName::Close)
+                    .shape(ui::IconButtonShape::Square)
+                    .tooltip(|cx| Tooltip::for_action("Cancel edit", &CloseReplyPreview, cx))
+                    .on_click(
+                        cx.listener(|_, _, cx| cx.dispatch_action(CloseReplyPreview.boxed_clone())),
+                    ),
+            )
     }
 }
 
@@ -859,8 +1435,13 @@ impl Render for ChatPanel {
                     ),
                 ),
             )
+            .when(self.open_thread.is_some(), |el| {
+                el.child(self.render_thread_header(cx))
+            })
             .child(div().flex_grow().px_2().map(|this| {
-                if self.active_chat.is_some() {
+                if self.active_chat.is_some() && self.open_thread.is_some() {
+                    this.child(list(self.thread_list.clone()).size_full())
+                } else if self.active_chat.is_some() {
                     this.child(list(self.message_list.clone()).size_full())
                 } else {
                     this.child(
@@ -944,13 +1525,18 @@ impl Render for ChatPanel {
                     )
                 })
             })
+            .when(self.editing_message_id.is_some(), |el| {
+                el.child(self.render_editing_preview(cx))
+            })
             .children(
                 Some(
                     h_flex()
                         .key_context("MessageEditor")
                         .on_action(cx.listener(ChatPanel::close_reply_preview))
                         .when(
-                            !self.is_scrolled_to_bottom && reply_to_message_id.is_none(),
+                            !self.is_scrolled_to_bottom
+                                && reply_to_message_id.is_none()
+                                && self.editing_message_id.is_none(),
                             |el| el.border_t_1().border_color(cx.theme().colors().border),
                         )
                         .p_2()
@@ -1056,6 +1642,8 @@ mod tests {
             nonce: 5,
             mentions: vec![(ranges[0].clone(), 101), (ranges[1].clone(), 102)],
             reply_to_message_id: None,
+            edited_at: None,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(&language_registry, 102, &message);
@@ -1103,6 +1691,8 @@ mod tests {
             nonce: 5,
             mentions: Vec::new(),
             reply_to_message_id: None,
+            edited_at: None,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(&language_registry, 102, &message);
@@ -1143,6 +1733,8 @@ mod tests {
             nonce: 5,
             mentions: Vec::new(),
             reply_to_message_id: None,
+            edited_at: None,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(&language_registry, 102, &message);
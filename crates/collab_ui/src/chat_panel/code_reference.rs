@@ -0,0 +1,275 @@
+use std::{ops::RangeInclusive, path::PathBuf};
+
+/// The prefix of the links that chat messages use to point at a location in a project.
+pub const CODE_REFERENCE_LINK_PREFIX: &str = "code-reference:";
+
+/// A range of lines in a project file, shared to a channel chat together with a snapshot of
+/// those lines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeReference {
+    /// The root name of the worktree that contains the file.
+    pub worktree_root_name: String,
+    /// The path of the file, relative to the root of its worktree.
+    pub path: PathBuf,
+    /// The zero-based rows spanned by the reference.
+    pub rows: RangeInclusive<u32>,
+    /// The commit that was checked out when the reference was shared.
+    pub sha: Option<String>,
+    /// The name of the file's language, used to highlight the snapshot.
+    pub language_name: Option<String>,
+    /// The contents of the referenced lines when the reference was shared.
+    pub snapshot: String,
+}
+
+impl CodeReference {
+    /// The body of a chat message that shares this reference: a link to the location,
+    /// followed by the snapshot in a fenced code block.
+    pub fn to_markdown(&self) -> String {
+        let location = format!(
+            "{}/{}",
+            self.worktree_root_name,
+            self.path.to_string_lossy()
+        );
+        let lines = if self.rows.start() == self.rows.end() {
+            format!("{}", self.rows.start() + 1)
+        } else {
+            format!("{}-{}", self.rows.start() + 1, self.rows.end() + 1)
+        };
+
+        let mut link = format!(
+            "{CODE_REFERENCE_LINK_PREFIX}{location}#L{}-L{}",
+            self.rows.start() + 1,
+            self.rows.end() + 1
+        );
+        if let Some(sha) = &self.sha {
+            link.push('@');
+            link.push_str(sha);
+        }
+
+        let mut markdown = format!("[`{location}:{lines}`](<{link}>)");
+        if let Some(sha) = &self.sha {
+            markdown.push_str(&format!(" at `{}`", &sha[..sha.len().min(7)]));
+        }
+
+        // The fence must be longer than any run of backticks in the snapshot.
+        let mut longest_backtick_run = 0;
+        let mut backtick_run = 0;
+        for c in self.snapshot.chars() {
+            if c == '`' {
+                backtick_run += 1;
+                longest_backtick_run = longest_backtick_run.max(backtick_run);
+            } else {
+                backtick_run = 0;
+            }
+        }
+        let fence = "`".repeat(3.max(longest_backtick_run + 1));
+
+        markdown.push_str("\n\n");
+        markdown.push_str(&fence);
+        markdown.push_str(self.language_name.as_deref().unwrap_or(""));
+        markdown.push('\n');
+        markdown.push_str(&self.snapshot);
+        if !self.snapshot.ends_with('\n') {
+            markdown.push('\n');
+        }
+        markdown.push_str(&fence);
+        markdown
+    }
+
+    /// Like [`Self::to_markdown`], but drops lines from the end of the snapshot, replacing them
+    /// with an ellipsis, until the message is at most `max_len` bytes long. Returns `None` if the
+    /// message doesn't fit even without a snapshot.
+    pub fn to_markdown_within(&self, max_len: usize) -> Option<String> {
+        let markdown = self.to_markdown();
+        if markdown.len() <= max_len {
+            return Some(markdown);
+        }
+
+        let mut reference = self.clone();
+        let mut end = self.snapshot.len().min(max_len);
+        while !self.snapshot.is_char_boundary(end) {
+            end -= 1;
+        }
+        loop {
+            end = self.snapshot[..end].rfind('\n').map_or(0, |ix| ix + 1);
+            reference.snapshot = format!("{}…\n", &self.snapshot[..end]);
+            let markdown = reference.to_markdown();
+            if markdown.len() <= max_len {
+                return Some(markdown);
+            } else if end == 0 {
+                return None;
+            }
+            end -= 1;
+        }
+    }
+
+    /// Parses the reference with the given link in a message that may contain several of them.
+    pub fn parse_with_link(markdown: &str, link: &str) -> Option<Self> {
+        let link_start = markdown.find(&format!("(<{link}>)"))?;
+        Self::parse(&markdown[link_start..])
+    }
+
+    /// Parses the body of a message that was created with [`Self::to_markdown`].
+    pub fn parse(markdown: &str) -> Option<Self> {
+        let link_start = markdown.find(&format!("(<{CODE_REFERENCE_LINK_PREFIX}"))? + 2;
+        let link_end = link_start + markdown[link_start..].find(">)")?;
+        let link = &markdown[link_start..link_end];
+        let (worktree_root_name, path, rows, sha) = Self::parse_link(link)?;
+
+        let mut lines = markdown[link_end..]
+            .lines()
+            .skip_while(|line| !line.starts_with("```"));
+        let opening_fence = lines.next()?;
+        let fence_len = opening_fence.chars().take_while(|c| *c == '`').count();
+        let fence = &opening_fence[..fence_len];
+        let language_name = Some(opening_fence[fence_len..].trim())
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+        let mut snapshot = String::new();
+        for line in lines.take_while(|line| *line != fence) {
+            snapshot.push_str(line);
+            snapshot.push('\n');
+        }
+
+        Some(Self {
+            worktree_root_name,
+            path,
+            rows,
+            sha,
+            language_name,
+            snapshot,
+        })
+    }
+
+    fn parse_link(link: &str) -> Option<(String, PathBuf, RangeInclusive<u32>, Option<String>)> {
+        let location = link.strip_prefix(CODE_REFERENCE_LINK_PREFIX)?;
+        let (location, sha) = match location.rsplit_once('@') {
+            Some((location, sha)) if sha.chars().all(|c| c.is_ascii_hexdigit()) => {
+                (location, Some(sha.to_string()))
+            }
+            _ => (location, None),
+        };
+        let (path, rows) = location.rsplit_once("#L")?;
+        let (start, end) = rows.split_once("-L")?;
+        let start = start.parse::<u32>().ok()?.checked_sub(1)?;
+        let end = end.parse::<u32>().ok()?.checked_sub(1)?;
+        let (worktree_root_name, path) = path.split_once('/')?;
+        Some((
+            worktree_root_name.to_string(),
+            PathBuf::from(path),
+            start..=end.max(start),
+            sha,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_code_reference_markdown() {
+        let reference = CodeReference {
+            worktree_root_name: "zed".into(),
+            path: "crates/my crate/src/lib.rs".into(),
+            rows: 9..=11,
+            sha: Some("0123456789abcdef0123456789abcdef01234567".into()),
+            language_name: Some("Rust".into()),
+            snapshot: "fn a() {\n    let s = \"```\";\n}\n".into(),
+        };
+
+        let markdown = reference.to_markdown();
+        assert_eq!(
+            markdown,
+            concat!(
+                "[`zed/crates/my crate/src/lib.rs:10-12`]",
+                "(<code-reference:zed/crates/my crate/src/lib.rs#L10-L12",
+                "@0123456789abcdef0123456789abcdef01234567>) at `0123456`\n",
+                "\n",
+                "````Rust\n",
+                "fn a() {\n",
+                "    let s = \"```\";\n",
+                "}\n",
+                "````",
+            )
+        );
+        assert_eq!(CodeReference::parse(&markdown), Some(reference));
+
+        let reference = CodeReference {
+            worktree_root_name: "notes".into(),
+            path: "todo.md".into(),
+            rows: 0..=0,
+            sha: None,
+            language_name: None,
+            snapshot: "- one\n".into(),
+        };
+        let markdown = reference.to_markdown();
+        assert_eq!(
+            markdown,
+            "[`notes/todo.md:1`](<code-reference:notes/todo.md#L1-L1>)\n\n```\n- one\n```"
+        );
+        assert_eq!(CodeReference::parse(&markdown), Some(reference));
+
+        assert_eq!(CodeReference::parse("just a message"), None);
+    }
+
+    #[test]
+    fn test_code_reference_markdown_within_limit() {
+        let reference = CodeReference {
+            worktree_root_name: "zed".into(),
+            path: "src/main.rs".into(),
+            rows: 0..=2,
+            sha: None,
+            language_name: Some("Rust".into()),
+            snapshot: "fn main() {\n    println!(\"héllo\");\n}\n".into(),
+        };
+        let full = reference.to_markdown();
+        assert_eq!(reference.to_markdown_within(full.len()), Some(full.clone()));
+
+        // Lines are dropped from the end until the message fits.
+        let markdown = reference.to_markdown_within(full.len() - 1).unwrap();
+        assert!(markdown.len() < full.len());
+        assert_eq!(
+            CodeReference::parse(&markdown).unwrap().snapshot,
+            "fn main() {\n…\n"
+        );
+        let markdown = reference.to_markdown_within(full.len() - 30).unwrap();
+        assert_eq!(CodeReference::parse(&markdown).unwrap().snapshot, "…\n");
+        assert_eq!(reference.to_markdown_within(20), None);
+    }
+
+    #[test]
+    fn test_parse_code_reference_with_link() {
+        let first = CodeReference {
+            worktree_root_name: "zed".into(),
+            path: "a.rs".into(),
+            rows: 0..=0,
+            sha: None,
+            language_name: None,
+            snapshot: "a\n".into(),
+        };
+        let second = CodeReference {
+            worktree_root_name: "zed".into(),
+            path: "b.rs".into(),
+            rows: 4..=5,
+            sha: None,
+            language_name: None,
+            snapshot: "b\nc\n".into(),
+        };
+        let markdown = format!("{}\n\n{}", first.to_markdown(), second.to_markdown());
+
+        assert_eq!(
+            CodeReference::parse_with_link(&markdown, "code-reference:zed/b.rs#L5-L6"),
+            Some(second)
+        );
+        assert_eq!(
+            CodeReference::parse_with_link(&markdown, "code-reference:zed/a.rs#L1-L1"),
+            Some(first)
+        );
+        assert_eq!(
+            CodeReference::parse_with_link(&markdown, "code-reference:zed/c.rs#L1-L1"),
+            None
+        );
+    }
+}
@@ -2,7 +2,9 @@ use anyhow::Result;
 use channel::{ChannelMembership, ChannelStore, MessageParams};
 use client::{ChannelId, UserId};
 use collections::{HashMap, HashSet};
-use editor::{AnchorRangeExt, CompletionProvider, Editor, EditorElement, EditorStyle};
+use editor::{
+    actions::MoveToEnd, AnchorRangeExt, CompletionProvider, Editor, EditorElement, EditorStyle,
+};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
     AsyncWindowContext, FocusableView, FontStyle, FontWeight, HighlightStyle, IntoElement, Model,
@@ -146,6 +148,15 @@ impl MessageEditor {
         self.reply_to_message_id = None;
     }
 
+    /// Replaces the contents of the editor, such as when editing a message that was already
+    /// sent. Mentions are found again once the new text has been parsed.
+    pub fn set_text(&mut self, text: &str, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            editor.set_text(text, cx);
+            editor.move_to_end(&MoveToEnd, cx);
+        });
+    }
+
     pub fn set_channel(
         &mut self,
         channel_id: ChannelId,
@@ -0,0 +1,532 @@
+use channel::{ChannelBuffer, CommentStore, CommentStoreEvent, CommentTarget};
+use collections::{HashMap, HashSet};
+use editor::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    Editor, EditorEvent, EditorMode,
+};
+use gpui::{
+    actions, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Render, Subscription,
+    Task, View, ViewContext, VisualContext as _, WeakView,
+};
+use language::{Anchor, Point, ToPoint as _};
+use menu::{Cancel, Confirm};
+use std::{mem, ops::Range, sync::Arc};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, Avatar, Button, Label};
+use workspace::notifications::DetachAndPromptErr;
+
+actions!(code_comments, [AddComment]);
+
+/// The most lines that the comment editor of a thread grows to before scrolling.
+const MAX_EDITOR_LINES: usize = 4;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|editor: &mut Editor, cx: &mut ViewContext<Editor>| {
+        if editor.mode() != EditorMode::Full {
+            return;
+        }
+        let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+            return;
+        };
+        if project::File::from_dyn(buffer.read(cx).file()).is_none() {
+            return;
+        }
+
+        // The comments live as long as the editor that displays them.
+        let code_comments = CodeComments::attach(editor, None, cx);
+        cx.on_release(move |_, _, _| drop(code_comments)).detach();
+    })
+    .detach();
+}
+
+/// Displays the review comments on a buffer as blocks in its editor. Project files can only be
+/// commented on while their project is shared.
+pub struct CodeComments {
+    editor: WeakView<Editor>,
+    comment_store: Model<CommentStore>,
+    channel_buffer: Option<Model<ChannelBuffer>>,
+    target: Option<CommentTarget>,
+    threads: HashMap<u64, View<CommentThreadView>>,
+    draft: Option<View<CommentThreadView>>,
+    block_ids: HashSet<BlockId>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl CodeComments {
+    /// Shows comments in the given editor, which must display a single buffer. Pass the channel
+    /// buffer when the editor displays channel notes.
+    pub fn attach(
+        editor: &mut Editor,
+        channel_buffer: Option<Model<ChannelBuffer>>,
+        cx: &mut ViewContext<Editor>,
+    ) -> View<Self> {
+        let editor_handle = cx.view().clone();
+        let code_comments = cx.new_view(|cx| Self::new(editor_handle, channel_buffer, cx));
+        let handle = code_comments.downgrade();
+        editor.register_action(move |_: &AddComment, cx| {
+            handle.update(cx, |this, cx| this.start_thread(cx)).ok();
+        });
+        code_comments
+    }
+
+    fn new(
+        editor: View<Editor>,
+        channel_buffer: Option<Model<ChannelBuffer>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let comment_store = CommentStore::global(cx);
+        let subscriptions = vec![
+            cx.subscribe(&comment_store, |this, _, event, cx| match event {
+                CommentStoreEvent::ThreadsChanged { target } => {
+                    if this.target.as_ref() == Some(target) {
+                        this.refresh_blocks(cx);
+                    }
+                }
+            }),
+            // A project may have been shared since the editor was opened.
+            cx.subscribe(&editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::Focused = event {
+                    this.refresh_target(cx);
+                }
+            }),
+        ];
+        cx.defer(|this, cx| this.refresh_target(cx));
+
+        Self {
+            editor: editor.downgrade(),
+            comment_store,
+            channel_buffer,
+            target: None,
+            threads: HashMap::default(),
+            draft: None,
+            block_ids: HashSet::default(),
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn current_target(&self, cx: &AppContext) -> Option<CommentTarget> {
+        if let Some(channel_buffer) = &self.channel_buffer {
+            return Some(CommentTarget::Channel(channel_buffer.read(cx).channel_id));
+        }
+
+        let editor = self.editor.upgrade()?;
+        let editor = editor.read(cx);
+        let project_id = editor
+            .workspace()?
+            .read(cx)
+            .project()
+            .read(cx)
+            .remote_id()?;
+        let buffer = editor.buffer().read(cx).as_singleton()?;
+        let file = project::File::from_dyn(buffer.read(cx).file())?;
+        Some(CommentTarget::ProjectPath {
+            project_id,
+            worktree_id: file.worktree_id(cx).to_proto(),
+            path: file.path.clone(),
+        })
+    }
+
+    fn refresh_target(&mut self, cx: &mut ViewContext<Self>) {
+        let target = self.current_target(cx);
+        if target == self.target {
+            return;
+        }
+
+        self.target = target;
+        self.threads.clear();
+        self.draft = None;
+        if let Some(target) = &self.target {
+            let load = self.comment_store.update(cx, |store, cx| match target {
+                CommentTarget::Channel(channel_id) => store.load_channel_threads(*channel_id, cx),
+                CommentTarget::ProjectPath { project_id, .. } => {
+                    store.load_project_threads(*project_id, cx)
+                }
+            });
+            load.detach_and_log_err(cx);
+        }
+        self.refresh_blocks(cx);
+    }
+
+    fn buffer_epoch(&self, cx: &AppContext) -> Option<u64> {
+        self.channel_buffer
+            .as_ref()
+            .map(|channel_buffer| channel_buffer.read(cx).epoch())
+    }
+
+    fn refresh_blocks(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let buffer_epoch = self.buffer_epoch(cx);
+
+        let threads = self.target.as_ref().map_or(Vec::new(), |target| {
+            self.comment_store
+                .read(cx)
+                .threads_for_target(target)
+                .map(|thread| (thread.id, thread.range_in(&snapshot, buffer_epoch)))
+                .collect()
+        });
+        self.threads
+            .retain(|thread_id, _| threads.iter().any(|(id, _)| id == thread_id));
+
+        let mut blocks = Vec::new();
+        for (thread_id, range) in threads {
+            let view = self
+                .threads
+                .entry(thread_id)
+                .or_insert_with(|| {
+                    let code_comments = cx.view().downgrade();
+                    cx.new_view(|cx| {
+                        CommentThreadView::new(Some(thread_id), range.clone(), code_comments, cx)
+                    })
+                })
+                .clone();
+            blocks.push((range.end.to_point(&snapshot), view));
+        }
+        if let Some(draft) = &self.draft {
+            blocks.push((draft.read(cx).range.end.to_point(&snapshot), draft.clone()));
+        }
+
+        let blocks = blocks
+            .into_iter()
+            .map(|(position, view)| (position, view.read(cx).block_height(cx), view))
+            .collect::<Vec<_>>();
+        let old_block_ids = mem::take(&mut self.block_ids);
+        self.block_ids = editor.update(cx, |editor, cx| {
+            editor.remove_blocks(old_block_ids, None, cx);
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            editor
+                .insert_blocks(
+                    blocks
+                        .into_iter()
+                        .map(|(position, height, view)| BlockProperties {
+                            position: snapshot.anchor_after(position),
+                            height,
+                            style: BlockStyle::Flex,
+                            render: Arc::new(move |cx: &mut BlockContext| {
+                                div()
+                                    .pl(cx.gutter_dimensions.width)
+                                    .child(view.clone())
+                                    .into_any_element()
+                            }),
+                            disposition: BlockDisposition::Below,
+                        }),
+                    None,
+                    cx,
+                )
+                .into_iter()
+                .collect()
+        });
+    }
+
+    /// Opens an editor for the first comment of a thread on the newest selection.
+    fn start_thread(&mut self, cx: &mut ViewContext<Self>) {
+        self.refresh_target(cx);
+        if self.target.is_none() {
+            return;
+        }
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let Some(range) = editor.update(cx, |editor, cx| {
+            let selection = editor.selections.newest::<Point>(cx);
+            let buffer = editor.buffer().read(cx).as_singleton()?;
+            let buffer = buffer.read(cx);
+            Some(buffer.anchor_before(selection.start)..buffer.anchor_after(selection.end))
+        }) else {
+            return;
+        };
+
+        let code_comments = cx.view().downgrade();
+        let draft = cx.new_view(|cx| CommentThreadView::new(None, range, code_comments, cx));
+        cx.focus_view(&draft);
+        self.draft = Some(draft);
+        self.refresh_blocks(cx);
+    }
+
+    fn create_thread(
+        &mut self,
+        range: Range<Anchor>,
+        body: String,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let Some(target) = self.target.clone() else {
+            return Task::ready(Err(anyhow::anyhow!("this file can't be commented on")));
+        };
+        let Some(buffer) = self
+            .editor
+            .upgrade()
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+        else {
+            return Task::ready(Err(anyhow::anyhow!("this file can't be commented on")));
+        };
+        let snapshot = buffer.read(cx).text_snapshot();
+        let buffer_epoch = self.buffer_epoch(cx);
+        let create = self.comment_store.update(cx, |store, cx| {
+            store.create_thread(target, range, &snapshot, buffer_epoch, body, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            create.await?;
+            this.update(&mut cx, |this, cx| this.discard_draft(cx))
+        })
+    }
+
+    fn discard_draft(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(draft) = self.draft.take() {
+            if draft.focus_handle(cx).contains_focused(cx) {
+                if let Some(editor) = self.editor.upgrade() {
+                    cx.focus_view(&editor);
+                }
+            }
+            self.refresh_blocks(cx);
+        }
+    }
+}
+
+impl Render for CodeComments {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        gpui::Empty
+    }
+}
+
+/// A comment thread, or the draft of a new one, displayed below the code it is about.
+pub struct CommentThreadView {
+    thread_id: Option<u64>,
+    range: Range<Anchor>,
+    code_comments: WeakView<CodeComments>,
+    comment_store: Model<CommentStore>,
+    editor: View<Editor>,
+    editor_line_count: usize,
+    local_timezone: UtcOffset,
+    _editor_subscription: Subscription,
+}
+
+impl CommentThreadView {
+    fn new(
+        thread_id: Option<u64>,
+        range: Range<Anchor>,
+        code_comments: WeakView<CodeComments>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::auto_height(MAX_EDITOR_LINES, cx);
+            editor.set_placeholder_text(
+                if thread_id.is_some() {
+                    "Reply…"
+                } else {
+                    "Add a comment…"
+                },
+                cx,
+            );
+            editor
+        });
+        let editor_subscription = cx.subscribe(
+            &editor,
+            |this: &mut Self, editor, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    // Blocks have a fixed height, so grow the block along with the editor.
+                    let line_count = editor
+                        .read(cx)
+                        .buffer()
+                        .read(cx)
+                        .snapshot(cx)
+                        .max_point()
+                        .row as usize
+                        + 1;
+                    if line_count.min(MAX_EDITOR_LINES) != this.editor_line_count {
+                        this.editor_line_count = line_count.min(MAX_EDITOR_LINES);
+                        this.code_comments
+                            .update(cx, |code_comments, cx| code_comments.refresh_blocks(cx))
+                            .ok();
+                    }
+                }
+            },
+        );
+
+        Self {
+            thread_id,
+            range,
+            code_comments,
+            comment_store: CommentStore::global(cx),
+            editor,
+            editor_line_count: 1,
+            local_timezone: cx.local_timezone(),
+            _editor_subscription: editor_subscription,
+        }
+    }
+
+    fn block_height(&self, cx: &AppContext) -> u8 {
+        let thread = self
+            .thread_id
+            .and_then(|thread_id| self.comment_store.read(cx).thread(thread_id));
+        if thread.map_or(false, |thread| thread.resolved) {
+            return 2;
+        }
+
+        let comment_lines = thread.map_or(0, |thread| {
+            thread
+                .comments
+                .iter()
+                .map(|comment| 1 + comment.body.lines().count())
+                .sum()
+        });
+        // Leave room for the buttons and the padding around the thread.
+        (comment_lines + self.editor_line_count + 2).min(u8::MAX as usize) as u8
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        let body = self.editor.read(cx).text(cx).trim().to_string();
+        if body.is_empty() {
+            return;
+        }
+
+        let task = if let Some(thread_id) = self.thread_id {
+            self.comment_store
+                .update(cx, |store, cx| store.add_comment(thread_id, body, cx))
+        } else if let Some(code_comments) = self.code_comments.upgrade() {
+            let range = self.range.clone();
+            code_comments.update(cx, |code_comments, cx| {
+                code_comments.create_thread(range, body, cx)
+            })
+        } else {
+            return;
+        };
+        self.editor.update(cx, |editor, cx| editor.clear(cx));
+        task.detach_and_prompt_err("Failed to post comment", cx, |_, _| None);
+    }
+
+    fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
+        if self.thread_id.is_none() {
+            self.code_comments
+                .update(cx, |code_comments, cx| code_comments.discard_draft(cx))
+                .ok();
+        } else {
+            cx.propagate();
+        }
+    }
+
+    fn set_resolved(&mut self, resolved: bool, cx: &mut ViewContext<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+        self.comment_store
+            .update(cx, |store, cx| {
+                store.set_thread_resolved(thread_id, resolved, cx)
+            })
+            .detach_and_prompt_err("Failed to update comment thread", cx, |_, _| None);
+    }
+}
+
+impl Render for CommentThreadView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let thread = self
+            .thread_id
+            .and_then(|thread_id| self.comment_store.read(cx).thread(thread_id))
+            .cloned();
+        let is_draft = self.thread_id.is_none();
+        let resolved = thread.as_ref().map_or(false, |thread| thread.resolved);
+        let comments = thread.map(|thread| thread.comments).unwrap_or_default();
+        let now = OffsetDateTime::now_utc();
+
+        v_flex()
+            .key_context("CommentThread")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .w_full()
+            .max_w(rems(48.))
+            .my_1()
+            .px_2()
+            .gap_1()
+            .border_l_2()
+            .border_color(if resolved {
+                cx.theme().colors().border_variant
+            } else {
+                cx.theme().colors().border_focused
+            })
+            .when(resolved, |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Label::new(format!(
+                                "Resolved thread with {} comment{}",
+                                comments.len(),
+                                if comments.len() == 1 { "" } else { "s" }
+                            ))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        )
+                        .child(
+                            Button::new("reopen", "Reopen")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, cx| this.set_resolved(false, cx))),
+                        ),
+                )
+            })
+            .when(!resolved, |this| {
+                this.children(comments.into_iter().map(|comment| {
+                    v_flex()
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Avatar::new(comment.sender.avatar_uri.clone()).size(rems(1.)),
+                                )
+                                .child(
+                                    Label::new(comment.sender.github_login.clone())
+                                        .size(LabelSize::Small),
+                                )
+                                .child(
+                                    Label::new(time_format::format_localized_timestamp(
+                                        comment.timestamp,
+                                        now,
+                                        self.local_timezone,
+                                        time_format::TimestampFormat::Relative,
+                                    ))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                                ),
+                        )
+                        .child(Label::new(comment.body))
+                }))
+                .child(
+                    div()
+                        .px_1()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .rounded_md()
+                        .child(self.editor.clone()),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("submit", if is_draft { "Comment" } else { "Reply" })
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, cx| this.confirm(&Confirm, cx))),
+                        )
+                        .child(if is_draft {
+                            Button::new("cancel", "Cancel")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, cx| this.cancel(&Cancel, cx)))
+                        } else {
+                            Button::new("resolve", "Resolve")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, cx| this.set_resolved(true, cx)))
+                        }),
+                )
+            })
+    }
+}
+
+impl FocusableView for CommentThreadView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<()> for CommentThreadView {}
@@ -1,7 +1,9 @@
 pub mod channel_view;
 pub mod chat_panel;
+pub mod code_comments;
 pub mod collab_panel;
 mod collab_titlebar_item;
+pub mod comments_panel;
 mod face_pile;
 pub mod notification_panel;
 pub mod notifications;
@@ -18,7 +20,7 @@ use gpui::{
 };
 use panel_settings::MessageEditorSettings;
 pub use panel_settings::{
-    ChatPanelSettings, CollaborationPanelSettings, NotificationPanelSettings,
+    ChatPanelSettings, CollaborationPanelSettings, CommentsPanelSettings, NotificationPanelSettings,
 };
 use settings::Settings;
 use workspace::{notifications::DetachAndPromptErr, AppState};
@@ -32,6 +34,7 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     CollaborationPanelSettings::register(cx);
     ChatPanelSettings::register(cx);
     NotificationPanelSettings::register(cx);
+    CommentsPanelSettings::register(cx);
     MessageEditorSettings::register(cx);
 
     vcs_menu::init(cx);
@@ -40,6 +43,8 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     channel_view::init(cx);
     chat_panel::init(cx);
     notification_panel::init(cx);
+    comments_panel::init(cx);
+    code_comments::init(cx);
     notifications::init(&app_state, cx);
 }
 
@@ -0,0 +1,370 @@
+use crate::CommentsPanelSettings;
+use anyhow::Result;
+use channel::{CommentStore, CommentTarget, CommentThread};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, AppContext, AsyncWindowContext, CursorStyle, EventEmitter, FocusHandle, FocusableView,
+    Model, Render, Subscription, Task, View, ViewContext, VisualContext as _, WeakView,
+    WindowContext,
+};
+use language::ToPoint as _;
+use project::{Fs, Project, WorktreeId};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::sync::Arc;
+use ui::{prelude::*, Avatar, Icon, IconName, Label};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::DetachAndPromptErr,
+    Workspace,
+};
+
+const COMMENTS_PANEL_KEY: &str = "CommentsPanel";
+
+/// Lists the open comment threads on the files of the workspace's shared project.
+pub struct CommentsPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    comment_store: Model<CommentStore>,
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    active: bool,
+    pending_serialization: Task<Option<()>>,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedCommentsPanel {
+    width: Option<Pixels>,
+}
+
+actions!(comments_panel, [ToggleFocus]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<CommentsPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+impl CommentsPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let comment_store = CommentStore::global(cx);
+            let subscriptions = vec![
+                cx.observe(&comment_store, |_, _, cx| cx.notify()),
+                cx.subscribe(&project, |this, _, event, cx| {
+                    if let project::Event::RemoteIdChanged(_) = event {
+                        this.load_threads(cx);
+                    }
+                }),
+                cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+            ];
+
+            Self {
+                workspace: workspace_handle,
+                project,
+                comment_store,
+                fs,
+                width: None,
+                active: false,
+                pending_serialization: Task::ready(None),
+                focus_handle: cx.focus_handle(),
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(COMMENTS_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedCommentsPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        COMMENTS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedCommentsPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn load_threads(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(project_id) = self.project.read(cx).remote_id() {
+            self.comment_store
+                .update(cx, |store, cx| store.load_project_threads(project_id, cx))
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn open_threads(&self, cx: &AppContext) -> Vec<CommentThread> {
+        let Some(project_id) = self.project.read(cx).remote_id() else {
+            return Vec::new();
+        };
+        self.comment_store
+            .read(cx)
+            .project_threads(project_id)
+            .filter(|thread| !thread.resolved)
+            .cloned()
+            .collect()
+    }
+
+    fn open_thread(&mut self, thread: &CommentThread, cx: &mut ViewContext<Self>) {
+        let CommentTarget::ProjectPath {
+            worktree_id, path, ..
+        } = &thread.target
+        else {
+            return;
+        };
+        let Some(open_path) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path(
+                    (WorktreeId::from_proto(*worktree_id), path.clone()),
+                    None,
+                    true,
+                    cx,
+                )
+            })
+            .log_err()
+        else {
+            return;
+        };
+
+        let thread = thread.clone();
+        cx.spawn(|_, mut cx| async move {
+            let item = open_path.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update(&mut cx, |editor, cx| {
+                    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+                        return;
+                    };
+                    let snapshot = buffer.read(cx).snapshot();
+                    let range = thread.range_in(&snapshot, None);
+                    let range = range.start.to_point(&snapshot)..range.end.to_point(&snapshot);
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([range])
+                    });
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to open file", cx, |_, _| None);
+    }
+
+    fn render_thread(
+        &self,
+        ix: usize,
+        thread: CommentThread,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let location = match &thread.target {
+            CommentTarget::ProjectPath { path, .. } => {
+                format!("{}:{}", path.to_string_lossy(), thread.row + 1)
+            }
+            CommentTarget::Channel(_) => String::new(),
+        };
+        let first_comment = thread.comments.first().cloned();
+        let reply_count = thread.comments.len().saturating_sub(1);
+
+        v_flex()
+            .id(ix)
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_0p5()
+            .cursor(CursorStyle::PointingHand)
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new(location).size(LabelSize::Small))
+                    .when(reply_count > 0, |this| {
+                        this.child(
+                            Label::new(format!(
+                                "{} repl{}",
+                                reply_count,
+                                if reply_count == 1 { "y" } else { "ies" }
+                            ))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        )
+                    }),
+            )
+            .children(first_comment.map(|comment| {
+                h_flex()
+                    .gap_1()
+                    .child(Avatar::new(comment.sender.avatar_uri.clone()).size(rems(1.)))
+                    .child(
+                        Label::new(comment.body.lines().next().unwrap_or_default().to_string())
+                            .color(Color::Muted),
+                    )
+            }))
+            .on_click(cx.listener(move |this, _, cx| this.open_thread(&thread, cx)))
+    }
+}
+
+impl Render for CommentsPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let is_shared = self.project.read(cx).remote_id().is_some();
+        let threads = self.open_threads(cx);
+
+        v_flex()
+            .size_full()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Comments"))
+                    .child(Icon::new(IconName::MessageBubbles)),
+            )
+            .map(|this| {
+                let message = if !is_shared {
+                    Some("Share this project to comment on its code.")
+                } else if threads.is_empty() {
+                    Some("There are no open comments.")
+                } else {
+                    None
+                };
+                if let Some(message) = message {
+                    this.child(
+                        v_flex().p_4().child(
+                            div().flex().w_full().items_center().child(
+                                Label::new(message)
+                                    .color(Color::Muted)
+                                    .size(LabelSize::Small),
+                            ),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        v_flex()
+                            .id("comment-threads")
+                            .size_full()
+                            .overflow_y_scroll()
+                            .children(
+                                threads
+                                    .into_iter()
+                                    .enumerate()
+                                    .map(|(ix, thread)| self.render_thread(ix, thread, cx)),
+                            ),
+                    )
+                }
+            })
+    }
+}
+
+impl FocusableView for CommentsPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for CommentsPanel {}
+
+impl Panel for CommentsPanel {
+    fn persistent_name() -> &'static str {
+        "CommentsPanel"
+    }
+
+    fn position(&self, cx: &gpui::WindowContext) -> DockPosition {
+        CommentsPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<CommentsPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &gpui::WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| CommentsPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        if active && !self.active {
+            self.load_threads(cx);
+        }
+        self.active = active;
+    }
+
+    fn icon(&self, cx: &gpui::WindowContext) -> Option<IconName> {
+        Some(IconName::MessageBubbles).filter(|_| CommentsPanelSettings::get_global(cx).button)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Comments Panel")
+    }
+
+    fn icon_label(&self, cx: &WindowContext) -> Option<String> {
+        let count = self.open_threads(cx).len();
+        if count == 0 {
+            None
+        } else {
+            Some(count.to_string())
+        }
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
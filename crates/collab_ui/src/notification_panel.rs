@@ -1,6 +1,9 @@
-use crate::{chat_panel::ChatPanel, NotificationPanelSettings};
+use crate::{
+    channel_view::ChannelView, chat_panel::ChatPanel, comments_panel::CommentsPanel,
+    NotificationPanelSettings,
+};
 use anyhow::Result;
-use channel::ChannelStore;
+use channel::{ChannelActivity, ChannelActivityKind, ChannelActivityStore, ChannelStore};
 use client::{ChannelId, Client, Notification, User, UserStore};
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
@@ -19,10 +22,14 @@ use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use std::{sync::Arc, time::Duration};
 use time::{OffsetDateTime, UtcOffset};
-use ui::{h_flex, prelude::*, v_flex, Avatar, Button, Icon, IconButton, IconName, Label, Tooltip};
+use ui::{
+    h_flex, prelude::*, v_flex, Avatar, Button, Icon, IconButton, IconName, Label, ToggleButton,
+    Tooltip,
+};
 use util::{ResultExt, TryFutureExt};
 use workspace::{
     dock::{DockPosition, Panel, PanelEvent},
+    notifications::DetachAndPromptErr,
     Workspace,
 };
 
@@ -36,10 +43,13 @@ pub struct NotificationPanel {
     user_store: Model<UserStore>,
     channel_store: Model<ChannelStore>,
     notification_store: Model<NotificationStore>,
+    activity_store: Model<ChannelActivityStore>,
     fs: Arc<dyn Fs>,
     width: Option<Pixels>,
     active: bool,
+    active_tab: NotificationPanelTab,
     notification_list: ListState,
+    activity_list: ListState,
     pending_serialization: Task<Option<()>>,
     subscriptions: Vec<gpui::Subscription>,
     workspace: WeakView<Workspace>,
@@ -47,9 +57,17 @@ pub struct NotificationPanel {
     local_timezone: UtcOffset,
     focus_handle: FocusHandle,
     mark_as_read_tasks: HashMap<u64, Task<Result<()>>>,
+    acknowledge_activity_task: Option<Task<()>>,
     unseen_notifications: Vec<NotificationEntry>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum NotificationPanelTab {
+    Notifications,
+    /// What other members have been doing in the user's channels.
+    Activity,
+}
+
 #[derive(Serialize, Deserialize)]
 struct SerializedNotificationPanel {
     width: Option<Pixels>,
@@ -126,6 +144,29 @@ impl NotificationPanel {
                 },
             ));
 
+            let view = cx.view().downgrade();
+            let activity_store = ChannelActivityStore::global(cx);
+            let activity_list = ListState::new(
+                activity_store.read(cx).activity_count(),
+                ListAlignment::Top,
+                px(1000.),
+                move |ix, cx| {
+                    view.upgrade()
+                        .and_then(|view| view.update(cx, |this, cx| this.render_activity(ix, cx)))
+                        .unwrap_or_else(|| div().into_any())
+                },
+            );
+            activity_list.set_scroll_handler(cx.listener(|this, event: &ListScrollEvent, cx| {
+                if event.count.saturating_sub(event.visible_range.end) < LOADING_THRESHOLD {
+                    if let Some(task) = this
+                        .activity_store
+                        .update(cx, |store, cx| store.load_more_activities(false, cx))
+                    {
+                        task.detach_and_log_err(cx);
+                    }
+                }
+            }));
+
             let mut this = Self {
                 fs,
                 client,
@@ -133,7 +174,10 @@ impl NotificationPanel {
                 local_timezone: cx.local_timezone(),
                 channel_store: ChannelStore::global(cx),
                 notification_store: NotificationStore::global(cx),
+                activity_store,
                 notification_list,
+                activity_list,
+                active_tab: NotificationPanelTab::Notifications,
                 pending_serialization: Task::ready(None),
                 workspace: workspace_handle,
                 focus_handle: cx.focus_handle(),
@@ -141,6 +185,7 @@ impl NotificationPanel {
                 subscriptions: Vec::new(),
                 active: false,
                 mark_as_read_tasks: HashMap::default(),
+                acknowledge_activity_task: None,
                 width: None,
                 unseen_notifications: Vec::new(),
             };
@@ -149,6 +194,7 @@ impl NotificationPanel {
             this.subscriptions.extend([
                 cx.observe(&this.notification_store, |_, _, cx| cx.notify()),
                 cx.subscribe(&this.notification_store, Self::on_notification_event),
+                cx.observe(&this.activity_store, Self::on_activity_changed),
                 cx.observe_global::<SettingsStore>(move |this: &mut Self, cx| {
                     let new_dock_position = this.position(cx);
                     if new_dock_position != old_dock_position {
@@ -337,6 +383,204 @@ impl NotificationPanel {
         )
     }
 
+    fn render_activity(&mut self, ix: usize, cx: &mut ViewContext<Self>) -> Option<AnyElement> {
+        let activity_store = self.activity_store.read(cx);
+        let activity = activity_store.activity_at(ix)?.clone();
+        let is_unread = activity_store.is_unread(&activity);
+        let channel = self
+            .channel_store
+            .read(cx)
+            .channel_for_id(activity.channel_id)?;
+        let actor = activity.user.github_login.clone();
+        let text = match &activity.kind {
+            ChannelActivityKind::JoinedCall => {
+                format!("{actor} joined the #{} call", channel.name)
+            }
+            ChannelActivityKind::SharedProject { .. } => {
+                format!("{actor} shared a project in #{}", channel.name)
+            }
+            ChannelActivityKind::EditedNotes => {
+                format!("{actor} edited the #{} notes", channel.name)
+            }
+            ChannelActivityKind::Mention { body, .. } => {
+                format!("{actor} mentioned you in #{}:\n{body}", channel.name)
+            }
+        };
+        let can_navigate = activity.kind != ChannelActivityKind::JoinedCall;
+
+        let now = OffsetDateTime::now_utc();
+        let relative_timestamp = time_format::format_localized_timestamp(
+            activity.timestamp,
+            now,
+            self.local_timezone,
+            time_format::TimestampFormat::Relative,
+        );
+        let absolute_timestamp = time_format::format_localized_timestamp(
+            activity.timestamp,
+            now,
+            self.local_timezone,
+            time_format::TimestampFormat::Absolute,
+        );
+
+        Some(
+            div()
+                .id(ix)
+                .flex()
+                .flex_row()
+                .size_full()
+                .px_2()
+                .py_1()
+                .gap_2()
+                .hover(|style| style.bg(cx.theme().colors().element_hover))
+                .when(can_navigate, |el| {
+                    el.cursor(CursorStyle::PointingHand).on_click({
+                        let activity = activity.clone();
+                        cx.listener(move |this, _, cx| this.did_click_activity(&activity, cx))
+                    })
+                })
+                .child(
+                    img(activity.user.avatar_uri.clone())
+                        .flex_none()
+                        .w_8()
+                        .h_8()
+                        .rounded_full(),
+                )
+                .child(
+                    v_flex()
+                        .gap_1()
+                        .size_full()
+                        .overflow_hidden()
+                        .child(Label::new(text).when(!is_unread, |label| label.color(Color::Muted)))
+                        .child(
+                            div()
+                                .id("activity_timestamp")
+                                .hover(|style| {
+                                    style.bg(cx.theme().colors().element_selected).rounded_md()
+                                })
+                                .child(Label::new(relative_timestamp).color(Color::Muted))
+                                .tooltip(move |cx| Tooltip::text(absolute_timestamp.clone(), cx)),
+                        ),
+                )
+                .into_any(),
+        )
+    }
+
+    fn did_click_activity(&mut self, activity: &ChannelActivity, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let channel_id = activity.channel_id;
+        let host_user_id = activity.user.id;
+        match activity.kind.clone() {
+            ChannelActivityKind::JoinedCall => {}
+            ChannelActivityKind::SharedProject { project_id } => {
+                workspace.update(cx, |workspace, cx| {
+                    let app_state = workspace.app_state().clone();
+                    workspace::join_in_room_project(project_id, host_user_id, app_state, cx)
+                        .detach_and_prompt_err("Failed to join project", cx, |_, _| None);
+                });
+            }
+            ChannelActivityKind::EditedNotes => {
+                cx.window_context().defer(move |cx| {
+                    ChannelView::open(channel_id, None, workspace, cx).detach_and_log_err(cx);
+                });
+            }
+            ChannelActivityKind::Mention { message_id, .. } => {
+                cx.window_context().defer(move |cx| {
+                    workspace.update(cx, |workspace, cx| {
+                        if let Some(panel) = workspace.focus_panel::<ChatPanel>(cx) {
+                            panel.update(cx, |panel, cx| {
+                                panel
+                                    .select_channel(channel_id, Some(message_id), cx)
+                                    .detach_and_log_err(cx);
+                            });
+                        }
+                    });
+                });
+            }
+        }
+    }
+
+    fn on_activity_changed(
+        &mut self,
+        activity_store: Model<ChannelActivityStore>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.activity_list
+            .reset(activity_store.read(cx).activity_count());
+        if self.active && self.active_tab == NotificationPanelTab::Activity {
+            self.acknowledge_activity_after_delay(cx);
+        }
+        cx.notify();
+    }
+
+    fn acknowledge_activity_after_delay(&mut self, cx: &mut ViewContext<Self>) {
+        if self.acknowledge_activity_task.is_some() {
+            return;
+        }
+        self.acknowledge_activity_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(MARK_AS_READ_DELAY).await;
+            this.update(&mut cx, |this, cx| {
+                this.acknowledge_activity_task = None;
+                if this.active && this.active_tab == NotificationPanelTab::Activity {
+                    this.activity_store
+                        .update(cx, |store, cx| store.acknowledge(cx));
+                }
+            })
+            .ok();
+        }));
+    }
+
+    fn select_tab(&mut self, tab: NotificationPanelTab, cx: &mut ViewContext<Self>) {
+        if self.active_tab == tab {
+            return;
+        }
+        self.active_tab = tab;
+        if tab == NotificationPanelTab::Activity {
+            // Refresh the feed, since the summary of unseen edits to channel notes
+            // isn't pushed by the server.
+            if let Some(task) = self
+                .activity_store
+                .update(cx, |store, cx| store.load_more_activities(true, cx))
+            {
+                task.detach_and_log_err(cx);
+            }
+            if self.active {
+                self.acknowledge_activity_after_delay(cx);
+            }
+        }
+        cx.notify();
+    }
+
+    fn render_tabs(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let unread_activity_count = self.activity_store.read(cx).unread_activity_count();
+        let activity_label = if unread_activity_count == 0 {
+            "Activity".to_string()
+        } else {
+            format!("Activity ({unread_activity_count})")
+        };
+
+        h_flex()
+            .child(
+                ToggleButton::new("notifications_tab", "Notifications")
+                    .style(ButtonStyle::Filled)
+                    .selected(self.active_tab == NotificationPanelTab::Notifications)
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.select_tab(NotificationPanelTab::Notifications, cx)
+                    }))
+                    .first(),
+            )
+            .child(
+                ToggleButton::new("activity_tab", activity_label)
+                    .style(ButtonStyle::Filled)
+                    .selected(self.active_tab == NotificationPanelTab::Activity)
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.select_tab(NotificationPanelTab::Activity, cx)
+                    }))
+                    .last(),
+            )
+    }
+
     fn present_notification(
         &self,
         entry: &NotificationEntry,
@@ -404,6 +648,32 @@ impl NotificationPanel {
                     can_navigate: true,
                 })
             }
+            Notification::CodeComment {
+                sender_id,
+                channel_id,
+                ref path,
+                ..
+            } => {
+                let sender = user_store.get_cached_user(sender_id)?;
+                let location = match (channel_id, path) {
+                    (Some(channel_id), _) => {
+                        let channel = channel_store.channel_for_id(ChannelId(channel_id))?;
+                        format!("the #{} notes", channel.name)
+                    }
+                    (None, Some(path)) => path.clone(),
+                    (None, None) => return None,
+                };
+                Some(NotificationPresenter {
+                    icon: "icons/conversations.svg",
+                    text: format!(
+                        "{} replied to your comment on {}",
+                        sender.github_login, location
+                    ),
+                    needs_response: false,
+                    actor: Some(sender),
+                    can_navigate: true,
+                })
+            }
         }
     }
 
@@ -414,7 +684,7 @@ impl NotificationPanel {
         cx: &mut ViewContext<Self>,
     ) {
         let should_mark_as_read = match notification {
-            Notification::ContactRequestAccepted { .. } => true,
+            Notification::ContactRequestAccepted { .. } | Notification::CodeComment { .. } => true,
             Notification::ContactRequest { .. }
             | Notification::ChannelInvitation { .. }
             | Notification::ChannelMessageMention { .. } => false,
@@ -440,13 +710,15 @@ impl NotificationPanel {
     }
 
     fn did_click_notification(&mut self, notification: &Notification, cx: &mut ViewContext<Self>) {
-        if let Notification::ChannelMessageMention {
-            message_id,
-            channel_id,
-            ..
-        } = notification.clone()
-        {
-            if let Some(workspace) = self.workspace.upgrade() {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        match notification.clone() {
+            Notification::ChannelMessageMention {
+                message_id,
+                channel_id,
+                ..
+            } => {
                 cx.window_context().defer(move |cx| {
                     workspace.update(cx, |workspace, cx| {
                         if let Some(panel) = workspace.focus_panel::<ChatPanel>(cx) {
@@ -459,6 +731,19 @@ impl NotificationPanel {
                     });
                 });
             }
+            Notification::CodeComment { channel_id, .. } => {
+                cx.window_context().defer(move |cx| {
+                    if let Some(channel_id) = channel_id {
+                        ChannelView::open(ChannelId(channel_id), None, workspace, cx)
+                            .detach_and_log_err(cx);
+                    } else {
+                        workspace.update(cx, |workspace, cx| {
+                            workspace.focus_panel::<CommentsPanel>(cx);
+                        });
+                    }
+                });
+            }
+            _ => {}
         }
     }
 
@@ -586,7 +871,7 @@ impl Render for NotificationPanel {
                     .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
                     .border_b_1()
                     .border_color(cx.theme().colors().border)
-                    .child(Label::new("Notifications"))
+                    .child(self.render_tabs(cx))
                     .child(Icon::new(IconName::Envelope)),
             )
             .map(|this| {
@@ -624,6 +909,20 @@ impl Render for NotificationPanel {
                                 ),
                             ),
                     )
+                } else if self.active_tab == NotificationPanelTab::Activity {
+                    if self.activity_list.item_count() == 0 {
+                        this.child(
+                            v_flex().p_4().child(
+                                div().flex().w_full().items_center().child(
+                                    Label::new("Nothing has happened in your channels yet.")
+                                        .color(Color::Muted)
+                                        .size(LabelSize::Small),
+                                ),
+                            ),
+                        )
+                    } else {
+                        this.child(list(self.activity_list.clone()).size_full())
+                    }
                 } else if self.notification_list.item_count() == 0 {
                     this.child(
                         v_flex().p_4().child(
@@ -687,10 +986,15 @@ impl Panel for NotificationPanel {
 
         if self.active {
             self.unseen_notifications = Vec::new();
+            if self.active_tab == NotificationPanelTab::Activity {
+                self.acknowledge_activity_after_delay(cx);
+            }
             cx.notify();
         }
 
-        if self.notification_store.read(cx).notification_count() == 0 {
+        if self.notification_store.read(cx).notification_count() == 0
+            && self.activity_store.read(cx).activity_count() == 0
+        {
             cx.emit(Event::Dismissed);
         }
     }
@@ -701,7 +1005,9 @@ impl Panel for NotificationPanel {
             return None;
         }
 
-        if self.unseen_notifications.is_empty() {
+        if self.unseen_notifications.is_empty()
+            && self.activity_store.read(cx).unread_activity_count() == 0
+        {
             return Some(IconName::Bell);
         }
 
@@ -26,6 +26,13 @@ pub struct NotificationPanelSettings {
     pub default_width: Pixels,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct CommentsPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
 pub struct PanelSettingsContent {
     /// Whether to show the panel button in the status bar.
@@ -87,6 +94,18 @@ impl Settings for NotificationPanelSettings {
     }
 }
 
+impl Settings for CommentsPanelSettings {
+    const KEY: Option<&'static str> = Some("comments_panel");
+    type FileContent = PanelSettingsContent;
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
 impl Settings for MessageEditorSettings {
     const KEY: Option<&'static str> = Some("message_editor");
     type FileContent = MessageEditorSettings;
@@ -0,0 +1,306 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::keymap::{
+    zed_keystroke, KeymapBuilder, EDITOR, FULL_EDITOR, PANE, PROJECT_PANEL, TERMINAL, WORKSPACE,
+};
+use crate::report::ImportReport;
+
+/// JetBrains action identifiers, along with the Zed actions they map onto and the context
+/// in which those actions are bound.
+const ACTIONS: &[(&str, &str, Option<&str>)] = &[
+    ("GotoAction", "command_palette::Toggle", WORKSPACE),
+    ("GotoFile", "file_finder::Toggle", WORKSPACE),
+    ("SearchEverywhere", "file_finder::Toggle", WORKSPACE),
+    ("GotoLine", "go_to_line::Toggle", FULL_EDITOR),
+    ("FileStructurePopup", "outline::Toggle", FULL_EDITOR),
+    ("GotoSymbol", "project_symbols::Toggle", WORKSPACE),
+    ("RecentProjectListGroup", "projects::OpenRecent", WORKSPACE),
+    ("FindInPath", "pane::DeploySearch", WORKSPACE),
+    ("ReplaceInPath", "pane::DeploySearch", WORKSPACE),
+    ("Find", "buffer_search::Deploy", FULL_EDITOR),
+    ("FindNext", "search::SelectNextMatch", PANE),
+    ("FindPrevious", "search::SelectPrevMatch", PANE),
+    ("SaveAll", "workspace::SaveAll", WORKSPACE),
+    ("NewFile", "workspace::NewFile", WORKSPACE),
+    ("OpenFile", "workspace::Open", WORKSPACE),
+    ("Exit", "zed::Quit", None),
+    ("ToggleFullScreen", "zed::ToggleFullScreen", None),
+    ("ShowSettings", "zed::OpenSettings", None),
+    (
+        "EditorIncreaseFontSize",
+        "zed::IncreaseBufferFontSize",
+        None,
+    ),
+    (
+        "EditorDecreaseFontSize",
+        "zed::DecreaseBufferFontSize",
+        None,
+    ),
+    ("EditorResetFontSize", "zed::ResetBufferFontSize", None),
+    (
+        "ActivateProjectToolWindow",
+        "project_panel::ToggleFocus",
+        WORKSPACE,
+    ),
+    (
+        "ActivateTerminalToolWindow",
+        "terminal_panel::ToggleFocus",
+        WORKSPACE,
+    ),
+    (
+        "ActivateProblemsViewToolWindow",
+        "diagnostics::Deploy",
+        WORKSPACE,
+    ),
+    ("HideAllWindows", "workspace::CloseAllDocks", WORKSPACE),
+    (
+        "ToggleDistractionFreeMode",
+        "workspace::ToggleZoom",
+        WORKSPACE,
+    ),
+    ("Run", "task::Spawn", WORKSPACE),
+    ("Rerun", "task::Rerun", WORKSPACE),
+    ("CloseContent", "pane::CloseActiveItem", PANE),
+    ("CloseAllEditors", "pane::CloseAllItems", PANE),
+    ("CloseAllEditorsButActive", "pane::CloseInactiveItems", PANE),
+    ("ReopenClosedTab", "pane::ReopenClosedItem", PANE),
+    ("NextTab", "pane::ActivateNextItem", PANE),
+    ("PreviousTab", "pane::ActivatePrevItem", PANE),
+    ("Back", "pane::GoBack", PANE),
+    ("Forward", "pane::GoForward", PANE),
+    ("SplitVertically", "pane::SplitRight", PANE),
+    ("SplitHorizontally", "pane::SplitDown", PANE),
+    ("$Undo", "editor::Undo", EDITOR),
+    ("$Redo", "editor::Redo", EDITOR),
+    ("$Copy", "editor::Copy", EDITOR),
+    ("$Cut", "editor::Cut", EDITOR),
+    ("$Paste", "editor::Paste", EDITOR),
+    ("$SelectAll", "editor::SelectAll", EDITOR),
+    ("$Delete", "editor::Delete", EDITOR),
+    ("EditorBackSpace", "editor::Backspace", EDITOR),
+    ("EditorLineStart", "editor::MoveToBeginningOfLine", EDITOR),
+    ("EditorLineEnd", "editor::MoveToEndOfLine", EDITOR),
+    ("EditorTextStart", "editor::MoveToBeginning", EDITOR),
+    ("EditorTextEnd", "editor::MoveToEnd", EDITOR),
+    (
+        "EditorPreviousWord",
+        "editor::MoveToPreviousWordStart",
+        EDITOR,
+    ),
+    ("EditorNextWord", "editor::MoveToNextWordEnd", EDITOR),
+    (
+        "EditorPreviousWordWithSelection",
+        "editor::SelectToPreviousWordStart",
+        EDITOR,
+    ),
+    (
+        "EditorNextWordWithSelection",
+        "editor::SelectToNextWordEnd",
+        EDITOR,
+    ),
+    (
+        "EditorDeleteToWordStart",
+        "editor::DeleteToPreviousWordStart",
+        EDITOR,
+    ),
+    (
+        "EditorDeleteToWordEnd",
+        "editor::DeleteToNextWordEnd",
+        EDITOR,
+    ),
+    ("EditorDeleteLine", "editor::DeleteLine", EDITOR),
+    ("EditorDuplicate", "editor::DuplicateLine", EDITOR),
+    ("EditorJoinLines", "editor::JoinLines", EDITOR),
+    ("EditorStartNewLine", "editor::NewlineBelow", EDITOR),
+    ("EditorStartNewLineBefore", "editor::NewlineAbove", EDITOR),
+    ("EditorIndentSelection", "editor::Indent", EDITOR),
+    ("EditorUnindentSelection", "editor::Outdent", EDITOR),
+    ("EditorSelectWord", "editor::SelectLargerSyntaxNode", EDITOR),
+    (
+        "EditorUnSelectWord",
+        "editor::SelectSmallerSyntaxNode",
+        EDITOR,
+    ),
+    ("EditorCloneCaretAbove", "editor::AddSelectionAbove", EDITOR),
+    ("EditorCloneCaretBelow", "editor::AddSelectionBelow", EDITOR),
+    ("EditorMatchBrace", "editor::MoveToEnclosingBracket", EDITOR),
+    ("CommentByLineComment", "editor::ToggleComments", EDITOR),
+    ("MoveLineUp", "editor::MoveLineUp", EDITOR),
+    ("MoveLineDown", "editor::MoveLineDown", EDITOR),
+    ("SelectNextOccurrence", "editor::SelectNext", EDITOR),
+    ("SelectAllOccurrences", "editor::SelectAllMatches", EDITOR),
+    ("CollapseRegion", "editor::Fold", EDITOR),
+    ("ExpandRegion", "editor::UnfoldLines", EDITOR),
+    ("GotoDeclaration", "editor::GoToDefinition", EDITOR),
+    ("GotoTypeDeclaration", "editor::GoToTypeDefinition", EDITOR),
+    ("FindUsages", "editor::FindAllReferences", EDITOR),
+    ("RenameElement", "editor::Rename", EDITOR),
+    ("ReformatCode", "editor::Format", EDITOR),
+    ("ShowIntentionActions", "editor::ToggleCodeActions", EDITOR),
+    ("QuickJavaDoc", "editor::Hover", EDITOR),
+    ("CodeCompletion", "editor::ShowCompletions", EDITOR),
+    ("GotoNextError", "editor::GoToDiagnostic", EDITOR),
+    ("GotoPreviousError", "editor::GoToPrevDiagnostic", EDITOR),
+    ("VcsShowNextChangeMarker", "editor::GoToHunk", FULL_EDITOR),
+    (
+        "VcsShowPrevChangeMarker",
+        "editor::GoToPrevHunk",
+        FULL_EDITOR,
+    ),
+    ("NewElement", "project_panel::NewFile", PROJECT_PANEL),
+    ("NewDir", "project_panel::NewDirectory", PROJECT_PANEL),
+    ("CopyPaths", "project_panel::CopyPath", PROJECT_PANEL),
+    (
+        "CopyContentRootPath",
+        "project_panel::CopyRelativePath",
+        PROJECT_PANEL,
+    ),
+    ("RevealIn", "project_panel::RevealInFinder", PROJECT_PANEL),
+    ("Terminal.ClearBuffer", "terminal::Clear", TERMINAL),
+    ("Terminal.CopySelectedText", "terminal::Copy", TERMINAL),
+    ("Terminal.Paste", "terminal::Paste", TERMINAL),
+];
+
+/// Converts the contents of a JetBrains keymap XML file to a Zed keymap.
+///
+/// Only the shortcuts listed in the file are imported, and not those it inherits from
+/// its parent keymap.
+pub fn import_keymap(content: &str, report: &mut ImportReport) -> Result<Value> {
+    let document =
+        roxmltree::Document::parse(content).context("failed to parse JetBrains keymap")?;
+    let root = document.root_element();
+    anyhow::ensure!(
+        root.has_tag_name("keymap"),
+        "expected a <keymap> element, found <{}>",
+        root.tag_name().name()
+    );
+
+    let mut keymap = KeymapBuilder::default();
+    for action in root.children().filter(|node| node.has_tag_name("action")) {
+        let Some(id) = action.attribute("id") else {
+            continue;
+        };
+        let zed_action = ACTIONS.iter().find(|(name, _, _)| *name == id);
+
+        for shortcut in action.children().filter(roxmltree::Node::is_element) {
+            let Some(first_keystroke) = shortcut.attribute("first-keystroke").or_else(|| {
+                shortcut
+                    .has_tag_name("mouse-shortcut")
+                    .then(|| shortcut.attribute("keystroke"))
+                    .flatten()
+            }) else {
+                continue;
+            };
+            let keystrokes = [
+                Some(first_keystroke),
+                shortcut.attribute("second-keystroke"),
+            ];
+            let item = format!(
+                "{} ({id})",
+                keystrokes
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+
+            if !shortcut.has_tag_name("keyboard-shortcut") {
+                report.skip(item, "only keyboard shortcuts are supported");
+                continue;
+            }
+            let Some((_, zed_action, context)) = zed_action else {
+                report.skip(item, "no equivalent Zed action");
+                continue;
+            };
+            let Some(keystrokes) = keystrokes
+                .iter()
+                .flatten()
+                .map(|keystroke| zed_keystroke_for_jetbrains(keystroke))
+                .collect::<Option<Vec<_>>>()
+            else {
+                report.skip(item, "unsupported keystroke");
+                continue;
+            };
+
+            keymap.bind(*context, keystrokes.join(" "), Value::from(*zed_action));
+            report.import();
+        }
+    }
+    Ok(keymap.into_json())
+}
+
+/// Converts a JetBrains keystroke, like `control shift BACK_SPACE`, to Zed's syntax.
+fn zed_keystroke_for_jetbrains(keystroke: &str) -> Option<String> {
+    let mut parts = keystroke.split_whitespace().collect::<Vec<_>>();
+    let key = parts.pop()?;
+    zed_keystroke(parts, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_import_keymap() {
+        let mut report = ImportReport::default();
+        let keymap = import_keymap(
+            r#"<keymap version="1" name="Custom" parent="$default">
+                <action id="EditorDuplicate">
+                    <keyboard-shortcut first-keystroke="control D" />
+                </action>
+                <action id="CommentByLineComment">
+                    <keyboard-shortcut first-keystroke="control SLASH" />
+                    <keyboard-shortcut first-keystroke="control K" second-keystroke="control C" />
+                </action>
+                <action id="GotoDeclaration">
+                    <mouse-shortcut keystroke="control button1" />
+                </action>
+                <action id="ActivateStructureToolWindow">
+                    <keyboard-shortcut first-keystroke="alt 7" />
+                </action>
+                <action id="GotoLine">
+                    <keyboard-shortcut first-keystroke="control NUMPAD1" />
+                </action>
+                <action id="$Redo" />
+            </keymap>"#,
+            &mut report,
+        )
+        .unwrap();
+
+        assert_eq!(
+            keymap,
+            json!([
+                {
+                    "context": "Editor",
+                    "bindings": {
+                        "ctrl-d": "editor::DuplicateLine",
+                        "ctrl-/": "editor::ToggleComments",
+                        "ctrl-k ctrl-c": "editor::ToggleComments"
+                    }
+                }
+            ])
+        );
+        assert_eq!(report.imported, 3);
+        assert_eq!(
+            report
+                .skipped
+                .iter()
+                .map(|skipped| (skipped.item.as_str(), skipped.reason.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    "control button1 (GotoDeclaration)",
+                    "only keyboard shortcuts are supported"
+                ),
+                (
+                    "alt 7 (ActivateStructureToolWindow)",
+                    "no equivalent Zed action"
+                ),
+                ("control NUMPAD1 (GotoLine)", "unsupported keystroke"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use serde_json::{Map, Value};
+
+pub const EDITOR: Option<&str> = Some("Editor");
+pub const FULL_EDITOR: Option<&str> = Some("Editor && mode == full");
+pub const PANE: Option<&str> = Some("Pane");
+pub const PROJECT_PANEL: Option<&str> = Some("ProjectPanel");
+pub const TERMINAL: Option<&str> = Some("Terminal");
+pub const WORKSPACE: Option<&str> = Some("Workspace");
+
+/// The order in which Zed writes the modifiers of a keystroke.
+const MODIFIERS: &[&str] = &["ctrl", "alt", "shift", "cmd", "fn"];
+
+/// A Zed keymap being assembled from another editor's key bindings, with one block of
+/// bindings per context.
+#[derive(Debug, Default)]
+pub struct KeymapBuilder {
+    blocks: Vec<(Option<String>, Map<String, Value>)>,
+}
+
+impl KeymapBuilder {
+    /// Binds the keystrokes to an action, or disables them if the action is `null`.
+    pub fn bind(&mut self, context: Option<&str>, keystrokes: String, action: Value) {
+        let ix = match self
+            .blocks
+            .iter()
+            .position(|(block_context, _)| block_context.as_deref() == context)
+        {
+            Some(ix) => ix,
+            None => {
+                self.blocks
+                    .push((context.map(ToString::to_string), Map::new()));
+                self.blocks.len() - 1
+            }
+        };
+        self.blocks[ix].1.insert(keystrokes, action);
+    }
+
+    pub fn into_json(self) -> Value {
+        Value::Array(
+            self.blocks
+                .into_iter()
+                .map(|(context, bindings)| {
+                    let mut block = Map::new();
+                    if let Some(context) = context {
+                        block.insert("context".into(), context.into());
+                    }
+                    block.insert("bindings".into(), bindings.into());
+                    block.into()
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Builds a keystroke in Zed's syntax from the names of its modifiers and key, returning
+/// `None` if one of them has no equivalent in Zed.
+pub fn zed_keystroke<'a>(
+    modifiers: impl IntoIterator<Item = &'a str>,
+    key: &str,
+) -> Option<String> {
+    let mut zed_modifiers = Vec::new();
+    for modifier in modifiers {
+        let modifier = match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => "ctrl",
+            "alt" | "option" => "alt",
+            "shift" => "shift",
+            "cmd" | "meta" | "super" | "win" => "cmd",
+            "fn" => "fn",
+            _ => return None,
+        };
+        if !zed_modifiers.contains(&modifier) {
+            zed_modifiers.push(modifier);
+        }
+    }
+    zed_modifiers.sort_by_key(|modifier| MODIFIERS.iter().position(|m| m == modifier));
+
+    let key = zed_key(key)?;
+    let mut keystroke = String::new();
+    for modifier in zed_modifiers {
+        keystroke.push_str(modifier);
+        keystroke.push('-');
+    }
+    keystroke.push_str(&key);
+    Some(keystroke)
+}
+
+/// Converts a key name used by another editor to the one Zed uses.
+fn zed_key(key: &str) -> Option<String> {
+    let lowercase = key.to_lowercase();
+    let key = match lowercase.as_str() {
+        "return" | "enter" => "enter",
+        "esc" | "escape" => "escape",
+        "back_space" | "backspace" => "backspace",
+        "del" | "delete" => "delete",
+        "ins" | "insert" => "insert",
+        "tab" => "tab",
+        "space" => "space",
+        "home" => "home",
+        "end" => "end",
+        "pageup" | "page_up" => "pageup",
+        "pagedown" | "page_down" | "page_down_key" => "pagedown",
+        "up" | "arrowup" => "up",
+        "down" | "arrowdown" => "down",
+        "left" | "arrowleft" => "left",
+        "right" | "arrowright" => "right",
+        "slash" | "forward_slash" => "/",
+        "back_slash" | "backslash" => "\\",
+        "open_bracket" => "[",
+        "close_bracket" => "]",
+        "comma" => ",",
+        "period" => ".",
+        "minus" => "-",
+        "equals" => "=",
+        "semicolon" => ";",
+        "quote" => "'",
+        "back_quote" | "backquote" => "`",
+        key if key.chars().count() == 1 => key,
+        key if key
+            .strip_prefix('f')
+            .and_then(|number| number.parse::<u8>().ok())
+            .map_or(false, |number| (1..=19).contains(&number)) =>
+        {
+            key
+        }
+        _ => return None,
+    };
+    Some(key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_zed_keystroke() {
+        assert_eq!(
+            zed_keystroke(["shift", "ctrl"], "P").as_deref(),
+            Some("ctrl-shift-p")
+        );
+        assert_eq!(
+            zed_keystroke(["meta", "alt"], "BACK_SPACE").as_deref(),
+            Some("alt-cmd-backspace")
+        );
+        assert_eq!(zed_keystroke(["fn"], "F12").as_deref(), Some("fn-f12"));
+        assert_eq!(zed_keystroke(["ctrl"], "numpad_add"), None);
+        assert_eq!(zed_keystroke(["hyper"], "a"), None);
+    }
+
+    #[test]
+    fn test_keymap_builder() {
+        let mut keymap = KeymapBuilder::default();
+        keymap.bind(EDITOR, "ctrl-d".into(), "editor::DuplicateLine".into());
+        keymap.bind(None, "ctrl-p".into(), "file_finder::Toggle".into());
+        keymap.bind(EDITOR, "ctrl-k".into(), Value::Null);
+        assert_eq!(
+            keymap.into_json(),
+            json!([
+                {
+                    "context": "Editor",
+                    "bindings": { "ctrl-d": "editor::DuplicateLine", "ctrl-k": null }
+                },
+                { "bindings": { "ctrl-p": "file_finder::Toggle" } }
+            ])
+        );
+    }
+}
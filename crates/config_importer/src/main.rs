@@ -0,0 +1,73 @@
+mod jetbrains;
+mod keymap;
+mod report;
+mod sublime;
+mod vscode;
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+
+use crate::report::ImportReport;
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    /// The path to write the Zed settings or keymap to. Defaults to stdout.
+    #[arg(long, short, global = true)]
+    output: Option<PathBuf>,
+
+    /// The path to write the report of what couldn't be imported to. Defaults to stderr.
+    #[arg(long, global = true)]
+    report: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Converts a VS Code `settings.json` file to Zed settings.
+    #[command(name = "vscode-settings")]
+    VsCodeSettings { path: PathBuf },
+    /// Converts a VS Code `keybindings.json` file to a Zed keymap.
+    #[command(name = "vscode-keybindings")]
+    VsCodeKeybindings { path: PathBuf },
+    /// Converts a Sublime Text `.sublime-keymap` file to a Zed keymap.
+    SublimeKeymap { path: PathBuf },
+    /// Converts a JetBrains keymap XML file to a Zed keymap.
+    JetbrainsKeymap { path: PathBuf },
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let (path, import): (_, fn(&str, &mut ImportReport) -> Result<serde_json::Value>) =
+        match &args.command {
+            Command::VsCodeSettings { path } => (path, vscode::import_settings),
+            Command::VsCodeKeybindings { path } => (path, vscode::import_keybindings),
+            Command::SublimeKeymap { path } => (path, sublime::import_keymap),
+            Command::JetbrainsKeymap { path } => (path, jetbrains::import_keymap),
+        };
+    let content = fs::read_to_string(path).with_context(|| format!("failed to read {path:?}"))?;
+
+    let mut report = ImportReport::default();
+    let output = import(&content, &mut report)?;
+    let output = serde_json::to_string_pretty(&output)?;
+
+    match &args.output {
+        Some(output_path) => fs::write(output_path, output)
+            .with_context(|| format!("failed to write {output_path:?}"))?,
+        None => println!("{output}"),
+    }
+    match &args.report {
+        Some(report_path) => fs::write(report_path, report.to_string())
+            .with_context(|| format!("failed to write {report_path:?}"))?,
+        None => write!(std::io::stderr(), "{report}")?,
+    }
+
+    Ok(())
+}
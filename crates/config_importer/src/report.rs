@@ -0,0 +1,41 @@
+use std::fmt;
+
+/// A setting or key binding that couldn't be imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedItem {
+    pub item: String,
+    pub reason: String,
+}
+
+/// A summary of what was imported from another editor's configuration.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub skipped: Vec<SkippedItem>,
+}
+
+impl ImportReport {
+    pub fn import(&mut self) {
+        self.imported += 1;
+    }
+
+    pub fn skip(&mut self, item: impl Into<String>, reason: impl Into<String>) {
+        self.skipped.push(SkippedItem {
+            item: item.into(),
+            reason: reason.into(),
+        });
+    }
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Imported {} items.", self.imported)?;
+        if !self.skipped.is_empty() {
+            writeln!(f, "Couldn't import {} items:", self.skipped.len())?;
+            for skipped in &self.skipped {
+                writeln!(f, "  - {}: {}", skipped.item, skipped.reason)?;
+            }
+        }
+        Ok(())
+    }
+}
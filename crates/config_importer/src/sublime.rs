@@ -0,0 +1,349 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::keymap::{zed_keystroke, KeymapBuilder, EDITOR, FULL_EDITOR, PANE, WORKSPACE};
+use crate::report::ImportReport;
+
+/// Sublime Text commands, along with the arguments they must be given, the Zed actions
+/// they map onto and the context in which those actions are bound.
+const COMMANDS: &[(&str, Option<&str>, &str, Option<&str>)] = &[
+    (
+        "show_overlay",
+        Some(r#"{"overlay": "command_palette"}"#),
+        "command_palette::Toggle",
+        WORKSPACE,
+    ),
+    (
+        "show_overlay",
+        Some(r#"{"overlay": "goto", "show_files": true}"#),
+        "file_finder::Toggle",
+        WORKSPACE,
+    ),
+    (
+        "show_overlay",
+        Some(r#"{"overlay": "goto", "text": "@"}"#),
+        "outline::Toggle",
+        FULL_EDITOR,
+    ),
+    (
+        "show_overlay",
+        Some(r#"{"overlay": "goto", "text": ":"}"#),
+        "go_to_line::Toggle",
+        FULL_EDITOR,
+    ),
+    (
+        "goto_symbol_in_project",
+        None,
+        "project_symbols::Toggle",
+        WORKSPACE,
+    ),
+    (
+        "show_panel",
+        Some(r#"{"panel": "find_in_files"}"#),
+        "pane::DeploySearch",
+        WORKSPACE,
+    ),
+    (
+        "show_panel",
+        Some(r#"{"panel": "find"}"#),
+        "buffer_search::Deploy",
+        FULL_EDITOR,
+    ),
+    ("find_next", None, "search::SelectNextMatch", PANE),
+    ("find_prev", None, "search::SelectPrevMatch", PANE),
+    (
+        "toggle_side_bar",
+        None,
+        "workspace::ToggleLeftDock",
+        WORKSPACE,
+    ),
+    ("save", None, "workspace::Save", WORKSPACE),
+    ("save_all", None, "workspace::SaveAll", WORKSPACE),
+    ("prompt_save_as", None, "workspace::SaveAs", WORKSPACE),
+    ("new_file", None, "workspace::NewFile", WORKSPACE),
+    ("prompt_open", None, "workspace::Open", WORKSPACE),
+    ("new_window", None, "workspace::NewWindow", WORKSPACE),
+    ("close_window", None, "workspace::CloseWindow", WORKSPACE),
+    ("exit", None, "zed::Quit", None),
+    ("toggle_full_screen", None, "zed::ToggleFullScreen", None),
+    (
+        "increase_font_size",
+        None,
+        "zed::IncreaseBufferFontSize",
+        None,
+    ),
+    (
+        "decrease_font_size",
+        None,
+        "zed::DecreaseBufferFontSize",
+        None,
+    ),
+    ("reset_font_size", None, "zed::ResetBufferFontSize", None),
+    ("close", None, "pane::CloseActiveItem", PANE),
+    ("close_all", None, "pane::CloseAllItems", PANE),
+    ("reopen_last_file", None, "pane::ReopenClosedItem", PANE),
+    ("next_view", None, "pane::ActivateNextItem", PANE),
+    ("prev_view", None, "pane::ActivatePrevItem", PANE),
+    ("jump_back", None, "pane::GoBack", PANE),
+    ("jump_forward", None, "pane::GoForward", PANE),
+    ("undo", None, "editor::Undo", EDITOR),
+    ("redo", None, "editor::Redo", EDITOR),
+    ("redo_or_repeat", None, "editor::Redo", EDITOR),
+    ("soft_undo", None, "editor::UndoSelection", EDITOR),
+    ("soft_redo", None, "editor::RedoSelection", EDITOR),
+    ("copy", None, "editor::Copy", EDITOR),
+    ("cut", None, "editor::Cut", EDITOR),
+    ("paste", None, "editor::Paste", EDITOR),
+    ("select_all", None, "editor::SelectAll", EDITOR),
+    (
+        "expand_selection",
+        Some(r#"{"to": "line"}"#),
+        "editor::SelectLine",
+        EDITOR,
+    ),
+    ("toggle_comment", None, "editor::ToggleComments", EDITOR),
+    ("duplicate_line", None, "editor::DuplicateLine", EDITOR),
+    ("swap_line_up", None, "editor::MoveLineUp", EDITOR),
+    ("swap_line_down", None, "editor::MoveLineDown", EDITOR),
+    ("join_lines", None, "editor::JoinLines", EDITOR),
+    ("indent", None, "editor::Indent", EDITOR),
+    ("unindent", None, "editor::Outdent", EDITOR),
+    (
+        "run_macro_file",
+        Some(r#"{"file": "res://Packages/Default/Delete Line.sublime-macro"}"#),
+        "editor::DeleteLine",
+        EDITOR,
+    ),
+    (
+        "run_macro_file",
+        Some(r#"{"file": "res://Packages/Default/Add Line.sublime-macro"}"#),
+        "editor::NewlineBelow",
+        EDITOR,
+    ),
+    (
+        "run_macro_file",
+        Some(r#"{"file": "res://Packages/Default/Add Line Before.sublime-macro"}"#),
+        "editor::NewlineAbove",
+        EDITOR,
+    ),
+    ("find_under_expand", None, "editor::SelectNext", EDITOR),
+    ("find_all_under", None, "editor::SelectAllMatches", EDITOR),
+    (
+        "select_lines",
+        Some(r#"{"forward": false}"#),
+        "editor::AddSelectionAbove",
+        EDITOR,
+    ),
+    (
+        "select_lines",
+        Some(r#"{"forward": true}"#),
+        "editor::AddSelectionBelow",
+        EDITOR,
+    ),
+    (
+        "move",
+        Some(r#"{"by": "words", "forward": false}"#),
+        "editor::MoveToPreviousWordStart",
+        EDITOR,
+    ),
+    (
+        "move",
+        Some(r#"{"by": "word_ends", "forward": true}"#),
+        "editor::MoveToNextWordEnd",
+        EDITOR,
+    ),
+    (
+        "move",
+        Some(r#"{"by": "words", "forward": false, "extend": true}"#),
+        "editor::SelectToPreviousWordStart",
+        EDITOR,
+    ),
+    (
+        "move",
+        Some(r#"{"by": "word_ends", "forward": true, "extend": true}"#),
+        "editor::SelectToNextWordEnd",
+        EDITOR,
+    ),
+    (
+        "move_to",
+        Some(r#"{"to": "bol"}"#),
+        "editor::MoveToBeginningOfLine",
+        EDITOR,
+    ),
+    (
+        "move_to",
+        Some(r#"{"to": "eol"}"#),
+        "editor::MoveToEndOfLine",
+        EDITOR,
+    ),
+    (
+        "move_to",
+        Some(r#"{"to": "bof"}"#),
+        "editor::MoveToBeginning",
+        EDITOR,
+    ),
+    (
+        "move_to",
+        Some(r#"{"to": "eof"}"#),
+        "editor::MoveToEnd",
+        EDITOR,
+    ),
+    (
+        "move_to",
+        Some(r#"{"to": "brackets"}"#),
+        "editor::MoveToEnclosingBracket",
+        EDITOR,
+    ),
+    (
+        "delete_word",
+        Some(r#"{"forward": false}"#),
+        "editor::DeleteToPreviousWordStart",
+        EDITOR,
+    ),
+    (
+        "delete_word",
+        Some(r#"{"forward": true}"#),
+        "editor::DeleteToNextWordEnd",
+        EDITOR,
+    ),
+    ("fold", None, "editor::Fold", EDITOR),
+    ("unfold", None, "editor::UnfoldLines", EDITOR),
+    ("goto_definition", None, "editor::GoToDefinition", EDITOR),
+    ("goto_reference", None, "editor::FindAllReferences", EDITOR),
+    ("auto_complete", None, "editor::ShowCompletions", EDITOR),
+];
+
+#[derive(Debug, Deserialize)]
+struct SublimeKeybinding {
+    keys: Vec<String>,
+    command: String,
+    #[serde(default)]
+    args: Map<String, Value>,
+    #[serde(default)]
+    context: Vec<Value>,
+}
+
+/// Converts the contents of a `.sublime-keymap` file to a Zed keymap.
+///
+/// Bindings that only apply in some Sublime Text contexts aren't imported, as those
+/// contexts have no equivalent in Zed and binding their keys everywhere would override
+/// more common bindings.
+pub fn import_keymap(content: &str, report: &mut ImportReport) -> Result<Value> {
+    let keybindings: Vec<SublimeKeybinding> =
+        serde_json_lenient::from_str(content).context("failed to parse Sublime Text keymap")?;
+
+    let mut keymap = KeymapBuilder::default();
+    for keybinding in keybindings {
+        let item = format!("{} ({})", keybinding.keys.join(" "), keybinding.command);
+        if !keybinding.context.is_empty() {
+            report.skip(item, "depends on a Sublime Text context");
+            continue;
+        }
+        let Some((_, _, action, context)) = COMMANDS.iter().find(|(command, args, _, _)| {
+            *command == keybinding.command && args_match(*args, &keybinding.args)
+        }) else {
+            report.skip(item, "no equivalent Zed action");
+            continue;
+        };
+        let Some(keystrokes) = keybinding
+            .keys
+            .iter()
+            .map(|keystroke| zed_keystroke_for_sublime(keystroke))
+            .collect::<Option<Vec<_>>>()
+        else {
+            report.skip(item, "unsupported keystroke");
+            continue;
+        };
+
+        keymap.bind(*context, keystrokes.join(" "), Value::from(*action));
+        report.import();
+    }
+    Ok(keymap.into_json())
+}
+
+/// Returns whether a binding's arguments are those expected for a command. Arguments
+/// that aren't expected must be `false`, which is the default for Sublime Text's flags.
+fn args_match(expected: Option<&str>, args: &Map<String, Value>) -> bool {
+    let expected: Map<String, Value> = match expected {
+        Some(expected) => serde_json::from_str(expected).unwrap(),
+        None => Map::new(),
+    };
+    expected
+        .iter()
+        .all(|(key, value)| args.get(key) == Some(value))
+        && args
+            .iter()
+            .all(|(key, value)| expected.contains_key(key) || *value == Value::Bool(false))
+}
+
+/// Converts a Sublime Text keystroke, like `ctrl+shift+p`, to Zed's syntax.
+fn zed_keystroke_for_sublime(keystroke: &str) -> Option<String> {
+    let mut parts = keystroke.split('+').collect::<Vec<_>>();
+    // The `+` key itself is written as `ctrl++`.
+    if keystroke.ends_with("++") {
+        parts.truncate(parts.len() - 2);
+        parts.push("+");
+    }
+    let key = parts.pop()?;
+    let modifiers = parts.into_iter().map(|modifier| match modifier {
+        // Sublime Text's platform-independent modifier.
+        "primary" if cfg!(target_os = "macos") => "cmd",
+        "primary" => "ctrl",
+        modifier => modifier,
+    });
+    zed_keystroke(modifiers, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_import_keymap() {
+        let mut report = ImportReport::default();
+        let keymap = import_keymap(
+            r#"[
+                { "keys": ["ctrl+shift+d"], "command": "duplicate_line" },
+                { "keys": ["ctrl+k", "ctrl+u"], "command": "upper_case" },
+                { "keys": ["ctrl+left"], "command": "move", "args": {"by": "words", "forward": false} },
+                { "keys": ["ctrl+shift+left"], "command": "move", "args": {"by": "words", "forward": false, "extend": true} },
+                { "keys": ["ctrl+r"], "command": "show_overlay", "args": {"overlay": "goto", "text": "@"} },
+                { "keys": ["escape"], "command": "hide_panel", "context": [{ "key": "panel_visible" }] },
+            ]"#,
+            &mut report,
+        )
+        .unwrap();
+
+        assert_eq!(
+            keymap,
+            json!([
+                {
+                    "context": "Editor",
+                    "bindings": {
+                        "ctrl-shift-d": "editor::DuplicateLine",
+                        "ctrl-left": "editor::MoveToPreviousWordStart",
+                        "ctrl-shift-left": "editor::SelectToPreviousWordStart"
+                    }
+                },
+                {
+                    "context": "Editor && mode == full",
+                    "bindings": { "ctrl-r": "outline::Toggle" }
+                }
+            ])
+        );
+        assert_eq!(report.imported, 4);
+        assert_eq!(
+            report
+                .skipped
+                .iter()
+                .map(|skipped| skipped.reason.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "no equivalent Zed action",
+                "depends on a Sublime Text context"
+            ]
+        );
+    }
+}
@@ -0,0 +1,873 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::keymap::{
+    zed_keystroke, KeymapBuilder, EDITOR, FULL_EDITOR, PANE, PROJECT_PANEL, TERMINAL, WORKSPACE,
+};
+use crate::report::ImportReport;
+
+/// VS Code commands, along with the Zed actions they map onto and the context in which
+/// those actions are bound.
+const COMMANDS: &[(&str, &str, Option<&str>)] = &[
+    (
+        "workbench.action.showCommands",
+        "command_palette::Toggle",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.quickOpen",
+        "file_finder::Toggle",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.gotoLine",
+        "go_to_line::Toggle",
+        FULL_EDITOR,
+    ),
+    (
+        "workbench.action.gotoSymbol",
+        "outline::Toggle",
+        FULL_EDITOR,
+    ),
+    (
+        "workbench.action.showAllSymbols",
+        "project_symbols::Toggle",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.openRecent",
+        "projects::OpenRecent",
+        WORKSPACE,
+    ),
+    ("workbench.action.files.save", "workspace::Save", WORKSPACE),
+    (
+        "workbench.action.files.saveAll",
+        "workspace::SaveAll",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.files.saveAs",
+        "workspace::SaveAs",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.files.newUntitledFile",
+        "workspace::NewFile",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.files.openFile",
+        "workspace::Open",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.files.openFolder",
+        "workspace::Open",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.newWindow",
+        "workspace::NewWindow",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.closeWindow",
+        "workspace::CloseWindow",
+        WORKSPACE,
+    ),
+    ("workbench.action.quit", "zed::Quit", None),
+    (
+        "workbench.action.toggleFullScreen",
+        "zed::ToggleFullScreen",
+        None,
+    ),
+    (
+        "workbench.action.closeActiveEditor",
+        "pane::CloseActiveItem",
+        PANE,
+    ),
+    (
+        "workbench.action.closeAllEditors",
+        "pane::CloseAllItems",
+        PANE,
+    ),
+    (
+        "workbench.action.closeOtherEditors",
+        "pane::CloseInactiveItems",
+        PANE,
+    ),
+    (
+        "workbench.action.reopenClosedEditor",
+        "pane::ReopenClosedItem",
+        PANE,
+    ),
+    (
+        "workbench.action.nextEditor",
+        "pane::ActivateNextItem",
+        PANE,
+    ),
+    (
+        "workbench.action.previousEditor",
+        "pane::ActivatePrevItem",
+        PANE,
+    ),
+    (
+        "workbench.action.lastEditorInGroup",
+        "pane::ActivateLastItem",
+        PANE,
+    ),
+    ("workbench.action.navigateBack", "pane::GoBack", PANE),
+    ("workbench.action.navigateForward", "pane::GoForward", PANE),
+    ("workbench.action.splitEditor", "pane::SplitRight", PANE),
+    (
+        "workbench.action.splitEditorRight",
+        "pane::SplitRight",
+        PANE,
+    ),
+    ("workbench.action.splitEditorLeft", "pane::SplitLeft", PANE),
+    ("workbench.action.splitEditorUp", "pane::SplitUp", PANE),
+    ("workbench.action.splitEditorDown", "pane::SplitDown", PANE),
+    (
+        "workbench.action.toggleSidebarVisibility",
+        "workspace::ToggleLeftDock",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.toggleAuxiliaryBar",
+        "workspace::ToggleRightDock",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.togglePanel",
+        "workspace::ToggleBottomDock",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.toggleMaximizedPanel",
+        "workspace::ToggleZoom",
+        WORKSPACE,
+    ),
+    (
+        "workbench.view.explorer",
+        "project_panel::ToggleFocus",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.findInFiles",
+        "pane::DeploySearch",
+        WORKSPACE,
+    ),
+    ("workbench.view.search", "pane::DeploySearch", WORKSPACE),
+    (
+        "workbench.actions.view.problems",
+        "diagnostics::Deploy",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.terminal.toggleTerminal",
+        "terminal_panel::ToggleFocus",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.terminal.new",
+        "workspace::NewTerminal",
+        WORKSPACE,
+    ),
+    (
+        "workbench.action.terminal.clear",
+        "terminal::Clear",
+        TERMINAL,
+    ),
+    (
+        "workbench.action.terminal.copySelection",
+        "terminal::Copy",
+        TERMINAL,
+    ),
+    (
+        "workbench.action.terminal.paste",
+        "terminal::Paste",
+        TERMINAL,
+    ),
+    ("workbench.action.tasks.runTask", "task::Spawn", WORKSPACE),
+    ("workbench.action.tasks.reRunTask", "task::Rerun", WORKSPACE),
+    (
+        "workbench.action.selectTheme",
+        "theme_selector::Toggle",
+        WORKSPACE,
+    ),
+    ("workbench.action.openSettings", "zed::OpenSettings", None),
+    (
+        "workbench.action.openSettingsJson",
+        "zed::OpenSettings",
+        None,
+    ),
+    (
+        "workbench.action.openGlobalKeybindings",
+        "zed::OpenKeymap",
+        None,
+    ),
+    (
+        "workbench.action.zoomIn",
+        "zed::IncreaseBufferFontSize",
+        None,
+    ),
+    (
+        "workbench.action.zoomOut",
+        "zed::DecreaseBufferFontSize",
+        None,
+    ),
+    (
+        "workbench.action.zoomReset",
+        "zed::ResetBufferFontSize",
+        None,
+    ),
+    (
+        "workbench.action.editor.nextChange",
+        "editor::GoToHunk",
+        FULL_EDITOR,
+    ),
+    (
+        "workbench.action.editor.previousChange",
+        "editor::GoToPrevHunk",
+        FULL_EDITOR,
+    ),
+    ("actions.find", "buffer_search::Deploy", FULL_EDITOR),
+    (
+        "editor.action.nextMatchFindAction",
+        "search::SelectNextMatch",
+        PANE,
+    ),
+    (
+        "editor.action.previousMatchFindAction",
+        "search::SelectPrevMatch",
+        PANE,
+    ),
+    ("undo", "editor::Undo", EDITOR),
+    ("redo", "editor::Redo", EDITOR),
+    ("cursorUndo", "editor::UndoSelection", EDITOR),
+    ("cursorRedo", "editor::RedoSelection", EDITOR),
+    ("editor.action.clipboardCopyAction", "editor::Copy", EDITOR),
+    ("editor.action.clipboardCutAction", "editor::Cut", EDITOR),
+    (
+        "editor.action.clipboardPasteAction",
+        "editor::Paste",
+        EDITOR,
+    ),
+    ("editor.action.selectAll", "editor::SelectAll", EDITOR),
+    ("expandLineSelection", "editor::SelectLine", EDITOR),
+    ("cursorHome", "editor::MoveToBeginningOfLine", EDITOR),
+    ("cursorEnd", "editor::MoveToEndOfLine", EDITOR),
+    ("cursorTop", "editor::MoveToBeginning", EDITOR),
+    ("cursorBottom", "editor::MoveToEnd", EDITOR),
+    ("cursorWordLeft", "editor::MoveToPreviousWordStart", EDITOR),
+    ("cursorWordEndRight", "editor::MoveToNextWordEnd", EDITOR),
+    (
+        "cursorWordLeftSelect",
+        "editor::SelectToPreviousWordStart",
+        EDITOR,
+    ),
+    (
+        "cursorWordEndRightSelect",
+        "editor::SelectToNextWordEnd",
+        EDITOR,
+    ),
+    (
+        "deleteWordLeft",
+        "editor::DeleteToPreviousWordStart",
+        EDITOR,
+    ),
+    ("deleteWordRight", "editor::DeleteToNextWordEnd", EDITOR),
+    (
+        "editor.action.commentLine",
+        "editor::ToggleComments",
+        EDITOR,
+    ),
+    (
+        "editor.action.copyLinesDownAction",
+        "editor::DuplicateLine",
+        EDITOR,
+    ),
+    (
+        "editor.action.moveLinesUpAction",
+        "editor::MoveLineUp",
+        EDITOR,
+    ),
+    (
+        "editor.action.moveLinesDownAction",
+        "editor::MoveLineDown",
+        EDITOR,
+    ),
+    ("editor.action.deleteLines", "editor::DeleteLine", EDITOR),
+    (
+        "editor.action.insertLineAfter",
+        "editor::NewlineBelow",
+        EDITOR,
+    ),
+    (
+        "editor.action.insertLineBefore",
+        "editor::NewlineAbove",
+        EDITOR,
+    ),
+    ("editor.action.joinLines", "editor::JoinLines", EDITOR),
+    ("editor.action.indentLines", "editor::Indent", EDITOR),
+    ("editor.action.outdentLines", "editor::Outdent", EDITOR),
+    (
+        "editor.action.addSelectionToNextFindMatch",
+        "editor::SelectNext",
+        EDITOR,
+    ),
+    (
+        "editor.action.addSelectionToPreviousFindMatch",
+        "editor::SelectPrevious",
+        EDITOR,
+    ),
+    (
+        "editor.action.selectHighlights",
+        "editor::SelectAllMatches",
+        EDITOR,
+    ),
+    (
+        "editor.action.insertCursorAbove",
+        "editor::AddSelectionAbove",
+        EDITOR,
+    ),
+    (
+        "editor.action.insertCursorBelow",
+        "editor::AddSelectionBelow",
+        EDITOR,
+    ),
+    (
+        "editor.action.smartSelect.expand",
+        "editor::SelectLargerSyntaxNode",
+        EDITOR,
+    ),
+    (
+        "editor.action.smartSelect.shrink",
+        "editor::SelectSmallerSyntaxNode",
+        EDITOR,
+    ),
+    (
+        "editor.action.jumpToBracket",
+        "editor::MoveToEnclosingBracket",
+        EDITOR,
+    ),
+    ("editor.fold", "editor::Fold", EDITOR),
+    ("editor.unfold", "editor::UnfoldLines", EDITOR),
+    (
+        "editor.action.revealDefinition",
+        "editor::GoToDefinition",
+        EDITOR,
+    ),
+    (
+        "editor.action.revealDefinitionAside",
+        "editor::GoToDefinitionSplit",
+        EDITOR,
+    ),
+    (
+        "editor.action.goToTypeDefinition",
+        "editor::GoToTypeDefinition",
+        EDITOR,
+    ),
+    (
+        "editor.action.goToReferences",
+        "editor::FindAllReferences",
+        EDITOR,
+    ),
+    (
+        "editor.action.referenceSearch.trigger",
+        "editor::FindAllReferences",
+        EDITOR,
+    ),
+    ("editor.action.rename", "editor::Rename", EDITOR),
+    ("editor.action.formatDocument", "editor::Format", EDITOR),
+    (
+        "editor.action.quickFix",
+        "editor::ToggleCodeActions",
+        EDITOR,
+    ),
+    ("editor.action.showHover", "editor::Hover", EDITOR),
+    (
+        "editor.action.triggerSuggest",
+        "editor::ShowCompletions",
+        EDITOR,
+    ),
+    (
+        "editor.action.inlineSuggest.trigger",
+        "copilot::Suggest",
+        EDITOR,
+    ),
+    (
+        "editor.action.marker.next",
+        "editor::GoToDiagnostic",
+        EDITOR,
+    ),
+    (
+        "editor.action.marker.prev",
+        "editor::GoToPrevDiagnostic",
+        EDITOR,
+    ),
+    ("explorer.newFile", "project_panel::NewFile", PROJECT_PANEL),
+    (
+        "explorer.newFolder",
+        "project_panel::NewDirectory",
+        PROJECT_PANEL,
+    ),
+    ("renameFile", "project_panel::Rename", PROJECT_PANEL),
+    ("deleteFile", "project_panel::Delete", PROJECT_PANEL),
+    ("filesExplorer.copy", "project_panel::Copy", PROJECT_PANEL),
+    ("filesExplorer.cut", "project_panel::Cut", PROJECT_PANEL),
+    ("filesExplorer.paste", "project_panel::Paste", PROJECT_PANEL),
+    ("copyFilePath", "project_panel::CopyPath", PROJECT_PANEL),
+    (
+        "copyRelativeFilePath",
+        "project_panel::CopyRelativePath",
+        PROJECT_PANEL,
+    ),
+    (
+        "revealFileInOS",
+        "project_panel::RevealInFinder",
+        PROJECT_PANEL,
+    ),
+];
+
+/// VS Code language identifiers, along with the names of the corresponding Zed languages.
+const LANGUAGES: &[(&str, &str)] = &[
+    ("c", "C"),
+    ("cpp", "C++"),
+    ("css", "CSS"),
+    ("elixir", "Elixir"),
+    ("go", "Go"),
+    ("html", "HTML"),
+    ("javascript", "JavaScript"),
+    ("javascriptreact", "JavaScript"),
+    ("json", "JSON"),
+    ("jsonc", "JSON"),
+    ("markdown", "Markdown"),
+    ("php", "PHP"),
+    ("python", "Python"),
+    ("ruby", "Ruby"),
+    ("rust", "Rust"),
+    ("shellscript", "Shell Script"),
+    ("toml", "TOML"),
+    ("typescript", "TypeScript"),
+    ("typescriptreact", "TSX"),
+    ("yaml", "YAML"),
+];
+
+/// Zed settings that can be overridden for a specific language.
+const LANGUAGE_SETTINGS: &[&str] = &[
+    "tab_size",
+    "hard_tabs",
+    "soft_wrap",
+    "preferred_line_length",
+    "show_wrap_guides",
+    "wrap_guides",
+    "format_on_save",
+    "remove_trailing_whitespace_on_save",
+    "ensure_final_newline_on_save",
+    "show_whitespaces",
+];
+
+#[derive(Debug, Deserialize)]
+struct VsCodeKeybinding {
+    key: String,
+    command: String,
+}
+
+/// Converts the contents of a VS Code `keybindings.json` file to a Zed keymap.
+///
+/// The `when` clauses of the bindings aren't converted, as they refer to VS Code's UI.
+/// Instead, each binding is added to the context in which Zed binds its action.
+pub fn import_keybindings(content: &str, report: &mut ImportReport) -> Result<Value> {
+    let keybindings: Vec<VsCodeKeybinding> =
+        serde_json_lenient::from_str(content).context("failed to parse VS Code keybindings")?;
+
+    let mut keymap = KeymapBuilder::default();
+    for keybinding in keybindings {
+        let item = format!("{} ({})", keybinding.key, keybinding.command);
+        let (command, removes) = match keybinding.command.strip_prefix('-') {
+            Some(command) => (command, true),
+            None => (keybinding.command.as_str(), false),
+        };
+        let Some((_, action, context)) = COMMANDS.iter().find(|(name, _, _)| *name == command)
+        else {
+            report.skip(item, "no equivalent Zed action");
+            continue;
+        };
+        let Some(keystrokes) = keystrokes(&keybinding.key) else {
+            report.skip(item, "unsupported keystroke");
+            continue;
+        };
+
+        let action = if removes {
+            Value::Null
+        } else {
+            Value::from(*action)
+        };
+        keymap.bind(*context, keystrokes, action);
+        report.import();
+    }
+    Ok(keymap.into_json())
+}
+
+/// Converts a VS Code key binding, like `ctrl+k ctrl+c`, to Zed's syntax.
+fn keystrokes(key: &str) -> Option<String> {
+    let keystrokes = key
+        .split_whitespace()
+        .map(|keystroke| {
+            let mut parts = keystroke.split('+').collect::<Vec<_>>();
+            // The `+` key itself is written as `ctrl++`.
+            if keystroke.ends_with("++") {
+                parts.truncate(parts.len() - 2);
+                parts.push("+");
+            }
+            let key = parts.pop()?;
+            zed_keystroke(parts, key)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(keystrokes.join(" "))
+}
+
+/// Converts the contents of a VS Code `settings.json` file to Zed settings.
+pub fn import_settings(content: &str, report: &mut ImportReport) -> Result<Value> {
+    let settings: Map<String, Value> =
+        serde_json_lenient::from_str(content).context("failed to parse VS Code settings")?;
+
+    let mut zed_settings = Value::Object(Map::new());
+    for (key, value) in &settings {
+        if let Some(language_ids) = key.strip_prefix('[').and_then(|key| key.strip_suffix(']')) {
+            let Some(overrides) = value.as_object() else {
+                report.skip(key, "expected an object");
+                continue;
+            };
+            // A single block can apply to several languages, like `[javascript][typescript]`.
+            for language_id in language_ids.split("][") {
+                let Some((_, language)) = LANGUAGES.iter().find(|(id, _)| *id == language_id)
+                else {
+                    report.skip(format!("[{language_id}]"), "no equivalent Zed language");
+                    continue;
+                };
+                for (key, value) in overrides {
+                    let item = format!("[{language_id}] {key}");
+                    match convert_setting(key, value, overrides) {
+                        Ok(Some((setting_path, value)))
+                            if LANGUAGE_SETTINGS.contains(&setting_path[0]) =>
+                        {
+                            let mut path = vec!["languages", *language];
+                            path.extend_from_slice(setting_path);
+                            set_path(&mut zed_settings, &path, value);
+                            report.import();
+                        }
+                        Ok(Some(_)) => report.skip(item, "can't be set per language in Zed"),
+                        Ok(None) => {}
+                        Err(reason) => report.skip(item, reason),
+                    }
+                }
+            }
+            continue;
+        }
+
+        match convert_setting(key, value, &settings) {
+            Ok(Some((path, value))) => {
+                set_path(&mut zed_settings, path, value);
+                report.import();
+            }
+            Ok(None) => {}
+            Err(reason) => report.skip(key, reason),
+        }
+    }
+    Ok(zed_settings)
+}
+
+/// Converts a VS Code setting to the path and value of a Zed setting. Returns `None` for
+/// settings that are converted along with another setting, and an error describing why
+/// a setting can't be converted otherwise.
+fn convert_setting(
+    key: &str,
+    value: &Value,
+    settings: &Map<String, Value>,
+) -> Result<Option<(&'static [&'static str], Value)>, &'static str> {
+    const UNSUPPORTED_VALUE: &str = "unsupported value";
+
+    let string = || value.as_str().ok_or(UNSUPPORTED_VALUE);
+    let number = || value.as_f64().ok_or(UNSUPPORTED_VALUE);
+    let boolean = || value.as_bool().ok_or(UNSUPPORTED_VALUE);
+
+    let (path, value): (&[&str], Value) = match key {
+        "editor.fontSize" => (&["buffer_font_size"], number()?.into()),
+        "editor.fontFamily" => (&["buffer_font_family"], first_font(string()?).into()),
+        "editor.tabSize" => (
+            &["tab_size"],
+            value.as_u64().ok_or(UNSUPPORTED_VALUE)?.into(),
+        ),
+        "editor.insertSpaces" => (&["hard_tabs"], (!boolean()?).into()),
+        "editor.wordWrap" => {
+            let soft_wrap = match string()? {
+                "off" => "none",
+                "on" => "editor_width",
+                "wordWrapColumn" | "bounded" => "preferred_line_length",
+                _ => return Err(UNSUPPORTED_VALUE),
+            };
+            (&["soft_wrap"], soft_wrap.into())
+        }
+        "editor.wordWrapColumn" => (
+            &["preferred_line_length"],
+            value.as_u64().ok_or(UNSUPPORTED_VALUE)?.into(),
+        ),
+        "editor.rulers" => {
+            let columns = value
+                .as_array()
+                .ok_or(UNSUPPORTED_VALUE)?
+                .iter()
+                .map(|ruler| {
+                    // Rulers can also be objects with a column and a color.
+                    ruler
+                        .as_u64()
+                        .or_else(|| ruler.get("column")?.as_u64())
+                        .map(Value::from)
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or(UNSUPPORTED_VALUE)?;
+            (&["wrap_guides"], columns.into())
+        }
+        "editor.formatOnSave" => (
+            &["format_on_save"],
+            if boolean()? { "on" } else { "off" }.into(),
+        ),
+        "editor.renderWhitespace" => {
+            let show_whitespaces = match string()? {
+                "none" => "none",
+                "selection" => "selection",
+                "all" | "boundary" | "trailing" => "all",
+                _ => return Err(UNSUPPORTED_VALUE),
+            };
+            (&["show_whitespaces"], show_whitespaces.into())
+        }
+        "editor.cursorBlinking" => (&["cursor_blink"], (string()? != "solid").into()),
+        "editor.lineNumbers" => match string()? {
+            "on" => (&["relative_line_numbers"], false.into()),
+            "relative" => (&["relative_line_numbers"], true.into()),
+            "off" => (&["gutter", "line_numbers"], false.into()),
+            _ => return Err(UNSUPPORTED_VALUE),
+        },
+        "editor.hover.enabled" => (&["hover_popover_enabled"], boolean()?.into()),
+        "editor.inlayHints.enabled" => (
+            &["inlay_hints", "enabled"],
+            matches!(value.as_str(), Some("on" | "onUnlessPressed")).into(),
+        ),
+        "files.autoSave" => {
+            let autosave = match string()? {
+                "off" => json!("off"),
+                "afterDelay" => {
+                    let milliseconds = settings
+                        .get("files.autoSaveDelay")
+                        .and_then(Value::as_u64)
+                        .unwrap_or(1000);
+                    json!({ "after_delay": { "milliseconds": milliseconds } })
+                }
+                "onFocusChange" => json!("on_focus_change"),
+                "onWindowChange" => json!("on_window_change"),
+                _ => return Err(UNSUPPORTED_VALUE),
+            };
+            (&["autosave"], autosave)
+        }
+        "files.autoSaveDelay" => return Ok(None),
+        "files.trimTrailingWhitespace" => {
+            (&["remove_trailing_whitespace_on_save"], boolean()?.into())
+        }
+        "files.insertFinalNewline" => (&["ensure_final_newline_on_save"], boolean()?.into()),
+        "files.exclude" => {
+            // Setting `file_scan_exclusions` replaces Zed's defaults, so keep those unless
+            // they are explicitly included again.
+            let patterns = value.as_object().ok_or(UNSUPPORTED_VALUE)?;
+            let mut globs = default_file_scan_exclusions()
+                .into_iter()
+                .filter(|glob| patterns.get(glob).and_then(Value::as_bool) != Some(false))
+                .collect::<Vec<_>>();
+            for (glob, excluded) in patterns {
+                if excluded.as_bool() == Some(true) && !globs.contains(glob) {
+                    globs.push(glob.clone());
+                }
+            }
+            (&["file_scan_exclusions"], globs.into())
+        }
+        "workbench.colorTheme" => (&["theme"], string()?.into()),
+        "workbench.sideBar.location" => match string()? {
+            location @ ("left" | "right") => (&["project_panel", "dock"], location.into()),
+            _ => return Err(UNSUPPORTED_VALUE),
+        },
+        "window.confirmBeforeClose" => (&["confirm_quit"], (string()? != "never").into()),
+        "terminal.integrated.fontSize" => (&["terminal", "font_size"], number()?.into()),
+        "terminal.integrated.fontFamily" => {
+            (&["terminal", "font_family"], first_font(string()?).into())
+        }
+        _ => return Err("no equivalent Zed setting"),
+    };
+    Ok(Some((path, value)))
+}
+
+/// The globs that Zed excludes from worktrees by default.
+fn default_file_scan_exclusions() -> Vec<String> {
+    const DEFAULT_SETTINGS: &str = include_str!("../../../assets/settings/default.json");
+    serde_json_lenient::from_str::<Value>(DEFAULT_SETTINGS)
+        .ok()
+        .and_then(|settings| {
+            settings
+                .get("file_scan_exclusions")?
+                .as_array()?
+                .iter()
+                .map(|glob| Some(glob.as_str()?.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the first font of a CSS-like list of font families, as Zed only supports one.
+fn first_font(families: &str) -> String {
+    families
+        .split(',')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_matches(|c| c == '\'' || c == '"')
+        .to_string()
+}
+
+/// Sets the value at the given path of a JSON object, creating intermediate objects.
+fn set_path(object: &mut Value, path: &[&str], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut object = object;
+    for key in parents {
+        if !object.get(*key).map_or(false, Value::is_object) {
+            object[*key] = Value::Object(Map::new());
+        }
+        object = &mut object[*key];
+    }
+    object[*last] = value;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_settings() {
+        let mut report = ImportReport::default();
+        let settings = import_settings(
+            r##"{
+                // VS Code settings files can contain comments.
+                "editor.fontSize": 13,
+                "editor.fontFamily": "'Fira Code', monospace",
+                "editor.insertSpaces": false,
+                "editor.rulers": [80, { "column": 100, "color": "#ff0000" }],
+                "files.autoSave": "afterDelay",
+                "files.autoSaveDelay": 500,
+                "editor.minimap.enabled": false,
+                "terminal.integrated.fontSize": 12,
+                "[rust][java]": {
+                    "editor.tabSize": 4,
+                    "workbench.colorTheme": "One Dark",
+                },
+            }"##,
+            &mut report,
+        )
+        .unwrap();
+
+        assert_eq!(
+            settings,
+            json!({
+                "buffer_font_size": 13.0,
+                "buffer_font_family": "Fira Code",
+                "hard_tabs": true,
+                "wrap_guides": [80, 100],
+                "autosave": { "after_delay": { "milliseconds": 500 } },
+                "terminal": { "font_size": 12.0 },
+                "languages": { "Rust": { "tab_size": 4 } }
+            })
+        );
+        assert_eq!(report.imported, 7);
+        assert_eq!(
+            report
+                .skipped
+                .iter()
+                .map(|skipped| skipped.item.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "editor.minimap.enabled",
+                "[rust] workbench.colorTheme",
+                "[java]"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_files_exclude() {
+        let mut report = ImportReport::default();
+        let settings = import_settings(
+            r#"{
+                "files.exclude": {
+                    "**/node_modules": true,
+                    "**/.DS_Store": false,
+                    "**/.git": true,
+                }
+            }"#,
+            &mut report,
+        )
+        .unwrap();
+
+        let exclusions = settings["file_scan_exclusions"].as_array().unwrap();
+        assert!(exclusions.contains(&json!("**/node_modules")));
+        assert!(exclusions.contains(&json!("**/.svn")));
+        assert!(!exclusions.contains(&json!("**/.DS_Store")));
+        assert_eq!(
+            exclusions
+                .iter()
+                .filter(|glob| **glob == json!("**/.git"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_import_keybindings() {
+        let mut report = ImportReport::default();
+        let keymap = import_keybindings(
+            r#"[
+                { "key": "ctrl+shift+d", "command": "editor.action.copyLinesDownAction", "when": "editorTextFocus" },
+                { "key": "ctrl+k ctrl+m", "command": "workbench.action.toggleMaximizedPanel" },
+                { "key": "ctrl+d", "command": "-editor.action.addSelectionToNextFindMatch" },
+                { "key": "ctrl+numpad_add", "command": "editor.action.fontZoomIn" },
+                { "key": "ctrl+numpad_add", "command": "workbench.action.zoomIn" }
+            ]"#,
+            &mut report,
+        )
+        .unwrap();
+
+        assert_eq!(
+            keymap,
+            json!([
+                {
+                    "context": "Editor",
+                    "bindings": { "ctrl-shift-d": "editor::DuplicateLine", "ctrl-d": null }
+                },
+                {
+                    "context": "Workspace",
+                    "bindings": { "ctrl-k ctrl-m": "workspace::ToggleZoom" }
+                }
+            ])
+        );
+        assert_eq!(report.imported, 3);
+        assert_eq!(
+            report
+                .skipped
+                .iter()
+                .map(|skipped| skipped.reason.as_str())
+                .collect::<Vec<_>>(),
+            ["no equivalent Zed action", "unsupported keystroke"]
+        );
+        assert_eq!(keystrokes("ctrl++").as_deref(), Some("ctrl-+"));
+    }
+}
@@ -0,0 +1,994 @@
+mod revision_picker;
+
+use std::{any::TypeId, ops::Range, sync::Arc};
+
+use anyhow::Result;
+use collections::HashSet;
+use editor::{
+    display_map::{BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    Editor, EditorEvent,
+};
+use git::diff::BufferDiff;
+use gpui::{
+    actions, AnyElement, AnyView, AppContext, EventEmitter, FocusHandle, FocusableView,
+    HighlightStyle, Hsla, Model, Render, Subscription, Task, View, ViewContext,
+};
+use language::{language_settings::SoftWrap, Buffer, Point};
+use project::{Project, ProjectPath};
+use revision_picker::RevisionPicker;
+use similar::{ChangeTag, TextDiff};
+use text::BufferId;
+use theme::ActiveTheme;
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent, ItemHandle},
+    Workspace,
+};
+
+actions!(
+    diff_view,
+    [
+        NextHunk,
+        PreviousHunk,
+        CompareWithSaved,
+        CompareWithIndex,
+        CompareWithRevision
+    ]
+);
+
+/// Hunks with a side longer than this many bytes aren't diffed word by word.
+const MAX_WORD_DIFF_LEN: usize = 10_000;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(DiffView::compare_with_saved);
+        workspace.register_action(DiffView::compare_with_index);
+        workspace.register_action(DiffView::compare_with_revision);
+    })
+    .detach();
+}
+
+/// Shows two buffers side by side, highlighting the lines and words that differ between them.
+/// Both sides are padded so that their unchanged lines stay aligned while scrolling.
+pub struct DiffView {
+    left_editor: View<Editor>,
+    right_editor: View<Editor>,
+    left_buffer: Model<Buffer>,
+    right_buffer: Model<Buffer>,
+    left_title: Option<SharedString>,
+    hunks: Vec<Hunk>,
+    left_blocks: HashSet<BlockId>,
+    right_blocks: HashSet<BlockId>,
+    update_diff_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// The rows of each buffer that differ from the other one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Hunk {
+    left_rows: Range<u32>,
+    right_rows: Range<u32>,
+}
+
+/// The differences between two versions of the buffers.
+struct Diff {
+    hunks: Vec<Hunk>,
+    left_words: Vec<Range<usize>>,
+    right_words: Vec<Range<usize>>,
+    left_snapshot: text::BufferSnapshot,
+    right_snapshot: text::BufferSnapshot,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    Prev,
+    Next,
+}
+
+enum DiffHighlight {}
+enum WordHighlight {}
+
+impl DiffView {
+    /// Opens the files at the given paths and adds a view comparing them to the active pane.
+    pub fn open(
+        left_path: ProjectPath,
+        right_path: ProjectPath,
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Task<Result<View<Self>>> {
+        let project = workspace.project().clone();
+        let left_buffer = project.update(cx, |project, cx| project.open_buffer(left_path, cx));
+        let right_buffer = project.update(cx, |project, cx| project.open_buffer(right_path, cx));
+        cx.spawn(|workspace, mut cx| async move {
+            let left_buffer = left_buffer.await?;
+            let right_buffer = right_buffer.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let view =
+                    cx.new_view(|cx| Self::new(left_buffer, None, right_buffer, Some(project), cx));
+                workspace.add_item_to_active_pane(Box::new(view.clone()), cx);
+                view
+            })
+        })
+    }
+
+    /// Compares the active editor's buffer with its file on disk, which helps resolving a
+    /// conflict after the file was changed by another program.
+    fn compare_with_saved(
+        workspace: &mut Workspace,
+        _: &CompareWithSaved,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(buffer) = active_buffer(workspace, cx) else {
+            return;
+        };
+        let Some((file_name, abs_path)) = buffer.read(cx).file().and_then(|file| {
+            let abs_path = file.as_local()?.abs_path(cx);
+            Some((file.file_name(cx).to_string_lossy().to_string(), abs_path))
+        }) else {
+            return;
+        };
+
+        let fs = workspace.project().read(cx).fs().clone();
+        cx.spawn(|workspace, mut cx| async move {
+            let saved_text = fs.load(&abs_path).await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let title = format!("{file_name} (on disk)");
+                Self::open_with_base_text(saved_text, title, buffer, workspace, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Compares the active editor's buffer with its file's staged contents in the git index.
+    fn compare_with_index(
+        workspace: &mut Workspace,
+        _: &CompareWithIndex,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(buffer) = active_buffer(workspace, cx) else {
+            return;
+        };
+        let (title, diff_base) = {
+            let buffer = buffer.read(cx);
+            let (Some(file), Some(diff_base)) = (buffer.file(), buffer.diff_base()) else {
+                return;
+            };
+            (
+                format!("{} (index)", file.file_name(cx).to_string_lossy()),
+                diff_base.to_string(),
+            )
+        };
+        Self::open_with_base_text(diff_base, title, buffer, workspace, cx);
+    }
+
+    /// Compares the active editor's buffer with its file at a git revision, which is picked
+    /// from HEAD and the latest commits or typed in.
+    fn compare_with_revision(
+        workspace: &mut Workspace,
+        _: &CompareWithRevision,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        if let Some(buffer) = active_buffer(workspace, cx) {
+            RevisionPicker::toggle(buffer, workspace, cx);
+        }
+    }
+
+    fn open_with_base_text(
+        base_text: String,
+        base_title: String,
+        buffer: Model<Buffer>,
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let language = buffer.read(cx).language().cloned();
+        let base_buffer = cx.new_model(|cx| {
+            let mut base_buffer = Buffer::new(
+                0,
+                BufferId::new(cx.entity_id().as_u64()).unwrap(),
+                base_text,
+            );
+            base_buffer.set_language(language, cx);
+            base_buffer
+        });
+        let project = workspace.project().clone();
+        let view = cx.new_view(|cx| {
+            Self::new(
+                base_buffer,
+                Some(base_title.into()),
+                buffer,
+                Some(project),
+                cx,
+            )
+        });
+        workspace.add_item_to_active_pane(Box::new(view), cx);
+    }
+
+    /// Creates a view comparing two buffers, with an optional title for the left one in case it
+    /// isn't backed by a file. Such buffers can't be saved, so they're shown read-only.
+    pub fn new(
+        left_buffer: Model<Buffer>,
+        left_title: Option<SharedString>,
+        right_buffer: Model<Buffer>,
+        project: Option<Model<Project>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut create_editor = |buffer: &Model<Buffer>| {
+            let read_only = buffer.read(cx).file().is_none();
+            cx.new_view(|cx| {
+                let mut editor = Editor::for_buffer(buffer.clone(), project.clone(), cx);
+                // Wrapped lines would take more rows on one side than on the other.
+                editor.set_soft_wrap_mode(SoftWrap::None, cx);
+                editor.set_read_only(read_only);
+                editor
+            })
+        };
+        let left_editor = create_editor(&left_buffer);
+        let right_editor = create_editor(&right_buffer);
+
+        let mut subscriptions = Vec::new();
+        for buffer in [&left_buffer, &right_buffer] {
+            subscriptions.push(cx.subscribe(buffer, |this, _, event, cx| {
+                if let language::Event::Edited = event {
+                    this.update_diff(cx);
+                }
+            }));
+        }
+        for editor in [&left_editor, &right_editor] {
+            subscriptions.push(
+                cx.subscribe(
+                    editor,
+                    |this, editor, event: &EditorEvent, cx| match event {
+                        EditorEvent::DirtyChanged
+                        | EditorEvent::Saved
+                        | EditorEvent::TitleChanged => cx.emit(ItemEvent::UpdateTab),
+                        EditorEvent::Edited => cx.emit(ItemEvent::Edit),
+                        EditorEvent::ScrollPositionChanged { local: true, .. } => {
+                            this.sync_scroll_position(&editor, cx)
+                        }
+                        _ => {}
+                    },
+                ),
+            );
+        }
+
+        let mut this = Self {
+            left_editor,
+            right_editor,
+            left_buffer,
+            right_buffer,
+            left_title,
+            hunks: Vec::new(),
+            left_blocks: HashSet::default(),
+            right_blocks: HashSet::default(),
+            update_diff_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.update_diff(cx);
+        this
+    }
+
+    fn update_diff(&mut self, cx: &mut ViewContext<Self>) {
+        let left_snapshot = self.left_buffer.read(cx).text_snapshot();
+        let right_snapshot = self.right_buffer.read(cx).text_snapshot();
+        self.update_diff_task = cx.spawn(|this, mut cx| async move {
+            let diff = cx
+                .background_executor()
+                .spawn(compute_diff(left_snapshot, right_snapshot))
+                .await;
+            this.update(&mut cx, |this, cx| {
+                // The buffers were edited while diffing them, and they'll be diffed again.
+                if diff.left_snapshot.version() != this.left_buffer.read(cx).version()
+                    || diff.right_snapshot.version() != this.right_buffer.read(cx).version()
+                {
+                    return;
+                }
+                this.apply_diff(diff, cx);
+            })
+            .log_err();
+        });
+    }
+
+    fn apply_diff(&mut self, diff: Diff, cx: &mut ViewContext<Self>) {
+        let status = cx.theme().status();
+        let (mut deleted_word, mut created_word) = (status.deleted, status.created);
+        deleted_word.fade_out(0.6);
+        created_word.fade_out(0.6);
+        let left_style = SideStyle {
+            row_color: status.deleted_background,
+            word_color: deleted_word,
+            filler_color: cx.theme().colors().editor_subheader_background,
+        };
+        let right_style = SideStyle {
+            row_color: status.created_background,
+            word_color: created_word,
+            ..left_style
+        };
+
+        // Pad the shorter side of each hunk so that the rows after it line up.
+        let mut left_fillers = Vec::new();
+        let mut right_fillers = Vec::new();
+        for hunk in &diff.hunks {
+            let (left_len, right_len) = (hunk.left_rows.len(), hunk.right_rows.len());
+            if left_len > right_len {
+                right_fillers.push((hunk.right_rows.end, left_len - right_len));
+            } else if right_len > left_len {
+                left_fillers.push((hunk.left_rows.end, right_len - left_len));
+            }
+        }
+
+        self.left_blocks = update_side(
+            &self.left_editor,
+            diff.hunks.iter().map(|hunk| hunk.left_rows.clone()),
+            diff.left_words,
+            left_fillers,
+            std::mem::take(&mut self.left_blocks),
+            left_style,
+            cx,
+        );
+        self.right_blocks = update_side(
+            &self.right_editor,
+            diff.hunks.iter().map(|hunk| hunk.right_rows.clone()),
+            diff.right_words,
+            right_fillers,
+            std::mem::take(&mut self.right_blocks),
+            right_style,
+            cx,
+        );
+        self.hunks = diff.hunks;
+
+        let right_editor = self.right_editor.clone();
+        self.sync_scroll_position(&right_editor, cx);
+        cx.notify();
+    }
+
+    /// Scrolls the other side to the same position as the given editor. Rows are aligned on
+    /// both sides, so the positions of matching lines are the same.
+    fn sync_scroll_position(&mut self, editor: &View<Editor>, cx: &mut ViewContext<Self>) {
+        let other_editor = if editor == &self.left_editor {
+            &self.right_editor
+        } else {
+            &self.left_editor
+        };
+        let scroll_position = editor.update(cx, |editor, cx| editor.scroll_position(cx));
+        other_editor.update(cx, |other_editor, cx| {
+            if other_editor.scroll_position(cx) != scroll_position {
+                other_editor.set_scroll_position(scroll_position, cx);
+            }
+        });
+    }
+
+    fn next_hunk(&mut self, _: &NextHunk, cx: &mut ViewContext<Self>) {
+        self.go_to_hunk(Direction::Next, cx);
+    }
+
+    fn previous_hunk(&mut self, _: &PreviousHunk, cx: &mut ViewContext<Self>) {
+        self.go_to_hunk(Direction::Prev, cx);
+    }
+
+    /// Moves the cursors on both sides to the hunk after or before the cursor of the focused
+    /// side.
+    fn go_to_hunk(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        let is_left_focused = self.left_editor.focus_handle(cx).contains_focused(cx);
+        let (focused_editor, other_editor) = if is_left_focused {
+            (&self.left_editor, &self.right_editor)
+        } else {
+            (&self.right_editor, &self.left_editor)
+        };
+        let focused_rows = |hunk: &Hunk| {
+            if is_left_focused {
+                hunk.left_rows.clone()
+            } else {
+                hunk.right_rows.clone()
+            }
+        };
+
+        let cursor_row = focused_editor.update(cx, |editor, cx| {
+            editor.selections.newest::<Point>(cx).head().row
+        });
+        let hunk = match direction {
+            Direction::Next => self
+                .hunks
+                .iter()
+                .find(|hunk| focused_rows(hunk).start > cursor_row),
+            Direction::Prev => self
+                .hunks
+                .iter()
+                .rev()
+                .find(|hunk| focused_rows(hunk).start < cursor_row),
+        };
+        let Some(hunk) = hunk.cloned() else {
+            return;
+        };
+
+        let (focused_row, other_row) = if is_left_focused {
+            (hunk.left_rows.start, hunk.right_rows.start)
+        } else {
+            (hunk.right_rows.start, hunk.left_rows.start)
+        };
+        // The other side follows the focused editor's scroll position, so it doesn't autoscroll.
+        move_cursor_to_row(other_editor, other_row, None, cx);
+        move_cursor_to_row(focused_editor, focused_row, Some(Autoscroll::center()), cx);
+    }
+
+    fn title(&self, buffer: &Model<Buffer>, cx: &AppContext) -> SharedString {
+        if buffer == &self.left_buffer {
+            if let Some(left_title) = &self.left_title {
+                return left_title.clone();
+            }
+        }
+        buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string())
+            .into()
+    }
+
+    fn render_side(
+        &self,
+        editor: &View<Editor>,
+        buffer: &Model<Buffer>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .h_full()
+            .overflow_hidden()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(self.title(buffer, cx)).color(Color::Muted)),
+            )
+            .child(div().flex_1().overflow_hidden().child(editor.clone()))
+    }
+}
+
+fn active_buffer(workspace: &Workspace, cx: &AppContext) -> Option<Model<Buffer>> {
+    workspace
+        .active_item_as::<Editor>(cx)?
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .as_singleton()
+}
+
+async fn compute_diff(
+    left_snapshot: text::BufferSnapshot,
+    right_snapshot: text::BufferSnapshot,
+) -> Diff {
+    let mut buffer_diff = BufferDiff::new();
+    buffer_diff
+        .update(&left_snapshot.text(), &right_snapshot)
+        .await;
+
+    // Hunks that only add lines don't say where they'd be on the left, so track how far apart
+    // the rows of both sides are after each hunk.
+    let mut row_delta = 0_i64;
+    let hunks = buffer_diff
+        .hunks_in_row_range(0..u32::MAX, &right_snapshot)
+        .map(|hunk| {
+            let right_rows = hunk.associated_range;
+            let left_rows = if hunk.diff_base_byte_range.is_empty() {
+                let row = (right_rows.start as i64 + row_delta) as u32;
+                row..row
+            } else {
+                rows_for_range(&left_snapshot, hunk.diff_base_byte_range)
+            };
+            row_delta += left_rows.len() as i64 - right_rows.len() as i64;
+            Hunk {
+                left_rows,
+                right_rows,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut left_words = Vec::new();
+    let mut right_words = Vec::new();
+    for hunk in &hunks {
+        if hunk.left_rows.is_empty() || hunk.right_rows.is_empty() {
+            continue;
+        }
+        let left_range = range_for_rows(&left_snapshot, &hunk.left_rows);
+        let right_range = range_for_rows(&right_snapshot, &hunk.right_rows);
+        if left_range.len() > MAX_WORD_DIFF_LEN || right_range.len() > MAX_WORD_DIFF_LEN {
+            continue;
+        }
+
+        let left_text = left_snapshot
+            .text_for_range(left_range.clone())
+            .collect::<String>();
+        let right_text = right_snapshot
+            .text_for_range(right_range.clone())
+            .collect::<String>();
+        let (hunk_left_words, hunk_right_words) = word_diff(&left_text, &right_text);
+        left_words.extend(
+            hunk_left_words
+                .into_iter()
+                .map(|range| left_range.start + range.start..left_range.start + range.end),
+        );
+        right_words.extend(
+            hunk_right_words
+                .into_iter()
+                .map(|range| right_range.start + range.start..right_range.start + range.end),
+        );
+    }
+
+    Diff {
+        hunks,
+        left_words,
+        right_words,
+        left_snapshot,
+        right_snapshot,
+    }
+}
+
+/// Converts a byte range to the range of rows it spans, including the row it ends on unless it
+/// ends at the start of that row.
+fn rows_for_range(snapshot: &text::BufferSnapshot, range: Range<usize>) -> Range<u32> {
+    let start = snapshot.offset_to_point(range.start);
+    if range.is_empty() {
+        return start.row..start.row;
+    }
+    let end = snapshot.offset_to_point(range.end);
+    start.row..if end.column == 0 {
+        end.row
+    } else {
+        end.row + 1
+    }
+}
+
+fn range_for_rows(snapshot: &text::BufferSnapshot, rows: &Range<u32>) -> Range<usize> {
+    let start = snapshot.point_to_offset(Point::new(rows.start, 0));
+    let end = if rows.end > snapshot.max_point().row {
+        snapshot.len()
+    } else {
+        snapshot.point_to_offset(Point::new(rows.end, 0))
+    };
+    start..end
+}
+
+/// Returns the byte ranges of the words removed from the old text and of those inserted in the
+/// new one, merging adjacent words into a single range.
+fn word_diff(old_text: &str, new_text: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+        match ranges.last_mut() {
+            Some(last_range) if last_range.end == range.start => last_range.end = range.end,
+            _ => ranges.push(range),
+        }
+    }
+
+    let mut old_ranges = Vec::new();
+    let mut new_ranges = Vec::new();
+    let mut old_offset = 0;
+    let mut new_offset = 0;
+    for change in TextDiff::from_words(old_text, new_text).iter_all_changes() {
+        let len = change.value().len();
+        match change.tag() {
+            ChangeTag::Equal => {
+                old_offset += len;
+                new_offset += len;
+            }
+            ChangeTag::Delete => {
+                push_range(&mut old_ranges, old_offset..old_offset + len);
+                old_offset += len;
+            }
+            ChangeTag::Insert => {
+                push_range(&mut new_ranges, new_offset..new_offset + len);
+                new_offset += len;
+            }
+        }
+    }
+    (old_ranges, new_ranges)
+}
+
+#[derive(Clone, Copy)]
+struct SideStyle {
+    row_color: Hsla,
+    word_color: Hsla,
+    filler_color: Hsla,
+}
+
+/// Highlights the changed rows and words of one side, and replaces the blocks padding it with
+/// new ones of the given heights, inserted above the given rows.
+fn update_side(
+    editor: &View<Editor>,
+    rows: impl Iterator<Item = Range<u32>>,
+    words: Vec<Range<usize>>,
+    fillers: Vec<(u32, usize)>,
+    old_blocks: HashSet<BlockId>,
+    style: SideStyle,
+    cx: &mut ViewContext<DiffView>,
+) -> HashSet<BlockId> {
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let max_row = snapshot.max_point().row;
+
+        editor.clear_row_highlights::<DiffHighlight>();
+        for rows in rows.filter(|rows| !rows.is_empty()) {
+            let start = snapshot.anchor_before(Point::new(rows.start, 0));
+            let end = snapshot.anchor_before(Point::new(rows.end - 1, 0));
+            editor.highlight_rows::<DiffHighlight>(start..end, Some(style.row_color), cx);
+        }
+
+        let words = words
+            .into_iter()
+            .map(|range| snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end))
+            .collect();
+        editor.highlight_text::<WordHighlight>(
+            words,
+            HighlightStyle {
+                background_color: Some(style.word_color),
+                ..Default::default()
+            },
+            cx,
+        );
+
+        editor.remove_blocks(old_blocks, None, cx);
+        let mut blocks = Vec::new();
+        for (row, mut height) in fillers {
+            let (position, disposition) = if row > max_row {
+                (Point::new(max_row, 0), BlockDisposition::Below)
+            } else {
+                (Point::new(row, 0), BlockDisposition::Above)
+            };
+            // Block heights fit in a byte, so taller fillers are split into several blocks.
+            while height > 0 {
+                let block_height = height.min(u8::MAX as usize);
+                let filler_color = style.filler_color;
+                blocks.push(BlockProperties {
+                    position: snapshot.anchor_before(position),
+                    height: block_height as u8,
+                    style: BlockStyle::Flex,
+                    render: Arc::new(move |_| {
+                        div().size_full().bg(filler_color).into_any_element()
+                    }),
+                    disposition,
+                });
+                height -= block_height;
+            }
+        }
+        editor.insert_blocks(blocks, None, cx).into_iter().collect()
+    })
+}
+
+fn move_cursor_to_row(
+    editor: &View<Editor>,
+    row: u32,
+    autoscroll: Option<Autoscroll>,
+    cx: &mut ViewContext<DiffView>,
+) {
+    editor.update(cx, |editor, cx| {
+        let max_row = editor.buffer().read(cx).snapshot(cx).max_point().row;
+        let point = Point::new(row.min(max_row), 0);
+        editor.change_selections(autoscroll, cx, |selections| {
+            selections.select_ranges([point..point])
+        });
+    });
+}
+
+impl EventEmitter<ItemEvent> for DiffView {}
+
+impl FocusableView for DiffView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.right_editor.focus_handle(cx)
+    }
+}
+
+impl Item for DiffView {
+    type Event = ItemEvent;
+
+    fn tab_content(
+        &self,
+        _detail: Option<usize>,
+        selected: bool,
+        cx: &WindowContext,
+    ) -> AnyElement {
+        let color = if selected {
+            Color::Default
+        } else {
+            Color::Muted
+        };
+        h_flex()
+            .gap_2()
+            .child(Icon::new(IconName::Split).color(color))
+            .child(
+                Label::new(format!(
+                    "{} ↔ {}",
+                    self.title(&self.left_buffer, cx),
+                    self.title(&self.right_buffer, cx)
+                ))
+                .color(color),
+            )
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        let path = |buffer: &Model<Buffer>| {
+            buffer
+                .read(cx)
+                .file()
+                .map(|file| file.full_path(cx).to_string_lossy().to_string())
+                .unwrap_or_else(|| self.title(buffer, cx).to_string())
+        };
+        Some(format!("{} ↔ {}", path(&self.left_buffer), path(&self.right_buffer)).into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("diff view")
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        self.left_editor.for_each_project_item(cx, f);
+        self.right_editor.for_each_project_item(cx, f);
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.left_buffer.read(cx).is_dirty() || self.right_buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.left_buffer.read(cx).has_conflict() || self.right_buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffers = [&self.left_buffer, &self.right_buffer]
+            .into_iter()
+            .filter(|buffer| {
+                let buffer = buffer.read(cx);
+                buffer.file().is_some() && buffer.is_dirty()
+            })
+            .cloned()
+            .collect::<HashSet<_>>();
+        project.update(cx, |project, cx| project.save_buffers(buffers, cx))
+    }
+
+    fn save_as(
+        &mut self,
+        _: Model<Project>,
+        _: std::path::PathBuf,
+        _: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let buffers = [&self.left_buffer, &self.right_buffer]
+            .into_iter()
+            .filter(|buffer| buffer.read(cx).file().is_some())
+            .cloned()
+            .collect::<HashSet<_>>();
+        let reload = project.update(cx, |project, cx| project.reload_buffers(buffers, true, cx));
+        cx.background_executor().spawn(async move {
+            reload.await?;
+            Ok(())
+        })
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.right_editor.to_any())
+        } else {
+            None
+        }
+    }
+}
+
+impl Render for DiffView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .key_context("DiffView")
+            .on_action(cx.listener(Self::next_hunk))
+            .on_action(cx.listener(Self::previous_hunk))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_side(&self.left_editor, &self.left_buffer, cx))
+            .child(div().w_px().h_full().bg(cx.theme().colors().border))
+            .child(self.render_side(&self.right_editor, &self.right_buffer, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_diffing_files(cx: &mut TestAppContext) {
+        let (view, left_buffer, right_buffer, cx) =
+            build_diff_view("one\ntwo\nthree\nfour\n", "one\nthree\nfour\nfive\n", cx).await;
+        view.update(cx, |view, _| {
+            assert_eq!(
+                view.hunks,
+                [
+                    Hunk {
+                        left_rows: 1..2,
+                        right_rows: 1..1,
+                    },
+                    Hunk {
+                        left_rows: 4..4,
+                        right_rows: 3..4,
+                    },
+                ]
+            );
+        });
+
+        right_buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+        cx.run_until_parked();
+        view.update(cx, |view, cx| {
+            assert_eq!(
+                view.hunks,
+                [
+                    Hunk {
+                        left_rows: 0..0,
+                        right_rows: 0..1,
+                    },
+                    Hunk {
+                        left_rows: 1..2,
+                        right_rows: 2..2,
+                    },
+                    Hunk {
+                        left_rows: 4..4,
+                        right_rows: 4..5,
+                    },
+                ]
+            );
+            assert!(view.is_dirty(cx));
+            assert!(!left_buffer.read(cx).is_dirty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_aligning_sides(cx: &mut TestAppContext) {
+        let (view, _, _, cx) = build_diff_view(
+            "one\ntwo\nthree\nfour\nfive\n",
+            "one\nthree\nfour\nfour and a half\nfive\nsix\nseven\n",
+            cx,
+        )
+        .await;
+
+        view.update(cx, |view, cx| {
+            assert_eq!(view.left_blocks.len(), 2);
+            assert_eq!(view.right_blocks.len(), 1);
+            let left_rows = view.left_editor.update(cx, |editor, cx| {
+                editor.snapshot(cx).display_snapshot.max_point().row()
+            });
+            let right_rows = view.right_editor.update(cx, |editor, cx| {
+                editor.snapshot(cx).display_snapshot.max_point().row()
+            });
+            assert_eq!(left_rows, right_rows);
+
+            view.right_editor.update(cx, |editor, cx| {
+                editor.set_scroll_position(gpui::Point::new(0., 3.), cx)
+            });
+            let left_scroll_position = view
+                .left_editor
+                .update(cx, |editor, cx| editor.scroll_position(cx));
+            assert_eq!(left_scroll_position, gpui::Point::new(0., 3.));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_navigating_hunks(cx: &mut TestAppContext) {
+        let (view, _, _, cx) = build_diff_view(
+            "one\ntwo\nthree\nfour\nfive\n",
+            "one\nthree\nfour\nfive\nsix\n",
+            cx,
+        )
+        .await;
+
+        let cursor_rows = |view: &View<DiffView>, cx: &mut VisualTestContext| {
+            view.update(cx, |view, cx| {
+                [&view.left_editor, &view.right_editor].map(|editor| {
+                    editor.update(cx, |editor, cx| {
+                        editor.selections.newest::<Point>(cx).head().row
+                    })
+                })
+            })
+        };
+
+        view.update(cx, |view, cx| {
+            cx.focus_view(&view.right_editor);
+            view.next_hunk(&NextHunk, cx);
+        });
+        assert_eq!(cursor_rows(&view, cx), [1, 1]);
+        view.update(cx, |view, cx| view.next_hunk(&NextHunk, cx));
+        assert_eq!(cursor_rows(&view, cx), [5, 4]);
+        view.update(cx, |view, cx| view.next_hunk(&NextHunk, cx));
+        assert_eq!(cursor_rows(&view, cx), [5, 4]);
+        view.update(cx, |view, cx| view.previous_hunk(&PreviousHunk, cx));
+        assert_eq!(cursor_rows(&view, cx), [1, 1]);
+    }
+
+    #[test]
+    fn test_word_diff() {
+        assert_eq!(
+            word_diff("let x = foo(a, b);\n", "let y = foo(a, c);\n"),
+            (vec![4..5, 15..18], vec![4..5, 15..18])
+        );
+        assert_eq!(
+            word_diff("fn main() {}\n", "pub fn main() {}\n"),
+            (vec![], vec![0..4])
+        );
+    }
+
+    async fn build_diff_view<'a>(
+        left_text: &str,
+        right_text: &str,
+        cx: &'a mut TestAppContext,
+    ) -> (
+        View<DiffView>,
+        Model<Buffer>,
+        Model<Buffer>,
+        &'a mut VisualTestContext,
+    ) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            editor::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": left_text, "b.txt": right_text }))
+            .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let left_buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/a.txt", cx)
+            })
+            .await
+            .unwrap();
+        let right_buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/b.txt", cx)
+            })
+            .await
+            .unwrap();
+
+        let (view, cx) = cx.add_window_view(|cx| {
+            DiffView::new(
+                left_buffer.clone(),
+                None,
+                right_buffer.clone(),
+                Some(project.clone()),
+                cx,
+            )
+        });
+        cx.run_until_parked();
+        (view, left_buffer, right_buffer, cx)
+    }
+}
@@ -0,0 +1,413 @@
+use std::{path::Path, sync::Arc};
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    Subscription, Task, View, ViewContext, WeakView,
+};
+use language::{Buffer, File as _};
+use picker::{Picker, PickerDelegate};
+use project::{File, ProjectPath};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Toast, Workspace};
+
+use crate::DiffView;
+
+const RECENT_COMMITS_COUNT: usize = 50;
+const SHORT_SHA_LEN: usize = 7;
+
+/// Picks the git revision to compare a buffer with, out of HEAD and the latest commits, or
+/// any other revision typed into the query, such as `HEAD~2` or a branch name.
+pub(crate) struct RevisionPicker {
+    picker: View<Picker<RevisionPickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl RevisionPicker {
+    pub(crate) fn toggle(
+        buffer: Model<Buffer>,
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(delegate) = RevisionPickerDelegate::new(buffer, workspace, cx) else {
+            return;
+        };
+        workspace.toggle_modal(cx, |cx| {
+            let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+            let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+            Self {
+                picker,
+                _subscription,
+            }
+        });
+    }
+}
+
+impl ModalView for RevisionPicker {}
+impl EventEmitter<DismissEvent> for RevisionPicker {}
+
+impl FocusableView for RevisionPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for RevisionPicker {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Revision {
+    /// The revision as understood by git.
+    revision: SharedString,
+    /// The revision as shown in the diff view's tab.
+    label: SharedString,
+}
+
+pub(crate) struct RevisionPickerDelegate {
+    workspace: WeakView<Workspace>,
+    buffer: Model<Buffer>,
+    file_name: String,
+    project_path: ProjectPath,
+    /// The path of the buffer's file relative to its repository's work directory.
+    repo_path: Arc<Path>,
+    revisions: Vec<Revision>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    query: String,
+}
+
+impl RevisionPickerDelegate {
+    fn new(
+        buffer: Model<Buffer>,
+        workspace: &Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Option<Self> {
+        let project = workspace.project().read(cx);
+        let file = File::from_dyn(buffer.read(cx).file())?;
+        let project_path = ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path().clone(),
+        };
+        let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+        let (work_directory, _) = worktree
+            .read(cx)
+            .as_local()?
+            .snapshot()
+            .repository_and_work_directory_for_path(&project_path.path)?;
+        let repo_path = project_path.path.strip_prefix(&work_directory).ok()?.into();
+        let commits = project
+            .get_repo(&project_path, cx)?
+            .lock()
+            .recent_commits(RECENT_COMMITS_COUNT)
+            .log_err()
+            .unwrap_or_default();
+
+        let mut revisions = vec![Revision {
+            revision: "HEAD".into(),
+            label: "HEAD".into(),
+        }];
+        let mut candidates = vec![StringMatchCandidate::new(0, "HEAD".into())];
+        for commit in commits {
+            let short_sha = &commit.sha[..SHORT_SHA_LEN.min(commit.sha.len())];
+            candidates.push(StringMatchCandidate::new(
+                revisions.len(),
+                format!("{short_sha} {}", commit.summary),
+            ));
+            revisions.push(Revision {
+                label: short_sha.to_string().into(),
+                revision: commit.sha.to_string().into(),
+            });
+        }
+
+        Some(Self {
+            workspace: cx.view().downgrade(),
+            file_name: file.file_name(cx).to_string_lossy().to_string(),
+            buffer,
+            project_path,
+            repo_path,
+            revisions,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+            query: String::new(),
+        })
+    }
+
+    /// The revision typed into the query is offered after the matching commits.
+    fn typed_revision(&self) -> Option<Revision> {
+        let query = self.query.trim();
+        (!query.is_empty()).then(|| Revision {
+            revision: query.to_string().into(),
+            label: query.to_string().into(),
+        })
+    }
+
+    fn compare_with_revision(&self, revision: Revision, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let Some(repo) = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .get_repo(&self.project_path, cx)
+        else {
+            return;
+        };
+        let buffer = self.buffer.clone();
+        let file_name = self.file_name.clone();
+        let repo_path = self.repo_path.clone();
+        let load_text = cx.background_executor().spawn({
+            let revision = revision.revision.clone();
+            async move { repo.lock().load_revision_text(&revision, &repo_path) }
+        });
+        cx.spawn(|_, mut cx| async move {
+            let text = load_text.await;
+            workspace.update(&mut cx, |workspace, cx| match text {
+                Ok(text) => {
+                    // Files added after the revision are compared with an empty one.
+                    let title = format!("{file_name} ({})", revision.label);
+                    DiffView::open_with_base_text(
+                        text.unwrap_or_default(),
+                        title,
+                        buffer,
+                        workspace,
+                        cx,
+                    );
+                }
+                Err(error) => {
+                    const REVISION_LOAD_FAILURE_ID: usize = 2049;
+                    let message = format!(
+                        "Failed to load {file_name} at revision '{}': {error}",
+                        revision.revision
+                    );
+                    workspace.show_toast(Toast::new(REVISION_LOAD_FAILURE_ID, message), cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for RevisionPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Select or enter a revision...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + usize::from(self.typed_revision().is_some())
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self.candidates.clone();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.trim().is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    query.trim(),
+                    true,
+                    RECENT_COMMITS_COUNT + 1,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.query = query;
+                    delegate.selected_index = 0;
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let revision = match self.matches.get(self.selected_index) {
+            Some(hit) => self.revisions.get(hit.candidate_id).cloned(),
+            None => self.typed_revision(),
+        };
+        if let Some(revision) = revision {
+            self.compare_with_revision(revision, cx);
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let label = match self.matches.get(ix) {
+            Some(hit) => {
+                HighlightedLabel::new(hit.string.clone(), hit.positions.clone()).into_any_element()
+            }
+            None => Label::new(format!("Revision '{}'", self.typed_revision()?.revision))
+                .color(Color::Muted)
+                .into_any_element(),
+        };
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(label),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::PathBuf;
+
+    #[gpui::test]
+    async fn test_comparing_with_revision(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({ ".git": {}, "src": { "a.txt": "one\ntwo\nthree\n" } }),
+        )
+        .await;
+        let dot_git = Path::new("/root/.git");
+        let path = Path::new("src/a.txt");
+        fs.add_commit_to_repo(
+            dot_git,
+            "1111111aaa",
+            "Add a.txt",
+            &[(path, "one\n".into())],
+        );
+        fs.add_commit_to_repo(
+            dot_git,
+            "2222222bbb",
+            "Edit a.txt",
+            &[(path, "one\ntwo\n".into())],
+        );
+
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(PathBuf::from("/root/src/a.txt"), true, cx)
+            })
+            .await
+            .unwrap();
+
+        // HEAD is offered first, followed by the latest commits.
+        let picker = open_picker(&workspace, cx);
+        picker.update(cx, |picker, _| {
+            let matches = picker
+                .delegate
+                .matches
+                .iter()
+                .map(|hit| hit.string.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(matches, ["HEAD", "2222222 Edit a.txt", "1111111 Add a.txt"]);
+        });
+        picker.update(cx, |picker, cx| {
+            picker.delegate.set_selected_index(2, cx);
+            picker.delegate.confirm(false, cx);
+        });
+        cx.run_until_parked();
+        assert_compared_with(&workspace, "one\n", "a.txt (1111111)", cx);
+
+        // Revisions that aren't listed can be typed in.
+        let picker = open_picker(&workspace, cx);
+        picker.update(cx, |picker, cx| picker.set_query("2222222bbb", cx));
+        cx.run_until_parked();
+        picker.update(cx, |picker, cx| {
+            assert!(picker.delegate.matches.is_empty());
+            assert_eq!(picker.delegate.match_count(), 1);
+            picker.delegate.confirm(false, cx);
+        });
+        cx.run_until_parked();
+        assert_compared_with(&workspace, "one\ntwo\n", "a.txt (2222222bbb)", cx);
+    }
+
+    fn open_picker(
+        workspace: &View<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> View<Picker<RevisionPickerDelegate>> {
+        workspace.update(cx, |workspace, cx| {
+            let editor = workspace
+                .active_pane()
+                .read(cx)
+                .items_of_type::<editor::Editor>()
+                .next()
+                .unwrap();
+            workspace.activate_item(&editor, cx);
+            cx.focus_view(&editor);
+        });
+        cx.dispatch_action(crate::CompareWithRevision);
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<RevisionPicker>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        })
+    }
+
+    fn assert_compared_with(
+        workspace: &View<Workspace>,
+        base_text: &str,
+        title: &str,
+        cx: &mut VisualTestContext,
+    ) {
+        workspace.update(cx, |workspace, cx| {
+            let view = workspace.active_item_as::<DiffView>(cx).unwrap();
+            let view = view.read(cx);
+            assert_eq!(view.left_buffer.read(cx).text(), base_text);
+            assert_eq!(&*view.title(&view.left_buffer, cx), title);
+            assert_eq!(view.right_buffer.read(cx).text(), "one\ntwo\nthree\n");
+        });
+    }
+}
@@ -237,6 +237,7 @@ gpui::actions!(
         SelectToPreviousWordStart,
         SelectToStartOfParagraph,
         SelectUp,
+        ShareSelectionToChat,
         ShowCharacterPalette,
         ShowCompletions,
         ShuffleLines,
@@ -268,6 +268,12 @@ pub fn init(cx: &mut AppContext) {
             .detach();
         }
     });
+
+    // Only the states of recently saved files are restored, so discard the others once
+    // per session.
+    cx.background_executor()
+        .spawn(async { persistence::DB.delete_stale_file_states().await })
+        .detach_and_log_err(cx);
 }
 
 trait InvalidationRegion {
@@ -419,6 +425,7 @@ pub struct Editor {
     hover_state: HoverState,
     gutter_hovered: bool,
     hovered_link_state: Option<HoveredLinkState>,
+    persisted_file_state: Option<items::PersistedFileState>,
     copilot_state: CopilotState,
     inlay_hint_cache: InlayHintCache,
     next_inlay_id: usize,
@@ -1562,6 +1569,7 @@ impl Editor {
             remote_id: None,
             hover_state: Default::default(),
             hovered_link_state: Default::default(),
+            persisted_file_state: None,
             copilot_state: Default::default(),
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
             gutter_hovered: false,
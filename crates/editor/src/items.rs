@@ -1,7 +1,7 @@
 use crate::{
     editor_settings::SeedQuerySetting, persistence::DB, scroll::ScrollAnchor, Anchor, Autoscroll,
     Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer, MultiBufferSnapshot,
-    NavigationData, ToPoint as _,
+    NavigationData, ToOffset as _, ToPoint as _,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
@@ -12,12 +12,13 @@ use gpui::{
     VisualContext, WeakView, WindowContext,
 };
 use language::{
-    proto::serialize_anchor as serialize_text_anchor, Bias, Buffer, CharKind, OffsetRangeExt,
-    Point, SelectionGoal,
+    proto::{serialize_anchor as serialize_text_anchor, serialize_fingerprint},
+    Bias, Buffer, CharKind, OffsetRangeExt, Point, SelectionGoal, SerializedHistory,
 };
 use project::repository::GitFileStatus;
 use project::{search::SearchQuery, FormatTrigger, Item as _, Project, ProjectPath};
 use rpc::proto::{self, update_view, PeerId};
+use serde::{Deserialize, Serialize};
 use settings::Settings;
 use workspace::item::ItemSettings;
 
@@ -28,6 +29,7 @@ use std::{
     ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, UNIX_EPOCH},
 };
 use text::{BufferId, Selection};
 use theme::Theme;
@@ -41,6 +43,10 @@ use workspace::{
 };
 
 pub const MAX_TAB_TITLE_LEN: usize = 24;
+/// How many transactions of a file's undo and redo stacks are persisted.
+const MAX_PERSISTED_TRANSACTIONS: usize = 100;
+/// Undo histories that are larger than this once serialized aren't persisted.
+const MAX_PERSISTED_HISTORY_LEN: usize = 1024 * 1024;
 
 impl FollowableItem for Editor {
     fn remote_id(&self) -> Option<ViewId> {
@@ -306,6 +312,7 @@ impl FollowableItem for Editor {
                 }
                 _ => false,
             },
+            _ => false,
         }
     }
 
@@ -315,7 +322,9 @@ impl FollowableItem for Editor {
         message: update_view::Variant,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<()>> {
-        let update_view::Variant::Editor(message) = message;
+        let update_view::Variant::Editor(message) = message else {
+            return Task::ready(Err(anyhow!("received a non-editor update for an editor")));
+        };
         let project = project.clone();
         cx.spawn(|this, mut cx| async move {
             update_editor_from_message(this, project, message, &mut cx).await
@@ -675,10 +684,12 @@ impl Item for Editor {
     fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
         let selection = self.selections.newest_anchor();
         self.push_to_nav_history(selection.head(), None, cx);
+        self.save_file_state(cx).detach_and_log_err(cx);
     }
 
     fn workspace_deactivated(&mut self, cx: &mut ViewContext<Self>) {
         self.hide_hovered_link(cx);
+        self.save_file_state(cx).detach_and_log_err(cx);
     }
 
     fn is_dirty(&self, cx: &AppContext) -> bool {
@@ -747,6 +758,9 @@ impl Item for Editor {
                 }
             }
 
+            this.update(&mut cx, |this, cx| {
+                this.save_file_state(cx).detach_and_log_err(cx)
+            })?;
             Ok(())
         })
     }
@@ -854,17 +868,22 @@ impl Item for Editor {
             item_id: ItemId,
             cx: &mut AppContext,
         ) {
-            if let Some(file) = buffer.read(cx).file().and_then(|file| file.as_local()) {
-                let path = file.abs_path(cx);
+            // Untitled buffers are stored without a path, so their contents can be restored.
+            let path = match buffer.read(cx).file() {
+                Some(file) => match file.as_local() {
+                    Some(file) => Some(file.abs_path(cx)),
+                    None => return,
+                },
+                None => None,
+            };
 
-                cx.background_executor()
-                    .spawn(async move {
-                        DB.save_path(item_id, workspace_id, path.clone())
-                            .await
-                            .log_err()
-                    })
-                    .detach();
-            }
+            cx.background_executor()
+                .spawn(async move {
+                    DB.save_path(item_id, workspace_id, path.clone())
+                        .await
+                        .log_err()
+                })
+                .detach();
         }
 
         if let Some(buffer) = self.buffer().read(cx).as_singleton() {
@@ -890,6 +909,46 @@ impl Item for Editor {
         Some("Editor")
     }
 
+    fn serialize_unsaved_changes(
+        &mut self,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        if !self.project.as_ref()?.read(cx).is_local() {
+            return None;
+        }
+
+        let buffer = self.buffer().read(cx).as_singleton()?;
+        let buffer = buffer.read(cx);
+        let path = match buffer.file() {
+            Some(file) => Some(file.as_local()?.abs_path(cx)),
+            None => None,
+        };
+        let contents = buffer.text();
+        let language = buffer
+            .language()
+            .map(|language| language.name().to_string());
+        // Remember which version of the file the changes were made to, so that conflicts can be
+        // detected when restoring them.
+        let mtime = buffer
+            .saved_mtime()
+            .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok());
+        let item_id = cx.view().item_id().as_u64() as ItemId;
+
+        Some(cx.background_executor().spawn(async move {
+            DB.save_unsaved_contents(
+                item_id,
+                workspace_id,
+                path,
+                contents,
+                language,
+                mtime.map(|mtime| mtime.as_secs() as i64),
+                mtime.map(|mtime| mtime.subsec_nanos() as i32),
+            )
+            .await
+        }))
+    }
+
     fn to_item_events(event: &EditorEvent, mut f: impl FnMut(ItemEvent)) {
         match event {
             EditorEvent::Closed => f(ItemEvent::CloseItem),
@@ -931,41 +990,75 @@ impl Item for Editor {
         item_id: ItemId,
         cx: &mut ViewContext<Pane>,
     ) -> Task<Result<View<Self>>> {
-        let project_item: Result<_> = project.update(cx, |project, cx| {
-            // Look up the path with this key associated, create a self with that path
-            let path = DB
-                .get_path(item_id, workspace_id)?
-                .context("No path stored for this editor")?;
-
-            let (worktree, path) = project
-                .find_local_worktree(&path, cx)
-                .with_context(|| format!("No worktree for path: {path:?}"))?;
-            let project_path = ProjectPath {
-                worktree_id: worktree.read(cx).id(),
-                path: path.into(),
-            };
-
-            Ok(project.open_path(project_path, cx))
-        });
+        let serialized_editor = DB
+            .get_serialized_editor(item_id, workspace_id)
+            .and_then(|editor| editor.context("No editor stored for this item"));
+        let (path, contents, language, mtime_seconds, mtime_nanos) = match serialized_editor {
+            Ok(serialized_editor) => serialized_editor,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let saved_mtime = mtime_seconds
+            .zip(mtime_nanos)
+            .map(|(seconds, nanos)| UNIX_EPOCH + Duration::new(seconds as u64, nanos as u32));
+
+        let buffer: Task<Result<Model<Buffer>>> = match (path, contents) {
+            (Some(path), contents) => {
+                let project_item = project.update(cx, |project, cx| {
+                    let (worktree, path) = project
+                        .find_local_worktree(&path, cx)
+                        .with_context(|| format!("No worktree for path: {path:?}"))?;
+                    let project_path = ProjectPath {
+                        worktree_id: worktree.read(cx).id(),
+                        path: path.into(),
+                    };
+                    anyhow::Ok(project.open_path(project_path, cx))
+                });
 
-        project_item
-            .map(|project_item| {
-                cx.spawn(|pane, mut cx| async move {
-                    let (_, project_item) = project_item.await?;
+                cx.spawn(|_, mut cx| async move {
+                    let (_, project_item) = project_item?.await?;
                     let buffer = project_item
                         .downcast::<Buffer>()
                         .map_err(|_| anyhow!("Project item at stored path was not a buffer"))?;
-                    pane.update(&mut cx, |_, cx| {
-                        cx.new_view(|cx| {
-                            let mut editor = Editor::for_buffer(buffer, Some(project), cx);
+                    if let Some(contents) = contents {
+                        buffer.update(&mut cx, |buffer, cx| {
+                            buffer.restore_unsaved_changes(contents, saved_mtime, cx)
+                        })?;
+                    }
+                    Ok(buffer)
+                })
+            }
+            (None, Some(contents)) => {
+                let language = language
+                    .map(|language| project.read(cx).languages().language_for_name(&language));
+                let project = project.clone();
+                cx.spawn(|_, mut cx| async move {
+                    let language = match language {
+                        Some(language) => language.await.log_err(),
+                        None => None,
+                    };
+                    project.update(&mut cx, |project, cx| {
+                        let buffer = project.create_buffer("", language, cx)?;
+                        // Insert the contents as an edit, so that the buffer is still unsaved.
+                        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, contents)], None, cx));
+                        anyhow::Ok(buffer)
+                    })?
+                })
+            }
+            (None, None) => Task::ready(Err(anyhow!("No path stored for this editor"))),
+        };
 
-                            editor.read_scroll_position_from_db(item_id, workspace_id, cx);
-                            editor
-                        })
-                    })
+        cx.spawn(|pane, mut cx| async move {
+            let buffer = buffer.await?;
+            pane.update(&mut cx, |_, cx| {
+                cx.new_view(|cx| {
+                    let mut editor = Editor::for_buffer(buffer, Some(project), cx);
+
+                    editor.restore_file_state(cx).detach_and_log_err(cx);
+                    editor.read_scroll_position_from_db(item_id, workspace_id, cx);
+                    editor
                 })
             })
-            .unwrap_or_else(|error| Task::ready(Err(error)))
+        })
     }
 }
 
@@ -977,7 +1070,183 @@ impl ProjectItem for Editor {
         buffer: Model<Buffer>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
-        Self::for_buffer(buffer, Some(project), cx)
+        let mut editor = Self::for_buffer(buffer, Some(project), cx);
+        editor.restore_file_state(cx).detach_and_log_err(cx);
+        editor
+    }
+}
+
+/// What was last persisted for an editor's file, so that it isn't saved again until it changes.
+#[derive(PartialEq)]
+pub(crate) struct PersistedFileState {
+    abs_path: PathBuf,
+    fingerprint: String,
+    version: clock::Global,
+    selections: Vec<Range<usize>>,
+    folds: Vec<Range<usize>>,
+}
+
+/// The state of an editor for a file that is restored the next time the file is opened, as long as
+/// its contents haven't changed.
+#[derive(Serialize, Deserialize)]
+struct SerializedFileState {
+    selections: Vec<Range<usize>>,
+    folds: Vec<Range<usize>>,
+    history: Option<SerializedHistory>,
+}
+
+impl Editor {
+    fn save_file_state(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return Task::ready(Ok(()));
+        };
+        let (abs_path, fingerprint, version) = {
+            let buffer = buffer.read(cx);
+            if buffer.is_dirty() {
+                return Task::ready(Ok(()));
+            }
+            let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+                return Task::ready(Ok(()));
+            };
+            (
+                file.abs_path(cx),
+                serialize_fingerprint(buffer.saved_version_fingerprint()),
+                buffer.version(),
+            )
+        };
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selections = self
+            .selections
+            .all::<usize>(cx)
+            .into_iter()
+            .map(|selection| selection.tail()..selection.head())
+            .collect::<Vec<_>>();
+        let folds = self
+            .display_map
+            .update(cx, |map, cx| map.snapshot(cx))
+            .folds_in_range(0..snapshot.len())
+            .map(|fold| fold.range.start.to_offset(&snapshot)..fold.range.end.to_offset(&snapshot))
+            .collect::<Vec<_>>();
+
+        // Editors are deactivated often, so only write the state when it changed since it was
+        // last saved.
+        let state = PersistedFileState {
+            abs_path,
+            fingerprint,
+            version,
+            selections,
+            folds,
+        };
+        if self.persisted_file_state.as_ref() == Some(&state) {
+            return Task::ready(Ok(()));
+        }
+        let history = buffer
+            .read(cx)
+            .serialize_history(MAX_PERSISTED_TRANSACTIONS);
+        let abs_path = state.abs_path.clone();
+        let fingerprint = state.fingerprint.clone();
+        let selections = serde_json::to_string(&state.selections);
+        let folds = serde_json::to_string(&state.folds);
+        self.persisted_file_state = Some(state);
+
+        cx.background_executor().spawn(async move {
+            let history = if history.is_empty() {
+                None
+            } else {
+                Some(serde_json::to_string(&history)?)
+                    .filter(|history| history.len() <= MAX_PERSISTED_HISTORY_LEN)
+            };
+            DB.save_file_state(abs_path, fingerprint, selections?, folds?, history)
+                .await
+        })
+    }
+
+    /// Restores the state persisted for the editor's file by [`Self::save_file_state`], if the
+    /// file's contents match those the state was saved with.
+    fn restore_file_state(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return Task::ready(Ok(()));
+        };
+        let Some(file) = buffer.read(cx).file().and_then(|file| file.as_local()) else {
+            return Task::ready(Ok(()));
+        };
+        let abs_path = file.abs_path(cx);
+
+        cx.spawn(|this, mut cx| async move {
+            let state = cx
+                .background_executor()
+                .spawn(async move {
+                    let Some((fingerprint, selections, folds, history)) =
+                        DB.get_file_state(abs_path).await?
+                    else {
+                        return anyhow::Ok(None);
+                    };
+                    let state = SerializedFileState {
+                        selections: serde_json::from_str(&selections)?,
+                        folds: serde_json::from_str(&folds)?,
+                        history: history
+                            .map(|history| serde_json::from_str(&history))
+                            .transpose()?,
+                    };
+                    Ok(Some((fingerprint, state)))
+                })
+                .await?;
+            let Some((fingerprint, state)) = state else {
+                return Ok(());
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let matches = {
+                    let buffer = buffer.read(cx);
+                    !buffer.is_dirty()
+                        && serialize_fingerprint(buffer.saved_version_fingerprint()) == fingerprint
+                };
+                if !matches {
+                    return;
+                }
+
+                // Restoring the history replays its edits, which would be broadcast to the
+                // collaborators in a shared project.
+                let is_shared = this
+                    .project
+                    .as_ref()
+                    .map_or(false, |project| project.read(cx).is_shared());
+                if let Some(history) = state.history.as_ref().filter(|_| !is_shared) {
+                    buffer.update(cx, |buffer, cx| {
+                        if buffer.peek_undo_stack().is_none() && buffer.peek_redo_stack().is_none()
+                        {
+                            buffer.restore_history(history, cx).log_err();
+                        }
+                    });
+                }
+
+                let len = buffer.read(cx).len();
+                this.fold_ranges(
+                    state
+                        .folds
+                        .into_iter()
+                        .filter(|fold| fold.start <= fold.end && fold.end <= len),
+                    false,
+                    cx,
+                );
+
+                // Don't override selections made since the file was opened, e.g. when navigating
+                // to a definition in it.
+                let selections = this.selections.all::<usize>(cx);
+                let is_pristine = selections.len() == 1 && selections[0].range() == (0..0);
+                let restored_selections = state
+                    .selections
+                    .into_iter()
+                    .filter(|selection| selection.start.max(selection.end) <= len)
+                    .collect::<Vec<_>>();
+                if is_pristine && !restored_selections.is_empty() {
+                    this.change_selections(None, cx, |selections| {
+                        selections.select_ranges(restored_selections)
+                    });
+                }
+            })
+        })
     }
 }
 
@@ -1246,9 +1515,12 @@ fn path_for_file<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use gpui::AppContext;
+    use crate::editor_tests::init_test;
+    use gpui::{AppContext, TestAppContext, WindowHandle};
     use language::TestFile;
-    use std::path::Path;
+    use project::FakeFs;
+    use serde_json::json;
+    use std::{path::Path, sync::Arc};
 
     #[gpui::test]
     fn test_path_for_file(cx: &mut AppContext) {
@@ -1258,4 +1530,157 @@ mod tests {
         };
         assert_eq!(path_for_file(&file, 0, false, cx), None);
     }
+
+    #[gpui::test]
+    async fn test_restore_file_state(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.executor().allow_parking();
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/restore_file_state",
+            json!({ "a.txt": "one\ntwo\nthree\nfour\n" }),
+        )
+        .await;
+        let path = "/restore_file_state/a.txt";
+
+        let (project, buffer, editor) = open_file(&fs, path, cx).await;
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+        editor
+            .update(cx, |editor, cx| {
+                editor.fold_ranges([Point::new(1, 0)..Point::new(2, 3)], false, cx);
+                editor.change_selections(None, cx, |selections| {
+                    selections.select_ranges([Point::new(4, 0)..Point::new(4, 2)])
+                });
+            })
+            .unwrap();
+        save(&project, &buffer, &editor, cx).await;
+
+        // Folds, selections and the undo history are restored when the file is opened again.
+        let (_, buffer, editor) = open_file(&fs, path, cx).await;
+        editor
+            .update(cx, |editor, cx| {
+                assert_eq!(editor.display_text(cx), "zero\n⋯\nthree\nfour\n");
+                assert_eq!(
+                    editor.selections.ranges::<Point>(cx),
+                    [Point::new(4, 0)..Point::new(4, 2)]
+                );
+            })
+            .unwrap();
+        buffer.update(cx, |buffer, cx| {
+            assert!(!buffer.is_dirty());
+            buffer.undo(cx);
+            assert_eq!(buffer.text(), "one\ntwo\nthree\nfour\n");
+            buffer.redo(cx);
+            assert_eq!(buffer.text(), "zero\none\ntwo\nthree\nfour\n");
+        });
+
+        // Nothing is restored once the file has changed on disk.
+        fs.insert_file(path, b"zero\none\ntwo\nthree\nfour\nfive\n".to_vec())
+            .await;
+        let (_, buffer, editor) = open_file(&fs, path, cx).await;
+        editor
+            .update(cx, |editor, cx| {
+                assert_eq!(
+                    editor.display_text(cx),
+                    "zero\none\ntwo\nthree\nfour\nfive\n"
+                );
+                assert_eq!(
+                    editor.selections.ranges::<Point>(cx),
+                    [Point::zero()..Point::zero()]
+                );
+            })
+            .unwrap();
+        buffer.read_with(cx, |buffer, _| assert!(buffer.peek_undo_stack().is_none()));
+    }
+
+    #[gpui::test]
+    async fn test_restore_file_state_limits(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.executor().allow_parking();
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/restore_file_state_limits",
+            json!({ "a.txt": "a\n", "b.txt": "b\n" }),
+        )
+        .await;
+
+        // Only the most recent transactions are persisted.
+        let path = "/restore_file_state_limits/a.txt";
+        let (project, buffer, editor) = open_file(&fs, path, cx).await;
+        buffer.update(cx, |buffer, cx| {
+            for _ in 0..MAX_PERSISTED_TRANSACTIONS + 5 {
+                buffer.edit([(0..0, "x")], None, cx);
+                buffer.finalize_last_transaction();
+            }
+        });
+        save(&project, &buffer, &editor, cx).await;
+
+        let (_, buffer, _) = open_file(&fs, path, cx).await;
+        buffer.update(cx, |buffer, cx| {
+            while buffer.undo(cx).is_some() {}
+            assert_eq!(buffer.text(), "xxxxxa\n");
+        });
+
+        // Histories that are too large are dropped, but the rest of the state is kept.
+        let path = "/restore_file_state_limits/b.txt";
+        let (project, buffer, editor) = open_file(&fs, path, cx).await;
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "y".repeat(MAX_PERSISTED_HISTORY_LEN))], None, cx)
+        });
+        editor
+            .update(cx, |editor, cx| {
+                editor.change_selections(None, cx, |selections| selections.select_ranges([1..2]))
+            })
+            .unwrap();
+        save(&project, &buffer, &editor, cx).await;
+
+        let (_, buffer, editor) = open_file(&fs, path, cx).await;
+        editor
+            .update(cx, |editor, cx| {
+                assert_eq!(editor.selections.ranges::<usize>(cx), [1..2]);
+            })
+            .unwrap();
+        buffer.read_with(cx, |buffer, _| assert!(buffer.peek_undo_stack().is_none()));
+    }
+
+    /// Opens the file in an editor of a new project, as if it was opened in a new session, and
+    /// waits for the state persisted for it to be restored.
+    async fn open_file(
+        fs: &Arc<FakeFs>,
+        path: &str,
+        cx: &mut TestAppContext,
+    ) -> (Model<Project>, Model<Buffer>, WindowHandle<Editor>) {
+        let project = Project::test(fs.clone(), [path.as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        let editor =
+            cx.add_window(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), cx));
+        editor
+            .update(cx, |editor, cx| editor.restore_file_state(cx))
+            .unwrap()
+            .await
+            .unwrap();
+        (project, buffer, editor)
+    }
+
+    async fn save(
+        project: &Model<Project>,
+        buffer: &Model<Buffer>,
+        editor: &WindowHandle<Editor>,
+        cx: &mut TestAppContext,
+    ) {
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+        editor
+            .update(cx, |editor, cx| editor.save_file_state(cx))
+            .unwrap()
+            .await
+            .unwrap();
+    }
 }
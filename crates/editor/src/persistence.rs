@@ -10,10 +10,23 @@ define_connection!(
     // editors(
     //   item_id: usize,
     //   workspace_id: usize,
-    //   path: PathBuf,
+    //   path: Option<PathBuf>,
     //   scroll_top_row: usize,
     //   scroll_vertical_offset: f32,
     //   scroll_horizontal_offset: f32,
+    //   contents: Option<String>,
+    //   language: Option<String>,
+    //   mtime_seconds: Option<i64>,
+    //   mtime_nanos: Option<i32>,
+    // )
+    //
+    // file_states(
+    //   path: PathBuf,
+    //   fingerprint: String,
+    //   selections: String,
+    //   folds: String,
+    //   history: Option<String>,
+    //   timestamp: String,
     // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
@@ -31,19 +44,62 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN scroll_top_row INTEGER NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_horizontal_offset REAL NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_vertical_offset REAL NOT NULL DEFAULT 0;
+        ),
+        // Allow editors without a path, and store unsaved contents so they survive restarts.
+        sql! (
+            CREATE TABLE editors_2(
+                item_id INTEGER NOT NULL,
+                workspace_id INTEGER NOT NULL,
+                path BLOB,
+                scroll_top_row INTEGER NOT NULL DEFAULT 0,
+                scroll_horizontal_offset REAL NOT NULL DEFAULT 0,
+                scroll_vertical_offset REAL NOT NULL DEFAULT 0,
+                contents TEXT,
+                language TEXT,
+                mtime_seconds INTEGER,
+                mtime_nanos INTEGER,
+                PRIMARY KEY(item_id, workspace_id),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+            INSERT INTO editors_2
+                (item_id, workspace_id, path, scroll_top_row, scroll_horizontal_offset, scroll_vertical_offset)
+            SELECT
+                item_id, workspace_id, path, scroll_top_row, scroll_horizontal_offset, scroll_vertical_offset
+            FROM editors;
+            DROP TABLE editors;
+            ALTER TABLE editors_2 RENAME TO editors;
+        ),
+        // Selections, folds and undo history of files, keyed by path rather than by editor, and
+        // stored along with the fingerprint of the contents they apply to.
+        sql! (
+            CREATE TABLE file_states(
+                path BLOB NOT NULL PRIMARY KEY,
+                fingerprint TEXT NOT NULL,
+                selections TEXT NOT NULL,
+                folds TEXT NOT NULL,
+                history TEXT,
+                timestamp TEXT DEFAULT CURRENT_TIMESTAMP NOT NULL
+            ) STRICT;
         )];
 );
 
 impl EditorDb {
     query! {
-        pub fn get_path(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<PathBuf>> {
-            SELECT path FROM editors
+        pub fn get_serialized_editor(
+            item_id: ItemId,
+            workspace_id: WorkspaceId
+        ) -> Result<Option<(Option<PathBuf>, Option<String>, Option<String>, Option<i64>, Option<i32>)>> {
+            SELECT path, contents, language, mtime_seconds, mtime_nanos
+            FROM editors
             WHERE item_id = ? AND workspace_id = ?
         }
     }
 
+    // Stores the editor's path, discarding any unsaved contents stored for it.
     query! {
-        pub async fn save_path(item_id: ItemId, workspace_id: WorkspaceId, path: PathBuf) -> Result<()> {
+        pub async fn save_path(item_id: ItemId, workspace_id: WorkspaceId, path: Option<PathBuf>) -> Result<()> {
             INSERT INTO editors
                 (item_id, workspace_id, path)
             VALUES
@@ -51,7 +107,34 @@ impl EditorDb {
             ON CONFLICT DO UPDATE SET
                 item_id = ?1,
                 workspace_id = ?2,
-                path = ?3
+                path = ?3,
+                contents = NULL,
+                language = NULL,
+                mtime_seconds = NULL,
+                mtime_nanos = NULL
+        }
+    }
+
+    query! {
+        pub async fn save_unsaved_contents(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            path: Option<PathBuf>,
+            contents: String,
+            language: Option<String>,
+            mtime_seconds: Option<i64>,
+            mtime_nanos: Option<i32>
+        ) -> Result<()> {
+            INSERT INTO editors
+                (item_id, workspace_id, path, contents, language, mtime_seconds, mtime_nanos)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT DO UPDATE SET
+                path = ?3,
+                contents = ?4,
+                language = ?5,
+                mtime_seconds = ?6,
+                mtime_nanos = ?7
         }
     }
 
@@ -80,4 +163,45 @@ impl EditorDb {
             WHERE item_id = ?1 AND workspace_id = ?2
         }
     }
+
+    query! {
+        pub async fn get_file_state(path: PathBuf) -> Result<Option<(String, String, String, Option<String>)>> {
+            SELECT fingerprint, selections, folds, history
+            FROM file_states
+            WHERE path = ?
+        }
+    }
+
+    query! {
+        pub async fn save_file_state(
+            path: PathBuf,
+            fingerprint: String,
+            selections: String,
+            folds: String,
+            history: Option<String>
+        ) -> Result<()> {
+            INSERT INTO file_states
+                (path, fingerprint, selections, folds, history)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT DO UPDATE SET
+                fingerprint = ?2,
+                selections = ?3,
+                folds = ?4,
+                history = ?5,
+                timestamp = CURRENT_TIMESTAMP
+        }
+    }
+
+    // Keeps the states of the most recently saved files, discarding the rest.
+    query! {
+        pub async fn delete_stale_file_states() -> Result<()> {
+            DELETE FROM file_states
+            WHERE path NOT IN (
+                SELECT path FROM file_states
+                ORDER BY timestamp DESC
+                LIMIT 1000
+            )
+        }
+    }
 }
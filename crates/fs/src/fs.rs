@@ -29,7 +29,7 @@ use util::{paths, ResultExt};
 #[cfg(any(test, feature = "test-support"))]
 use collections::{btree_map, BTreeMap};
 #[cfg(any(test, feature = "test-support"))]
-use repository::{FakeGitRepositoryState, GitFileStatus};
+use repository::{Commit, FakeGitRepositoryState, GitFileStatus};
 #[cfg(any(test, feature = "test-support"))]
 use std::ffi::OsStr;
 
@@ -824,6 +824,32 @@ impl FakeFs {
         });
     }
 
+    /// Adds a commit with the given file contents, making it the repository's HEAD.
+    pub fn add_commit_to_repo(
+        &self,
+        dot_git: &Path,
+        sha: &str,
+        summary: &str,
+        contents: &[(&Path, String)],
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.commits.insert(
+                0,
+                Commit {
+                    sha: sha.into(),
+                    summary: summary.into(),
+                },
+            );
+            state.revision_contents.insert(
+                sha.to_string(),
+                contents
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone()))
+                    .collect(),
+            );
+        });
+    }
+
     pub fn set_status_for_repo_via_working_copy_change(
         &self,
         dot_git: &Path,
@@ -21,10 +21,28 @@ pub struct Branch {
     pub unix_timestamp: Option<i64>,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub struct Commit {
+    pub sha: Box<str>,
+    /// The first line of the commit message.
+    pub summary: Box<str>,
+}
+
 pub trait GitRepository: Send {
     fn reload_index(&self);
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
 
+    /// Loads the contents of a file at the given revision, such as `HEAD` or a commit SHA.
+    /// Returns `None` if the file doesn't exist at that revision.
+    fn load_revision_text(
+        &self,
+        revision: &str,
+        relative_file_path: &Path,
+    ) -> Result<Option<String>>;
+
+    /// Returns up to `limit` commits reachable from HEAD, newest first.
+    fn recent_commits(&self, limit: usize) -> Result<Vec<Commit>>;
+
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
@@ -92,6 +110,37 @@ impl GitRepository for LibGitRepository {
         None
     }
 
+    fn load_revision_text(
+        &self,
+        revision: &str,
+        relative_file_path: &Path,
+    ) -> Result<Option<String>> {
+        check_path_to_repo_path_errors(relative_file_path)?;
+        let tree = self.revparse_single(revision)?.peel_to_commit()?.tree()?;
+        let entry = match tree.get_path(relative_file_path) {
+            Ok(entry) => entry,
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let content = entry.to_object(self)?.peel_to_blob()?.content().to_owned();
+        Ok(Some(String::from_utf8(content)?))
+    }
+
+    fn recent_commits(&self, limit: usize) -> Result<Vec<Commit>> {
+        let mut revwalk = self.revwalk()?;
+        revwalk.push_head()?;
+        revwalk
+            .take(limit)
+            .map(|oid| {
+                let commit = self.find_commit(oid?)?;
+                Ok(Commit {
+                    sha: commit.id().to_string().into(),
+                    summary: commit.summary().unwrap_or_default().into(),
+                })
+            })
+            .collect()
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let remote = self.find_remote(name).ok()?;
         remote.url().map(|url| url.to_string())
@@ -253,6 +302,9 @@ pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
+    /// Commits reachable from HEAD, newest first.
+    pub commits: Vec<Commit>,
+    pub revision_contents: HashMap<String, HashMap<PathBuf, String>>,
 }
 
 impl FakeGitRepository {
@@ -269,6 +321,23 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_revision_text(&self, revision: &str, path: &Path) -> Result<Option<String>> {
+        let state = self.state.lock();
+        let sha = match revision {
+            "HEAD" => state.commits.first().map(|commit| &*commit.sha),
+            _ => Some(revision),
+        };
+        let contents = sha
+            .and_then(|sha| state.revision_contents.get(sha))
+            .ok_or_else(|| anyhow::anyhow!("unknown revision {revision}"))?;
+        Ok(contents.get(path).cloned())
+    }
+
+    fn recent_commits(&self, limit: usize) -> Result<Vec<Commit>> {
+        let state = self.state.lock();
+        Ok(state.commits.iter().take(limit).cloned().collect())
+    }
+
     fn remote_url(&self, _name: &str) -> Option<String> {
         None
     }
@@ -279,7 +348,8 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn head_sha(&self) -> Option<String> {
-        None
+        let state = self.state.lock();
+        state.commits.first().map(|commit| commit.sha.to_string())
     }
 
     fn staged_statuses(&self, path_prefix: &Path) -> TreeMap<RepoPath, GitFileStatus> {
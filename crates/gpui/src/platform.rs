@@ -74,6 +74,16 @@ pub(crate) fn current_platform() -> Rc<dyn Platform> {
     Rc::new(WindowsPlatform::new())
 }
 
+#[cfg(target_os = "linux")]
+pub(crate) fn current_headless_platform() -> Rc<dyn Platform> {
+    Rc::new(LinuxPlatform::new_headless())
+}
+// Other platforms can run without opening any windows.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn current_headless_platform() -> Rc<dyn Platform> {
+    current_platform()
+}
+
 pub(crate) trait Platform: 'static {
     fn background_executor(&self) -> BackgroundExecutor;
     fn foreground_executor(&self) -> ForegroundExecutor;
@@ -109,6 +109,25 @@ impl Keystroke {
         })
     }
 
+    /// Produces a representation of this keystroke in the same syntax accepted by
+    /// [`Keystroke::parse`], ignoring the ime_key.
+    pub fn unparse(&self) -> String {
+        let mut text = String::new();
+        for (enabled, modifier) in [
+            (self.modifiers.control, "ctrl-"),
+            (self.modifiers.alt, "alt-"),
+            (self.modifiers.shift, "shift-"),
+            (self.modifiers.command, "cmd-"),
+            (self.modifiers.function, "fn-"),
+        ] {
+            if enabled {
+                text.push_str(modifier);
+            }
+        }
+        text.push_str(&self.key);
+        text
+    }
+
     /// Returns a new keystroke with the ime_key filled.
     /// This is used for dispatch_keystroke where we want users to
     /// be able to simulate typing "space", etc.
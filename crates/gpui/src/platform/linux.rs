@@ -1,5 +1,6 @@
 mod client;
 mod dispatcher;
+mod headless;
 mod platform;
 mod text_system;
 mod util;
@@ -0,0 +1,55 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use copypasta::nop_clipboard::NopClipboardContext;
+use copypasta::ClipboardProvider;
+
+use crate::platform::linux::client::Client;
+use crate::platform::PlatformWindow;
+use crate::{AnyWindowHandle, CursorStyle, DisplayId, PlatformDisplay, WindowParams};
+
+/// A client for machines without a display server, which runs the event loop but can't open
+/// any windows.
+pub(crate) struct HeadlessClient {
+    clipboard: Rc<RefCell<NopClipboardContext>>,
+}
+
+impl HeadlessClient {
+    pub(crate) fn new() -> Self {
+        Self {
+            clipboard: Rc::new(RefCell::new(NopClipboardContext::new().unwrap())),
+        }
+    }
+}
+
+impl Client for HeadlessClient {
+    fn displays(&self) -> Vec<Rc<dyn PlatformDisplay>> {
+        Vec::new()
+    }
+
+    fn primary_display(&self) -> Option<Rc<dyn PlatformDisplay>> {
+        None
+    }
+
+    fn display(&self, _id: DisplayId) -> Option<Rc<dyn PlatformDisplay>> {
+        None
+    }
+
+    fn open_window(
+        &self,
+        _handle: AnyWindowHandle,
+        _options: WindowParams,
+    ) -> Box<dyn PlatformWindow> {
+        panic!("can't open windows in a headless app")
+    }
+
+    fn set_cursor_style(&self, _style: CursorStyle) {}
+
+    fn get_clipboard(&self) -> Rc<RefCell<dyn ClipboardProvider>> {
+        self.clipboard.clone()
+    }
+
+    fn get_primary(&self) -> Rc<RefCell<dyn ClipboardProvider>> {
+        self.clipboard.clone()
+    }
+}
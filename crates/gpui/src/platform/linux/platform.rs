@@ -28,6 +28,7 @@ use crate::{
     SemanticVersion, Task, WindowOptions, WindowParams,
 };
 
+use super::headless::HeadlessClient;
 use super::x11::X11Client;
 
 #[derive(Default)]
@@ -69,6 +70,30 @@ impl LinuxPlatform {
         let wayland_display = env::var_os("WAYLAND_DISPLAY");
         let use_wayland = wayland_display.is_some() && !wayland_display.unwrap().is_empty();
 
+        let inner = Self::new_inner();
+        if use_wayland {
+            Self {
+                client: Rc::new(WaylandClient::new(Rc::clone(&inner))),
+                inner,
+            }
+        } else {
+            Self {
+                client: X11Client::new(Rc::clone(&inner)),
+                inner,
+            }
+        }
+    }
+
+    /// Creates a platform that doesn't connect to a display server.
+    pub(crate) fn new_headless() -> Self {
+        let inner = Self::new_inner();
+        Self {
+            client: Rc::new(HeadlessClient::new()),
+            inner,
+        }
+    }
+
+    fn new_inner() -> Rc<LinuxPlatformInner> {
         let (main_sender, main_receiver) = calloop::channel::channel::<Runnable>();
         let text_system = Arc::new(LinuxTextSystem::new());
         let callbacks = RefCell::new(Callbacks::default());
@@ -84,7 +109,7 @@ impl LinuxPlatform {
 
         let dispatcher = Arc::new(LinuxDispatcher::new(main_sender));
 
-        let inner = Rc::new(LinuxPlatformInner {
+        Rc::new(LinuxPlatformInner {
             loop_handle: Rc::new(event_loop.handle()),
             loop_signal: event_loop.get_signal(),
             event_loop: RefCell::new(event_loop),
@@ -92,19 +117,7 @@ impl LinuxPlatform {
             foreground_executor: ForegroundExecutor::new(dispatcher.clone()),
             text_system,
             callbacks,
-        });
-
-        if use_wayland {
-            Self {
-                client: Rc::new(WaylandClient::new(Rc::clone(&inner))),
-                inner,
-            }
-        } else {
-            Self {
-                client: X11Client::new(Rc::clone(&inner)),
-                inner,
-            }
-        }
+        })
     }
 }
 
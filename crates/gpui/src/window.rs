@@ -2,9 +2,9 @@ use crate::{
     point, px, size, transparent_black, Action, AnyDrag, AnyView, AppContext, Arena,
     AsyncWindowContext, Bounds, Context, Corners, CursorStyle, DispatchActionListener,
     DispatchNodeId, DispatchTree, DisplayId, Edges, Effect, Entity, EntityId, EventEmitter,
-    FileDropEvent, Flatten, Global, GlobalElementId, GlobalPixels, Hsla, KeyBinding, KeyDownEvent,
-    KeyMatch, KeymatchResult, Keystroke, KeystrokeEvent, Model, ModelContext, Modifiers,
-    MouseButton, MouseMoveEvent, MouseUpEvent, Pixels, PlatformAtlas, PlatformDisplay,
+    FileDropEvent, Flatten, Global, GlobalElementId, GlobalPixels, Hsla, KeyBinding, KeyContext,
+    KeyDownEvent, KeyMatch, KeymatchResult, Keystroke, KeystrokeEvent, Model, ModelContext,
+    Modifiers, MouseButton, MouseMoveEvent, MouseUpEvent, Pixels, PlatformAtlas, PlatformDisplay,
     PlatformInput, PlatformWindow, Point, PromptLevel, Render, ScaledPixels, SharedString, Size,
     SubscriberSet, Subscription, TaffyLayoutEngine, Task, TextStyle, TextStyleRefinement, View,
     VisualContext, WeakView, WindowAppearance, WindowOptions, WindowParams, WindowTextSystem,
@@ -309,6 +309,7 @@ pub struct Window {
     pub(crate) focus: Option<FocusId>,
     focus_enabled: bool,
     pending_input: Option<PendingInput>,
+    keystroke_recorder: Option<FocusId>,
     prompt: Option<RenderablePromptHandle>,
 }
 
@@ -563,6 +564,7 @@ impl Window {
             focus: None,
             focus_enabled: true,
             pending_input: None,
+            keystroke_recorder: None,
             prompt: None,
         }
     }
@@ -1294,7 +1296,12 @@ impl<'a> WindowContext<'a> {
             .dispatch_tree
             .dispatch_path(node_id);
 
-        if let Some(key_down_event) = event.downcast_ref::<KeyDownEvent>() {
+        let is_recording_keystrokes =
+            self.window.focus.is_some() && self.window.focus == self.window.keystroke_recorder;
+        if let Some(key_down_event) = event
+            .downcast_ref::<KeyDownEvent>()
+            .filter(|_| !is_recording_keystrokes)
+        {
             let KeymatchResult { bindings, pending } = self
                 .window
                 .rendered_frame
@@ -1666,6 +1673,29 @@ impl<'a> WindowContext<'a> {
         actions
     }
 
+    /// Returns the key contexts of the currently focused element and its ancestors,
+    /// arranged from the root to the focused element.
+    pub fn context_stack(&self) -> Vec<KeyContext> {
+        let dispatch_tree = &self.window.rendered_frame.dispatch_tree;
+        let node_id = self
+            .window
+            .focus
+            .and_then(|focus_id| dispatch_tree.focusable_node_id(focus_id))
+            .unwrap_or_else(|| dispatch_tree.root_node_id());
+        dispatch_tree
+            .dispatch_path(node_id)
+            .into_iter()
+            .filter_map(|node_id| dispatch_tree.node(node_id).context.clone())
+            .collect()
+    }
+
+    /// Sends key down events to the element with the given focus handle, without
+    /// matching them against the keymap, for as long as it's focused. This allows
+    /// recording keystrokes that are bound to actions. Pass `None` to stop recording.
+    pub fn set_keystroke_recorder(&mut self, focus_handle: Option<&FocusHandle>) {
+        self.window.keystroke_recorder = focus_handle.map(|handle| handle.id);
+    }
+
     /// Returns key bindings that invoke the given action on the currently focused element.
     pub fn bindings_for_action(&self, action: &dyn Action) -> Vec<KeyBinding> {
         self.window
@@ -46,8 +46,9 @@ use text::*;
 pub use text::{
     Anchor, Bias, Buffer as TextBuffer, BufferId, BufferSnapshot as TextBufferSnapshot, Edit,
     OffsetRangeExt, OffsetUtf16, Patch, Point, PointUtf16, Rope, RopeFingerprint, Selection,
-    SelectionGoal, Subscription, TextDimension, TextSummary, ToOffset, ToOffsetUtf16, ToPoint,
-    ToPointUtf16, Transaction, TransactionId, Unclipped,
+    SelectionGoal, SerializedHistory, SerializedTransaction, Subscription, TextDimension,
+    TextSummary, ToOffset, ToOffsetUtf16, ToPoint, ToPointUtf16, Transaction, TransactionId,
+    Unclipped,
 };
 use theme::SyntaxTheme;
 #[cfg(any(test, feature = "test-support"))]
@@ -1706,6 +1707,91 @@ impl Buffer {
         }
     }
 
+    /// Restores unsaved changes that were made on top of the file's contents as of `saved_mtime`,
+    /// e.g. after the buffer was persisted when quitting. The restored text is left unsaved, and if
+    /// the file has been modified on disk in the meantime, the buffer is marked as conflicted.
+    pub fn restore_unsaved_changes(
+        &mut self,
+        text: String,
+        saved_mtime: Option<SystemTime>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if self.text() == text {
+            return;
+        }
+
+        self.set_text(text, cx);
+        if saved_mtime.is_some() {
+            self.saved_mtime = saved_mtime;
+        }
+        cx.notify();
+    }
+
+    /// Replays an undo history obtained from [`TextBuffer::serialize_history`], so that its
+    /// transactions can be undone and redone. The buffer must contain the text the history was
+    /// serialized with and have no history of its own. Its text is left unchanged.
+    pub fn restore_history(
+        &mut self,
+        history: &SerializedHistory,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        if self.peek_undo_stack().is_some() || self.peek_redo_stack().is_some() {
+            return Err(anyhow!("buffer already has an undo history"));
+        }
+        history.initial_text(&self.text())?;
+        let was_dirty = self.is_dirty();
+
+        // Rewind to the text preceding the history, without recording it as a transaction.
+        self.start_transaction();
+        for transaction in history.undo_stack.iter().rev() {
+            self.apply_serialized_transaction(&transaction.inverse(), cx);
+        }
+        if let Some(transaction_id) = self.end_transaction(cx) {
+            self.forget_transaction(transaction_id);
+        }
+
+        for transaction in &history.undo_stack {
+            self.start_transaction();
+            self.apply_serialized_transaction(transaction, cx);
+            self.end_transaction(cx);
+            self.finalize_last_transaction();
+        }
+        let mut redo_count = 0;
+        for transaction in &history.redo_stack {
+            self.start_transaction();
+            self.apply_serialized_transaction(transaction, cx);
+            if self.end_transaction(cx).is_some() {
+                redo_count += 1;
+            }
+            self.finalize_last_transaction();
+        }
+        for _ in 0..redo_count {
+            self.undo(cx);
+        }
+
+        if !was_dirty && self.is_dirty() {
+            self.saved_version = self.version();
+            cx.emit(Event::DirtyChanged);
+            cx.notify();
+        }
+        Ok(())
+    }
+
+    fn apply_serialized_transaction(
+        &mut self,
+        transaction: &SerializedTransaction,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.edit(
+            transaction
+                .edits
+                .iter()
+                .map(|edit| (edit.range.clone(), edit.new_text.as_str())),
+            None,
+            cx,
+        );
+    }
+
     /// Replaces the buffer's entire text.
     pub fn set_text<T>(&mut self, text: T, cx: &mut ModelContext<Self>) -> Option<clock::Lamport>
     where
@@ -335,6 +335,48 @@ fn test_edit_events(cx: &mut gpui::AppContext) {
     );
 }
 
+#[gpui::test]
+fn test_restore_history(cx: &mut AppContext) {
+    let buffer = cx.new_model(|cx| {
+        let mut buffer = Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), "abc");
+        buffer.edit([(3..3, "d")], None, cx);
+        buffer.edit([(0..1, "A")], None, cx);
+        buffer.edit([(1..3, "")], None, cx);
+        buffer.undo(cx);
+        buffer
+    });
+    let history = buffer.read(cx).serialize_history(usize::MAX);
+
+    let restored =
+        cx.new_model(|cx| Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), "Abcd"));
+    restored.update(cx, |buffer, cx| {
+        buffer.restore_history(&history, cx).unwrap();
+        assert_eq!(buffer.text(), "Abcd");
+        assert!(!buffer.is_dirty());
+
+        buffer.redo(cx);
+        assert_eq!(buffer.text(), "Ad");
+        buffer.undo(cx);
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "abcd");
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "abc");
+        assert!(buffer.undo(cx).is_none());
+
+        // A history can't be restored onto a buffer that already has one.
+        assert!(buffer.restore_history(&history, cx).is_err());
+    });
+
+    // Nor onto a buffer whose text doesn't match the history.
+    let mismatched =
+        cx.new_model(|cx| Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), "Abce"));
+    mismatched.update(cx, |buffer, cx| {
+        assert!(buffer.restore_history(&history, cx).is_err());
+        assert_eq!(buffer.text(), "Abce");
+        assert!(buffer.undo(cx).is_none());
+    });
+}
+
 #[gpui::test]
 async fn test_apply_diff(cx: &mut TestAppContext) {
     let text = "a\nbb\nccc\ndddd\neeeee\nffffff\n";
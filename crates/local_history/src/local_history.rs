@@ -0,0 +1,20 @@
+mod local_history_view;
+
+use std::sync::Arc;
+
+use fs::Fs;
+use gpui::{actions, AppContext};
+use project::LocalHistory;
+use workspace::Workspace;
+
+pub use local_history_view::LocalHistoryView;
+
+actions!(local_history, [Open, Restore]);
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    LocalHistory::init(fs, cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(LocalHistoryView::open);
+    })
+    .detach();
+}
@@ -0,0 +1,528 @@
+use std::{ops::Range, path::PathBuf};
+
+use chrono::{DateTime, Local};
+use editor::Editor;
+use git::diff::BufferDiff;
+use gpui::{
+    uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, KeyContext,
+    Model, Render, Subscription, Task, ViewContext,
+};
+use language::{Buffer, Point};
+use menu::{SelectNext, SelectPrev};
+use project::{LocalHistory, LocalHistoryEntry, LocalHistoryEvent};
+use theme::ActiveTheme;
+use ui::{
+    prelude::*,
+    utils::{DateTimeType, FormatDistance},
+    ListItem, ListItemSpacing, Tooltip,
+};
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace,
+};
+
+use crate::{Open, Restore};
+
+/// Shows the snapshots recorded in a file's local history, and how each differs from the file's
+/// current contents.
+pub struct LocalHistoryView {
+    focus_handle: FocusHandle,
+    buffer: Model<Buffer>,
+    abs_path: PathBuf,
+    file_name: SharedString,
+    history: Model<LocalHistory>,
+    entries: Vec<LocalHistoryEntry>,
+    selected_index: usize,
+    diff: Vec<DiffLine>,
+    load_entries_task: Task<()>,
+    update_diff_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DiffLineKind {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DiffLine {
+    kind: DiffLineKind,
+    text: SharedString,
+}
+
+impl LocalHistoryView {
+    pub fn open(workspace: &mut Workspace, _: &Open, cx: &mut ViewContext<Workspace>) {
+        let Some(history) = LocalHistory::global(cx) else {
+            return;
+        };
+        let Some(buffer) = workspace
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+        else {
+            return;
+        };
+        let Some(abs_path) = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| Some(file.as_local()?.abs_path(cx)))
+        else {
+            return;
+        };
+
+        let existing = workspace
+            .active_pane()
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|view| view.read(cx).abs_path == abs_path);
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, cx);
+        } else {
+            let view = cx.new_view(|cx| Self::new(buffer, abs_path, history, cx));
+            workspace.add_item_to_active_pane(Box::new(view), cx);
+        }
+    }
+
+    fn new(
+        buffer: Model<Buffer>,
+        abs_path: PathBuf,
+        history: Model<LocalHistory>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let file_name = abs_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .into();
+        let subscriptions = vec![
+            cx.subscribe(&history, |this, _, event, cx| match event {
+                LocalHistoryEvent::EntryAdded { abs_path } => {
+                    if *abs_path == this.abs_path {
+                        this.load_entries(cx);
+                    }
+                }
+            }),
+            cx.subscribe(&buffer, |this, _, event, cx| {
+                if let language::Event::Edited = event {
+                    this.update_diff(cx);
+                }
+            }),
+        ];
+
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            buffer,
+            abs_path,
+            file_name,
+            history,
+            entries: Vec::new(),
+            selected_index: 0,
+            diff: Vec::new(),
+            load_entries_task: Task::ready(()),
+            update_diff_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.load_entries(cx);
+        this
+    }
+
+    fn load_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let entries = self.history.read(cx).entries(&self.abs_path, cx);
+        self.load_entries_task = cx.spawn(|this, mut cx| async move {
+            let Some(entries) = entries.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                // Keep the same snapshot selected when new ones are recorded.
+                let selected_digest = this
+                    .entries
+                    .get(this.selected_index)
+                    .map(|entry| (entry.digest.clone(), entry.saved_at));
+                this.selected_index = selected_digest
+                    .and_then(|(digest, saved_at)| {
+                        entries
+                            .iter()
+                            .position(|entry| entry.digest == digest && entry.saved_at == saved_at)
+                    })
+                    .unwrap_or(0);
+                this.entries = entries;
+                this.update_diff(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn select(&mut self, index: usize, cx: &mut ViewContext<Self>) {
+        if index < self.entries.len() && index != self.selected_index {
+            self.selected_index = index;
+            self.update_diff(cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        self.select(self.selected_index + 1, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if let Some(index) = self.selected_index.checked_sub(1) {
+            self.select(index, cx);
+        }
+    }
+
+    fn update_diff(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.entries.get(self.selected_index) else {
+            self.diff.clear();
+            cx.notify();
+            return;
+        };
+
+        let base_text = self.history.read(cx).load(&self.abs_path, entry, cx);
+        let snapshot = self.buffer.read(cx).text_snapshot();
+        self.update_diff_task = cx.spawn(|this, mut cx| async move {
+            let Some(base_text) = base_text.await.log_err() else {
+                return;
+            };
+            let diff = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut diff = BufferDiff::new();
+                    diff.update(&base_text, &snapshot).await;
+                    diff_lines(&base_text, &diff, &snapshot)
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.diff = diff;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn restore(&mut self, _: &Restore, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.entries.get(self.selected_index) else {
+            return;
+        };
+
+        let text = self.history.read(cx).load(&self.abs_path, entry, cx);
+        let buffer = self.buffer.clone();
+        cx.spawn(|_, mut cx| async move {
+            let text = text.await?;
+            let diff = buffer
+                .update(&mut cx, |buffer, cx| buffer.diff(text, cx))?
+                .await;
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.finalize_last_transaction();
+                buffer.apply_diff(diff, cx);
+                buffer.finalize_last_transaction();
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_entries(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .id("local-history-entries")
+            .w(rems(16.))
+            .h_full()
+            .p_1()
+            .overflow_y_scroll()
+            .border_r_1()
+            .border_color(cx.theme().colors().border)
+            .children(self.entries.iter().enumerate().map(|(ix, entry)| {
+                let saved_at: DateTime<Local> = entry.saved_at.into();
+                ListItem::new(ix)
+                    .inset(true)
+                    .spacing(ListItemSpacing::Sparse)
+                    .selected(ix == self.selected_index)
+                    .on_click(cx.listener(move |this, _, cx| this.select(ix, cx)))
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .justify_between()
+                            .child(Label::new(
+                                FormatDistance::from_now(DateTimeType::Local(saved_at))
+                                    .add_suffix(true)
+                                    .to_string(),
+                            ))
+                            .child(
+                                Label::new(format_size(entry.len))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                    .tooltip(move |cx| {
+                        Tooltip::text(saved_at.format("%Y-%m-%d %H:%M:%S").to_string(), cx)
+                    })
+            }))
+    }
+
+    fn render_diff(&self, cx: &mut ViewContext<Self>) -> AnyElement {
+        if self.entries.is_empty() {
+            return v_flex()
+                .size_full()
+                .items_center()
+                .justify_center()
+                .child(Label::new("No local history for this file yet").color(Color::Muted))
+                .into_any_element();
+        }
+
+        v_flex()
+            .size_full()
+            .child(
+                h_flex()
+                    .p_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        Label::new("Changes made since this snapshot was saved")
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Button::new("restore", "Restore")
+                            .tooltip(|cx| {
+                                Tooltip::for_action("Restore this snapshot", &Restore, cx)
+                            })
+                            .on_click(cx.listener(|this, _, cx| this.restore(&Restore, cx))),
+                    ),
+            )
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "local-history-diff",
+                    self.diff.len(),
+                    |this, range: Range<usize>, cx| {
+                        let colors = cx.theme().status();
+                        this.diff[range]
+                            .iter()
+                            .map(|line| {
+                                let (prefix, background) = match line.kind {
+                                    DiffLineKind::Unchanged => (" ", None),
+                                    DiffLineKind::Added => ("+", Some(colors.created_background)),
+                                    DiffLineKind::Removed => ("-", Some(colors.deleted_background)),
+                                };
+                                h_flex()
+                                    .w_full()
+                                    .px_2()
+                                    .font("Zed Mono")
+                                    .when_some(background, |this, background| this.bg(background))
+                                    .child(Label::new(format!("{prefix} {}", line.text)))
+                            })
+                            .collect()
+                    },
+                )
+                .size_full(),
+            )
+            .into_any_element()
+    }
+}
+
+/// Lists the lines of the buffer, interleaved with the lines of `base_text` that they replaced.
+fn diff_lines(
+    base_text: &str,
+    diff: &BufferDiff,
+    snapshot: &text::BufferSnapshot,
+) -> Vec<DiffLine> {
+    let line = |row: u32| DiffLine {
+        kind: DiffLineKind::Unchanged,
+        text: snapshot
+            .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+            .collect::<String>()
+            .into(),
+    };
+
+    let mut lines = Vec::new();
+    let mut row = 0;
+    for hunk in diff.hunks_in_row_range(0..u32::MAX, snapshot) {
+        lines.extend((row..hunk.associated_range.start).map(line));
+        lines.extend(
+            base_text[hunk.diff_base_byte_range.clone()]
+                .lines()
+                .map(|text| DiffLine {
+                    kind: DiffLineKind::Removed,
+                    text: text.to_string().into(),
+                }),
+        );
+        lines.extend(hunk.associated_range.clone().map(|row| DiffLine {
+            kind: DiffLineKind::Added,
+            ..line(row)
+        }));
+        row = hunk.associated_range.end;
+    }
+    lines.extend((row..=snapshot.max_point().row).map(line));
+    lines
+}
+
+fn format_size(len: usize) -> String {
+    if len < 1024 {
+        format!("{len} B")
+    } else if len < 1024 * 1024 {
+        format!("{:.1} KB", len as f64 / 1024.)
+    } else {
+        format!("{:.1} MB", len as f64 / (1024. * 1024.))
+    }
+}
+
+impl EventEmitter<ItemEvent> for LocalHistoryView {}
+
+impl FocusableView for LocalHistoryView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for LocalHistoryView {
+    type Event = ItemEvent;
+
+    fn tab_content(
+        &self,
+        _detail: Option<usize>,
+        selected: bool,
+        _cx: &WindowContext,
+    ) -> AnyElement {
+        h_flex()
+            .gap_2()
+            .child(Icon::new(IconName::FileGit).color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            }))
+            .child(
+                Label::new(format!("History: {}", self.file_name)).color(if selected {
+                    Color::Default
+                } else {
+                    Color::Muted
+                }),
+            )
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(self.abs_path.to_string_lossy().to_string().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("local history")
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for LocalHistoryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut key_context = KeyContext::default();
+        key_context.add("LocalHistory");
+        key_context.add("menu");
+
+        h_flex()
+            .key_context(key_context)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::restore))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_entries(cx))
+            .child(self.render_diff(cx))
+    }
+}
+
+#[cfg(test)]
+impl LocalHistoryView {
+    fn diff_text(&self) -> String {
+        self.diff
+            .iter()
+            .map(|line| {
+                let prefix = match line.kind {
+                    DiffLineKind::Unchanged => ' ',
+                    DiffLineKind::Added => '+',
+                    DiffLineKind::Removed => '-',
+                };
+                format!("{prefix}{}\n", line.text)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::Project;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_diffing_and_restoring_entries(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "one\ntwo\nthree" }))
+            .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let history = cx.new_model(|_| LocalHistory::new(fs.clone(), "/history".into()));
+        let abs_path = PathBuf::from("/root/a.txt");
+        history
+            .update(cx, |history, cx| {
+                history.record(abs_path.clone(), "one\nthree".into(), cx)
+            })
+            .await
+            .unwrap();
+
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(&abs_path, cx))
+            .await
+            .unwrap();
+        let (view, cx) = cx.add_window_view(|cx| {
+            LocalHistoryView::new(buffer.clone(), abs_path.clone(), history.clone(), cx)
+        });
+        cx.run_until_parked();
+        view.update(cx, |view, _| {
+            assert_eq!(view.entries.len(), 1);
+            assert_eq!(view.diff_text(), " one\n+two\n three\n");
+        });
+
+        // Newly recorded snapshots are listed first, without changing the selection.
+        history
+            .update(cx, |history, cx| {
+                history.record(abs_path.clone(), "one\ntwo\nfour".into(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        view.update(cx, |view, _| {
+            assert_eq!(view.entries.len(), 2);
+            assert_eq!(view.selected_index, 1);
+            assert_eq!(view.diff_text(), " one\n+two\n three\n");
+        });
+
+        view.update(cx, |view, cx| view.select_prev(&SelectPrev, cx));
+        cx.run_until_parked();
+        view.update(cx, |view, _| {
+            assert_eq!(view.diff_text(), " one\n two\n-four\n+three\n");
+        });
+
+        view.update(cx, |view, cx| view.select_next(&SelectNext, cx));
+        view.update(cx, |view, cx| view.restore(&Restore, cx));
+        cx.run_until_parked();
+        buffer.update(cx, |buffer, _| {
+            assert_eq!(buffer.text(), "one\nthree");
+            assert!(buffer.is_dirty());
+        });
+        view.update(cx, |view, _| {
+            assert_eq!(view.diff_text(), " one\n three\n");
+        });
+    }
+}
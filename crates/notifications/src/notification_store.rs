@@ -285,6 +285,9 @@ impl NotificationStore {
                     user_ids.push(sender_id);
                     message_ids.push(message_id);
                 }
+                Notification::CodeComment { sender_id, .. } => {
+                    user_ids.push(sender_id);
+                }
             }
         }
 
@@ -0,0 +1,293 @@
+//! A history of the contents of files saved in a local project, kept independently of version control.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use anyhow::{Context as _, Result};
+use fs::{Fs, RemoveOptions};
+use futures::lock::Mutex;
+use gpui::{AppContext, Context, EventEmitter, Global, Model, ModelContext, Task};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use text::Rope;
+use util::paths::LOCAL_HISTORY_DIR;
+
+/// How many snapshots are kept for each file. Older snapshots are discarded when new ones are recorded.
+const MAX_ENTRIES_PER_FILE: usize = 50;
+/// Files larger than this aren't recorded.
+const MAX_FILE_SIZE: usize = 8 * 1024 * 1024;
+
+/// A snapshot of a file's contents, recorded when the file was saved.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalHistoryEntry {
+    pub saved_at: SystemTime,
+    /// The SHA-256 digest of the file's contents, which are stored under that name.
+    pub digest: String,
+    pub len: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LocalHistoryEvent {
+    EntryAdded { abs_path: PathBuf },
+}
+
+/// Records a snapshot of every file saved in a local project.
+///
+/// Each file's history is stored in a directory named after the digest of its path, containing an index
+/// of its entries and one file per distinct snapshot, named after the digest of its contents.
+pub struct LocalHistory {
+    store: Arc<Store>,
+}
+
+struct GlobalLocalHistory(Model<LocalHistory>);
+
+impl Global for GlobalLocalHistory {}
+
+impl EventEmitter<LocalHistoryEvent> for LocalHistory {}
+
+impl LocalHistory {
+    pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+        let history = cx.new_model(|_| Self::new(fs, LOCAL_HISTORY_DIR.clone()));
+        cx.set_global(GlobalLocalHistory(history));
+    }
+
+    pub fn global(cx: &AppContext) -> Option<Model<Self>> {
+        cx.try_global::<GlobalLocalHistory>()
+            .map(|history| history.0.clone())
+    }
+
+    pub fn new(fs: Arc<dyn Fs>, dir: PathBuf) -> Self {
+        Self {
+            store: Arc::new(Store {
+                fs,
+                dir,
+                lock: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Records a snapshot of the file at `abs_path`, unless its latest snapshot has the same contents.
+    pub fn record(
+        &mut self,
+        abs_path: PathBuf,
+        text: Rope,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let store = self.store.clone();
+        cx.spawn(|this, mut cx| async move {
+            let added = cx
+                .background_executor()
+                .spawn({
+                    let abs_path = abs_path.clone();
+                    async move { store.record(&abs_path, text.to_string()).await }
+                })
+                .await?;
+            if added {
+                this.update(&mut cx, |_, cx| {
+                    cx.emit(LocalHistoryEvent::EntryAdded { abs_path })
+                })?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the snapshots recorded for the file at `abs_path`, newest first.
+    pub fn entries(
+        &self,
+        abs_path: &Path,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<LocalHistoryEntry>>> {
+        let store = self.store.clone();
+        let abs_path = abs_path.to_path_buf();
+        cx.background_executor().spawn(async move {
+            let mut entries = store.load_index(&abs_path).await?.entries;
+            entries.reverse();
+            Ok(entries)
+        })
+    }
+
+    /// Loads the contents of one of the file's snapshots.
+    pub fn load(
+        &self,
+        abs_path: &Path,
+        entry: &LocalHistoryEntry,
+        cx: &AppContext,
+    ) -> Task<Result<String>> {
+        let store = self.store.clone();
+        let path = store.file_dir(abs_path).join(&entry.digest);
+        cx.background_executor().spawn(async move {
+            store
+                .fs
+                .load(&path)
+                .await
+                .with_context(|| format!("loading local history entry {path:?}"))
+        })
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Index {
+    path: PathBuf,
+    entries: Vec<LocalHistoryEntry>,
+}
+
+struct Store {
+    fs: Arc<dyn Fs>,
+    dir: PathBuf,
+    /// Serializes updates to the indices.
+    lock: Mutex<()>,
+}
+
+impl Store {
+    fn file_dir(&self, abs_path: &Path) -> PathBuf {
+        self.dir.join(digest(abs_path.to_string_lossy().as_bytes()))
+    }
+
+    async fn load_index(&self, abs_path: &Path) -> Result<Index> {
+        let index_path = self.file_dir(abs_path).join("index.json");
+        if !self.fs.is_file(&index_path).await {
+            return Ok(Index::default());
+        }
+        let index = self.fs.load(&index_path).await?;
+        serde_json::from_str(&index).with_context(|| format!("parsing {index_path:?}"))
+    }
+
+    async fn record(&self, abs_path: &Path, text: String) -> Result<bool> {
+        if text.len() > MAX_FILE_SIZE {
+            return Ok(false);
+        }
+
+        let _lock = self.lock.lock().await;
+        let mut index = self.load_index(abs_path).await?;
+        let digest = digest(text.as_bytes());
+        if index
+            .entries
+            .last()
+            .map_or(false, |entry| entry.digest == digest)
+        {
+            return Ok(false);
+        }
+
+        let dir = self.file_dir(abs_path);
+        self.fs.create_dir(&dir).await?;
+        let content_path = dir.join(&digest);
+        if !self.fs.is_file(&content_path).await {
+            self.fs.atomic_write(content_path, text.clone()).await?;
+        }
+
+        index.path = abs_path.to_path_buf();
+        index.entries.push(LocalHistoryEntry {
+            saved_at: SystemTime::now(),
+            digest,
+            len: text.len(),
+        });
+        let removed_count = index.entries.len().saturating_sub(MAX_ENTRIES_PER_FILE);
+        let removed_entries = index.entries.drain(..removed_count).collect::<Vec<_>>();
+        self.fs
+            .atomic_write(dir.join("index.json"), serde_json::to_string(&index)?)
+            .await?;
+
+        // Snapshots are shared by all entries with the same contents.
+        for removed_entry in removed_entries {
+            if index
+                .entries
+                .iter()
+                .all(|entry| entry.digest != removed_entry.digest)
+            {
+                self.fs
+                    .remove_file(
+                        &dir.join(&removed_entry.digest),
+                        RemoveOptions {
+                            recursive: false,
+                            ignore_if_not_exists: true,
+                        },
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+fn digest(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_recording_entries(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let history = cx.new_model(|_| LocalHistory::new(fs.clone(), "/history".into()));
+        let path = Path::new("/project/a.txt");
+
+        let entries =
+            |cx: &mut TestAppContext| history.update(cx, |history, cx| history.entries(path, cx));
+        assert!(entries(cx).await.unwrap().is_empty());
+
+        for text in ["one", "two", "two", "one"] {
+            history
+                .update(cx, |history, cx| {
+                    history.record(path.to_path_buf(), text.into(), cx)
+                })
+                .await
+                .unwrap();
+        }
+
+        // Consecutive saves of the same contents are deduplicated.
+        let entries = entries(cx).await.unwrap();
+        let mut contents = Vec::new();
+        for entry in &entries {
+            let text = history
+                .update(cx, |history, cx| history.load(path, entry, cx))
+                .await
+                .unwrap();
+            contents.push(text);
+        }
+        assert_eq!(contents, ["one", "two", "one"]);
+        assert_eq!(entries[0].digest, entries[2].digest);
+    }
+
+    #[gpui::test]
+    async fn test_capping_entries(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let history = cx.new_model(|_| LocalHistory::new(fs.clone(), "/history".into()));
+        let path = Path::new("/project/a.txt");
+
+        for i in 0..MAX_ENTRIES_PER_FILE + 2 {
+            history
+                .update(cx, |history, cx| {
+                    history.record(path.to_path_buf(), i.to_string().as_str().into(), cx)
+                })
+                .await
+                .unwrap();
+        }
+
+        let entries = history
+            .update(cx, |history, cx| history.entries(path, cx))
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES_PER_FILE);
+        let oldest = history
+            .update(cx, |history, cx| {
+                history.load(path, entries.last().unwrap(), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(oldest, "2");
+
+        // The contents of discarded entries are removed.
+        let file_count = fs.files().len();
+        assert_eq!(file_count, MAX_ENTRIES_PER_FILE + 1);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod debounced_delay;
+pub mod local_history;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
@@ -101,6 +102,7 @@ use worktree::{Snapshot, Traversal};
 
 pub use fs::*;
 pub use language::Location;
+pub use local_history::{LocalHistory, LocalHistoryEntry, LocalHistoryEvent};
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 #[cfg(feature = "test-support")]
@@ -310,6 +312,14 @@ pub enum Event {
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
+    /// The host of this remote project shared a terminal with us.
+    TerminalShared(Model<terminal::Terminal>),
+    /// A collaborator asked to type in one of our shared terminals, which is answered through
+    /// [`Project::respond_to_terminal_control_request`].
+    TerminalControlRequested {
+        terminal: Model<terminal::Terminal>,
+        peer_id: proto::PeerId,
+    },
 }
 
 pub enum LanguageServerState {
@@ -576,6 +586,11 @@ impl Project {
         client.add_model_request_handler(Self::handle_open_buffer_by_path);
         client.add_model_request_handler(Self::handle_save_buffer);
         client.add_model_message_handler(Self::handle_update_diff_base);
+        client.add_model_message_handler(Self::handle_share_terminal);
+        client.add_model_message_handler(Self::handle_update_terminal);
+        client.add_model_message_handler(Self::handle_unshare_terminal);
+        client.add_model_request_handler(Self::handle_terminal_input);
+        client.add_model_request_handler(Self::handle_request_terminal_control);
         client.add_model_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
     }
 
@@ -641,6 +656,9 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    remote: None,
+                    shared: HashMap::default(),
+                    joined: HashMap::default(),
                 },
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
@@ -776,6 +794,9 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    remote: None,
+                    shared: HashMap::default(),
+                    joined: HashMap::default(),
                 },
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
@@ -1235,23 +1256,28 @@ impl Project {
         }
     }
 
+    /// Copies an entry to the given path, in the worktree with the given id or, if there's none,
+    /// in the same worktree.
     pub fn copy_entry(
         &mut self,
         entry_id: ProjectEntryId,
+        new_worktree_id: Option<WorktreeId>,
         new_path: impl Into<Arc<Path>>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Option<Entry>>> {
         let Some(worktree) = self.worktree_for_entry(entry_id, cx) else {
             return Task::ready(Ok(None));
         };
+        let new_worktree = match new_worktree_id {
+            Some(new_worktree_id) => match self.worktree_for_id(new_worktree_id, cx) {
+                Some(new_worktree) => new_worktree,
+                None => return Task::ready(Ok(None)),
+            },
+            None => worktree.clone(),
+        };
         let new_path = new_path.into();
         if self.is_local() {
-            worktree.update(cx, |worktree, cx| {
-                worktree
-                    .as_local_mut()
-                    .unwrap()
-                    .copy_entry(entry_id, new_path, cx)
-            })
+            copy_local_entry(&worktree, entry_id, &new_worktree, new_path, cx)
         } else {
             let client = self.client.clone();
             let project_id = self.remote_id().unwrap();
@@ -1262,10 +1288,11 @@ impl Project {
                         project_id,
                         entry_id: entry_id.to_proto(),
                         new_path: new_path.to_string_lossy().into(),
+                        new_worktree_id: new_worktree_id.map(|id| id.to_proto()),
                     })
                     .await?;
                 match response.entry {
-                    Some(entry) => worktree
+                    Some(entry) => new_worktree
                         .update(&mut cx, |worktree, cx| {
                             worktree.as_remote_mut().unwrap().insert_entry(
                                 entry,
@@ -1617,6 +1644,7 @@ impl Project {
             self.client_state = ProjectClientState::Local;
             self.collaborators.clear();
             self.shared_buffers.clear();
+            self.terminals.shared.clear();
             self.client_subscriptions.clear();
 
             for worktree_handle in self.worktrees.iter_mut() {
@@ -1686,6 +1714,7 @@ impl Project {
             *sharing_has_stopped = true;
 
             self.collaborators.clear();
+            self.terminals.joined.clear();
 
             for worktree in &self.worktrees {
                 if let Some(worktree) = worktree.upgrade() {
@@ -2006,9 +2035,28 @@ impl Project {
         };
         let worktree = file.worktree.clone();
         let path = file.path.clone();
-        worktree.update(cx, |worktree, cx| match worktree {
+        let history_entry = LocalHistory::global(cx)
+            .filter(|_| file.is_local())
+            .map(|history| {
+                (
+                    history,
+                    file.abs_path(cx),
+                    buffer.read(cx).as_rope().clone(),
+                )
+            });
+        let save = worktree.update(cx, |worktree, cx| match worktree {
             Worktree::Local(worktree) => worktree.save_buffer(buffer, path, false, cx),
             Worktree::Remote(worktree) => worktree.save_buffer(buffer, cx),
+        });
+        cx.spawn(|_, mut cx| async move {
+            save.await?;
+            if let Some((history, abs_path, text)) = history_entry {
+                history
+                    .update(&mut cx, |history, cx| history.record(abs_path, text, cx))?
+                    .await
+                    .log_err();
+            }
+            Ok(())
         })
     }
 
@@ -2022,6 +2070,8 @@ impl Project {
         let old_file = File::from_dyn(buffer.read(cx).file())
             .filter(|f| f.is_local())
             .cloned();
+        let history = LocalHistory::global(cx);
+        let text = buffer.read(cx).as_rope().clone();
         cx.spawn(move |this, mut cx| async move {
             if let Some(old_file) = &old_file {
                 this.update(&mut cx, |this, cx| {
@@ -2037,6 +2087,12 @@ impl Project {
                     Worktree::Remote(_) => panic!("cannot remote buffers as new files"),
                 })?
                 .await?;
+            if let Some(history) = history {
+                history
+                    .update(&mut cx, |history, cx| history.record(abs_path, text, cx))?
+                    .await
+                    .log_err();
+            }
 
             this.update(&mut cx, |this, cx| {
                 this.detect_language_for_buffer(&buffer, cx);
@@ -7302,6 +7358,7 @@ impl Project {
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
+            this.resend_shared_terminals(cx);
             cx.notify();
         })?;
 
@@ -7379,6 +7436,9 @@ impl Project {
                 }
             }
             this.shared_buffers.remove(&peer_id);
+            for shared_terminal in this.terminals.shared.values_mut() {
+                shared_terminal.remove_peer(&peer_id);
+            }
 
             cx.emit(Event::CollaboratorLeft(peer_id));
             cx.notify();
@@ -7502,18 +7562,23 @@ impl Project {
         mut cx: AsyncAppContext,
     ) -> Result<proto::ProjectEntryResponse> {
         let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
-        let worktree = this.update(&mut cx, |this, cx| {
-            this.worktree_for_entry(entry_id, cx)
-                .ok_or_else(|| anyhow!("worktree not found"))
+        let (worktree, new_worktree) = this.update(&mut cx, |this, cx| {
+            let worktree = this
+                .worktree_for_entry(entry_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))?;
+            let new_worktree = match envelope.payload.new_worktree_id {
+                Some(new_worktree_id) => this
+                    .worktree_for_id(WorktreeId::from_proto(new_worktree_id), cx)
+                    .ok_or_else(|| anyhow!("worktree not found"))?,
+                None => worktree.clone(),
+            };
+            anyhow::Ok((worktree, new_worktree))
         })??;
-        let worktree_scan_id = worktree.update(&mut cx, |worktree, _| worktree.scan_id())?;
-        let entry = worktree
-            .update(&mut cx, |worktree, cx| {
+        let worktree_scan_id = new_worktree.update(&mut cx, |worktree, _| worktree.scan_id())?;
+        let entry = cx
+            .update(|cx| {
                 let new_path = PathBuf::from(envelope.payload.new_path);
-                worktree
-                    .as_local_mut()
-                    .unwrap()
-                    .copy_entry(entry_id, new_path, cx)
+                copy_local_entry(&worktree, entry_id, &new_worktree, new_path.into(), cx)
             })?
             .await?;
         Ok(proto::ProjectEntryResponse {
@@ -9534,6 +9599,36 @@ impl LspAdapterDelegate for ProjectLspAdapterDelegate {
     }
 }
 
+/// Copies an entry of a local worktree, either within that worktree or into another one.
+fn copy_local_entry(
+    worktree: &Model<Worktree>,
+    entry_id: ProjectEntryId,
+    new_worktree: &Model<Worktree>,
+    new_path: Arc<Path>,
+    cx: &mut AppContext,
+) -> Task<Result<Option<Entry>>> {
+    if worktree == new_worktree {
+        return worktree.update(cx, |worktree, cx| {
+            worktree
+                .as_local_mut()
+                .unwrap()
+                .copy_entry(entry_id, new_path, cx)
+        });
+    }
+
+    let worktree = worktree.read(cx);
+    let Some(entry) = worktree.entry_for_id(entry_id) else {
+        return Task::ready(Ok(None));
+    };
+    let abs_old_path = worktree.abs_path().join(&entry.path);
+    new_worktree.update(cx, |new_worktree, cx| {
+        new_worktree
+            .as_local_mut()
+            .unwrap()
+            .copy_external_entry(abs_old_path, new_path, cx)
+    })
+}
+
 fn serialize_symbol(symbol: &Symbol) -> proto::Symbol {
     proto::Symbol {
         language_server_name: symbol.language_server_name.0.to_string(),
@@ -3215,6 +3215,47 @@ async fn test_buffer_is_dirty(cx: &mut gpui::TestAppContext) {
     cx.update(|cx| assert!(buffer3.read(cx).is_dirty()));
 }
 
+#[gpui::test]
+async fn test_restoring_unsaved_changes(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "file1": "abc", "file2": "def" }))
+        .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    // Changes made to the file as it is on disk are unsaved, but not conflicted.
+    let buffer1 = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/file1", cx))
+        .await
+        .unwrap();
+    buffer1.update(cx, |buffer, cx| {
+        let saved_mtime = buffer.saved_mtime();
+        buffer.restore_unsaved_changes("abcd".into(), saved_mtime, cx);
+        assert_eq!(buffer.text(), "abcd");
+        assert!(buffer.is_dirty());
+        assert!(!buffer.has_conflict());
+    });
+
+    // Changes made to an older version of the file conflict with it.
+    let buffer2 = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/file2", cx))
+        .await
+        .unwrap();
+    let old_mtime = buffer2.update(cx, |buffer, _| buffer.saved_mtime());
+    fs.save("/dir/file2".as_ref(), &"DEF".into(), LineEnding::Unix)
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    buffer2.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "DEF");
+        buffer.restore_unsaved_changes("defg".into(), old_mtime, cx);
+        assert_eq!(buffer.text(), "defg");
+        assert!(buffer.is_dirty());
+        assert!(buffer.has_conflict());
+    });
+}
+
 #[gpui::test]
 async fn test_buffer_file_changes_on_disk(cx: &mut gpui::TestAppContext) {
     init_test(cx);
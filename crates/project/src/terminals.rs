@@ -1,21 +1,85 @@
-use crate::Project;
-use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, WeakModel};
+use crate::{Event, Project, ProjectClientState};
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, Client, TypedEnvelope};
+use collections::{HashMap, HashSet};
+use futures::{
+    channel::{mpsc, oneshot},
+    StreamExt,
+};
+use gpui::{
+    AnyWindowHandle, AsyncAppContext, Context, Entity, Model, ModelContext, Subscription, Task,
+    WeakModel,
+};
 use settings::Settings;
 use smol::channel::bounded;
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use terminal::{
     terminal_settings::{self, Shell, TerminalSettings, VenvSettingsContent},
     SpawnTask, TaskState, Terminal, TerminalBuilder,
 };
+use util::{shell_quote, ResultExt};
+
+/// How often a shared terminal's changes are sent to collaborators.
+pub const SHARED_TERMINAL_UPDATE_THROTTLE: Duration = Duration::from_millis(50);
 
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    pub(crate) remote: Option<RemoteTerminals>,
+    /// Terminals the host shares with collaborators, by their id.
+    pub(crate) shared: HashMap<u64, SharedTerminal>,
+    /// Terminals shared by the host of a remote project, by their id on the host.
+    pub(crate) joined: HashMap<u64, JoinedTerminal>,
+}
+
+pub(crate) struct SharedTerminal {
+    terminal: WeakModel<Terminal>,
+    /// What collaborators last received, which the next update is diffed against.
+    sent_rows: Vec<String>,
+    sent_title: String,
+    sent_cursor: (usize, usize),
+    approved_peers: HashSet<proto::PeerId>,
+    pending_control_requests: HashMap<proto::PeerId, oneshot::Sender<bool>>,
+    pending_update: Option<Task<()>>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl SharedTerminal {
+    pub(crate) fn remove_peer(&mut self, peer_id: &proto::PeerId) {
+        self.approved_peers.remove(peer_id);
+        self.pending_control_requests.remove(peer_id);
+    }
+}
+
+pub(crate) struct JoinedTerminal {
+    terminal: WeakModel<Terminal>,
+    has_control: bool,
+    _forward_input: Task<Result<()>>,
+}
+
+/// How terminals are spawned for a project hosted by a remote development server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RemoteTerminals {
+    /// The server runs on this machine, so terminals are spawned locally.
+    Local,
+    /// Terminals are spawned on the server's machine by running `ssh` with these arguments,
+    /// which end with the destination.
+    Ssh { args: Vec<String> },
 }
 
 impl Project {
+    /// Allows terminals to be created for this remote project, spawning them on the machine that
+    /// hosts its files.
+    pub fn set_remote_terminals(&mut self, remote_terminals: Option<RemoteTerminals>) {
+        self.terminals.remote = remote_terminals;
+    }
+
     pub fn create_terminal(
         &mut self,
         working_directory: Option<PathBuf>,
@@ -23,8 +87,31 @@ impl Project {
         window: AnyWindowHandle,
         cx: &mut ModelContext<Self>,
     ) -> anyhow::Result<Model<Terminal>> {
+        self.create_terminal_internal(working_directory, spawn_task, None, window, cx)
+    }
+
+    /// Creates a terminal that shows the scrollback of a previous session above its new shell.
+    pub fn restore_terminal(
+        &mut self,
+        working_directory: Option<PathBuf>,
+        scrollback: Option<String>,
+        window: AnyWindowHandle,
+        cx: &mut ModelContext<Self>,
+    ) -> anyhow::Result<Model<Terminal>> {
+        self.create_terminal_internal(working_directory, None, scrollback, window, cx)
+    }
+
+    fn create_terminal_internal(
+        &mut self,
+        working_directory: Option<PathBuf>,
+        spawn_task: Option<SpawnTask>,
+        restored_scrollback: Option<String>,
+        window: AnyWindowHandle,
+        cx: &mut ModelContext<Self>,
+    ) -> anyhow::Result<Model<Terminal>> {
+        let remote_terminals = self.terminals.remote.clone();
         anyhow::ensure!(
-            !self.is_remote(),
+            !self.is_remote() || remote_terminals.is_some(),
             "creating terminals as a guest is not supported yet"
         );
 
@@ -49,15 +136,23 @@ impl Project {
         } else {
             (None, settings.shell.clone())
         };
+        let (local_working_directory, shell) = match &remote_terminals {
+            Some(RemoteTerminals::Ssh { args }) => {
+                (None, ssh_shell(args, working_directory.as_deref(), shell))
+            }
+            Some(RemoteTerminals::Local) | None => (working_directory.clone(), shell),
+        };
 
         let terminal = TerminalBuilder::new(
-            working_directory.clone(),
+            local_working_directory.clone(),
             spawn_task,
             shell,
             env,
             Some(settings.blinking.clone()),
             settings.alternate_scroll,
             settings.max_scroll_history_lines,
+            settings.shell_integration,
+            restored_scrollback,
             window,
             completion_tx,
         )
@@ -82,10 +177,13 @@ impl Project {
             })
             .detach();
 
-            if let Some(python_settings) = &python_settings.as_option() {
+            if let Some(python_settings) = &python_settings
+                .as_option()
+                .filter(|_| !matches!(remote_terminals, Some(RemoteTerminals::Ssh { .. })))
+            {
                 let activate_command = Project::get_activate_command(python_settings);
                 let activate_script_path =
-                    self.find_activate_script_path(python_settings, working_directory);
+                    self.find_activate_script_path(python_settings, local_working_directory);
                 self.activate_python_virtual_environment(
                     activate_command,
                     activate_script_path,
@@ -159,6 +257,497 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakModel<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Shows a terminal to the collaborators in this shared project. They can type in it once
+    /// the host approves their [`Project::request_terminal_control`].
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let ProjectClientState::Shared { remote_id, .. } = self.client_state else {
+            return Err(anyhow!("terminals can only be shared in a shared project"));
+        };
+        anyhow::ensure!(
+            !terminal.read(cx).is_remote(),
+            "a terminal shared by the host can't be shared again"
+        );
+
+        let terminal_id = terminal.entity_id().as_u64();
+        if self.terminals.shared.contains_key(&terminal_id) {
+            return Ok(());
+        }
+
+        let subscriptions = [
+            cx.subscribe(terminal, move |this, _, event, cx| match event {
+                terminal::Event::Wakeup | terminal::Event::TitleChanged => {
+                    this.schedule_terminal_update(terminal_id, cx)
+                }
+                terminal::Event::CloseTerminal => this.unshare_terminal_internal(terminal_id, cx),
+                _ => {}
+            }),
+            cx.observe_release(terminal, move |this, _, cx| {
+                this.unshare_terminal_internal(terminal_id, cx)
+            }),
+        ];
+        self.terminals.shared.insert(
+            terminal_id,
+            SharedTerminal {
+                terminal: terminal.downgrade(),
+                sent_rows: Vec::new(),
+                sent_title: String::new(),
+                sent_cursor: (0, 0),
+                approved_peers: HashSet::default(),
+                pending_control_requests: HashMap::default(),
+                pending_update: None,
+                _subscriptions: subscriptions,
+            },
+        );
+        self.send_shared_terminal(remote_id, terminal_id, cx)?;
+        cx.notify();
+        Ok(())
+    }
+
+    /// Stops showing a terminal to collaborators, closing it for them.
+    pub fn unshare_terminal(&mut self, terminal: &Model<Terminal>, cx: &mut ModelContext<Self>) {
+        self.unshare_terminal_internal(terminal.entity_id().as_u64(), cx);
+    }
+
+    pub fn is_terminal_shared(&self, terminal: &Model<Terminal>) -> bool {
+        self.terminals
+            .shared
+            .contains_key(&terminal.entity_id().as_u64())
+    }
+
+    /// The id collaborators know a terminal by, if it's shared by us or by the host.
+    pub fn shared_terminal_id(&self, terminal: &Model<Terminal>) -> Option<u64> {
+        if self.is_terminal_shared(terminal) {
+            Some(terminal.entity_id().as_u64())
+        } else {
+            self.joined_terminal_id(terminal)
+        }
+    }
+
+    /// The terminal that collaborators know by the given [`Project::shared_terminal_id`].
+    pub fn shared_terminal(&self, terminal_id: u64) -> Option<Model<Terminal>> {
+        let terminal = match self.terminals.shared.get(&terminal_id) {
+            Some(shared) => &shared.terminal,
+            None => &self.terminals.joined.get(&terminal_id)?.terminal,
+        };
+        terminal.upgrade()
+    }
+
+    /// Answers a collaborator's request to type in a shared terminal, which is reported by
+    /// [`Event::TerminalControlRequested`].
+    pub fn respond_to_terminal_control_request(
+        &mut self,
+        terminal: &Model<Terminal>,
+        peer_id: proto::PeerId,
+        allow: bool,
+    ) {
+        let Some(shared) = self
+            .terminals
+            .shared
+            .get_mut(&terminal.entity_id().as_u64())
+        else {
+            return;
+        };
+        if let Some(response_tx) = shared.pending_control_requests.remove(&peer_id) {
+            if allow {
+                shared.approved_peers.insert(peer_id);
+            }
+            response_tx.send(allow).ok();
+        }
+    }
+
+    /// Asks the host for permission to type in a terminal they shared, resolving to whether
+    /// they agreed. Input is dropped until they do.
+    pub fn request_terminal_control(
+        &mut self,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<bool>> {
+        let Some(project_id) = self.remote_id().filter(|_| self.is_remote()) else {
+            return Task::ready(Err(anyhow!("not a remote project")));
+        };
+        let Some(terminal_id) = self.joined_terminal_id(terminal) else {
+            return Task::ready(Err(anyhow!("terminal isn't shared by the host")));
+        };
+
+        let request = self.client.request(proto::RequestTerminalControl {
+            project_id,
+            terminal_id,
+        });
+        cx.spawn(move |this, mut cx| async move {
+            let granted = request.await?.granted;
+            this.update(&mut cx, |this, cx| {
+                if let Some(joined) = this.terminals.joined.get_mut(&terminal_id) {
+                    joined.has_control = granted;
+                    cx.notify();
+                }
+            })?;
+            Ok(granted)
+        })
+    }
+
+    /// Whether the host of this project allowed us to type in a terminal they shared.
+    pub fn has_terminal_control(&self, terminal: &Model<Terminal>) -> bool {
+        self.joined_terminal_id(terminal)
+            .and_then(|terminal_id| self.terminals.joined.get(&terminal_id))
+            .map_or(false, |joined| joined.has_control)
+    }
+
+    fn joined_terminal_id(&self, terminal: &Model<Terminal>) -> Option<u64> {
+        self.terminals
+            .joined
+            .iter()
+            .find(|(_, joined)| joined.terminal.entity_id() == terminal.entity_id())
+            .map(|(terminal_id, _)| *terminal_id)
+    }
+
+    fn unshare_terminal_internal(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        if self.terminals.shared.remove(&terminal_id).is_none() {
+            return;
+        }
+        if let ProjectClientState::Shared { remote_id, .. } = self.client_state {
+            self.client
+                .send(proto::UnshareTerminal {
+                    project_id: remote_id,
+                    terminal_id,
+                })
+                .log_err();
+        }
+        cx.notify();
+    }
+
+    /// Sends all shared terminals again, so that collaborators who just joined can see them.
+    pub(crate) fn resend_shared_terminals(&mut self, cx: &mut ModelContext<Self>) {
+        if let ProjectClientState::Shared { remote_id, .. } = self.client_state {
+            let terminal_ids = self.terminals.shared.keys().copied().collect::<Vec<_>>();
+            for terminal_id in terminal_ids {
+                self.send_shared_terminal(remote_id, terminal_id, cx)
+                    .log_err();
+            }
+        }
+    }
+
+    fn send_shared_terminal(
+        &mut self,
+        project_id: u64,
+        terminal_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let shared = self
+            .terminals
+            .shared
+            .get(&terminal_id)
+            .context("terminal isn't shared")?;
+        let terminal = shared.terminal.upgrade().context("terminal was dropped")?;
+        self.client.send(proto::ShareTerminal {
+            project_id,
+            terminal_id,
+            title: terminal.read(cx).title(false),
+        })?;
+        self.send_terminal_update(project_id, terminal_id, true, cx)
+    }
+
+    fn schedule_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(shared) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        if shared.pending_update.is_some() {
+            return;
+        }
+
+        shared.pending_update = Some(cx.spawn(move |this, mut cx| async move {
+            cx.background_executor()
+                .timer(SHARED_TERMINAL_UPDATE_THROTTLE)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                let Some(shared) = this.terminals.shared.get_mut(&terminal_id) else {
+                    return;
+                };
+                shared.pending_update.take();
+                if let Some(project_id) = this.remote_id() {
+                    this.send_terminal_update(project_id, terminal_id, false, cx)
+                        .log_err();
+                }
+            })
+            .ok();
+        }));
+    }
+
+    /// Sends the rows of a shared terminal that changed since the last update, or all of them
+    /// with `replace_screen`.
+    fn send_terminal_update(
+        &mut self,
+        project_id: u64,
+        terminal_id: u64,
+        replace_screen: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let shared = self
+            .terminals
+            .shared
+            .get_mut(&terminal_id)
+            .context("terminal isn't shared")?;
+        let terminal = shared.terminal.upgrade().context("terminal was dropped")?;
+        let terminal = terminal.read(cx);
+        let rows = terminal.screen_rows();
+        let title = terminal.title(false);
+        let cursor = terminal.cursor_position();
+
+        // When the terminal is resized, its rows no longer line up with the ones sent before.
+        let replace_screen = replace_screen || rows.len() != shared.sent_rows.len();
+        let changed_rows = rows
+            .iter()
+            .enumerate()
+            .filter(|(line, row)| {
+                if replace_screen {
+                    !row.is_empty()
+                } else {
+                    shared.sent_rows.get(*line) != Some(row)
+                }
+            })
+            .map(|(line, row)| proto::TerminalRow {
+                line: line as u32,
+                content: row.clone(),
+            })
+            .collect::<Vec<_>>();
+        if !replace_screen
+            && changed_rows.is_empty()
+            && title == shared.sent_title
+            && cursor == shared.sent_cursor
+        {
+            return Ok(());
+        }
+
+        self.client.send(proto::UpdateTerminal {
+            project_id,
+            terminal_id,
+            title: title.clone(),
+            rows: changed_rows,
+            replace_screen,
+            cursor_line: cursor.0 as u32,
+            cursor_column: cursor.1 as u32,
+        })?;
+        shared.sent_rows = rows;
+        shared.sent_title = title;
+        shared.sent_cursor = cursor;
+        Ok(())
+    }
+
+    pub(crate) async fn handle_share_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ShareTerminal>,
+        client: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let sender_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            anyhow::ensure!(
+                this.host().map(|host| host.peer_id) == Some(sender_id),
+                "only the host can share terminals"
+            );
+            let terminal_id = envelope.payload.terminal_id;
+            if let Some(joined) = this.terminals.joined.get(&terminal_id) {
+                if joined.terminal.upgrade().is_some() {
+                    return Ok(());
+                }
+            }
+
+            let project_id = envelope.payload.project_id;
+            let (input_tx, mut input_rx) = mpsc::unbounded();
+            let terminal = cx.new_model(|cx| TerminalBuilder::new_remote(input_tx).subscribe(cx));
+            terminal.update(cx, |terminal, cx| {
+                terminal.set_mirrored_rows(envelope.payload.title, [], true, (0, 0), cx)
+            });
+            let forward_input = cx.spawn(move |this, mut cx| async move {
+                while let Some(input) = input_rx.next().await {
+                    let has_control = this.update(&mut cx, |this, _| {
+                        this.terminals
+                            .joined
+                            .get(&terminal_id)
+                            .map_or(false, |joined| joined.has_control)
+                    })?;
+                    if has_control {
+                        client
+                            .request(proto::TerminalInput {
+                                project_id,
+                                terminal_id,
+                                input,
+                            })
+                            .await
+                            .log_err();
+                    }
+                }
+                anyhow::Ok(())
+            });
+            this.terminals.joined.insert(
+                terminal_id,
+                JoinedTerminal {
+                    terminal: terminal.downgrade(),
+                    has_control: false,
+                    _forward_input: forward_input,
+                },
+            );
+            cx.emit(Event::TerminalShared(terminal));
+            Ok(())
+        })?
+    }
+
+    pub(crate) async fn handle_update_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateTerminal>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let sender_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            anyhow::ensure!(
+                this.host().map(|host| host.peer_id) == Some(sender_id),
+                "only the host can update terminals"
+            );
+            let update = envelope.payload;
+            let Some(terminal) = this
+                .terminals
+                .joined
+                .get(&update.terminal_id)
+                .and_then(|joined| joined.terminal.upgrade())
+            else {
+                return Ok(());
+            };
+            terminal.update(cx, |terminal, cx| {
+                terminal.set_mirrored_rows(
+                    update.title,
+                    update
+                        .rows
+                        .iter()
+                        .map(|row| (row.line as usize, row.content.as_str())),
+                    update.replace_screen,
+                    (update.cursor_line as usize, update.cursor_column as usize),
+                    cx,
+                )
+            });
+            Ok(())
+        })?
+    }
+
+    pub(crate) async fn handle_unshare_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UnshareTerminal>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let sender_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            anyhow::ensure!(
+                this.host().map(|host| host.peer_id) == Some(sender_id),
+                "only the host can unshare terminals"
+            );
+            if let Some(terminal) = this
+                .terminals
+                .joined
+                .remove(&envelope.payload.terminal_id)
+                .and_then(|joined| joined.terminal.upgrade())
+            {
+                terminal.update(cx, |_, cx| cx.emit(terminal::Event::CloseTerminal));
+            }
+            Ok(())
+        })?
+    }
+
+    pub(crate) async fn handle_terminal_input(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::TerminalInput>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let sender_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            let shared = this
+                .terminals
+                .shared
+                .get(&envelope.payload.terminal_id)
+                .context("terminal isn't shared")?;
+            anyhow::ensure!(
+                shared.approved_peers.contains(&sender_id),
+                "the host hasn't allowed this collaborator to type in the terminal"
+            );
+            let terminal = shared.terminal.upgrade().context("terminal was dropped")?;
+            terminal.update(cx, |terminal, _| {
+                terminal.input_bytes(envelope.payload.input)
+            });
+            Ok(proto::Ack {})
+        })?
+    }
+
+    pub(crate) async fn handle_request_terminal_control(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::RequestTerminalControl>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::RequestTerminalControlResponse> {
+        let sender_id = envelope.original_sender_id()?;
+        let response_rx = this.update(&mut cx, |this, cx| {
+            let shared = this
+                .terminals
+                .shared
+                .get_mut(&envelope.payload.terminal_id)
+                .context("terminal isn't shared")?;
+            if shared.approved_peers.contains(&sender_id) {
+                return Ok(None);
+            }
+            let terminal = shared.terminal.upgrade().context("terminal was dropped")?;
+
+            let (response_tx, response_rx) = oneshot::channel();
+            shared
+                .pending_control_requests
+                .insert(sender_id, response_tx);
+            cx.emit(Event::TerminalControlRequested {
+                terminal,
+                peer_id: sender_id,
+            });
+            anyhow::Ok(Some(response_rx))
+        })??;
+
+        let granted = match response_rx {
+            Some(response_rx) => response_rx.await.unwrap_or(false),
+            None => true,
+        };
+        Ok(proto::RequestTerminalControlResponse { granted })
+    }
+}
+
+/// Wraps the shell in an `ssh` invocation that starts it in the given directory on the remote
+/// machine.
+fn ssh_shell(ssh_args: &[String], working_directory: Option<&Path>, shell: Shell) -> Shell {
+    let mut command = String::new();
+    if let Some(working_directory) = working_directory {
+        command.push_str("cd ");
+        command.push_str(&shell_quote(&working_directory.to_string_lossy()));
+        command.push_str(" && ");
+    }
+    command.push_str("exec ");
+    match shell {
+        Shell::System => command.push_str("$SHELL -l"),
+        Shell::Program(program) => command.push_str(&shell_quote(&program)),
+        Shell::WithArguments { program, args } => {
+            command.push_str(&shell_quote(&program));
+            for arg in args {
+                command.push(' ');
+                command.push_str(&shell_quote(&arg));
+            }
+        }
+    }
+
+    let mut args = vec!["-t".to_string()];
+    args.extend(ssh_args.iter().cloned());
+    args.push(command);
+    Shell::WithArguments {
+        program: "ssh".to_string(),
+        args,
+    }
 }
 
 // TODO: Add a few tests for adding and removing terminal tabs
@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use collections::HashMap;
+
+const CAPTURE: &str = "${capture}";
+
+/// Determines which of the files in a directory are nested under another one, given the
+/// nesting patterns from the settings. Returns, for each file name, the index of the
+/// file it's shown under. Files that are nested under a file which is itself nested are
+/// shown under the outermost one.
+pub(crate) fn nest_files(
+    names: &[&str],
+    patterns: &BTreeMap<String, Vec<String>>,
+) -> Vec<Option<usize>> {
+    let indices_by_name = names
+        .iter()
+        .enumerate()
+        .map(|(ix, name)| (*name, ix))
+        .collect::<HashMap<_, _>>();
+    let mut parents = vec![None; names.len()];
+
+    for (parent_ix, parent_name) in names.iter().enumerate() {
+        for (parent_pattern, child_patterns) in patterns {
+            let Some(capture) = capture(parent_pattern, parent_name) else {
+                continue;
+            };
+            for child_pattern in child_patterns {
+                let child_pattern = child_pattern.replace(CAPTURE, capture);
+                let mut nest = |child_ix: usize| {
+                    if child_ix != parent_ix
+                        && parents[child_ix].is_none()
+                        && !is_nested_under(&parents, parent_ix, child_ix)
+                    {
+                        parents[child_ix] = Some(parent_ix);
+                    }
+                };
+                if child_pattern.contains('*') {
+                    for (child_ix, child_name) in names.iter().enumerate() {
+                        if matches_wildcard(&child_pattern, child_name) {
+                            nest(child_ix);
+                        }
+                    }
+                } else if let Some(child_ix) = indices_by_name.get(child_pattern.as_str()) {
+                    nest(*child_ix);
+                }
+            }
+        }
+    }
+
+    (0..names.len())
+        .map(|ix| {
+            let mut outermost_ix = parents[ix]?;
+            while let Some(parent_ix) = parents[outermost_ix] {
+                outermost_ix = parent_ix;
+            }
+            Some(outermost_ix)
+        })
+        .collect()
+}
+
+/// Returns whether the file at `ix` is nested, at any depth, under the one at `ancestor_ix`.
+fn is_nested_under(parents: &[Option<usize>], mut ix: usize, ancestor_ix: usize) -> bool {
+    while let Some(parent_ix) = parents[ix] {
+        if parent_ix == ancestor_ix {
+            return true;
+        }
+        ix = parent_ix;
+    }
+    false
+}
+
+/// Matches a file name against a parent pattern, which can contain a single `*`, and
+/// returns the part of the name matched by the `*`.
+fn capture<'a>(pattern: &str, name: &'a str) -> Option<&'a str> {
+    let Some((prefix, suffix)) = pattern.split_once('*') else {
+        return (pattern == name).then_some("");
+    };
+    if suffix.contains('*') || name.len() < prefix.len() + suffix.len() {
+        return None;
+    }
+    name.strip_prefix(prefix)?.strip_suffix(suffix)
+}
+
+/// Matches a file name against a pattern in which `*` matches any sequence of characters.
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return pattern == name;
+    };
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(ix) => rest = &rest[ix + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nest_files() {
+        let patterns = BTreeMap::from_iter([
+            (
+                "*.ts".to_string(),
+                vec![
+                    "${capture}.js".to_string(),
+                    "${capture}.test.ts".to_string(),
+                ],
+            ),
+            ("*.js".to_string(), vec!["${capture}.js.map".to_string()]),
+            ("Cargo.toml".to_string(), vec!["Cargo.lock".to_string()]),
+            ("*.rs".to_string(), vec!["${capture}_*.rs".to_string()]),
+        ]);
+        let names = [
+            "Cargo.lock",
+            "Cargo.toml",
+            "foo.js",
+            "foo.js.map",
+            "foo.test.ts",
+            "foo.ts",
+            "bar.js",
+            "lib.rs",
+            "lib_tests.rs",
+        ];
+        assert_eq!(
+            nest_files(&names, &patterns),
+            [
+                Some(1),
+                None,
+                Some(5),
+                Some(5),
+                Some(5),
+                None,
+                None,
+                None,
+                Some(7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matches_wildcard() {
+        assert!(matches_wildcard("foo_*.rs", "foo_bar.rs"));
+        assert!(matches_wildcard("*.min.*", "app.min.js"));
+        assert!(!matches_wildcard("*.min.*", "app.js"));
+        assert!(matches_wildcard("Cargo.lock", "Cargo.lock"));
+        assert!(!matches_wildcard("a*a", "a"));
+    }
+}
@@ -1,14 +1,17 @@
 pub mod file_associations;
+mod file_nesting;
 mod project_panel_settings;
 use client::{ErrorCode, ErrorExt};
-use settings::Settings;
+use settings::{Settings, SettingsStore};
 
 use db::kvp::KEY_VALUE_STORE;
+use diff_view::DiffView;
 use editor::{actions::Cancel, scroll::Autoscroll, Editor};
 use file_associations::FileAssociations;
 
 use anyhow::{anyhow, Result};
-use collections::{hash_map, HashMap};
+use collections::{hash_map, HashMap, HashSet};
+use fuzzy::{CharBag, PathMatchCandidate};
 use gpui::{
     actions, div, impl_actions, overlay, px, uniform_list, Action, AppContext, AssetSource,
     AsyncWindowContext, ClipboardItem, DismissEvent, Div, EventEmitter, FocusHandle, FocusableView,
@@ -18,20 +21,21 @@ use gpui::{
 };
 use menu::{Confirm, SelectNext, SelectPrev};
 use project::{
-    repository::GitFileStatus, Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath,
-    Worktree, WorktreeId,
+    repository::GitFileStatus, DiagnosticSummary, Entry, EntryKind, Fs, Project, ProjectEntryId,
+    ProjectPath, Worktree, WorktreeId,
 };
 use project_panel_settings::{ProjectPanelDockPosition, ProjectPanelSettings};
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
+    collections::BTreeMap,
     ffi::OsStr,
     ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
 };
 use theme::ThemeSettings;
-use ui::{prelude::*, v_flex, ContextMenu, Icon, KeyBinding, Label, ListItem};
+use ui::{prelude::*, v_flex, ContextMenu, Disclosure, Icon, KeyBinding, Label, ListItem};
 use unicase::UniCase;
 use util::{maybe, NumericPrefixWithSuffix, ResultExt, TryFutureExt};
 use workspace::{
@@ -52,10 +56,27 @@ pub struct ProjectPanel {
     last_worktree_root_id: Option<ProjectEntryId>,
     expanded_dir_ids: HashMap<WorktreeId, Vec<ProjectEntryId>>,
     selection: Option<Selection>,
+    /// Entries picked with shift-click, which "Search Inside" searches together.
+    marked_entries: HashSet<ProjectEntryId>,
     context_menu: Option<(View<ContextMenu>, Point<Pixels>, Subscription)>,
     edit_state: Option<EditState>,
     filename_editor: View<Editor>,
+    filter_editor: View<Editor>,
+    show_filter: bool,
+    filter_query: String,
+    /// The entries whose names match the filter query.
+    filter_matches: HashSet<ProjectEntryId>,
+    /// The paths of each worktree that are shown while filtering: those of the entries
+    /// matching the filter query, and of the directories containing them.
+    filter_visible_paths: HashMap<WorktreeId, HashSet<Arc<Path>>>,
+    pending_filter: Task<()>,
+    /// Maps the files nested under other files to the files they're nested under.
+    nesting_parents: HashMap<ProjectEntryId, ProjectEntryId>,
+    nesting_parent_ids: HashSet<ProjectEntryId>,
+    /// The diagnostic summaries of files, summed up for the directories containing them.
+    diagnostic_summaries: HashMap<(WorktreeId, Arc<Path>), DiagnosticSummary>,
     clipboard_entry: Option<ClipboardEntry>,
+    compare_entry: Option<ProjectEntryId>,
     _dragged_entry_destination: Option<Arc<Path>>,
     workspace: WeakView<Workspace>,
     width: Option<Pixels>,
@@ -99,11 +120,15 @@ pub struct EntryDetails {
     is_ignored: bool,
     is_expanded: bool,
     is_selected: bool,
+    is_marked: bool,
     is_editing: bool,
     is_processing: bool,
     is_cut: bool,
     git_status: Option<GitFileStatus>,
     is_dotenv: bool,
+    has_nested_entries: bool,
+    error_count: usize,
+    warning_count: usize,
 }
 
 #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
@@ -128,10 +153,14 @@ actions!(
         OpenInTerminal,
         Cut,
         Paste,
+        Duplicate,
         Rename,
         Open,
         ToggleFocus,
+        ToggleFilter,
         NewSearchInDirectory,
+        SelectForCompare,
+        CompareWithSelected,
     ]
 );
 
@@ -197,10 +226,19 @@ impl ProjectPanel {
                 project::Event::WorktreeRemoved(id) => {
                     this.expanded_dir_ids.remove(id);
                     this.update_visible_entries(None, cx);
+                    this.update_diagnostic_summaries(cx);
+                    cx.notify();
+                }
+                project::Event::DiagnosticsUpdated { .. }
+                | project::Event::DiskBasedDiagnosticsFinished { .. } => {
+                    this.update_diagnostic_summaries(cx);
                     cx.notify();
                 }
                 project::Event::WorktreeUpdatedEntries(_, _) | project::Event::WorktreeAdded => {
                     this.update_visible_entries(None, cx);
+                    if !this.filter_query.is_empty() {
+                        this.update_filter_matches(cx);
+                    }
                     cx.notify();
                 }
                 _ => {}
@@ -228,11 +266,36 @@ impl ProjectPanel {
             })
             .detach();
 
+            let filter_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Filter files...", cx);
+                editor
+            });
+
+            cx.subscribe(&filter_editor, |this, filter_editor, event, cx| {
+                if let editor::EditorEvent::BufferEdited = event {
+                    this.filter_query = filter_editor.read(cx).text(cx);
+                    this.update_filter_matches(cx);
+                }
+            })
+            .detach();
+
             cx.observe_global::<FileAssociations>(|_, cx| {
                 cx.notify();
             })
             .detach();
 
+            let mut file_nesting = ProjectPanelSettings::get_global(cx).file_nesting.clone();
+            cx.observe_global::<SettingsStore>(move |this, cx| {
+                let new_file_nesting = &ProjectPanelSettings::get_global(cx).file_nesting;
+                if *new_file_nesting != file_nesting {
+                    file_nesting = new_file_nesting.clone();
+                    this.update_visible_entries(None, cx);
+                    cx.notify();
+                }
+            })
+            .detach();
+
             let mut this = Self {
                 project: project.clone(),
                 fs: workspace.app_state().fs.clone(),
@@ -242,16 +305,28 @@ impl ProjectPanel {
                 last_worktree_root_id: Default::default(),
                 expanded_dir_ids: Default::default(),
                 selection: None,
+                marked_entries: HashSet::default(),
                 edit_state: None,
                 context_menu: None,
                 filename_editor,
+                filter_editor,
+                show_filter: false,
+                filter_query: String::new(),
+                filter_matches: HashSet::default(),
+                filter_visible_paths: HashMap::default(),
+                pending_filter: Task::ready(()),
+                nesting_parents: HashMap::default(),
+                nesting_parent_ids: HashSet::default(),
+                diagnostic_summaries: HashMap::default(),
                 clipboard_entry: None,
+                compare_entry: None,
                 _dragged_entry_destination: None,
                 workspace: workspace.weak_handle(),
                 width: None,
                 pending_serialization: Task::ready(None),
             };
             this.update_visible_entries(None, cx);
+            this.update_diagnostic_summaries(cx);
 
             this
         });
@@ -401,6 +476,11 @@ impl ProjectPanel {
         if let Some((worktree, entry)) = self.selected_entry(cx) {
             let is_root = Some(entry) == worktree.root_entry();
             let is_dir = entry.is_dir();
+            let is_file = entry.is_file();
+            let can_search_inside = is_dir || self.marked_entries.contains(&entry.id);
+            let can_compare = self
+                .compare_entry
+                .map_or(false, |compare_entry| compare_entry != entry.id);
             let worktree_id = worktree.id();
             let is_local = project.is_local();
             let is_read_only = project.is_read_only();
@@ -410,9 +490,19 @@ impl ProjectPanel {
                     is_read_only,
                     |menu| {
                         menu.action("Copy Relative Path", Box::new(CopyRelativePath))
-                            .when(is_dir, |menu| {
+                            .when(can_search_inside, |menu| {
                                 menu.action("Search Inside", Box::new(NewSearchInDirectory))
                             })
+                            .when(is_file, |menu| {
+                                menu.separator()
+                                    .action("Select for Compare", Box::new(SelectForCompare))
+                                    .when(can_compare, |menu| {
+                                        menu.action(
+                                            "Compare with Selected",
+                                            Box::new(CompareWithSelected),
+                                        )
+                                    })
+                            })
                     },
                     |menu| {
                         menu.when(is_local, |menu| {
@@ -438,10 +528,11 @@ impl ProjectPanel {
                         .separator()
                         .action("Cut", Box::new(Cut))
                         .action("Copy", Box::new(Copy))
-                        .when_some(self.clipboard_entry, |menu, entry| {
-                            menu.when(entry.worktree_id() == worktree_id, |menu| {
-                                menu.action("Paste", Box::new(Paste))
-                            })
+                        .when(self.clipboard_entry.is_some(), |menu| {
+                            menu.action("Paste", Box::new(Paste))
+                        })
+                        .when(!is_root, |menu| {
+                            menu.action("Duplicate", Box::new(Duplicate))
                         })
                         .separator()
                         .action("Copy Path", Box::new(CopyPath))
@@ -450,7 +541,19 @@ impl ProjectPanel {
                         .action("Reveal in Finder", Box::new(RevealInFinder))
                         .when(is_dir, |menu| {
                             menu.action("Open in Terminal", Box::new(OpenInTerminal))
-                                .action("Search Inside", Box::new(NewSearchInDirectory))
+                        })
+                        .when(can_search_inside, |menu| {
+                            menu.action("Search Inside", Box::new(NewSearchInDirectory))
+                        })
+                        .when(is_file, |menu| {
+                            menu.separator()
+                                .action("Select for Compare", Box::new(SelectForCompare))
+                                .when(can_compare, |menu| {
+                                    menu.action(
+                                        "Compare with Selected",
+                                        Box::new(CompareWithSelected),
+                                    )
+                                })
                         })
                         .separator()
                         .action("Rename", Box::new(Rename))
@@ -474,7 +577,8 @@ impl ProjectPanel {
 
     fn expand_selected_entry(&mut self, _: &ExpandSelectedEntry, cx: &mut ViewContext<Self>) {
         if let Some((worktree, entry)) = self.selected_entry(cx) {
-            if entry.is_dir() {
+            let is_dir = entry.is_dir();
+            if is_dir || self.nesting_parent_ids.contains(&entry.id) {
                 let worktree_id = worktree.id();
                 let entry_id = entry.id;
                 let expanded_dir_ids =
@@ -487,9 +591,11 @@ impl ProjectPanel {
                 match expanded_dir_ids.binary_search(&entry_id) {
                     Ok(_) => self.select_next(&SelectNext, cx),
                     Err(ix) => {
-                        self.project.update(cx, |project, cx| {
-                            project.expand_entry(worktree_id, entry_id, cx);
-                        });
+                        if is_dir {
+                            self.project.update(cx, |project, cx| {
+                                project.expand_entry(worktree_id, entry_id, cx);
+                            });
+                        }
 
                         expanded_dir_ids.insert(ix, entry_id);
                         self.update_visible_entries(None, cx);
@@ -541,6 +647,9 @@ impl ProjectPanel {
 
     fn toggle_expanded(&mut self, entry_id: ProjectEntryId, cx: &mut ViewContext<Self>) {
         if let Some(worktree_id) = self.project.read(cx).worktree_id_for_entry(entry_id, cx) {
+            // Files that other files are nested under are expanded like directories,
+            // but there's nothing to load for them.
+            let is_dir = !self.nesting_parent_ids.contains(&entry_id);
             if let Some(expanded_dir_ids) = self.expanded_dir_ids.get_mut(&worktree_id) {
                 self.project.update(cx, |project, cx| {
                     match expanded_dir_ids.binary_search(&entry_id) {
@@ -548,7 +657,9 @@ impl ProjectPanel {
                             expanded_dir_ids.remove(ix);
                         }
                         Err(ix) => {
-                            project.expand_entry(worktree_id, entry_id, cx);
+                            if is_dir {
+                                project.expand_entry(worktree_id, entry_id, cx);
+                            }
                             expanded_dir_ids.insert(ix, entry_id);
                         }
                     }
@@ -560,6 +671,131 @@ impl ProjectPanel {
         }
     }
 
+    fn toggle_filter(&mut self, _: &ToggleFilter, cx: &mut ViewContext<Self>) {
+        if self.show_filter {
+            self.show_filter = false;
+            self.filter_query.clear();
+            self.update_filter_matches(cx);
+            self.filter_editor.update(cx, |editor, cx| editor.clear(cx));
+            self.expand_to_selection(cx);
+            self.update_visible_entries(None, cx);
+            self.autoscroll(cx);
+            cx.focus(&self.focus_handle);
+        } else {
+            self.show_filter = true;
+            cx.focus_view(&self.filter_editor);
+        }
+        cx.notify();
+    }
+
+    /// Matches the names of the worktrees' entries against the filter query on a background
+    /// thread, then shows the entries that match.
+    fn update_filter_matches(&mut self, cx: &mut ViewContext<Self>) {
+        if self.filter_query.is_empty() {
+            self.pending_filter = Task::ready(());
+            self.filter_matches.clear();
+            self.filter_visible_paths.clear();
+            self.update_visible_entries(None, cx);
+            cx.notify();
+            return;
+        }
+
+        let query = self.filter_query.clone();
+        let snapshots = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).snapshot())
+            .collect::<Vec<_>>();
+        let matches = cx.background_executor().spawn(async move {
+            let mut filter_matches = HashSet::default();
+            let mut filter_visible_paths = HashMap::default();
+            for snapshot in snapshots {
+                let mut names = snapshot
+                    .entries(false)
+                    .filter_map(|entry| entry.path.file_name()?.to_str())
+                    .collect::<Vec<_>>();
+                names.sort_unstable();
+                names.dedup();
+                let candidates = names
+                    .iter()
+                    .map(|name| PathMatchCandidate {
+                        path: Path::new(name),
+                        char_bag: CharBag::from(*name),
+                    })
+                    .collect::<Vec<_>>();
+                let max_results = candidates.len();
+                let matching_names = fuzzy::match_fixed_path_set(
+                    candidates,
+                    snapshot.id().to_usize(),
+                    &query,
+                    false,
+                    max_results,
+                )
+                .into_iter()
+                .map(|path_match| path_match.path)
+                .collect::<HashSet<_>>();
+
+                let visible_paths = filter_visible_paths
+                    .entry(snapshot.id())
+                    .or_insert_with(HashSet::default);
+                visible_paths.insert(Arc::from(Path::new("")));
+                for entry in snapshot.entries(false) {
+                    if entry
+                        .path
+                        .file_name()
+                        .map_or(false, |name| matching_names.contains(Path::new(name)))
+                    {
+                        filter_matches.insert(entry.id);
+                        for ancestor in entry.path.ancestors() {
+                            if !visible_paths.insert(Arc::from(ancestor)) {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            (filter_matches, filter_visible_paths)
+        });
+
+        self.pending_filter = cx.spawn(|this, mut cx| async move {
+            let (filter_matches, filter_visible_paths) = matches.await;
+            this.update(&mut cx, |this, cx| {
+                this.filter_matches = filter_matches;
+                this.filter_visible_paths = filter_visible_paths;
+                this.update_visible_entries(None, cx);
+                this.select_first_filter_match(cx);
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Selects the first entry matching the filter, unless the selected entry matches it.
+    fn select_first_filter_match(&mut self, cx: &mut ViewContext<Self>) {
+        if self.filter_query.is_empty()
+            || self.selection.map_or(false, |selection| {
+                self.filter_matches.contains(&selection.entry_id)
+            })
+        {
+            return;
+        }
+
+        self.selection = self
+            .visible_entries
+            .iter()
+            .find_map(|(worktree_id, worktree_entries)| {
+                let entry = worktree_entries
+                    .iter()
+                    .find(|entry| self.filter_matches.contains(&entry.id))?;
+                Some(Selection {
+                    worktree_id: *worktree_id,
+                    entry_id: entry.id,
+                })
+            });
+        self.autoscroll(cx);
+    }
+
     fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
         if let Some(selection) = self.selection {
             let (mut worktree_ix, mut entry_ix, _) =
@@ -912,10 +1148,6 @@ impl ProjectPanel {
         maybe!({
             let (worktree, entry) = self.selected_entry(cx)?;
             let clipboard_entry = self.clipboard_entry?;
-            if clipboard_entry.worktree_id() != worktree.id() {
-                return None;
-            }
-
             let clipboard_entry_file_name = self
                 .project
                 .read(cx)
@@ -924,43 +1156,25 @@ impl ProjectPanel {
                 .file_name()?
                 .to_os_string();
 
-            let mut new_path = entry.path.to_path_buf();
+            let mut directory = entry.path.to_path_buf();
             // If we're pasting into a file, or a directory into itself, go up one level.
             if entry.is_file() || (entry.is_dir() && entry.id == clipboard_entry.entry_id()) {
-                new_path.pop();
-            }
-
-            new_path.push(&clipboard_entry_file_name);
-            let extension = new_path.extension().map(|e| e.to_os_string());
-            let file_name_without_extension = Path::new(&clipboard_entry_file_name).file_stem()?;
-            let mut ix = 0;
-            while worktree.entry_for_path(&new_path).is_some() {
-                new_path.pop();
-
-                let mut new_file_name = file_name_without_extension.to_os_string();
-                new_file_name.push(" copy");
-                if ix > 0 {
-                    new_file_name.push(format!(" {}", ix));
-                }
-                if let Some(extension) = extension.as_ref() {
-                    new_file_name.push(".");
-                    new_file_name.push(extension);
-                }
-
-                new_path.push(new_file_name);
-                ix += 1;
+                directory.pop();
             }
+            let new_path = path_for_copy(worktree, &directory, &clipboard_entry_file_name)?;
+            let worktree_id = worktree.id();
 
             if clipboard_entry.is_cut() {
-                self.project
-                    .update(cx, |project, cx| {
-                        project.rename_entry(clipboard_entry.entry_id(), new_path, cx)
-                    })
-                    .detach_and_log_err(cx)
+                self.move_entry_to_path(clipboard_entry.entry_id(), worktree_id, new_path, cx);
             } else {
                 self.project
                     .update(cx, |project, cx| {
-                        project.copy_entry(clipboard_entry.entry_id(), new_path, cx)
+                        project.copy_entry(
+                            clipboard_entry.entry_id(),
+                            Some(worktree_id),
+                            new_path,
+                            cx,
+                        )
                     })
                     .detach_and_log_err(cx)
             }
@@ -969,6 +1183,33 @@ impl ProjectPanel {
         });
     }
 
+    fn duplicate(&mut self, _: &Duplicate, cx: &mut ViewContext<Self>) {
+        maybe!({
+            let (worktree, entry) = self.selected_entry(cx)?;
+            let directory = entry.path.parent()?;
+            let new_path = path_for_copy(worktree, directory, entry.path.file_name()?)?;
+            let worktree_id = worktree.id();
+            let entry_id = entry.id;
+
+            let copy = self.project.update(cx, |project, cx| {
+                project.copy_entry(entry_id, None, new_path, cx)
+            });
+            cx.spawn(|this, mut cx| async move {
+                let new_entry = copy.await?;
+                this.update(&mut cx, |this, cx| {
+                    if let Some(new_entry) = new_entry {
+                        this.update_visible_entries(Some((worktree_id, new_entry.id)), cx);
+                        this.autoscroll(cx);
+                        cx.notify();
+                    }
+                })
+            })
+            .detach_and_log_err(cx);
+
+            Some(())
+        });
+    }
+
     fn copy_path(&mut self, _: &CopyPath, cx: &mut ViewContext<Self>) {
         if let Some((worktree, entry)) = self.selected_entry(cx) {
             cx.write_to_clipboard(ClipboardItem::new(
@@ -1010,26 +1251,70 @@ impl ProjectPanel {
         _: &NewSearchInDirectory,
         cx: &mut ViewContext<Self>,
     ) {
-        if let Some((worktree, entry)) = self.selected_entry(cx) {
-            if entry.is_dir() {
-                let include_root = self.project.read(cx).visible_worktrees(cx).count() > 1;
-                let dir_path = if include_root {
-                    let mut full_path = PathBuf::from(worktree.root_name());
-                    full_path.push(&entry.path);
-                    Arc::from(full_path)
-                } else {
-                    entry.path.clone()
-                };
+        let Some((worktree, entry)) = self.selected_entry(cx) else {
+            return;
+        };
+        let paths = if self.marked_entries.contains(&entry.id) {
+            self.marked_search_paths(cx)
+        } else if entry.is_dir() {
+            vec![self.search_path(worktree, entry, cx)]
+        } else {
+            return;
+        };
 
-                self.workspace
-                    .update(cx, |workspace, cx| {
-                        search::ProjectSearchView::new_search_in_directory(
-                            workspace, &dir_path, cx,
-                        );
-                    })
-                    .ok();
+        let paths = paths.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                search::ProjectSearchView::new_search_in_paths(workspace, &paths, cx);
+            })
+            .ok();
+    }
+
+    fn toggle_marked(&mut self, entry_id: ProjectEntryId, cx: &mut ViewContext<Self>) {
+        let Some(worktree_id) = self.project.read(cx).worktree_id_for_entry(entry_id, cx) else {
+            return;
+        };
+        // The first shift-click marks the selected entry as well, to start from it.
+        if self.marked_entries.is_empty() {
+            if let Some(selection) = self.selection {
+                self.marked_entries.insert(selection.entry_id);
             }
         }
+        if !self.marked_entries.remove(&entry_id) {
+            self.marked_entries.insert(entry_id);
+        }
+        self.selection = Some(Selection {
+            worktree_id,
+            entry_id,
+        });
+        cx.notify();
+    }
+
+    /// The paths of the marked entries, as project search filters match them.
+    fn marked_search_paths(&self, cx: &AppContext) -> Vec<Arc<Path>> {
+        let project = self.project.read(cx);
+        let mut paths = self
+            .marked_entries
+            .iter()
+            .filter_map(|entry_id| {
+                let worktree = project.worktree_for_entry(*entry_id, cx)?.read(cx);
+                let entry = worktree.entry_for_id(*entry_id)?;
+                Some(self.search_path(worktree, entry, cx))
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    fn search_path(&self, worktree: &Worktree, entry: &Entry, cx: &AppContext) -> Arc<Path> {
+        let include_root = self.project.read(cx).visible_worktrees(cx).count() > 1;
+        if include_root {
+            let mut full_path = PathBuf::from(worktree.root_name());
+            full_path.push(&entry.path);
+            Arc::from(full_path)
+        } else {
+            entry.path.clone()
+        }
     }
 
     fn move_entry(
@@ -1039,28 +1324,96 @@ impl ProjectPanel {
         destination_is_file: bool,
         cx: &mut ViewContext<Self>,
     ) {
-        let destination_worktree = self.project.update(cx, |project, cx| {
-            let entry_path = project.path_for_entry(entry_to_move, cx)?;
-            let destination_entry_path = project.path_for_entry(destination, cx)?.path.clone();
+        let project = self.project.read(cx);
+        let Some(entry_path) = project.path_for_entry(entry_to_move, cx) else {
+            return;
+        };
+        let Some(destination_path) = project.path_for_entry(destination, cx) else {
+            return;
+        };
+        let Some(file_name) = entry_path.path.file_name() else {
+            return;
+        };
 
-            let mut destination_path = destination_entry_path.as_ref();
-            if destination_is_file {
-                destination_path = destination_path.parent()?;
-            }
+        let mut new_path = destination_path.path.to_path_buf();
+        if destination_is_file {
+            new_path.pop();
+        }
+        new_path.push(file_name);
+        // Entries can't be moved to where they already are, or into themselves.
+        if destination_path.worktree_id != entry_path.worktree_id
+            || !new_path.starts_with(&entry_path.path)
+        {
+            self.move_entry_to_path(entry_to_move, destination_path.worktree_id, new_path, cx);
+        }
 
-            let mut new_path = destination_path.to_path_buf();
-            new_path.push(entry_path.path.file_name()?);
-            if new_path != entry_path.path.as_ref() {
-                let task = project.rename_entry(entry_to_move, new_path, cx);
-                cx.foreground_executor().spawn(task).detach_and_log_err(cx);
-            }
+        self.expand_entry(destination_path.worktree_id, destination, cx);
+    }
 
-            project.worktree_id_for_entry(destination, cx)
+    fn move_entry_to_path(
+        &mut self,
+        entry_id: ProjectEntryId,
+        new_worktree_id: WorktreeId,
+        new_path: PathBuf,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let project = self.project.clone();
+        project.update(cx, |project, cx| {
+            if project.worktree_id_for_entry(entry_id, cx) == Some(new_worktree_id) {
+                project
+                    .rename_entry(entry_id, new_path, cx)
+                    .detach_and_log_err(cx);
+            } else {
+                // Entries can only be renamed within their worktree, so they're moved to other
+                // worktrees by copying and then deleting them.
+                let copy = project.copy_entry(entry_id, Some(new_worktree_id), new_path, cx);
+                cx.spawn(|project, mut cx| async move {
+                    copy.await?;
+                    let delete = project
+                        .update(&mut cx, |project, cx| project.delete_entry(entry_id, cx))?;
+                    if let Some(delete) = delete {
+                        delete.await?;
+                    }
+                    anyhow::Ok(())
+                })
+                .detach_and_log_err(cx);
+            }
         });
+    }
+
+    fn select_for_compare(&mut self, _: &SelectForCompare, cx: &mut ViewContext<Self>) {
+        if let Some((_, entry)) = self.selected_entry(cx) {
+            if entry.is_file() {
+                self.compare_entry = Some(entry.id);
+                cx.notify();
+            }
+        }
+    }
 
-        if let Some(destination_worktree) = destination_worktree {
-            self.expand_entry(destination_worktree, destination, cx);
+    fn compare_with_selected(&mut self, _: &CompareWithSelected, cx: &mut ViewContext<Self>) {
+        let project = self.project.read(cx);
+        let Some(left_path) = self
+            .compare_entry
+            .and_then(|entry_id| project.path_for_entry(entry_id, cx))
+        else {
+            return;
+        };
+        let Some((worktree, entry)) = self.selected_entry(cx) else {
+            return;
+        };
+        if !entry.is_file() || Some(entry.id) == self.compare_entry {
+            return;
         }
+        let right_path = ProjectPath {
+            worktree_id: worktree.id(),
+            path: entry.path.clone(),
+        };
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                DiffView::open(left_path, right_path, workspace, cx).detach_and_log_err(cx);
+            })
+            .ok();
     }
 
     fn index_for_selection(&self, selection: Selection) -> Option<(usize, usize, usize)> {
@@ -1136,7 +1489,11 @@ impl ProjectPanel {
             .and_then(|worktree| worktree.read(cx).root_entry())
             .map(|entry| entry.id);
 
+        let settings = ProjectPanelSettings::get_global(cx);
+        let is_filtering = !self.filter_query.is_empty();
         self.visible_entries.clear();
+        self.nesting_parents.clear();
+        self.nesting_parent_ids.clear();
         for worktree in project.visible_worktrees(cx) {
             let snapshot = worktree.read(cx).snapshot();
             let worktree_id = snapshot.id();
@@ -1167,10 +1524,24 @@ impl ProjectPanel {
                 }
             }
 
+            // When filtering, only show the entries whose names match the query, and the
+            // directories containing them.
+            let visible_paths = is_filtering.then(|| self.filter_visible_paths.get(&worktree_id));
+
             let mut visible_worktree_entries = Vec::new();
-            let mut entry_iter = snapshot.entries(true);
+            let mut entry_iter = snapshot.entries(!is_filtering);
 
             while let Some(entry) = entry_iter.entry() {
+                let is_visible = visible_paths.map_or(true, |paths| {
+                    paths.map_or(false, |paths| paths.contains(entry.path.as_ref()))
+                });
+                if !is_visible {
+                    if !entry_iter.advance_to_sibling() {
+                        entry_iter.advance();
+                    }
+                    continue;
+                }
+
                 visible_worktree_entries.push(entry.clone());
                 if Some(entry.id) == new_entry_parent_id {
                     visible_worktree_entries.push(Entry {
@@ -1186,7 +1557,8 @@ impl ProjectPanel {
                         git_status: entry.git_status,
                     });
                 }
-                if expanded_dir_ids.binary_search(&entry.id).is_err()
+                if !is_filtering
+                    && expanded_dir_ids.binary_search(&entry.id).is_err()
                     && entry_iter.advance_to_sibling()
                 {
                     continue;
@@ -1241,6 +1613,17 @@ impl ProjectPanel {
                     }
                 }
             });
+
+            if settings.file_nesting.enabled && !is_filtering {
+                nest_entries(
+                    &mut visible_worktree_entries,
+                    expanded_dir_ids,
+                    &settings.file_nesting.patterns,
+                    &mut self.nesting_parents,
+                    &mut self.nesting_parent_ids,
+                );
+            }
+
             self.visible_entries
                 .push((worktree_id, visible_worktree_entries));
         }
@@ -1253,6 +1636,21 @@ impl ProjectPanel {
         }
     }
 
+    fn update_diagnostic_summaries(&mut self, cx: &mut ViewContext<Self>) {
+        self.diagnostic_summaries.clear();
+        let project = self.project.read(cx);
+        for (project_path, _, summary) in project.diagnostic_summaries(false, cx) {
+            for path in project_path.path.ancestors() {
+                let path_summary = self
+                    .diagnostic_summaries
+                    .entry((project_path.worktree_id, path.into()))
+                    .or_default();
+                path_summary.error_count += summary.error_count;
+                path_summary.warning_count += summary.warning_count;
+            }
+        }
+    }
+
     fn expand_entry(
         &mut self,
         worktree_id: WorktreeId,
@@ -1324,7 +1722,14 @@ impl ProjectPanel {
                 let entry_range = range.start.saturating_sub(ix)..end_ix - ix;
                 for entry in visible_worktree_entries[entry_range].iter() {
                     let status = git_status_setting.then(|| entry.git_status).flatten();
-                    let is_expanded = expanded_entry_ids.binary_search(&entry.id).is_ok();
+                    // All the directories shown while filtering contain matching entries.
+                    let is_expanded = expanded_entry_ids.binary_search(&entry.id).is_ok()
+                        || (!self.filter_query.is_empty() && entry.is_dir());
+                    let diagnostic_summary = self
+                        .diagnostic_summaries
+                        .get(&(*worktree_id, entry.path.clone()))
+                        .copied()
+                        .unwrap_or_default();
                     let icon = match entry.kind {
                         EntryKind::File(_) => {
                             if show_file_icons {
@@ -1351,13 +1756,15 @@ impl ProjectPanel {
                             .to_string(),
                         icon,
                         path: entry.path.clone(),
-                        depth: entry.path.components().count(),
+                        depth: entry.path.components().count()
+                            + self.nesting_parents.contains_key(&entry.id) as usize,
                         kind: entry.kind,
                         is_ignored: entry.is_ignored,
                         is_expanded,
                         is_selected: self.selection.map_or(false, |e| {
                             e.worktree_id == snapshot.id() && e.entry_id == entry.id
                         }),
+                        is_marked: self.marked_entries.contains(&entry.id),
                         is_editing: false,
                         is_processing: false,
                         is_cut: self
@@ -1365,6 +1772,9 @@ impl ProjectPanel {
                             .map_or(false, |e| e.is_cut() && e.entry_id() == entry.id),
                         git_status: status,
                         is_dotenv: entry.is_private,
+                        has_nested_entries: self.nesting_parent_ids.contains(&entry.id),
+                        error_count: diagnostic_summary.error_count,
+                        warning_count: diagnostic_summary.warning_count,
                     };
 
                     if let Some(edit_state) = &self.edit_state {
@@ -1428,6 +1838,35 @@ impl ProjectPanel {
         let file_name = details.filename.clone();
         let icon = details.icon.clone();
         let depth = details.depth;
+        let end_slot = h_flex()
+            .gap_1()
+            .when(settings.show_diagnostics, |this| {
+                this.when(details.error_count > 0, |this| {
+                    this.child(
+                        Label::new(details.error_count.to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Error),
+                    )
+                })
+                .when(details.warning_count > 0, |this| {
+                    this.child(
+                        Label::new(details.warning_count.to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Warning),
+                    )
+                })
+            })
+            .when(details.has_nested_entries, |this| {
+                let on_toggle: Arc<dyn Fn(&gpui::ClickEvent, &mut WindowContext)> =
+                    Arc::new(cx.listener(move |this, _, cx| this.toggle_expanded(entry_id, cx)));
+                this.child(
+                    Disclosure::new(
+                        ("nested-entries", entry_id.to_proto() as usize),
+                        details.is_expanded,
+                    )
+                    .on_toggle(on_toggle),
+                )
+            });
         div()
             .id(entry_id.to_proto() as usize)
             .on_drag(entry_id, move |entry_id, cx| {
@@ -1447,7 +1886,8 @@ impl ProjectPanel {
                 ListItem::new(entry_id.to_proto() as usize)
                     .indent_level(depth)
                     .indent_step_size(px(settings.indent_size))
-                    .selected(is_selected)
+                    .selected(is_selected || details.is_marked)
+                    .end_slot(end_slot)
                     .child(if let Some(icon) = &icon {
                         div().child(Icon::from_path(icon.to_string()).color(filename_text_color))
                     } else {
@@ -1467,6 +1907,14 @@ impl ProjectPanel {
                         if event.down.button == MouseButton::Right {
                             return;
                         }
+                        if event.down.modifiers.shift {
+                            this.toggle_marked(entry_id, cx);
+                            return;
+                        }
+                        if !this.marked_entries.is_empty() {
+                            this.marked_entries.clear();
+                            cx.notify();
+                        }
                         if !show_editor {
                             if kind.is_dir() {
                                 this.toggle_expanded(entry_id, cx);
@@ -1495,13 +1943,17 @@ impl ProjectPanel {
         dispatch_context.add("ProjectPanel");
         dispatch_context.add("menu");
 
-        let identifier = if self.filename_editor.focus_handle(cx).is_focused(cx) {
+        let is_filtering = self.filter_editor.focus_handle(cx).is_focused(cx);
+        let identifier = if is_filtering || self.filename_editor.focus_handle(cx).is_focused(cx) {
             "editing"
         } else {
             "not_editing"
         };
 
         dispatch_context.add(identifier);
+        if is_filtering {
+            dispatch_context.add("filtering");
+        }
         dispatch_context
     }
 
@@ -1525,6 +1977,13 @@ impl ProjectPanel {
             let worktree_id = worktree.id();
             self.expand_entry(worktree_id, entry_id, cx);
             self.update_visible_entries(Some((worktree_id, entry_id)), cx);
+            if let Some(parent_id) = self.nesting_parents.get(&entry_id).copied() {
+                let expanded_dir_ids = self.expanded_dir_ids.entry(worktree_id).or_default();
+                if let Err(ix) = expanded_dir_ids.binary_search(&parent_id) {
+                    expanded_dir_ids.insert(ix, parent_id);
+                    self.update_visible_entries(None, cx);
+                }
+            }
             self.autoscroll(cx);
             cx.notify();
         }
@@ -1537,7 +1996,7 @@ impl Render for ProjectPanel {
         let project = self.project.read(cx);
 
         if has_worktree {
-            div()
+            v_flex()
                 .id("project-panel")
                 .size_full()
                 .relative()
@@ -1547,12 +2006,15 @@ impl Render for ProjectPanel {
                 .on_action(cx.listener(Self::expand_selected_entry))
                 .on_action(cx.listener(Self::collapse_selected_entry))
                 .on_action(cx.listener(Self::collapse_all_entries))
+                .on_action(cx.listener(Self::toggle_filter))
                 .on_action(cx.listener(Self::open))
                 .on_action(cx.listener(Self::confirm))
                 .on_action(cx.listener(Self::cancel))
                 .on_action(cx.listener(Self::copy_path))
                 .on_action(cx.listener(Self::copy_relative_path))
                 .on_action(cx.listener(Self::new_search_in_directory))
+                .on_action(cx.listener(Self::select_for_compare))
+                .on_action(cx.listener(Self::compare_with_selected))
                 .when(!project.is_read_only(), |el| {
                     el.on_action(cx.listener(Self::new_file))
                         .on_action(cx.listener(Self::new_directory))
@@ -1561,6 +2023,7 @@ impl Render for ProjectPanel {
                         .on_action(cx.listener(Self::cut))
                         .on_action(cx.listener(Self::copy))
                         .on_action(cx.listener(Self::paste))
+                        .on_action(cx.listener(Self::duplicate))
                 })
                 .when(project.is_local(), |el| {
                     el.on_action(cx.listener(Self::reveal_in_finder))
@@ -1577,6 +2040,22 @@ impl Render for ProjectPanel {
                     }),
                 )
                 .track_focus(&self.focus_handle)
+                .when(self.show_filter, |this| {
+                    this.child(
+                        h_flex()
+                            .px_2()
+                            .py_1()
+                            .gap_2()
+                            .border_b_1()
+                            .border_color(cx.theme().colors().border_variant)
+                            .child(
+                                Icon::new(IconName::MagnifyingGlass)
+                                    .size(IconSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(self.filter_editor.clone()),
+                    )
+                })
                 .child(
                     uniform_list(
                         cx.view().clone(),
@@ -1595,7 +2074,8 @@ impl Render for ProjectPanel {
                             }
                         },
                     )
-                    .size_full()
+                    .flex_1()
+                    .w_full()
                     .track_scroll(self.scroll_handle.clone()),
                 )
                 .children(self.context_menu.as_ref().map(|(menu, position, _)| {
@@ -1719,6 +2199,106 @@ impl FocusableView for ProjectPanel {
     }
 }
 
+/// Moves the files nested under other files right after those files, or removes them
+/// from the visible entries if those files are collapsed.
+fn nest_entries(
+    entries: &mut Vec<Entry>,
+    expanded_entry_ids: &[ProjectEntryId],
+    patterns: &BTreeMap<String, Vec<String>>,
+    nesting_parents: &mut HashMap<ProjectEntryId, ProjectEntryId>,
+    nesting_parent_ids: &mut HashSet<ProjectEntryId>,
+) {
+    let mut files_by_directory = HashMap::<&Path, Vec<usize>>::default();
+    for (ix, entry) in entries.iter().enumerate() {
+        if entry.is_file() && entry.id != NEW_ENTRY_ID {
+            if let Some(directory) = entry.path.parent() {
+                files_by_directory.entry(directory).or_default().push(ix);
+            }
+        }
+    }
+
+    let mut nested_ixs_by_parent_ix = HashMap::<usize, Vec<usize>>::default();
+    for file_ixs in files_by_directory.values() {
+        let names = file_ixs
+            .iter()
+            .map(|ix| {
+                entries[*ix]
+                    .path
+                    .file_name()
+                    .and_then(OsStr::to_str)
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        for (ix, parent_ix) in file_nesting::nest_files(&names, patterns)
+            .into_iter()
+            .enumerate()
+        {
+            if let Some(parent_ix) = parent_ix {
+                nested_ixs_by_parent_ix
+                    .entry(file_ixs[parent_ix])
+                    .or_default()
+                    .push(file_ixs[ix]);
+            }
+        }
+    }
+    if nested_ixs_by_parent_ix.is_empty() {
+        return;
+    }
+
+    let mut nested_entries = Vec::with_capacity(entries.len());
+    let mut is_nested = vec![false; entries.len()];
+    for nested_ixs in nested_ixs_by_parent_ix.values() {
+        for ix in nested_ixs {
+            is_nested[*ix] = true;
+        }
+    }
+    for (ix, entry) in entries.iter().enumerate() {
+        if is_nested[ix] {
+            continue;
+        }
+        nested_entries.push(entry.clone());
+        if let Some(nested_ixs) = nested_ixs_by_parent_ix.get_mut(&ix) {
+            nested_ixs.sort_unstable();
+            nesting_parent_ids.insert(entry.id);
+            let is_expanded = expanded_entry_ids.binary_search(&entry.id).is_ok();
+            for nested_ix in nested_ixs {
+                let nested_entry = &entries[*nested_ix];
+                nesting_parents.insert(nested_entry.id, entry.id);
+                if is_expanded {
+                    nested_entries.push(nested_entry.clone());
+                }
+            }
+        }
+    }
+    *entries = nested_entries;
+}
+
+/// Returns a path in the given directory for a copy of an entry named `file_name`, adding a
+/// " copy" suffix to the name if there's already an entry with it.
+fn path_for_copy(worktree: &Worktree, directory: &Path, file_name: &OsStr) -> Option<PathBuf> {
+    let mut new_path = directory.join(file_name);
+    let extension = new_path.extension().map(|e| e.to_os_string());
+    let file_name_without_extension = Path::new(file_name).file_stem()?;
+    let mut ix = 0;
+    while worktree.entry_for_path(&new_path).is_some() {
+        new_path.pop();
+
+        let mut new_file_name = file_name_without_extension.to_os_string();
+        new_file_name.push(" copy");
+        if ix > 0 {
+            new_file_name.push(format!(" {}", ix));
+        }
+        if let Some(extension) = extension.as_ref() {
+            new_file_name.push(".");
+            new_file_name.push(extension);
+        }
+
+        new_path.push(new_file_name);
+        ix += 1;
+    }
+    Some(new_path)
+}
+
 impl ClipboardEntry {
     fn is_cut(&self) -> bool {
         matches!(self, Self::Cut { .. })
@@ -1745,12 +2325,17 @@ mod tests {
     use super::*;
     use collections::HashSet;
     use gpui::{TestAppContext, View, VisualTestContext, WindowHandle};
+    use language::{
+        Diagnostic, DiagnosticEntry, DiagnosticSeverity, LanguageServerId, PointUtf16, Unclipped,
+    };
     use pretty_assertions::assert_eq;
     use project::{FakeFs, WorktreeSettings};
     use serde_json::json;
     use settings::SettingsStore;
     use std::path::{Path, PathBuf};
-    use workspace::AppState;
+    use workspace::{item::Item, AppState};
+
+    use crate::project_panel_settings::FileNestingContent;
 
     #[gpui::test]
     async fn test_visible_list(cx: &mut gpui::TestAppContext) {
@@ -2525,45 +3110,269 @@ mod tests {
     }
 
     #[gpui::test]
-    async fn test_copy_paste_directory(cx: &mut gpui::TestAppContext) {
+    async fn test_duplicate(cx: &mut gpui::TestAppContext) {
         init_test(cx);
 
         let fs = FakeFs::new(cx.executor().clone());
         fs.insert_tree(
-            "/root",
+            "/root1",
             json!({
-                "a": {
-                    "one.txt": "",
-                    "two.txt": "",
-                    "inner_dir": {
-                        "three.txt": "",
-                        "four.txt": "",
-                    }
+                "dir": {
+                    "b.txt": "",
                 },
-                "b": {}
+                "a.txt": "",
             }),
         )
         .await;
 
-        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let project = Project::test(fs.clone(), ["/root1".as_ref()], cx).await;
         let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
         let cx = &mut VisualTestContext::from_window(*workspace, cx);
         let panel = workspace
             .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
             .unwrap();
 
-        select_path(&panel, "root/a", cx);
-        panel.update(cx, |panel, cx| {
-            panel.copy(&Default::default(), cx);
-            panel.select_next(&Default::default(), cx);
-            panel.paste(&Default::default(), cx);
-        });
+        select_path(&panel, "root1/a.txt", cx);
+        panel.update(cx, |panel, cx| panel.duplicate(&Duplicate, cx));
         cx.executor().run_until_parked();
-
-        let pasted_dir = find_project_entry(&panel, "root/b/a", cx);
-        assert_ne!(pasted_dir, None, "Pasted directory should have an entry");
-
-        let pasted_dir_file = find_project_entry(&panel, "root/b/a/one.txt", cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                "v root1",
+                "    > dir",
+                "      a copy.txt  <== selected",
+                "      a.txt",
+            ]
+        );
+
+        select_path(&panel, "root1/dir", cx);
+        panel.update(cx, |panel, cx| panel.duplicate(&Duplicate, cx));
+        cx.executor().run_until_parked();
+        toggle_expand_dir(&panel, "root1/dir copy", cx);
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                "v root1",
+                "    > dir",
+                "    v dir copy  <== selected",
+                "          b.txt",
+                "      a copy.txt",
+                "      a.txt",
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_move_entries_between_worktrees(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root1",
+            json!({
+                "dir1": {},
+                "a.txt": "A",
+                "b.txt": "B",
+            }),
+        )
+        .await;
+        fs.insert_tree(
+            "/root2",
+            json!({
+                "dir2": {},
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root1".as_ref(), "/root2".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        // Dropping an entry onto a directory of another worktree moves it there.
+        let a_txt = find_project_entry(&panel, "root1/a.txt", cx).unwrap();
+        let dir2 = find_project_entry(&panel, "root2/dir2", cx).unwrap();
+        panel.update(cx, |panel, cx| panel.move_entry(a_txt, dir2, false, cx));
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                "v root1",
+                "    > dir1",
+                "      b.txt",
+                "v root2",
+                "    v dir2",
+                "          a.txt",
+            ]
+        );
+        assert_eq!(
+            fs.load("/root2/dir2/a.txt".as_ref()).await.unwrap(),
+            "A".to_string()
+        );
+
+        // Cut entries can be pasted into another worktree too.
+        select_path(&panel, "root1/b.txt", cx);
+        panel.update(cx, |panel, cx| panel.cut(&Cut, cx));
+        select_path(&panel, "root2/dir2/a.txt", cx);
+        panel.update(cx, |panel, cx| panel.paste(&Paste, cx));
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..50, cx),
+            &[
+                "v root1",
+                "    > dir1",
+                "v root2",
+                "    v dir2",
+                "          a.txt  <== selected",
+                "          b.txt",
+            ]
+        );
+
+        // Dropping an entry into itself does nothing.
+        let dir1 = find_project_entry(&panel, "root1/dir1", cx).unwrap();
+        panel.update(cx, |panel, cx| panel.move_entry(dir1, dir1, false, cx));
+        cx.executor().run_until_parked();
+        assert!(find_project_entry(&panel, "root1/dir1", cx).is_some());
+    }
+
+    #[gpui::test]
+    async fn test_compare_with_selected(cx: &mut gpui::TestAppContext) {
+        init_test_with_editor(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root1",
+            json!({
+                "a.txt": "one\ntwo\n",
+                "b.txt": "one\nthree\n",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root1".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        select_path(&panel, "root1/a.txt", cx);
+        panel.update(cx, |panel, cx| {
+            panel.select_for_compare(&SelectForCompare, cx);
+            // A file can't be compared with itself.
+            panel.compare_with_selected(&CompareWithSelected, cx);
+        });
+        cx.executor().run_until_parked();
+        workspace
+            .update(cx, |workspace, cx| {
+                assert!(workspace.active_item_as::<DiffView>(cx).is_none());
+            })
+            .unwrap();
+
+        select_path(&panel, "root1/b.txt", cx);
+        panel.update(cx, |panel, cx| {
+            panel.compare_with_selected(&CompareWithSelected, cx)
+        });
+        cx.executor().run_until_parked();
+        workspace
+            .update(cx, |workspace, cx| {
+                let diff_view = workspace.active_item_as::<DiffView>(cx).unwrap();
+                assert_eq!(
+                    diff_view.read(cx).tab_tooltip_text(cx).unwrap(),
+                    "root1/a.txt ↔ root1/b.txt"
+                );
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_marking_entries(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root1",
+            json!({
+                "a.txt": "",
+                "b.txt": "",
+                "c.txt": "",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root1".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        select_path(&panel, "root1/a.txt", cx);
+        let c_txt = find_project_entry(&panel, "root1/c.txt", cx).unwrap();
+        panel.update(cx, |panel, cx| panel.toggle_marked(c_txt, cx));
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v root1",
+                "      a.txt  <== marked",
+                "      b.txt",
+                "      c.txt  <== selected",
+            ]
+        );
+        panel.update(cx, |panel, cx| {
+            assert_eq!(marked_search_paths(panel, cx), ["a.txt", "c.txt"]);
+        });
+
+        // Marking an entry again unmarks it.
+        panel.update(cx, |panel, cx| panel.toggle_marked(c_txt, cx));
+        panel.update(cx, |panel, cx| {
+            assert_eq!(marked_search_paths(panel, cx), ["a.txt"]);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_copy_paste_directory(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "a": {
+                    "one.txt": "",
+                    "two.txt": "",
+                    "inner_dir": {
+                        "three.txt": "",
+                        "four.txt": "",
+                    }
+                },
+                "b": {}
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+
+        select_path(&panel, "root/a", cx);
+        panel.update(cx, |panel, cx| {
+            panel.copy(&Default::default(), cx);
+            panel.select_next(&Default::default(), cx);
+            panel.paste(&Default::default(), cx);
+        });
+        cx.executor().run_until_parked();
+
+        let pasted_dir = find_project_entry(&panel, "root/b/a", cx);
+        assert_ne!(pasted_dir, None, "Pasted directory should have an entry");
+
+        let pasted_dir_file = find_project_entry(&panel, "root/b/a/one.txt", cx);
         assert_ne!(
             pasted_dir_file, None,
             "Pasted directory file should have an entry"
@@ -2923,6 +3732,270 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_filter_visible_entries(cx: &mut gpui::TestAppContext) {
+        init_test_with_editor(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/project_root",
+            json!({
+                "docs": {
+                    "manual.md": "# Manual",
+                },
+                "src": {
+                    "lib.rs": "// Library",
+                    "main.rs": "// Main",
+                },
+                "README.md": "# Readme",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/project_root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    > docs",
+                "    > src",
+                "      README.md",
+            ]
+        );
+
+        panel.update(cx, |panel, cx| panel.toggle_filter(&ToggleFilter, cx));
+        let filter_editor = panel.update(cx, |panel, _| panel.filter_editor.clone());
+        filter_editor.update(cx, |editor, cx| editor.set_text("ma", cx));
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    v docs",
+                "          manual.md  <== selected",
+                "    v src",
+                "          main.rs",
+            ]
+        );
+
+        panel.update(cx, |panel, cx| panel.select_next(&SelectNext, cx));
+        panel.update(cx, |panel, cx| panel.select_next(&SelectNext, cx));
+        panel.update(cx, |panel, cx| panel.toggle_filter(&ToggleFilter, cx));
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    > docs",
+                "    v src",
+                "          lib.rs",
+                "          main.rs  <== selected",
+                "      README.md",
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_file_nesting(cx: &mut gpui::TestAppContext) {
+        init_test_with_editor(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<ProjectPanelSettings>(cx, |project_panel_settings| {
+                    project_panel_settings.file_nesting = Some(FileNestingContent {
+                        enabled: Some(true),
+                        patterns: Some(BTreeMap::from_iter([
+                            (
+                                "*.ts".to_string(),
+                                vec!["${capture}.js".to_string(), "${capture}.d.ts".to_string()],
+                            ),
+                            ("*.js".to_string(), vec!["${capture}.js.map".to_string()]),
+                        ])),
+                    })
+                });
+            })
+        });
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/project_root",
+            json!({
+                "src": {
+                    "app.d.ts": "",
+                    "app.js": "",
+                    "app.js.map": "",
+                    "app.ts": "",
+                    "util.js": "",
+                    "util.js.map": "",
+                },
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/project_root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+        cx.executor().run_until_parked();
+
+        toggle_expand_dir(&panel, "project_root/src", cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    v src  <== selected",
+                "        > app.ts",
+                "        > util.js",
+            ]
+        );
+
+        select_path(&panel, "project_root/src/app.ts", cx);
+        panel.update(cx, |panel, cx| {
+            panel.expand_selected_entry(&ExpandSelectedEntry, cx)
+        });
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    v src",
+                "        v app.ts  <== selected",
+                "              app.d.ts",
+                "              app.js",
+                "              app.js.map",
+                "        > util.js",
+            ]
+        );
+
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<ProjectPanelSettings>(cx, |project_panel_settings| {
+                    project_panel_settings.file_nesting = Some(FileNestingContent {
+                        enabled: Some(false),
+                        patterns: None,
+                    })
+                });
+            })
+        });
+        cx.executor().run_until_parked();
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v project_root",
+                "    v src",
+                "          app.d.ts",
+                "          app.js",
+                "          app.js.map",
+                "          app.ts  <== selected",
+                "          util.js",
+                "          util.js.map",
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_diagnostic_badges(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/project_root",
+            json!({
+                "src": {
+                    "lib.rs": "mod one;",
+                    "main.rs": "fn main() {}",
+                },
+                "README.md": "# Readme",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/project_root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace
+            .update(cx, |workspace, cx| ProjectPanel::new(workspace, cx))
+            .unwrap();
+        toggle_expand_dir(&panel, "project_root/src", cx);
+
+        project.update(cx, |project, cx| {
+            for (path, severities) in [
+                (
+                    "/project_root/src/lib.rs",
+                    vec![DiagnosticSeverity::ERROR, DiagnosticSeverity::WARNING],
+                ),
+                ("/project_root/src/main.rs", vec![DiagnosticSeverity::ERROR]),
+            ] {
+                let entries = severities
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ix, severity)| DiagnosticEntry {
+                        range: Unclipped(PointUtf16::new(0, 0))..Unclipped(PointUtf16::new(0, 1)),
+                        diagnostic: Diagnostic {
+                            severity,
+                            is_primary: true,
+                            group_id: ix,
+                            message: "oops".to_string(),
+                            ..Default::default()
+                        },
+                    })
+                    .collect();
+                project
+                    .update_diagnostic_entries(
+                        LanguageServerId(0),
+                        PathBuf::from(path),
+                        None,
+                        entries,
+                        cx,
+                    )
+                    .unwrap();
+            }
+        });
+        cx.executor().run_until_parked();
+
+        // Directories show the diagnostics of all the files they contain.
+        assert_eq!(
+            diagnostic_badges(&panel, cx),
+            &[
+                ("project_root".to_string(), 2, 1),
+                ("src".to_string(), 2, 1),
+                ("lib.rs".to_string(), 1, 1),
+                ("main.rs".to_string(), 1, 0),
+                ("README.md".to_string(), 0, 0),
+            ]
+        );
+
+        project.update(cx, |project, cx| {
+            project
+                .update_diagnostic_entries(
+                    LanguageServerId(0),
+                    PathBuf::from("/project_root/src/lib.rs"),
+                    None,
+                    Vec::new(),
+                    cx,
+                )
+                .unwrap();
+        });
+        cx.executor().run_until_parked();
+        assert_eq!(
+            diagnostic_badges(&panel, cx),
+            &[
+                ("project_root".to_string(), 1, 0),
+                ("src".to_string(), 1, 0),
+                ("lib.rs".to_string(), 0, 0),
+                ("main.rs".to_string(), 1, 0),
+                ("README.md".to_string(), 0, 0),
+            ]
+        );
+    }
+
     #[gpui::test]
     async fn test_collapse_all_entries(cx: &mut gpui::TestAppContext) {
         init_test_with_editor(cx);
@@ -3528,6 +4601,14 @@ mod tests {
         });
     }
 
+    fn marked_search_paths(panel: &ProjectPanel, cx: &AppContext) -> Vec<String> {
+        panel
+            .marked_search_paths(cx)
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect()
+    }
+
     fn find_project_entry(
         panel: &View<ProjectPanel>,
         path: impl AsRef<Path>,
@@ -3545,6 +4626,23 @@ mod tests {
         })
     }
 
+    fn diagnostic_badges(
+        panel: &View<ProjectPanel>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<(String, usize, usize)> {
+        let mut result = Vec::new();
+        panel.update(cx, |panel, cx| {
+            panel.for_each_visible_entry(0..usize::MAX, cx, |_, details, _| {
+                result.push((
+                    details.filename.clone(),
+                    details.error_count,
+                    details.warning_count,
+                ));
+            });
+        });
+        result
+    }
+
     fn visible_entries_as_strings(
         panel: &View<ProjectPanel>,
         range: Range<usize>,
@@ -3569,7 +4667,7 @@ mod tests {
                 }
 
                 let indent = "    ".repeat(details.depth);
-                let icon = if details.kind.is_dir() {
+                let icon = if details.kind.is_dir() || details.has_nested_entries {
                     if details.is_expanded {
                         "v "
                     } else {
@@ -3587,6 +4685,8 @@ mod tests {
                 };
                 let selected = if details.is_selected {
                     "  <== selected"
+                } else if details.is_marked {
+                    "  <== marked"
                 } else {
                     ""
                 };
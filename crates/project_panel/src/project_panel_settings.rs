@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use anyhow;
 use gpui::Pixels;
 use schemars::JsonSchema;
@@ -20,6 +22,14 @@ pub struct ProjectPanelSettings {
     pub git_status: bool,
     pub indent_size: f32,
     pub auto_reveal_entries: bool,
+    pub show_diagnostics: bool,
+    pub file_nesting: FileNesting,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct FileNesting {
+    pub enabled: bool,
+    pub patterns: BTreeMap<String, Vec<String>>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
@@ -54,6 +64,26 @@ pub struct ProjectPanelSettingsContent {
     ///
     /// Default: true
     pub auto_reveal_entries: Option<bool>,
+    /// Whether to show the number of errors and warnings reported for files,
+    /// including in the directories that contain them.
+    ///
+    /// Default: true
+    pub show_diagnostics: Option<bool>,
+    /// Settings for showing related files under the file they belong to.
+    pub file_nesting: Option<FileNestingContent>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct FileNestingContent {
+    /// Whether to nest related files in the project panel.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Maps a pattern for the name of a parent file to the patterns for the names of
+    /// the files in the same directory that get nested under it. The part of the
+    /// parent's name matched by `*` can be referred to as `${capture}` in the
+    /// patterns of the nested files.
+    pub patterns: Option<BTreeMap<String, Vec<String>>>,
 }
 
 impl Settings for ProjectPanelSettings {
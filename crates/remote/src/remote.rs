@@ -0,0 +1,9 @@
+mod ssh_session;
+
+pub use ssh_session::*;
+
+/// The id under which a remote development server shares its project with the client.
+pub const REMOTE_PROJECT_ID: u64 = 1;
+
+/// The name of the remote development server binary, which is looked up on the remote `PATH`.
+pub const REMOTE_SERVER_BINARY: &str = "zed-remote-server";
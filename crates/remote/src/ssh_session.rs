@@ -0,0 +1,301 @@
+use crate::{REMOTE_PROJECT_ID, REMOTE_SERVER_BINARY};
+use anyhow::{anyhow, Context as _, Result};
+use client::{Client, UserStore};
+use fs::Fs;
+use futures::{AsyncBufReadExt as _, StreamExt as _};
+use gpui::{AsyncAppContext, BackgroundExecutor, Context as _, Model};
+use language::LanguageRegistry;
+use project::{terminals::RemoteTerminals, Project};
+use rpc::Connection;
+use smol::process::{Child, Command, Stdio};
+use std::{path::PathBuf, sync::Arc};
+use util::shell_quote;
+
+/// How to reach a machine over SSH.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SshConnectionOptions {
+    pub host: String,
+    pub username: Option<String>,
+    pub port: Option<u16>,
+}
+
+impl SshConnectionOptions {
+    /// Parses a URL of the form `ssh://[user@]host[:port]/path`, returning the connection options
+    /// and the path on the remote machine.
+    pub fn parse_url(url: &str) -> Option<(Self, PathBuf)> {
+        let url = url.strip_prefix("ssh://")?;
+        let (authority, path) = match url.find('/') {
+            Some(ix) => (&url[..ix], &url[ix..]),
+            None => (url, "/"),
+        };
+        let (username, host) = match authority.rsplit_once('@') {
+            Some((username, host)) => (Some(username.to_string()), host),
+            None => (None, authority),
+        };
+        let (host, port) = match host.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port.parse().ok()?)),
+            None => (host, None),
+        };
+        // A host or user starting with a dash would be taken by `ssh` for an option.
+        if host.is_empty()
+            || host.starts_with('-')
+            || username
+                .as_ref()
+                .map_or(false, |username| username.starts_with('-'))
+        {
+            return None;
+        }
+
+        Some((
+            Self {
+                host: host.to_string(),
+                username,
+                port,
+            },
+            PathBuf::from(path),
+        ))
+    }
+
+    /// The arguments that tell `ssh` where to connect, ending with the destination, which is
+    /// preceded by `--` so that it's never parsed as an option.
+    pub fn ssh_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(port) = self.port {
+            args.push("-p".to_string());
+            args.push(port.to_string());
+        }
+        args.push("--".to_string());
+        args.push(match &self.username {
+            Some(username) => format!("{}@{}", username, self.host),
+            None => self.host.clone(),
+        });
+        args
+    }
+}
+
+/// Where a remote development server runs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RemoteServer {
+    /// Runs the server on another machine, talking to it over the standard input and output of
+    /// an SSH session.
+    Ssh(SshConnectionOptions),
+    /// Runs the server binary at the given path as a local process, standing in for a remote
+    /// machine.
+    Local(PathBuf),
+}
+
+impl RemoteServer {
+    /// A local server that uses the server binary next to the running executable.
+    pub fn local() -> Result<Self> {
+        let exe = std::env::current_exe()?;
+        Ok(Self::Local(exe.with_file_name(REMOTE_SERVER_BINARY)))
+    }
+
+    /// The program and arguments that start a server hosting the given paths.
+    fn command(&self, paths: &[PathBuf]) -> (PathBuf, Vec<String>) {
+        match self {
+            Self::Ssh(options) => {
+                let mut remote_command = REMOTE_SERVER_BINARY.to_string();
+                for path in paths {
+                    remote_command.push(' ');
+                    remote_command.push_str(&shell_quote(&path.to_string_lossy()));
+                }
+
+                let mut args = vec!["-T".into(), "-o".into(), "BatchMode=yes".into()];
+                args.extend(options.ssh_args());
+                args.push(remote_command);
+                ("ssh".into(), args)
+            }
+            Self::Local(binary) => (
+                binary.clone(),
+                paths
+                    .iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect(),
+            ),
+        }
+    }
+
+    /// How terminals are spawned for projects hosted by this server.
+    pub fn terminals(&self) -> RemoteTerminals {
+        match self {
+            Self::Ssh(options) => RemoteTerminals::Ssh {
+                args: options.ssh_args(),
+            },
+            Self::Local(_) => RemoteTerminals::Local,
+        }
+    }
+
+    /// Starts the server and connects to it through its standard input and output.
+    pub fn start(
+        &self,
+        paths: &[PathBuf],
+        executor: &BackgroundExecutor,
+    ) -> Result<(Connection, Child)> {
+        let (program, args) = self.command(paths);
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to start remote server {:?}", self))?;
+
+        let stdin = child.stdin.take().ok_or_else(|| anyhow!("no stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| anyhow!("no stdout"))?;
+        if let Some(stderr) = child.stderr.take() {
+            executor
+                .spawn(async move {
+                    let mut lines = smol::io::BufReader::new(stderr).lines();
+                    while let Some(Ok(line)) = lines.next().await {
+                        log::info!("remote server: {}", line);
+                    }
+                })
+                .detach();
+        }
+
+        Ok((Connection::over_byte_streams(stdout, stdin), child))
+    }
+}
+
+/// Starts a remote development server for the given paths and opens the project it hosts.
+pub async fn open_remote_project(
+    server: RemoteServer,
+    paths: Vec<PathBuf>,
+    languages: Arc<LanguageRegistry>,
+    fs: Arc<dyn Fs>,
+    mut cx: AsyncAppContext,
+) -> Result<Model<Project>> {
+    let (connection, mut child) = server.start(&paths, cx.background_executor())?;
+    let client = cx.update(|cx| {
+        Client::new(
+            Arc::new(clock::RealSystemClock),
+            Client::global(cx).http_client(),
+            cx,
+        )
+    })?;
+    let project = connect_to_remote_project(connection, client, languages, fs, cx.clone()).await?;
+    project.update(&mut cx, |project, _| {
+        project.set_remote_terminals(Some(server.terminals()))
+    })?;
+
+    cx.background_executor()
+        .spawn(async move {
+            let status = child.status().await?;
+            log::info!("remote server exited with {}", status);
+            anyhow::Ok(())
+        })
+        .detach();
+
+    Ok(project)
+}
+
+/// Joins the project hosted by a remote development server on the other end of the connection,
+/// using a client dedicated to that server.
+pub async fn connect_to_remote_project(
+    connection: Connection,
+    client: Arc<Client>,
+    languages: Arc<LanguageRegistry>,
+    fs: Arc<dyn Fs>,
+    cx: AsyncAppContext,
+) -> Result<Model<Project>> {
+    cx.update(|cx| Project::init(&client, cx))?;
+    client
+        .connect_over(connection, &cx)
+        .await
+        .context("failed to connect to remote server")?;
+    let user_store = cx.new_model(|cx| UserStore::new(client.clone(), cx))?;
+    Project::remote(REMOTE_PROJECT_ID, client, user_store, languages, fs, cx).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(
+            SshConnectionOptions::parse_url("ssh://me@build-box:2222/home/me/zed"),
+            Some((
+                SshConnectionOptions {
+                    host: "build-box".into(),
+                    username: Some("me".into()),
+                    port: Some(2222),
+                },
+                PathBuf::from("/home/me/zed")
+            ))
+        );
+        assert_eq!(
+            SshConnectionOptions::parse_url("ssh://build-box"),
+            Some((
+                SshConnectionOptions {
+                    host: "build-box".into(),
+                    username: None,
+                    port: None,
+                },
+                PathBuf::from("/")
+            ))
+        );
+        assert_eq!(SshConnectionOptions::parse_url("ssh://:22/foo"), None);
+        assert_eq!(SshConnectionOptions::parse_url("ssh://host:port/foo"), None);
+        assert_eq!(SshConnectionOptions::parse_url("file:///foo"), None);
+        assert_eq!(
+            SshConnectionOptions::parse_url("ssh://-oProxyCommand=touch%20pwned/foo"),
+            None
+        );
+        assert_eq!(
+            SshConnectionOptions::parse_url("ssh://-oProxyCommand=sh@build-box/foo"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_local_command() {
+        let server = RemoteServer::Local(PathBuf::from("/opt/zed/zed-remote-server"));
+        assert_eq!(
+            server.command(&[PathBuf::from("/tmp/project")]),
+            (
+                PathBuf::from("/opt/zed/zed-remote-server"),
+                vec!["/tmp/project".to_string()]
+            )
+        );
+        assert_eq!(server.terminals(), RemoteTerminals::Local);
+    }
+
+    #[test]
+    fn test_ssh_command() {
+        let server = RemoteServer::Ssh(SshConnectionOptions {
+            host: "build-box".into(),
+            username: Some("me".into()),
+            port: Some(2222),
+        });
+        assert_eq!(
+            server.command(&[PathBuf::from("/home/me/my project")]),
+            (
+                PathBuf::from("ssh"),
+                vec![
+                    "-T".to_string(),
+                    "-o".into(),
+                    "BatchMode=yes".into(),
+                    "-p".into(),
+                    "2222".into(),
+                    "--".into(),
+                    "me@build-box".into(),
+                    "zed-remote-server '/home/me/my project'".into(),
+                ]
+            )
+        );
+        assert_eq!(
+            server.terminals(),
+            RemoteTerminals::Ssh {
+                args: vec![
+                    "-p".into(),
+                    "2222".into(),
+                    "--".into(),
+                    "me@build-box".into()
+                ]
+            }
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use client::{Client, UserStore};
+use fs::RealFs;
+use gpui::{App, Context as _, Task};
+use language::LanguageRegistry;
+use node_runtime::RealNodeRuntime;
+use project::Project;
+use rpc::Connection;
+use settings::{
+    default_settings, handle_settings_file_changes, watch_config_file, Settings, SettingsStore,
+};
+use std::{path::PathBuf, sync::Arc};
+use util::{http::HttpClientWithUrl, paths, ResultExt as _};
+
+fn main() {
+    // Standard output carries the connection to the client, so logs are written to standard
+    // error, which the client forwards to its own log.
+    env_logger::init();
+
+    let worktree_paths = std::env::args_os()
+        .skip(1)
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    if worktree_paths.is_empty() {
+        eprintln!("usage: {} PATH...", remote::REMOTE_SERVER_BINARY);
+        std::process::exit(1);
+    }
+
+    log::info!("========== starting remote server ==========");
+    let app = App::headless();
+    let fs = Arc::new(RealFs);
+    let user_settings_file_rx = watch_config_file(
+        &app.background_executor(),
+        fs.clone(),
+        paths::SETTINGS.clone(),
+    );
+
+    app.run(move |cx| {
+        release_channel::init(env!("CARGO_PKG_VERSION"), cx);
+
+        let mut store = SettingsStore::default();
+        store
+            .set_default_settings(default_settings().as_ref(), cx)
+            .unwrap();
+        cx.set_global(store);
+        handle_settings_file_changes(user_settings_file_rx, cx);
+        client::init_settings(cx);
+
+        let http = Arc::new(HttpClientWithUrl::new(
+            &client::ClientSettings::get_global(cx).server_url,
+        ));
+        let client = Client::new(Arc::new(clock::RealSystemClock), http.clone(), cx);
+        let mut languages =
+            LanguageRegistry::new(Task::ready(()), cx.background_executor().clone());
+        languages.set_language_server_download_dir(paths::LANGUAGES_DIR.clone());
+        let languages = Arc::new(languages);
+        let node_runtime = RealNodeRuntime::new(http);
+
+        language::init(cx);
+        languages::init(languages.clone(), node_runtime.clone(), cx);
+        Project::init(&client, cx);
+
+        let user_store = cx.new_model(|cx| UserStore::new(client.clone(), cx));
+        let project = Project::local(client.clone(), node_runtime, user_store, languages, fs, cx);
+        for path in worktree_paths {
+            project
+                .update(cx, |project, cx| {
+                    project.find_or_create_local_worktree(path, true, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+
+        let connection = Connection::over_byte_streams(
+            smol::Unblock::new(std::io::stdin()),
+            smol::Unblock::new(std::io::stdout()),
+        );
+        let server = remote_server::serve(project, client, connection, cx);
+        cx.spawn(|cx| async move {
+            server.await.log_err();
+            log::info!("client disconnected, shutting down");
+            cx.update(|cx| cx.quit()).ok();
+        })
+        .detach();
+    });
+}
@@ -0,0 +1,164 @@
+mod router;
+
+use anyhow::{Context as _, Result};
+use client::Client;
+use gpui::{AppContext, Model, Task};
+use project::Project;
+use router::Router;
+use rpc::Connection;
+use std::sync::Arc;
+
+/// Hosts the project for the client on the other end of the connection, until that client
+/// disconnects.
+///
+/// The project is hosted as if it were shared with a single guest through the collaboration
+/// server, so the client uses the same code paths it uses when joining a collaborator's project.
+/// `host_client` must be the client the project was created with, and must not be connected.
+pub fn serve(
+    project: Model<Project>,
+    host_client: Arc<Client>,
+    connection: Connection,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    let project = project.downgrade();
+    cx.spawn(|cx| async move {
+        let (host_connection, router_connection) = Connection::pair();
+        let router = Router::run(router_connection, connection, project, &cx)?;
+        host_client
+            .connect_over(host_connection, &cx)
+            .await
+            .context("failed to connect the project's client")?;
+        router.await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::UserStore;
+    use clock::FakeSystemClock;
+    use fs::{FakeFs, Fs};
+    use gpui::{Context as _, TestAppContext};
+    use language::LanguageRegistry;
+    use node_runtime::FakeNodeRuntime;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::http::FakeHttpClient;
+
+    #[gpui::test]
+    async fn test_remote_project(server_cx: &mut TestAppContext, client_cx: &mut TestAppContext) {
+        init_test(server_cx);
+        init_test(client_cx);
+
+        let fs = FakeFs::new(server_cx.executor());
+        fs.insert_tree(
+            "/code",
+            json!({
+                "project": {
+                    "a.txt": "one",
+                    "b.txt": "two",
+                },
+            }),
+        )
+        .await;
+
+        let host_client = server_cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let project = server_cx.update(|cx| {
+            Project::init(&host_client, cx);
+            let user_store = cx.new_model(|cx| UserStore::new(host_client.clone(), cx));
+            Project::local(
+                host_client.clone(),
+                FakeNodeRuntime::new(),
+                user_store,
+                Arc::new(LanguageRegistry::test(cx.background_executor().clone())),
+                fs.clone(),
+                cx,
+            )
+        });
+        project
+            .update(server_cx, |project, cx| {
+                project.find_or_create_local_worktree("/code/project", true, cx)
+            })
+            .await
+            .unwrap();
+
+        let (server_connection, client_connection) = Connection::pair();
+        let _server =
+            server_cx.update(|cx| serve(project.clone(), host_client, server_connection, cx));
+
+        let client = client_cx.update(|cx| {
+            Client::new(
+                Arc::new(FakeSystemClock::default()),
+                FakeHttpClient::with_404_response(),
+                cx,
+            )
+        });
+        let remote_project = remote::connect_to_remote_project(
+            client_connection,
+            client,
+            Arc::new(LanguageRegistry::test(client_cx.executor())),
+            FakeFs::new(client_cx.executor()),
+            client_cx.to_async(),
+        )
+        .await
+        .unwrap();
+        client_cx.executor().run_until_parked();
+
+        let worktree_id = remote_project.read_with(client_cx, |project, cx| {
+            let worktree = project.worktrees().next().unwrap().read(cx);
+            assert_eq!(
+                worktree
+                    .paths()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect::<Vec<_>>(),
+                ["a.txt", "b.txt"]
+            );
+            worktree.id()
+        });
+
+        // Edits made by the client are saved on the server's file system.
+        let buffer = remote_project
+            .update(client_cx, |project, cx| {
+                project.open_buffer((worktree_id, "a.txt"), cx)
+            })
+            .await
+            .unwrap();
+        buffer.update(client_cx, |buffer, cx| {
+            assert_eq!(buffer.text(), "one");
+            buffer.edit([(3..3, " and two")], None, cx);
+        });
+        remote_project
+            .update(client_cx, |project, cx| project.save_buffer(buffer, cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            fs.load(Path::new("/code/project/a.txt")).await.unwrap(),
+            "one and two"
+        );
+
+        // Files created on the server show up for the client.
+        fs.insert_file("/code/project/c.txt", "three".into()).await;
+        server_cx.executor().run_until_parked();
+        remote_project.read_with(client_cx, |project, cx| {
+            let worktree = project.worktrees().next().unwrap().read(cx);
+            assert!(worktree.entry_for_path("c.txt").is_some());
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            release_channel::init("0.0.0", cx);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+}
@@ -0,0 +1,324 @@
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use futures::{FutureExt as _, StreamExt as _};
+use gpui::{AsyncAppContext, Task, WeakModel};
+use project::Project;
+use remote::REMOTE_PROJECT_ID;
+use rpc::{
+    proto::{self, AnyTypedEnvelope, EnvelopedMessage, RequestMessage},
+    Connection, ConnectionId, ErrorExt as _, Peer, TypedEnvelope,
+};
+use std::{any::TypeId, sync::Arc};
+use util::TryFutureExt as _;
+
+const HOST_USER_ID: u64 = 1;
+const GUEST_USER_ID: u64 = 2;
+
+type MessageHandler =
+    Box<dyn Fn(&Router, Box<dyn AnyTypedEnvelope>, &mut AsyncAppContext) -> Result<()>>;
+
+/// Routes messages between the project hosted in this process and the client on the other end of
+/// the guest connection, playing the part the collaboration server plays for a shared project with
+/// a single guest.
+///
+/// Messages are handled in the order they arrive, and forwarded requests are sent before the next
+/// message is handled, so that buffer operations and worktree updates stay ordered.
+pub(crate) struct Router {
+    peer: Arc<Peer>,
+    host: ConnectionId,
+    guest: ConnectionId,
+    project: WeakModel<Project>,
+    handlers: HashMap<TypeId, MessageHandler>,
+}
+
+impl Router {
+    /// Starts routing messages. The returned task completes when the guest disconnects.
+    pub(crate) fn run(
+        host_connection: Connection,
+        guest_connection: Connection,
+        project: WeakModel<Project>,
+        cx: &AsyncAppContext,
+    ) -> Result<Task<Result<()>>> {
+        let executor = cx.background_executor().clone();
+        let peer = Peer::new(0);
+        let (host, host_io, host_incoming) = peer.add_connection(host_connection, {
+            let executor = executor.clone();
+            move |duration| executor.timer(duration)
+        });
+        let (guest, guest_io, guest_incoming) = peer.add_connection(guest_connection, {
+            let executor = executor.clone();
+            move |duration| executor.timer(duration)
+        });
+        executor.spawn(host_io.log_err()).detach();
+        let guest_io = executor.spawn(guest_io);
+
+        for connection_id in [host, guest] {
+            peer.send(
+                connection_id,
+                proto::Hello {
+                    peer_id: Some(connection_id.into()),
+                },
+            )?;
+        }
+
+        let mut router = Self {
+            peer,
+            host,
+            guest,
+            project,
+            handlers: HashMap::default(),
+        };
+        router
+            .add_handler(Self::join_project)
+            .add_handler(Self::leave_project)
+            .add_handler(Self::get_users)
+            .add_handler(Self::forward_and_ack::<proto::UpdateProject>)
+            .add_handler(Self::forward_and_ack::<proto::UpdateWorktree>)
+            .add_handler(Self::forward_to_guest::<proto::StartLanguageServer>)
+            .add_handler(Self::forward_to_guest::<proto::UpdateLanguageServer>)
+            .add_handler(Self::forward_to_guest::<proto::UpdateDiagnosticSummary>)
+            .add_handler(Self::forward_to_guest::<proto::UpdateWorktreeSettings>)
+            .add_handler(Self::forward_to_guest::<proto::CreateBufferForPeer>)
+            .add_handler(Self::forward_to_guest::<proto::RefreshInlayHints>)
+            .add_handler(Self::forward_to_guest::<proto::UpdateBufferFile>)
+            .add_handler(Self::forward_to_guest::<proto::BufferReloaded>)
+            .add_handler(Self::forward_to_guest::<proto::BufferSaved>)
+            .add_handler(Self::forward_to_guest::<proto::UpdateDiffBase>)
+            .add_handler(Self::update_buffer)
+            .add_handler(Self::forward_to_host::<proto::GetHover>)
+            .add_handler(Self::forward_to_host::<proto::GetDefinition>)
+            .add_handler(Self::forward_to_host::<proto::GetTypeDefinition>)
+            .add_handler(Self::forward_to_host::<proto::GetReferences>)
+            .add_handler(Self::forward_to_host::<proto::SearchProject>)
+            .add_handler(Self::forward_to_host::<proto::GetDocumentHighlights>)
+            .add_handler(Self::forward_to_host::<proto::GetProjectSymbols>)
+            .add_handler(Self::forward_to_host::<proto::OpenBufferForSymbol>)
+            .add_handler(Self::forward_to_host::<proto::OpenBufferById>)
+            .add_handler(Self::forward_to_host::<proto::SynchronizeBuffers>)
+            .add_handler(Self::forward_to_host::<proto::InlayHints>)
+            .add_handler(Self::forward_to_host::<proto::ResolveInlayHint>)
+            .add_handler(Self::forward_to_host::<proto::OpenBufferByPath>)
+            .add_handler(Self::forward_to_host::<proto::GetCompletions>)
+            .add_handler(Self::forward_to_host::<proto::ApplyCompletionAdditionalEdits>)
+            .add_handler(Self::forward_to_host::<proto::ResolveCompletionDocumentation>)
+            .add_handler(Self::forward_to_host::<proto::GetCodeActions>)
+            .add_handler(Self::forward_to_host::<proto::ApplyCodeAction>)
+            .add_handler(Self::forward_to_host::<proto::PrepareRename>)
+            .add_handler(Self::forward_to_host::<proto::PerformRename>)
+            .add_handler(Self::forward_to_host::<proto::ReloadBuffers>)
+            .add_handler(Self::forward_to_host::<proto::FormatBuffers>)
+            .add_handler(Self::forward_to_host::<proto::CreateProjectEntry>)
+            .add_handler(Self::forward_to_host::<proto::RenameProjectEntry>)
+            .add_handler(Self::forward_to_host::<proto::CopyProjectEntry>)
+            .add_handler(Self::forward_to_host::<proto::DeleteProjectEntry>)
+            .add_handler(Self::forward_to_host::<proto::ExpandProjectEntry>)
+            .add_handler(Self::forward_to_host::<proto::OnTypeFormatting>)
+            .add_handler(Self::forward_to_host::<proto::SaveBuffer>)
+            .add_handler(Self::forward_to_host::<proto::LspExtExpandMacro>);
+
+        Ok(cx.spawn(|mut cx| async move {
+            let mut guest_io = guest_io.fuse();
+            let mut incoming = futures::stream::select(host_incoming, guest_incoming);
+            loop {
+                futures::select_biased! {
+                    result = guest_io => return result,
+                    message = incoming.next() => {
+                        let Some(message) = message else {
+                            return Ok(());
+                        };
+                        router.handle_message(message, &mut cx);
+                    }
+                }
+            }
+        }))
+    }
+
+    fn add_handler<M: EnvelopedMessage>(
+        &mut self,
+        handler: fn(&Self, TypedEnvelope<M>, &mut AsyncAppContext) -> Result<()>,
+    ) -> &mut Self {
+        let prev_handler = self.handlers.insert(
+            TypeId::of::<M>(),
+            Box::new(move |router, envelope, cx| {
+                let envelope = envelope.into_any().downcast::<TypedEnvelope<M>>().unwrap();
+                handler(router, *envelope, cx)
+            }),
+        );
+        if prev_handler.is_some() {
+            panic!("registered a handler for the same message twice");
+        }
+        self
+    }
+
+    fn handle_message(&self, message: Box<dyn AnyTypedEnvelope>, cx: &mut AsyncAppContext) {
+        let type_name = message.payload_type_name();
+        if let Some(handler) = self.handlers.get(&message.payload_type_id()) {
+            if let Err(error) = handler(self, message, cx) {
+                log::error!("error handling message {}: {:?}", type_name, error);
+            }
+        } else {
+            log::warn!("unhandled message {}", type_name);
+        }
+    }
+
+    fn join_project(
+        &self,
+        request: TypedEnvelope<proto::JoinProject>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        if request.sender_id != self.guest || request.payload.project_id != REMOTE_PROJECT_ID {
+            return Err(anyhow!("invalid project join request"));
+        }
+
+        let project = self
+            .project
+            .upgrade()
+            .ok_or_else(|| anyhow!("project was dropped"))?;
+        let worktrees = project.update(cx, |project, cx| {
+            // The project is only shared once a client joins, so that the full state of its
+            // worktrees is streamed to that client.
+            if !project.is_shared() {
+                project.shared(REMOTE_PROJECT_ID, cx)?;
+            }
+            anyhow::Ok(project.worktree_metadata_protos(cx))
+        })??;
+
+        self.peer.send(
+            self.host,
+            proto::AddProjectCollaborator {
+                project_id: REMOTE_PROJECT_ID,
+                collaborator: Some(proto::Collaborator {
+                    peer_id: Some(self.guest.into()),
+                    replica_id: 1,
+                    user_id: GUEST_USER_ID,
+                }),
+            },
+        )?;
+        self.peer.respond(
+            request.receipt(),
+            proto::JoinProjectResponse {
+                project_id: REMOTE_PROJECT_ID,
+                replica_id: 1,
+                worktrees,
+                collaborators: vec![proto::Collaborator {
+                    peer_id: Some(self.host.into()),
+                    replica_id: 0,
+                    user_id: HOST_USER_ID,
+                }],
+                language_servers: Vec::new(),
+                role: proto::ChannelRole::Member.into(),
+            },
+        )?;
+        Ok(())
+    }
+
+    fn leave_project(
+        &self,
+        message: TypedEnvelope<proto::LeaveProject>,
+        _: &mut AsyncAppContext,
+    ) -> Result<()> {
+        self.peer.send(
+            self.host,
+            proto::RemoveProjectCollaborator {
+                project_id: message.payload.project_id,
+                peer_id: Some(self.guest.into()),
+            },
+        )
+    }
+
+    fn get_users(
+        &self,
+        request: TypedEnvelope<proto::GetUsers>,
+        _: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let users = request
+            .payload
+            .user_ids
+            .iter()
+            .filter_map(|&id| {
+                let github_login = match id {
+                    HOST_USER_ID => "host",
+                    GUEST_USER_ID => "you",
+                    _ => return None,
+                };
+                Some(proto::User {
+                    id,
+                    github_login: github_login.into(),
+                    avatar_url: String::new(),
+                })
+            })
+            .collect();
+        self.peer
+            .respond(request.receipt(), proto::UsersResponse { users })
+    }
+
+    fn update_buffer(
+        &self,
+        request: TypedEnvelope<proto::UpdateBuffer>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        if request.sender_id == self.guest {
+            self.forward_to_host(request, cx)
+        } else {
+            self.forward_and_ack(request, cx)
+        }
+    }
+
+    /// Forwards a request from the guest to the host, relaying the host's response.
+    fn forward_to_host<T: RequestMessage>(
+        &self,
+        request: TypedEnvelope<T>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        if request.sender_id != self.guest {
+            return Err(anyhow!("{} must be sent by the guest", T::NAME));
+        }
+
+        let receipt = request.receipt();
+        let response = self
+            .peer
+            .forward_request(self.guest, self.host, request.payload);
+        let peer = self.peer.clone();
+        cx.background_executor()
+            .spawn(
+                async move {
+                    match response.await {
+                        Ok(response) => peer.respond(receipt, response),
+                        Err(error) => peer.respond_with_error(receipt, error.to_proto()),
+                    }
+                }
+                .log_err(),
+            )
+            .detach();
+        Ok(())
+    }
+
+    /// Forwards an update from the host to the guest, acknowledging it once it has been sent.
+    fn forward_and_ack<T: RequestMessage<Response = proto::Ack>>(
+        &self,
+        request: TypedEnvelope<T>,
+        _: &mut AsyncAppContext,
+    ) -> Result<()> {
+        if request.sender_id != self.host {
+            return Err(anyhow!("{} must be sent by the host", T::NAME));
+        }
+
+        let receipt = request.receipt();
+        self.peer
+            .forward_send(self.host, self.guest, request.payload)?;
+        self.peer.respond(receipt, proto::Ack {})
+    }
+
+    fn forward_to_guest<T: EnvelopedMessage>(
+        &self,
+        message: TypedEnvelope<T>,
+        _: &mut AsyncAppContext,
+    ) -> Result<()> {
+        if message.sender_id != self.host {
+            return Err(anyhow!("{} must be sent by the host", T::NAME));
+        }
+
+        self.peer
+            .forward_send(self.host, self.guest, message.payload)
+    }
+}
@@ -49,6 +49,17 @@ pub struct Mention {
 
 impl RichText {
     pub fn element(&self, id: ElementId, cx: &WindowContext) -> AnyElement {
+        self.element_with_link_handler(id, |_, _| false, cx)
+    }
+
+    /// Renders the text like [`Self::element`], but first passes clicked links to `handle_link`,
+    /// which returns whether it handled the link. Unhandled web links are opened in the browser.
+    pub fn element_with_link_handler(
+        &self,
+        id: ElementId,
+        handle_link: impl Fn(&str, &mut WindowContext) -> bool + 'static,
+        cx: &WindowContext,
+    ) -> AnyElement {
         let theme = cx.theme();
         let code_background = theme.colors().surface_background;
 
@@ -103,6 +114,9 @@ impl RichText {
             let link_urls = self.link_urls.clone();
             move |ix, cx| {
                 let url = &link_urls[ix];
+                if handle_link(url, cx) {
+                    return;
+                }
                 if url.starts_with("http") {
                     cx.open_url(url);
                 }
@@ -1,5 +1,7 @@
 use async_tungstenite::tungstenite::Message as WebSocketMessage;
+use futures::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
 use futures::{SinkExt as _, StreamExt as _};
+use std::io;
 
 pub struct Connection {
     pub(crate) tx:
@@ -32,6 +34,48 @@ impl Connection {
         self.tx.send(message).await
     }
 
+    /// Sends messages over a pair of byte streams, such as the standard input and output of a
+    /// process, by writing each one as a frame prefixed with its kind and length.
+    pub fn over_byte_streams<R, W>(reader: R, writer: W) -> Self
+    where
+        R: 'static + Send + Unpin + AsyncRead,
+        W: 'static + Send + Unpin + AsyncWrite,
+    {
+        let tx = futures::sink::unfold(writer, |mut writer, message| async move {
+            write_frame(&mut writer, message).await?;
+            Ok::<_, anyhow::Error>(writer)
+        });
+        let rx = futures::stream::unfold(Some(reader), |reader| async move {
+            let mut reader = reader?;
+            match read_frame(&mut reader).await {
+                Ok(Some(message)) => Some((Ok(message), Some(reader))),
+                Ok(None) => None,
+                Err(error) => Some((Err(error.into()), None)),
+            }
+        });
+        Self {
+            tx: Box::new(Box::pin(tx)),
+            rx: Box::new(Box::pin(rx)),
+        }
+    }
+
+    /// Creates two connections that are joined to each other in memory, for talking to a peer
+    /// that runs in the same process.
+    pub fn pair() -> (Self, Self) {
+        let (a_tx, a_rx) = futures::channel::mpsc::unbounded();
+        let (b_tx, b_rx) = futures::channel::mpsc::unbounded();
+        (
+            Self {
+                tx: Box::new(a_tx.sink_map_err(anyhow::Error::new)),
+                rx: Box::new(b_rx.map(Ok)),
+            },
+            Self {
+                tx: Box::new(b_tx.sink_map_err(anyhow::Error::new)),
+                rx: Box::new(a_rx.map(Ok)),
+            },
+        )
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn in_memory(
         executor: gpui::BackgroundExecutor,
@@ -105,3 +149,80 @@ impl Connection {
         }
     }
 }
+
+const BINARY_FRAME: u8 = 0;
+const PING_FRAME: u8 = 1;
+const PONG_FRAME: u8 = 2;
+const CLOSE_FRAME: u8 = 3;
+
+async fn write_frame(
+    writer: &mut (impl AsyncWrite + Unpin),
+    message: WebSocketMessage,
+) -> io::Result<()> {
+    let (kind, payload) = match message {
+        WebSocketMessage::Binary(payload) => (BINARY_FRAME, payload),
+        WebSocketMessage::Ping(_) => (PING_FRAME, Vec::new()),
+        WebSocketMessage::Pong(_) => (PONG_FRAME, Vec::new()),
+        WebSocketMessage::Close(_) => (CLOSE_FRAME, Vec::new()),
+        WebSocketMessage::Text(_) | WebSocketMessage::Frame(_) => return Ok(()),
+    };
+    let len = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "message is too long"))?;
+    writer.write_all(&[kind]).await?;
+    writer.write_all(&len.to_le_bytes()).await?;
+    writer.write_all(&payload).await?;
+    writer.flush().await
+}
+
+/// Reads the next frame, returning `None` once the stream has ended.
+async fn read_frame(reader: &mut (impl AsyncRead + Unpin)) -> io::Result<Option<WebSocketMessage>> {
+    let mut kind = [0; 1];
+    match reader.read_exact(&mut kind).await {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    let mut len = [0; 4];
+    reader.read_exact(&mut len).await?;
+    let mut payload = vec![0; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut payload).await?;
+
+    match kind[0] {
+        BINARY_FRAME => Ok(Some(WebSocketMessage::Binary(payload))),
+        PING_FRAME => Ok(Some(WebSocketMessage::Ping(payload))),
+        PONG_FRAME => Ok(Some(WebSocketMessage::Pong(payload))),
+        CLOSE_FRAME => Ok(Some(WebSocketMessage::Close(None))),
+        kind => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid frame kind {kind}"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames() {
+        futures::executor::block_on(async {
+            let messages = [
+                WebSocketMessage::Binary(vec![1, 2, 3]),
+                WebSocketMessage::Ping(Vec::new()),
+                WebSocketMessage::Binary(Vec::new()),
+                WebSocketMessage::Pong(Vec::new()),
+                WebSocketMessage::Close(None),
+            ];
+            let mut bytes = Vec::new();
+            for message in messages.clone() {
+                write_frame(&mut bytes, message).await.unwrap();
+            }
+
+            let mut reader = futures::io::Cursor::new(bytes);
+            for message in messages {
+                assert_eq!(read_frame(&mut reader).await.unwrap(), Some(message));
+            }
+            assert_eq!(read_frame(&mut reader).await.unwrap(), None);
+        });
+    }
+}
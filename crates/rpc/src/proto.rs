@@ -136,7 +136,10 @@ impl fmt::Display for PeerId {
 messages!(
     (Ack, Foreground),
     (AckBufferOperation, Background),
+    (AckChannelActivity, Foreground),
     (AckChannelMessage, Background),
+    (AddChannelMessageReaction, Foreground),
+    (AddComment, Foreground),
     (AddNotification, Foreground),
     (AddProjectCollaborator, Foreground),
     (ApplyCodeAction, Background),
@@ -148,11 +151,16 @@ messages!(
     (Call, Foreground),
     (CallCanceled, Foreground),
     (CancelCall, Foreground),
+    (ChannelActivityAdded, Foreground),
     (ChannelMessageSent, Foreground),
+    (ChannelMessageUpdate, Foreground),
+    (CommentThreadResponse, Foreground),
+    (CommentThreadUpdated, Foreground),
     (CopyProjectEntry, Foreground),
     (CreateBufferForPeer, Foreground),
     (CreateChannel, Foreground),
     (CreateChannelResponse, Foreground),
+    (CreateCommentThread, Foreground),
     (CreateProjectEntry, Foreground),
     (CreateRoom, Foreground),
     (CreateRoomResponse, Foreground),
@@ -168,13 +176,21 @@ messages!(
     (FormatBuffers, Foreground),
     (FormatBuffersResponse, Foreground),
     (FuzzySearchUsers, Foreground),
+    (GetChannelActivity, Foreground),
+    (GetChannelActivityResponse, Foreground),
+    (GetChannelBufferCheckpoints, Background),
+    (GetChannelBufferCheckpointsResponse, Background),
     (GetChannelMembers, Foreground),
     (GetChannelMembersResponse, Foreground),
+    (GetChannelMessageEdits, Background),
+    (GetChannelMessageEditsResponse, Background),
     (GetChannelMessages, Background),
     (GetChannelMessagesById, Background),
     (GetChannelMessagesResponse, Background),
     (GetCodeActions, Background),
     (GetCodeActionsResponse, Background),
+    (GetCommentThreads, Foreground),
+    (GetCommentThreadsResponse, Foreground),
     (GetCompletions, Background),
     (GetCompletionsResponse, Background),
     (GetDefinition, Background),
@@ -215,6 +231,8 @@ messages!(
     (LeaveChannelChat, Foreground),
     (LeaveProject, Foreground),
     (LeaveRoom, Foreground),
+    (LoadChannelBufferCheckpoint, Background),
+    (LoadChannelBufferCheckpointResponse, Background),
     (MarkNotificationRead, Foreground),
     (MoveChannel, Foreground),
     (OnTypeFormatting, Background),
@@ -239,12 +257,15 @@ messages!(
     (ReloadBuffersResponse, Foreground),
     (RemoveChannelMember, Foreground),
     (RemoveChannelMessage, Foreground),
+    (RemoveChannelMessageReaction, Foreground),
     (RemoveContact, Foreground),
     (RemoveProjectCollaborator, Foreground),
     (RenameChannel, Foreground),
     (RenameChannelResponse, Foreground),
     (RenameProjectEntry, Foreground),
     (RequestContact, Foreground),
+    (RequestTerminalControl, Foreground),
+    (RequestTerminalControlResponse, Foreground),
     (ResolveCompletionDocumentation, Background),
     (ResolveCompletionDocumentationResponse, Background),
     (ResolveInlayHint, Background),
@@ -255,23 +276,28 @@ messages!(
     (SaveBuffer, Foreground),
     (SetChannelMemberRole, Foreground),
     (SetChannelVisibility, Foreground),
+    (SetCommentThreadResolved, Foreground),
     (SearchProject, Background),
     (SearchProjectResponse, Background),
     (SendChannelMessage, Background),
     (SendChannelMessageResponse, Background),
     (ShareProject, Foreground),
     (ShareProjectResponse, Foreground),
+    (ShareTerminal, Foreground),
     (ShowContacts, Foreground),
     (StartLanguageServer, Foreground),
     (SynchronizeBuffers, Foreground),
     (SynchronizeBuffersResponse, Foreground),
+    (TerminalInput, Foreground),
     (Test, Foreground),
     (Unfollow, Foreground),
     (UnshareProject, Foreground),
+    (UnshareTerminal, Foreground),
     (UpdateBuffer, Foreground),
     (UpdateBufferFile, Foreground),
     (UpdateChannelBuffer, Foreground),
     (UpdateChannelBufferCollaborators, Foreground),
+    (UpdateChannelMessage, Foreground),
     (UpdateChannels, Foreground),
     (UpdateUserChannels, Foreground),
     (UpdateContacts, Foreground),
@@ -283,6 +309,7 @@ messages!(
     (UpdateParticipantLocation, Foreground),
     (UpdateProject, Foreground),
     (UpdateProjectCollaborator, Foreground),
+    (UpdateTerminal, Foreground),
     (UpdateWorktree, Foreground),
     (UpdateWorktreeSettings, Foreground),
     (UsersResponse, Foreground),
@@ -292,6 +319,8 @@ messages!(
 );
 
 request_messages!(
+    (AddChannelMessageReaction, ChannelMessageUpdate),
+    (AddComment, CommentThreadResponse),
     (ApplyCodeAction, ApplyCodeActionResponse),
     (
         ApplyCompletionAdditionalEdits,
@@ -301,6 +330,7 @@ request_messages!(
     (CancelCall, Ack),
     (CopyProjectEntry, ProjectEntryResponse),
     (CreateChannel, CreateChannelResponse),
+    (CreateCommentThread, CommentThreadResponse),
     (CreateProjectEntry, ProjectEntryResponse),
     (CreateRoom, CreateRoomResponse),
     (DeclineCall, Ack),
@@ -310,10 +340,17 @@ request_messages!(
     (Follow, FollowResponse),
     (FormatBuffers, FormatBuffersResponse),
     (FuzzySearchUsers, UsersResponse),
+    (GetChannelActivity, GetChannelActivityResponse),
+    (
+        GetChannelBufferCheckpoints,
+        GetChannelBufferCheckpointsResponse
+    ),
     (GetChannelMembers, GetChannelMembersResponse),
+    (GetChannelMessageEdits, GetChannelMessageEditsResponse),
     (GetChannelMessages, GetChannelMessagesResponse),
     (GetChannelMessagesById, GetChannelMessagesResponse),
     (GetCodeActions, GetCodeActionsResponse),
+    (GetCommentThreads, GetCommentThreadsResponse),
     (GetCompletions, GetCompletionsResponse),
     (GetDefinition, GetDefinitionResponse),
     (GetImplementation, GetImplementationResponse),
@@ -336,6 +373,10 @@ request_messages!(
     (JoinRoom, JoinRoomResponse),
     (LeaveChannelBuffer, Ack),
     (LeaveRoom, Ack),
+    (
+        LoadChannelBufferCheckpoint,
+        LoadChannelBufferCheckpointResponse
+    ),
     (MarkNotificationRead, Ack),
     (MoveChannel, Ack),
     (OnTypeFormatting, OnTypeFormattingResponse),
@@ -351,10 +392,12 @@ request_messages!(
     (ReloadBuffers, ReloadBuffersResponse),
     (RemoveChannelMember, Ack),
     (RemoveChannelMessage, Ack),
+    (RemoveChannelMessageReaction, ChannelMessageUpdate),
     (RemoveContact, Ack),
     (RenameChannel, RenameChannelResponse),
     (RenameProjectEntry, ProjectEntryResponse),
     (RequestContact, Ack),
+    (RequestTerminalControl, RequestTerminalControlResponse),
     (
         ResolveCompletionDocumentation,
         ResolveCompletionDocumentationResponse
@@ -367,10 +410,13 @@ request_messages!(
     (SendChannelMessage, SendChannelMessageResponse),
     (SetChannelMemberRole, Ack),
     (SetChannelVisibility, Ack),
+    (SetCommentThreadResolved, CommentThreadResponse),
     (ShareProject, ShareProjectResponse),
     (SynchronizeBuffers, SynchronizeBuffersResponse),
+    (TerminalInput, Ack),
     (Test, Test),
     (UpdateBuffer, Ack),
+    (UpdateChannelMessage, ChannelMessageUpdate),
     (UpdateParticipantLocation, Ack),
     (UpdateProject, Ack),
     (UpdateWorktree, Ack),
@@ -413,13 +459,17 @@ entity_messages!(
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
+    RequestTerminalControl,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
     SaveBuffer,
     SearchProject,
+    ShareTerminal,
     StartLanguageServer,
     SynchronizeBuffers,
+    TerminalInput,
     UnshareProject,
+    UnshareTerminal,
     UpdateBuffer,
     UpdateBufferFile,
     UpdateDiagnosticSummary,
@@ -427,6 +477,7 @@ entity_messages!(
     UpdateLanguageServer,
     UpdateProject,
     UpdateProjectCollaborator,
+    UpdateTerminal,
     UpdateWorktree,
     UpdateWorktreeSettings,
     LspExtExpandMacro,
@@ -435,6 +486,7 @@ entity_messages!(
 entity_messages!(
     {channel_id, Channel},
     ChannelMessageSent,
+    ChannelMessageUpdate,
     RemoveChannelMessage,
     UpdateChannelBuffer,
     UpdateChannelBufferCollaborators,
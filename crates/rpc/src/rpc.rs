@@ -12,3 +12,6 @@ pub use peer::*;
 mod macros;
 
 pub const PROTOCOL_VERSION: u32 = 68;
+
+/// The length, in bytes, of the longest channel message body that the server accepts.
+pub const MAX_CHANNEL_MESSAGE_LEN: usize = 1024;
@@ -8,6 +8,24 @@ pub struct SearchHistory {
 }
 
 impl SearchHistory {
+    /// Creates a history of the given entries, from oldest to newest, with none of them selected.
+    pub fn new(entries: impl IntoIterator<Item = String>) -> Self {
+        let mut history = entries
+            .into_iter()
+            .collect::<SmallVec<[String; SEARCH_HISTORY_LIMIT]>>();
+        if history.len() > SEARCH_HISTORY_LIMIT {
+            history.drain(..history.len() - SEARCH_HISTORY_LIMIT);
+        }
+        Self {
+            history,
+            selected: None,
+        }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.history
+    }
+
     pub fn add(&mut self, search_string: String) {
         if let Some(i) = self.selected {
             if search_string == self.history[i] {
@@ -157,6 +175,19 @@ mod tests {
         assert_eq!(search_history.current(), Some("TypeScript"));
     }
 
+    #[test]
+    fn test_new() {
+        let mut search_history =
+            SearchHistory::new((0..SEARCH_HISTORY_LIMIT + 2).map(|i| format!("item{i}")));
+        assert_eq!(search_history.entries().len(), SEARCH_HISTORY_LIMIT);
+        assert_eq!(search_history.entries()[0], "item2");
+        assert_eq!(search_history.current(), None);
+        assert_eq!(
+            search_history.previous(),
+            Some(format!("item{}", SEARCH_HISTORY_LIMIT + 1).as_str())
+        );
+    }
+
     #[test]
     fn test_reset_selection() {
         let mut search_history = SearchHistory::default();
@@ -1,9 +1,10 @@
 use gpui::{Action, SharedString};
+use serde::{Deserialize, Serialize};
 
 use crate::{ActivateRegexMode, ActivateSemanticMode, ActivateTextMode};
 
 // TODO: Update the default search mode to get from config
-#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum SearchMode {
     #[default]
     Text,
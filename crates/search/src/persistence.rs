@@ -0,0 +1,190 @@
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{AppContext, Global};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use util::ResultExt;
+
+use crate::{history::SearchHistory, SearchMode, SearchOptions};
+
+const SEARCH_HISTORY_KEY: &str = "ProjectSearchHistory";
+const SAVED_SEARCHES_KEY: &str = "ProjectSavedSearches";
+
+/// Project search state shared by all search views: the queries and path filters searched for,
+/// and the searches saved by name. It's written to the database once it was loaded from it.
+#[derive(Default)]
+pub(crate) struct SearchStore {
+    pub queries: SearchHistory,
+    pub included_files: SearchHistory,
+    pub excluded_files: SearchHistory,
+    pub saved_searches: Vec<SavedSearch>,
+    persistent: bool,
+}
+
+impl Global for SearchStore {}
+
+/// A project search that can be run again by name.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    pub mode: SearchMode,
+    pub whole_word: bool,
+    pub case_sensitive: bool,
+    pub include_ignored: bool,
+    pub included_files: String,
+    pub excluded_files: String,
+    pub open_files_only: bool,
+}
+
+impl SavedSearch {
+    pub fn options(&self) -> SearchOptions {
+        let mut options = SearchOptions::NONE;
+        options.set(SearchOptions::WHOLE_WORD, self.whole_word);
+        options.set(SearchOptions::CASE_SENSITIVE, self.case_sensitive);
+        options.set(SearchOptions::INCLUDE_IGNORED, self.include_ignored);
+        options
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct SerializedSearchHistory {
+    queries: Vec<String>,
+    included_files: Vec<String>,
+    excluded_files: Vec<String>,
+}
+
+impl SearchStore {
+    /// Reads the history and the saved searches from the database, keeping anything that was
+    /// added in the meantime.
+    pub fn load(cx: &mut AppContext) {
+        cx.spawn(|mut cx| async move {
+            let (history, saved_searches) = cx
+                .background_executor()
+                .spawn(async move {
+                    (
+                        read_kvp::<SerializedSearchHistory>(SEARCH_HISTORY_KEY),
+                        read_kvp::<Vec<SavedSearch>>(SAVED_SEARCHES_KEY),
+                    )
+                })
+                .await;
+
+            cx.update(|cx| {
+                let store = cx.default_global::<Self>();
+                let history = history.unwrap_or_default();
+                let mut loaded = Self {
+                    queries: SearchHistory::new(history.queries),
+                    included_files: SearchHistory::new(history.included_files),
+                    excluded_files: SearchHistory::new(history.excluded_files),
+                    saved_searches: saved_searches.unwrap_or_default(),
+                    persistent: true,
+                };
+                for query in store.queries.entries() {
+                    loaded.queries.add(query.clone());
+                }
+                for included_files in store.included_files.entries() {
+                    loaded.included_files.add(included_files.clone());
+                }
+                for excluded_files in store.excluded_files.entries() {
+                    loaded.excluded_files.add(excluded_files.clone());
+                }
+                for saved_search in store.saved_searches.drain(..) {
+                    loaded.save_search(saved_search);
+                }
+                *store = loaded;
+            })
+            .log_err();
+        })
+        .detach();
+    }
+
+    /// Updates the store, writing it to the database if it was loaded from there.
+    pub fn update(cx: &mut AppContext, update: impl FnOnce(&mut Self)) {
+        let store = cx.default_global::<Self>();
+        update(store);
+        if !store.persistent {
+            return;
+        }
+
+        let history = SerializedSearchHistory {
+            queries: store.queries.entries().to_vec(),
+            included_files: store.included_files.entries().to_vec(),
+            excluded_files: store.excluded_files.entries().to_vec(),
+        };
+        let saved_searches = store.saved_searches.clone();
+        cx.background_executor()
+            .spawn(async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        SEARCH_HISTORY_KEY.into(),
+                        serde_json::to_string(&history)?,
+                    )
+                    .await?;
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        SAVED_SEARCHES_KEY.into(),
+                        serde_json::to_string(&saved_searches)?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Returns the store's history of a kind of input, with none of its entries selected, for a
+    /// search view to navigate through.
+    pub fn history(cx: &AppContext, history: impl Fn(&Self) -> &SearchHistory) -> SearchHistory {
+        cx.try_global::<Self>()
+            .map(|store| SearchHistory::new(history(store).entries().iter().cloned()))
+            .unwrap_or_default()
+    }
+
+    /// Saves a search, replacing any other search with the same name.
+    pub fn save_search(&mut self, search: SavedSearch) {
+        if let Some(existing) = self
+            .saved_searches
+            .iter_mut()
+            .find(|existing| existing.name == search.name)
+        {
+            *existing = search;
+        } else {
+            self.saved_searches.push(search);
+        }
+    }
+
+    pub fn delete_search(&mut self, name: &str) {
+        self.saved_searches.retain(|search| search.name != name);
+    }
+}
+
+fn read_kvp<T: DeserializeOwned>(key: &str) -> Option<T> {
+    KEY_VALUE_STORE
+        .read_kvp(key)
+        .log_err()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).log_err())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saving_searches() {
+        let mut store = SearchStore::default();
+        let search = |name: &str, query: &str| SavedSearch {
+            name: name.into(),
+            query: query.into(),
+            ..Default::default()
+        };
+
+        store.save_search(search("todos", "TODO"));
+        store.save_search(search("fixmes", "FIXME"));
+        store.save_search(search("todos", "TODO|XXX"));
+        assert_eq!(
+            store.saved_searches,
+            [search("todos", "TODO|XXX"), search("fixmes", "FIXME")]
+        );
+
+        store.delete_search("todos");
+        assert_eq!(store.saved_searches, [search("fixmes", "FIXME")]);
+    }
+}
@@ -1,16 +1,18 @@
 use crate::{
-    history::SearchHistory, mode::SearchMode, ActivateRegexMode, ActivateSemanticMode,
-    ActivateTextMode, CycleMode, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
-    SearchOptions, SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
-    ToggleReplace, ToggleWholeWord,
+    history::SearchHistory,
+    mode::SearchMode,
+    persistence::{SavedSearch, SearchStore},
+    ActivateRegexMode, ActivateSemanticMode, ActivateTextMode, CycleMode, NextHistoryQuery,
+    PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions, SelectNextMatch, SelectPrevMatch,
+    ToggleCaseSensitive, ToggleIncludeIgnored, ToggleReplace, ToggleWholeWord,
 };
-use anyhow::{Context as _, Result};
+use anyhow::{anyhow, Context as _, Result};
 use collections::HashMap;
 use editor::{
     actions::SelectAll,
     items::active_match_index,
     scroll::{Autoscroll, Axis},
-    Anchor, Editor, EditorEvent, MultiBuffer, MAX_TAB_TITLE_LEN,
+    Anchor, Editor, EditorEvent, ExcerptId, MultiBuffer, MAX_TAB_TITLE_LEN,
 };
 use editor::{EditorElement, EditorStyle};
 use gpui::{
@@ -25,6 +27,7 @@ use project::{
     search::{SearchInputs, SearchQuery},
     Project,
 };
+use rpc::proto::{self, PeerId};
 use semantic_index::{SemanticIndex, SemanticIndexStatus};
 
 use collections::HashSet;
@@ -35,6 +38,7 @@ use std::{
     mem,
     ops::{Not, Range},
     path::{Path, PathBuf},
+    sync::Arc,
     time::{Duration, Instant},
 };
 use theme::ThemeSettings;
@@ -46,10 +50,11 @@ use ui::{
 };
 use util::{paths::PathMatcher, ResultExt as _};
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, ItemHandle},
+    item::{BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent, ItemHandle},
+    register_followable_item,
     searchable::{Direction, SearchableItem, SearchableItemHandle},
-    ItemNavHistory, Pane, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
-    WorkspaceId,
+    ItemNavHistory, Pane, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, ViewId,
+    Workspace, WorkspaceId,
 };
 
 const MIN_INPUT_WIDTH_REMS: f32 = 15.;
@@ -57,7 +62,15 @@ const MAX_INPUT_WIDTH_REMS: f32 = 30.;
 
 actions!(
     project_search,
-    [SearchInNew, ToggleFocus, NextField, ToggleFilters]
+    [
+        SearchInNew,
+        ToggleFocus,
+        NextField,
+        ToggleFilters,
+        ToggleOpenFilesOnly,
+        SaveSearch,
+        OpenSavedSearch
+    ]
 );
 
 #[derive(Default)]
@@ -67,10 +80,14 @@ impl Global for ActiveSettings {}
 
 pub fn init(cx: &mut AppContext) {
     cx.set_global(ActiveSettings::default());
+    register_followable_item::<ProjectSearchView>(cx);
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         register_workspace_action(workspace, move |search_bar, _: &ToggleFilters, cx| {
             search_bar.toggle_filters(cx);
         });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleOpenFilesOnly, cx| {
+            search_bar.toggle_open_files_only(cx);
+        });
         register_workspace_action(workspace, move |search_bar, _: &ToggleCaseSensitive, cx| {
             search_bar.toggle_search_option(SearchOptions::CASE_SENSITIVE, cx);
         });
@@ -112,6 +129,7 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action_for_present_search(workspace, |workspace, action, cx| {
             ProjectSearchView::search_in_new(workspace, action, cx)
         });
+        crate::saved_searches::register(workspace);
 
         // Both on present and dismissed search, we need to unconditionally handle those actions to focus from the editor.
         workspace.register_action(move |workspace, action: &DeploySearch, cx| {
@@ -142,6 +160,8 @@ struct ProjectSearch {
     active_query: Option<SearchQuery>,
     search_id: usize,
     search_history: SearchHistory,
+    included_files_history: SearchHistory,
+    excluded_files_history: SearchHistory,
     no_results: Option<bool>,
     limit_reached: bool,
 }
@@ -169,8 +189,13 @@ pub struct ProjectSearchView {
     included_files_editor: View<Editor>,
     excluded_files_editor: View<Editor>,
     filters_enabled: bool,
+    open_files_only: bool,
+    open_file_paths: Vec<PathBuf>,
+    workspace: Option<WeakView<Workspace>>,
     replace_enabled: bool,
     current_mode: SearchMode,
+    /// Set when this view mirrors the project search of a followed collaborator.
+    remote_id: Option<ViewId>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -185,6 +210,7 @@ struct ProjectSearchSettings {
     search_options: SearchOptions,
     filters_enabled: bool,
     current_mode: SearchMode,
+    open_files_only: bool,
 }
 
 pub struct ProjectSearchBar {
@@ -204,7 +230,9 @@ impl ProjectSearch {
             match_ranges: Default::default(),
             active_query: None,
             search_id: 0,
-            search_history: SearchHistory::default(),
+            search_history: SearchStore::history(cx, |store| &store.queries),
+            included_files_history: SearchStore::history(cx, |store| &store.included_files),
+            excluded_files_history: SearchStore::history(cx, |store| &store.excluded_files),
             no_results: None,
             limit_reached: false,
         }
@@ -221,17 +249,39 @@ impl ProjectSearch {
             active_query: self.active_query.clone(),
             search_id: self.search_id,
             search_history: self.search_history.clone(),
+            included_files_history: self.included_files_history.clone(),
+            excluded_files_history: self.excluded_files_history.clone(),
             no_results: self.no_results,
             limit_reached: self.limit_reached,
         })
     }
 
+    fn history(&mut self, panel: InputPanel) -> &mut SearchHistory {
+        match panel {
+            InputPanel::Query => &mut self.search_history,
+            InputPanel::Include => &mut self.included_files_history,
+            InputPanel::Exclude => &mut self.excluded_files_history,
+        }
+    }
+
+    fn add_to_history(&mut self, panel: InputPanel, entry: String, cx: &mut ModelContext<Self>) {
+        if entry.is_empty() {
+            return;
+        }
+        self.history(panel).add(entry.clone());
+        SearchStore::update(cx, |store| match panel {
+            InputPanel::Query => store.queries.add(entry),
+            InputPanel::Include => store.included_files.add(entry),
+            InputPanel::Exclude => store.excluded_files.add(entry),
+        });
+    }
+
     fn search(&mut self, query: SearchQuery, cx: &mut ModelContext<Self>) {
         let search = self
             .project
             .update(cx, |project, cx| project.search(query.clone(), cx));
         self.search_id += 1;
-        self.search_history.add(query.as_str().to_string());
+        self.add_to_history(InputPanel::Query, query.as_str().to_string(), cx);
         self.active_query = Some(query);
         self.match_ranges.clear();
         self.pending_search = Some(cx.spawn(|this, mut cx| async move {
@@ -298,7 +348,7 @@ impl ProjectSearch {
         });
         self.search_id += 1;
         self.match_ranges.clear();
-        self.search_history.add(inputs.as_str().to_string());
+        self.add_to_history(InputPanel::Query, inputs.as_str().to_string(), cx);
         self.no_results = None;
         self.pending_search = Some(cx.spawn(|this, mut cx| async move {
             let results = search?.await.log_err()?;
@@ -592,6 +642,7 @@ impl Item for ProjectSearchView {
     }
 
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.workspace = Some(workspace.weak_handle());
         self.results_editor
             .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
     }
@@ -648,6 +699,180 @@ impl Item for ProjectSearchView {
     }
 }
 
+impl FollowableItem for ProjectSearchView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        let Some(proto::view::Variant::Editor(results)) =
+            self.results_editor.read(cx).to_state_proto(cx)
+        else {
+            return None;
+        };
+        Some(proto::view::Variant::ProjectSearch(
+            proto::view::ProjectSearch {
+                query: self.active_query_proto(cx),
+                results: Some(results),
+            },
+        ))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<Result<View<Self>>>> {
+        let Some(proto::view::Variant::ProjectSearch(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::ProjectSearch(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let project = workspace.read(cx).project().clone();
+        let results = state.results.unwrap_or_default();
+        let existing = pane
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|view| view.read(cx).remote_id == Some(remote_id));
+        // An existing view already holds the leader's excerpts, which keep their ids.
+        let mut inserted_excerpts = Vec::new();
+        if existing.is_none() {
+            let mut previous_excerpt_id = ExcerptId::min().to_proto();
+            for excerpt in results.excerpts {
+                let excerpt_id = excerpt.id;
+                inserted_excerpts.push(proto::ExcerptInsertion {
+                    excerpt: Some(excerpt),
+                    previous_excerpt_id: Some(previous_excerpt_id),
+                });
+                previous_excerpt_id = excerpt_id;
+            }
+        }
+        let view = existing.unwrap_or_else(|| {
+            let model = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+            cx.new_view(|cx| {
+                let mut view = Self::new(model, cx, None);
+                view.remote_id = Some(remote_id);
+                view
+            })
+        });
+
+        let update = proto::update_view::ProjectSearch {
+            query: state.query,
+            results: Some(proto::update_view::Editor {
+                inserted_excerpts,
+                selections: results.selections,
+                pending_selection: results.pending_selection,
+                scroll_top_anchor: results.scroll_top_anchor,
+                scroll_x: results.scroll_x,
+                scroll_y: results.scroll_y,
+                ..Default::default()
+            }),
+        };
+        let update = view.update(cx, |view, cx| {
+            view.apply_update_proto(
+                &project,
+                proto::update_view::Variant::ProjectSearch(update),
+                cx,
+            )
+        });
+        Some(cx.spawn(|_| async move {
+            update.await?;
+            Ok(view)
+        }))
+    }
+
+    fn to_follow_event(event: &ViewEvent) -> Option<FollowEvent> {
+        match event {
+            // Mirroring the leader's query edits the query editor, so only local selections and
+            // scrolling stop following.
+            ViewEvent::EditorEvent(
+                EditorEvent::SelectionsChanged { local }
+                | EditorEvent::ScrollPositionChanged { local, .. },
+            ) if *local => Some(FollowEvent::Unfollow),
+            _ => None,
+        }
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &ViewEvent,
+        update: &mut Option<proto::update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        let update = update
+            .get_or_insert_with(|| proto::update_view::Variant::ProjectSearch(Default::default()));
+        let proto::update_view::Variant::ProjectSearch(update) = update else {
+            return false;
+        };
+
+        match event {
+            ViewEvent::UpdateTab => {
+                update.query = self.active_query_proto(cx);
+                true
+            }
+            ViewEvent::EditorEvent(event) => {
+                let mut results = update
+                    .results
+                    .take()
+                    .map(proto::update_view::Variant::Editor);
+                let changed =
+                    self.results_editor
+                        .read(cx)
+                        .add_event_to_update_proto(event, &mut results, cx);
+                if let Some(proto::update_view::Variant::Editor(results)) = results {
+                    update.results = Some(results);
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        project: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let proto::update_view::Variant::ProjectSearch(message) = message else {
+            return Task::ready(Err(anyhow!(
+                "received a non-search update for a project search"
+            )));
+        };
+
+        if let Some(query) = message.query {
+            self.set_leader_query(query, cx);
+        }
+        let update_results = message.results.map(|results| {
+            self.results_editor.update(cx, |editor, cx| {
+                editor.apply_update_proto(project, proto::update_view::Variant::Editor(results), cx)
+            })
+        });
+        cx.spawn(|this, mut cx| async move {
+            if let Some(update_results) = update_results {
+                update_results.await?;
+            }
+            this.update(&mut cx, |this, cx| this.find_leader_matches(cx))?
+                .await;
+            Ok(())
+        })
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        self.results_editor.update(cx, |editor, cx| {
+            editor.set_leader_peer_id(leader_peer_id, cx)
+        })
+    }
+}
+
 impl ProjectSearchView {
     fn toggle_filters(&mut self, cx: &mut ViewContext<Self>) {
         self.filters_enabled = !self.filters_enabled;
@@ -659,11 +884,22 @@ impl ProjectSearchView {
         });
     }
 
+    fn toggle_open_files_only(&mut self, cx: &mut ViewContext<Self>) {
+        self.open_files_only = !self.open_files_only;
+        cx.update_global(|state: &mut ActiveSettings, cx| {
+            state.0.insert(
+                self.model.read(cx).project.downgrade(),
+                self.current_settings(),
+            );
+        });
+    }
+
     fn current_settings(&self) -> ProjectSearchSettings {
         ProjectSearchSettings {
             search_options: self.search_options,
             filters_enabled: self.filters_enabled,
             current_mode: self.current_mode,
+            open_files_only: self.open_files_only,
         }
     }
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut ViewContext<Self>) {
@@ -869,15 +1105,17 @@ impl ProjectSearchView {
         let mut subscriptions = Vec::new();
 
         // Read in settings if available
-        let (mut options, current_mode, filters_enabled) = if let Some(settings) = settings {
-            (
-                settings.search_options,
-                settings.current_mode,
-                settings.filters_enabled,
-            )
-        } else {
-            (SearchOptions::NONE, Default::default(), false)
-        };
+        let (mut options, current_mode, filters_enabled, open_files_only) =
+            if let Some(settings) = settings {
+                (
+                    settings.search_options,
+                    settings.current_mode,
+                    settings.filters_enabled,
+                    settings.open_files_only,
+                )
+            } else {
+                (SearchOptions::NONE, Default::default(), false, false)
+            };
 
         {
             let model = model.read(cx);
@@ -982,8 +1220,12 @@ impl ProjectSearchView {
             included_files_editor,
             excluded_files_editor,
             filters_enabled,
+            open_files_only,
+            open_file_paths: Vec::new(),
+            workspace: None,
             current_mode,
             replace_enabled: false,
+            remote_id: None,
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
@@ -1008,9 +1250,23 @@ impl ProjectSearchView {
         dir_path: &Path,
         cx: &mut ViewContext<Workspace>,
     ) {
-        let Some(filter_str) = dir_path.to_str() else {
+        Self::new_search_in_paths(workspace, &[dir_path], cx)
+    }
+
+    /// Opens a new project search, restricted to the given files and directories.
+    pub fn new_search_in_paths(
+        workspace: &mut Workspace,
+        paths: &[&Path],
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let filter_str = paths
+            .iter()
+            .filter_map(|path| path.to_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if filter_str.is_empty() {
             return;
-        };
+        }
 
         let model = cx.new_model(|cx| ProjectSearch::new(workspace.project().clone(), cx));
         let search = cx.new_view(|cx| ProjectSearchView::new(model, cx, None));
@@ -1024,6 +1280,52 @@ impl ProjectSearchView {
         });
     }
 
+    pub(crate) fn run_saved_search(
+        workspace: &mut Workspace,
+        saved_search: &SavedSearch,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let settings = ProjectSearchSettings {
+            search_options: saved_search.options(),
+            filters_enabled: saved_search.open_files_only
+                || !saved_search.included_files.is_empty()
+                || !saved_search.excluded_files.is_empty(),
+            current_mode: saved_search.mode,
+            open_files_only: saved_search.open_files_only,
+        };
+        let model = cx.new_model(|cx| ProjectSearch::new(workspace.project().clone(), cx));
+        let search = cx.new_view(|cx| ProjectSearchView::new(model, cx, Some(settings)));
+        workspace.add_item_to_active_pane(Box::new(search.clone()), cx);
+        search.update(cx, |search, cx| {
+            search.set_query(&saved_search.query, cx);
+            search.included_files_editor.update(cx, |editor, cx| {
+                editor.set_text(saved_search.included_files.as_str(), cx)
+            });
+            search.excluded_files_editor.update(cx, |editor, cx| {
+                editor.set_text(saved_search.excluded_files.as_str(), cx)
+            });
+            search.search(cx);
+        });
+    }
+
+    pub(crate) fn query_text(&self, cx: &AppContext) -> String {
+        self.query_editor.read(cx).text(cx)
+    }
+
+    pub(crate) fn saved_search(&self, name: String, cx: &AppContext) -> SavedSearch {
+        SavedSearch {
+            name,
+            query: self.query_text(cx),
+            mode: self.current_mode,
+            whole_word: self.search_options.contains(SearchOptions::WHOLE_WORD),
+            case_sensitive: self.search_options.contains(SearchOptions::CASE_SENSITIVE),
+            include_ignored: self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+            included_files: self.included_files_editor.read(cx).text(cx),
+            excluded_files: self.excluded_files_editor.read(cx).text(cx),
+            open_files_only: self.open_files_only,
+        }
+    }
+
     // Re-activate the most recently activated search in this pane or the most recent if it has been closed.
     // If no search exists in the workspace, create a new one.
     fn deploy_search(
@@ -1045,7 +1347,9 @@ impl ProjectSearchView {
             .active_item(cx)
             .and_then(|item| item.downcast::<ProjectSearchView>())
         {
+            let open_file_paths = open_file_paths(workspace, cx);
             let new_query = search_view.update(cx, |search_view, cx| {
+                search_view.open_file_paths = open_file_paths;
                 let new_query = search_view.build_search_query(cx);
                 if new_query.is_some() {
                     if let Some(old_query) = search_view.model.read(cx).active_query.clone() {
@@ -1126,23 +1430,40 @@ impl ProjectSearchView {
     }
 
     fn search(&mut self, cx: &mut ViewContext<Self>) {
-        let mode = self.current_mode;
-        match mode {
-            SearchMode::Semantic => {
-                if self.semantic_state.is_some() {
-                    if let Some(query) = self.build_search_query(cx) {
-                        self.model
-                            .update(cx, |model, cx| model.semantic_search(query.as_inner(), cx));
-                    }
+        if self.open_files_only {
+            // The search may be run from within a workspace update, so list the open files once
+            // it's done.
+            cx.defer(|this, cx| {
+                if let Some(workspace) = this.workspace.as_ref().and_then(|w| w.upgrade()) {
+                    this.open_file_paths = open_file_paths(workspace.read(cx), cx);
                 }
-            }
+                this.run_search(cx);
+            });
+        } else {
+            self.run_search(cx);
+        }
+    }
 
-            _ => {
-                if let Some(query) = self.build_search_query(cx) {
-                    self.model.update(cx, |model, cx| model.search(query, cx));
-                }
+    fn run_search(&mut self, cx: &mut ViewContext<Self>) {
+        let mode = self.current_mode;
+        let query = match mode {
+            SearchMode::Semantic if self.semantic_state.is_none() => None,
+            _ => self.build_search_query(cx),
+        };
+        let Some(query) = query else {
+            return;
+        };
+
+        let included_files = self.included_files_editor.read(cx).text(cx);
+        let excluded_files = self.excluded_files_editor.read(cx).text(cx);
+        self.model.update(cx, |model, cx| {
+            model.add_to_history(InputPanel::Include, included_files, cx);
+            model.add_to_history(InputPanel::Exclude, excluded_files, cx);
+            match mode {
+                SearchMode::Semantic => model.semantic_search(query.as_inner(), cx),
+                _ => model.search(query, cx),
             }
-        }
+        });
     }
 
     fn build_search_query(&mut self, cx: &mut ViewContext<Self>) -> Option<SearchQuery> {
@@ -1184,6 +1505,23 @@ impl ProjectSearchView {
                 }
             };
 
+        let mut no_open_files = false;
+        let included_files = if self.open_files_only {
+            let open_files = self
+                .open_file_paths
+                .iter()
+                .filter(|path| {
+                    included_files.is_empty()
+                        || included_files.iter().any(|matcher| matcher.is_match(path))
+                })
+                .filter_map(|path| PathMatcher::literal(path).log_err())
+                .collect::<Vec<_>>();
+            no_open_files = open_files.is_empty();
+            open_files
+        } else {
+            included_files
+        };
+
         let current_mode = self.current_mode;
         let query = match current_mode {
             SearchMode::Regex => {
@@ -1240,7 +1578,7 @@ impl ProjectSearchView {
                 }
             },
         };
-        if !self.panels_with_errors.is_empty() {
+        if !self.panels_with_errors.is_empty() || no_open_files {
             return None;
         }
         if query.as_ref().is_some_and(|query| query.is_empty()) {
@@ -1292,6 +1630,29 @@ impl ProjectSearchView {
             .update(cx, |query_editor, cx| query_editor.set_text(query, cx));
     }
 
+    fn focused_panel(&self, cx: &WindowContext) -> InputPanel {
+        if self.included_files_editor.focus_handle(cx).is_focused(cx) {
+            InputPanel::Include
+        } else if self.excluded_files_editor.focus_handle(cx).is_focused(cx) {
+            InputPanel::Exclude
+        } else {
+            InputPanel::Query
+        }
+    }
+
+    fn editor_for_panel(&self, panel: InputPanel) -> &View<Editor> {
+        match panel {
+            InputPanel::Query => &self.query_editor,
+            InputPanel::Include => &self.included_files_editor,
+            InputPanel::Exclude => &self.excluded_files_editor,
+        }
+    }
+
+    fn set_panel_text(&mut self, panel: InputPanel, text: &str, cx: &mut ViewContext<Self>) {
+        self.editor_for_panel(panel)
+            .update(cx, |editor, cx| editor.set_text(text, cx));
+    }
+
     fn focus_results_editor(&mut self, cx: &mut ViewContext<Self>) {
         self.query_editor.update(cx, |query_editor, cx| {
             let cursor = query_editor.selections.newest_anchor().head();
@@ -1353,6 +1714,53 @@ impl ProjectSearchView {
         self.active_match_index.is_some()
     }
 
+    fn active_query_proto(&self, cx: &AppContext) -> Option<proto::SearchProject> {
+        let model = self.model.read(cx);
+        let project_id = model.project.read(cx).remote_id()?;
+        Some(model.active_query.as_ref()?.to_proto(project_id))
+    }
+
+    /// Shows the query a followed collaborator searched for, without running it again, as
+    /// their results are mirrored into the results editor.
+    fn set_leader_query(&mut self, query: proto::SearchProject, cx: &mut ViewContext<Self>) {
+        let Some(query) = SearchQuery::from_proto(query).log_err() else {
+            return;
+        };
+        if self.query_text(cx) != query.as_str() {
+            self.set_query(query.as_str(), cx);
+        }
+        self.search_options = SearchOptions::from_query(&query);
+        self.current_mode = if query.is_regex() {
+            SearchMode::Regex
+        } else {
+            SearchMode::Text
+        };
+        self.model
+            .update(cx, |model, _| model.active_query = Some(query));
+        cx.notify();
+    }
+
+    /// Highlights the matches of the mirrored query in the mirrored results.
+    fn find_leader_matches(&mut self, cx: &mut ViewContext<Self>) -> Task<()> {
+        let Some(query) = self.model.read(cx).active_query.clone() else {
+            return Task::ready(());
+        };
+        let matches = self
+            .results_editor
+            .update(cx, |editor, cx| editor.find_matches(Arc::new(query), cx));
+        cx.spawn(|this, mut cx| async move {
+            let match_ranges = matches.await;
+            this.update(&mut cx, |this, cx| {
+                this.model.update(cx, |model, cx| {
+                    model.no_results = Some(match_ranges.is_empty());
+                    model.match_ranges = match_ranges;
+                    cx.notify();
+                })
+            })
+            .ok();
+        })
+    }
+
     fn landing_text_minor(&self) -> SharedString {
         match self.current_mode {
             SearchMode::Text | SearchMode::Regex => "Include/exclude specific paths with the filter option. Matching exact word and/or casing is available too.".into(),
@@ -1508,6 +1916,19 @@ impl ProjectSearchBar {
         }
     }
 
+    fn toggle_open_files_only(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| {
+                search_view.toggle_open_files_only(cx);
+                search_view.search(cx);
+            });
+            cx.notify();
+            true
+        } else {
+            false
+        }
+    }
+
     fn move_focus_to_results(&self, cx: &mut ViewContext<Self>) {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
@@ -1538,15 +1959,17 @@ impl ProjectSearchBar {
     fn next_history_query(&mut self, _: &NextHistoryQuery, cx: &mut ViewContext<Self>) {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
+                let panel = search_view.focused_panel(cx);
                 let new_query = search_view.model.update(cx, |model, _| {
-                    if let Some(new_query) = model.search_history.next().map(str::to_string) {
+                    let history = model.history(panel);
+                    if let Some(new_query) = history.next().map(str::to_string) {
                         new_query
                     } else {
-                        model.search_history.reset_selection();
+                        history.reset_selection();
                         String::new()
                     }
                 });
-                search_view.set_query(&new_query, cx);
+                search_view.set_panel_text(panel, &new_query, cx);
             });
         }
     }
@@ -1554,23 +1977,25 @@ impl ProjectSearchBar {
     fn previous_history_query(&mut self, _: &PreviousHistoryQuery, cx: &mut ViewContext<Self>) {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
-                if search_view.query_editor.read(cx).text(cx).is_empty() {
-                    if let Some(new_query) = search_view
-                        .model
-                        .read(cx)
-                        .search_history
-                        .current()
-                        .map(str::to_string)
-                    {
-                        search_view.set_query(&new_query, cx);
+                let panel = search_view.focused_panel(cx);
+                if search_view
+                    .editor_for_panel(panel)
+                    .read(cx)
+                    .text(cx)
+                    .is_empty()
+                {
+                    if let Some(new_query) = search_view.model.update(cx, |model, _| {
+                        model.history(panel).current().map(str::to_string)
+                    }) {
+                        search_view.set_panel_text(panel, &new_query, cx);
                         return;
                     }
                 }
 
                 if let Some(new_query) = search_view.model.update(cx, |model, _| {
-                    model.search_history.previous().map(str::to_string)
+                    model.history(panel).previous().map(str::to_string)
                 }) {
-                    search_view.set_query(&new_query, cx);
+                    search_view.set_panel_text(panel, &new_query, cx);
                 }
             });
         }
@@ -1928,7 +2353,27 @@ impl Render for ProjectSearchBar {
                         .border_1()
                         .border_color(search.border_color_for(InputPanel::Include, cx))
                         .rounded_lg()
+                        .on_action(
+                            cx.listener(|this, action, cx| this.previous_history_query(action, cx)),
+                        )
+                        .on_action(
+                            cx.listener(|this, action, cx| this.next_history_query(action, cx)),
+                        )
                         .child(self.render_text_input(&search.included_files_editor, cx))
+                        .child(
+                            IconButton::new("project-search-open-files-only", IconName::File)
+                                .tooltip(|cx| {
+                                    Tooltip::for_action(
+                                        "Only search open files",
+                                        &ToggleOpenFilesOnly,
+                                        cx,
+                                    )
+                                })
+                                .selected(search.open_files_only)
+                                .on_click(cx.listener(|this, _, cx| {
+                                    this.toggle_open_files_only(cx);
+                                })),
+                        )
                         .when(search.current_mode != SearchMode::Semantic, |this| {
                             this.child(
                                 SearchOptions::INCLUDE_IGNORED.as_button(
@@ -1956,6 +2401,12 @@ impl Render for ProjectSearchBar {
                         .border_1()
                         .border_color(search.border_color_for(InputPanel::Exclude, cx))
                         .rounded_lg()
+                        .on_action(
+                            cx.listener(|this, action, cx| this.previous_history_query(action, cx)),
+                        )
+                        .on_action(
+                            cx.listener(|this, action, cx| this.next_history_query(action, cx)),
+                        )
                         .child(self.render_text_input(&search.excluded_files_editor, cx)),
                 )
         });
@@ -1966,6 +2417,9 @@ impl Render for ProjectSearchBar {
             .on_action(cx.listener(|this, _: &ToggleFilters, cx| {
                 this.toggle_filters(cx);
             }))
+            .on_action(cx.listener(|this, _: &ToggleOpenFilesOnly, cx| {
+                this.toggle_open_files_only(cx);
+            }))
             .on_action(cx.listener(|this, _: &ActivateTextMode, cx| {
                 this.activate_search_mode(SearchMode::Text, cx)
             }))
@@ -2063,6 +2517,27 @@ impl ToolbarItemView for ProjectSearchBar {
     }
 }
 
+/// Lists the paths of the files open in the workspace, as matched by the project search filters.
+fn open_file_paths(workspace: &Workspace, cx: &AppContext) -> Vec<PathBuf> {
+    let project = workspace.project().read(cx);
+    let prefix_root_name = project.visible_worktrees(cx).count() > 1;
+    let mut paths = workspace
+        .items(cx)
+        .filter_map(|item| {
+            let project_path = item.project_path(cx)?;
+            if prefix_root_name {
+                let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+                Some(Path::new(worktree.read(cx).root_name()).join(&project_path.path))
+            } else {
+                Some(project_path.path.to_path_buf())
+            }
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
 fn register_workspace_action<A: Action>(
     workspace: &mut Workspace,
     callback: fn(&mut ProjectSearchBar, &A, &mut ViewContext<ProjectSearchBar>),
@@ -3220,6 +3695,246 @@ pub mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_filter_history(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let workspace = window.root(cx).unwrap();
+        let search_bar = window.build_view(cx, |_| ProjectSearchBar::new());
+        window
+            .update(cx, {
+                let search_bar = search_bar.clone();
+                move |workspace, cx| {
+                    workspace.panes()[0].update(cx, move |pane, cx| {
+                        pane.toolbar()
+                            .update(cx, |toolbar, cx| toolbar.add_item(search_bar, cx))
+                    });
+                    ProjectSearchView::new_search(workspace, &workspace::NewSearch, cx)
+                }
+            })
+            .unwrap();
+        let search_view = cx.read(|cx| {
+            workspace
+                .read(cx)
+                .active_pane()
+                .read(cx)
+                .active_item()
+                .and_then(|item| item.downcast::<ProjectSearchView>())
+                .expect("Search view expected to appear after new search event trigger")
+        });
+
+        for included_files in ["one.rs", "two.rs"] {
+            window
+                .update(cx, |_, cx| {
+                    search_view.update(cx, |search_view, cx| {
+                        search_view.set_query("ONE", cx);
+                        search_view
+                            .included_files_editor
+                            .update(cx, |editor, cx| editor.set_text(included_files, cx));
+                        search_view.search(cx);
+                    });
+                })
+                .unwrap();
+            cx.background_executor.run_until_parked();
+        }
+        window
+            .update(cx, |_, cx| {
+                search_view.update(cx, |search_view, cx| {
+                    let focus_handle = search_view.included_files_editor.focus_handle(cx);
+                    cx.focus(&focus_handle);
+                });
+            })
+            .unwrap();
+
+        let included_files = |cx: &mut TestAppContext| {
+            window
+                .update(cx, |_, cx| {
+                    search_view.read(cx).included_files_editor.read(cx).text(cx)
+                })
+                .unwrap()
+        };
+        window
+            .update(cx, |_, cx| {
+                search_bar.update(cx, |search_bar, cx| {
+                    search_bar.previous_history_query(&PreviousHistoryQuery, cx);
+                });
+            })
+            .unwrap();
+        assert_eq!(included_files(cx), "one.rs");
+        window
+            .update(cx, |_, cx| {
+                search_bar.update(cx, |search_bar, cx| {
+                    search_bar.next_history_query(&NextHistoryQuery, cx);
+                });
+            })
+            .unwrap();
+        assert_eq!(included_files(cx), "two.rs");
+        window
+            .update(cx, |_, cx| {
+                search_bar.update(cx, |search_bar, cx| {
+                    search_bar.next_history_query(&NextHistoryQuery, cx);
+                });
+            })
+            .unwrap();
+        assert_eq!(included_files(cx), "");
+        window
+            .update(cx, |_, cx| {
+                search_view.update(cx, |search_view, cx| {
+                    assert_eq!(
+                        search_view.query_editor.read(cx).text(cx),
+                        "ONE",
+                        "Navigating the filter history should leave the query alone"
+                    );
+                });
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_search_open_files_only(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let worktree_id = project.update(cx, |this, cx| {
+            this.worktrees().next().unwrap().read(cx).id()
+        });
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let workspace = window.root(cx).unwrap();
+        for path in ["one.rs", "three.rs"] {
+            window
+                .update(cx, |workspace, cx| {
+                    workspace.open_path((worktree_id, path), None, true, cx)
+                })
+                .unwrap()
+                .await
+                .unwrap();
+        }
+
+        window
+            .update(cx, |workspace, cx| {
+                ProjectSearchView::new_search(workspace, &workspace::NewSearch, cx)
+            })
+            .unwrap();
+        let search_view = cx.read(|cx| {
+            workspace
+                .read(cx)
+                .active_pane()
+                .read(cx)
+                .active_item()
+                .and_then(|item| item.downcast::<ProjectSearchView>())
+                .expect("Search view expected to appear after new search event trigger")
+        });
+        window
+            .update(cx, |_, cx| {
+                search_view.update(cx, |search_view, cx| {
+                    search_view.toggle_open_files_only(cx);
+                    search_view.set_query("const", cx);
+                    search_view.search(cx);
+                });
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        window
+            .update(cx, |_, cx| {
+                search_view.update(cx, |search_view, cx| {
+                    let results = search_view
+                        .results_editor
+                        .update(cx, |editor, cx| editor.display_text(cx));
+                    assert!(results.contains("const ONE"));
+                    assert!(results.contains("const THREE"));
+                    assert!(
+                        !results.contains("const TWO"),
+                        "Files that aren't open should not be searched"
+                    );
+                });
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_run_saved_search(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let workspace = window.root(cx).unwrap();
+
+        let saved_search = SavedSearch {
+            name: "ones".into(),
+            query: "one::ONE".into(),
+            case_sensitive: true,
+            excluded_files: "three.rs".into(),
+            ..Default::default()
+        };
+        window
+            .update(cx, |workspace, cx| {
+                ProjectSearchView::run_saved_search(workspace, &saved_search, cx)
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        let search_view = cx.read(|cx| {
+            workspace
+                .read(cx)
+                .active_pane()
+                .read(cx)
+                .active_item()
+                .and_then(|item| item.downcast::<ProjectSearchView>())
+                .expect("Search view expected to appear after running a saved search")
+        });
+        window
+            .update(cx, |_, cx| {
+                search_view.update(cx, |search_view, cx| {
+                    assert!(search_view.filters_enabled);
+                    assert_eq!(search_view.search_options, SearchOptions::CASE_SENSITIVE);
+                    assert_eq!(
+                        search_view.saved_search("ones".into(), cx),
+                        saved_search,
+                        "Saving the search again should give back the same search"
+                    );
+                    assert_eq!(
+                        search_view
+                            .results_editor
+                            .update(cx, |editor, cx| editor.display_text(cx)),
+                        "\n\nconst TWO: usize = one::ONE + one::ONE;"
+                    );
+                });
+            })
+            .unwrap();
+    }
+
     #[gpui::test]
     async fn test_deploy_search_with_multiple_panes(cx: &mut TestAppContext) {
         init_test(cx);
@@ -3441,7 +4156,7 @@ pub mod tests {
             .expect("unable to update search view");
     }
 
-    fn init_test(cx: &mut TestAppContext) {
+    pub(crate) fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
             cx.set_global(settings);
@@ -0,0 +1,409 @@
+use editor::{actions::SelectAll, Editor, EditorEvent};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Subscription, Task,
+    View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::{
+    persistence::{SavedSearch, SearchStore},
+    project_search::{OpenSavedSearch, SaveSearch},
+    ProjectSearchView,
+};
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(SaveSearchModal::toggle);
+    workspace.register_action(SavedSearches::toggle);
+}
+
+/// Asks for the name to save the active project search under.
+pub struct SaveSearchModal {
+    name_editor: View<Editor>,
+    search_view: View<ProjectSearchView>,
+    _subscription: Subscription,
+}
+
+impl SaveSearchModal {
+    fn toggle(workspace: &mut Workspace, _: &SaveSearch, cx: &mut ViewContext<Workspace>) {
+        let Some(search_view) = workspace.active_item_as::<ProjectSearchView>(cx) else {
+            return;
+        };
+        workspace.toggle_modal(cx, |cx| Self::new(search_view, cx));
+    }
+
+    fn new(search_view: View<ProjectSearchView>, cx: &mut ViewContext<Self>) -> Self {
+        let query = search_view.read(cx).query_text(cx);
+        let name_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Search name", cx);
+            editor.set_text(query, cx);
+            editor.select_all(&SelectAll, cx);
+            editor
+        });
+        let _subscription = cx.subscribe(&name_editor, |_, _, event: &EditorEvent, cx| {
+            if let EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+        Self {
+            name_editor,
+            search_view,
+            _subscription,
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let search = self.search_view.read(cx).saved_search(name, cx);
+        SearchStore::update(cx, |store| store.save_search(search));
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for SaveSearchModal {}
+
+impl EventEmitter<DismissEvent> for SaveSearchModal {}
+
+impl FocusableView for SaveSearchModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl Render for SaveSearchModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .elevation_2(cx)
+            .key_context("SaveSearchModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(
+                v_flex()
+                    .px_1()
+                    .pt_0p5()
+                    .gap_px()
+                    .child(
+                        v_flex()
+                            .py_0p5()
+                            .px_1()
+                            .child(div().px_1().py_0p5().child(self.name_editor.clone())),
+                    )
+                    .child(
+                        div()
+                            .h_px()
+                            .w_full()
+                            .bg(cx.theme().colors().element_background),
+                    )
+                    .child(
+                        h_flex().justify_between().px_2().py_1().child(
+                            Label::new("Save the search, replacing any with the same name")
+                                .color(Color::Muted),
+                        ),
+                    ),
+            )
+    }
+}
+
+/// Lists the saved searches, running the confirmed one in a new project search.
+pub struct SavedSearches {
+    picker: View<Picker<SavedSearchesDelegate>>,
+}
+
+impl SavedSearches {
+    fn toggle(workspace: &mut Workspace, _: &OpenSavedSearch, cx: &mut ViewContext<Workspace>) {
+        let workspace_handle = cx.view().downgrade();
+        workspace.toggle_modal(cx, |cx| Self::new(workspace_handle, cx));
+    }
+
+    fn new(workspace: WeakView<Workspace>, cx: &mut ViewContext<Self>) -> Self {
+        let saved_searches = cx
+            .try_global::<SearchStore>()
+            .map(|store| store.saved_searches.clone())
+            .unwrap_or_default();
+        let delegate = SavedSearchesDelegate {
+            saved_searches_view: cx.view().downgrade(),
+            workspace,
+            saved_searches,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for SavedSearches {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for SavedSearches {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SavedSearches {}
+impl ModalView for SavedSearches {}
+
+pub struct SavedSearchesDelegate {
+    saved_searches_view: WeakView<SavedSearches>,
+    workspace: WeakView<Workspace>,
+    saved_searches: Vec<SavedSearch>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl SavedSearchesDelegate {
+    /// Deletes the search of the given match, keeping the remaining matches pointed at their
+    /// searches until the matches are updated.
+    fn delete_search(&mut self, ix: usize, cx: &mut AppContext) {
+        if ix >= self.matches.len() {
+            return;
+        }
+        let hit = self.matches.remove(ix);
+        for other_hit in &mut self.matches {
+            if other_hit.candidate_id > hit.candidate_id {
+                other_hit.candidate_id -= 1;
+            }
+        }
+        if self.selected_index > ix {
+            self.selected_index -= 1;
+        }
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+
+        let search = self.saved_searches.remove(hit.candidate_id);
+        SearchStore::update(cx, |store| store.delete_search(&search.name));
+    }
+}
+
+impl PickerDelegate for SavedSearchesDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Run a saved search...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .saved_searches
+            .iter()
+            .enumerate()
+            .map(|(id, search)| StringMatchCandidate::new(id, search.name.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(search) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.saved_searches.get(hit.candidate_id))
+            .cloned()
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                ProjectSearchView::run_saved_search(workspace, &search, cx)
+            })
+            .log_err();
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.saved_searches_view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let search = self.saved_searches.get(hit.candidate_id)?;
+        let delete_button = IconButton::new("delete", IconName::Close)
+            .icon_size(IconSize::Small)
+            .on_click(cx.listener(move |this, _, cx| {
+                cx.stop_propagation();
+                this.delegate.delete_search(ix, cx);
+                this.update_matches(this.query(cx), cx);
+            }))
+            .tooltip(|cx| Tooltip::text("Delete Saved Search", cx));
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            search.name.clone(),
+                            hit.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(search.query.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .end_hover_slot(delete_button),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_search::tests::init_test;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_delete_saved_search(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = 2;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let workspace = window.root(cx).unwrap();
+
+        cx.update(|cx| {
+            SearchStore::update(cx, |store| {
+                for (name, query) in [("ones", "ONE"), ("twos", "TWO")] {
+                    store.save_search(SavedSearch {
+                        name: name.into(),
+                        query: query.into(),
+                        ..Default::default()
+                    });
+                }
+            })
+        });
+        window
+            .update(cx, |workspace, cx| {
+                SavedSearches::toggle(workspace, &OpenSavedSearch, cx)
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        let picker = window
+            .update(cx, |workspace, cx| {
+                workspace
+                    .active_modal::<SavedSearches>(cx)
+                    .expect("saved searches should be open")
+                    .read(cx)
+                    .picker
+                    .clone()
+            })
+            .unwrap();
+
+        // Delete the first search while the second one is selected, and confirm before the
+        // matches are updated.
+        window
+            .update(cx, |_, cx| {
+                picker.update(cx, |picker, cx| {
+                    picker.delegate.set_selected_index(1, cx);
+                    picker.delegate.delete_search(0, cx);
+                    assert_eq!(picker.delegate.matches.len(), 1);
+                    assert_eq!(picker.delegate.selected_index, 0);
+                    picker.delegate.confirm(false, cx);
+                })
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        cx.read(|cx| {
+            assert_eq!(
+                cx.global::<SearchStore>()
+                    .saved_searches
+                    .iter()
+                    .map(|search| search.name.as_str())
+                    .collect::<Vec<_>>(),
+                &["twos"]
+            );
+            let search_view = workspace
+                .read(cx)
+                .active_pane()
+                .read(cx)
+                .active_item()
+                .and_then(|item| item.downcast::<ProjectSearchView>())
+                .expect("confirming should run the selected search");
+            assert_eq!(search_view.read(cx).query_text(cx), "TWO");
+        });
+    }
+}
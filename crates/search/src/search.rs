@@ -10,13 +10,16 @@ use ui::{ButtonStyle, IconButton};
 pub mod buffer_search;
 mod history;
 mod mode;
+mod persistence;
 pub mod project_search;
+mod saved_searches;
 pub(crate) mod search_bar;
 
 pub fn init(cx: &mut AppContext) {
     menu::init();
     buffer_search::init(cx);
     project_search::init(cx);
+    persistence::SearchStore::load(cx);
 }
 
 actions!(
@@ -0,0 +1,24 @@
+use std::ops::Range;
+
+/// Returns the range to delete in order to remove an element from a JSON array or object,
+/// including its separating comma and, if it's on its own line, the line itself.
+pub(crate) fn json_removal_range(node: tree_sitter::Node, text: &str) -> Range<usize> {
+    let mut range = node.byte_range();
+    if let Some(comma) = node.next_sibling().filter(|next| next.kind() == ",") {
+        range.end = comma.end_byte();
+    } else if let Some(comma) = node.prev_sibling().filter(|prev| prev.kind() == ",") {
+        range.start = comma.start_byte();
+    }
+
+    let line_start = text[..range.start].rfind('\n').map_or(0, |ix| ix + 1);
+    let line_end = text[range.end..]
+        .find('\n')
+        .map_or(text.len(), |ix| range.end + ix + 1);
+    if text[line_start..range.start].trim().is_empty()
+        && text[range.end..line_end].trim().is_empty()
+    {
+        line_start..line_end
+    } else {
+        range
+    }
+}
@@ -1,7 +1,11 @@
-use crate::{settings_store::parse_json_with_comments, SettingsAssets};
+use crate::{
+    json_edit::json_removal_range,
+    settings_store::{parse_json_with_comments, to_pretty_json},
+    SettingsAssets,
+};
 use anyhow::{anyhow, Context, Result};
 use collections::BTreeMap;
-use gpui::{Action, AppContext, KeyBinding, SharedString};
+use gpui::{Action, AppContext, Global, KeyBinding, SharedString};
 use schemars::{
     gen::{SchemaGenerator, SchemaSettings},
     schema::{InstanceType, Schema, SchemaObject, SingleOrVec, SubschemaValidation},
@@ -9,6 +13,7 @@ use schemars::{
 };
 use serde::Deserialize;
 use serde_json::Value;
+use std::ops::Range;
 use util::{asset_str, ResultExt};
 
 #[derive(Debug, Deserialize, Default, Clone, JsonSchema)]
@@ -36,6 +41,79 @@ impl JsonSchema for KeymapAction {
     }
 }
 
+impl KeymapAction {
+    /// Returns the name of the bound action, or `None` if the binding disables its keystrokes.
+    pub fn name(&self) -> Option<&str> {
+        match &self.0 {
+            Value::String(name) => Some(name),
+            Value::Array(items) => items.first().and_then(Value::as_str),
+            _ => None,
+        }
+    }
+
+    /// Returns the action's data, if any.
+    pub fn data(&self) -> Option<&Value> {
+        match &self.0 {
+            Value::Array(items) => items.get(1),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.0
+    }
+}
+
+impl KeymapBlock {
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    pub fn bindings(&self) -> impl Iterator<Item = (&str, &KeymapAction)> {
+        self.bindings
+            .iter()
+            .map(|(keystrokes, action)| (keystrokes.as_str(), action))
+    }
+}
+
+/// The keymap file that a set of key bindings was loaded from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeymapSource {
+    Default,
+    /// The keymap of another editor, selected with the `base_keymap` setting.
+    Base,
+    Vim,
+    User,
+}
+
+impl KeymapSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeymapSource::Default => "Default",
+            KeymapSource::Base => "Base",
+            KeymapSource::Vim => "Vim",
+            KeymapSource::User => "User",
+        }
+    }
+}
+
+/// The keymap files whose bindings are currently loaded, in the order in which they
+/// were added to the app's keymap.
+#[derive(Default)]
+pub struct LoadedKeymaps(Vec<(KeymapSource, KeymapFile)>);
+
+impl Global for LoadedKeymaps {}
+
+impl LoadedKeymaps {
+    pub fn keymaps(&self) -> impl Iterator<Item = (KeymapSource, &KeymapFile)> {
+        self.0.iter().map(|(source, keymap)| (*source, keymap))
+    }
+
+    pub fn clear(cx: &mut AppContext) {
+        cx.default_global::<Self>().0.clear();
+    }
+}
+
 #[derive(Deserialize)]
 struct ActionWithData(Box<str>, Value);
 
@@ -46,6 +124,21 @@ impl KeymapFile {
         Self::parse(content.as_ref())?.add_to_cx(cx)
     }
 
+    /// Like [`Self::load_asset`], but records the keymap in [`LoadedKeymaps`].
+    pub fn load_asset_as(
+        asset_path: &str,
+        source: KeymapSource,
+        cx: &mut AppContext,
+    ) -> Result<()> {
+        let content = asset_str::<SettingsAssets>(asset_path);
+
+        Self::parse(content.as_ref())?.add_to_cx_as(source, cx)
+    }
+
+    pub fn blocks(&self) -> &[KeymapBlock] {
+        &self.0
+    }
+
     pub fn parse(content: &str) -> Result<Self> {
         parse_json_with_comments::<Self>(content)
     }
@@ -96,6 +189,93 @@ impl KeymapFile {
         Ok(())
     }
 
+    /// Like [`Self::add_to_cx`], but records the keymap in [`LoadedKeymaps`].
+    pub fn add_to_cx_as(self, source: KeymapSource, cx: &mut AppContext) -> Result<()> {
+        cx.default_global::<LoadedKeymaps>()
+            .0
+            .push((source, self.clone()));
+        self.add_to_cx(cx)
+    }
+
+    /// Binds `keystrokes` to `action` in the block of the keymap file whose context is
+    /// `context`, preserving the rest of the file's contents, including its comments.
+    /// Passing no action removes the binding from the file, whereas passing a null
+    /// action disables the keystrokes in that context.
+    pub fn update_binding_in_json_text(
+        text: &mut String,
+        context: Option<&str>,
+        keystrokes: &str,
+        action: Option<&Value>,
+        tab_size: usize,
+    ) -> Result<()> {
+        let source = text.as_str();
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&tree_sitter_json::language()).unwrap();
+        let syntax_tree = parser.parse(source, None).unwrap();
+        let root = syntax_tree.root_node();
+
+        let Some(blocks) = json_children(root).next() else {
+            if let Some(action) = action {
+                let block = new_keymap_block(context, keystrokes, action, tab_size);
+                *text = format!("[\n{}\n]\n", indent_lines(&block, tab_size));
+            }
+            return Ok(());
+        };
+        if blocks.kind() != "array" {
+            return Err(anyhow!("keymap file doesn't contain an array of bindings"));
+        }
+
+        let block = json_children(blocks).find(|block| {
+            let block_context = json_object_value(*block, "context", source)
+                .and_then(|block_context| json_string(block_context, source));
+            block.kind() == "object" && block_context.as_deref() == context
+        });
+        let bindings = block.and_then(|block| {
+            json_object_value(block, "bindings", source).filter(|value| value.kind() == "object")
+        });
+        let binding = bindings.and_then(|bindings| {
+            json_children(bindings).find(|pair| {
+                pair.child_by_field_name("key")
+                    .and_then(|key| json_string(key, source))
+                    .map_or(false, |key| key == keystrokes)
+            })
+        });
+
+        let (range, new_text) = match (block, bindings, binding, action) {
+            (_, _, Some(binding), Some(action)) => {
+                let value = binding
+                    .child_by_field_name("value")
+                    .ok_or_else(|| anyhow!("binding has no value"))?;
+                (
+                    value.byte_range(),
+                    to_pretty_json(action, tab_size, line_indent(binding.start_byte(), source)),
+                )
+            }
+            (_, _, Some(binding), None) => (json_removal_range(binding, source), String::new()),
+            (_, Some(bindings), None, Some(action)) => json_insertion(
+                bindings,
+                &new_binding(keystrokes, action, tab_size),
+                source,
+                tab_size,
+            ),
+            (Some(block), None, None, Some(action)) => json_insertion(
+                block,
+                &new_bindings_pair(keystrokes, action, tab_size),
+                source,
+                tab_size,
+            ),
+            (None, _, None, Some(action)) => json_insertion(
+                blocks,
+                &new_keymap_block(context, keystrokes, action, tab_size),
+                source,
+                tab_size,
+            ),
+            (_, _, None, None) => return Ok(()),
+        };
+        text.replace_range(range, &new_text);
+        Ok(())
+    }
+
     pub fn generate_json_schema(action_names: &[SharedString]) -> serde_json::Value {
         let mut root_schema = SchemaSettings::draft07()
             .with(|settings| settings.option_add_null_type = false)
@@ -141,9 +321,136 @@ fn no_action() -> Box<dyn gpui::Action> {
     gpui::NoAction.boxed_clone()
 }
 
+// The following functions format new parts of a keymap file as if they started at the
+// beginning of a line, leaving it to the caller to indent them. Actions with data are
+// pretty-printed over several lines.
+fn new_binding(keystrokes: &str, action: &Value, tab_size: usize) -> String {
+    format!(
+        "{}: {}",
+        Value::from(keystrokes),
+        to_pretty_json(action, tab_size, 0)
+    )
+}
+
+fn new_bindings_pair(keystrokes: &str, action: &Value, tab_size: usize) -> String {
+    format!(
+        "\"bindings\": {{\n{}\n}}",
+        indent_lines(&new_binding(keystrokes, action, tab_size), tab_size)
+    )
+}
+
+fn new_keymap_block(
+    context: Option<&str>,
+    keystrokes: &str,
+    action: &Value,
+    tab_size: usize,
+) -> String {
+    let mut block = "{\n".to_string();
+    if let Some(context) = context {
+        block.push_str(&indent_lines(
+            &format!("\"context\": {},\n", Value::from(context)),
+            tab_size,
+        ));
+    }
+    block.push_str(&indent_lines(
+        &new_bindings_pair(keystrokes, action, tab_size),
+        tab_size,
+    ));
+    block.push_str("\n}");
+    block
+}
+
+/// Indents each non-empty line of `text` by `indent` spaces.
+fn indent_lines(text: &str, indent: usize) -> String {
+    let indent = " ".repeat(indent);
+    text.split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{indent}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the elements of a JSON array, the pairs of a JSON object, or the value of a
+/// JSON document, skipping comments.
+fn json_children<'a>(node: tree_sitter::Node<'a>) -> impl Iterator<Item = tree_sitter::Node<'a>> {
+    (0..node.named_child_count())
+        .filter_map(move |ix| node.named_child(ix))
+        .filter(|child| child.kind() != "comment")
+}
+
+fn json_string(node: tree_sitter::Node, text: &str) -> Option<String> {
+    (node.kind() == "string")
+        .then(|| serde_json::from_str(&text[node.byte_range()]).ok())
+        .flatten()
+}
+
+fn json_object_value<'a>(
+    object: tree_sitter::Node<'a>,
+    key: &str,
+    text: &str,
+) -> Option<tree_sitter::Node<'a>> {
+    json_children(object)
+        .find(|pair| {
+            pair.child_by_field_name("key")
+                .and_then(|pair_key| json_string(pair_key, text))
+                .map_or(false, |pair_key| pair_key == key)
+        })?
+        .child_by_field_name("value")
+}
+
+/// Returns the edit that appends `child` to a JSON array or object, indenting it to match
+/// the parent's other children.
+fn json_insertion(
+    parent: tree_sitter::Node,
+    child: &str,
+    text: &str,
+    tab_size: usize,
+) -> (Range<usize>, String) {
+    if let Some(last) = json_children(parent).last() {
+        let child = indent_lines(child, column(last.start_byte(), text));
+        match last.next_sibling().filter(|next| next.kind() == ",") {
+            Some(comma) => (comma.end_byte()..comma.end_byte(), format!("\n{child},")),
+            None => (last.end_byte()..last.end_byte(), format!(",\n{child}")),
+        }
+    } else {
+        let indent = line_indent(parent.start_byte(), text);
+        let (open, close) = if parent.kind() == "array" {
+            ("[", "]")
+        } else {
+            ("{", "}")
+        };
+        (
+            parent.byte_range(),
+            format!(
+                "{open}\n{}\n{}{close}",
+                indent_lines(child, indent + tab_size),
+                " ".repeat(indent)
+            ),
+        )
+    }
+}
+
+fn column(offset: usize, text: &str) -> usize {
+    offset - text[..offset].rfind('\n').map_or(0, |ix| ix + 1)
+}
+
+fn line_indent(offset: usize, text: &str) -> usize {
+    let line_start = text[..offset].rfind('\n').map_or(0, |ix| ix + 1);
+    text[line_start..offset]
+        .chars()
+        .take_while(|c| *c == ' ')
+        .count()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::KeymapFile;
+    use serde_json::{json, Value};
 
     #[test]
     fn can_deserialize_keymap_with_trailing_comma() {
@@ -160,4 +467,113 @@ mod tests {
         };
         KeymapFile::parse(json).unwrap();
     }
+
+    #[test]
+    fn test_update_binding_in_json_text() {
+        let mut text = indoc::indoc! {r#"
+            [
+              // Editor bindings
+              {
+                "context": "Editor",
+                "bindings": {
+                  "ctrl-a": "editor::SelectAll",
+                  "ctrl-b": "editor::MoveLeft"
+                }
+              }
+            ]
+        "#}
+        .to_string();
+
+        // Replacing an existing binding
+        KeymapFile::update_binding_in_json_text(
+            &mut text,
+            Some("Editor"),
+            "ctrl-b",
+            Some(&json!(["editor::MoveRight", { "stop_at_soft_wraps": true }])),
+            2,
+        )
+        .unwrap();
+
+        // Adding a binding to an existing block
+        KeymapFile::update_binding_in_json_text(
+            &mut text,
+            Some("Editor"),
+            "ctrl-c",
+            Some(&Value::Null),
+            2,
+        )
+        .unwrap();
+
+        // Adding a binding to a new block
+        KeymapFile::update_binding_in_json_text(
+            &mut text,
+            None,
+            "ctrl-shift-p",
+            Some(&json!("command_palette::Toggle")),
+            2,
+        )
+        .unwrap();
+
+        // Removing a binding
+        KeymapFile::update_binding_in_json_text(&mut text, Some("Editor"), "ctrl-a", None, 2)
+            .unwrap();
+
+        assert_eq!(
+            text,
+            indoc::indoc! {r#"
+                [
+                  // Editor bindings
+                  {
+                    "context": "Editor",
+                    "bindings": {
+                      "ctrl-b": [
+                        "editor::MoveRight",
+                        {
+                          "stop_at_soft_wraps": true
+                        }
+                      ],
+                      "ctrl-c": null
+                    }
+                  },
+                  {
+                    "bindings": {
+                      "ctrl-shift-p": "command_palette::Toggle"
+                    }
+                  }
+                ]
+            "#}
+        );
+
+        let mut text = String::new();
+        KeymapFile::update_binding_in_json_text(
+            &mut text,
+            Some("Workspace"),
+            "ctrl-w",
+            Some(&json!(["pane::CloseActiveItem", { "save_intent": "skip" }])),
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            text,
+            indoc::indoc! {r#"
+                [
+                  {
+                    "context": "Workspace",
+                    "bindings": {
+                      "ctrl-w": [
+                        "pane::CloseActiveItem",
+                        {
+                          "save_intent": "skip"
+                        }
+                      ]
+                    }
+                  }
+                ]
+            "#}
+        );
+        let keymap = KeymapFile::parse(&text).unwrap();
+        let (keystrokes, action) = keymap.blocks()[0].bindings().next().unwrap();
+        assert_eq!(keystrokes, "ctrl-w");
+        assert_eq!(action.name(), Some("pane::CloseActiveItem"));
+    }
 }
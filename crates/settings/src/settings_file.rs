@@ -2,7 +2,7 @@ use crate::{settings_store::SettingsStore, Settings};
 use anyhow::{Context, Result};
 use fs::Fs;
 use futures::{channel::mpsc, StreamExt};
-use gpui::{AppContext, BackgroundExecutor};
+use gpui::{AppContext, BackgroundExecutor, Task};
 use std::{io::ErrorKind, path::PathBuf, sync::Arc, time::Duration};
 use util::{paths, ResultExt};
 
@@ -134,3 +134,78 @@ pub fn update_settings_file<T: Settings>(
     })
     .detach_and_log_err(cx);
 }
+
+/// Sets the value at `key_path` in the settings file at `settings_path`, preserving the rest
+/// of the file's contents, including its comments. Setting a value to null removes it.
+pub fn update_settings_file_at_path(
+    fs: Arc<dyn Fs>,
+    settings_path: PathBuf,
+    key_path: Vec<String>,
+    value: serde_json::Value,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    cx.spawn(|cx| async move {
+        let old_text = if settings_path == *paths::SETTINGS {
+            load_settings(&fs).await?
+        } else if fs.is_file(&settings_path).await {
+            fs.load(&settings_path).await?
+        } else {
+            crate::initial_local_settings_content().to_string()
+        };
+        let new_text = cx.read_global(|store: &SettingsStore, _cx| {
+            let key_path = key_path.iter().map(String::as_str).collect::<Vec<_>>();
+            let mut new_text = old_text;
+            for (range, replacement) in store.edits_for_update_at_path(&new_text, &key_path, value)
+            {
+                new_text.replace_range(range, &replacement);
+            }
+            new_text
+        })?;
+
+        let resolved_path = if fs.is_file(&settings_path).await {
+            fs.canonicalize(&settings_path).await.with_context(|| {
+                format!("Failed to canonicalize settings path {:?}", settings_path)
+            })?
+        } else {
+            if let Some(parent) = settings_path.parent() {
+                fs.create_dir(parent).await?;
+            }
+            settings_path
+        };
+        fs.atomic_write(resolved_path.clone(), new_text)
+            .await
+            .with_context(|| format!("Failed to write settings to file {:?}", resolved_path))
+    })
+}
+
+/// Updates the text of the user's keymap file, for example with
+/// [`KeymapFile::update_binding_in_json_text`].
+pub fn update_keymap_file(
+    fs: Arc<dyn Fs>,
+    update: impl 'static + Send + FnOnce(&mut String) -> Result<()>,
+    cx: &mut AppContext,
+) -> Task<Result<()>> {
+    cx.background_executor().spawn(async move {
+        let keymap_path = paths::KEYMAP.as_path();
+        let mut text = if fs.is_file(keymap_path).await {
+            fs.load(keymap_path).await?
+        } else {
+            String::new()
+        };
+        update(&mut text)?;
+
+        let resolved_path = if fs.is_file(keymap_path).await {
+            fs.canonicalize(keymap_path)
+                .await
+                .with_context(|| format!("Failed to canonicalize keymap path {:?}", keymap_path))?
+        } else {
+            if let Some(parent) = keymap_path.parent() {
+                fs.create_dir(parent).await?;
+            }
+            keymap_path.to_path_buf()
+        };
+        fs.atomic_write(resolved_path.clone(), text)
+            .await
+            .with_context(|| format!("Failed to write keymap to file {:?}", resolved_path))
+    })
+}
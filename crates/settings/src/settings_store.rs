@@ -15,6 +15,8 @@ use std::{
 };
 use util::{merge_non_null_json_value_into, RangeExt, ResultExt as _};
 
+use crate::json_edit::json_removal_range;
+
 /// A value that can be defined as a user setting.
 ///
 /// Settings can be loaded from a combination of multiple JSON files.
@@ -266,6 +268,16 @@ impl SettingsStore {
         &self.raw_user_settings
     }
 
+    /// Get the default settings as a raw JSON value.
+    pub fn raw_default_settings(&self) -> &serde_json::Value {
+        &self.raw_default_settings
+    }
+
+    /// Get the settings defined in a worktree's settings file as a raw JSON value.
+    pub fn raw_local_settings(&self, root_id: usize, path: &Path) -> Option<&serde_json::Value> {
+        self.raw_local_settings.get(&(root_id, Arc::from(path)))
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn test(cx: &mut AppContext) -> Self {
         let mut this = Self::default();
@@ -348,6 +360,44 @@ impl SettingsStore {
         edits
     }
 
+    /// Sets the value at the given key path in a JSON file, returning a list of edits
+    /// to apply to the JSON file. Unlike [`Self::edits_for_update`], this doesn't
+    /// require knowing the setting's type, which is useful for settings that are only
+    /// known through the JSON schema. Setting a value to null removes it.
+    pub fn edits_for_update_at_path(
+        &self,
+        text: &str,
+        key_path: &[&str],
+        new_value: serde_json::Value,
+    ) -> Vec<(Range<usize>, String)> {
+        let raw_settings = parse_json_with_comments::<serde_json::Value>(text).unwrap_or_default();
+        let old_value = key_path
+            .iter()
+            .try_fold(&raw_settings, |value, key| value.get(key))
+            .cloned()
+            .unwrap_or_default();
+
+        let mut edits = Vec::new();
+        if new_value.is_null() {
+            if let Some(range) = removal_range_in_json_text(text, key_path) {
+                edits.push((range, String::new()));
+            }
+            return edits;
+        }
+        let tab_size = self.json_tab_size();
+        let mut key_path = key_path.to_vec();
+        let mut text = text.to_string();
+        update_value_in_json_text(
+            &mut text,
+            &mut key_path,
+            tab_size,
+            &old_value,
+            &new_value,
+            &mut edits,
+        );
+        edits
+    }
+
     /// Configure the tab sized when updating JSON files.
     pub fn set_json_tab_size_callback<T: Settings>(
         &mut self,
@@ -893,7 +943,39 @@ fn replace_value_in_json_text(
     }
 }
 
-fn to_pretty_json(value: &impl Serialize, indent_size: usize, indent_prefix_len: usize) -> String {
+/// Returns the range to delete in order to remove the value at the given key path from a
+/// JSON file, along with its key.
+fn removal_range_in_json_text(text: &str, key_path: &[&str]) -> Option<Range<usize>> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&tree_sitter_json::language()).unwrap();
+    let syntax_tree = parser.parse(text, None)?;
+
+    let mut value = syntax_tree.root_node().named_child(0)?;
+    let mut pair = None;
+    for key in key_path {
+        if value.kind() != "object" {
+            return None;
+        }
+        let quoted_key = format!("\"{key}\"");
+        let mut cursor = value.walk();
+        let found = value.named_children(&mut cursor).find(|child| {
+            child.kind() == "pair"
+                && child
+                    .child_by_field_name("key")
+                    .and_then(|key| text.get(key.byte_range()))
+                    .map_or(false, |key| key == quoted_key)
+        })?;
+        value = found.child_by_field_name("value")?;
+        pair = Some(found);
+    }
+    Some(json_removal_range(pair?, text))
+}
+
+pub(crate) fn to_pretty_json(
+    value: &impl Serialize,
+    indent_size: usize,
+    indent_prefix_len: usize,
+) -> String {
     const SPACES: [u8; 32] = [b' '; 32];
 
     debug_assert!(indent_size <= SPACES.len());
@@ -1222,6 +1304,101 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_setting_store_update_at_path(cx: &mut AppContext) {
+        let store = SettingsStore::default();
+        let update = |old_json: &str, key_path: &[&str], value: serde_json::Value| {
+            let mut new_json = old_json.to_string();
+            for (range, replacement) in store.edits_for_update_at_path(old_json, key_path, value) {
+                new_json.replace_range(range, &replacement);
+            }
+            new_json
+        };
+
+        // comments and unrelated keys are preserved
+        pretty_assertions::assert_eq!(
+            update(
+                &r#"{
+                    // The user's age.
+                    "user": { "age": 36, "name": "Max" },
+                    "turbo": false
+                }"#
+                .unindent(),
+                &["user", "age"],
+                serde_json::json!(37),
+            ),
+            r#"{
+                // The user's age.
+                "user": { "age": 37, "name": "Max" },
+                "turbo": false
+            }"#
+            .unindent(),
+        );
+
+        // missing objects are created
+        pretty_assertions::assert_eq!(
+            update(
+                &r#"{
+                    "turbo": false
+                }"#
+                .unindent(),
+                &["user", "staff"],
+                serde_json::json!(true),
+            ),
+            r#"{
+                "user": {
+                    "staff": true
+                },
+                "turbo": false
+            }"#
+            .unindent(),
+        );
+
+        // setting an existing value to null removes it
+        pretty_assertions::assert_eq!(
+            update(
+                &r#"{
+                    "user": { "age": 36, "name": "Max" },
+                    "turbo": false
+                }"#
+                .unindent(),
+                &["turbo"],
+                serde_json::Value::Null,
+            ),
+            r#"{
+                "user": { "age": 36, "name": "Max" }
+            }"#
+            .unindent(),
+        );
+        pretty_assertions::assert_eq!(
+            update(
+                &r#"{
+                    "user": {
+                        "age": 36,
+                        "name": "Max"
+                    },
+                    "turbo": false
+                }"#
+                .unindent(),
+                &["user", "age"],
+                serde_json::Value::Null,
+            ),
+            r#"{
+                "user": {
+                    "name": "Max"
+                },
+                "turbo": false
+            }"#
+            .unindent(),
+        );
+
+        // setting a missing value to null does nothing
+        pretty_assertions::assert_eq!(
+            update(r#"{ "turbo": false }"#, &["user"], serde_json::Value::Null),
+            r#"{ "turbo": false }"#,
+        );
+    }
+
     fn check_settings_update<T: Settings>(
         store: &mut SettingsStore,
         old_json: String,
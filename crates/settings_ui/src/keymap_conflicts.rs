@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use collections::HashMap;
+use gpui::{KeyBindingContextPredicate, KeyContext, Keystroke, SharedString};
+
+/// The largest number of context stacks against which two predicates are evaluated
+/// when comparing them.
+const MAX_CONTEXT_STACKS: usize = 4096;
+
+/// A key binding, as far as detecting conflicts between bindings is concerned.
+pub(crate) struct BindingInfo<'a> {
+    pub keystrokes: &'a [Keystroke],
+    pub predicate: Option<&'a KeyBindingContextPredicate>,
+    /// Whether the binding disables its keystrokes instead of binding them to an action.
+    pub disables: bool,
+}
+
+/// How a binding is affected by the other bindings for the same keystrokes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum BindingStatus {
+    Ok,
+    /// The binding is overridden, in every context it applies to, by a binding that
+    /// was added after it.
+    ShadowedBy(usize),
+    /// The binding's keystrokes are disabled in every context it applies to.
+    DisabledBy(usize),
+    /// The binding applies to some of the same contexts as other bindings, without
+    /// being more or less specific than them.
+    ConflictsWith(Vec<usize>),
+}
+
+/// Determines the status of each of the given bindings, which must be in the order they
+/// were added to the keymap, in which later bindings take precedence over earlier ones.
+pub(crate) fn binding_statuses(bindings: &[BindingInfo]) -> Vec<BindingStatus> {
+    let mut statuses = vec![BindingStatus::Ok; bindings.len()];
+    let mut bindings_by_keystrokes = HashMap::<&[Keystroke], Vec<usize>>::default();
+    for (ix, binding) in bindings.iter().enumerate() {
+        bindings_by_keystrokes
+            .entry(binding.keystrokes)
+            .or_default()
+            .push(ix);
+    }
+
+    for group in bindings_by_keystrokes.values() {
+        for (i, &earlier_ix) in group.iter().enumerate() {
+            for &later_ix in &group[i + 1..] {
+                let earlier = &bindings[earlier_ix];
+                let later = &bindings[later_ix];
+                let Some(relation) = relate_predicates(earlier.predicate, later.predicate) else {
+                    continue;
+                };
+
+                match (earlier.disables, later.disables) {
+                    (true, true) => {}
+                    // Disabled keystrokes are ignored regardless of the order of the bindings.
+                    (false, true) if relation.first_implies_second => {
+                        statuses[earlier_ix] = BindingStatus::DisabledBy(later_ix);
+                    }
+                    (true, false) if relation.second_implies_first => {
+                        statuses[later_ix] = BindingStatus::DisabledBy(earlier_ix);
+                    }
+                    (false, false) if relation.first_implies_second => {
+                        if !matches!(statuses[earlier_ix], BindingStatus::DisabledBy(_)) {
+                            statuses[earlier_ix] = BindingStatus::ShadowedBy(later_ix);
+                        }
+                    }
+                    (false, false)
+                        if relation.overlaps
+                            && !relation.second_implies_first
+                            && relation.shares_names =>
+                    {
+                        for (ix, other_ix) in [(earlier_ix, later_ix), (later_ix, earlier_ix)] {
+                            if let BindingStatus::ConflictsWith(others) = &mut statuses[ix] {
+                                others.push(other_ix);
+                            } else if statuses[ix] == BindingStatus::Ok {
+                                statuses[ix] = BindingStatus::ConflictsWith(vec![other_ix]);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    statuses
+}
+
+/// How two context predicates relate to each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct PredicateRelation {
+    /// Whether some context stack satisfies both predicates.
+    pub overlaps: bool,
+    /// Whether every context stack that satisfies the first predicate satisfies the second.
+    pub first_implies_second: bool,
+    /// Whether every context stack that satisfies the second predicate satisfies the first.
+    pub second_implies_first: bool,
+    /// Whether both predicates refer to some of the same identifiers. Predicates that
+    /// don't, like `Editor` and `Terminal`, usually match different elements, which are
+    /// ordered by their depth when dispatching keystrokes.
+    pub shares_names: bool,
+}
+
+/// Compares two predicates by evaluating them against every combination of the contexts
+/// that they refer to. A missing predicate matches every context. Returns `None` if the
+/// predicates refer to too many contexts to be compared.
+pub(crate) fn relate_predicates(
+    first: Option<&KeyBindingContextPredicate>,
+    second: Option<&KeyBindingContextPredicate>,
+) -> Option<PredicateRelation> {
+    let mut first_names = BTreeMap::default();
+    let mut second_names = BTreeMap::default();
+    let mut depth = 1;
+    if let Some(first) = first {
+        collect_names(first, &mut first_names);
+        depth = depth.max(predicate_depth(first));
+    }
+    if let Some(second) = second {
+        collect_names(second, &mut second_names);
+        depth = depth.max(predicate_depth(second));
+    }
+    let shares_names = first_names
+        .keys()
+        .any(|name| second_names.contains_key(name));
+
+    let mut names = first_names;
+    for (name, values) in second_names {
+        names.entry(name).or_default().extend(values);
+    }
+
+    let contexts = all_contexts(&names)?;
+    let stack_count = u32::try_from(depth)
+        .ok()
+        .and_then(|depth| contexts.len().checked_pow(depth))
+        .filter(|count| *count <= MAX_CONTEXT_STACKS)?;
+
+    let mut relation = PredicateRelation {
+        overlaps: false,
+        first_implies_second: true,
+        second_implies_first: true,
+        shares_names,
+    };
+    let mut stack = Vec::with_capacity(depth);
+    for mut stack_ix in 0..stack_count {
+        stack.clear();
+        for _ in 0..depth {
+            stack.push(contexts[stack_ix % contexts.len()].clone());
+            stack_ix /= contexts.len();
+        }
+
+        let first_matches = first.map_or(true, |predicate| predicate.eval(&stack));
+        let second_matches = second.map_or(true, |predicate| predicate.eval(&stack));
+        relation.overlaps |= first_matches && second_matches;
+        relation.first_implies_second &= !first_matches || second_matches;
+        relation.second_implies_first &= !second_matches || first_matches;
+    }
+    Some(relation)
+}
+
+/// Collects the identifiers a predicate refers to, along with the values they're compared to.
+fn collect_names(
+    predicate: &KeyBindingContextPredicate,
+    names: &mut BTreeMap<SharedString, BTreeSet<SharedString>>,
+) {
+    match predicate {
+        KeyBindingContextPredicate::Identifier(name) => {
+            names.entry(name.clone()).or_default();
+        }
+        KeyBindingContextPredicate::Equal(key, value)
+        | KeyBindingContextPredicate::NotEqual(key, value) => {
+            names.entry(key.clone()).or_default().insert(value.clone());
+        }
+        KeyBindingContextPredicate::Not(predicate) => collect_names(predicate, names),
+        KeyBindingContextPredicate::Child(left, right)
+        | KeyBindingContextPredicate::And(left, right)
+        | KeyBindingContextPredicate::Or(left, right) => {
+            collect_names(left, names);
+            collect_names(right, names);
+        }
+    }
+}
+
+/// Returns how many contexts of a context stack a predicate looks at.
+fn predicate_depth(predicate: &KeyBindingContextPredicate) -> usize {
+    match predicate {
+        KeyBindingContextPredicate::Identifier(_)
+        | KeyBindingContextPredicate::Equal(_, _)
+        | KeyBindingContextPredicate::NotEqual(_, _) => 1,
+        KeyBindingContextPredicate::Not(predicate) => predicate_depth(predicate),
+        KeyBindingContextPredicate::Child(parent, child) => {
+            (predicate_depth(parent) + 1).max(predicate_depth(child))
+        }
+        KeyBindingContextPredicate::And(left, right)
+        | KeyBindingContextPredicate::Or(left, right) => {
+            predicate_depth(left).max(predicate_depth(right))
+        }
+    }
+}
+
+/// Returns every distinct context with respect to the given identifiers: each one can be
+/// missing, present without a value, or set to one of the values it's compared to, or to
+/// some other value.
+fn all_contexts(names: &BTreeMap<SharedString, BTreeSet<SharedString>>) -> Option<Vec<KeyContext>> {
+    let mut contexts = vec![KeyContext::default()];
+    for (name, values) in names {
+        let mut states = vec![None, Some(None)];
+        if !values.is_empty() {
+            states.extend(values.iter().cloned().map(Some).map(Some));
+            // No identifier is empty, so this stands for any other value.
+            states.push(Some(Some(SharedString::default())));
+        }
+        if contexts.len() * states.len() > MAX_CONTEXT_STACKS {
+            return None;
+        }
+
+        contexts = contexts
+            .into_iter()
+            .flat_map(|context| {
+                states.iter().map(move |state| {
+                    let mut context = context.clone();
+                    match state {
+                        None => {}
+                        Some(None) => context.add(name.clone()),
+                        Some(Some(value)) => context.set(name.clone(), value.clone()),
+                    }
+                    context
+                })
+            })
+            .collect();
+    }
+    Some(contexts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding<'a>(
+        keystrokes: &'a [Keystroke],
+        predicate: Option<&'a KeyBindingContextPredicate>,
+    ) -> BindingInfo<'a> {
+        BindingInfo {
+            keystrokes,
+            predicate,
+            disables: false,
+        }
+    }
+
+    #[test]
+    fn test_relate_predicates() {
+        let parse = |source| KeyBindingContextPredicate::parse(source).unwrap();
+
+        let relation = relate_predicates(
+            Some(&parse("Editor && mode == full")),
+            Some(&parse("Editor")),
+        )
+        .unwrap();
+        assert!(relation.overlaps);
+        assert!(relation.first_implies_second);
+        assert!(!relation.second_implies_first);
+
+        let relation = relate_predicates(
+            Some(&parse("Editor && mode == full")),
+            Some(&parse("Editor && mode == auto_height")),
+        )
+        .unwrap();
+        assert!(!relation.overlaps);
+
+        let relation = relate_predicates(
+            Some(&parse("Editor && !menu")),
+            Some(&parse("Editor && vim_mode == normal")),
+        )
+        .unwrap();
+        assert!(relation.overlaps);
+        assert!(!relation.first_implies_second);
+        assert!(!relation.second_implies_first);
+        assert!(relation.shares_names);
+
+        let relation =
+            relate_predicates(Some(&parse("Workspace > Pane")), Some(&parse("Pane"))).unwrap();
+        assert!(relation.first_implies_second);
+        assert!(!relation.second_implies_first);
+
+        let relation = relate_predicates(None, Some(&parse("Terminal"))).unwrap();
+        assert!(relation.overlaps);
+        assert!(relation.second_implies_first);
+        assert!(!relation.shares_names);
+    }
+
+    #[test]
+    fn test_binding_statuses() {
+        let parse = |source| KeyBindingContextPredicate::parse(source).unwrap();
+        let ctrl_a = [Keystroke::parse("ctrl-a").unwrap()];
+        let ctrl_b = [Keystroke::parse("ctrl-b").unwrap()];
+        let editor = parse("Editor");
+        let full_editor = parse("Editor && mode == full");
+        let vim_editor = parse("Editor && vim_mode == normal");
+        let terminal = parse("Terminal");
+
+        let bindings = [
+            binding(&ctrl_a, None),
+            binding(&ctrl_a, Some(&full_editor)),
+            binding(&ctrl_a, Some(&vim_editor)),
+            binding(&ctrl_a, Some(&terminal)),
+            binding(&ctrl_b, Some(&full_editor)),
+            binding(&ctrl_b, Some(&editor)),
+            BindingInfo {
+                keystrokes: &ctrl_b,
+                predicate: Some(&terminal),
+                disables: true,
+            },
+            binding(&ctrl_b, Some(&terminal)),
+        ];
+        assert_eq!(
+            binding_statuses(&bindings),
+            [
+                // A global binding followed by more specific ones.
+                BindingStatus::Ok,
+                BindingStatus::ConflictsWith(vec![2]),
+                BindingStatus::ConflictsWith(vec![1]),
+                BindingStatus::Ok,
+                // A specific binding followed by a more general one.
+                BindingStatus::ShadowedBy(5),
+                BindingStatus::Ok,
+                BindingStatus::Ok,
+                BindingStatus::DisabledBy(6),
+            ]
+        );
+    }
+}
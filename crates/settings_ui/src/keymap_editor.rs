@@ -0,0 +1,771 @@
+use std::sync::Arc;
+
+use collections::HashSet;
+use editor::{Editor, EditorEvent};
+use fs::Fs;
+use gpui::{
+    list, Action, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView,
+    KeyBindingContextPredicate, KeyContext, KeyDownEvent, Keystroke, ListAlignment, ListState,
+    NoAction, Render, Subscription, View, ViewContext, VisualContext, WindowContext,
+};
+use serde_json::Value;
+use settings::{KeymapFile, KeymapSource, LoadedKeymaps};
+use ui::{prelude::*, IconButtonShape, ToggleButton, Tooltip};
+use util::ResultExt;
+use workspace::{
+    item::{Item, ItemEvent},
+    notifications::NotifyTaskExt,
+    Workspace, WorkspaceId,
+};
+
+use crate::{
+    keymap_conflicts::{binding_statuses, BindingInfo, BindingStatus},
+    render_text_input,
+};
+
+/// The longest sequence of keystrokes that can be recorded.
+const MAX_RECORDED_KEYSTROKES: usize = 4;
+
+/// An action, along with one of the bindings for it, if it has any.
+struct KeymapEntry {
+    action_name: SharedString,
+    /// The action as it's written in keymap files: either its name, or its name and data.
+    action: Value,
+    binding: Option<EntryBinding>,
+}
+
+struct EntryBinding {
+    keystrokes: String,
+    key_binding: gpui::KeyBinding,
+    source: KeymapSource,
+    context: Option<SharedString>,
+    status: BindingStatus,
+    /// Whether the binding applies to the inspected context.
+    is_active: bool,
+}
+
+impl KeymapEntry {
+    fn matches(&self, query: &str) -> bool {
+        let action_name = self.action_name.to_lowercase();
+        let context = self
+            .binding
+            .as_ref()
+            .and_then(|binding| binding.context.as_deref())
+            .unwrap_or("")
+            .to_lowercase();
+        let keystrokes = self
+            .binding
+            .as_ref()
+            .map_or("", |binding| binding.keystrokes.as_str());
+        query.split_whitespace().all(|word| {
+            let word = word.to_lowercase();
+            action_name.contains(&word)
+                || action_name.replace('_', " ").contains(&word)
+                || context.contains(&word)
+                || keystrokes.contains(&word)
+        })
+    }
+
+    fn starts_with_keystrokes(&self, keystrokes: &[Keystroke]) -> bool {
+        self.binding.as_ref().map_or(false, |binding| {
+            let bound = binding.key_binding.keystrokes();
+            bound.len() >= keystrokes.len()
+                && bound.iter().zip(keystrokes).all(|(bound, keystroke)| {
+                    bound.key == keystroke.key && bound.modifiers == keystroke.modifiers
+                })
+        })
+    }
+
+    fn has_issue(&self) -> bool {
+        self.binding
+            .as_ref()
+            .map_or(false, |binding| binding.status != BindingStatus::Ok)
+    }
+}
+
+/// What recorded keystrokes are used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RecordingTarget {
+    /// Finding the actions bound to the keystrokes.
+    Search,
+    /// Binding the keystrokes to the action of the given entry.
+    Binding(usize),
+}
+
+/// Lists every action along with its key bindings, flagging the bindings that are
+/// shadowed by or conflict with other bindings, and edits the user's keymap file.
+pub struct KeymapEditor {
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    recorder_focus_handle: FocusHandle,
+    entries: Vec<KeymapEntry>,
+    filtered_entries: Vec<usize>,
+    query_editor: View<Editor>,
+    list_state: ListState,
+    /// The key contexts of the element that was focused when the keymap editor was opened.
+    inspected_context: Vec<KeyContext>,
+    only_active: bool,
+    only_issues: bool,
+    recording: Option<RecordingTarget>,
+    recorded_keystrokes: Vec<Keystroke>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl KeymapEditor {
+    pub fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let inspected_context = cx.context_stack();
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let query_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Search actions, contexts and keystrokes...", cx);
+                editor
+            });
+
+            let view = cx.view().downgrade();
+            let list_state = ListState::new(0, ListAlignment::Top, px(1000.), move |ix, cx| {
+                if let Some(view) = view.upgrade() {
+                    view.update(cx, |view, cx| view.render_entry(ix, cx))
+                } else {
+                    div().into_any()
+                }
+            });
+
+            let recorder_focus_handle = cx.focus_handle();
+            let subscriptions = vec![
+                cx.subscribe(&query_editor, |this, _, event, cx| {
+                    if let EditorEvent::Edited = event {
+                        this.filter_entries(cx);
+                    }
+                }),
+                cx.observe_global::<LoadedKeymaps>(|this, cx| {
+                    this.stop_recording(cx);
+                    this.entries = build_entries(&this.inspected_context, cx);
+                    this.filter_entries(cx);
+                }),
+                cx.on_blur(&recorder_focus_handle, |this, cx| this.stop_recording(cx)),
+            ];
+
+            let mut this = Self {
+                fs,
+                focus_handle: cx.focus_handle(),
+                recorder_focus_handle,
+                entries: build_entries(&inspected_context, cx),
+                filtered_entries: Vec::new(),
+                query_editor,
+                list_state,
+                inspected_context,
+                only_active: false,
+                only_issues: false,
+                recording: None,
+                recorded_keystrokes: Vec::new(),
+                _subscriptions: subscriptions,
+            };
+            this.filter_entries(cx);
+            this
+        })
+    }
+
+    fn filter_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        let searched_keystrokes = match self.recording {
+            Some(RecordingTarget::Search) => self.recorded_keystrokes.as_slice(),
+            _ => &[],
+        };
+        self.filtered_entries = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                entry.matches(&query)
+                    && (searched_keystrokes.is_empty()
+                        || entry.starts_with_keystrokes(searched_keystrokes))
+                    && (!self.only_active
+                        || entry
+                            .binding
+                            .as_ref()
+                            .map_or(false, |binding| binding.is_active))
+                    && (!self.only_issues || entry.has_issue())
+            })
+            .map(|(ix, _)| ix)
+            .collect();
+        self.filtered_entries.sort_by(|a, b| {
+            self.entries[*a]
+                .action_name
+                .cmp(&self.entries[*b].action_name)
+        });
+        self.list_state.reset(self.filtered_entries.len());
+        cx.notify();
+    }
+
+    fn start_recording(&mut self, target: RecordingTarget, cx: &mut ViewContext<Self>) {
+        self.recording = Some(target);
+        self.recorded_keystrokes.clear();
+        cx.focus(&self.recorder_focus_handle);
+        cx.set_keystroke_recorder(Some(&self.recorder_focus_handle));
+        self.filter_entries(cx);
+    }
+
+    fn stop_recording(&mut self, cx: &mut ViewContext<Self>) {
+        if self.recording.take().is_some() {
+            cx.set_keystroke_recorder(None);
+            self.recorded_keystrokes.clear();
+            if self.recorder_focus_handle.is_focused(cx) {
+                cx.focus_view(&self.query_editor);
+            }
+            self.filter_entries(cx);
+        }
+    }
+
+    fn record_keystroke(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        if self.recording.is_none() || event.is_held {
+            return;
+        }
+        if self.recorded_keystrokes.len() == MAX_RECORDED_KEYSTROKES {
+            self.recorded_keystrokes.clear();
+        }
+        self.recorded_keystrokes.push(Keystroke {
+            ime_key: None,
+            ..event.keystroke.clone()
+        });
+        cx.stop_propagation();
+        if self.recording == Some(RecordingTarget::Search) {
+            self.filter_entries(cx);
+        } else {
+            cx.notify();
+        }
+    }
+
+    /// Binds the recorded keystrokes to the action of the entry being edited.
+    fn save_recorded_binding(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(RecordingTarget::Binding(ix)) = self.recording else {
+            return;
+        };
+        if self.recorded_keystrokes.is_empty() {
+            return;
+        }
+
+        let entry = &self.entries[ix];
+        let keystrokes = self
+            .recorded_keystrokes
+            .iter()
+            .map(Keystroke::unparse)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let action = entry.action.clone();
+        let (context, replaced_keystrokes) = match &entry.binding {
+            // Changing one of the user's bindings replaces it, whereas changing any other
+            // binding adds a binding for the same action and context to the user's keymap.
+            Some(binding) => (
+                binding.context.as_ref().map(ToString::to_string),
+                (binding.source == KeymapSource::User).then(|| binding.keystrokes.clone()),
+            ),
+            None => (None, None),
+        };
+        settings::update_keymap_file(
+            self.fs.clone(),
+            move |text| {
+                if let Some(replaced_keystrokes) = replaced_keystrokes {
+                    KeymapFile::update_binding_in_json_text(
+                        text,
+                        context.as_deref(),
+                        &replaced_keystrokes,
+                        None,
+                        2,
+                    )?;
+                }
+                KeymapFile::update_binding_in_json_text(
+                    text,
+                    context.as_deref(),
+                    &keystrokes,
+                    Some(&action),
+                    2,
+                )
+            },
+            cx,
+        )
+        .detach_and_notify_err(cx);
+        self.stop_recording(cx);
+    }
+
+    /// Removes one of the user's bindings, or disables any other binding in its context.
+    fn remove_binding(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(binding) = &self.entries[ix].binding else {
+            return;
+        };
+        let context = binding.context.as_ref().map(ToString::to_string);
+        let keystrokes = binding.keystrokes.clone();
+        let action = (binding.source != KeymapSource::User).then_some(Value::Null);
+        settings::update_keymap_file(
+            self.fs.clone(),
+            move |text| {
+                KeymapFile::update_binding_in_json_text(
+                    text,
+                    context.as_deref(),
+                    &keystrokes,
+                    action.as_ref(),
+                    2,
+                )
+            },
+            cx,
+        )
+        .detach_and_notify_err(cx);
+    }
+
+    fn render_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        let Some(&entry_ix) = self.filtered_entries.get(ix) else {
+            return div().into_any();
+        };
+        let entry = &self.entries[entry_ix];
+        let is_recording_binding = self.recording == Some(RecordingTarget::Binding(entry_ix));
+
+        let keystrokes = if is_recording_binding {
+            self.render_recorded_keystrokes("Press the new keystrokes…", cx)
+                .into_any_element()
+        } else if let Some(binding) = &entry.binding {
+            ui::KeyBinding::new(binding.key_binding.clone()).into_any_element()
+        } else {
+            Label::new("Unbound")
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .into_any_element()
+        };
+
+        h_flex()
+            .id(("keymap-entry", entry_ix))
+            .w_full()
+            .px_4()
+            .py_2()
+            .gap_4()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_1()
+                    .child(Label::new(entry.action_name.clone()))
+                    .when_some(
+                        entry.action.as_array().and_then(|items| items.get(1)),
+                        |this, data| {
+                            this.child(
+                                Label::new(data.to_string())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        },
+                    ),
+            )
+            .child(h_flex().w(rems(12.)).child(keystrokes))
+            .child(
+                h_flex().w(rems(16.)).when_some(
+                    entry
+                        .binding
+                        .as_ref()
+                        .and_then(|binding| binding.context.clone()),
+                    |this, context| {
+                        this.child(
+                            Label::new(context)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    },
+                ),
+            )
+            .child(h_flex().w(rems(6.)).gap_1().when_some(
+                entry.binding.as_ref(),
+                |this, binding| {
+                    this.child(
+                        Label::new(binding.source.name())
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .when(binding.is_active, |this| {
+                        this.child(
+                            Icon::new(IconName::Check)
+                                .size(IconSize::Small)
+                                .color(Color::Accent),
+                        )
+                    })
+                    .child(self.render_status(entry_ix, &binding.status))
+                },
+            ))
+            .child(
+                h_flex()
+                    .w(rems(4.))
+                    .gap_1()
+                    .justify_end()
+                    .child(self.render_entry_buttons(entry_ix, cx)),
+            )
+            .into_any_element()
+    }
+
+    fn render_status(&self, entry_ix: usize, status: &BindingStatus) -> AnyElement {
+        let describe = |ix: &usize| {
+            let entry = &self.entries[*ix];
+            match &entry.binding {
+                Some(binding) => format!(
+                    "{} ({}{})",
+                    entry.action_name,
+                    binding.source.name(),
+                    binding
+                        .context
+                        .as_ref()
+                        .map(|context| format!(", in {context}"))
+                        .unwrap_or_default()
+                ),
+                None => entry.action_name.to_string(),
+            }
+        };
+        let (message, color) = match status {
+            BindingStatus::Ok => return div().into_any_element(),
+            BindingStatus::ShadowedBy(ix) => {
+                (format!("Shadowed by {}", describe(ix)), Color::Muted)
+            }
+            BindingStatus::DisabledBy(ix) => {
+                (format!("Disabled by {}", describe(ix)), Color::Muted)
+            }
+            BindingStatus::ConflictsWith(ixs) => (
+                format!(
+                    "Conflicts with {}",
+                    ixs.iter().map(describe).collect::<Vec<_>>().join("; ")
+                ),
+                Color::Warning,
+            ),
+        };
+        div()
+            .id(("status", entry_ix))
+            .child(
+                Icon::new(IconName::ExclamationTriangle)
+                    .size(IconSize::Small)
+                    .color(color),
+            )
+            .tooltip(move |cx| Tooltip::text(message.clone(), cx))
+            .into_any_element()
+    }
+
+    fn render_entry_buttons(&self, entry_ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        let entry = &self.entries[entry_ix];
+        if self.recording == Some(RecordingTarget::Binding(entry_ix)) {
+            return h_flex()
+                .gap_1()
+                .child(
+                    IconButton::new(("save", entry_ix), IconName::Check)
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::Small)
+                        .disabled(self.recorded_keystrokes.is_empty())
+                        .tooltip(|cx| Tooltip::text("Save Binding", cx))
+                        .on_click(cx.listener(|this, _, cx| this.save_recorded_binding(cx))),
+                )
+                .child(
+                    IconButton::new(("cancel", entry_ix), IconName::Close)
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::Small)
+                        .tooltip(|cx| Tooltip::text("Cancel", cx))
+                        .on_click(cx.listener(|this, _, cx| this.stop_recording(cx))),
+                )
+                .into_any_element();
+        }
+
+        let is_user_binding = entry
+            .binding
+            .as_ref()
+            .map_or(false, |binding| binding.source == KeymapSource::User);
+        let disables = entry.action.is_null();
+        h_flex()
+            .gap_1()
+            .when(!disables, |this| {
+                let (icon, tooltip) = if is_user_binding {
+                    (IconName::Replace, "Change Binding")
+                } else {
+                    (IconName::Plus, "Add Binding")
+                };
+                this.child(
+                    IconButton::new(("record", entry_ix), icon)
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::Small)
+                        .tooltip(move |cx| Tooltip::text(tooltip, cx))
+                        .on_click(cx.listener(move |this, _, cx| {
+                            this.start_recording(RecordingTarget::Binding(entry_ix), cx)
+                        })),
+                )
+            })
+            .when(entry.binding.is_some(), |this| {
+                let tooltip = if is_user_binding {
+                    "Remove Binding"
+                } else {
+                    "Disable Binding"
+                };
+                this.child(
+                    IconButton::new(("remove", entry_ix), IconName::Delete)
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::Small)
+                        .tooltip(move |cx| Tooltip::text(tooltip, cx))
+                        .on_click(
+                            cx.listener(move |this, _, cx| this.remove_binding(entry_ix, cx)),
+                        ),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn render_recorded_keystrokes(
+        &self,
+        placeholder: &'static str,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let recorded = (!self.recorded_keystrokes.is_empty()).then(|| {
+            self.recorded_keystrokes
+                .iter()
+                .map(Keystroke::unparse)
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        div()
+            .track_focus(&self.recorder_focus_handle)
+            .on_key_down(cx.listener(Self::record_keystroke))
+            .px_2()
+            .py_1()
+            .border_1()
+            .border_color(cx.theme().colors().border_focused)
+            .rounded_md()
+            .child(match recorded {
+                Some(recorded) => Label::new(recorded).size(LabelSize::Small),
+                None => Label::new(placeholder)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            })
+    }
+
+    fn render_search_bar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let is_recording_search = self.recording == Some(RecordingTarget::Search);
+        h_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                h_flex()
+                    .flex_1()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .rounded_lg()
+                    .child(Icon::new(IconName::MagnifyingGlass))
+                    .child(render_text_input(&self.query_editor, cx)),
+            )
+            .when(is_recording_search, |this| {
+                this.child(self.render_recorded_keystrokes("Press keystrokes to find…", cx))
+            })
+            .child(
+                IconButton::new("record-search", IconName::Command)
+                    .shape(IconButtonShape::Square)
+                    .selected(is_recording_search)
+                    .tooltip(|cx| Tooltip::text("Find Actions by Keystroke", cx))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        if is_recording_search {
+                            this.stop_recording(cx);
+                        } else {
+                            this.start_recording(RecordingTarget::Search, cx);
+                        }
+                    })),
+            )
+            .child(
+                ToggleButton::new("only-active", "Active Here")
+                    .style(ButtonStyle::Filled)
+                    .selected(self.only_active)
+                    .disabled(self.inspected_context.is_empty())
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.only_active = !this.only_active;
+                        this.filter_entries(cx);
+                    }))
+                    .tooltip(|cx| {
+                        Tooltip::text("Only show bindings that apply to the inspected context", cx)
+                    })
+                    .first(),
+            )
+            .child(
+                ToggleButton::new("only-issues", "Conflicts")
+                    .style(ButtonStyle::Filled)
+                    .selected(self.only_issues)
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.only_issues = !this.only_issues;
+                        this.filter_entries(cx);
+                    }))
+                    .tooltip(|cx| Tooltip::text("Only show shadowed and conflicting bindings", cx))
+                    .last(),
+            )
+    }
+}
+
+/// Lists the bindings of the loaded keymaps in the order they were added to the keymap,
+/// followed by the actions that aren't bound to any keystrokes.
+fn build_entries(inspected_context: &[KeyContext], cx: &AppContext) -> Vec<KeymapEntry> {
+    let mut entries = Vec::new();
+    let mut predicates = Vec::new();
+    if let Some(loaded_keymaps) = cx.try_global::<LoadedKeymaps>() {
+        for (source, keymap) in loaded_keymaps.keymaps() {
+            for block in keymap.blocks() {
+                let predicate = match block.context() {
+                    Some(context) => match KeyBindingContextPredicate::parse(context).log_err() {
+                        Some(predicate) => Some(predicate),
+                        None => continue,
+                    },
+                    None => None,
+                };
+                for (keystrokes, action) in block.bindings() {
+                    let Some(key_binding) =
+                        gpui::KeyBinding::load(keystrokes, NoAction.boxed_clone(), None).log_err()
+                    else {
+                        continue;
+                    };
+                    let is_active = (1..=inspected_context.len()).any(|depth| {
+                        predicate.as_ref().map_or(true, |predicate| {
+                            predicate.eval(&inspected_context[..depth])
+                        })
+                    });
+                    entries.push(KeymapEntry {
+                        action_name: action.name().unwrap_or(NoAction.name()).to_string().into(),
+                        action: action.value().clone(),
+                        binding: Some(EntryBinding {
+                            keystrokes: keystrokes.to_string(),
+                            key_binding,
+                            source,
+                            context: block.context().map(|context| context.to_string().into()),
+                            status: BindingStatus::Ok,
+                            is_active,
+                        }),
+                    });
+                    predicates.push(predicate.clone());
+                }
+            }
+        }
+    }
+
+    // Bound entries come first, so their indices are the same as those of the bindings.
+    let bindings = entries
+        .iter()
+        .zip(&predicates)
+        .filter_map(|(entry, predicate)| {
+            Some(BindingInfo {
+                keystrokes: entry.binding.as_ref()?.key_binding.keystrokes(),
+                predicate: predicate.as_ref(),
+                disables: entry.action.is_null(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let statuses = binding_statuses(&bindings);
+    for (entry, status) in entries.iter_mut().zip(statuses) {
+        if let Some(binding) = &mut entry.binding {
+            binding.status = status;
+        }
+    }
+
+    let bound_action_names = entries
+        .iter()
+        .map(|entry| entry.action_name.clone())
+        .collect::<HashSet<_>>();
+    for action_name in cx.all_action_names() {
+        if !bound_action_names.contains(action_name) {
+            entries.push(KeymapEntry {
+                action_name: action_name.clone(),
+                action: Value::from(action_name.to_string()),
+                binding: None,
+            });
+        }
+    }
+    entries
+}
+
+impl Render for KeymapEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut key_context = KeyContext::default();
+        key_context.add("KeymapEditor");
+
+        let inspected_context = self
+            .inspected_context
+            .iter()
+            .map(|context| format!("{context:?}"))
+            .collect::<Vec<_>>()
+            .join(" > ");
+
+        v_flex()
+            .key_context(key_context)
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                v_flex()
+                    .gap_4()
+                    .p_4()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        v_flex()
+                            .gap_1()
+                            .child(Headline::new("Keymap").size(HeadlineSize::XLarge))
+                            .when(!inspected_context.is_empty(), |this| {
+                                this.child(
+                                    Label::new(format!("Inspected context: {inspected_context}"))
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    .child(self.render_search_bar(cx)),
+            )
+            .child(if self.filtered_entries.is_empty() {
+                div()
+                    .p_4()
+                    .child(Label::new("No actions match your search.").color(Color::Muted))
+                    .into_any_element()
+            } else {
+                list(self.list_state.clone()).size_full().into_any_element()
+            })
+    }
+}
+
+impl EventEmitter<ItemEvent> for KeymapEditor {}
+
+impl FocusableView for KeymapEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        if self.recording.is_some() {
+            self.recorder_focus_handle.clone()
+        } else {
+            self.query_editor.focus_handle(cx)
+        }
+    }
+}
+
+impl Item for KeymapEditor {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        Label::new("Keymap")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("keymap editor")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: WorkspaceId,
+        _: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        None
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
@@ -0,0 +1,256 @@
+use serde_json::Value;
+
+/// Keys of the settings schema that aren't shown in the settings editor, because their
+/// values are maps keyed by user-defined names, or they duplicate other settings.
+const EXCLUDED_KEYS: &[&str] = &["languages", "lsp", "dev", "nightly", "preview", "stable"];
+const MAX_DEPTH: usize = 4;
+
+/// A setting described by the settings JSON schema.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SettingEntry {
+    pub key_path: Vec<String>,
+    pub description: Option<String>,
+    pub kind: SettingKind,
+}
+
+/// The kind of control used to edit a setting.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum SettingKind {
+    Toggle,
+    Enum(Vec<String>),
+    Number {
+        integer: bool,
+    },
+    Font(Vec<String>),
+    Text,
+    /// A setting whose value can only be edited in the settings file.
+    Json,
+}
+
+impl SettingEntry {
+    pub fn key(&self) -> String {
+        self.key_path.join(".")
+    }
+
+    pub fn matches(&self, query: &str) -> bool {
+        let key = self.key().to_lowercase();
+        let description = self.description.as_deref().unwrap_or("").to_lowercase();
+        query.split_whitespace().all(|word| {
+            let word = word.to_lowercase();
+            key.contains(&word)
+                || key.replace('_', " ").contains(&word)
+                || description.contains(&word)
+        })
+    }
+}
+
+/// Flattens the settings JSON schema into the list of settings it describes, sorted by key.
+pub(crate) fn setting_entries(schema: &Value) -> Vec<SettingEntry> {
+    let mut entries = Vec::new();
+    let mut key_path = Vec::new();
+    collect_entries(schema, schema, &mut key_path, &mut entries);
+    entries.sort_by(|a, b| a.key_path.cmp(&b.key_path));
+    entries
+}
+
+fn collect_entries(
+    root: &Value,
+    schema: &Value,
+    key_path: &mut Vec<String>,
+    entries: &mut Vec<SettingEntry>,
+) {
+    let Some(properties) = resolve(root, schema)
+        .get("properties")
+        .and_then(Value::as_object)
+    else {
+        return;
+    };
+
+    for (key, property) in properties {
+        if key_path.is_empty() && EXCLUDED_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+
+        key_path.push(key.clone());
+        let resolved = resolve(root, property);
+        let description = property
+            .get("description")
+            .or_else(|| resolved.get("description"))
+            .and_then(Value::as_str)
+            .map(ToString::to_string);
+
+        match kind(root, key, resolved) {
+            Some(kind) => entries.push(SettingEntry {
+                key_path: key_path.clone(),
+                description,
+                kind,
+            }),
+            None if key_path.len() < MAX_DEPTH => {
+                collect_entries(root, resolved, key_path, entries);
+            }
+            None => {}
+        }
+        key_path.pop();
+    }
+}
+
+/// Returns the kind of control for a setting, or `None` if it's an object whose
+/// properties should be listed as separate settings.
+fn kind(root: &Value, key: &str, schema: &Value) -> Option<SettingKind> {
+    let is_font = key.ends_with("font_family");
+    if let Some(options) = enum_options(root, schema) {
+        return Some(if is_font {
+            SettingKind::Font(options)
+        } else {
+            SettingKind::Enum(options)
+        });
+    }
+
+    let instance_type = match schema.get("type") {
+        Some(Value::String(instance_type)) => Some(instance_type.as_str()),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|instance_type| *instance_type != "null"),
+        _ => None,
+    };
+    match instance_type {
+        Some("boolean") => Some(SettingKind::Toggle),
+        Some("integer") => Some(SettingKind::Number { integer: true }),
+        Some("number") => Some(SettingKind::Number { integer: false }),
+        Some("string") if is_font => Some(SettingKind::Font(Vec::new())),
+        Some("string") => Some(SettingKind::Text),
+        Some("object") | None
+            if schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .map_or(false, |properties| !properties.is_empty()) =>
+        {
+            None
+        }
+        _ => Some(SettingKind::Json),
+    }
+}
+
+/// Returns the values a setting can take, if it can only take one of a set of strings.
+fn enum_options(root: &Value, schema: &Value) -> Option<Vec<String>> {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(|value| value.as_str().map(ToString::to_string))
+            .collect();
+    }
+    if let Some(value) = schema.get("const").and_then(Value::as_str) {
+        return Some(vec![value.to_string()]);
+    }
+
+    let variants = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)?;
+    let mut options = Vec::new();
+    for variant in variants {
+        options.extend(enum_options(root, resolve(root, variant))?);
+    }
+    Some(options)
+}
+
+/// Follows references to the schema's definitions.
+fn resolve<'a>(root: &'a Value, mut schema: &'a Value) -> &'a Value {
+    for _ in 0..MAX_DEPTH {
+        if let Some(definition) = schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix("#/definitions/"))
+            .and_then(|name| root.get("definitions")?.get(name))
+        {
+            schema = definition;
+        } else if let Some([inner]) = schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+        {
+            schema = inner;
+        } else {
+            break;
+        }
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_setting_entries() {
+        let schema = json!({
+            "definitions": {
+                "FontFamilies": { "type": "string", "enum": ["Zed Mono", "Zed Sans"] },
+                "ShowWhitespace": {
+                    "oneOf": [
+                        { "description": "Only in selections.", "type": "string", "enum": ["selection"] },
+                        { "type": "string", "enum": ["none", "all"] }
+                    ]
+                },
+                "Scrollbar": {
+                    "type": "object",
+                    "properties": {
+                        "git_diff": { "description": "Whether to show git diff indicators.", "type": "boolean" }
+                    }
+                }
+            },
+            "type": "object",
+            "properties": {
+                "buffer_font_family": { "$ref": "#/definitions/FontFamilies" },
+                "tab_size": { "description": "How many columns a tab should occupy.", "type": "integer" },
+                "show_whitespaces": {
+                    "description": "Whether to show tabs and spaces.",
+                    "allOf": [{ "$ref": "#/definitions/ShowWhitespace" }]
+                },
+                "scrollbar": { "$ref": "#/definitions/Scrollbar" },
+                "file_types": { "type": "object", "additionalProperties": { "type": "array" } },
+                "languages": { "type": "object", "properties": { "Rust": { "type": "object" } } },
+                "theme": { "type": ["string", "null"] }
+            }
+        });
+
+        let entries = setting_entries(&schema);
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.key(), entry.kind.clone()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    "buffer_font_family".to_string(),
+                    SettingKind::Font(vec!["Zed Mono".into(), "Zed Sans".into()])
+                ),
+                ("file_types".to_string(), SettingKind::Json),
+                ("scrollbar.git_diff".to_string(), SettingKind::Toggle),
+                (
+                    "show_whitespaces".to_string(),
+                    SettingKind::Enum(vec!["selection".into(), "none".into(), "all".into()])
+                ),
+                (
+                    "tab_size".to_string(),
+                    SettingKind::Number { integer: true }
+                ),
+                ("theme".to_string(), SettingKind::Text),
+            ]
+        );
+        assert_eq!(
+            entries[3].description.as_deref(),
+            Some("Whether to show tabs and spaces.")
+        );
+        assert_eq!(
+            entries[2].description.as_deref(),
+            Some("Whether to show git diff indicators.")
+        );
+
+        assert!(entries[4].matches("tab size"));
+        assert!(entries[4].matches("COLUMNS"));
+        assert!(!entries[4].matches("tab font"));
+    }
+}
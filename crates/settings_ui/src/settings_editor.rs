@@ -0,0 +1,558 @@
+use std::{path::Path, sync::Arc};
+
+use editor::{Editor, EditorEvent};
+use feature_flags::FeatureFlagAppExt;
+use fs::Fs;
+use gpui::{
+    list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, KeyContext,
+    ListAlignment, ListState, Model, Render, Subscription, View, ViewContext, VisualContext,
+    WeakView, WindowContext,
+};
+use project::{Project, Worktree};
+use serde_json::Value;
+use settings::{SettingsJsonSchemaParams, SettingsStore};
+use ui::{
+    popover_menu, prelude::*, Checkbox, ContextMenu, IconButtonShape, Selection, ToggleButton,
+    Tooltip,
+};
+use util::paths::{self, LOCAL_SETTINGS_RELATIVE_PATH};
+use workspace::{
+    item::{Item, ItemEvent},
+    notifications::NotifyTaskExt,
+    Workspace, WorkspaceId,
+};
+
+use crate::{
+    font_picker::FontPickerDelegate,
+    render_text_input,
+    schema::{setting_entries, SettingEntry, SettingKind},
+};
+
+/// Which settings file changes are written to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SettingsTarget {
+    User,
+    Project,
+}
+
+/// Where the effective value of a setting comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SettingSource {
+    Default,
+    User,
+    Project,
+}
+
+impl SettingSource {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Default => "Default",
+            Self::User => "User",
+            Self::Project => "Project",
+        }
+    }
+}
+
+/// Lists every setting described by the settings JSON schema, with a control for changing it.
+pub struct SettingsEditor {
+    fs: Arc<dyn Fs>,
+    project: Model<Project>,
+    focus_handle: FocusHandle,
+    entries: Vec<SettingEntry>,
+    filtered_entries: Vec<usize>,
+    font_names: Vec<String>,
+    query_editor: View<Editor>,
+    list_state: ListState,
+    target: SettingsTarget,
+    /// The setting whose value is being typed in, if any.
+    value_editor: Option<(usize, View<Editor>)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SettingsEditor {
+    pub fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let project = workspace.project().clone();
+        let language_names = workspace.app_state().languages.language_names();
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let font_names = cx.text_system().all_font_names();
+            let schema = cx.global::<SettingsStore>().json_schema(
+                &SettingsJsonSchemaParams {
+                    staff_mode: cx.is_staff(),
+                    language_names: &language_names,
+                    font_names: &font_names,
+                },
+                cx,
+            );
+            let entries = setting_entries(&schema);
+
+            let query_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Search settings...", cx);
+                editor
+            });
+
+            let view = cx.view().downgrade();
+            let list_state = ListState::new(0, ListAlignment::Top, px(1000.), move |ix, cx| {
+                if let Some(view) = view.upgrade() {
+                    view.update(cx, |view, cx| view.render_entry(ix, cx))
+                } else {
+                    div().into_any()
+                }
+            });
+
+            let subscriptions = vec![
+                cx.subscribe(&query_editor, |this, _, event, cx| {
+                    if let EditorEvent::Edited = event {
+                        this.filter_entries(cx);
+                    }
+                }),
+                cx.observe_global::<SettingsStore>(|this, cx| {
+                    this.list_state.reset(this.filtered_entries.len());
+                    cx.notify();
+                }),
+            ];
+
+            let mut this = Self {
+                fs,
+                project,
+                focus_handle: cx.focus_handle(),
+                entries,
+                filtered_entries: Vec::new(),
+                font_names,
+                query_editor,
+                list_state,
+                target: SettingsTarget::User,
+                value_editor: None,
+                _subscriptions: subscriptions,
+            };
+            this.filter_entries(cx);
+            this
+        })
+    }
+
+    fn filter_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        self.filtered_entries = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.matches(&query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.value_editor = None;
+        self.list_state.reset(self.filtered_entries.len());
+        cx.notify();
+    }
+
+    /// The worktree whose `.zed/settings.json` file is used for project settings.
+    fn settings_worktree(&self, cx: &AppContext) -> Option<Model<Worktree>> {
+        let project = self.project.read(cx);
+        if !project.is_local() {
+            return None;
+        }
+        project.visible_worktrees(cx).find(|worktree| {
+            worktree
+                .read(cx)
+                .root_entry()
+                .map_or(false, |entry| entry.is_dir())
+        })
+    }
+
+    fn value(&self, entry: &SettingEntry, cx: &AppContext) -> Option<(Value, SettingSource)> {
+        let store = cx.global::<SettingsStore>();
+        let lookup = |settings: &Value| {
+            entry
+                .key_path
+                .iter()
+                .try_fold(settings, |value, key| value.get(key))
+                .filter(|value| !value.is_null())
+                .cloned()
+        };
+
+        let local_settings = self.settings_worktree(cx).and_then(|worktree| {
+            store.raw_local_settings(worktree.read(cx).id().to_usize(), Path::new(""))
+        });
+        if let Some(value) = local_settings.and_then(lookup) {
+            Some((value, SettingSource::Project))
+        } else if let Some(value) = lookup(store.raw_user_settings()) {
+            Some((value, SettingSource::User))
+        } else {
+            lookup(store.raw_default_settings()).map(|value| (value, SettingSource::Default))
+        }
+    }
+
+    fn set_value(&mut self, key_path: Vec<String>, value: Value, cx: &mut ViewContext<Self>) {
+        let settings_path = match self.target {
+            SettingsTarget::User => paths::SETTINGS.clone(),
+            SettingsTarget::Project => {
+                let Some(worktree) = self.settings_worktree(cx) else {
+                    return;
+                };
+                let abs_path = worktree.read(cx).abs_path();
+                abs_path.join(*LOCAL_SETTINGS_RELATIVE_PATH)
+            }
+        };
+        settings::update_settings_file_at_path(self.fs.clone(), settings_path, key_path, value, cx)
+            .detach_and_notify_err(cx);
+    }
+
+    fn start_editing(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let entry = &self.entries[ix];
+        let text = match self.value(entry, cx) {
+            Some((Value::String(text), _)) => text,
+            Some((value, _)) => value.to_string(),
+            None => String::new(),
+        };
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_text(text, cx);
+            editor.select_all(&Default::default(), cx);
+            editor
+        });
+        cx.focus_view(&editor);
+        self.value_editor = Some((ix, editor));
+        self.list_state.reset(self.filtered_entries.len());
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let Some((ix, editor)) = self.value_editor.take() else {
+            return;
+        };
+        let entry = &self.entries[ix];
+        let text = editor.read(cx).text(cx);
+        let value = match entry.kind {
+            SettingKind::Number { integer: true } => {
+                text.trim().parse::<i64>().ok().map(Value::from)
+            }
+            SettingKind::Number { integer: false } => {
+                text.trim().parse::<f64>().ok().map(Value::from)
+            }
+            _ => Some(Value::String(text)),
+        };
+        if let Some(value) = value {
+            let key_path = entry.key_path.clone();
+            self.set_value(key_path, value, cx);
+        }
+        self.stop_editing(cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        if self.value_editor.is_some() {
+            self.stop_editing(cx);
+        } else {
+            cx.propagate();
+        }
+    }
+
+    fn stop_editing(&mut self, cx: &mut ViewContext<Self>) {
+        self.value_editor = None;
+        self.list_state.reset(self.filtered_entries.len());
+        cx.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn render_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
+        let Some(&entry_ix) = self.filtered_entries.get(ix) else {
+            return div().into_any();
+        };
+        let entry = &self.entries[entry_ix];
+        let value = self.value(entry, cx);
+        let source = value.as_ref().map(|(_, source)| *source);
+        let is_overridden_by_target = match (source, self.target) {
+            (Some(SettingSource::User), SettingsTarget::User) => true,
+            (Some(SettingSource::Project), SettingsTarget::Project) => true,
+            _ => false,
+        };
+
+        h_flex()
+            .id(("setting", entry_ix))
+            .w_full()
+            .px_4()
+            .py_2()
+            .gap_4()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_1()
+                    .child(h_flex().gap_2().child(Label::new(entry.key())).when_some(
+                        source,
+                        |this, source| {
+                            this.child(
+                                Label::new(source.label())
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Muted),
+                            )
+                        },
+                    ))
+                    .when_some(entry.description.clone(), |this, description| {
+                        this.child(
+                            Label::new(description)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(self.render_control(entry_ix, value.map(|(value, _)| value), cx))
+                    .when(is_overridden_by_target, |this| {
+                        let key_path = entry.key_path.clone();
+                        this.child(
+                            IconButton::new(("reset", entry_ix), IconName::ArrowCircle)
+                                .shape(IconButtonShape::Square)
+                                .icon_size(IconSize::Small)
+                                .tooltip(|cx| Tooltip::text("Reset to Inherited Value", cx))
+                                .on_click(cx.listener(move |this, _, cx| {
+                                    this.set_value(key_path.clone(), Value::Null, cx)
+                                })),
+                        )
+                    }),
+            )
+            .into_any_element()
+    }
+
+    fn render_control(
+        &self,
+        ix: usize,
+        value: Option<Value>,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        let entry = &self.entries[ix];
+        let key_path = entry.key_path.clone();
+        let view = cx.view().downgrade();
+        let value_label: SharedString = match &value {
+            Some(Value::String(value)) => value.clone().into(),
+            Some(value) => value.to_string().into(),
+            None => "Not Set".into(),
+        };
+
+        match &entry.kind {
+            SettingKind::Toggle => {
+                let checked = if value == Some(Value::Bool(true)) {
+                    Selection::Selected
+                } else {
+                    Selection::Unselected
+                };
+                Checkbox::new(("toggle", ix), checked)
+                    .on_click(move |selection, cx| {
+                        let value = Value::Bool(*selection == Selection::Selected);
+                        update(&view, key_path.clone(), value, cx);
+                    })
+                    .into_any_element()
+            }
+            SettingKind::Enum(options) => {
+                let options = options.clone();
+                popover_menu(("options", ix))
+                    .trigger(
+                        Button::new(("options-trigger", ix), value_label)
+                            .icon(IconName::ChevronDown)
+                            .icon_position(IconPosition::End)
+                            .icon_size(IconSize::Small),
+                    )
+                    .menu(move |cx| {
+                        let view = view.clone();
+                        let key_path = key_path.clone();
+                        let options = options.clone();
+                        Some(ContextMenu::build(cx, move |menu, _| {
+                            options.into_iter().fold(menu, |menu, option| {
+                                let view = view.clone();
+                                let key_path = key_path.clone();
+                                menu.entry(option.clone(), None, move |cx| {
+                                    update(&view, key_path.clone(), Value::from(option.clone()), cx)
+                                })
+                            })
+                        }))
+                    })
+                    .into_any_element()
+            }
+            SettingKind::Font(options) => {
+                let font_names = if options.is_empty() {
+                    self.font_names.clone()
+                } else {
+                    options.clone()
+                };
+                let current_font = value
+                    .as_ref()
+                    .and_then(Value::as_str)
+                    .map(ToString::to_string);
+                popover_menu(("font", ix))
+                    .trigger(
+                        Button::new(("font-trigger", ix), value_label)
+                            .icon(IconName::ChevronDown)
+                            .icon_position(IconPosition::End)
+                            .icon_size(IconSize::Small),
+                    )
+                    .menu(move |cx| {
+                        let view = view.clone();
+                        let key_path = key_path.clone();
+                        Some(FontPickerDelegate::build(
+                            font_names.clone(),
+                            current_font.clone(),
+                            move |font, cx| update(&view, key_path.clone(), Value::from(font), cx),
+                            cx,
+                        ))
+                    })
+                    .into_any_element()
+            }
+            SettingKind::Number { .. } | SettingKind::Text => match &self.value_editor {
+                Some((editing_ix, editor)) if *editing_ix == ix => div()
+                    .w(rems(16.))
+                    .px_2()
+                    .py_1()
+                    .border_1()
+                    .border_color(cx.theme().colors().border_focused)
+                    .rounded_md()
+                    .child(render_text_input(editor, cx))
+                    .into_any_element(),
+                _ => Button::new(("edit", ix), value_label)
+                    .tooltip(|cx| Tooltip::text("Edit Value", cx))
+                    .on_click(cx.listener(move |this, _, cx| this.start_editing(ix, cx)))
+                    .into_any_element(),
+            },
+            SettingKind::Json => Button::new(("json", ix), "Edit in settings.json")
+                .tooltip(move |cx| Tooltip::text(value_label.clone(), cx))
+                .on_click(|_, cx| cx.dispatch_action(Box::new(zed_actions::OpenSettings)))
+                .into_any_element(),
+        }
+    }
+
+    fn render_target_toggle(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let has_project_settings = self.settings_worktree(cx).is_some();
+        h_flex()
+            .child(
+                ToggleButton::new("target-user", "User")
+                    .style(ButtonStyle::Filled)
+                    .selected(self.target == SettingsTarget::User)
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.target = SettingsTarget::User;
+                        this.list_state.reset(this.filtered_entries.len());
+                        cx.notify();
+                    }))
+                    .tooltip(|cx| Tooltip::text("Change your user settings", cx))
+                    .first(),
+            )
+            .child(
+                ToggleButton::new("target-project", "Project")
+                    .style(ButtonStyle::Filled)
+                    .selected(self.target == SettingsTarget::Project)
+                    .disabled(!has_project_settings)
+                    .on_click(cx.listener(|this, _, cx| {
+                        this.target = SettingsTarget::Project;
+                        this.list_state.reset(this.filtered_entries.len());
+                        cx.notify();
+                    }))
+                    .tooltip(|cx| Tooltip::text("Change this project's .zed/settings.json", cx))
+                    .last(),
+            )
+    }
+}
+
+fn update(
+    view: &WeakView<SettingsEditor>,
+    key_path: Vec<String>,
+    value: Value,
+    cx: &mut WindowContext,
+) {
+    view.update(cx, |this, cx| this.set_value(key_path, value, cx))
+        .ok();
+}
+
+impl Render for SettingsEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut key_context = KeyContext::default();
+        key_context.add("SettingsEditor");
+
+        v_flex()
+            .key_context(key_context)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                v_flex()
+                    .gap_4()
+                    .p_4()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .justify_between()
+                            .child(Headline::new("Settings").size(HeadlineSize::XLarge))
+                            .child(self.render_target_toggle(cx)),
+                    )
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .px_2()
+                            .py_1()
+                            .gap_2()
+                            .border_1()
+                            .border_color(cx.theme().colors().border)
+                            .rounded_lg()
+                            .child(Icon::new(IconName::MagnifyingGlass))
+                            .child(render_text_input(&self.query_editor, cx)),
+                    ),
+            )
+            .child(if self.filtered_entries.is_empty() {
+                div()
+                    .p_4()
+                    .child(Label::new("No settings match your search.").color(Color::Muted))
+                    .into_any_element()
+            } else {
+                list(self.list_state.clone()).size_full().into_any_element()
+            })
+    }
+}
+
+impl EventEmitter<ItemEvent> for SettingsEditor {}
+
+impl FocusableView for SettingsEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        match &self.value_editor {
+            Some((_, editor)) => editor.focus_handle(cx),
+            None => self.query_editor.focus_handle(cx),
+        }
+    }
+}
+
+impl Item for SettingsEditor {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        Label::new("Settings")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("settings editor")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: WorkspaceId,
+        _: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        None
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
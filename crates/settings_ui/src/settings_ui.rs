@@ -0,0 +1,79 @@
+mod font_picker;
+mod keymap_conflicts;
+mod keymap_editor;
+mod schema;
+mod settings_editor;
+
+use editor::{Editor, EditorElement, EditorStyle};
+use gpui::{
+    actions, AppContext, FontStyle, FontWeight, TextStyle, View, WhiteSpace, WindowContext,
+};
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::prelude::*;
+use workspace::Workspace;
+
+pub use keymap_editor::KeymapEditor;
+pub use settings_editor::SettingsEditor;
+
+actions!(zed, [OpenSettingsEditor, OpenKeymapEditor]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|workspace, _: &OpenSettingsEditor, cx| {
+                let existing = workspace
+                    .active_pane()
+                    .read(cx)
+                    .items_of_type::<SettingsEditor>()
+                    .next();
+                if let Some(existing) = existing {
+                    workspace.activate_item(&existing, cx);
+                } else {
+                    let settings_editor = SettingsEditor::new(workspace, cx);
+                    workspace.add_item_to_active_pane(Box::new(settings_editor), cx)
+                }
+            })
+            .register_action(|workspace, _: &OpenKeymapEditor, cx| {
+                let existing = workspace
+                    .active_pane()
+                    .read(cx)
+                    .items_of_type::<KeymapEditor>()
+                    .next();
+                if let Some(existing) = existing {
+                    workspace.activate_item(&existing, cx);
+                } else {
+                    let keymap_editor = KeymapEditor::new(workspace, cx);
+                    workspace.add_item_to_active_pane(Box::new(keymap_editor), cx)
+                }
+            });
+    })
+    .detach();
+}
+
+fn render_text_input(editor: &View<Editor>, cx: &WindowContext) -> impl IntoElement {
+    let settings = ThemeSettings::get_global(cx);
+    let text_style = TextStyle {
+        color: cx.theme().colors().text,
+        font_family: settings.ui_font.family.clone(),
+        font_features: settings.ui_font.features,
+        font_size: rems(0.875).into(),
+        font_weight: FontWeight::NORMAL,
+        font_style: FontStyle::Normal,
+        line_height: relative(1.3),
+        background_color: None,
+        underline: None,
+        strikethrough: None,
+        white_space: WhiteSpace::Normal,
+    };
+
+    EditorElement::new(
+        editor,
+        EditorStyle {
+            background: cx.theme().colors().editor_background,
+            local_player: cx.theme().players().local(),
+            text: text_style,
+            ..Default::default()
+        },
+    )
+}
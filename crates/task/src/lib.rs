@@ -6,7 +6,7 @@ pub mod static_source;
 
 use collections::HashMap;
 use gpui::ModelContext;
-use static_source::RevealStrategy;
+use static_source::{RevealStrategy, TerminalSplit};
 use std::any::Any;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -37,6 +37,8 @@ pub struct SpawnInTerminal {
     pub allow_concurrent_runs: bool,
     /// What to do with the terminal pane and tab, after the command was started.
     pub reveal: RevealStrategy,
+    /// Which split of the terminal panel to spawn the process in, the active one if not set.
+    pub split: Option<TerminalSplit>,
 }
 
 /// Keeps track of the file associated with a task and context of tasks execution (i.e. current file or current function)
@@ -51,6 +51,7 @@ impl Task for OneshotTask {
             use_new_terminal: Default::default(),
             allow_concurrent_runs: Default::default(),
             reveal: RevealStrategy::default(),
+            split: None,
         })
     }
 }
@@ -39,6 +39,7 @@ impl Task for StaticTask {
             command: self.definition.command.clone(),
             args: self.definition.args.clone(),
             reveal: self.definition.reveal,
+            split: self.definition.split,
             env: definition_env,
         })
     }
@@ -91,6 +92,12 @@ pub(crate) struct Definition {
     /// * `never` — avoid changing current terminal pane focus, but still add/reuse the task's tab there
     #[serde(default)]
     pub reveal: RevealStrategy,
+    /// Which split of the terminal panel to spawn the task's terminal in:
+    /// * not set — use the active split (default)
+    /// * `left`, `right`, `up` or `down` — open a split next to the active one in that direction,
+    ///   and keep using it for later runs of the task, while it's open
+    #[serde(default)]
+    pub split: Option<TerminalSplit>,
 }
 
 /// What to do with the terminal pane and tab, after the command was started.
@@ -104,6 +111,20 @@ pub enum RevealStrategy {
     Never,
 }
 
+/// Where to open a split of the terminal panel for a task, relative to the active one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalSplit {
+    /// Open the split to the left of the active one.
+    Left,
+    /// Open the split to the right of the active one.
+    Right,
+    /// Open the split above the active one.
+    Up,
+    /// Open the split below the active one.
+    Down,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct DefinitionProvider(Vec<Definition>);
@@ -0,0 +1,346 @@
+//! Persisting a terminal's scrollback across restarts, and mirroring a shared terminal's screen.
+//!
+//! The primary grid is serialized back into text with SGR sequences for its colors and styles,
+//! so that a restored terminal can feed it through Alacritty's parser again before the new
+//! shell starts writing. The replayed history is never sent to the shell.
+//!
+//! The rows on screen are serialized the same way when a terminal is shared with collaborators.
+//! Each row is serialized separately, so that only the rows that changed are sent to them.
+
+use std::fmt::Write as _;
+
+use alacritty_terminal::{
+    event::EventListener,
+    grid::{Dimensions, Row},
+    index::{Column, Line},
+    term::{
+        cell::{Cell, Flags},
+        TermMode,
+    },
+    vte::ansi::{Color, NamedColor, Processor},
+    Term,
+};
+
+/// Upper bound for the size of a persisted scrollback, regardless of its line count.
+pub const MAX_PERSISTED_SCROLLBACK_BYTES: usize = 1024 * 1024;
+
+/// Printed below the replayed history, so it's not mistaken for output of the new shell.
+const RESTORED_SEPARATOR: &str = "\x1b[0;2m[Restored session]\x1b[0m\r\n\r\n";
+
+/// Serializes up to `max_lines` of the most recent rows above the cursor, including their colors.
+///
+/// Returns `None` while the alternate screen is active, as the primary grid can't be read then.
+pub fn snapshot<T>(term: &Term<T>, max_lines: usize, max_bytes: usize) -> Option<String> {
+    if term.mode().contains(TermMode::ALT_SCREEN) {
+        return None;
+    }
+
+    let grid = term.grid();
+    // The cursor's line holds the live prompt, which the restored shell prints again.
+    let end = grid.cursor.point.line.0;
+    let max_lines = max_lines.min(i32::MAX as usize) as i32;
+    let start = end
+        .saturating_sub(max_lines)
+        .max(-(grid.history_size() as i32));
+
+    let mut rows = Vec::new();
+    let mut len = 0;
+    for line in (start..end).rev() {
+        let mut row = String::new();
+        write_row(&grid[Line(line)], &mut row);
+        if len + row.len() > max_bytes {
+            break;
+        }
+        len += row.len();
+        rows.push(row);
+    }
+
+    let scrollback = rows.into_iter().rev().collect::<String>();
+    let scrollback = scrollback.trim_start_matches("\r\n");
+    if scrollback.trim().is_empty() {
+        None
+    } else {
+        Some(scrollback.to_string())
+    }
+}
+
+/// Feeds a previously persisted scrollback into the terminal, followed by a separator.
+pub(crate) fn replay<T: EventListener>(term: &mut Term<T>, scrollback: &str) {
+    let mut parser: Processor = Processor::new();
+    for byte in scrollback.bytes().chain(RESTORED_SEPARATOR.bytes()) {
+        parser.advance(term, byte);
+    }
+}
+
+/// Serializes each row on screen, without line breaks.
+pub fn screen_rows<T>(term: &Term<T>) -> Vec<String> {
+    let grid = term.grid();
+    (0..grid.screen_lines() as i32)
+        .map(|line| {
+            let mut row = String::new();
+            write_row(&grid[Line(line)], &mut row);
+            let len = row.trim_end_matches("\r\n").len();
+            row.truncate(len);
+            row
+        })
+        .collect()
+}
+
+/// Overwrites the given rows with rows serialized by [`screen_rows`], then moves the cursor.
+pub(crate) fn apply_rows<'a, T: EventListener>(
+    term: &mut Term<T>,
+    rows: impl IntoIterator<Item = (usize, &'a str)>,
+    replace_screen: bool,
+    cursor: (usize, usize),
+) {
+    // Line wrapping is disabled while writing, so that rows wider than this terminal are cut
+    // off instead of overwriting the row below them.
+    let mut output = String::from("\x1b[0m\x1b[?7l");
+    if replace_screen {
+        output.push_str("\x1b[3J\x1b[2J");
+    }
+    let screen_lines = term.screen_lines();
+    for (line, row) in rows {
+        if line < screen_lines {
+            write!(output, "\x1b[{};1H\x1b[2K{row}", line + 1).ok();
+        }
+    }
+    write!(output, "\x1b[?7h\x1b[{};{}H", cursor.0 + 1, cursor.1 + 1).ok();
+
+    let mut parser: Processor = Processor::new();
+    for byte in output.bytes() {
+        parser.advance(term, byte);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Style {
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+        }
+    }
+}
+
+impl Style {
+    fn of(cell: &Cell) -> Self {
+        Style {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags
+                & (Flags::BOLD
+                    | Flags::DIM
+                    | Flags::ITALIC
+                    | Flags::UNDERLINE
+                    | Flags::INVERSE
+                    | Flags::HIDDEN
+                    | Flags::STRIKEOUT),
+        }
+    }
+
+    fn write_sgr(&self, out: &mut String) {
+        out.push_str("\x1b[0");
+        for (flag, code) in [
+            (Flags::BOLD, 1),
+            (Flags::DIM, 2),
+            (Flags::ITALIC, 3),
+            (Flags::UNDERLINE, 4),
+            (Flags::INVERSE, 7),
+            (Flags::HIDDEN, 8),
+            (Flags::STRIKEOUT, 9),
+        ] {
+            if self.flags.contains(flag) {
+                write!(out, ";{code}").ok();
+            }
+        }
+        write_color(self.fg, 30, out);
+        write_color(self.bg, 40, out);
+        out.push('m');
+    }
+}
+
+fn write_color(color: Color, base: u8, out: &mut String) {
+    match color {
+        Color::Named(named) => {
+            let index = named as usize;
+            if index < 8 {
+                write!(out, ";{}", base as usize + index).ok();
+            } else if index < 16 {
+                write!(out, ";{}", base as usize + 60 + index - 8).ok();
+            }
+        }
+        Color::Indexed(index) => {
+            write!(out, ";{};5;{index}", base + 8).ok();
+        }
+        Color::Spec(rgb) => {
+            write!(out, ";{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b).ok();
+        }
+    }
+}
+
+fn is_blank(cell: &Cell) -> bool {
+    cell.c == ' '
+        && cell.bg == Color::Named(NamedColor::Background)
+        && !cell
+            .flags
+            .intersects(Flags::INVERSE | Flags::UNDERLINE | Flags::STRIKEOUT)
+}
+
+fn write_row(row: &Row<Cell>, out: &mut String) {
+    let columns = row.len();
+    let wraps = columns > 0 && row[Column(columns - 1)].flags.contains(Flags::WRAPLINE);
+    let len = if wraps {
+        columns
+    } else {
+        (0..columns)
+            .rev()
+            .find(|&column| !is_blank(&row[Column(column)]))
+            .map_or(0, |column| column + 1)
+    };
+
+    let mut style = Style::default();
+    for column in 0..len {
+        let cell = &row[Column(column)];
+        if cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
+        }
+
+        let cell_style = Style::of(cell);
+        if cell_style != style {
+            cell_style.write_sgr(out);
+            style = cell_style;
+        }
+        out.push(cell.c);
+        if let Some(zerowidth) = cell.zerowidth() {
+            out.extend(zerowidth);
+        }
+    }
+
+    if style != Style::default() {
+        out.push_str("\x1b[0m");
+    }
+    // Wrapped rows are left to wrap again, so they reflow to the restored terminal's width.
+    if !wraps {
+        out.push_str("\r\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{event::VoidListener, term::Config};
+
+    use super::*;
+
+    struct TestSize {
+        lines: usize,
+        columns: usize,
+    }
+
+    impl Dimensions for TestSize {
+        fn total_lines(&self) -> usize {
+            self.lines
+        }
+
+        fn screen_lines(&self) -> usize {
+            self.lines
+        }
+
+        fn columns(&self) -> usize {
+            self.columns
+        }
+    }
+
+    fn term_with_output(output: &str) -> Term<VoidListener> {
+        let mut term = Term::new(
+            Config::default(),
+            &TestSize {
+                lines: 5,
+                columns: 20,
+            },
+            VoidListener,
+        );
+        let mut parser: Processor = Processor::new();
+        for byte in output.bytes() {
+            parser.advance(&mut term, byte);
+        }
+        term
+    }
+
+    #[test]
+    fn test_snapshot_keeps_colors_and_history() {
+        let term =
+            term_with_output("one\r\n\x1b[31mtwo\x1b[0m\r\nthree\r\nfour\r\nfive\r\nsix\r\n$ ");
+        assert_eq!(
+            snapshot(&term, 100, MAX_PERSISTED_SCROLLBACK_BYTES).as_deref(),
+            Some("one\r\n\x1b[0;31mtwo\x1b[0m\r\nthree\r\nfour\r\nfive\r\nsix\r\n")
+        );
+    }
+
+    #[test]
+    fn test_snapshot_caps() {
+        let term = term_with_output("one\r\ntwo\r\nthree\r\n$ ");
+        assert_eq!(
+            snapshot(&term, 2, MAX_PERSISTED_SCROLLBACK_BYTES).as_deref(),
+            Some("two\r\nthree\r\n")
+        );
+        assert_eq!(snapshot(&term, 100, 8).as_deref(), Some("three\r\n"));
+        assert_eq!(snapshot(&term_with_output("$ "), 100, 100), None);
+    }
+
+    #[test]
+    fn test_replay_round_trip() {
+        let term = term_with_output("\x1b[1;44mbold\x1b[0m plain\r\n$ ");
+        let scrollback = snapshot(&term, 100, MAX_PERSISTED_SCROLLBACK_BYTES).unwrap();
+
+        let mut restored = term_with_output("");
+        replay(&mut restored, &scrollback);
+        let cell = &restored.grid()[Line(0)][Column(0)];
+        assert_eq!(cell.c, 'b');
+        assert!(cell.flags.contains(Flags::BOLD));
+        assert_eq!(cell.bg, Color::Named(NamedColor::Blue));
+        assert_eq!(restored.grid()[Line(0)][Column(5)].c, 'p');
+        assert_eq!(restored.grid().cursor.point.line, Line(3));
+    }
+
+    #[test]
+    fn test_apply_screen_rows() {
+        let term = term_with_output("one\r\n\x1b[33mtwo\x1b[0m\r\nthree\r\n$ ");
+        let rows = screen_rows(&term);
+        assert_eq!(rows, ["one", "\x1b[0;33mtwo\x1b[0m", "three", "$", ""]);
+
+        let mut mirrored = term_with_output("a\r\nb\r\nc\r\nd\r\ne");
+        apply_rows(
+            &mut mirrored,
+            rows.iter()
+                .enumerate()
+                .map(|(line, row)| (line, row.as_str())),
+            true,
+            (3, 2),
+        );
+        assert_eq!(screen_rows(&mirrored), rows);
+        assert_eq!(mirrored.grid().cursor.point, term.grid().cursor.point);
+
+        apply_rows(
+            &mut mirrored,
+            [(1, "a row that is wider than the terminal")],
+            false,
+            (1, 0),
+        );
+        let rows = screen_rows(&mirrored);
+        assert!(rows[1].starts_with("a row that is wider"));
+        assert_eq!(rows[2], "three");
+        assert_eq!(mirrored.grid().cursor.point.line, Line(1));
+        assert_eq!(mirrored.grid().cursor.point.column, Column(0));
+    }
+}
@@ -0,0 +1,1033 @@
+//! Shell integration via the semantic prompt sequences (OSC 133) and their VS Code
+//! extension (OSC 633).
+//!
+//! Alacritty ignores these sequences, so the PTY reader is wrapped in a [`ShellIntegrationPty`]
+//! which scans the byte stream for them before handing it to Alacritty's parser. Marks are
+//! positioned by the number of line feeds that preceded them and are only resolved to grid
+//! lines when needed, walking up from the cursor. This keeps them correct while the scrollback
+//! is rotated, which absolute grid lines would not be.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, OnceLock,
+    },
+};
+
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::cell::Flags,
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+    Term,
+};
+use collections::HashMap;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use polling::{Event as PollingEvent, PollMode, Poller};
+use util::ResultExt;
+
+use crate::terminal_settings::Shell;
+
+/// Only the most recent commands are tracked, older ones have usually scrolled out of the
+/// scrollback anyway.
+const MAX_COMMAND_BLOCKS: usize = 1024;
+/// Sequences longer than this are not shell integration marks and are skipped.
+const MAX_SEQUENCE_LEN: usize = 4096;
+
+/// A semantic mark emitted by the shell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShellMark {
+    /// `OSC 133 ; A`, the shell is about to print the prompt.
+    PromptStart,
+    /// `OSC 133 ; B`, the prompt was printed and the user can type a command.
+    CommandStart,
+    /// `OSC 133 ; C`, the command was submitted and its output follows.
+    CommandExecuted,
+    /// `OSC 133 ; D [; exit_code]`, the command has finished.
+    CommandFinished { exit_code: Option<i32> },
+    /// `OSC 633 ; E ; command_line`, the command line that is about to be executed.
+    CommandLine(String),
+    /// `OSC 633 ; P ; Cwd=path` or `OSC 7 ; file://host/path`.
+    WorkingDirectory(PathBuf),
+    /// The visible screen or the scrollback was erased, previous marks can't be located anymore.
+    ScreenCleared,
+}
+
+/// A mark together with the number of line feeds that preceded it in the PTY output.
+type PositionedMark = (usize, ShellMark);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+    Csi,
+    Skip,
+}
+
+/// Incrementally scans PTY output for shell integration sequences, counting the line feeds
+/// written to the primary screen along the way.
+pub(crate) struct MarkScanner {
+    state: ScanState,
+    sequence: Vec<u8>,
+    newlines: usize,
+    alt_screen: bool,
+}
+
+impl MarkScanner {
+    pub fn new() -> Self {
+        Self {
+            state: ScanState::Ground,
+            sequence: Vec::new(),
+            newlines: 0,
+            alt_screen: false,
+        }
+    }
+
+    pub fn newlines(&self) -> usize {
+        self.newlines
+    }
+
+    pub fn scan(&mut self, bytes: &[u8], mut on_mark: impl FnMut(usize, ShellMark)) {
+        for &byte in bytes {
+            match self.state {
+                ScanState::Ground => match byte {
+                    b'\n' if !self.alt_screen => self.newlines += 1,
+                    0x1b => self.state = ScanState::Escape,
+                    _ => {}
+                },
+                ScanState::Escape => self.escape(byte),
+                ScanState::Osc => match byte {
+                    0x07 => {
+                        self.finish_osc(&mut on_mark);
+                        self.state = ScanState::Ground;
+                    }
+                    0x1b => self.state = ScanState::OscEscape,
+                    0x18 | 0x1a => self.state = ScanState::Ground,
+                    _ => self.push(byte),
+                },
+                ScanState::OscEscape => {
+                    if byte == b'\\' {
+                        self.finish_osc(&mut on_mark);
+                        self.state = ScanState::Ground;
+                    } else {
+                        // An escape aborts the string and starts a new sequence.
+                        self.escape(byte);
+                    }
+                }
+                ScanState::Csi => match byte {
+                    0x40..=0x7e => {
+                        self.finish_csi(byte, &mut on_mark);
+                        self.state = ScanState::Ground;
+                    }
+                    0x1b => self.state = ScanState::Escape,
+                    0x18 | 0x1a => self.state = ScanState::Ground,
+                    _ => self.push(byte),
+                },
+                ScanState::Skip => match byte {
+                    0x07 | 0x18 | 0x1a => self.state = ScanState::Ground,
+                    0x1b => self.state = ScanState::OscEscape,
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    fn escape(&mut self, byte: u8) {
+        self.sequence.clear();
+        self.state = match byte {
+            b']' => ScanState::Osc,
+            b'[' => ScanState::Csi,
+            0x1b => ScanState::Escape,
+            _ => ScanState::Ground,
+        };
+    }
+
+    fn push(&mut self, byte: u8) {
+        if self.sequence.len() < MAX_SEQUENCE_LEN {
+            self.sequence.push(byte);
+        } else if self.state == ScanState::Osc {
+            self.state = ScanState::Skip;
+        } else {
+            self.state = ScanState::Ground;
+        }
+    }
+
+    fn finish_osc(&mut self, on_mark: &mut impl FnMut(usize, ShellMark)) {
+        if let Some(mark) = parse_osc(&self.sequence) {
+            on_mark(self.newlines, mark);
+        }
+    }
+
+    fn finish_csi(&mut self, action: u8, on_mark: &mut impl FnMut(usize, ShellMark)) {
+        let Ok(params) = std::str::from_utf8(&self.sequence) else {
+            return;
+        };
+        match action {
+            b'h' | b'l' => {
+                if let Some(modes) = params.strip_prefix('?') {
+                    if modes
+                        .split(';')
+                        .any(|mode| matches!(mode, "47" | "1047" | "1049"))
+                    {
+                        self.alt_screen = action == b'h';
+                    }
+                }
+            }
+            b'J' if !self.alt_screen && matches!(params, "2" | "3") => {
+                on_mark(self.newlines, ShellMark::ScreenCleared)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn parse_osc(payload: &[u8]) -> Option<ShellMark> {
+    let payload = std::str::from_utf8(payload).ok()?;
+    let (command, rest) = payload.split_once(';').unwrap_or((payload, ""));
+    match command {
+        "133" | "633" => {
+            let mut params = rest.splitn(2, ';');
+            let mark = match params.next()? {
+                "A" => ShellMark::PromptStart,
+                "B" => ShellMark::CommandStart,
+                "C" => ShellMark::CommandExecuted,
+                "D" => ShellMark::CommandFinished {
+                    exit_code: params
+                        .next()
+                        .and_then(|params| params.split(';').next())
+                        .and_then(|exit_code| exit_code.parse().ok()),
+                },
+                "E" if command == "633" => {
+                    let command_line = params.next().unwrap_or_default();
+                    let command_line = command_line.split(';').next().unwrap_or_default();
+                    ShellMark::CommandLine(unescape_value(command_line))
+                }
+                "P" if command == "633" => {
+                    let cwd = params.next()?.strip_prefix("Cwd=")?;
+                    ShellMark::WorkingDirectory(PathBuf::from(unescape_value(cwd)))
+                }
+                _ => return None,
+            };
+            Some(mark)
+        }
+        "7" => {
+            let location = rest.strip_prefix("file://")?;
+            let path = &location[location.find('/')?..];
+            Some(ShellMark::WorkingDirectory(PathBuf::from(percent_decode(
+                path,
+            ))))
+        }
+        _ => None,
+    }
+}
+
+/// Reverts the escaping of `OSC 633` values, where `\` is sent as `\\` and
+/// other special characters as `\xAB`.
+fn unescape_value(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if bytes[ix] == b'\\' {
+            match bytes.get(ix + 1) {
+                Some(b'\\') => {
+                    unescaped.push(b'\\');
+                    ix += 2;
+                    continue;
+                }
+                Some(b'x') => {
+                    if let Some(byte) = value
+                        .get(ix + 2..ix + 4)
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    {
+                        unescaped.push(byte);
+                        ix += 4;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+        }
+        unescaped.push(bytes[ix]);
+        ix += 1;
+    }
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if bytes[ix] == b'%' {
+            if let Some(byte) = value
+                .get(ix + 1..ix + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(byte);
+                ix += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[ix]);
+        ix += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Wraps a PTY so that its output is scanned for shell integration marks before it
+/// reaches Alacritty. The output itself is passed through untouched.
+pub(crate) struct ShellIntegrationPty<P> {
+    reader: MarkReader<P>,
+}
+
+pub(crate) struct MarkReader<P> {
+    pty: P,
+    scanner: MarkScanner,
+    marks_tx: UnboundedSender<PositionedMark>,
+    newlines: Arc<AtomicUsize>,
+}
+
+impl<P: EventedReadWrite> io::Read for MarkReader<P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.pty.reader().read(buf)?;
+        let marks_tx = &self.marks_tx;
+        self.scanner.scan(&buf[..read], |newline_index, mark| {
+            marks_tx.unbounded_send((newline_index, mark)).ok();
+        });
+        self.newlines
+            .store(self.scanner.newlines(), Ordering::Release);
+        Ok(read)
+    }
+}
+
+impl<P: EventedReadWrite> EventedReadWrite for ShellIntegrationPty<P> {
+    type Reader = MarkReader<P>;
+    type Writer = P::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        event: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.reader.pty.register(poll, event, mode)
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        event: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.reader.pty.reregister(poll, event, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.reader.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        &mut self.reader
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.reader.pty.writer()
+    }
+}
+
+impl<P: EventedPty> EventedPty for ShellIntegrationPty<P> {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.reader.pty.next_child_event()
+    }
+}
+
+impl<P: OnResize> OnResize for ShellIntegrationPty<P> {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.reader.pty.on_resize(window_size)
+    }
+}
+
+/// A single command run in the shell, delimited by shell integration marks.
+#[derive(Clone, Debug)]
+pub struct CommandBlock {
+    /// The command line, if reported by the shell.
+    pub command: Option<String>,
+    /// The exit code, once the command has finished.
+    pub exit_code: Option<i32>,
+    prompt: usize,
+    output_start: Option<usize>,
+    output_end: Option<usize>,
+}
+
+impl CommandBlock {
+    pub fn is_running(&self) -> bool {
+        self.output_start.is_some() && self.output_end.is_none()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.output_end.is_some()
+    }
+}
+
+/// A finished command's prompt line that is currently visible in the terminal.
+#[derive(Clone, Debug)]
+pub struct CommandMarker {
+    pub line: Line,
+    pub exit_code: i32,
+}
+
+/// The shell integration state of a terminal.
+pub struct ShellIntegration {
+    marks_rx: UnboundedReceiver<PositionedMark>,
+    newlines: Arc<AtomicUsize>,
+    blocks: VecDeque<CommandBlock>,
+    working_directory: Option<PathBuf>,
+    active: bool,
+}
+
+impl ShellIntegration {
+    pub(crate) fn wrap_pty<P>(pty: P) -> (Self, ShellIntegrationPty<P>) {
+        let (marks_tx, marks_rx) = unbounded();
+        let newlines = Arc::new(AtomicUsize::new(0));
+        let pty = ShellIntegrationPty {
+            reader: MarkReader {
+                pty,
+                scanner: MarkScanner::new(),
+                marks_tx,
+                newlines: newlines.clone(),
+            },
+        };
+        let shell_integration = Self {
+            marks_rx,
+            newlines,
+            blocks: VecDeque::new(),
+            working_directory: None,
+            active: false,
+        };
+        (shell_integration, pty)
+    }
+
+    /// Integration for a terminal without a shell of its own, which never reports marks.
+    pub(crate) fn inactive() -> Self {
+        let (_, marks_rx) = unbounded();
+        Self {
+            marks_rx,
+            newlines: Arc::new(AtomicUsize::new(0)),
+            blocks: VecDeque::new(),
+            working_directory: None,
+            active: false,
+        }
+    }
+
+    /// Whether the shell has reported any marks, meaning that command boundaries and
+    /// the working directory are tracked.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn working_directory(&self) -> Option<&Path> {
+        self.working_directory.as_deref()
+    }
+
+    pub fn command_blocks(&self) -> impl DoubleEndedIterator<Item = &CommandBlock> {
+        self.blocks.iter()
+    }
+
+    /// The command line of the command that is currently being executed.
+    pub fn running_command(&self) -> Option<&str> {
+        self.blocks
+            .back()
+            .filter(|block| block.is_running())
+            .and_then(|block| block.command.as_deref())
+    }
+
+    /// Applies the marks received since the last call. Returns whether there were any.
+    pub(crate) fn process_marks(&mut self) -> bool {
+        let mut received = false;
+        while let Ok(Some((newline_index, mark))) = self.marks_rx.try_next() {
+            received = true;
+            self.apply_mark(newline_index, mark);
+        }
+        self.active |= received;
+        received
+    }
+
+    fn apply_mark(&mut self, newline_index: usize, mark: ShellMark) {
+        match mark {
+            ShellMark::PromptStart => {
+                if let Some(block) = self.blocks.back_mut() {
+                    if block.is_running() {
+                        block.output_end = Some(newline_index);
+                    }
+                }
+                if self.blocks.len() == MAX_COMMAND_BLOCKS {
+                    self.blocks.pop_front();
+                }
+                self.blocks.push_back(CommandBlock {
+                    command: None,
+                    exit_code: None,
+                    prompt: newline_index,
+                    output_start: None,
+                    output_end: None,
+                });
+            }
+            ShellMark::CommandStart => {}
+            ShellMark::CommandExecuted => {
+                if let Some(block) = self.blocks.back_mut() {
+                    block.output_start.get_or_insert(newline_index);
+                }
+            }
+            ShellMark::CommandFinished { exit_code } => {
+                if let Some(block) = self.blocks.back_mut() {
+                    // Shells report a status for empty command lines as well, ignore those.
+                    if block.output_start.is_some() && block.output_end.is_none() {
+                        block.output_end = Some(newline_index);
+                        block.exit_code = exit_code;
+                    }
+                }
+            }
+            ShellMark::CommandLine(command) => {
+                if let Some(block) = self.blocks.back_mut() {
+                    block.command = Some(command);
+                }
+            }
+            ShellMark::WorkingDirectory(path) => self.working_directory = Some(path),
+            ShellMark::ScreenCleared => {
+                let current = self.blocks.pop_back().filter(|block| !block.is_finished());
+                self.blocks.clear();
+                self.blocks.extend(current);
+            }
+        }
+    }
+
+    /// Grid lines of the prompts of all commands that are still in the scrollback,
+    /// from the most recent to the oldest.
+    pub(crate) fn prompt_lines<'a, T>(
+        &'a self,
+        term: &'a Term<T>,
+    ) -> impl Iterator<Item = (Line, &'a CommandBlock)> + 'a {
+        let mut resolver = LineResolver::new(term, self.newlines.load(Ordering::Acquire));
+        self.blocks
+            .iter()
+            .rev()
+            .map_while(move |block| Some((resolver.resolve(block.prompt)?, block)))
+    }
+
+    /// The closest prompt line above the top of the viewport.
+    pub(crate) fn previous_prompt_line<T>(&self, term: &Term<T>) -> Option<Line> {
+        let viewport_top = Line(-(term.grid().display_offset() as i32));
+        self.prompt_lines(term)
+            .map(|(line, _)| line)
+            .find(|line| *line < viewport_top)
+    }
+
+    /// The closest prompt line below the top of the viewport.
+    pub(crate) fn next_prompt_line<T>(&self, term: &Term<T>) -> Option<Line> {
+        let viewport_top = Line(-(term.grid().display_offset() as i32));
+        self.prompt_lines(term)
+            .map(|(line, _)| line)
+            .take_while(|line| *line > viewport_top)
+            .last()
+    }
+
+    /// The markers to show next to finished commands in the visible part of the terminal.
+    pub(crate) fn command_markers<T>(&self, term: &Term<T>) -> Vec<CommandMarker> {
+        let display_offset = term.grid().display_offset() as i32;
+        let top = Line(-display_offset);
+        let bottom = Line(top.0 + term.screen_lines() as i32);
+        self.prompt_lines(term)
+            .take_while(|(line, _)| *line >= top)
+            .filter(|(line, _)| *line < bottom)
+            .filter_map(|(line, block)| {
+                Some(CommandMarker {
+                    line,
+                    exit_code: block.exit_code?,
+                })
+            })
+            .collect()
+    }
+
+    /// The grid range covered by the output of the most recent finished command.
+    pub(crate) fn last_command_output<T>(&self, term: &Term<T>) -> Option<(AlacPoint, AlacPoint)> {
+        let block = self.blocks.iter().rev().find(|block| block.is_finished())?;
+        let mut resolver = LineResolver::new(term, self.newlines.load(Ordering::Acquire));
+        let end = resolver.resolve(block.output_end?)?;
+        let start = resolver.resolve(block.output_start?)?;
+        if start >= end {
+            return None;
+        }
+        Some((
+            AlacPoint::new(start, Column(0)),
+            AlacPoint::new(end - 1, term.last_column()),
+        ))
+    }
+}
+
+/// Maps line feed counts from the PTY output to the grid lines they ended up on, walking up
+/// the scrollback from the cursor. Wrapped lines are skipped as they were not produced by a
+/// line feed.
+struct LineResolver<'a, T> {
+    term: &'a Term<T>,
+    newlines: usize,
+    line: Line,
+    offset: usize,
+}
+
+impl<'a, T> LineResolver<'a, T> {
+    fn new(term: &'a Term<T>, newlines: usize) -> Self {
+        Self {
+            term,
+            newlines,
+            line: logical_line_start(term, term.grid().cursor.point.line),
+            offset: 0,
+        }
+    }
+
+    /// Resolves a line feed count to the first grid line of the logical line it is on.
+    /// Counts must be resolved in decreasing order.
+    fn resolve(&mut self, newline_index: usize) -> Option<Line> {
+        let target = self.newlines.saturating_sub(newline_index);
+        while self.offset < target {
+            if self.line <= self.term.topmost_line() {
+                return None;
+            }
+            self.line = logical_line_start(self.term, self.line - 1);
+            self.offset += 1;
+        }
+        Some(self.line)
+    }
+}
+
+fn logical_line_start<T>(term: &Term<T>, mut line: Line) -> Line {
+    let grid = term.grid();
+    let last_column = grid.last_column();
+    while line > grid.topmost_line() && grid[line - 1][last_column].flags.contains(Flags::WRAPLINE)
+    {
+        line -= 1;
+    }
+    line
+}
+
+const BASH_SCRIPT: &str = include_str!("shell_integration/zed.bash");
+const ZSH_ENV_SCRIPT: &str = include_str!("shell_integration/zshenv.zsh");
+const ZSH_PROFILE_SCRIPT: &str = include_str!("shell_integration/zprofile.zsh");
+const ZSH_RC_SCRIPT: &str = include_str!("shell_integration/zshrc.zsh");
+const ZSH_LOGIN_SCRIPT: &str = include_str!("shell_integration/zlogin.zsh");
+const FISH_SCRIPT: &str = include_str!("shell_integration/zed.fish");
+
+/// Environment variables that are changed to inject the integration scripts. They are set
+/// process-wide before spawning the shell, so their original values are restored for
+/// terminals that are spawned without shell integration.
+const INJECTED_VARIABLES: &[&str] = &["ZDOTDIR", "XDG_DATA_DIRS"];
+/// Environment variables that only exist to pass information to the integration scripts.
+const INTEGRATION_VARIABLES: &[&str] = &[
+    "ZED_SHELL_INTEGRATION",
+    "ZED_SHELL_INTEGRATION_DIR",
+    "ZED_SHELL_LOGIN",
+    "ZED_USER_ZDOTDIR",
+    "ZED_USER_XDG_DATA_DIRS",
+];
+
+/// How to launch a shell so that it loads the integration scripts.
+pub(crate) struct Injection {
+    pub shell: Shell,
+    pub env: HashMap<String, String>,
+}
+
+/// Restores the environment variables that may have been changed by a previous injection.
+pub(crate) fn restore_environment() {
+    for (name, value) in original_environment() {
+        match value {
+            Some(value) => std::env::set_var(name, value),
+            None => std::env::remove_var(name),
+        }
+    }
+    for name in INTEGRATION_VARIABLES {
+        std::env::remove_var(name);
+    }
+}
+
+fn original_environment() -> &'static HashMap<&'static str, Option<String>> {
+    static ORIGINAL_ENVIRONMENT: OnceLock<HashMap<&'static str, Option<String>>> = OnceLock::new();
+    ORIGINAL_ENVIRONMENT.get_or_init(|| {
+        INJECTED_VARIABLES
+            .iter()
+            .map(|name| (*name, std::env::var(name).ok()))
+            .collect()
+    })
+}
+
+/// Determines how to launch the given shell with the integration scripts loaded.
+/// Returns `None` for shells that aren't supported.
+pub(crate) fn inject(shell: &Shell) -> Option<Injection> {
+    if cfg!(windows) {
+        return None;
+    }
+
+    let (program, args) = match shell {
+        Shell::System => (std::env::var("SHELL").ok()?, Vec::new()),
+        Shell::Program(program) => (program.clone(), Vec::new()),
+        Shell::WithArguments { program, args } => (program.clone(), args.clone()),
+    };
+    let scripts_dir = install_scripts()?;
+    let original_environment = original_environment();
+    let mut env = HashMap::default();
+    env.insert("ZED_SHELL_INTEGRATION".to_string(), "1".to_string());
+
+    let shell_name = Path::new(&program).file_name()?.to_str()?;
+    let shell = match shell_name {
+        "bash" => {
+            // Bash only reads a custom startup file when it is passed as an argument, which
+            // would conflict with any arguments configured by the user.
+            if !args.is_empty() {
+                return None;
+            }
+            if matches!(shell, Shell::System) && cfg!(target_os = "macos") {
+                env.insert("ZED_SHELL_LOGIN".to_string(), "1".to_string());
+            }
+            Shell::WithArguments {
+                program,
+                args: vec![
+                    "--init-file".to_string(),
+                    scripts_dir.join("zed.bash").to_string_lossy().into_owned(),
+                ],
+            }
+        }
+        "zsh" => {
+            if let Some(Some(user_zdotdir)) = original_environment.get("ZDOTDIR") {
+                env.insert("ZED_USER_ZDOTDIR".to_string(), user_zdotdir.clone());
+            }
+            env.insert(
+                "ZDOTDIR".to_string(),
+                scripts_dir.join("zsh").to_string_lossy().into_owned(),
+            );
+            shell.clone()
+        }
+        "fish" => {
+            let integration_dir = scripts_dir.join("fish").to_string_lossy().into_owned();
+            let data_dirs = match original_environment.get("XDG_DATA_DIRS") {
+                Some(Some(data_dirs)) => {
+                    env.insert("ZED_USER_XDG_DATA_DIRS".to_string(), data_dirs.clone());
+                    format!("{integration_dir}:{data_dirs}")
+                }
+                _ => format!("{integration_dir}:/usr/local/share:/usr/share"),
+            };
+            env.insert("ZED_SHELL_INTEGRATION_DIR".to_string(), integration_dir);
+            env.insert("XDG_DATA_DIRS".to_string(), data_dirs);
+            shell.clone()
+        }
+        _ => return None,
+    };
+
+    Some(Injection { shell, env })
+}
+
+/// Writes the integration scripts to the support directory, once per process.
+fn install_scripts() -> Option<PathBuf> {
+    static SCRIPTS_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    SCRIPTS_DIR
+        .get_or_init(|| {
+            let dir = util::paths::SHELL_INTEGRATION_DIR.clone();
+            let files = [
+                ("zed.bash", BASH_SCRIPT),
+                ("zsh/.zshenv", ZSH_ENV_SCRIPT),
+                ("zsh/.zprofile", ZSH_PROFILE_SCRIPT),
+                ("zsh/.zshrc", ZSH_RC_SCRIPT),
+                ("zsh/.zlogin", ZSH_LOGIN_SCRIPT),
+                ("fish/fish/vendor_conf.d/zed.fish", FISH_SCRIPT),
+            ];
+            for (path, contents) in files {
+                let path = dir.join(path);
+                if std::fs::read_to_string(&path).ok().as_deref() == Some(contents) {
+                    continue;
+                }
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).log_err()?;
+                }
+                std::fs::write(&path, contents).log_err()?;
+            }
+            Some(dir)
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        event::VoidListener, grid::Scroll, term::Config, vte::ansi::Processor,
+    };
+
+    use super::*;
+
+    fn scan(chunks: &[&[u8]]) -> (Vec<PositionedMark>, usize) {
+        let mut scanner = MarkScanner::new();
+        let mut marks = Vec::new();
+        for chunk in chunks {
+            scanner.scan(chunk, |newline_index, mark| {
+                marks.push((newline_index, mark))
+            });
+        }
+        (marks, scanner.newlines())
+    }
+
+    #[test]
+    fn test_scan_semantic_prompt_marks() {
+        let (marks, newlines) = scan(&[
+            b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n",
+            b"\x1b]633;E;ls\x07\x1b]133;C\x07Cargo.toml\r\nsrc\r\n",
+            b"\x1b]133;D;2\x1b\\\x1b]133;A\x07$ ",
+        ]);
+        assert_eq!(
+            marks,
+            vec![
+                (0, ShellMark::PromptStart),
+                (0, ShellMark::CommandStart),
+                (1, ShellMark::CommandLine("ls".into())),
+                (1, ShellMark::CommandExecuted),
+                (3, ShellMark::CommandFinished { exit_code: Some(2) }),
+                (3, ShellMark::PromptStart),
+            ]
+        );
+        assert_eq!(newlines, 3);
+    }
+
+    #[test]
+    fn test_scan_sequences_split_across_reads() {
+        let (marks, _) = scan(&[b"\x1b", b"]13", b"3;D;1", b"27\x1b", b"\\"]);
+        assert_eq!(
+            marks,
+            vec![(
+                0,
+                ShellMark::CommandFinished {
+                    exit_code: Some(127)
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_scan_working_directory() {
+        let (marks, _) = scan(&[
+            b"\x1b]633;P;Cwd=/tmp/a\\x3bb\\\\c\x07",
+            b"\x1b]7;file://host/home/user/with%20space\x07",
+        ]);
+        assert_eq!(
+            marks,
+            vec![
+                (0, ShellMark::WorkingDirectory(PathBuf::from("/tmp/a;b\\c"))),
+                (
+                    0,
+                    ShellMark::WorkingDirectory(PathBuf::from("/home/user/with space"))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_ignores_alternate_screen_newlines() {
+        let (marks, newlines) = scan(&[
+            b"one\r\n\x1b[?1049h",
+            b"\r\n\r\n\x1b[2J\r\n",
+            b"\x1b[?1049l\r\ntwo\x1b[2J",
+        ]);
+        assert_eq!(newlines, 2);
+        assert_eq!(marks, vec![(2, ShellMark::ScreenCleared)]);
+    }
+
+    #[test]
+    fn test_unknown_sequences_are_skipped() {
+        let (marks, newlines) = scan(&[
+            b"\x1b]0;title\x07\x1b]8;;https://zed.dev\x1b\\link\x1b]8;;\x1b\\\r\n",
+            b"\x1b[1;31mred\x1b[0m\r\n\x1b]133;Z\x07",
+        ]);
+        assert!(marks.is_empty());
+        assert_eq!(newlines, 2);
+    }
+
+    struct TestSize {
+        lines: usize,
+        columns: usize,
+    }
+
+    impl Dimensions for TestSize {
+        fn total_lines(&self) -> usize {
+            self.lines
+        }
+
+        fn screen_lines(&self) -> usize {
+            self.lines
+        }
+
+        fn columns(&self) -> usize {
+            self.columns
+        }
+    }
+
+    /// Feeds `output` to both a 10x4 terminal and its shell integration, as the PTY reader
+    /// would.
+    fn run_output(output: &[u8], config: Config) -> (Term<VoidListener>, ShellIntegration) {
+        let size = TestSize {
+            lines: 4,
+            columns: 10,
+        };
+        let mut term = Term::new(config, &size, VoidListener);
+        let mut parser: Processor = Processor::new();
+        for &byte in output {
+            parser.advance(&mut term, byte);
+        }
+
+        let mut shell_integration = ShellIntegration::inactive();
+        let mut scanner = MarkScanner::new();
+        scanner.scan(output, |newline_index, mark| {
+            shell_integration.apply_mark(newline_index, mark)
+        });
+        shell_integration
+            .newlines
+            .store(scanner.newlines(), Ordering::Release);
+        (term, shell_integration)
+    }
+
+    /// Two commands, the first of which prints a line that wraps, followed by a prompt.
+    /// This leaves three lines in the scrollback:
+    ///
+    /// ```text
+    /// -3 $ ls
+    /// -2 aaaaaaaaaa
+    /// -1 aaaaa
+    ///  0 b
+    ///  1 $ false
+    ///  2 x
+    ///  3 $
+    /// ```
+    const TWO_COMMANDS: &[u8] = b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\
+        \x1b]633;E;ls\x07\x1b]133;C\x07aaaaaaaaaaaaaaa\r\nb\r\n\x1b]133;D;0\x07\
+        \x1b]133;A\x07$ \x1b]133;B\x07false\r\n\
+        \x1b]633;E;false\x07\x1b]133;C\x07x\r\n\x1b]133;D;1\x07\
+        \x1b]133;A\x07$ \x1b]133;B\x07";
+
+    fn two_commands() -> (Term<VoidListener>, ShellIntegration) {
+        run_output(TWO_COMMANDS, Config::default())
+    }
+
+    #[test]
+    fn test_apply_marks() {
+        let (_, shell_integration) = two_commands();
+        let blocks = shell_integration
+            .command_blocks()
+            .map(|block| {
+                (
+                    block.command.as_deref(),
+                    block.exit_code,
+                    block.is_finished(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            blocks,
+            [
+                (Some("ls"), Some(0), true),
+                (Some("false"), Some(1), true),
+                (None, None, false),
+            ]
+        );
+        assert_eq!(shell_integration.running_command(), None);
+
+        let mut shell_integration = ShellIntegration::inactive();
+        shell_integration.apply_mark(0, ShellMark::PromptStart);
+        // Shells report a status for empty command lines, which doesn't finish the block.
+        shell_integration.apply_mark(1, ShellMark::CommandFinished { exit_code: Some(0) });
+        assert!(!shell_integration
+            .command_blocks()
+            .next()
+            .unwrap()
+            .is_finished());
+
+        shell_integration.apply_mark(1, ShellMark::PromptStart);
+        shell_integration.apply_mark(1, ShellMark::CommandLine("sleep 10".into()));
+        shell_integration.apply_mark(2, ShellMark::CommandExecuted);
+        assert_eq!(shell_integration.running_command(), Some("sleep 10"));
+
+        // A new prompt ends a command that never reported its status.
+        shell_integration.apply_mark(3, ShellMark::PromptStart);
+        assert_eq!(shell_integration.running_command(), None);
+        assert_eq!(shell_integration.command_blocks().count(), 3);
+
+        // Clearing the screen drops every block but the current one.
+        shell_integration.apply_mark(3, ShellMark::ScreenCleared);
+        assert_eq!(shell_integration.command_blocks().count(), 1);
+        shell_integration.apply_mark(3, ShellMark::WorkingDirectory(PathBuf::from("/home/user")));
+        assert_eq!(
+            shell_integration.working_directory(),
+            Some(Path::new("/home/user"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_prompt_lines() {
+        let (term, shell_integration) = two_commands();
+        let prompt_lines = shell_integration
+            .prompt_lines(&term)
+            .map(|(line, block)| (line, block.command.as_deref()))
+            .collect::<Vec<_>>();
+        // The wrapped output line is not counted as a line of its own.
+        assert_eq!(
+            prompt_lines,
+            [
+                (Line(3), None),
+                (Line(1), Some("false")),
+                (Line(-3), Some("ls")),
+            ]
+        );
+
+        let (start, end) = shell_integration.last_command_output(&term).unwrap();
+        assert_eq!(term.bounds_to_string(start, end).trim(), "x");
+
+        let markers = shell_integration.command_markers(&term);
+        let markers = markers
+            .iter()
+            .map(|marker| (marker.line, marker.exit_code))
+            .collect::<Vec<_>>();
+        assert_eq!(markers, [(Line(1), 1)]);
+    }
+
+    #[test]
+    fn test_prompts_scrolled_out_of_the_scrollback() {
+        let mut output = TWO_COMMANDS.to_vec();
+        // Push the first prompt out of a scrollback that holds a single screen.
+        output.extend_from_slice(b"\r\n".repeat(5).as_slice());
+        let (term, shell_integration) = run_output(
+            &output,
+            Config {
+                scrolling_history: 4,
+                ..Config::default()
+            },
+        );
+
+        let prompt_lines = shell_integration
+            .prompt_lines(&term)
+            .map(|(line, _)| line)
+            .collect::<Vec<_>>();
+        assert_eq!(prompt_lines, [Line(-2), Line(-4)]);
+    }
+
+    #[test]
+    fn test_jump_to_prompts() {
+        let (mut term, shell_integration) = two_commands();
+        assert_eq!(
+            shell_integration.previous_prompt_line(&term),
+            Some(Line(-3))
+        );
+        assert_eq!(shell_integration.next_prompt_line(&term), Some(Line(1)));
+
+        term.scroll_display(Scroll::Delta(3));
+        assert_eq!(shell_integration.previous_prompt_line(&term), None);
+        assert_eq!(shell_integration.next_prompt_line(&term), Some(Line(1)));
+    }
+}
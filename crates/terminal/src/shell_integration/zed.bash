@@ -0,0 +1,81 @@
+# Zed shell integration for bash, loaded with `--init-file`.
+# Reports prompts, commands, their exit status and the working directory
+# using the OSC 133 and OSC 633 sequences.
+
+if [ -n "$ZED_SHELL_LOGIN" ]; then
+    unset ZED_SHELL_LOGIN
+    [ -r /etc/profile ] && . /etc/profile
+    if [ -r "$HOME/.bash_profile" ]; then
+        . "$HOME/.bash_profile"
+    elif [ -r "$HOME/.bash_login" ]; then
+        . "$HOME/.bash_login"
+    elif [ -r "$HOME/.profile" ]; then
+        . "$HOME/.profile"
+    fi
+else
+    [ -r "$HOME/.bashrc" ] && . "$HOME/.bashrc"
+fi
+
+if [ -z "$ZED_SHELL_INTEGRATION" ] || [ -n "$__zed_integration_loaded" ]; then
+    return
+fi
+__zed_integration_loaded=1
+unset ZED_SHELL_INTEGRATION
+
+__zed_escape_value() {
+    local value="${1//\\/\\\\}"
+    value="${value//;/\\x3b}"
+    value="${value//$'\n'/\\x0a}"
+    builtin printf '%s' "$value"
+}
+
+__zed_in_prompt=
+__zed_command_running=
+__zed_original_ps1=
+
+__zed_update_prompt() {
+    if [[ "$PS1" != *'133;A'* ]]; then
+        __zed_original_ps1="$PS1"
+    fi
+    PS1='\[\e]133;A\a\]'"$__zed_original_ps1"'\[\e]133;B\a\]'
+}
+
+__zed_precmd() {
+    local status="$?"
+    __zed_in_prompt=
+    if [ -n "$__zed_command_running" ]; then
+        builtin printf '\e]133;D;%s\a' "$status"
+    fi
+    __zed_command_running=
+    return "$status"
+}
+
+# Runs after the user's own prompt hooks, so that the DEBUG trap doesn't
+# report those hooks as commands typed at the prompt.
+__zed_prompt_ready() {
+    local status="$?"
+    builtin printf '\e]633;P;Cwd=%s\a' "$(__zed_escape_value "$PWD")"
+    __zed_update_prompt
+    __zed_in_prompt=1
+    return "$status"
+}
+
+__zed_preexec() {
+    if [ -z "$__zed_in_prompt" ] || [ -n "$COMP_LINE" ]; then
+        return
+    fi
+    case "$BASH_COMMAND" in
+        __zed_precmd*) return ;;
+    esac
+    __zed_in_prompt=
+    __zed_command_running=1
+    local command_line
+    command_line="$(HISTTIMEFORMAT= builtin history 1 | sed 's/^ *[0-9]* *//')"
+    builtin printf '\e]633;E;%s\a' "$(__zed_escape_value "$command_line")"
+    builtin printf '\e]133;C\a'
+}
+
+PROMPT_COMMAND="__zed_precmd
+${PROMPT_COMMAND}
+__zed_prompt_ready"
+trap '__zed_preexec' DEBUG
@@ -0,0 +1,47 @@
+# Zed shell integration for fish, loaded from vendor_conf.d by prepending
+# its directory to XDG_DATA_DIRS.
+# Reports prompts, commands, their exit status and the working directory
+# using the OSC 133 and OSC 633 sequences.
+
+if set -q ZED_USER_XDG_DATA_DIRS
+    set -gx XDG_DATA_DIRS $ZED_USER_XDG_DATA_DIRS
+else
+    set -e XDG_DATA_DIRS
+end
+set -e ZED_USER_XDG_DATA_DIRS
+set -e ZED_SHELL_INTEGRATION_DIR
+
+if not set -q ZED_SHELL_INTEGRATION; or set -q __zed_integration_loaded
+    exit
+end
+set -g __zed_integration_loaded 1
+set -e ZED_SHELL_INTEGRATION
+
+function __zed_escape_value
+    string replace -a '\\' '\\\\' -- $argv | string replace -a ';' '\\x3b' | string join '\\x0a'
+end
+
+function __zed_preexec --on-event fish_preexec
+    set -g __zed_command_running 1
+    printf '\e]633;E;%s\a' (__zed_escape_value $argv)
+    printf '\e]133;C\a'
+end
+
+function __zed_postexec --on-event fish_postexec
+    set -l exit_status $status
+    if set -q __zed_command_running
+        printf '\e]133;D;%s\a' $exit_status
+    end
+    set -e __zed_command_running
+end
+
+function __zed_prompt_start --on-event fish_prompt
+    printf '\e]633;P;Cwd=%s\a' (__zed_escape_value $PWD)
+    printf '\e]133;A\a'
+end
+
+functions -c fish_prompt __zed_original_fish_prompt
+function fish_prompt
+    __zed_original_fish_prompt
+    printf '\e]133;B\a'
+end
@@ -3,6 +3,8 @@ pub mod mappings;
 pub use alacritty_terminal;
 
 mod pty_info;
+pub mod scrollback;
+pub mod shell_integration;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -38,6 +40,7 @@ use futures::StreamExt;
 use pty_info::PtyProcessInfo;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::{CommandMarker, ShellIntegration};
 use smol::channel::{Receiver, Sender};
 use task::{static_source::RevealStrategy, TaskId};
 use terminal_settings::{AlternateScroll, Shell, TerminalBlink, TerminalSettings};
@@ -64,7 +67,16 @@ use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
 
 actions!(
     terminal,
-    [Clear, Copy, Paste, ShowCharacterPalette, SearchTest,]
+    [
+        Clear,
+        Copy,
+        Paste,
+        ShowCharacterPalette,
+        SearchTest,
+        ScrollToPreviousPrompt,
+        ScrollToNextPrompt,
+        CopyLastCommandOutput,
+    ]
 );
 
 ///Scrolling is unbearably sluggish by default. Alacritty supports a configurable
@@ -123,6 +135,9 @@ enum InternalEvent {
     // Adjusted mouse position, should open
     FindHyperlink(Point<Pixels>, bool),
     Copy,
+    ScrollToPreviousPrompt,
+    ScrollToNextPrompt,
+    CopyLastCommandOutput,
 }
 
 ///A translation struct for Alacritty to communicate with us from their event loop
@@ -296,6 +311,9 @@ pub struct SpawnTask {
 const DEFAULT_SCROLL_HISTORY_LINES: usize = 10_000;
 const MAX_SCROLL_HISTORY_LINES: usize = 100_000;
 
+const URL_REGEX: &str = r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#;
+const WORD_REGEX: &str = r#"[\$\+\w.\[\]:/@\-~]+"#;
+
 pub struct TerminalBuilder {
     terminal: Terminal,
     events_rx: UnboundedReceiver<AlacTermEvent>,
@@ -307,13 +325,29 @@ impl TerminalBuilder {
         working_directory: Option<PathBuf>,
         task: Option<TaskState>,
         shell: Shell,
-        env: HashMap<String, String>,
+        mut env: HashMap<String, String>,
         blink_settings: Option<TerminalBlink>,
         alternate_scroll: AlternateScroll,
         max_scroll_history_lines: Option<usize>,
+        inject_shell_integration: bool,
+        restored_scrollback: Option<String>,
         window: AnyWindowHandle,
         completion_tx: Sender<()>,
     ) -> Result<TerminalBuilder> {
+        // The integration scripts are injected through the process environment, which is
+        // shared by all terminals, so changes made for a previous terminal are undone first.
+        shell_integration::restore_environment();
+        let shell = match (inject_shell_integration && task.is_none())
+            .then(|| shell_integration::inject(&shell))
+            .flatten()
+        {
+            Some(injection) => {
+                env.extend(injection.env);
+                injection.shell
+            }
+            None => shell,
+        };
+
         let pty_options = {
             let alac_shell = match shell.clone() {
                 Shell::System => None,
@@ -378,6 +412,11 @@ impl TerminalBuilder {
             term.unset_private_mode(PrivateMode::Named(NamedPrivateMode::AlternateScroll));
         }
 
+        //Replay the previous session's history before the shell gets to write anything
+        if let Some(scrollback) = restored_scrollback.filter(|_| task.is_none()) {
+            scrollback::replay(&mut term, &scrollback);
+        }
+
         let term = Arc::new(FairMutex::new(term));
 
         //Setup the pty...
@@ -397,6 +436,7 @@ impl TerminalBuilder {
         };
 
         let pty_info = PtyProcessInfo::new(&pty);
+        let (shell_integration, pty) = ShellIntegration::wrap_pty(pty);
 
         //And connect them together
         let event_loop = EventLoop::new(
@@ -411,12 +451,9 @@ impl TerminalBuilder {
         let pty_tx = event_loop.channel();
         let _io_thread = event_loop.spawn(); // DANGER
 
-        let url_regex = RegexSearch::new(r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#).unwrap();
-        let word_regex = RegexSearch::new(r#"[\$\+\w.\[\]:/@\-~]+"#).unwrap();
-
         let terminal = Terminal {
             task,
-            pty_tx: Notifier(pty_tx),
+            pty_tx: Some(Notifier(pty_tx)),
             completion_tx,
             term,
             events: VecDeque::with_capacity(10), //Should never get this high.
@@ -424,7 +461,10 @@ impl TerminalBuilder {
             last_mouse: None,
             matches: Vec::new(),
             selection_head: None,
-            pty_info,
+            pty_info: Some(pty_info),
+            shell_integration,
+            mirrored_title: None,
+            remote_input: None,
             breadcrumb_text: String::new(),
             scroll_px: px(0.),
             last_mouse_position: None,
@@ -432,8 +472,8 @@ impl TerminalBuilder {
             selection_phase: SelectionPhase::Ended,
             cmd_pressed: false,
             hovered_word: false,
-            url_regex,
-            word_regex,
+            url_regex: RegexSearch::new(URL_REGEX).unwrap(),
+            word_regex: RegexSearch::new(WORD_REGEX).unwrap(),
         };
 
         Ok(TerminalBuilder {
@@ -442,6 +482,59 @@ impl TerminalBuilder {
         })
     }
 
+    /// Creates a mirror of a terminal shared by a collaborator, whose content is updated through
+    /// [`Terminal::set_mirrored_rows`]. Input to it is sent through `input_tx`.
+    pub fn new_remote(input_tx: UnboundedSender<Vec<u8>>) -> TerminalBuilder {
+        Self::without_process(Some(input_tx))
+    }
+
+    /// Creates a terminal without a process, whose output is written by
+    /// [`Terminal::write_output`].
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn new_fake() -> TerminalBuilder {
+        Self::without_process(None)
+    }
+
+    fn without_process(remote_input: Option<UnboundedSender<Vec<u8>>>) -> TerminalBuilder {
+        let (events_tx, events_rx) = unbounded();
+        let term = Term::new(
+            Config::default(),
+            &TerminalSize::default(),
+            ZedListener(events_tx),
+        );
+        let (completion_tx, _) = smol::channel::bounded(1);
+
+        let terminal = Terminal {
+            task: None,
+            pty_tx: None,
+            completion_tx,
+            term: Arc::new(FairMutex::new(term)),
+            events: VecDeque::with_capacity(10),
+            last_content: Default::default(),
+            last_mouse: None,
+            matches: Vec::new(),
+            selection_head: None,
+            pty_info: None,
+            shell_integration: ShellIntegration::inactive(),
+            mirrored_title: None,
+            remote_input,
+            breadcrumb_text: String::new(),
+            scroll_px: px(0.),
+            last_mouse_position: None,
+            next_link_id: 0,
+            selection_phase: SelectionPhase::Ended,
+            cmd_pressed: false,
+            hovered_word: false,
+            url_regex: RegexSearch::new(URL_REGEX).unwrap(),
+            word_regex: RegexSearch::new(WORD_REGEX).unwrap(),
+        };
+
+        TerminalBuilder {
+            terminal,
+            events_rx,
+        }
+    }
+
     pub fn subscribe(mut self, cx: &mut ModelContext<Terminal>) -> Terminal {
         //Event loop
         cx.spawn(|terminal, mut cx| async move {
@@ -532,6 +625,7 @@ pub struct TerminalContent {
     pub cursor_char: char,
     pub size: TerminalSize,
     pub last_hovered_word: Option<HoveredWord>,
+    pub command_markers: Vec<CommandMarker>,
 }
 
 #[derive(Clone)]
@@ -556,6 +650,7 @@ impl Default for TerminalContent {
             cursor_char: Default::default(),
             size: Default::default(),
             last_hovered_word: None,
+            command_markers: Vec::new(),
         }
     }
 }
@@ -567,7 +662,9 @@ pub enum SelectionPhase {
 }
 
 pub struct Terminal {
-    pty_tx: Notifier,
+    /// `None` for terminals without a process, such as mirrors of a terminal shared by a
+    /// collaborator.
+    pty_tx: Option<Notifier>,
     completion_tx: Sender<()>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     events: VecDeque<InternalEvent>,
@@ -579,7 +676,11 @@ pub struct Terminal {
     pub last_content: TerminalContent,
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
-    pub pty_info: PtyProcessInfo,
+    pub pty_info: Option<PtyProcessInfo>,
+    shell_integration: ShellIntegration,
+    mirrored_title: Option<String>,
+    /// Where input goes for mirrors of a terminal shared by a collaborator.
+    remote_input: Option<UnboundedSender<Vec<u8>>>,
     scroll_px: Pixels,
     next_link_id: usize,
     selection_phase: SelectionPhase,
@@ -639,7 +740,19 @@ impl Terminal {
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
 
-                if self.pty_info.has_changed() {
+                if self.shell_integration.process_marks() {
+                    // The shell reports its working directory and commands itself, so the
+                    // process info only needs to be refreshed on command boundaries.
+                    if let Some(pty_info) = &mut self.pty_info {
+                        pty_info.has_changed();
+                    }
+                    cx.emit(Event::TitleChanged);
+                } else if !self.shell_integration.is_active()
+                    && self
+                        .pty_info
+                        .as_mut()
+                        .map_or(false, |pty_info| pty_info.has_changed())
+                {
                     cx.emit(Event::TitleChanged);
                 }
             }
@@ -655,7 +768,80 @@ impl Terminal {
     }
 
     pub fn get_cwd(&self) -> Option<PathBuf> {
-        self.pty_info.current.as_ref().map(|info| info.cwd.clone())
+        self.shell_integration
+            .working_directory()
+            .map(|cwd| cwd.to_path_buf())
+            .or_else(|| {
+                let info = self.pty_info.as_ref()?.current.as_ref()?;
+                Some(info.cwd.clone())
+            })
+    }
+
+    pub fn shell_integration(&self) -> &ShellIntegration {
+        &self.shell_integration
+    }
+
+    /// Whether this terminal mirrors a terminal shared by a collaborator.
+    pub fn is_remote(&self) -> bool {
+        self.remote_input.is_some()
+    }
+
+    /// Serializes each row of the visible screen separately, so only the rows that changed need
+    /// to be sent to mirrors of this terminal.
+    pub fn screen_rows(&self) -> Vec<String> {
+        scrollback::screen_rows(&*self.term.lock())
+    }
+
+    /// The line and column of the cursor on the visible screen.
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let point = self.term.lock().grid().cursor.point;
+        (point.line.0.max(0) as usize, point.column.0)
+    }
+
+    /// Overwrites rows of a mirror terminal with rows serialized by [`Terminal::screen_rows`],
+    /// erasing all other rows if `replace_screen` is set.
+    pub fn set_mirrored_rows<'a>(
+        &mut self,
+        title: String,
+        rows: impl IntoIterator<Item = (usize, &'a str)>,
+        replace_screen: bool,
+        cursor: (usize, usize),
+        cx: &mut ModelContext<Self>,
+    ) {
+        if !self.is_remote() {
+            return;
+        }
+
+        scrollback::apply_rows(&mut *self.term.lock(), rows, replace_screen, cursor);
+        if self.mirrored_title.as_ref() != Some(&title) {
+            self.mirrored_title = Some(title);
+            cx.emit(Event::TitleChanged);
+        }
+        cx.emit(Event::Wakeup);
+        cx.notify();
+    }
+
+    /// Writes output as if it came from the terminal's process.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn write_output(&mut self, output: &[u8], cx: &mut ModelContext<Self>) {
+        let mut parser: alacritty_terminal::vte::ansi::Processor =
+            alacritty_terminal::vte::ansi::Processor::new();
+        let mut term = self.term.lock();
+        for byte in output {
+            parser.advance(&mut *term, *byte);
+        }
+        drop(term);
+        cx.emit(Event::Wakeup);
+        cx.notify();
+    }
+
+    /// Serializes the most recent scrollback, so it can be replayed when the terminal is restored.
+    pub fn scrollback_snapshot(&self, max_lines: usize) -> Option<String> {
+        scrollback::snapshot(
+            &*self.term.lock(),
+            max_lines,
+            scrollback::MAX_PERSISTED_SCROLLBACK_BYTES,
+        )
     }
 
     ///Takes events from Alacritty and translates them to behavior on this view
@@ -678,7 +864,9 @@ impl Terminal {
 
                 self.last_content.size = new_size;
 
-                self.pty_tx.0.send(Msg::Resize(new_size.into())).ok();
+                if let Some(pty_tx) = &self.pty_tx {
+                    pty_tx.0.send(Msg::Resize(new_size.into())).ok();
+                }
 
                 term.resize(new_size);
             }
@@ -751,6 +939,30 @@ impl Terminal {
                 term.scroll_to_point(*point);
                 self.refresh_hovered_word();
             }
+            InternalEvent::ScrollToPreviousPrompt | InternalEvent::ScrollToNextPrompt => {
+                if term.mode().contains(TermMode::ALT_SCREEN) {
+                    return;
+                }
+                let viewport_top = Line(-(term.grid().display_offset() as i32));
+                let target = if matches!(event, InternalEvent::ScrollToPreviousPrompt) {
+                    self.shell_integration.previous_prompt_line(term)
+                } else {
+                    self.shell_integration.next_prompt_line(term)
+                };
+                match target {
+                    Some(line) => term.scroll_display(AlacScroll::Delta(viewport_top.0 - line.0)),
+                    None if matches!(event, InternalEvent::ScrollToNextPrompt) => {
+                        term.scroll_display(AlacScroll::Bottom)
+                    }
+                    None => {}
+                }
+                self.refresh_hovered_word();
+            }
+            InternalEvent::CopyLastCommandOutput => {
+                if let Some((start, end)) = self.shell_integration.last_command_output(term) {
+                    cx.write_to_clipboard(ClipboardItem::new(term.bounds_to_string(start, end)))
+                }
+            }
             InternalEvent::FindHyperlink(position, open) => {
                 let prev_hovered_word = self.last_content.last_hovered_word.take();
 
@@ -950,6 +1162,18 @@ impl Terminal {
         self.events.push_back(InternalEvent::Copy);
     }
 
+    pub fn scroll_to_previous_prompt(&mut self) {
+        self.events.push_back(InternalEvent::ScrollToPreviousPrompt);
+    }
+
+    pub fn scroll_to_next_prompt(&mut self) {
+        self.events.push_back(InternalEvent::ScrollToNextPrompt);
+    }
+
+    pub fn copy_last_command_output(&mut self) {
+        self.events.push_back(InternalEvent::CopyLastCommandOutput);
+    }
+
     pub fn clear(&mut self) {
         self.events.push_back(InternalEvent::Clear)
     }
@@ -961,11 +1185,15 @@ impl Terminal {
 
     ///Write the Input payload to the tty.
     fn write_to_pty(&self, input: String) {
-        self.pty_tx.notify(input.into_bytes());
+        self.write_bytes_to_pty(input.into_bytes());
     }
 
     fn write_bytes_to_pty(&self, input: Vec<u8>) {
-        self.pty_tx.notify(input);
+        if let Some(pty_tx) = &self.pty_tx {
+            pty_tx.notify(input);
+        } else if let Some(remote_input) = &self.remote_input {
+            remote_input.unbounded_send(input).ok();
+        }
     }
 
     pub fn input(&mut self, input: String) {
@@ -1022,10 +1250,15 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, cx)
         }
 
-        self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content =
+            Self::make_content(&terminal, &self.last_content, &self.shell_integration);
     }
 
-    fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
+    fn make_content(
+        term: &Term<ZedListener>,
+        last_content: &TerminalContent,
+        shell_integration: &ShellIntegration,
+    ) -> TerminalContent {
         let content = term.renderable_content();
         TerminalContent {
             cells: content
@@ -1050,6 +1283,11 @@ impl Terminal {
             cursor_char: term.grid()[content.cursor.point].c,
             size: last_content.size,
             last_hovered_word: last_content.last_hovered_word.clone(),
+            command_markers: if content.mode.contains(TermMode::ALT_SCREEN) {
+                Vec::new()
+            } else {
+                shell_integration.command_markers(term)
+            },
         }
     }
 
@@ -1099,7 +1337,7 @@ impl Terminal {
 
             if self.mouse_changed(point, side) {
                 if let Some(bytes) = mouse_moved_report(point, e, self.last_content.mode) {
-                    self.pty_tx.notify(bytes);
+                    self.write_bytes_to_pty(bytes);
                 }
             }
         } else if self.cmd_pressed {
@@ -1173,7 +1411,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, true, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else if e.button == MouseButton::Left {
             let position = e.position - origin;
@@ -1220,7 +1458,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, false, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             if e.button == MouseButton::Left && setting.copy_on_select {
@@ -1258,7 +1496,7 @@ impl Terminal {
                 if let Some(scrolls) = scroll_report(point, scroll_lines, e, self.last_content.mode)
                 {
                     for scroll in scrolls {
-                        self.pty_tx.notify(scroll);
+                        self.write_bytes_to_pty(scroll);
                     }
                 };
             } else if self
@@ -1267,7 +1505,7 @@ impl Terminal {
                 .contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
                 && !e.shift
             {
-                self.pty_tx.notify(alt_scroll(scroll_lines))
+                self.write_bytes_to_pty(alt_scroll(scroll_lines))
             } else {
                 if scroll_lines != 0 {
                     let scroll = AlacScroll::Delta(scroll_lines);
@@ -1332,27 +1570,41 @@ impl Terminal {
                     task_state.label.clone()
                 }
             }
+            None if self.pty_tx.is_none() => {
+                let title = self.mirrored_title.as_deref().unwrap_or("Terminal");
+                if truncate {
+                    truncate_and_trailoff(title, MAX_CHARS)
+                } else {
+                    title.to_string()
+                }
+            }
             None => self
                 .pty_info
-                .current
                 .as_ref()
+                .and_then(|pty_info| pty_info.current.as_ref())
                 .map(|fpi| {
-                    let process_file = fpi
-                        .cwd
+                    let process_file = self
+                        .get_cwd()
+                        .unwrap_or_default()
                         .file_name()
                         .map(|name| name.to_string_lossy().to_string())
                         .unwrap_or_default();
 
-                    let argv = fpi.argv.clone();
-                    let process_name = format!(
-                        "{}{}",
-                        fpi.name,
-                        if argv.len() >= 1 {
-                            format!(" {}", (argv[1..]).join(" "))
-                        } else {
-                            "".to_string()
+                    let process_name = match self.shell_integration.running_command() {
+                        Some(command) => command.to_string(),
+                        None => {
+                            let argv = fpi.argv.clone();
+                            format!(
+                                "{}{}",
+                                fpi.name,
+                                if argv.len() >= 1 {
+                                    format!(" {}", (argv[1..]).join(" "))
+                                } else {
+                                    "".to_string()
+                                }
+                            )
                         }
-                    );
+                    };
                     let (process_file, process_name) = if truncate {
                         (
                             truncate_and_trailoff(&process_file, MAX_CHARS),
@@ -1394,7 +1646,9 @@ impl Terminal {
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        if let Some(pty_tx) = &self.pty_tx {
+            pty_tx.0.send(Msg::Shutdown).ok();
+        }
     }
 }
 
@@ -42,6 +42,9 @@ pub struct TerminalSettings {
     pub detect_venv: VenvSettings,
     pub max_scroll_history_lines: Option<usize>,
     pub toolbar: Toolbar,
+    pub shell_integration: bool,
+    pub persist_scrollback: bool,
+    pub max_persisted_scrollback_lines: usize,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -163,6 +166,25 @@ pub struct TerminalSettingsContent {
     pub max_scroll_history_lines: Option<usize>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
+    /// Whether to load Zed's integration scripts into bash, zsh and fish.
+    /// They report where each command starts and ends, its exit status and
+    /// the shell's working directory.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
+    /// Whether to save the recent scrollback of each terminal, including its
+    /// colors, and show it above a fresh shell when the terminal is restored.
+    /// Set this to false in a project's settings to keep its terminal output
+    /// from being written to disk.
+    ///
+    /// Default: false
+    pub persist_scrollback: Option<bool>,
+    /// The maximum number of lines to save when `persist_scrollback` is enabled.
+    /// The saved history is also limited to 1MB per terminal.
+    ///
+    /// Default: 1000
+    pub max_persisted_scrollback_lines: Option<usize>,
 }
 
 impl settings::Settings for TerminalSettings {
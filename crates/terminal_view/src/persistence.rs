@@ -34,6 +34,9 @@ define_connection! {
             DROP TABLE terminals;
 
             ALTER TABLE terminals2 RENAME TO terminals;
+        ),
+        sql!(
+            ALTER TABLE terminals ADD COLUMN scrollback TEXT;
         )];
 }
 
@@ -56,8 +59,10 @@ impl TerminalDb {
             workspace_id: WorkspaceId,
             working_directory: PathBuf
         ) -> Result<()> {
-            INSERT OR REPLACE INTO terminals(item_id, workspace_id, working_directory)
-            VALUES (?, ?, ?)
+            INSERT INTO terminals(item_id, workspace_id, working_directory)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT DO UPDATE SET
+                working_directory = ?3
         }
     }
 
@@ -65,7 +70,28 @@ impl TerminalDb {
         pub fn get_working_directory(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<PathBuf>> {
             SELECT working_directory
             FROM terminals
-            WHERE item_id = ? AND workspace_id = ?
+            WHERE item_id = ? AND workspace_id = ? AND working_directory IS NOT NULL
+        }
+    }
+
+    query! {
+        pub async fn save_scrollback(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            scrollback: Option<String>
+        ) -> Result<()> {
+            INSERT INTO terminals(item_id, workspace_id, scrollback)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT DO UPDATE SET
+                scrollback = ?3
+        }
+    }
+
+    query! {
+        pub fn get_scrollback(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT scrollback
+            FROM terminals
+            WHERE item_id = ? AND workspace_id = ? AND scrollback IS NOT NULL
         }
     }
 }
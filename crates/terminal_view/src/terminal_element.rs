@@ -1,8 +1,8 @@
 use editor::{CursorLayout, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    div, fill, point, px, relative, AnyElement, Bounds, DispatchPhase, Element, ElementContext,
-    FocusHandle, Font, FontStyle, FontWeight, HighlightStyle, Hitbox, Hsla, InputHandler,
-    InteractiveElement, Interactivity, IntoElement, LayoutId, Model, ModelContext,
+    div, fill, point, px, relative, size, AnyElement, Bounds, DispatchPhase, Element,
+    ElementContext, FocusHandle, Font, FontStyle, FontWeight, HighlightStyle, Hitbox, Hsla,
+    InputHandler, InteractiveElement, Interactivity, IntoElement, LayoutId, Model, ModelContext,
     ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels, Point, ShapedLine,
     StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle, UnderlineStyle,
     WeakView, WhiteSpace, WindowContext, WindowTextSystem,
@@ -43,6 +43,7 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    command_markers: Vec<(i32, Hsla)>,
     last_hovered_word: Option<HoveredWord>,
 }
 
@@ -680,9 +681,23 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_markers,
                     ..
                 } = &self.terminal.read(cx).last_content;
 
+                // Mark the prompts of finished commands in the gutter, by their exit status
+                let command_markers = command_markers
+                    .iter()
+                    .map(|marker| {
+                        let color = if marker.exit_code == 0 {
+                            theme.status().success
+                        } else {
+                            theme.status().error
+                        };
+                        (marker.line.0 + *display_offset as i32, color)
+                    })
+                    .collect();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -767,6 +782,7 @@ impl Element for TerminalElement {
                     display_offset: *display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    command_markers,
                     last_hovered_word,
                 }
             })
@@ -824,6 +840,23 @@ impl Element for TerminalElement {
                     rect.paint(origin, &layout, cx);
                 }
 
+                let marker_size = (layout.gutter * 0.4).min(layout.dimensions.line_height * 0.4);
+                for (line, color) in &layout.command_markers {
+                    let marker_origin = point(
+                        bounds.origin.x + (layout.gutter - marker_size) / 2.,
+                        origin.y
+                            + *line as f32 * layout.dimensions.line_height
+                            + (layout.dimensions.line_height - marker_size) / 2.,
+                    );
+                    cx.paint_quad(
+                        fill(
+                            Bounds::new(marker_origin, size(marker_size, marker_size)),
+                            *color,
+                        )
+                        .corner_radii(marker_size / 2.),
+                    );
+                }
+
                 for (relative_highlighted_range, color) in layout.relative_highlighted_ranges.iter()
                 {
                     if let Some((start_y, highlighted_range_lines)) =
@@ -5,16 +5,20 @@ use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
 use futures::future::join_all;
 use gpui::{
-    actions, Action, AppContext, AsyncWindowContext, Entity, EventEmitter, ExternalPaths,
-    FocusHandle, FocusableView, IntoElement, ParentElement, Pixels, Render, Styled, Subscription,
-    Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    actions, Action, AppContext, AsyncWindowContext, Axis, Entity, EntityId, EventEmitter,
+    ExternalPaths, FocusHandle, FocusableView, InteractiveElement, IntoElement, Model,
+    ParentElement, Pixels, Point, Render, Styled, Subscription, Task, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
-use project::{Fs, ProjectEntryId};
+use project::{Fs, Project, ProjectEntryId};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use task::{static_source::RevealStrategy, SpawnInTerminal, TaskId};
+use task::{
+    static_source::{RevealStrategy, TerminalSplit},
+    SpawnInTerminal, TaskId,
+};
 use terminal::{
     terminal_settings::{Shell, TerminalDockPosition, TerminalSettings},
     SpawnTask,
@@ -26,7 +30,8 @@ use workspace::{
     item::Item,
     pane,
     ui::IconName,
-    DraggedTab, NewTerminal, Pane, Workspace,
+    ActivatePaneInDirection, ActivePaneDecorator, DraggedTab, Member, NewTerminal, Pane, PaneAxis,
+    PaneGroup, SplitDirection, SwapPaneInDirection, Workspace, HANDLE_HITBOX_SIZE,
 };
 
 use anyhow::Result;
@@ -49,7 +54,9 @@ pub fn init(cx: &mut AppContext) {
 }
 
 pub struct TerminalPanel {
-    pane: View<Pane>,
+    center: PaneGroup,
+    active_pane: View<Pane>,
+    project: Model<Project>,
     fs: Arc<dyn Fs>,
     workspace: WeakView<Workspace>,
     width: Option<Pixels>,
@@ -57,110 +64,19 @@ pub struct TerminalPanel {
     pending_serialization: Task<Option<()>>,
     pending_terminals_to_add: usize,
     _subscriptions: Vec<Subscription>,
+    pane_subscriptions: HashMap<EntityId, Vec<Subscription>>,
     deferred_tasks: HashMap<TaskId, Task<()>>,
+    /// Splits that were opened for tasks, which are reused when the tasks are spawned again.
+    task_panes: HashMap<TaskId, WeakView<Pane>>,
 }
 
 impl TerminalPanel {
     fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
-        let terminal_panel = cx.view().downgrade();
-        let pane = cx.new_view(|cx| {
-            let mut pane = Pane::new(
-                workspace.weak_handle(),
-                workspace.project().clone(),
-                Default::default(),
-                None,
-                NewTerminal.boxed_clone(),
-                cx,
-            );
-            pane.set_can_split(false, cx);
-            pane.set_can_navigate(false, cx);
-            pane.display_nav_history_buttons(false);
-            pane.set_render_tab_bar_buttons(cx, move |pane, cx| {
-                let terminal_panel = terminal_panel.clone();
-                h_flex()
-                    .gap_2()
-                    .child(
-                        IconButton::new("plus", IconName::Plus)
-                            .icon_size(IconSize::Small)
-                            .on_click(move |_, cx| {
-                                terminal_panel
-                                    .update(cx, |panel, cx| panel.add_terminal(None, None, cx))
-                                    .log_err();
-                            })
-                            .tooltip(|cx| Tooltip::text("New Terminal", cx)),
-                    )
-                    .child({
-                        let zoomed = pane.is_zoomed();
-                        IconButton::new("toggle_zoom", IconName::Maximize)
-                            .icon_size(IconSize::Small)
-                            .selected(zoomed)
-                            .selected_icon(IconName::Minimize)
-                            .on_click(cx.listener(|pane, _, cx| {
-                                pane.toggle_zoom(&workspace::ToggleZoom, cx);
-                            }))
-                            .tooltip(move |cx| {
-                                Tooltip::text(if zoomed { "Zoom Out" } else { "Zoom In" }, cx)
-                            })
-                    })
-                    .into_any_element()
-            });
-
-            let workspace = workspace.weak_handle();
-            pane.set_custom_drop_handle(cx, move |pane, dropped_item, cx| {
-                if let Some(tab) = dropped_item.downcast_ref::<DraggedTab>() {
-                    let item = if &tab.pane == cx.view() {
-                        pane.item_for_index(tab.ix)
-                    } else {
-                        tab.pane.read(cx).item_for_index(tab.ix)
-                    };
-                    if let Some(item) = item {
-                        if item.downcast::<TerminalView>().is_some() {
-                            return ControlFlow::Continue(());
-                        } else if let Some(project_path) = item.project_path(cx) {
-                            if let Some(entry_path) = workspace
-                                .update(cx, |workspace, cx| {
-                                    workspace
-                                        .project()
-                                        .read(cx)
-                                        .absolute_path(&project_path, cx)
-                                })
-                                .log_err()
-                                .flatten()
-                            {
-                                add_paths_to_terminal(pane, &[entry_path], cx);
-                            }
-                        }
-                    }
-                } else if let Some(&entry_id) = dropped_item.downcast_ref::<ProjectEntryId>() {
-                    if let Some(entry_path) = workspace
-                        .update(cx, |workspace, cx| {
-                            let project = workspace.project().read(cx);
-                            project
-                                .path_for_entry(entry_id, cx)
-                                .and_then(|project_path| project.absolute_path(&project_path, cx))
-                        })
-                        .log_err()
-                        .flatten()
-                    {
-                        add_paths_to_terminal(pane, &[entry_path], cx);
-                    }
-                } else if let Some(paths) = dropped_item.downcast_ref::<ExternalPaths>() {
-                    add_paths_to_terminal(pane, paths.paths(), cx);
-                }
-
-                ControlFlow::Break(())
-            });
-            let buffer_search_bar = cx.new_view(search::BufferSearchBar::new);
-            pane.toolbar()
-                .update(cx, |toolbar, cx| toolbar.add_item(buffer_search_bar, cx));
-            pane
-        });
-        let subscriptions = vec![
-            cx.observe(&pane, |_, _, cx| cx.notify()),
-            cx.subscribe(&pane, Self::handle_pane_event),
-        ];
-        let this = Self {
-            pane,
+        let pane = new_terminal_pane(workspace.weak_handle(), workspace.project().clone(), cx);
+        let mut this = Self {
+            center: PaneGroup::new(pane.clone()),
+            active_pane: pane.clone(),
+            project: workspace.project().clone(),
             fs: workspace.app_state().fs.clone(),
             workspace: workspace.weak_handle(),
             pending_serialization: Task::ready(None),
@@ -168,11 +84,30 @@ impl TerminalPanel {
             height: None,
             pending_terminals_to_add: 0,
             deferred_tasks: HashMap::default(),
-            _subscriptions: subscriptions,
+            task_panes: HashMap::default(),
+            _subscriptions: Vec::new(),
+            pane_subscriptions: HashMap::default(),
         };
+        this.subscribe_to_pane(&pane, cx);
         this
     }
 
+    fn new_pane(&mut self, cx: &mut ViewContext<Self>) -> View<Pane> {
+        let pane = new_terminal_pane(self.workspace.clone(), self.project.clone(), cx);
+        self.subscribe_to_pane(&pane, cx);
+        pane
+    }
+
+    fn subscribe_to_pane(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
+        self.pane_subscriptions.insert(
+            pane.entity_id(),
+            vec![
+                cx.observe(pane, |_, _, cx| cx.notify()),
+                cx.subscribe(pane, Self::handle_pane_event),
+            ],
+        );
+    }
+
     pub async fn load(
         workspace: WeakView<Workspace>,
         mut cx: AsyncWindowContext,
@@ -188,34 +123,35 @@ impl TerminalPanel {
             .log_err()
             .flatten();
 
-        let (panel, pane, items) = workspace.update(&mut cx, |workspace, cx| {
+        let (panel, pending_panes) = workspace.update(&mut cx, |workspace, cx| {
             let panel = cx.new_view(|cx| TerminalPanel::new(workspace, cx));
-            let items = if let Some(serialized_panel) = serialized_panel.as_ref() {
+            let pending_panes = if let Some(serialized_panel) = serialized_panel {
                 panel.update(cx, |panel, cx| {
                     cx.notify();
                     panel.height = serialized_panel.height.map(|h| h.round());
                     panel.width = serialized_panel.width.map(|w| w.round());
-                    panel.pane.update(cx, |_, cx| {
-                        serialized_panel
-                            .items
-                            .iter()
-                            .map(|item_id| {
-                                TerminalView::deserialize(
-                                    workspace.project().clone(),
-                                    workspace.weak_handle(),
-                                    workspace.database_id(),
-                                    *item_id,
-                                    cx,
-                                )
-                            })
-                            .collect::<Vec<_>>()
-                    })
+                    // Panels serialized before splits were supported only have a single pane.
+                    let layout = serialized_panel.panes.unwrap_or(SerializedPaneGroup::Pane {
+                        items: serialized_panel.items,
+                        active_item_id: serialized_panel.active_item_id,
+                        active: true,
+                    });
+
+                    let mut pending_panes = Vec::new();
+                    if let Some(root) =
+                        panel.deserialize_member(&layout, workspace, &mut pending_panes, cx)
+                    {
+                        panel.center = PaneGroup::with_root(root);
+                        if !panel.center.panes().contains(&&panel.active_pane) {
+                            panel.active_pane = panel.center.first_pane();
+                        }
+                    }
+                    pending_panes
                 })
             } else {
                 Vec::new()
             };
-            let pane = panel.read(cx).pane.clone();
-            (panel, pane, items)
+            (panel, pending_panes)
         })?;
 
         if let Some(workspace) = workspace.upgrade() {
@@ -233,47 +169,147 @@ impl TerminalPanel {
                 .ok();
         }
 
-        let pane = pane.downgrade();
-        let items = futures::future::join_all(items).await;
-        pane.update(&mut cx, |pane, cx| {
-            let active_item_id = serialized_panel
-                .as_ref()
-                .and_then(|panel| panel.active_item_id);
-            let mut active_ix = None;
-            for item in items {
-                if let Some(item) = item.log_err() {
-                    let item_id = item.entity_id().as_u64();
-                    pane.add_item(Box::new(item), false, false, None, cx);
-                    if Some(item_id) == active_item_id {
-                        active_ix = Some(pane.items_len() - 1);
+        for (pane, items, active_item_id) in pending_panes {
+            let items = futures::future::join_all(items).await;
+            pane.update(&mut cx, |pane, cx| {
+                let mut active_ix = None;
+                for item in items {
+                    if let Some(item) = item.log_err() {
+                        let item_id = item.entity_id().as_u64();
+                        pane.add_item(Box::new(item), false, false, None, cx);
+                        if Some(item_id) == active_item_id {
+                            active_ix = Some(pane.items_len() - 1);
+                        }
                     }
                 }
-            }
 
-            if let Some(active_ix) = active_ix {
-                pane.activate_item(active_ix, false, false, cx)
+                if let Some(active_ix) = active_ix {
+                    pane.activate_item(active_ix, false, false, cx)
+                }
+            })?;
+        }
+
+        // Splits whose terminals all failed to restore are not worth keeping around.
+        panel.update(&mut cx, |panel, cx| {
+            let empty_panes = panel
+                .center
+                .panes()
+                .into_iter()
+                .filter(|pane| pane.read(cx).items_len() == 0)
+                .cloned()
+                .collect::<Vec<_>>();
+            for pane in empty_panes {
+                panel.remove_pane(&pane, cx);
             }
         })?;
 
         Ok(panel)
     }
 
+    fn deserialize_member(
+        &mut self,
+        serialized: &SerializedPaneGroup,
+        workspace: &Workspace,
+        pending_panes: &mut Vec<PendingPane>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Member> {
+        match serialized {
+            SerializedPaneGroup::Group {
+                axis,
+                flexes,
+                children,
+            } => {
+                let mut members = children
+                    .iter()
+                    .filter_map(|child| {
+                        self.deserialize_member(child, workspace, pending_panes, cx)
+                    })
+                    .collect::<Vec<_>>();
+                if members.len() <= 1 {
+                    return members.pop();
+                }
+                let flexes = flexes
+                    .clone()
+                    .filter(|flexes| flexes.len() == members.len());
+                Some(Member::Axis(PaneAxis::load(
+                    (*axis).into(),
+                    members,
+                    flexes,
+                )))
+            }
+            SerializedPaneGroup::Pane {
+                items,
+                active_item_id,
+                active,
+            } => {
+                // The pane created along with the panel is reused for the first restored pane.
+                let pane = if pending_panes.is_empty() {
+                    self.active_pane.clone()
+                } else {
+                    self.new_pane(cx)
+                };
+                let items = pane.update(cx, |_, cx| {
+                    items
+                        .iter()
+                        .map(|item_id| {
+                            TerminalView::deserialize(
+                                workspace.project().clone(),
+                                workspace.weak_handle(),
+                                workspace.database_id(),
+                                *item_id,
+                                cx,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                });
+                if *active {
+                    self.active_pane = pane.clone();
+                }
+                pending_panes.push((pane.downgrade(), items, *active_item_id));
+                Some(Member::Pane(pane))
+            }
+        }
+    }
+
     fn handle_pane_event(
         &mut self,
-        _pane: View<Pane>,
+        pane: View<Pane>,
         event: &pane::Event,
         cx: &mut ViewContext<Self>,
     ) {
         match event {
             pane::Event::ActivateItem { .. } => self.serialize(cx),
             pane::Event::RemoveItem { .. } => self.serialize(cx),
-            pane::Event::Remove => cx.emit(PanelEvent::Close),
+            pane::Event::Remove => {
+                if self.center.panes().len() > 1 {
+                    self.remove_pane(&pane, cx);
+                } else {
+                    cx.emit(PanelEvent::Close);
+                }
+            }
             pane::Event::ZoomIn => cx.emit(PanelEvent::ZoomIn),
             pane::Event::ZoomOut => cx.emit(PanelEvent::ZoomOut),
+            pane::Event::Focus => {
+                if self.active_pane != pane {
+                    self.active_pane = pane;
+                    self.serialize(cx);
+                    cx.notify();
+                }
+            }
+            pane::Event::Split(direction) => {
+                let working_directory = pane
+                    .read(cx)
+                    .active_item()
+                    .and_then(|item| item.downcast::<TerminalView>())
+                    .and_then(|terminal_view| terminal_view.read(cx).terminal().read(cx).get_cwd());
+                let new_pane = self.split_pane(&pane, *direction, cx);
+                self.active_pane = new_pane.clone();
+                cx.focus_view(&new_pane);
+                self.add_terminal(working_directory, None, cx);
+            }
 
             pane::Event::AddItem { item } => {
                 if let Some(workspace) = self.workspace.upgrade() {
-                    let pane = self.pane.clone();
                     workspace.update(cx, |workspace, cx| item.added_to_pane(workspace, pane, cx))
                 }
             }
@@ -282,6 +318,86 @@ impl TerminalPanel {
         }
     }
 
+    /// Opens a new, empty split next to the given pane.
+    fn split_pane(
+        &mut self,
+        pane: &View<Pane>,
+        direction: SplitDirection,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Pane> {
+        let new_pane = self.new_pane(cx);
+        self.center.split(pane, &new_pane, direction).log_err();
+        cx.notify();
+        new_pane
+    }
+
+    fn remove_pane(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
+        if !self.center.remove(pane).log_err().unwrap_or(false) {
+            return;
+        }
+
+        self.pane_subscriptions.remove(&pane.entity_id());
+        if &self.active_pane == pane {
+            self.active_pane = self.center.first_pane();
+            if pane.focus_handle(cx).contains_focused(cx) {
+                cx.focus_view(&self.active_pane);
+            }
+        }
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn find_pane_in_direction(
+        &self,
+        direction: SplitDirection,
+        cx: &WindowContext,
+    ) -> Option<View<Pane>> {
+        let bounding_box = self.center.bounding_box_for_pane(&self.active_pane)?;
+        let cursor = self.active_pane.read(cx).pixel_position_of_cursor(cx);
+        let center = match cursor {
+            Some(cursor) if bounding_box.contains(&cursor) => cursor,
+            _ => bounding_box.center(),
+        };
+
+        let distance_to_next = Pixels::from(HANDLE_HITBOX_SIZE);
+        let target = match direction {
+            SplitDirection::Left => Point::new(bounding_box.left() - distance_to_next, center.y),
+            SplitDirection::Right => Point::new(bounding_box.right() + distance_to_next, center.y),
+            SplitDirection::Up => Point::new(center.x, bounding_box.top() - distance_to_next),
+            SplitDirection::Down => Point::new(center.x, bounding_box.bottom() + distance_to_next),
+        };
+        self.center.pane_at_pixel_position(target).cloned()
+    }
+
+    fn activate_pane_in_direction(
+        &mut self,
+        action: &ActivatePaneInDirection,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(pane) = self.find_pane_in_direction(action.0, cx) {
+            cx.focus_view(&pane);
+        } else {
+            // Let the workspace move the focus out of the panel.
+            cx.propagate();
+        }
+    }
+
+    fn swap_pane_in_direction(&mut self, action: &SwapPaneInDirection, cx: &mut ViewContext<Self>) {
+        if let Some(to) = self.find_pane_in_direction(action.0, cx) {
+            self.center.swap(&self.active_pane.clone(), &to);
+            self.serialize(cx);
+            cx.notify();
+        }
+    }
+
+    fn items_len(&self, cx: &AppContext) -> usize {
+        self.center
+            .panes()
+            .into_iter()
+            .map(|pane| pane.read(cx).items_len())
+            .sum()
+    }
+
     pub fn open_terminal(
         workspace: &mut Workspace,
         action: &workspace::OpenTerminal,
@@ -326,20 +442,21 @@ impl TerminalPanel {
         let reveal = spawn_task.reveal;
 
         let working_directory = spawn_in_terminal.cwd.clone();
+        let split = spawn_in_terminal.split;
         let allow_concurrent_runs = spawn_in_terminal.allow_concurrent_runs;
         let use_new_terminal = spawn_in_terminal.use_new_terminal;
 
         if allow_concurrent_runs && use_new_terminal {
-            self.spawn_in_new_terminal(spawn_task, working_directory, cx);
+            self.spawn_in_new_terminal(spawn_task, working_directory, split, cx);
             return;
         }
 
         let terminals_for_task = self.terminals_for_task(&spawn_in_terminal.id, cx);
         if terminals_for_task.is_empty() {
-            self.spawn_in_new_terminal(spawn_task, working_directory, cx);
+            self.spawn_in_new_terminal(spawn_task, working_directory, split, cx);
             return;
         }
-        let (existing_item_index, existing_terminal) = terminals_for_task
+        let existing_terminal = terminals_for_task
             .last()
             .expect("covered no terminals case above")
             .clone();
@@ -348,13 +465,7 @@ impl TerminalPanel {
                 !use_new_terminal,
                 "Should have handled 'allow_concurrent_runs && use_new_terminal' case above"
             );
-            self.replace_terminal(
-                working_directory,
-                spawn_task,
-                existing_item_index,
-                existing_terminal,
-                cx,
-            );
+            self.replace_terminal(working_directory, spawn_task, existing_terminal, cx);
         } else {
             self.deferred_tasks.insert(
                 spawn_in_terminal.id.clone(),
@@ -366,13 +477,13 @@ impl TerminalPanel {
                                 terminal_panel.spawn_in_new_terminal(
                                     spawn_task,
                                     working_directory,
+                                    split,
                                     cx,
                                 );
                             } else {
                                 terminal_panel.replace_terminal(
                                     working_directory,
                                     spawn_task,
-                                    existing_item_index,
                                     existing_terminal,
                                     cx,
                                 );
@@ -384,7 +495,7 @@ impl TerminalPanel {
 
             match reveal {
                 RevealStrategy::Always => {
-                    self.activate_terminal_view(existing_item_index, cx);
+                    self.activate_terminal_view(&existing_terminal, cx);
                     let task_workspace = self.workspace.clone();
                     cx.spawn(|_, mut cx| async move {
                         task_workspace
@@ -402,12 +513,18 @@ impl TerminalPanel {
         &mut self,
         spawn_task: SpawnTask,
         working_directory: Option<PathBuf>,
+        split: Option<TerminalSplit>,
         cx: &mut ViewContext<Self>,
     ) {
         let reveal = spawn_task.reveal;
-        self.add_terminal(working_directory, Some(spawn_task), cx);
+        let pane = match split {
+            Some(split) => self.pane_for_task(&spawn_task.id, split, cx),
+            None => self.active_pane.clone(),
+        };
+        self.add_terminal_to_pane(pane.clone(), working_directory, Some(spawn_task), cx);
         match reveal {
             RevealStrategy::Always => {
+                self.active_pane = pane;
                 let task_workspace = self.workspace.clone();
                 cx.spawn(|_, mut cx| async move {
                     task_workspace
@@ -433,31 +550,60 @@ impl TerminalPanel {
         this.update(cx, |this, cx| this.add_terminal(None, None, cx))
     }
 
-    fn terminals_for_task(
-        &self,
+    /// Returns the split that was opened for the task before, or opens a new one next to the
+    /// active split.
+    fn pane_for_task(
+        &mut self,
         id: &TaskId,
-        cx: &mut AppContext,
-    ) -> Vec<(usize, View<TerminalView>)> {
-        self.pane
-            .read(cx)
-            .items()
-            .enumerate()
-            .filter_map(|(index, item)| Some((index, item.act_as::<TerminalView>(cx)?)))
-            .filter_map(|(index, terminal_view)| {
-                let task_state = terminal_view.read(cx).terminal().read(cx).task()?;
-                if &task_state.id == id {
-                    Some((index, terminal_view))
-                } else {
-                    None
-                }
+        split: TerminalSplit,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Pane> {
+        if let Some(pane) = self
+            .task_panes
+            .get(id)
+            .and_then(|pane| pane.upgrade())
+            .filter(|pane| self.center.panes().contains(&pane))
+        {
+            return pane;
+        }
+
+        let direction = match split {
+            TerminalSplit::Left => SplitDirection::Left,
+            TerminalSplit::Right => SplitDirection::Right,
+            TerminalSplit::Up => SplitDirection::Up,
+            TerminalSplit::Down => SplitDirection::Down,
+        };
+        let pane = self.split_pane(&self.active_pane.clone(), direction, cx);
+        self.task_panes.insert(id.clone(), pane.downgrade());
+        pane
+    }
+
+    fn terminals_for_task(&self, id: &TaskId, cx: &mut AppContext) -> Vec<View<TerminalView>> {
+        self.center
+            .panes()
+            .into_iter()
+            .flat_map(|pane| pane.read(cx).items())
+            .filter_map(|item| item.act_as::<TerminalView>(cx))
+            .filter(|terminal_view| {
+                terminal_view
+                    .read(cx)
+                    .terminal()
+                    .read(cx)
+                    .task()
+                    .map_or(false, |task_state| &task_state.id == id)
             })
             .collect()
     }
 
-    fn activate_terminal_view(&self, item_index: usize, cx: &mut WindowContext) {
-        self.pane.update(cx, |pane, cx| {
-            pane.activate_item(item_index, true, true, cx)
-        })
+    fn activate_terminal_view(&self, terminal_view: &View<TerminalView>, cx: &mut WindowContext) {
+        for pane in self.center.panes() {
+            if let Some(item_index) = pane.read(cx).index_for_item(terminal_view) {
+                pane.update(cx, |pane, cx| {
+                    pane.activate_item(item_index, true, true, cx)
+                });
+                return;
+            }
+        }
     }
 
     fn add_terminal(
@@ -465,11 +611,20 @@ impl TerminalPanel {
         working_directory: Option<PathBuf>,
         spawn_task: Option<SpawnTask>,
         cx: &mut ViewContext<Self>,
+    ) {
+        self.add_terminal_to_pane(self.active_pane.clone(), working_directory, spawn_task, cx)
+    }
+
+    fn add_terminal_to_pane(
+        &mut self,
+        pane: View<Pane>,
+        working_directory: Option<PathBuf>,
+        spawn_task: Option<SpawnTask>,
+        cx: &mut ViewContext<Self>,
     ) {
         let workspace = self.workspace.clone();
         self.pending_terminals_to_add += 1;
         cx.spawn(|terminal_panel, mut cx| async move {
-            let pane = terminal_panel.update(&mut cx, |this, _| this.pane.clone())?;
             workspace.update(&mut cx, |workspace, cx| {
                 let working_directory = if let Some(working_directory) = working_directory {
                     Some(working_directory)
@@ -509,28 +664,8 @@ impl TerminalPanel {
     }
 
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
-        let mut items_to_serialize = HashSet::default();
-        let items = self
-            .pane
-            .read(cx)
-            .items()
-            .filter_map(|item| {
-                let terminal_view = item.act_as::<TerminalView>(cx)?;
-                if terminal_view.read(cx).terminal().read(cx).task().is_some() {
-                    None
-                } else {
-                    let id = item.item_id().as_u64();
-                    items_to_serialize.insert(id);
-                    Some(id)
-                }
-            })
-            .collect::<Vec<_>>();
-        let active_item_id = self
-            .pane
-            .read(cx)
-            .active_item()
-            .map(|item| item.item_id().as_u64())
-            .filter(|active_id| items_to_serialize.contains(active_id));
+        let panes = self.serialize_member(&self.center.root, cx);
+        let (items, active_item_id) = serialized_pane_items(&self.active_pane, cx);
         let height = self.height;
         let width = self.width;
         self.pending_serialization = cx.background_executor().spawn(
@@ -541,6 +676,7 @@ impl TerminalPanel {
                         serde_json::to_string(&SerializedTerminalPanel {
                             items,
                             active_item_id,
+                            panes: Some(panes),
                             height,
                             width,
                         })?,
@@ -552,11 +688,32 @@ impl TerminalPanel {
         );
     }
 
+    fn serialize_member(&self, member: &Member, cx: &AppContext) -> SerializedPaneGroup {
+        match member {
+            Member::Axis(axis) => SerializedPaneGroup::Group {
+                axis: axis.axis.into(),
+                flexes: Some(axis.flexes.lock().clone()),
+                children: axis
+                    .members
+                    .iter()
+                    .map(|member| self.serialize_member(member, cx))
+                    .collect(),
+            },
+            Member::Pane(pane) => {
+                let (items, active_item_id) = serialized_pane_items(pane, cx);
+                SerializedPaneGroup::Pane {
+                    items,
+                    active_item_id,
+                    active: pane == &self.active_pane,
+                }
+            }
+        }
+    }
+
     fn replace_terminal(
         &self,
         working_directory: Option<PathBuf>,
         spawn_task: SpawnTask,
-        terminal_item_index: usize,
         terminal_to_replace: View<TerminalView>,
         cx: &mut ViewContext<'_, Self>,
     ) -> Option<()> {
@@ -577,7 +734,7 @@ impl TerminalPanel {
 
         match reveal {
             RevealStrategy::Always => {
-                self.activate_terminal_view(terminal_item_index, cx);
+                self.activate_terminal_view(&terminal_to_replace, cx);
                 let task_workspace = self.workspace.clone();
                 cx.spawn(|_, mut cx| async move {
                     task_workspace
@@ -593,11 +750,139 @@ impl TerminalPanel {
     }
 }
 
+/// The ids of the pane's terminals that can be restored, and of its active one.
+fn serialized_pane_items(pane: &View<Pane>, cx: &AppContext) -> (Vec<u64>, Option<u64>) {
+    let mut items_to_serialize = HashSet::default();
+    let items = pane
+        .read(cx)
+        .items()
+        .filter_map(|item| {
+            let terminal_view = item.act_as::<TerminalView>(cx)?;
+            let terminal = terminal_view.read(cx).terminal().read(cx);
+            if terminal.task().is_some() || terminal.is_remote() {
+                None
+            } else {
+                let id = item.item_id().as_u64();
+                items_to_serialize.insert(id);
+                Some(id)
+            }
+        })
+        .collect::<Vec<_>>();
+    let active_item_id = pane
+        .read(cx)
+        .active_item()
+        .map(|item| item.item_id().as_u64())
+        .filter(|active_id| items_to_serialize.contains(active_id));
+    (items, active_item_id)
+}
+
+fn new_terminal_pane(
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    cx: &mut ViewContext<TerminalPanel>,
+) -> View<Pane> {
+    let terminal_panel = cx.view().downgrade();
+    cx.new_view(|cx| {
+        let mut pane = Pane::new(
+            workspace.clone(),
+            project,
+            Default::default(),
+            None,
+            NewTerminal.boxed_clone(),
+            cx,
+        );
+        pane.set_can_split(false, cx);
+        pane.set_can_navigate(false, cx);
+        pane.display_nav_history_buttons(false);
+        pane.set_render_tab_bar_buttons(cx, move |pane, cx| {
+            let terminal_panel = terminal_panel.clone();
+            let pane_handle = cx.view().clone();
+            h_flex()
+                .gap_2()
+                .child(
+                    IconButton::new("plus", IconName::Plus)
+                        .icon_size(IconSize::Small)
+                        .on_click(move |_, cx| {
+                            terminal_panel
+                                .update(cx, |panel, cx| {
+                                    panel.add_terminal_to_pane(pane_handle.clone(), None, None, cx)
+                                })
+                                .log_err();
+                        })
+                        .tooltip(|cx| Tooltip::text("New Terminal", cx)),
+                )
+                .child({
+                    let zoomed = pane.is_zoomed();
+                    IconButton::new("toggle_zoom", IconName::Maximize)
+                        .icon_size(IconSize::Small)
+                        .selected(zoomed)
+                        .selected_icon(IconName::Minimize)
+                        .on_click(cx.listener(|pane, _, cx| {
+                            pane.toggle_zoom(&workspace::ToggleZoom, cx);
+                        }))
+                        .tooltip(move |cx| {
+                            Tooltip::text(if zoomed { "Zoom Out" } else { "Zoom In" }, cx)
+                        })
+                })
+                .into_any_element()
+        });
+
+        pane.set_custom_drop_handle(cx, move |pane, dropped_item, cx| {
+            if let Some(tab) = dropped_item.downcast_ref::<DraggedTab>() {
+                let item = if &tab.pane == cx.view() {
+                    pane.item_for_index(tab.ix)
+                } else {
+                    tab.pane.read(cx).item_for_index(tab.ix)
+                };
+                if let Some(item) = item {
+                    if item.downcast::<TerminalView>().is_some() {
+                        return ControlFlow::Continue(());
+                    } else if let Some(project_path) = item.project_path(cx) {
+                        if let Some(entry_path) = workspace
+                            .update(cx, |workspace, cx| {
+                                workspace
+                                    .project()
+                                    .read(cx)
+                                    .absolute_path(&project_path, cx)
+                            })
+                            .log_err()
+                            .flatten()
+                        {
+                            add_paths_to_terminal(pane, &[entry_path], cx);
+                        }
+                    }
+                }
+            } else if let Some(&entry_id) = dropped_item.downcast_ref::<ProjectEntryId>() {
+                if let Some(entry_path) = workspace
+                    .update(cx, |workspace, cx| {
+                        let project = workspace.project().read(cx);
+                        project
+                            .path_for_entry(entry_id, cx)
+                            .and_then(|project_path| project.absolute_path(&project_path, cx))
+                    })
+                    .log_err()
+                    .flatten()
+                {
+                    add_paths_to_terminal(pane, &[entry_path], cx);
+                }
+            } else if let Some(paths) = dropped_item.downcast_ref::<ExternalPaths>() {
+                add_paths_to_terminal(pane, paths.paths(), cx);
+            }
+
+            ControlFlow::Break(())
+        });
+        let buffer_search_bar = cx.new_view(search::BufferSearchBar::new);
+        pane.toolbar()
+            .update(cx, |toolbar, cx| toolbar.add_item(buffer_search_bar, cx));
+        pane
+    })
+}
+
 async fn wait_for_terminals_tasks(
-    terminals_for_task: Vec<(usize, View<TerminalView>)>,
+    terminals_for_task: Vec<View<TerminalView>>,
     cx: &mut AsyncWindowContext,
 ) {
-    let pending_tasks = terminals_for_task.iter().filter_map(|(_, terminal)| {
+    let pending_tasks = terminals_for_task.iter().filter_map(|terminal| {
         terminal
             .update(cx, |terminal_view, cx| {
                 terminal_view
@@ -632,7 +917,7 @@ impl Render for TerminalPanel {
         let mut registrar = DivRegistrar::new(
             |panel, cx| {
                 panel
-                    .pane
+                    .active_pane
                     .read(cx)
                     .toolbar()
                     .read(cx)
@@ -641,13 +926,29 @@ impl Render for TerminalPanel {
             cx,
         );
         BufferSearchBar::register(&mut registrar);
-        registrar.into_div().size_full().child(self.pane.clone())
+        let content = if self.active_pane.read(cx).is_zoomed() {
+            self.active_pane.clone().into_any_element()
+        } else {
+            self.center
+                .render(
+                    None,
+                    &ActivePaneDecorator::new(&self.active_pane, &self.workspace),
+                    cx,
+                )
+                .into_any_element()
+        };
+        registrar
+            .into_div()
+            .size_full()
+            .on_action(cx.listener(Self::activate_pane_in_direction))
+            .on_action(cx.listener(Self::swap_pane_in_direction))
+            .child(content)
     }
 }
 
 impl FocusableView for TerminalPanel {
     fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
-        self.pane.focus_handle(cx)
+        self.active_pane.focus_handle(cx)
     }
 }
 
@@ -695,21 +996,28 @@ impl Panel for TerminalPanel {
     }
 
     fn is_zoomed(&self, cx: &WindowContext) -> bool {
-        self.pane.read(cx).is_zoomed()
+        self.active_pane.read(cx).is_zoomed()
     }
 
     fn set_zoomed(&mut self, zoomed: bool, cx: &mut ViewContext<Self>) {
-        self.pane.update(cx, |pane, cx| pane.set_zoomed(zoomed, cx));
+        if zoomed {
+            self.active_pane
+                .update(cx, |pane, cx| pane.set_zoomed(true, cx));
+        } else {
+            for pane in self.center.panes() {
+                pane.update(cx, |pane, cx| pane.set_zoomed(false, cx));
+            }
+        }
     }
 
     fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
-        if active && self.pane.read(cx).items_len() == 0 && self.pending_terminals_to_add == 0 {
+        if active && self.items_len(cx) == 0 && self.pending_terminals_to_add == 0 {
             self.add_terminal(None, None, cx)
         }
     }
 
     fn icon_label(&self, cx: &WindowContext) -> Option<String> {
-        let count = self.pane.read(cx).items_len();
+        let count = self.items_len(cx);
         if count == 0 {
             None
         } else {
@@ -736,8 +1044,262 @@ impl Panel for TerminalPanel {
 
 #[derive(Serialize, Deserialize)]
 struct SerializedTerminalPanel {
+    /// The terminals of the active pane, the only ones restored by versions without splits.
     items: Vec<u64>,
     active_item_id: Option<u64>,
+    #[serde(default)]
+    panes: Option<SerializedPaneGroup>,
     width: Option<Pixels>,
     height: Option<Pixels>,
 }
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SerializedPaneGroup {
+    Group {
+        axis: SerializedAxis,
+        flexes: Option<Vec<f32>>,
+        children: Vec<SerializedPaneGroup>,
+    },
+    Pane {
+        items: Vec<u64>,
+        active_item_id: Option<u64>,
+        active: bool,
+    },
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SerializedAxis {
+    Horizontal,
+    Vertical,
+}
+
+impl From<Axis> for SerializedAxis {
+    fn from(axis: Axis) -> Self {
+        match axis {
+            Axis::Horizontal => SerializedAxis::Horizontal,
+            Axis::Vertical => SerializedAxis::Vertical,
+        }
+    }
+}
+
+impl From<SerializedAxis> for Axis {
+    fn from(axis: SerializedAxis) -> Self {
+        match axis {
+            SerializedAxis::Horizontal => Axis::Horizontal,
+            SerializedAxis::Vertical => Axis::Vertical,
+        }
+    }
+}
+
+/// A restored pane, along with its terminals being deserialized and the id of the active one.
+type PendingPane = (
+    WeakView<Pane>,
+    Vec<Task<Result<View<TerminalView>>>>,
+    Option<u64>,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use workspace::{item::test::TestItem, AppState};
+
+    #[gpui::test]
+    async fn test_split_navigation(cx: &mut TestAppContext) {
+        let (_, panel, cx) = init_test(cx).await;
+
+        // ┌──────┬───────┐
+        // │      │ right │
+        // │ left ├───────┤
+        // │      │ below │
+        // └──────┴───────┘
+        let (left, right, below) = panel.update(cx, |panel, cx| {
+            let left = panel.active_pane.clone();
+            let right = panel.split_pane(&left, SplitDirection::Right, cx);
+            let below = panel.split_pane(&right, SplitDirection::Down, cx);
+            panel.active_pane = below.clone();
+            (left, right, below)
+        });
+        cx.run_until_parked();
+
+        panel.update(cx, |panel, cx| {
+            assert_eq!(panel.center.panes(), [&left, &right, &below]);
+            assert_eq!(
+                panel.find_pane_in_direction(SplitDirection::Left, cx),
+                Some(left.clone())
+            );
+            assert_eq!(
+                panel.find_pane_in_direction(SplitDirection::Up, cx),
+                Some(right.clone())
+            );
+            assert_eq!(
+                panel.find_pane_in_direction(SplitDirection::Right, cx),
+                None
+            );
+            assert_eq!(panel.find_pane_in_direction(SplitDirection::Down, cx), None);
+        });
+
+        // Activating a split focuses it, which makes it the active one.
+        panel.update(cx, |panel, cx| {
+            panel.activate_pane_in_direction(&ActivatePaneInDirection(SplitDirection::Up), cx)
+        });
+        cx.run_until_parked();
+        panel.update(cx, |panel, cx| {
+            assert_eq!(panel.active_pane, right);
+            assert!(right.focus_handle(cx).contains_focused(cx));
+        });
+
+        panel.update(cx, |panel, cx| {
+            panel.swap_pane_in_direction(&SwapPaneInDirection(SplitDirection::Left), cx)
+        });
+        panel.update(cx, |panel, _| {
+            assert_eq!(panel.center.panes(), [&right, &left, &below]);
+        });
+
+        // Removing the active split activates another one.
+        panel.update(cx, |panel, cx| panel.remove_pane(&right, cx));
+        panel.update(cx, |panel, _| {
+            assert_eq!(panel.center.panes(), [&left, &below]);
+            assert_eq!(panel.active_pane, left);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_serialize_splits(cx: &mut TestAppContext) {
+        let (workspace, panel, cx) = init_test(cx).await;
+
+        let serialized = panel.update(cx, |panel, cx| {
+            let left = panel.active_pane.clone();
+            let right = panel.split_pane(&left, SplitDirection::Right, cx);
+            let below = panel.split_pane(&right, SplitDirection::Down, cx);
+            panel.active_pane = below;
+            serde_json::to_value(panel.serialize_member(&panel.center.root, cx)).unwrap()
+        });
+        let pane =
+            |active| json!({ "pane": { "items": [], "active_item_id": null, "active": active } });
+        assert_eq!(
+            serialized,
+            json!({
+                "group": {
+                    "axis": "horizontal",
+                    "flexes": [1.0, 1.0],
+                    "children": [
+                        pane(false),
+                        {
+                            "group": {
+                                "axis": "vertical",
+                                "flexes": [1.0, 1.0],
+                                "children": [pane(false), pane(true)],
+                            }
+                        },
+                    ],
+                }
+            })
+        );
+
+        // The layout is restored into a new panel, reusing the pane it was created with.
+        let restored = workspace.update(cx, |workspace, cx| {
+            cx.new_view(|cx| TerminalPanel::new(workspace, cx))
+        });
+        let initial_pane = restored.update(cx, |restored, _| restored.active_pane.clone());
+        let layout = serde_json::from_value::<SerializedPaneGroup>(serialized).unwrap();
+        let pending_panes = workspace.update(cx, |workspace, cx| {
+            restored.update(cx, |restored, cx| {
+                let mut pending_panes = Vec::new();
+                let root = restored
+                    .deserialize_member(&layout, workspace, &mut pending_panes, cx)
+                    .unwrap();
+                restored.center = PaneGroup::with_root(root);
+                pending_panes
+            })
+        });
+        assert_eq!(pending_panes.len(), 3);
+        restored.update(cx, |restored, cx| {
+            let panes = restored.center.panes();
+            assert_eq!(panes.len(), 3);
+            assert_eq!(panes[0], &initial_pane);
+            assert_eq!(&restored.active_pane, panes[2]);
+            assert_eq!(
+                serde_json::to_value(restored.serialize_member(&restored.center.root, cx)).unwrap(),
+                serde_json::to_value(&layout).unwrap()
+            );
+        });
+
+        // Panels saved before splits were supported have no layout.
+        let legacy = serde_json::from_value::<SerializedTerminalPanel>(json!({
+            "items": [1, 2],
+            "active_item_id": 2,
+            "width": null,
+            "height": null,
+        }))
+        .unwrap();
+        assert!(legacy.panes.is_none());
+        assert_eq!(legacy.items, [1, 2]);
+    }
+
+    #[gpui::test]
+    async fn test_task_split(cx: &mut TestAppContext) {
+        let (_, panel, cx) = init_test(cx).await;
+        let build = TaskId("build".into());
+        let test = TaskId("test".into());
+
+        panel.update(cx, |panel, cx| {
+            let first = panel.active_pane.clone();
+
+            // A task opens a split next to the active one, without activating it.
+            let build_pane = panel.pane_for_task(&build, TerminalSplit::Right, cx);
+            assert_eq!(panel.center.panes(), [&first, &build_pane]);
+            assert_eq!(panel.active_pane, first);
+
+            // Later runs of the task reuse its split, while other tasks get their own.
+            assert_eq!(
+                panel.pane_for_task(&build, TerminalSplit::Right, cx),
+                build_pane
+            );
+            let test_pane = panel.pane_for_task(&test, TerminalSplit::Down, cx);
+            assert_eq!(panel.center.panes(), [&first, &test_pane, &build_pane]);
+
+            // Once its split is closed, the task opens a new one.
+            panel.remove_pane(&build_pane, cx);
+            let new_build_pane = panel.pane_for_task(&build, TerminalSplit::Left, cx);
+            assert_ne!(new_build_pane, build_pane);
+            assert_eq!(panel.center.panes(), [&new_build_pane, &first, &test_pane]);
+        });
+    }
+
+    async fn init_test(
+        cx: &mut TestAppContext,
+    ) -> (View<Workspace>, View<TerminalPanel>, &mut VisualTestContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let panel = workspace.update(cx, |workspace, cx| {
+            let panel = cx.new_view(|cx| TerminalPanel::new(workspace, cx));
+            // Keep the panel from opening a terminal when it's shown.
+            panel.update(cx, |panel, cx| {
+                let item = Box::new(cx.new_view(TestItem::new));
+                panel
+                    .active_pane
+                    .update(cx, |pane, cx| pane.add_item(item, true, true, None, cx));
+            });
+            workspace.add_panel(panel.clone(), cx);
+            workspace.focus_panel::<TerminalPanel>(cx);
+            panel
+        });
+        cx.run_until_parked();
+
+        (workspace, panel, cx)
+    }
+}
@@ -6,31 +6,34 @@ use collections::HashSet;
 use editor::{scroll::Autoscroll, Editor};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
-    div, impl_actions, overlay, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle,
-    FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton, MouseDownEvent, Pixels,
-    Render, Styled, Subscription, Task, View, VisualContext, WeakView,
+    actions, div, impl_actions, overlay, AnyElement, AppContext, DismissEvent, EventEmitter,
+    FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton,
+    MouseDownEvent, Pixels, PromptLevel, Render, Styled, Subscription, Task, View, VisualContext,
+    WeakView,
 };
 use language::Bias;
 use persistence::TERMINAL_DB;
 use project::{search::SearchQuery, Fs, LocalWorktree, Metadata, Project};
-use settings::SettingsStore;
+use rpc::proto::{self, PeerId};
+use settings::{SettingsLocation, SettingsStore};
 use terminal::{
     alacritty_terminal::{
         index::Point,
         term::{search::RegexSearch, TermMode},
     },
     terminal_settings::{TerminalBlink, TerminalSettings, WorkingDirectory},
-    Clear, Copy, Event, MaybeNavigationTarget, Paste, ShowCharacterPalette, Terminal,
+    Clear, Copy, CopyLastCommandOutput, Event, MaybeNavigationTarget, Paste, ScrollToNextPrompt,
+    ScrollToPreviousPrompt, ShowCharacterPalette, Terminal,
 };
 use terminal_element::TerminalElement;
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label};
 use util::{paths::PathLikeWithPosition, ResultExt};
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent},
-    notifications::NotifyResultExt,
-    register_deserializable_item,
+    item::{BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent},
+    notifications::{NotifyResultExt, NotifyTaskExt},
+    register_deserializable_item, register_followable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, Workspace,
+    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, ViewId, Workspace,
     WorkspaceId,
 };
 
@@ -52,6 +55,7 @@ const REGEX_SPECIAL_CHARS: &[char] = &[
 ];
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+const SCROLLBACK_SERIALIZATION_DELAY: Duration = Duration::from_secs(2);
 
 ///Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
@@ -65,18 +69,94 @@ pub struct SendKeystroke(String);
 
 impl_actions!(terminal, [SendText, SendKeystroke]);
 
+actions!(
+    terminal,
+    [
+        ShareWithCollaborators,
+        StopSharingWithCollaborators,
+        RequestControl
+    ]
+);
+
 pub fn init(cx: &mut AppContext) {
     terminal_panel::init(cx);
     terminal::init(cx);
 
     register_deserializable_item::<TerminalView>(cx);
+    register_followable_item::<TerminalView>(cx);
 
-    cx.observe_new_views(|workspace: &mut Workspace, _| {
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
         workspace.register_action(TerminalView::deploy);
+
+        let project = workspace.project().clone();
+        cx.subscribe(&project, |workspace, _, event, cx| match event {
+            project::Event::TerminalShared(terminal) => {
+                open_shared_terminal(workspace, terminal.clone(), cx)
+            }
+            project::Event::TerminalControlRequested { terminal, peer_id } => {
+                prompt_for_terminal_control(workspace, terminal.clone(), *peer_id, cx)
+            }
+            _ => {}
+        })
+        .detach();
     })
     .detach();
 }
 
+/// Opens a terminal shared by the host of a remote project, without taking focus from what the
+/// guest is doing.
+fn open_shared_terminal(
+    workspace: &mut Workspace,
+    terminal: Model<Terminal>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let workspace_handle = workspace.weak_handle();
+    let workspace_id = workspace.database_id();
+    let view = cx.new_view(|cx| TerminalView::new(terminal, workspace_handle, workspace_id, cx));
+    workspace.active_pane().update(cx, |pane, cx| {
+        pane.add_item(Box::new(view), false, false, None, cx)
+    });
+}
+
+fn prompt_for_terminal_control(
+    workspace: &mut Workspace,
+    terminal: Model<Terminal>,
+    peer_id: PeerId,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let github_login = project
+        .read(cx)
+        .collaborators()
+        .get(&peer_id)
+        .and_then(|collaborator| {
+            workspace
+                .app_state()
+                .user_store
+                .read(cx)
+                .get_cached_user(collaborator.user_id)
+        })
+        .map(|user| user.github_login.clone());
+    let message = match github_login {
+        Some(github_login) => format!("{github_login} wants to type in your terminal"),
+        None => "A collaborator wants to type in your terminal".to_string(),
+    };
+    let title = terminal.read(cx).title(true);
+    let answer = cx.prompt(
+        PromptLevel::Info,
+        &message,
+        Some(&title),
+        &["Allow", "Deny"],
+    );
+    cx.spawn(|_, mut cx| async move {
+        let allow = answer.await.ok() == Some(0);
+        project.update(&mut cx, |project, _| {
+            project.respond_to_terminal_control_request(&terminal, peer_id, allow)
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
 ///A terminal view, maintains the PTY's file handles and communicates with the terminal
 pub struct TerminalView {
     terminal: Model<Terminal>,
@@ -92,6 +172,12 @@ pub struct TerminalView {
     can_navigate_to_selected_word: bool,
     workspace_id: WorkspaceId,
     show_title: bool,
+    /// Whether the database may hold a scrollback for this terminal that has to be cleared
+    /// when persisting it gets disabled.
+    has_persisted_scrollback: bool,
+    pending_scrollback_serialization: Option<Task<()>>,
+    /// Set when this view was opened for a shared terminal that a followed collaborator focused.
+    remote_id: Option<ViewId>,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -168,6 +254,9 @@ impl TerminalView {
             can_navigate_to_selected_word: false,
             workspace_id,
             show_title: TerminalSettings::get_global(cx).toolbar.title,
+            has_persisted_scrollback: true,
+            pending_scrollback_serialization: None,
+            remote_id: None,
             _subscriptions: vec![
                 focus_in,
                 focus_out,
@@ -195,8 +284,37 @@ impl TerminalView {
         position: gpui::Point<Pixels>,
         cx: &mut ViewContext<Self>,
     ) {
+        let terminal = self.terminal.read(cx);
+        let has_shell_integration = terminal.shell_integration().is_active();
+        let (can_share, is_shared, can_request_control) = match self.project(cx) {
+            Some(project) => {
+                let project = project.read(cx);
+                let is_shared = project.is_terminal_shared(&self.terminal);
+                (
+                    project.is_shared() && !is_shared && !terminal.is_remote(),
+                    is_shared,
+                    terminal.is_remote() && !project.has_terminal_control(&self.terminal),
+                )
+            }
+            None => (false, false, false),
+        };
         let context_menu = ContextMenu::build(cx, |menu, _| {
             menu.action("Clear", Box::new(Clear))
+                .when(has_shell_integration, |menu| {
+                    menu.action("Copy Last Command Output", Box::new(CopyLastCommandOutput))
+                })
+                .when(can_share, |menu| {
+                    menu.action("Share with Collaborators", Box::new(ShareWithCollaborators))
+                })
+                .when(is_shared, |menu| {
+                    menu.action(
+                        "Stop Sharing with Collaborators",
+                        Box::new(StopSharingWithCollaborators),
+                    )
+                })
+                .when(can_request_control, |menu| {
+                    menu.action("Request Control", Box::new(RequestControl))
+                })
                 .action("Close", Box::new(CloseActiveItem { save_intent: None }))
         });
 
@@ -215,12 +333,119 @@ impl TerminalView {
         self.context_menu = Some((context_menu, position, subscription));
     }
 
+    fn project(&self, cx: &AppContext) -> Option<Model<Project>> {
+        self.workspace
+            .upgrade()
+            .map(|workspace| workspace.read(cx).project().clone())
+    }
+
+    fn share_with_collaborators(&mut self, _: &ShareWithCollaborators, cx: &mut ViewContext<Self>) {
+        let terminal = self.terminal.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .project()
+                    .update(cx, |project, cx| project.share_terminal(&terminal, cx))
+                    .notify_err(workspace, cx);
+            })
+            .ok();
+    }
+
+    fn stop_sharing_with_collaborators(
+        &mut self,
+        _: &StopSharingWithCollaborators,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(project) = self.project(cx) {
+            project.update(cx, |project, cx| {
+                project.unshare_terminal(&self.terminal, cx)
+            });
+        }
+    }
+
+    fn request_control(&mut self, _: &RequestControl, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project(cx) else {
+            return;
+        };
+        let request = project.update(cx, |project, cx| {
+            project.request_terminal_control(&self.terminal, cx)
+        });
+        cx.spawn(|_, _| async move {
+            let granted = request.await?;
+            anyhow::ensure!(
+                granted,
+                "The host declined your request to type in this terminal"
+            );
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(cx);
+    }
+
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         let settings = TerminalSettings::get_global(cx);
         self.show_title = settings.toolbar.title;
+        self.schedule_scrollback_serialization(cx);
         cx.notify();
     }
 
+    fn schedule_scrollback_serialization(&mut self, cx: &mut ViewContext<Self>) {
+        let terminal = self.terminal.read(cx);
+        if self.pending_scrollback_serialization.is_some()
+            || terminal.task().is_some()
+            || terminal.is_remote()
+        {
+            return;
+        }
+
+        self.pending_scrollback_serialization = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(SCROLLBACK_SERIALIZATION_DELAY)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_scrollback_serialization = None;
+                this.serialize_scrollback(cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn serialize_scrollback(&mut self, cx: &mut ViewContext<Self>) {
+        let terminal = self.terminal.read(cx);
+        let Some(project) = self
+            .workspace
+            .upgrade()
+            .map(|workspace| workspace.read(cx).project().clone())
+        else {
+            return;
+        };
+        let settings = terminal_settings_for(project.read(cx), terminal.get_cwd().as_deref(), cx);
+
+        let scrollback = if settings.persist_scrollback {
+            // The primary screen can't be read while an application uses the alternate one,
+            // so the last snapshot is kept.
+            if terminal.last_content.mode.contains(TermMode::ALT_SCREEN) {
+                return;
+            }
+            terminal.scrollback_snapshot(settings.max_persisted_scrollback_lines)
+        } else if self.has_persisted_scrollback {
+            None
+        } else {
+            return;
+        };
+
+        self.has_persisted_scrollback = scrollback.is_some();
+        let item_id = cx.entity_id().as_u64();
+        let workspace_id = self.workspace_id;
+        cx.background_executor()
+            .spawn(async move {
+                TERMINAL_DB
+                    .save_scrollback(item_id, workspace_id, scrollback)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+
     fn show_character_palette(&mut self, _: &ShowCharacterPalette, cx: &mut ViewContext<Self>) {
         if !self
             .terminal
@@ -250,6 +475,28 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(&mut self, _: &ScrollToNextPrompt, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn copy_last_command_output(&mut self, _: &CopyLastCommandOutput, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.copy_last_command_output());
+        cx.notify();
+    }
+
     pub fn should_show_cursor(&self, focused: bool, cx: &mut gpui::ViewContext<Self>) -> bool {
         //Don't blink the cursor when not focused, blinking is disabled, or paused
         if !focused
@@ -442,6 +689,7 @@ fn subscribe_for_terminal_events(
                 cx.emit(Event::Wakeup);
                 cx.emit(ItemEvent::UpdateTab);
                 cx.emit(SearchEvent::MatchesInvalidated);
+                this.schedule_scrollback_serialization(cx);
             }
 
             Event::Bell => {
@@ -741,8 +989,14 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::copy))
             .on_action(cx.listener(TerminalView::paste))
             .on_action(cx.listener(TerminalView::clear))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
+            .on_action(cx.listener(TerminalView::share_with_collaborators))
+            .on_action(cx.listener(TerminalView::stop_sharing_with_collaborators))
+            .on_action(cx.listener(TerminalView::request_control))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -861,6 +1115,11 @@ impl Item for TerminalView {
         Some("Terminal")
     }
 
+    fn is_serializable(&self, cx: &AppContext) -> bool {
+        // Mirrors of a terminal shared by a collaborator can't be restored.
+        !self.terminal.read(cx).is_remote()
+    }
+
     fn deserialize(
         project: Model<Project>,
         workspace: WeakView<Workspace>,
@@ -886,8 +1145,20 @@ impl Item for TerminalView {
                 })
                 .filter(|cwd| !cwd.as_os_str().is_empty());
 
+            let persist_scrollback = project.update(&mut cx, |project, cx| {
+                terminal_settings_for(project, cwd.as_deref(), cx).persist_scrollback
+            })?;
+            let scrollback = if persist_scrollback {
+                TERMINAL_DB
+                    .get_scrollback(item_id, workspace_id)
+                    .log_err()
+                    .flatten()
+            } else {
+                None
+            };
+
             let terminal = project.update(&mut cx, |project, cx| {
-                project.create_terminal(cwd, None, window, cx)
+                project.restore_terminal(cwd, scrollback, window, cx)
             })??;
             pane.update(&mut cx, |_, cx| {
                 cx.new_view(|cx| TerminalView::new(terminal, workspace, workspace_id, cx))
@@ -913,6 +1184,94 @@ impl Item for TerminalView {
     }
 }
 
+impl FollowableItem for TerminalView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        // Only terminals shared with collaborators can be followed, and their content reaches
+        // followers through the shared terminal's own updates.
+        let terminal_id = self
+            .project(cx)?
+            .read(cx)
+            .shared_terminal_id(&self.terminal)?;
+        Some(proto::view::Variant::Terminal(proto::view::Terminal {
+            terminal_id,
+        }))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<anyhow::Result<View<Self>>>> {
+        let Some(proto::view::Variant::Terminal(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::Terminal(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let Some(terminal) = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .shared_terminal(state.terminal_id)
+        else {
+            return Some(Task::ready(Err(anyhow::anyhow!(
+                "terminal {} isn't shared",
+                state.terminal_id
+            ))));
+        };
+        let existing = pane
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|view| view.read(cx).terminal == terminal);
+        let view = existing.unwrap_or_else(|| {
+            let workspace_id = workspace.read(cx).database_id();
+            let workspace = workspace.downgrade();
+            cx.new_view(|cx| TerminalView::new(terminal, workspace, workspace_id, cx))
+        });
+        view.update(cx, |view, _| view.remote_id = Some(remote_id));
+        Some(Task::ready(Ok(view)))
+    }
+
+    fn to_follow_event(_: &ItemEvent) -> Option<FollowEvent> {
+        None
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        _: &ItemEvent,
+        _: &mut Option<proto::update_view::Variant>,
+        _: &WindowContext,
+    ) -> bool {
+        false
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        _: proto::update_view::Variant,
+        _: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        Task::ready(Err(anyhow::anyhow!(
+            "terminals are updated through their shared terminal"
+        )))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, _: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        cx.notify();
+    }
+}
+
 impl SearchableItem for TerminalView {
     type Match = RangeInclusive<Point>;
 
@@ -1029,6 +1388,26 @@ impl SearchableItem for TerminalView {
     }
 }
 
+/// Terminal settings that apply to the given directory, including those of the project's settings
+/// file when the directory belongs to one of its worktrees.
+fn terminal_settings_for<'a>(
+    project: &Project,
+    cwd: Option<&Path>,
+    cx: &'a AppContext,
+) -> &'a TerminalSettings {
+    cwd.and_then(|cwd| project.find_local_worktree(cwd, cx))
+        .map(|(worktree, path)| {
+            TerminalSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: worktree.read(cx).id().to_usize(),
+                    path: &path,
+                }),
+                cx,
+            )
+        })
+        .unwrap_or_else(|| TerminalSettings::get_global(cx))
+}
+
 ///Gets the working directory for the given workspace, respecting the user's settings.
 pub fn get_working_directory(
     workspace: &Workspace,
@@ -591,6 +591,47 @@ fn test_history() {
     assert_eq!(buffer.text(), "X12cde6");
 }
 
+#[test]
+fn test_serialize_history() {
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abcdef".into());
+    buffer.edit([(1..2, "BB"), (4..4, "X")]);
+    buffer.edit([(0..0, "12")]);
+    buffer.edit([(3..5, "")]);
+    buffer.edit([(0..1, "")]);
+    buffer.undo();
+    assert_eq!(buffer.text(), "12acdXef");
+
+    let history = buffer.serialize_history(usize::MAX);
+    assert_eq!(history.undo_stack.len(), 3);
+    assert_eq!(history.redo_stack.len(), 1);
+    assert_eq!(history.initial_text(&buffer.text()).unwrap(), "abcdef");
+
+    // Replaying the undo and redo stacks visits the same texts as the original buffer.
+    let mut text = "abcdef".to_string();
+    let mut texts = Vec::new();
+    for transaction in history.undo_stack.iter().chain(&history.redo_stack) {
+        transaction.apply(&mut text).unwrap();
+        texts.push(text.clone());
+    }
+    assert_eq!(texts, ["aBBcdXef", "12aBBcdXef", "12acdXef", "2acdXef"]);
+
+    // Only the most recent transactions are kept.
+    let history = buffer.serialize_history(2);
+    assert_eq!(history.undo_stack.len(), 2);
+    assert_eq!(history.initial_text(&buffer.text()).unwrap(), "aBBcdXef");
+
+    // Histories are rejected if they don't apply to the text.
+    assert!(history.initial_text("12acdXeg").is_err());
+
+    // Serializing the history doesn't affect the buffer.
+    assert_eq!(buffer.text(), "12acdXef");
+    buffer.undo();
+    assert_eq!(buffer.text(), "12aBBcdXef");
+    buffer.redo();
+    buffer.redo();
+    assert_eq!(buffer.text(), "2acdXef");
+}
+
 #[test]
 fn test_finalize_last_transaction() {
     let now = Instant::now();
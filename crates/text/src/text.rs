@@ -23,6 +23,7 @@ use lazy_static::lazy_static;
 use regex::Regex;
 pub use rope::*;
 pub use selection::*;
+use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
     cmp::{self, Ordering, Reverse},
@@ -125,6 +126,89 @@ impl HistoryEntry {
     }
 }
 
+/// A buffer's undo and redo stacks, described in terms of the text their transactions changed, so
+/// that they can be persisted and later replayed onto a buffer with the same contents.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializedHistory {
+    /// The transactions that can be undone, oldest first.
+    pub undo_stack: Vec<SerializedTransaction>,
+    /// The transactions that can be redone, starting with the next one to redo.
+    pub redo_stack: Vec<SerializedTransaction>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializedTransaction {
+    /// The edits made by the transaction, ordered by their ranges in the text preceding it.
+    pub edits: Vec<SerializedEdit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializedEdit {
+    pub range: Range<usize>,
+    pub old_text: String,
+    pub new_text: String,
+}
+
+impl SerializedHistory {
+    pub fn is_empty(&self) -> bool {
+        self.undo_stack.is_empty() && self.redo_stack.is_empty()
+    }
+
+    /// Checks that the history can be replayed onto `text`, returning the text as it was before
+    /// the oldest transaction on the undo stack.
+    pub fn initial_text(&self, text: &str) -> Result<String> {
+        let mut initial_text = text.to_string();
+        for transaction in self.undo_stack.iter().rev() {
+            transaction.inverse().apply(&mut initial_text)?;
+        }
+
+        let mut redone_text = initial_text.clone();
+        for transaction in &self.undo_stack {
+            transaction.apply(&mut redone_text)?;
+        }
+        if redone_text != text {
+            return Err(anyhow!("undo stack doesn't lead back to the current text"));
+        }
+        for transaction in &self.redo_stack {
+            transaction.apply(&mut redone_text)?;
+        }
+
+        Ok(initial_text)
+    }
+}
+
+impl SerializedTransaction {
+    /// Returns a transaction that reverts this one, expressed in terms of the text following it.
+    pub fn inverse(&self) -> Self {
+        let mut delta = 0_isize;
+        let edits = self
+            .edits
+            .iter()
+            .map(|edit| {
+                let start = (edit.range.start as isize + delta) as usize;
+                delta += edit.new_text.len() as isize - edit.range.len() as isize;
+                SerializedEdit {
+                    range: start..start + edit.new_text.len(),
+                    old_text: edit.new_text.clone(),
+                    new_text: edit.old_text.clone(),
+                }
+            })
+            .collect();
+        Self { edits }
+    }
+
+    fn apply(&self, text: &mut String) -> Result<()> {
+        for edit in self.edits.iter().rev() {
+            if text.get(edit.range.clone()) != Some(edit.old_text.as_str()) {
+                return Err(anyhow!("edit at {:?} doesn't match the text", edit.range));
+            }
+            text.replace_range(edit.range.clone(), &edit.new_text);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
 struct History {
     base_text: Rope,
     operations: TreeMap<clock::Lamport, Operation>,
@@ -1254,6 +1338,71 @@ impl Buffer {
         }
     }
 
+    /// Describes up to `max_transactions` of the most recent transactions on the undo stack, and
+    /// as many on the redo stack, in terms of the text they changed.
+    pub fn serialize_history(&self, max_transactions: usize) -> SerializedHistory {
+        let mut history = SerializedHistory::default();
+        if self.history.transaction_depth > 0 {
+            return history;
+        }
+
+        // Undo and redo the transactions on a copy of the buffer, observing how its text changes.
+        let mut scratch = self.detached_copy();
+        while history.undo_stack.len() < max_transactions {
+            let version = scratch.version();
+            let text = scratch.visible_text.clone();
+            if scratch.undo().is_none() {
+                break;
+            }
+            let inverse = scratch.serialize_edits_since(&version, &text);
+            history.undo_stack.push(inverse.inverse());
+        }
+        history.undo_stack.reverse();
+
+        let mut scratch = self.detached_copy();
+        while history.redo_stack.len() < max_transactions {
+            let version = scratch.version();
+            let text = scratch.visible_text.clone();
+            if scratch.redo().is_none() {
+                break;
+            }
+            history
+                .redo_stack
+                .push(scratch.serialize_edits_since(&version, &text));
+        }
+
+        history
+    }
+
+    fn serialize_edits_since(
+        &self,
+        version: &clock::Global,
+        old_text: &Rope,
+    ) -> SerializedTransaction {
+        let edits = self
+            .edits_since::<usize>(version)
+            .map(|edit| SerializedEdit {
+                old_text: old_text.chunks_in_range(edit.old.clone()).collect(),
+                new_text: self.visible_text.chunks_in_range(edit.new).collect(),
+                range: edit.old,
+            })
+            .collect();
+        SerializedTransaction { edits }
+    }
+
+    fn detached_copy(&self) -> Self {
+        Self {
+            snapshot: self.snapshot.clone(),
+            history: self.history.clone(),
+            deferred_ops: OperationQueue::new(),
+            deferred_replicas: HashSet::default(),
+            lamport_clock: self.lamport_clock,
+            subscriptions: Default::default(),
+            edit_id_resolvers: Default::default(),
+            wait_for_version_txs: Default::default(),
+        }
+    }
+
     pub fn redo_to_transaction(&mut self, transaction_id: TransactionId) -> Vec<Operation> {
         let transactions = self
             .history
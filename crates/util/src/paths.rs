@@ -41,6 +41,8 @@ lazy_static::lazy_static! {
     pub static ref COPILOT_DIR: PathBuf = SUPPORT_DIR.join("copilot");
     pub static ref DEFAULT_PRETTIER_DIR: PathBuf = SUPPORT_DIR.join("prettier");
     pub static ref DB_DIR: PathBuf = SUPPORT_DIR.join("db");
+    pub static ref SHELL_INTEGRATION_DIR: PathBuf = SUPPORT_DIR.join("shell_integration");
+    pub static ref LOCAL_HISTORY_DIR: PathBuf = SUPPORT_DIR.join("local_history");
     pub static ref CRASHES_DIR: PathBuf = if cfg!(target_os = "macos") {
         HOME.join("Library/Logs/DiagnosticReports")
     } else if cfg!(target_os = "windows") {
@@ -281,6 +283,11 @@ impl PathMatcher {
         })
     }
 
+    /// Creates a matcher for the given path, treating any glob metacharacters in it literally.
+    pub fn literal(path: &Path) -> Result<Self, globset::Error> {
+        Self::new(&globset::escape(&path.to_string_lossy()))
+    }
+
     pub fn is_match<P: AsRef<Path>>(&self, other: P) -> bool {
         let other_path = other.as_ref();
         other_path.starts_with(&self.maybe_path)
@@ -488,6 +495,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn literal_path() {
+        let path_matcher = PathMatcher::literal(Path::new("app/[id]/page.tsx")).unwrap();
+        assert!(path_matcher.is_match(Path::new("app/[id]/page.tsx")));
+        assert!(!path_matcher.is_match(Path::new("app/i/page.tsx")));
+
+        let path_matcher = PathMatcher::literal(Path::new("src/{a,b}.rs")).unwrap();
+        assert!(path_matcher.is_match(Path::new("src/{a,b}.rs")));
+        assert!(!path_matcher.is_match(Path::new("src/a.rs")));
+    }
+
     #[test]
     fn project_search() {
         let path = Path::new("/Users/someonetoignore/work/zed/zed.dev/node_modules");
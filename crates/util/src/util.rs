@@ -90,6 +90,11 @@ pub fn truncate_and_remove_front(s: &str, max_chars: usize) -> String {
     }
 }
 
+/// Quotes the argument so that a POSIX shell treats it as a single word.
+pub fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
 pub fn post_inc<T: From<u8> + AddAssign<T> + Copy>(value: &mut T) -> T {
     let prev = *value;
     *value += T::from(1);
@@ -580,6 +585,12 @@ mod tests {
         assert_eq!(truncate_and_trailoff("èèèèèè", 5), "èèèèè…");
     }
 
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("foo bar"), "'foo bar'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
     #[test]
     fn test_numeric_prefix_with_suffix() {
         let mut sorted = vec!["1-abc", "10", "11def", "2", "21-abc"];
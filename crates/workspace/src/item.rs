@@ -212,6 +212,23 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
         None
     }
 
+    /// Whether this item should be saved with the workspace, for items whose kind can
+    /// otherwise be serialized.
+    fn is_serializable(&self, _cx: &AppContext) -> bool {
+        true
+    }
+
+    /// Persists the item's unsaved changes, so that they're restored along with the workspace
+    /// instead of prompting the user to save them when quitting. Returns `None` if the item's
+    /// unsaved changes can't be restored.
+    fn serialize_unsaved_changes(
+        &mut self,
+        _workspace_id: WorkspaceId,
+        _cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        None
+    }
+
     fn deserialize(
         _project: Model<Project>,
         _workspace: WeakView<Workspace>,
@@ -296,6 +313,12 @@ pub trait ItemHandle: 'static + Send {
     fn breadcrumb_location(&self, cx: &AppContext) -> ToolbarItemLocation;
     fn breadcrumbs(&self, theme: &Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>>;
     fn serialized_item_kind(&self) -> Option<&'static str>;
+    fn is_serializable(&self, cx: &AppContext) -> bool;
+    fn serialize_unsaved_changes(
+        &self,
+        workspace_id: WorkspaceId,
+        cx: &mut WindowContext,
+    ) -> Option<Task<Result<()>>>;
     fn show_toolbar(&self, cx: &AppContext) -> bool;
     fn pixel_position_of_cursor(&self, cx: &AppContext) -> Option<Point<Pixels>>;
 }
@@ -656,6 +679,20 @@ impl<T: Item> ItemHandle for View<T> {
         T::serialized_item_kind()
     }
 
+    fn is_serializable(&self, cx: &AppContext) -> bool {
+        self.read(cx).is_serializable(cx)
+    }
+
+    fn serialize_unsaved_changes(
+        &self,
+        workspace_id: WorkspaceId,
+        cx: &mut WindowContext,
+    ) -> Option<Task<Result<()>>> {
+        self.update(cx, |item, cx| {
+            item.serialize_unsaved_changes(workspace_id, cx)
+        })
+    }
+
     fn show_toolbar(&self, cx: &AppContext) -> bool {
         self.read(cx).show_toolbar()
     }
@@ -845,6 +882,8 @@ pub mod test {
         pub save_count: usize,
         pub save_as_count: usize,
         pub reload_count: usize,
+        pub serialize_unsaved_changes_count: usize,
+        pub can_restore_unsaved_changes: bool,
         pub is_dirty: bool,
         pub is_singleton: bool,
         pub has_conflict: bool,
@@ -906,6 +945,8 @@ pub mod test {
                 save_count: 0,
                 save_as_count: 0,
                 reload_count: 0,
+                serialize_unsaved_changes_count: 0,
+                can_restore_unsaved_changes: false,
                 is_dirty: false,
                 has_conflict: false,
                 project_items: Vec::new(),
@@ -944,6 +985,11 @@ pub mod test {
             self
         }
 
+        pub fn with_restorable_unsaved_changes(mut self, can_restore: bool) -> Self {
+            self.can_restore_unsaved_changes = can_restore;
+            self
+        }
+
         pub fn with_project_items(mut self, items: &[Model<TestProjectItem>]) -> Self {
             self.project_items.clear();
             self.project_items.extend(items.iter().cloned());
@@ -1114,6 +1160,19 @@ pub mod test {
             Some("TestItem")
         }
 
+        fn serialize_unsaved_changes(
+            &mut self,
+            _workspace_id: WorkspaceId,
+            _cx: &mut ViewContext<Self>,
+        ) -> Option<Task<anyhow::Result<()>>> {
+            if self.can_restore_unsaved_changes {
+                self.serialize_unsaved_changes_count += 1;
+                Some(Task::ready(Ok(())))
+            } else {
+                None
+            }
+        }
+
         fn deserialize(
             _project: Model<Project>,
             _workspace: WeakView<Workspace>,
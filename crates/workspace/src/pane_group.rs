@@ -3,8 +3,8 @@ use anyhow::{anyhow, Result};
 use call::{ActiveCall, ParticipantLocation};
 use collections::HashMap;
 use gpui::{
-    point, size, AnyView, AnyWeakView, Axis, Bounds, IntoElement, Model, MouseButton, Pixels,
-    Point, StyleRefinement, View, ViewContext,
+    point, size, AnyElement, AnyView, AnyWeakView, Axis, Bounds, Hsla, IntoElement, Model,
+    MouseButton, Pixels, Point, StyleRefinement, View, WeakView, WindowContext,
 };
 use parking_lot::Mutex;
 use project::Project;
@@ -18,11 +18,11 @@ const VERTICAL_MIN_SIZE: f32 = 100.;
 
 #[derive(Clone)]
 pub struct PaneGroup {
-    pub(crate) root: Member,
+    pub root: Member,
 }
 
 impl PaneGroup {
-    pub(crate) fn with_root(root: Member) -> Self {
+    pub fn with_root(root: Member) -> Self {
         Self { root }
     }
 
@@ -88,42 +88,28 @@ impl PaneGroup {
         };
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub(crate) fn render(
+    pub fn render(
         &self,
-        project: &Model<Project>,
-        follower_states: &HashMap<View<Pane>, FollowerState>,
-        active_call: Option<&Model<ActiveCall>>,
-        active_pane: &View<Pane>,
         zoomed: Option<&AnyWeakView>,
-        app_state: &Arc<AppState>,
-        cx: &mut ViewContext<Workspace>,
+        render_cx: &dyn PaneLeaderDecorator,
+        cx: &mut WindowContext,
     ) -> impl IntoElement {
-        self.root.render(
-            project,
-            0,
-            follower_states,
-            active_call,
-            active_pane,
-            zoomed,
-            app_state,
-            cx,
-        )
+        self.root.render(0, zoomed, render_cx, cx)
     }
 
-    pub(crate) fn panes(&self) -> Vec<&View<Pane>> {
+    pub fn panes(&self) -> Vec<&View<Pane>> {
         let mut panes = Vec::new();
         self.root.collect_panes(&mut panes);
         panes
     }
 
-    pub(crate) fn first_pane(&self) -> View<Pane> {
+    pub fn first_pane(&self) -> View<Pane> {
         self.root.first_pane()
     }
 }
 
 #[derive(Clone)]
-pub(crate) enum Member {
+pub enum Member {
     Axis(PaneAxis),
     Pane(View<Pane>),
 }
@@ -160,17 +146,12 @@ impl Member {
         }
     }
 
-    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &self,
-        project: &Model<Project>,
         basis: usize,
-        follower_states: &HashMap<View<Pane>, FollowerState>,
-        active_call: Option<&Model<ActiveCall>>,
-        active_pane: &View<Pane>,
         zoomed: Option<&AnyWeakView>,
-        app_state: &Arc<AppState>,
-        cx: &mut ViewContext<Workspace>,
+        render_cx: &dyn PaneLeaderDecorator,
+        cx: &mut WindowContext,
     ) -> impl IntoElement {
         match self {
             Member::Pane(pane) => {
@@ -178,62 +159,7 @@ impl Member {
                     return div().into_any();
                 }
 
-                let follower_state = follower_states.get(pane);
-
-                let leader = follower_state.and_then(|state| {
-                    let room = active_call?.read(cx).room()?.read(cx);
-                    room.remote_participant_for_peer_id(state.leader_id)
-                });
-
-                let is_in_unshared_view = follower_state.map_or(false, |state| {
-                    state.active_view_id.is_some_and(|view_id| {
-                        !state.items_by_leader_view_id.contains_key(&view_id)
-                    })
-                });
-
-                let mut leader_border = None;
-                let mut leader_status_box = None;
-                let mut leader_join_data = None;
-                if let Some(leader) = &leader {
-                    let mut leader_color = cx
-                        .theme()
-                        .players()
-                        .color_for_participant(leader.participant_index.0)
-                        .cursor;
-                    leader_color.fade_out(0.3);
-                    leader_border = Some(leader_color);
-
-                    leader_status_box = match leader.location {
-                        ParticipantLocation::SharedProject {
-                            project_id: leader_project_id,
-                        } => {
-                            if Some(leader_project_id) == project.read(cx).remote_id() {
-                                if is_in_unshared_view {
-                                    Some(Label::new(format!(
-                                        "{} is in an unshared pane",
-                                        leader.user.github_login
-                                    )))
-                                } else {
-                                    None
-                                }
-                            } else {
-                                leader_join_data = Some((leader_project_id, leader.user.id));
-                                Some(Label::new(format!(
-                                    "Follow {} to their active project",
-                                    leader.user.github_login,
-                                )))
-                            }
-                        }
-                        ParticipantLocation::UnsharedProject => Some(Label::new(format!(
-                            "{} is viewing an unshared Zed project",
-                            leader.user.github_login
-                        ))),
-                        ParticipantLocation::External => Some(Label::new(format!(
-                            "{} is viewing a window outside of Zed",
-                            leader.user.github_login
-                        ))),
-                    };
-                }
+                let decoration = render_cx.decorate(pane, cx);
 
                 div()
                     .relative()
@@ -243,7 +169,7 @@ impl Member {
                         AnyView::from(pane.clone())
                             .cached(StyleRefinement::default().v_flex().size_full()),
                     )
-                    .when_some(leader_border, |this, color| {
+                    .when_some(decoration.border, |this, color| {
                         this.child(
                             div()
                                 .absolute()
@@ -254,49 +180,12 @@ impl Member {
                                 .border_color(color),
                         )
                     })
-                    .when_some(leader_status_box, |this, status_box| {
-                        this.child(
-                            div()
-                                .absolute()
-                                .w_96()
-                                .bottom_3()
-                                .right_3()
-                                .elevation_2(cx)
-                                .p_1()
-                                .child(status_box)
-                                .when_some(
-                                    leader_join_data,
-                                    |this, (leader_project_id, leader_user_id)| {
-                                        this.cursor_pointer().on_mouse_down(
-                                            MouseButton::Left,
-                                            cx.listener(move |this, _, cx| {
-                                                crate::join_in_room_project(
-                                                    leader_project_id,
-                                                    leader_user_id,
-                                                    this.app_state().clone(),
-                                                    cx,
-                                                )
-                                                .detach_and_log_err(cx);
-                                            }),
-                                        )
-                                    },
-                                ),
-                        )
+                    .when_some(decoration.status_box, |this, status_box| {
+                        this.child(status_box)
                     })
                     .into_any()
             }
-            Member::Axis(axis) => axis
-                .render(
-                    project,
-                    basis + 1,
-                    follower_states,
-                    active_call,
-                    active_pane,
-                    zoomed,
-                    app_state,
-                    cx,
-                )
-                .into_any(),
+            Member::Axis(axis) => axis.render(basis + 1, zoomed, render_cx, cx).into_any(),
         }
     }
 
@@ -313,7 +202,7 @@ impl Member {
 }
 
 #[derive(Clone)]
-pub(crate) struct PaneAxis {
+pub struct PaneAxis {
     pub axis: Axis,
     pub members: Vec<Member>,
     pub flexes: Arc<Mutex<Vec<f32>>>,
@@ -475,17 +364,12 @@ impl PaneAxis {
         None
     }
 
-    #[allow(clippy::too_many_arguments)]
     fn render(
         &self,
-        project: &Model<Project>,
         basis: usize,
-        follower_states: &HashMap<View<Pane>, FollowerState>,
-        active_call: Option<&Model<ActiveCall>>,
-        active_pane: &View<Pane>,
         zoomed: Option<&AnyWeakView>,
-        app_state: &Arc<AppState>,
-        cx: &mut ViewContext<Workspace>,
+        render_cx: &dyn PaneLeaderDecorator,
+        cx: &mut WindowContext,
     ) -> gpui::AnyElement {
         debug_assert!(self.members.len() == self.flexes.lock().len());
         let mut active_pane_ix = None;
@@ -495,23 +379,14 @@ impl PaneAxis {
             basis,
             self.flexes.clone(),
             self.bounding_boxes.clone(),
-            cx.view().downgrade(),
+            render_cx.workspace().clone(),
         )
         .children(self.members.iter().enumerate().map(|(ix, member)| {
-            if member.contains(active_pane) {
+            if member.contains(render_cx.active_pane()) {
                 active_pane_ix = Some(ix);
             }
             member
-                .render(
-                    project,
-                    (basis + ix) * 10,
-                    follower_states,
-                    active_call,
-                    active_pane,
-                    zoomed,
-                    app_state,
-                    cx,
-                )
+                .render((basis + ix) * 10, zoomed, render_cx, cx)
                 .into_any_element()
         }))
         .with_active_pane(active_pane_ix)
@@ -519,6 +394,160 @@ impl PaneAxis {
     }
 }
 
+/// Decides how the panes of a [`PaneGroup`] are decorated when rendered, e.g. to show which
+/// collaborator is being followed in them.
+pub trait PaneLeaderDecorator {
+    fn decorate(&self, pane: &View<Pane>, cx: &mut WindowContext) -> LeaderDecoration;
+    fn active_pane(&self) -> &View<Pane>;
+    fn workspace(&self) -> &WeakView<Workspace>;
+}
+
+#[derive(Default)]
+pub struct LeaderDecoration {
+    border: Option<Hsla>,
+    status_box: Option<AnyElement>,
+}
+
+/// Renders the workspace's center panes, along with the state of the collaborators followed in them.
+pub(crate) struct PaneRenderContext<'a> {
+    pub project: &'a Model<Project>,
+    pub follower_states: &'a HashMap<View<Pane>, FollowerState>,
+    pub active_call: Option<&'a Model<ActiveCall>>,
+    pub active_pane: &'a View<Pane>,
+    pub app_state: &'a Arc<AppState>,
+    pub workspace: &'a WeakView<Workspace>,
+}
+
+impl<'a> PaneLeaderDecorator for PaneRenderContext<'a> {
+    fn decorate(&self, pane: &View<Pane>, cx: &mut WindowContext) -> LeaderDecoration {
+        let follower_state = self.follower_states.get(pane);
+
+        let leader = follower_state.and_then(|state| {
+            let room = self.active_call?.read(cx).room()?.read(cx);
+            room.remote_participant_for_peer_id(state.leader_id)
+        });
+
+        let Some(leader) = leader else {
+            return LeaderDecoration::default();
+        };
+
+        let is_in_unshared_view = follower_state.map_or(false, |state| {
+            state
+                .active_view_id
+                .is_some_and(|view_id| !state.items_by_leader_view_id.contains_key(&view_id))
+        });
+
+        let mut leader_color = cx
+            .theme()
+            .players()
+            .color_for_participant(leader.participant_index.0)
+            .cursor;
+        leader_color.fade_out(0.3);
+
+        let mut leader_join_data = None;
+        let leader_status_box = match leader.location {
+            ParticipantLocation::SharedProject {
+                project_id: leader_project_id,
+            } => {
+                if Some(leader_project_id) == self.project.read(cx).remote_id() {
+                    if is_in_unshared_view {
+                        Some(Label::new(format!(
+                            "{} is in an unshared pane",
+                            leader.user.github_login
+                        )))
+                    } else {
+                        None
+                    }
+                } else {
+                    leader_join_data = Some((leader_project_id, leader.user.id));
+                    Some(Label::new(format!(
+                        "Follow {} to their active project",
+                        leader.user.github_login,
+                    )))
+                }
+            }
+            ParticipantLocation::UnsharedProject => Some(Label::new(format!(
+                "{} is viewing an unshared Zed project",
+                leader.user.github_login
+            ))),
+            ParticipantLocation::External => Some(Label::new(format!(
+                "{} is viewing a window outside of Zed",
+                leader.user.github_login
+            ))),
+        };
+
+        let status_box = leader_status_box.map(|status_box| {
+            div()
+                .absolute()
+                .w_96()
+                .bottom_3()
+                .right_3()
+                .elevation_2(cx)
+                .p_1()
+                .child(status_box)
+                .when_some(
+                    leader_join_data,
+                    |this, (leader_project_id, leader_user_id)| {
+                        let app_state = self.app_state.clone();
+                        this.cursor_pointer()
+                            .on_mouse_down(MouseButton::Left, move |_, cx| {
+                                crate::join_in_room_project(
+                                    leader_project_id,
+                                    leader_user_id,
+                                    app_state.clone(),
+                                    cx,
+                                )
+                                .detach_and_log_err(cx);
+                            })
+                    },
+                )
+                .into_any_element()
+        });
+
+        LeaderDecoration {
+            border: Some(leader_color),
+            status_box,
+        }
+    }
+
+    fn active_pane(&self) -> &View<Pane> {
+        self.active_pane
+    }
+
+    fn workspace(&self) -> &WeakView<Workspace> {
+        self.workspace
+    }
+}
+
+/// Renders panes without any decorations, for pane groups that are not followed by collaborators.
+pub struct ActivePaneDecorator<'a> {
+    active_pane: &'a View<Pane>,
+    workspace: &'a WeakView<Workspace>,
+}
+
+impl<'a> ActivePaneDecorator<'a> {
+    pub fn new(active_pane: &'a View<Pane>, workspace: &'a WeakView<Workspace>) -> Self {
+        Self {
+            active_pane,
+            workspace,
+        }
+    }
+}
+
+impl<'a> PaneLeaderDecorator for ActivePaneDecorator<'a> {
+    fn decorate(&self, _: &View<Pane>, _: &mut WindowContext) -> LeaderDecoration {
+        LeaderDecoration::default()
+    }
+
+    fn active_pane(&self) -> &View<Pane> {
+        self.active_pane
+    }
+
+    fn workspace(&self) -> &WeakView<Workspace> {
+        self.workspace
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
 pub enum SplitDirection {
     Up,
@@ -1317,16 +1317,61 @@ impl Workspace {
                 }
             }
 
+            // When quitting, unsaved changes that can be restored with the workspace don't need
+            // to be saved.
+            let restorable_items = if quitting {
+                this.update(&mut cx, |this, cx| this.serialize_unsaved_items(cx))?
+                    .await
+            } else {
+                HashSet::default()
+            };
+
             this.update(&mut cx, |this, cx| {
-                this.save_all_internal(SaveIntent::Close, cx)
+                this.save_all_internal(SaveIntent::Close, restorable_items, cx)
             })?
             .await
         })
     }
 
+    fn serialize_unsaved_items(&mut self, cx: &mut ViewContext<Self>) -> Task<HashSet<EntityId>> {
+        if !WorkspaceSettings::get_global(cx).restore_unsaved_buffers
+            || !self.project.read(cx).is_local()
+        {
+            return Task::ready(HashSet::default());
+        }
+
+        let workspace_id = self.database_id();
+        let dirty_items = self
+            .panes
+            .iter()
+            .flat_map(|pane| pane.read(cx).items().cloned().collect::<Vec<_>>())
+            .filter(|item| item.is_dirty(cx))
+            .collect::<Vec<_>>();
+        let tasks = dirty_items
+            .into_iter()
+            .filter_map(|item| {
+                let item_id = item.item_id();
+                let task = item.serialize_unsaved_changes(workspace_id, cx)?;
+                Some(async move { task.await.log_err().map(|_| item_id) })
+            })
+            .collect::<Vec<_>>();
+
+        cx.background_executor().spawn(async move {
+            futures::future::join_all(tasks)
+                .await
+                .into_iter()
+                .flatten()
+                .collect()
+        })
+    }
+
     fn save_all(&mut self, action: &SaveAll, cx: &mut ViewContext<Self>) {
-        self.save_all_internal(action.save_intent.unwrap_or(SaveIntent::SaveAll), cx)
-            .detach_and_log_err(cx);
+        self.save_all_internal(
+            action.save_intent.unwrap_or(SaveIntent::SaveAll),
+            HashSet::default(),
+            cx,
+        )
+        .detach_and_log_err(cx);
     }
 
     fn send_keystrokes(&mut self, action: &SendKeystrokes, cx: &mut ViewContext<Self>) {
@@ -1372,6 +1417,7 @@ impl Workspace {
     fn save_all_internal(
         &mut self,
         mut save_intent: SaveIntent,
+        skipped_items: HashSet<EntityId>,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<bool>> {
         if self.project.read(cx).is_disconnected() {
@@ -1382,7 +1428,7 @@ impl Workspace {
             .iter()
             .flat_map(|pane| {
                 pane.read(cx).items().filter_map(|item| {
-                    if item.is_dirty(cx) {
+                    if item.is_dirty(cx) && !skipped_items.contains(&item.item_id()) {
                         Some((pane.downgrade(), item.boxed_clone()))
                     } else {
                         None
@@ -3386,6 +3432,7 @@ impl Workspace {
                 let active_item_id = pane.active_item().map(|item| item.item_id());
                 (
                     pane.items()
+                        .filter(|item_handle| item_handle.is_serializable(cx))
                         .filter_map(|item_handle| {
                             Some(SerializedItem {
                                 kind: Arc::from(item_handle.serialized_item_kind()?),
@@ -3983,12 +4030,15 @@ impl Render for Workspace {
                                     .flex_1()
                                     .overflow_hidden()
                                     .child(self.center.render(
-                                        &self.project,
-                                        &self.follower_states,
-                                        self.active_call(),
-                                        &self.active_pane,
                                         self.zoomed.as_ref(),
-                                        &self.app_state,
+                                        &PaneRenderContext {
+                                            project: &self.project,
+                                            follower_states: &self.follower_states,
+                                            active_call: self.active_call(),
+                                            active_pane: &self.active_pane,
+                                            app_state: &self.app_state,
+                                            workspace: &self.weak_self,
+                                        },
                                         cx,
                                     ))
                                     .children(
@@ -5083,6 +5133,56 @@ mod tests {
         assert!(!task.await.unwrap());
     }
 
+    #[gpui::test]
+    async fn test_quitting_with_restorable_unsaved_changes(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "one": "" })).await;
+
+        let project = Project::test(fs, ["root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        // Items whose unsaved changes can be restored are persisted instead of prompting.
+        let item1 = cx.new_view(|cx| {
+            TestItem::new(cx)
+                .with_dirty(true)
+                .with_restorable_unsaved_changes(true)
+        });
+        let item2 = cx.new_view(|cx| TestItem::new(cx).with_dirty(true));
+        workspace.update(cx, |w, cx| {
+            w.add_item_to_active_pane(Box::new(item1.clone()), cx);
+            w.add_item_to_active_pane(Box::new(item2.clone()), cx);
+        });
+        let task = workspace.update(cx, |w, cx| w.prepare_to_close(true, cx));
+        cx.executor().run_until_parked();
+        cx.simulate_prompt_answer(1); // don't save the other item
+        cx.executor().run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(task.await.unwrap());
+        item1.read_with(cx, |item, _| {
+            assert_eq!(item.serialize_unsaved_changes_count, 1);
+            assert_eq!(item.save_count, 0);
+        });
+        item2.read_with(cx, |item, _| {
+            assert_eq!(item.serialize_unsaved_changes_count, 0);
+            assert_eq!(item.save_count, 0);
+        });
+
+        // Closing the window without quitting prompts for all unsaved items.
+        let task = workspace.update(cx, |w, cx| w.prepare_to_close(false, cx));
+        cx.executor().run_until_parked();
+        cx.simulate_prompt_answer(2); // cancel save all
+        cx.executor().run_until_parked();
+        cx.simulate_prompt_answer(2); // cancel saving the first item
+        cx.executor().run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(!task.await.unwrap());
+        item1.read_with(cx, |item, _| {
+            assert_eq!(item.serialize_unsaved_changes_count, 1);
+        });
+    }
+
     #[gpui::test]
     async fn test_close_pane_items(cx: &mut TestAppContext) {
         init_test(cx);
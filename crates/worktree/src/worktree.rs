@@ -1409,14 +1409,27 @@ impl LocalWorktree {
             Some(entry) => entry.path.clone(),
             None => return Task::ready(Ok(None)),
         };
+        match self.absolutize(&old_path) {
+            Ok(abs_old_path) => self.copy_external_entry(abs_old_path, new_path, cx),
+            Err(error) => Task::ready(Err(error)),
+        }
+    }
+
+    /// Copies a file or directory that isn't part of this worktree, like an entry of another
+    /// worktree, into it.
+    pub fn copy_external_entry(
+        &self,
+        abs_old_path: PathBuf,
+        new_path: impl Into<Arc<Path>>,
+        cx: &mut ModelContext<Worktree>,
+    ) -> Task<Result<Option<Entry>>> {
         let new_path = new_path.into();
-        let abs_old_path = self.absolutize(&old_path);
         let abs_new_path = self.absolutize(&new_path);
         let fs = self.fs.clone();
         let copy = cx.background_executor().spawn(async move {
             copy_recursive(
                 fs.as_ref(),
-                &abs_old_path?,
+                &abs_old_path,
                 &abs_new_path?,
                 Default::default(),
             )
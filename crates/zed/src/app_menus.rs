@@ -16,7 +16,9 @@ pub fn app_menus() -> Vec<Menu<'static>> {
                     name: "Preferences",
                     items: vec![
                         MenuItem::action("Open Settings", super::OpenSettings),
+                        MenuItem::action("Open Settings Editor", settings_ui::OpenSettingsEditor),
                         MenuItem::action("Open Key Bindings", super::OpenKeymap),
+                        MenuItem::action("Open Keymap Editor", settings_ui::OpenKeymapEditor),
                         MenuItem::action("Open Default Settings", super::OpenDefaultSettings),
                         MenuItem::action("Open Default Key Bindings", super::OpenDefaultKeymap),
                         MenuItem::action("Open Local Settings", super::OpenLocalSettings),
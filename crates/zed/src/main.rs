@@ -12,7 +12,7 @@ use editor::Editor;
 use env_logger::Builder;
 use fs::RealFs;
 use futures::{future, StreamExt};
-use gpui::{App, AppContext, AsyncAppContext, Context, SemanticVersion, Task};
+use gpui::{App, AppContext, AsyncAppContext, Context, SemanticVersion, Task, VisualContext};
 use isahc::{prelude::Configurable, Request};
 use language::LanguageRegistry;
 use log::LevelFilter;
@@ -22,6 +22,7 @@ use mimalloc::MiMalloc;
 use node_runtime::RealNodeRuntime;
 use parking_lot::Mutex;
 use release_channel::{AppCommitSha, ReleaseChannel, RELEASE_CHANNEL};
+use remote::RemoteServer;
 use serde::{Deserialize, Serialize};
 use settings::{
     default_settings, handle_settings_file_changes, watch_config_file, Settings, SettingsStore,
@@ -50,7 +51,7 @@ use util::{
 };
 use uuid::Uuid;
 use welcome::{show_welcome_view, BaseKeymap, FIRST_OPEN};
-use workspace::{AppState, WorkspaceStore};
+use workspace::{AppState, Workspace, WorkspaceStore};
 use zed::{
     app_menus, build_window_options, ensure_only_instance, handle_cli_connection,
     handle_keymap_file_changes, initialize_workspace, open_paths_with_positions, IsOnlyInstance,
@@ -251,6 +252,8 @@ fn main() {
         language_selector::init(cx);
         theme_selector::init(cx);
         language_tools::init(cx);
+        local_history::init(fs.clone(), cx);
+        diff_view::init(cx);
         call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         notifications::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         collab_ui::init(&app_state, cx);
@@ -258,6 +261,7 @@ fn main() {
         markdown_preview::init(cx);
         welcome::init(cx);
         extensions_ui::init(cx);
+        settings_ui::init(cx);
 
         cx.set_menus(app_menus());
         initialize_workspace(app_state.clone(), cx);
@@ -343,6 +347,30 @@ fn handle_open_request(
         }));
     }
 
+    for (connection_options, path) in request.open_remote_projects {
+        let app_state = app_state.clone();
+        cx.spawn(|mut cx| async move {
+            let project = remote::open_remote_project(
+                RemoteServer::Ssh(connection_options),
+                vec![path],
+                app_state.languages.clone(),
+                app_state.fs.clone(),
+                cx.clone(),
+            )
+            .await?;
+            cx.update(|cx| {
+                let options = (app_state.build_window_options)(None, cx);
+                cx.open_window(options, |cx| {
+                    cx.new_view(|cx| {
+                        Workspace::new(Default::default(), project, app_state.clone(), cx)
+                    })
+                })
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     if !request.open_channel_notes.is_empty() || request.join_channel.is_some() {
         cx.spawn(|mut cx| async move {
             if let Some(task) = task {
@@ -901,7 +929,10 @@ fn collect_url_args(cx: &AppContext) -> Vec<String> {
         .filter_map(|arg| match std::fs::canonicalize(Path::new(&arg)) {
             Ok(path) => Some(format!("file://{}", path.to_string_lossy())),
             Err(error) => {
-                if arg.starts_with("file://") || arg.starts_with("zed-cli://") {
+                if arg.starts_with("file://")
+                    || arg.starts_with("zed-cli://")
+                    || arg.starts_with("ssh://")
+                {
                     Some(arg)
                 } else if let Some(_) = parse_zed_link(&arg, cx) {
                     Some(arg)
@@ -25,8 +25,8 @@ use release_channel::{AppCommitSha, ReleaseChannel};
 use rope::Rope;
 use search::project_search::ProjectSearchBar;
 use settings::{
-    initial_local_settings_content, initial_tasks_content, watch_config_file, KeymapFile, Settings,
-    SettingsStore, DEFAULT_KEYMAP_PATH,
+    initial_local_settings_content, initial_tasks_content, watch_config_file, KeymapFile,
+    KeymapSource, LoadedKeymaps, Settings, SettingsStore, DEFAULT_KEYMAP_PATH,
 };
 use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc};
 use task::{oneshot_source::OneshotSource, static_source::StaticSource};
@@ -185,6 +185,10 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace_handle.clone(),
                 cx.clone(),
             );
+            let comments_panel = collab_ui::comments_panel::CommentsPanel::load(
+                workspace_handle.clone(),
+                cx.clone(),
+            );
             let (
                 project_panel,
                 terminal_panel,
@@ -192,6 +196,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                comments_panel,
             ) = futures::try_join!(
                 project_panel,
                 terminal_panel,
@@ -199,6 +204,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                comments_panel,
             )?;
 
             workspace_handle.update(&mut cx, |workspace, cx| {
@@ -208,6 +214,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
+                workspace.add_panel(comments_panel, cx);
                 cx.focus_self();
             })
         })
@@ -380,6 +387,13 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                         .toggle_panel_focus::<collab_ui::notification_panel::NotificationPanel>(cx);
                 },
             )
+            .register_action(
+                |workspace: &mut Workspace,
+                 _: &collab_ui::comments_panel::ToggleFocus,
+                 cx: &mut ViewContext<Workspace>| {
+                    workspace.toggle_panel_focus::<collab_ui::comments_panel::CommentsPanel>(cx);
+                },
+            )
             .register_action(
                 |workspace: &mut Workspace,
                  _: &terminal_panel::ToggleFocus,
@@ -628,19 +642,23 @@ pub fn handle_keymap_file_changes(
 
 fn reload_keymaps(cx: &mut AppContext, keymap_content: &KeymapFile) {
     cx.clear_key_bindings();
+    LoadedKeymaps::clear(cx);
     load_default_keymap(cx);
-    keymap_content.clone().add_to_cx(cx).log_err();
+    keymap_content
+        .clone()
+        .add_to_cx_as(KeymapSource::User, cx)
+        .log_err();
     cx.set_menus(app_menus());
 }
 
 pub fn load_default_keymap(cx: &mut AppContext) {
-    KeymapFile::load_asset(DEFAULT_KEYMAP_PATH, cx).unwrap();
+    KeymapFile::load_asset_as(DEFAULT_KEYMAP_PATH, KeymapSource::Default, cx).unwrap();
     if VimModeSetting::get_global(cx).0 {
-        KeymapFile::load_asset("keymaps/vim.json", cx).unwrap();
+        KeymapFile::load_asset_as("keymaps/vim.json", KeymapSource::Vim, cx).unwrap();
     }
 
     if let Some(asset_path) = BaseKeymap::get_global(cx).asset_path() {
-        KeymapFile::load_asset(asset_path, cx).unwrap();
+        KeymapFile::load_asset_as(asset_path, KeymapSource::Base, cx).unwrap();
     }
 }
 